@@ -0,0 +1,276 @@
+//! Functions heavy on clones and allocations, for performance review
+//!
+//! A heuristic, not a profile: `.clone()`, `.to_string()`, `.to_owned()`,
+//! `Vec::new()` and `format!` are counted in each function body and functions are
+//! ranked by how many they have. Bodies aren't kept after parsing, so the files are
+//! tokenized again and each occurrence goes to the innermost function or method
+//! whose lines contain it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{TokenStream, TokenTree};
+
+use super::types::AnalyzedItem;
+use super::usage::base_type_name;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AllocKind {
+    Clone,
+    ToString,
+    ToOwned,
+    VecNew,
+    Format,
+}
+
+impl AllocKind {
+    pub const ALL: [AllocKind; 5] = [
+        Self::Clone,
+        Self::ToString,
+        Self::ToOwned,
+        Self::VecNew,
+        Self::Format,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Clone => "clone()",
+            Self::ToString => "to_string()",
+            Self::ToOwned => "to_owned()",
+            Self::VecNew => "Vec::new()",
+            Self::Format => "format!",
+        }
+    }
+}
+
+/// One occurrence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Allocation {
+    pub kind: AllocKind,
+    pub line: usize,
+}
+
+/// Occurrences in a file's source, in order
+pub fn scan(source: &str) -> Vec<Allocation> {
+    let Ok(tokens) = source.parse::<TokenStream>() else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    scan_tokens(tokens, &mut found);
+    found
+}
+
+fn scan_tokens(tokens: TokenStream, found: &mut Vec<Allocation>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        let ident = match token {
+            TokenTree::Group(group) => {
+                scan_tokens(group.stream(), found);
+                continue;
+            }
+            TokenTree::Ident(ident) => ident.to_string(),
+            _ => continue,
+        };
+        let text = |at: usize| tokens.get(at).map(TokenTree::to_string);
+        let after_dot = i > 0 && text(i - 1).as_deref() == Some(".");
+        let called = text(i + 1).as_deref() == Some("()");
+        let kind = match ident.as_str() {
+            "clone" if after_dot && called => AllocKind::Clone,
+            "to_string" if after_dot && called => AllocKind::ToString,
+            "to_owned" if after_dot && called => AllocKind::ToOwned,
+            "format" if text(i + 1).as_deref() == Some("!") => AllocKind::Format,
+            "new" if called && is_vec_path(&tokens[..i]) => AllocKind::VecNew,
+            _ => continue,
+        };
+        found.push(Allocation {
+            kind,
+            line: token.span().start().line,
+        });
+    }
+}
+
+/// `before` ends in `Vec::` or `Vec::<T>::`
+fn is_vec_path(before: &[TokenTree]) -> bool {
+    let text: Vec<String> = before
+        .iter()
+        .rev()
+        .take(32)
+        .map(|t| t.to_string())
+        .collect();
+    let colons = [":".to_string(), ":".to_string()];
+    let Some(rest) = text.strip_prefix(colons.as_slice()) else {
+        return false;
+    };
+    let rest = match rest.first().map(String::as_str) {
+        // Skip back over the turbofish to its `::<`
+        Some(">") => {
+            let mut depth = 0;
+            let Some(open) = rest.iter().position(|t| {
+                match t.as_str() {
+                    ">" => depth += 1,
+                    "<" => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            }) else {
+                return false;
+            };
+            match rest[open + 1..].strip_prefix(colons.as_slice()) {
+                Some(rest) => rest,
+                None => return false,
+            }
+        }
+        _ => rest,
+    };
+    rest.first().is_some_and(|t| t == "Vec")
+}
+
+/// A function or method and its allocations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotspot {
+    /// `name` or `Type::name`
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+    pub allocations: Vec<Allocation>,
+}
+
+impl Hotspot {
+    /// Occurrences of each kind with their lines, most common first
+    pub fn by_kind(&self) -> Vec<(AllocKind, Vec<usize>)> {
+        let mut kinds: Vec<(AllocKind, Vec<usize>)> = AllocKind::ALL
+            .iter()
+            .map(|&kind| {
+                let lines = self
+                    .allocations
+                    .iter()
+                    .filter(|a| a.kind == kind)
+                    .map(|a| a.line)
+                    .collect();
+                (kind, lines)
+            })
+            .filter(|(_, lines): &(AllocKind, Vec<usize>)| !lines.is_empty())
+            .collect();
+        kinds.sort_by_key(|(_, lines)| std::cmp::Reverse(lines.len()));
+        kinds
+    }
+}
+
+/// Functions and methods of `items` with any allocations, most first. Files are read
+/// through `read` (`std::fs::read_to_string` outside tests).
+pub fn hotspots(items: &[AnalyzedItem], read: impl Fn(&Path) -> Option<String>) -> Vec<Hotspot> {
+    // (file, first line, last line, name) of every function and method
+    let mut spans: Vec<(&Path, usize, usize, String)> = Vec::new();
+    for item in items {
+        let loc = match item {
+            AnalyzedItem::Function(f) => &f.source_location,
+            AnalyzedItem::Impl(im) => &im.source_location,
+            _ => continue,
+        };
+        let (Some(file), Some(start), Some(end)) = (&loc.file, loc.line, loc.end_line) else {
+            continue;
+        };
+        if let AnalyzedItem::Impl(im) = item {
+            // Methods only carry lines; the file is the impl's
+            let ty = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+            for method in &im.methods {
+                let m = &method.source_location;
+                if let (Some(start), Some(end)) = (m.line, m.end_line) {
+                    spans.push((file, start, end, format!("{}::{}", ty, method.name)));
+                }
+            }
+        } else {
+            spans.push((file, start, end, item.name().to_string()));
+        }
+    }
+
+    let mut scanned: HashMap<&Path, Vec<Allocation>> = HashMap::new();
+    for &(file, ..) in &spans {
+        scanned
+            .entry(file)
+            .or_insert_with(|| read(file).map(|source| scan(&source)).unwrap_or_default());
+    }
+    let mut hotspots: Vec<Hotspot> = spans
+        .iter()
+        .map(|(file, start, _, name)| Hotspot {
+            name: name.clone(),
+            file: file.to_path_buf(),
+            line: *start,
+            allocations: Vec::new(),
+        })
+        .collect();
+    for (file, allocations) in &scanned {
+        for allocation in allocations {
+            let innermost = spans
+                .iter()
+                .enumerate()
+                .filter(|(_, (f, start, end, _))| {
+                    f == file && (*start..=*end).contains(&allocation.line)
+                })
+                .min_by_key(|(_, (_, start, end, _))| end - start);
+            if let Some((i, _)) = innermost {
+                hotspots[i].allocations.push(*allocation);
+            }
+        }
+    }
+    hotspots.retain(|h| !h.allocations.is_empty());
+    hotspots.sort_by(|a, b| {
+        b.allocations
+            .len()
+            .cmp(&a.allocations.len())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    const SOURCE: &str = r##"pub struct Report { title: String }
+
+impl Report {
+    pub fn render(&self, rows: &[String]) -> String {
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(format!("{}: {}", self.title.clone(), row.to_owned()));
+        }
+        out.join("\n").to_string()
+    }
+}
+
+pub fn label(id: u32) -> String {
+    // id.to_string() in a comment is not counted
+    let ids: Vec<u32> = Vec::<u32>::new();
+    format!("#{}", id) + &ids.len().to_string()
+}
+
+pub fn quiet() {}
+"##;
+
+    #[test]
+    fn test_hotspots_rank_functions() {
+        let file = PathBuf::from("/p/src/report.rs");
+        let items = RustAnalyzer::new()
+            .analyze_source_with_path(SOURCE, Some(file.clone()))
+            .unwrap();
+        let found = hotspots(&items, |path| (path == file).then(|| SOURCE.to_string()));
+        let ranked: Vec<(&str, usize)> = found
+            .iter()
+            .map(|h| (h.name.as_str(), h.allocations.len()))
+            .collect();
+        assert_eq!(ranked, vec![("Report::render", 5), ("label", 3)]);
+        assert_eq!(
+            found[0].by_kind(),
+            vec![
+                (AllocKind::Clone, vec![7]),
+                (AllocKind::ToString, vec![9]),
+                (AllocKind::ToOwned, vec![7]),
+                (AllocKind::VecNew, vec![5]),
+                (AllocKind::Format, vec![7]),
+            ]
+        );
+        assert_eq!(found[1].line, 13);
+    }
+}
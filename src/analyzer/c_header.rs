@@ -0,0 +1,500 @@
+//! C declarations for the crate's FFI surface
+//!
+//! A cbindgen-style preview: `#[repr(C)]` structs become `typedef struct`s, fieldless
+//! enums with a C or primitive `repr` become C enums (plus a fixed-width typedef for
+//! the latter), exported functions become prototypes and the crate's other types
+//! seen in those signatures are declared opaque. Rust types with no C spelling are
+//! written as `/* String */` comments so the preview still shows where they are.
+
+use std::collections::{HashMap, HashSet};
+
+use super::const_eval::ConstTable;
+use super::enum_layout::{EnumLayout, Repr};
+use super::ffi::{collect_names, extern_abi, has_no_mangle};
+use super::pretty;
+use super::types::{AnalyzedItem, EnumInfo, FunctionInfo, StructInfo, VariantFields};
+use syn::{GenericArgument, PathArguments, ReturnType, Type};
+
+/// Declaration of one item as C; `None` for items outside the FFI surface
+/// (functions that aren't exported, types without a C `repr`, ...)
+pub fn c_declaration(item: &AnalyzedItem, items: &[AnalyzedItem]) -> Option<String> {
+    CNames::new(items).declaration(item)
+}
+
+/// A whole header for the crate's FFI surface, guarded by `<NAME>_H`
+pub fn c_header(items: &[AnalyzedItem], name: &str) -> String {
+    let names = CNames::new(items);
+    let exported: Vec<&AnalyzedItem> = items
+        .iter()
+        .filter(|i| names.declaration(i).is_some())
+        .collect();
+
+    // The crate's types the surface mentions, for opaque declarations and typedefs
+    let mut mentioned: Vec<String> = Vec::new();
+    for item in &exported {
+        let types: Vec<&str> = match item {
+            AnalyzedItem::Function(f) => f
+                .parameters
+                .iter()
+                .map(|p| &*p.ty)
+                .chain(f.return_type.as_deref())
+                .collect(),
+            AnalyzedItem::Struct(s) => s.fields.iter().map(|f| &*f.ty).collect(),
+            _ => Vec::new(),
+        };
+        for ty in types
+            .into_iter()
+            .filter_map(|t| syn::parse_str::<Type>(t).ok())
+        {
+            collect_names(&ty, &mut mentioned);
+        }
+    }
+    let mut seen = HashSet::new();
+    mentioned.retain(|n| seen.insert(n.clone()));
+
+    let guard: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut out = format!(
+        "#ifndef {guard}_H\n#define {guard}_H\n\n\
+         #include <stdarg.h>\n#include <stdbool.h>\n#include <stddef.h>\n\
+         #include <stdint.h>\n#include <stdlib.h>\n"
+    );
+    let declarations = |pick: fn(&AnalyzedItem) -> bool| -> Vec<String> {
+        exported
+            .iter()
+            .filter(|i| pick(i))
+            .filter_map(|i| {
+                let decl = names.declaration(i)?;
+                Some(format!("{}{}", doc_comment(i.documentation()), decl))
+            })
+            .collect()
+    };
+    let blocks = [
+        declarations(|i| matches!(i, AnalyzedItem::Enum(_))),
+        mentioned
+            .iter()
+            .filter(|n| matches!(names.local.get(n.as_str()), Some(Local::Opaque)))
+            .map(|n| format!("typedef struct {n} {n};"))
+            .collect(),
+        declarations(|i| matches!(i, AnalyzedItem::Struct(_))),
+        mentioned
+            .iter()
+            .filter_map(|n| match names.local.get(n.as_str()) {
+                Some(Local::Alias(ty)) => Some(format!("typedef {};", names.declarator(ty, n))),
+                _ => None,
+            })
+            .collect(),
+        declarations(|i| matches!(i, AnalyzedItem::Function(_))),
+    ];
+    for block in blocks.iter().filter(|b| !b.is_empty()) {
+        out.push('\n');
+        out.push_str(&block.join("\n\n"));
+        out.push('\n');
+    }
+    out.push_str(&format!("\n#endif /* {guard}_H */\n"));
+    out
+}
+
+fn doc_comment(docs: Option<&str>) -> String {
+    match docs {
+        Some(docs) if !docs.trim().is_empty() => {
+            let body: Vec<String> = docs
+                .lines()
+                .map(|l| format!(" * {}", l).trim_end().to_string())
+                .collect();
+            format!("/**\n{}\n */\n", body.join("\n"))
+        }
+        _ => String::new(),
+    }
+}
+
+fn is_fieldless(en: &EnumInfo) -> bool {
+    en.variants
+        .iter()
+        .all(|v| matches!(v.fields, VariantFields::Unit))
+}
+
+/// How one of the crate's types is spelled in C
+enum Local {
+    /// `repr(C)` struct or C-like enum, declared under its own name
+    Named,
+    /// `repr(transparent)`: spelled as its field
+    Transparent(Type),
+    /// Type alias, declared with a `typedef`
+    Alias(Type),
+    /// No C layout: only usable behind a pointer
+    Opaque,
+}
+
+struct CNames<'a> {
+    local: HashMap<&'a str, Local>,
+    consts: ConstTable,
+}
+
+impl<'a> CNames<'a> {
+    fn new(items: &'a [AnalyzedItem]) -> Self {
+        let mut local = HashMap::new();
+        for item in items {
+            let entry = match item {
+                AnalyzedItem::Struct(s) => {
+                    let repr = Repr::from_attributes(&s.attributes);
+                    match s.fields.first().and_then(|f| syn::parse_str(&f.ty).ok()) {
+                        Some(ty) if repr.transparent => Local::Transparent(ty),
+                        _ if repr.c => Local::Named,
+                        _ => Local::Opaque,
+                    }
+                }
+                AnalyzedItem::Enum(e) => {
+                    let repr = Repr::from_attributes(&e.attributes);
+                    if (repr.c || repr.int.is_some()) && is_fieldless(e) {
+                        Local::Named
+                    } else {
+                        Local::Opaque
+                    }
+                }
+                AnalyzedItem::TypeAlias(t) => match syn::parse_str(&t.ty) {
+                    Ok(ty) => Local::Alias(ty),
+                    Err(_) => continue,
+                },
+                _ => continue,
+            };
+            local.insert(item.name(), entry);
+        }
+        Self {
+            local,
+            consts: ConstTable::new(items),
+        }
+    }
+
+    fn declaration(&self, item: &AnalyzedItem) -> Option<String> {
+        match item {
+            AnalyzedItem::Function(f) => self.function(f),
+            AnalyzedItem::Struct(s) => self.structure(s),
+            AnalyzedItem::Enum(e) => self.enumeration(e),
+            _ => None,
+        }
+    }
+
+    fn function(&self, f: &FunctionInfo) -> Option<String> {
+        if extern_abi(f).is_none() && !has_no_mangle(f) {
+            return None;
+        }
+        let params: Vec<String> = f
+            .parameters
+            .iter()
+            .filter(|p| !p.is_self)
+            .map(|p| match syn::parse_str::<Type>(&p.ty) {
+                Ok(ty) => self.declarator(&ty, &p.name),
+                Err(_) => format!("/* {} */ {}", p.ty, p.name),
+            })
+            .collect();
+        let params = if params.is_empty() {
+            "void".to_string()
+        } else {
+            params.join(", ")
+        };
+        let ret = match f.return_type.as_deref().map(syn::parse_str::<Type>) {
+            Some(Ok(ty)) => self.c_type(&ty),
+            Some(Err(_)) => "/* ? */".to_string(),
+            None => "void".to_string(),
+        };
+        Some(format!("{}({});", spaced(ret, &f.name), params))
+    }
+
+    fn structure(&self, st: &StructInfo) -> Option<String> {
+        match self.local.get(st.name.as_str())? {
+            Local::Named => {
+                let fields: Vec<String> = st
+                    .fields
+                    .iter()
+                    .map(|f| {
+                        // Tuple struct fields are named `_0`, `_1`, ... as cbindgen does
+                        let name = if f.name.starts_with(|c: char| c.is_ascii_digit()) {
+                            format!("_{}", f.name)
+                        } else {
+                            f.name.clone()
+                        };
+                        match syn::parse_str::<Type>(&f.ty) {
+                            Ok(ty) => format!("  {};", self.declarator(&ty, &name)),
+                            Err(_) => format!("  /* {} */ {};", f.ty, name),
+                        }
+                    })
+                    .collect();
+                Some(format!(
+                    "typedef struct {name} {{\n{}\n}} {name};",
+                    fields.join("\n"),
+                    name = st.name
+                ))
+            }
+            Local::Transparent(ty) => Some(format!("typedef {};", self.declarator(ty, &st.name))),
+            _ => None,
+        }
+    }
+
+    fn enumeration(&self, en: &EnumInfo) -> Option<String> {
+        if !matches!(self.local.get(en.name.as_str())?, Local::Named) {
+            return None;
+        }
+        let layout = EnumLayout::with_consts(en, &self.consts);
+        let variants: Vec<String> = layout
+            .discriminants
+            .iter()
+            .map(|d| match d.value {
+                Some(value) => format!("  {} = {},", d.variant, value),
+                None => format!("  {},", d.variant),
+            })
+            .collect();
+        let body = variants.join("\n");
+        Some(match layout.repr.int.as_deref().and_then(c_primitive) {
+            Some(int) if !layout.repr.c => format!(
+                "enum {name} {{\n{body}\n}};\ntypedef {int} {name};",
+                name = en.name
+            ),
+            _ => format!("typedef enum {name} {{\n{body}\n}} {name};", name = en.name),
+        })
+    }
+
+    /// `ty` declaring `name`: `const Point *p`, `uint8_t buf[16]`, `void (*cb)(int32_t)`
+    fn declarator(&self, ty: &Type, name: &str) -> String {
+        match ty {
+            Type::BareFn(f) => {
+                let params: Vec<String> = f.inputs.iter().map(|a| self.c_type(&a.ty)).collect();
+                let params = if params.is_empty() {
+                    "void".to_string()
+                } else {
+                    params.join(", ")
+                };
+                let ret = match &f.output {
+                    ReturnType::Type(_, ret) => self.c_type(ret),
+                    ReturnType::Default => "void".to_string(),
+                };
+                format!("{} (*{})({})", ret, name, params)
+            }
+            Type::Array(a) => format!(
+                "{}[{}]",
+                self.declarator(&a.elem, name),
+                pretty::expr(&a.len)
+            ),
+            Type::Paren(p) => self.declarator(&p.elem, name),
+            Type::Group(g) => self.declarator(&g.elem, name),
+            Type::Path(p) => {
+                let last = p.path.segments.last();
+                match (last.map(|s| s.ident.to_string()).as_deref(), first_arg(ty)) {
+                    (Some("Option"), Some(inner)) if matches!(inner, Type::BareFn(_)) => {
+                        self.declarator(inner, name)
+                    }
+                    _ => spaced(self.c_type(ty), name),
+                }
+            }
+            _ => spaced(self.c_type(ty), name),
+        }
+    }
+
+    /// C spelling of `ty` without a declarator
+    fn c_type(&self, ty: &Type) -> String {
+        match ty {
+            Type::Ptr(p) => self.pointer(&p.elem, p.mutability.is_some()),
+            Type::Reference(r) => self.pointer(&r.elem, r.mutability.is_some()),
+            Type::Tuple(t) if t.elems.is_empty() => "void".into(),
+            Type::Never(_) => "void".into(),
+            Type::Paren(p) => self.c_type(&p.elem),
+            Type::Group(g) => self.c_type(&g.elem),
+            Type::Path(p) => {
+                let Some(last) = p.path.segments.last() else {
+                    return unmapped(ty);
+                };
+                let name = last.ident.to_string();
+                if let Some(c) = c_primitive(&name) {
+                    return c.to_string();
+                }
+                match (name.as_str(), first_arg(ty)) {
+                    ("Box" | "NonNull", Some(inner)) => self.pointer(inner, true),
+                    ("Option", Some(inner)) if self.is_pointer(inner) => self.c_type(inner),
+                    (_, _) if name.starts_with("NonZero") => {
+                        c_primitive(&name["NonZero".len()..].to_lowercase())
+                            .map_or_else(|| unmapped(ty), str::to_string)
+                    }
+                    _ => match self.local.get(name.as_str()) {
+                        Some(Local::Transparent(inner)) => self.c_type(inner),
+                        Some(_) => name,
+                        None => unmapped(ty),
+                    },
+                }
+            }
+            _ => unmapped(ty),
+        }
+    }
+
+    /// Types an `Option` of which is a nullable pointer (following local aliases)
+    fn is_pointer(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Reference(_) | Type::Ptr(_) | Type::BareFn(_) => true,
+            Type::Path(p) => p.path.segments.last().is_some_and(|s| {
+                s.ident == "Box"
+                    || s.ident == "NonNull"
+                    || matches!(
+                        self.local.get(s.ident.to_string().as_str()),
+                        Some(Local::Alias(inner)) if self.is_pointer(inner)
+                    )
+            }),
+            _ => false,
+        }
+    }
+
+    fn pointer(&self, pointee: &Type, mutable: bool) -> String {
+        let inner = self.c_type(pointee);
+        let inner = if inner.ends_with('*') {
+            format!("{}*", inner)
+        } else {
+            format!("{} *", inner)
+        };
+        if mutable {
+            inner
+        } else {
+            format!("const {}", inner)
+        }
+    }
+}
+
+fn spaced(c_type: String, name: &str) -> String {
+    if c_type.ends_with('*') {
+        format!("{}{}", c_type, name)
+    } else {
+        format!("{} {}", c_type, name)
+    }
+}
+
+fn unmapped(ty: &Type) -> String {
+    format!("/* {} */", pretty::ty(ty))
+}
+
+fn first_arg(ty: &Type) -> Option<&Type> {
+    let Type::Path(p) = ty else {
+        return None;
+    };
+    match &p.path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|a| match a {
+            GenericArgument::Type(t) => Some(t),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// C spelling of a Rust primitive or `core::ffi` type
+fn c_primitive(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "usize" => "uintptr_t",
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "isize" => "intptr_t",
+        "f32" | "c_float" => "float",
+        "f64" | "c_double" => "double",
+        "bool" => "bool",
+        "c_void" => "void",
+        "c_char" => "char",
+        "c_schar" => "signed char",
+        "c_uchar" => "unsigned char",
+        "c_short" => "short",
+        "c_ushort" => "unsigned short",
+        "c_int" => "int",
+        "c_uint" => "unsigned int",
+        "c_long" => "long",
+        "c_ulong" => "unsigned long",
+        "c_longlong" => "long long",
+        "c_ulonglong" => "unsigned long long",
+        "size_t" => "size_t",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    const SOURCE: &str = r#"
+        /// A point in the plane
+        #[repr(C)]
+        pub struct Point { pub x: f64, pub y: f64 }
+        #[repr(C)]
+        pub struct Buffer { pub data: [u8; 16], pub name: *const c_char, pub label: String }
+        #[repr(u8)]
+        pub enum Mode { Read = 1, Write }
+        pub struct Engine { points: Vec<Point> }
+        #[repr(transparent)]
+        pub struct Meters(f64);
+        pub type Callback = extern "C" fn(*const Point, usize) -> bool;
+
+        #[no_mangle]
+        pub extern "C" fn engine_new() -> *mut Engine { todo!() }
+        #[no_mangle]
+        pub extern "C" fn engine_step(e: &mut Engine, dt: Meters, mode: Mode, cb: Option<Callback>) {}
+        pub fn rust_only() {}
+    "#;
+
+    #[test]
+    fn test_item_declarations() {
+        let items = RustAnalyzer::new().analyze_source(SOURCE).unwrap();
+        let decl = |name: &str| {
+            let item = items.iter().find(|i| i.name() == name).unwrap();
+            c_declaration(item, &items)
+        };
+        assert_eq!(
+            decl("Point").as_deref(),
+            Some("typedef struct Point {\n  double x;\n  double y;\n} Point;")
+        );
+        assert_eq!(
+            decl("Buffer").as_deref(),
+            Some(
+                "typedef struct Buffer {\n  uint8_t data[16];\n  const char *name;\n  \
+                 /* String */ label;\n} Buffer;"
+            )
+        );
+        assert_eq!(
+            decl("Mode").as_deref(),
+            Some("enum Mode {\n  Read = 1,\n  Write = 2,\n};\ntypedef uint8_t Mode;")
+        );
+        assert_eq!(decl("Meters").as_deref(), Some("typedef double Meters;"));
+        assert_eq!(
+            decl("engine_step").as_deref(),
+            Some("void engine_step(Engine *e, double dt, Mode mode, Callback cb);")
+        );
+        assert_eq!(
+            decl("engine_new").as_deref(),
+            Some("Engine *engine_new(void);")
+        );
+        assert_eq!(decl("Engine"), None);
+        assert_eq!(decl("rust_only"), None);
+    }
+
+    #[test]
+    fn test_header_orders_declarations() {
+        let items = RustAnalyzer::new().analyze_source(SOURCE).unwrap();
+        let header = c_header(&items, "my-engine");
+        assert!(header.starts_with("#ifndef MY_ENGINE_H\n#define MY_ENGINE_H\n"));
+        assert!(header.ends_with("#endif /* MY_ENGINE_H */\n"));
+        assert!(header.contains("/**\n * A point in the plane\n */\ntypedef struct Point {"));
+        assert!(header.contains("typedef struct Engine Engine;"));
+        assert!(header.contains("typedef bool (*Callback)(const Point *, uintptr_t);"));
+        let at = |text: &str| header.find(text).unwrap();
+        assert!(at("enum Mode") < at("typedef struct Engine Engine;"));
+        assert!(at("typedef struct Engine Engine;") < at("typedef struct Point {"));
+        assert!(at("typedef struct Point {") < at("typedef bool (*Callback)"));
+        assert!(at("typedef bool (*Callback)") < at("Engine *engine_new(void);"));
+    }
+}
@@ -0,0 +1,434 @@
+//! `#[cfg(..)]` conditions and the items compiled for a target
+//!
+//! Predicates are parsed into [`Cfg`] and evaluated against a [`CfgTarget`]: a
+//! target triple and a set of enabled features. The `target_*` values, `unix` and
+//! `windows` are derived from the triple the way rustc's built-in targets set them
+//! (closely enough for the common ones); `debug_assertions` is on, `test` and
+//! custom cfgs such as `docsrs` are off. An item also inherits the conditions of
+//! the `mod` declarations it is inside, which [`module_cfgs`] collects per file.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use syn::punctuated::Punctuated;
+use syn::{Expr, Item, Lit, Meta, Token};
+
+use super::dependency::CrateInfo;
+use super::types::AnalyzedItem;
+
+/// Triples offered by the target selector
+pub const TARGETS: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "aarch64-linux-android",
+    "wasm32-unknown-unknown",
+    "wasm32-wasip1",
+    "thumbv7em-none-eabihf",
+];
+
+/// A `cfg` predicate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cfg {
+    /// `unix`, `test`, ...
+    Name(String),
+    /// `feature = "tui"`, `target_os = "linux"`, ...
+    KeyValue(String, String),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
+}
+
+impl Cfg {
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::from_meta(&syn::parse_str(text).ok()?)
+    }
+
+    /// The predicate of a `#[cfg(..)]`; `None` for other attributes
+    pub fn from_attribute(attr: &syn::Attribute) -> Option<Self> {
+        if !attr.path().is_ident("cfg") {
+            return None;
+        }
+        Self::from_meta(&attr.parse_args().ok()?)
+    }
+
+    fn from_meta(meta: &Meta) -> Option<Self> {
+        let name = meta.path().get_ident()?.to_string();
+        match meta {
+            Meta::Path(_) => Some(Self::Name(name)),
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(lit) => match &lit.lit {
+                    Lit::Str(s) => Some(Self::KeyValue(name, s.value())),
+                    _ => None,
+                },
+                _ => None,
+            },
+            Meta::List(list) => {
+                let args = list
+                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                    .ok()?;
+                let args: Vec<Cfg> = args.iter().map(Self::from_meta).collect::<Option<_>>()?;
+                match name.as_str() {
+                    "all" => Some(Self::All(args)),
+                    "any" => Some(Self::Any(args)),
+                    "not" if args.len() == 1 => Some(Self::Not(Box::new(args.into_iter().next()?))),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    pub fn eval(&self, target: &CfgTarget) -> bool {
+        match self {
+            Self::Name(name) => match name.as_str() {
+                "unix" | "windows" => target.family().contains(&name.as_str()),
+                "debug_assertions" => true,
+                _ => false,
+            },
+            Self::KeyValue(key, value) => match key.as_str() {
+                "feature" => target.features.contains(value),
+                "target_family" => target.family().contains(&value.as_str()),
+                "target_arch" => target.arch() == value,
+                "target_os" => target.os() == value,
+                "target_env" => target.env() == value,
+                "target_vendor" => target.vendor() == value,
+                "target_pointer_width" => target.pointer_width() == value,
+                "target_endian" => value == "little",
+                "target_has_atomic" => true,
+                "panic" => value == "unwind",
+                _ => false,
+            },
+            Self::All(all) => all.iter().all(|c| c.eval(target)),
+            Self::Any(any) => any.iter().any(|c| c.eval(target)),
+            Self::Not(inner) => !inner.eval(target),
+        }
+    }
+}
+
+impl fmt::Display for Cfg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, name: &str, args: &[Cfg]| {
+            let args: Vec<String> = args.iter().map(Cfg::to_string).collect();
+            write!(f, "{}({})", name, args.join(", "))
+        };
+        match self {
+            Self::Name(name) => write!(f, "{}", name),
+            Self::KeyValue(key, value) => write!(f, "{} = {:?}", key, value),
+            Self::All(all) => list(f, "all", all),
+            Self::Any(any) => list(f, "any", any),
+            Self::Not(inner) => write!(f, "not({})", inner),
+        }
+    }
+}
+
+/// What `cfg` predicates are checked against: a target triple and the enabled
+/// features
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CfgTarget {
+    pub triple: String,
+    pub features: BTreeSet<String>,
+}
+
+impl CfgTarget {
+    pub fn new(triple: &str, features: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            triple: triple.to_string(),
+            features: features.into_iter().collect(),
+        }
+    }
+
+    fn parts(&self) -> Vec<&str> {
+        self.triple.split('-').collect()
+    }
+
+    pub fn arch(&self) -> &str {
+        let arch = self.parts()[0];
+        match arch {
+            "i386" | "i586" | "i686" => "x86",
+            _ if arch.starts_with("aarch64") => "aarch64",
+            _ if arch.starts_with("thumb") || arch.starts_with("arm") => "arm",
+            _ if arch.starts_with("riscv64") => "riscv64",
+            _ if arch.starts_with("riscv32") => "riscv32",
+            _ => arch,
+        }
+    }
+
+    pub fn vendor(&self) -> &str {
+        match self.parts().as_slice() {
+            [_, vendor, _, ..] => vendor,
+            _ => "unknown",
+        }
+    }
+
+    pub fn os(&self) -> &str {
+        let parts = self.parts();
+        if parts.contains(&"android") || parts.iter().any(|p| p.starts_with("androideabi")) {
+            return "android";
+        }
+        for part in parts.iter().skip(1) {
+            match *part {
+                "linux" | "windows" | "ios" | "freebsd" | "netbsd" | "openbsd" | "none" => {
+                    return part
+                }
+                "darwin" | "macos" => return "macos",
+                _ if part.starts_with("wasi") => return "wasi",
+                _ => {}
+            }
+        }
+        // `wasm32-unknown-unknown`: the OS is the third part
+        parts.get(2).copied().unwrap_or("unknown")
+    }
+
+    pub fn env(&self) -> &str {
+        let last = self.parts().last().copied().unwrap_or_default();
+        for env in ["msvc", "musl", "gnu"] {
+            if last.starts_with(env) {
+                return env;
+            }
+        }
+        ""
+    }
+
+    pub fn family(&self) -> Vec<&str> {
+        let mut family = Vec::new();
+        match self.os() {
+            "linux" | "android" | "macos" | "ios" | "freebsd" | "netbsd" | "openbsd" => {
+                family.push("unix")
+            }
+            "windows" => family.push("windows"),
+            _ => {}
+        }
+        if self.arch().starts_with("wasm") {
+            family.push("wasm");
+        }
+        family
+    }
+
+    pub fn pointer_width(&self) -> &str {
+        match self.arch() {
+            "x86_64" | "aarch64" | "riscv64" | "wasm64" | "powerpc64" | "mips64" | "s390x" => "64",
+            _ => "32",
+        }
+    }
+
+    /// Whether every condition of the item holds; unparsable ones are ignored
+    pub fn compiles(&self, item: &AnalyzedItem) -> bool {
+        item.cfg()
+            .iter()
+            .filter_map(|c| Cfg::parse(c))
+            .all(|c| c.eval(self))
+    }
+}
+
+/// Features a [`CfgTarget`] is checked with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeatureSet {
+    /// `default` and the features it lists
+    #[default]
+    Default,
+    All,
+    None,
+}
+
+impl FeatureSet {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Default => "default features",
+            Self::All => "all features",
+            Self::None => "no features",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Default => Self::All,
+            Self::All => Self::None,
+            Self::None => Self::Default,
+        }
+    }
+
+    /// The enabled features of the crate
+    pub fn features(self, info: &CrateInfo) -> Vec<String> {
+        match self {
+            Self::Default if info.features.iter().any(|f| f == "default") => {
+                let mut features = info.default_features.clone();
+                features.push("default".to_string());
+                features
+            }
+            Self::Default | Self::None => Vec::new(),
+            Self::All => info.features.clone(),
+        }
+    }
+}
+
+/// Items sharing the same conditions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CfgGroup {
+    /// `None` for the unconditional items; several conditions are shown as `all(..)`
+    pub condition: Option<String>,
+    /// Indices into the items
+    pub items: Vec<usize>,
+}
+
+/// Items grouped by their conditions, unconditional ones first
+pub fn group_by_cfg(items: &[AnalyzedItem]) -> Vec<CfgGroup> {
+    let mut groups: BTreeMap<Option<String>, Vec<usize>> = BTreeMap::new();
+    for (i, item) in items.iter().enumerate() {
+        let condition = match item.cfg() {
+            [] => None,
+            [one] => Some(one.clone()),
+            many => Some(format!("all({})", many.join(", "))),
+        };
+        groups.entry(condition).or_default().push(i);
+    }
+    groups
+        .into_iter()
+        .map(|(condition, items)| CfgGroup { condition, items })
+        .collect()
+}
+
+/// Conditions of every `mod` in a file, which is `module`, with any; by module path
+/// from the crate root
+pub fn module_cfgs(source: &str, module: &[String]) -> Vec<(Vec<String>, Vec<String>)> {
+    let Ok(ast) = syn::parse_file(source) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    scan_modules(&ast.items, module, &mut out);
+    out
+}
+
+fn scan_modules(items: &[Item], module: &[String], out: &mut Vec<(Vec<String>, Vec<String>)>) {
+    for item in items {
+        let Item::Mod(m) = item else {
+            continue;
+        };
+        let mut path = module.to_vec();
+        path.push(m.ident.to_string());
+        let cfg: Vec<String> = m
+            .attrs
+            .iter()
+            .filter_map(Cfg::from_attribute)
+            .map(|c| c.to_string())
+            .collect();
+        if !cfg.is_empty() {
+            out.push((path.clone(), cfg));
+        }
+        if let Some((_, ref content)) = m.content {
+            scan_modules(content, &path, out);
+        }
+    }
+}
+
+/// Prefix every item's conditions with those of the modules it is inside
+pub fn apply_module_cfgs(items: &mut [AnalyzedItem], modules: &[(Vec<String>, Vec<String>)]) {
+    let mut modules: Vec<&(Vec<String>, Vec<String>)> = modules.iter().collect();
+    modules.sort_by_key(|(path, _)| path.len());
+    for item in items {
+        let inherited: Vec<String> = modules
+            .iter()
+            .filter(|(path, _)| item.module_path().starts_with(path))
+            .flat_map(|(_, cfg)| cfg.iter().cloned())
+            .collect();
+        if inherited.is_empty() {
+            continue;
+        }
+        let own = std::mem::take(item.cfg_mut());
+        let cfg = item.cfg_mut();
+        for c in inherited.into_iter().chain(own) {
+            if !cfg.contains(&c) {
+                cfg.push(c);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+    use std::path::Path;
+
+    #[test]
+    fn test_predicates_evaluate_per_target() {
+        let linux = CfgTarget::new("x86_64-unknown-linux-gnu", ["tui".to_string()]);
+        let windows = CfgTarget::new("x86_64-pc-windows-msvc", []);
+        let wasm = CfgTarget::new("wasm32-unknown-unknown", []);
+        let eval = |text: &str, target: &CfgTarget| Cfg::parse(text).unwrap().eval(target);
+
+        assert!(eval("unix", &linux) && !eval("unix", &windows));
+        assert!(eval(r#"target_os = "windows""#, &windows));
+        assert!(eval(r#"all(feature = "tui", not(windows))"#, &linux));
+        assert!(!eval(r#"feature = "tui""#, &windows));
+        assert!(eval(r#"target_family = "wasm""#, &wasm));
+        assert!(eval(r#"target_os = "unknown""#, &wasm));
+        assert!(!eval("any(unix, windows)", &wasm));
+        assert!(eval(r#"target_pointer_width = "32""#, &wasm));
+        assert!(!eval("test", &linux));
+
+        assert_eq!(CfgTarget::new("aarch64-linux-android", []).os(), "android");
+        assert_eq!(
+            CfgTarget::new("aarch64-apple-darwin", []).family(),
+            vec!["unix"]
+        );
+        assert_eq!(CfgTarget::new("thumbv7em-none-eabihf", []).arch(), "arm");
+        assert_eq!(
+            Cfg::parse(r#"all(unix,not(target_os="macos"))"#)
+                .unwrap()
+                .to_string(),
+            r#"all(unix, not(target_os = "macos"))"#
+        );
+    }
+
+    #[test]
+    fn test_items_inherit_module_conditions() {
+        let source = r#"
+            #[cfg(unix)]
+            pub mod unix {
+                pub fn fd() {}
+                #[cfg(feature = "async")]
+                pub fn poll() {}
+            }
+            #[cfg(windows)]
+            pub fn handle() {}
+            pub fn portable() {}
+        "#;
+        let file = Path::new("src/lib.rs");
+        let mut items = RustAnalyzer::new()
+            .analyze_source_with_path(source, Some(file.to_path_buf()))
+            .unwrap();
+        apply_module_cfgs(&mut items, &module_cfgs(source, &[]));
+        let cfg = |name: &str| {
+            items
+                .iter()
+                .find(|i| i.name() == name)
+                .unwrap()
+                .cfg()
+                .to_vec()
+        };
+        assert_eq!(cfg("unix"), vec!["unix"]);
+        assert_eq!(cfg("fd"), vec!["unix"]);
+        assert_eq!(cfg("poll"), vec!["unix", r#"feature = "async""#]);
+        assert!(cfg("portable").is_empty());
+
+        let groups = group_by_cfg(&items);
+        let labels: Vec<Option<&str>> = groups.iter().map(|g| g.condition.as_deref()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                None,
+                Some(r#"all(unix, feature = "async")"#),
+                Some("unix"),
+                Some("windows")
+            ]
+        );
+
+        let windows = CfgTarget::new("x86_64-pc-windows-msvc", []);
+        let compiled: Vec<&str> = items
+            .iter()
+            .filter(|i| windows.compiles(i))
+            .map(|i| i.name())
+            .collect();
+        assert_eq!(compiled, vec!["handle", "portable"]);
+    }
+}
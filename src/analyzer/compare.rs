@@ -0,0 +1,80 @@
+//! Matching items between two analyzed projects (comparison mode)
+//!
+//! Items correspond when they have the same kind and qualified name; a matched
+//! pair is identical when their rendered definitions are equal.
+
+use super::types::AnalyzedItem;
+
+/// How an item relates to the other project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStatus {
+    /// Present on both sides with the same definition
+    Same,
+    /// Present on both sides, definitions differ
+    Changed,
+    /// No item with this kind and path on the other side
+    Missing,
+}
+
+impl DiffStatus {
+    /// One-character marker for item lists
+    pub fn marker(self) -> &'static str {
+        match self {
+            DiffStatus::Same => "=",
+            DiffStatus::Changed => "≠",
+            DiffStatus::Missing => "+",
+        }
+    }
+}
+
+/// Item in `others` with the same kind and qualified name
+pub fn find_counterpart<'a>(
+    item: &AnalyzedItem,
+    others: &'a [AnalyzedItem],
+) -> Option<&'a AnalyzedItem> {
+    let name = item.qualified_name();
+    others
+        .iter()
+        .find(|o| o.kind() == item.kind() && o.qualified_name() == name)
+}
+
+/// Compare an item against the other project's items
+pub fn diff_status(item: &AnalyzedItem, others: &[AnalyzedItem]) -> DiffStatus {
+    match find_counterpart(item, others) {
+        None => DiffStatus::Missing,
+        Some(other) if other.definition() == item.definition() => DiffStatus::Same,
+        Some(_) => DiffStatus::Changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_diff_status() {
+        let analyzer = RustAnalyzer::new();
+        let upstream = analyzer
+            .analyze_source(
+                "pub struct Config { pub name: String } pub fn load() {} pub fn old() {}",
+            )
+            .unwrap();
+        let fork = analyzer
+            .analyze_source(
+                "pub struct Config { pub name: String, pub verbose: bool } pub fn load() {} pub fn new_api() {}",
+            )
+            .unwrap();
+
+        let status = |name: &str| {
+            let item = fork.iter().find(|i| i.name() == name).unwrap();
+            diff_status(item, &upstream)
+        };
+        assert_eq!(status("Config"), DiffStatus::Changed);
+        assert_eq!(status("load"), DiffStatus::Same);
+        assert_eq!(status("new_api"), DiffStatus::Missing);
+
+        let load = fork.iter().find(|i| i.name() == "load").unwrap();
+        assert!(find_counterpart(load, &upstream).is_some_and(|o| o.name() == "load"));
+    }
+}
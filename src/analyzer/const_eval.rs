@@ -0,0 +1,328 @@
+//! Evaluation of simple constant expressions
+//!
+//! Integer `const`s and enum discriminants are evaluated when they are built from
+//! literals, arithmetic and bit operators, `as` casts, `u8::MAX`-style bounds and
+//! other integer `const`s of the crate (looked up by name). Everything is computed
+//! in `i128`, with `!` taken in the declared type's width so `!0` as a `u16` is
+//! `0xffff`. Only `as` casts wrap: a value that doesn't fit the declared type is
+//! left alone, as rustc rejects the overflow, and so is `u128::MAX`, which doesn't
+//! fit an `i128`. Calls, floats and anything else are left alone too.
+
+use std::collections::HashMap;
+
+use super::types::AnalyzedItem;
+use syn::{BinOp, Expr, Lit, UnOp};
+
+/// Values of the crate's integer `const`s, by name
+#[derive(Debug, Clone, Default)]
+pub struct ConstTable {
+    values: HashMap<String, (i128, String)>,
+}
+
+impl ConstTable {
+    /// Evaluate every `const` in `items`, letting them refer to each other
+    pub fn new(items: &[AnalyzedItem]) -> Self {
+        let consts: Vec<(&str, &str, Expr)> = items
+            .iter()
+            .filter_map(|item| match item {
+                AnalyzedItem::Const(c) if int_bits(&c.ty).is_some() => {
+                    let expr = syn::parse_str(c.value.as_deref()?).ok()?;
+                    Some((c.name.as_str(), c.ty.as_str(), expr))
+                }
+                _ => None,
+            })
+            .collect();
+        let mut table = Self::default();
+        // Each pass resolves at least one more link of a chain of references
+        for _ in 0..=consts.len() {
+            let mut changed = false;
+            for (name, ty, expr) in &consts {
+                if table.values.contains_key(*name) {
+                    continue;
+                }
+                if let Some(value) = table.eval_typed(expr, ty) {
+                    table
+                        .values
+                        .insert(name.to_string(), (value, ty.to_string()));
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        table
+    }
+
+    pub fn get(&self, name: &str) -> Option<i128> {
+        self.values.get(name).map(|(value, _)| *value)
+    }
+
+    /// Value of `expr` as source text, before wrapping to any type
+    pub fn eval(&self, expr: &str) -> Option<i128> {
+        self.eval_expr(&syn::parse_str(expr).ok()?, None)
+    }
+
+    /// Value of `expr` as a `const` of type `ty`; `None` when it doesn't fit
+    pub fn eval_as(&self, expr: &str, ty: &str) -> Option<i128> {
+        self.eval_typed(&syn::parse_str(expr).ok()?, ty)
+    }
+
+    fn eval_typed(&self, expr: &Expr, ty: &str) -> Option<i128> {
+        let (min, max) = int_range(ty)?;
+        self.eval_expr(expr, Some(ty))
+            .filter(|value| (min..=max).contains(value))
+    }
+
+    /// Single-bit `const`s of type `ty` (other than `name`) that together make up
+    /// `value`, e.g. `READ | WRITE`; `None` unless at least two do and every set
+    /// bit is named
+    pub fn flags(&self, name: &str, value: i128, ty: &str) -> Option<Vec<String>> {
+        if value <= 0 || value.count_ones() < 2 {
+            return None;
+        }
+        let mut bits: Vec<(i128, &str)> = self
+            .values
+            .iter()
+            .filter(|(n, (v, t))| *n != name && t == ty && *v > 0 && v.count_ones() == 1)
+            .map(|(n, (v, _))| (*v, n.as_str()))
+            .collect();
+        bits.sort();
+        bits.dedup_by_key(|(v, _)| *v);
+        let named = bits
+            .iter()
+            .filter(|(bit, _)| value & bit != 0)
+            .fold(0, |acc, (bit, _)| acc | bit);
+        (named == value).then(|| {
+            bits.iter()
+                .filter(|(bit, _)| value & bit != 0)
+                .map(|(_, n)| n.to_string())
+                .collect()
+        })
+    }
+
+    /// `ty` is the integer type `expr` is evaluated in, when known
+    fn eval_expr(&self, expr: &Expr, ty: Option<&str>) -> Option<i128> {
+        match expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(int) => int.base10_parse().ok(),
+                Lit::Byte(byte) => Some(byte.value().into()),
+                Lit::Char(c) => Some(u32::from(c.value()).into()),
+                _ => None,
+            },
+            Expr::Paren(p) => self.eval_expr(&p.expr, ty),
+            Expr::Group(g) => self.eval_expr(&g.expr, ty),
+            Expr::Unary(u) => {
+                let value = self.eval_expr(&u.expr, ty)?;
+                match u.op {
+                    UnOp::Neg(_) => value.checked_neg(),
+                    UnOp::Not(_) => Some(ty.map_or(!value, |ty| wrap(!value, ty))),
+                    _ => None,
+                }
+            }
+            Expr::Binary(b) => {
+                let lhs = self.eval_expr(&b.left, ty)?;
+                // A shift amount has a type of its own
+                let rhs = match b.op {
+                    BinOp::Shl(_) | BinOp::Shr(_) => self.eval_expr(&b.right, None)?,
+                    _ => self.eval_expr(&b.right, ty)?,
+                };
+                match b.op {
+                    BinOp::Add(_) => lhs.checked_add(rhs),
+                    BinOp::Sub(_) => lhs.checked_sub(rhs),
+                    BinOp::Mul(_) => lhs.checked_mul(rhs),
+                    BinOp::Div(_) => lhs.checked_div(rhs),
+                    BinOp::Rem(_) => lhs.checked_rem(rhs),
+                    BinOp::Shl(_) => {
+                        let n = u32::try_from(rhs).ok()?;
+                        lhs.checked_shl(n).filter(|value| value >> n == lhs)
+                    }
+                    BinOp::Shr(_) => lhs.checked_shr(u32::try_from(rhs).ok()?),
+                    BinOp::BitAnd(_) => Some(lhs & rhs),
+                    BinOp::BitOr(_) => Some(lhs | rhs),
+                    BinOp::BitXor(_) => Some(lhs ^ rhs),
+                    _ => None,
+                }
+            }
+            Expr::Cast(cast) => {
+                let ty = super::pretty::ty(&cast.ty);
+                int_bits(&ty)?;
+                Some(wrap(self.eval_expr(&cast.expr, None)?, &ty))
+            }
+            Expr::Path(p) if p.qself.is_none() => {
+                let segments: Vec<String> = p
+                    .path
+                    .segments
+                    .iter()
+                    .map(|s| s.ident.to_string())
+                    .collect();
+                match segments.as_slice() {
+                    [ty, bound] if int_bits(ty).is_some() => {
+                        let (min, max) = int_range(ty)?;
+                        match bound.as_str() {
+                            "MIN" => Some(min),
+                            "MAX" if ty == "u128" => None,
+                            "MAX" => Some(max),
+                            "BITS" => Some(int_bits(ty)?.0.into()),
+                            _ => None,
+                        }
+                    }
+                    [.., name] => self.get(name),
+                    [] => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Width and signedness of a primitive integer type (`isize`/`usize` as 64-bit)
+pub fn int_bits(ty: &str) -> Option<(u32, bool)> {
+    Some(match ty {
+        "u8" => (8, false),
+        "u16" => (16, false),
+        "u32" => (32, false),
+        "u64" | "usize" => (64, false),
+        "u128" => (128, false),
+        "i8" => (8, true),
+        "i16" => (16, true),
+        "i32" => (32, true),
+        "i64" | "isize" => (64, true),
+        "i128" => (128, true),
+        _ => return None,
+    })
+}
+
+/// Inclusive range of a primitive integer type (`u128` capped at `i128::MAX`, the
+/// most that is evaluated)
+pub fn int_range(ty: &str) -> Option<(i128, i128)> {
+    Some(match int_bits(ty)? {
+        (128, true) => (i128::MIN, i128::MAX),
+        (128, false) => (0, i128::MAX),
+        (n, true) => (-(1i128 << (n - 1)), (1i128 << (n - 1)) - 1),
+        (n, false) => (0, (1i128 << n) - 1),
+    })
+}
+
+/// `value` wrapped into `ty` as an `as` cast would; unchanged for 128-bit and
+/// non-integer types
+pub fn wrap(value: i128, ty: &str) -> i128 {
+    match int_bits(ty) {
+        Some((n, signed)) if n < 128 => {
+            let unsigned = value.rem_euclid(1i128 << n);
+            if signed && unsigned >= 1i128 << (n - 1) {
+                unsigned - (1i128 << n)
+            } else {
+                unsigned
+            }
+        }
+        _ => value,
+    }
+}
+
+/// Bits of `value` as stored in `ty` (two's complement for negative values)
+fn stored_bits(value: i128, ty: &str) -> u128 {
+    match int_bits(ty) {
+        Some((n, _)) if n < 128 => (value as u128) & ((1u128 << n) - 1),
+        _ => value as u128,
+    }
+}
+
+/// `0x1f`, negative values shown as stored in `ty`
+pub fn hex(value: i128, ty: &str) -> String {
+    format!("0x{:x}", stored_bits(value, ty))
+}
+
+/// `0b0001_1111`, padded to whole nibbles and grouped by four
+pub fn binary(value: i128, ty: &str) -> String {
+    let digits = format!("{:b}", stored_bits(value, ty));
+    let padded = format!("{}{}", "0".repeat((4 - digits.len() % 4) % 4), digits);
+    let groups: Vec<&str> = padded
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+    format!("0b{}", groups.join("_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_consts_evaluate_in_terms_of_each_other() {
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                r#"
+                pub const READ: u8 = 1 << 0;
+                pub const WRITE: u8 = 1 << 1;
+                pub const EXEC: u8 = 0b100;
+                pub const ALL: u8 = READ | WRITE | EXEC;
+                pub const RW: u8 = ALL & !EXEC;
+                pub const PAGE: usize = 4 * 1024;
+                pub const LAST: i8 = (200u16 as i8) - 1;
+                pub const TOP: u32 = u16::MAX as u32 + 1;
+                pub const NAME: &str = "x";
+                pub const RATIO: f64 = 1.5;
+                "#,
+            )
+            .unwrap();
+        let table = ConstTable::new(&items);
+        assert_eq!(table.get("ALL"), Some(7));
+        assert_eq!(table.get("RW"), Some(3));
+        assert_eq!(table.get("PAGE"), Some(4096));
+        assert_eq!(table.get("LAST"), Some(-57));
+        assert_eq!(table.get("TOP"), Some(65536));
+        assert_eq!(table.get("NAME"), None);
+        assert_eq!(table.get("RATIO"), None);
+
+        assert_eq!(
+            table.flags("ALL", 7, "u8"),
+            Some(vec!["READ".into(), "WRITE".into(), "EXEC".into()])
+        );
+        assert_eq!(table.flags("WRITE", 2, "u8"), None);
+        assert_eq!(table.flags("X", 9, "u8"), None);
+        assert_eq!(table.eval("EXEC << 2"), Some(16));
+    }
+
+    #[test]
+    fn test_representations() {
+        assert_eq!(hex(255, "u8"), "0xff");
+        assert_eq!(hex(-1, "i16"), "0xffff");
+        assert_eq!(binary(5, "u8"), "0b0101");
+        assert_eq!(binary(0x1f0, "u16"), "0b0001_1111_0000");
+        assert_eq!(wrap(-1, "u32"), 0xffff_ffff);
+        assert_eq!(wrap(128, "i8"), -128);
+        assert_eq!(int_range("u128"), Some((0, i128::MAX)));
+    }
+
+    #[test]
+    fn test_overflow_is_left_unevaluated() {
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                r#"
+                pub const E: u8 = 255 + 1;
+                pub const G: u32 = 1 << 40;
+                pub const H: u64 = 1 << 40;
+                pub const TOP: u128 = u128::MAX;
+                pub const HIGH: i128 = 3 << 126;
+                pub const MASK: u16 = !0;
+                pub const LOW: u8 = 300u16 as u8;
+                pub const NEG: u32 = -1;
+                "#,
+            )
+            .unwrap();
+        let table = ConstTable::new(&items);
+        assert_eq!(table.get("E"), None);
+        assert_eq!(table.get("G"), None);
+        assert_eq!(table.get("H"), Some(1 << 40));
+        assert_eq!(table.get("TOP"), None);
+        assert_eq!(table.get("HIGH"), None);
+        assert_eq!(table.get("MASK"), Some(0xffff));
+        assert_eq!(table.get("LOW"), Some(44));
+        assert_eq!(table.get("NEG"), None);
+        assert_eq!(table.eval_as("u8::MAX + 1", "u8"), None);
+        assert_eq!(table.eval_as("u8::MAX + 1", "u16"), Some(256));
+    }
+}
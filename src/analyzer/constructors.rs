@@ -0,0 +1,324 @@
+//! Ways to construct a type, as ready-to-paste snippets
+//!
+//! Looks through the type's inherent impls for associated functions returning
+//! `Self` (`new`, `with_*`, `from_*`, ...) and `builder()`s, its `Default` /
+//! `From` / `FromStr` impls, and falls back to the struct literal. Arguments
+//! are filled in with the parameter names so the snippet reads like a call site.
+
+use super::types::{AnalyzedItem, FunctionInfo, StructInfo, StructKind, Visibility};
+use super::usage::base_type_name;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConstructorKind {
+    /// `new` or `new_*`
+    New,
+    /// `builder()`, chained through the builder's setters
+    Builder,
+    /// `Default` (derived or implemented)
+    Default,
+    /// Other associated functions returning `Self` (`from_*`, `with_*`, ...)
+    Associated,
+    /// `impl From<T>` / `impl FromStr`
+    Conversion,
+    /// `Type { .. }` / `Type(..)`
+    Literal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constructor {
+    pub kind: ConstructorKind,
+    /// e.g. `Config::new`, `impl From<PathBuf>`, `struct literal`
+    pub label: String,
+    /// Code to paste; may span several lines
+    pub snippet: String,
+}
+
+/// Constructors of `st`, most idiomatic first
+pub fn constructors(st: &StructInfo, items: &[AnalyzedItem]) -> Vec<Constructor> {
+    let name = st.name.as_str();
+    let mut found = Vec::new();
+    let mut has_default = st.derives.iter().any(|d| d == "Default");
+
+    for item in items {
+        let AnalyzedItem::Impl(im) = item else {
+            continue;
+        };
+        if base_type_name(&im.self_ty) != Some(name) {
+            continue;
+        }
+        match im.trait_name.as_deref().map(trait_base) {
+            None => {
+                for method in im.methods.iter().filter(|m| is_constructor(m, name)) {
+                    found.push(associated(method, name, items));
+                }
+            }
+            Some("Default") => has_default = true,
+            Some("From") => {
+                let from = generic_arg(im.trait_name.as_deref().unwrap_or_default());
+                found.push(Constructor {
+                    kind: ConstructorKind::Conversion,
+                    label: format!("impl From<{}>", from),
+                    snippet: format!("let value: {} = {}::from(value);", name, name),
+                });
+            }
+            Some("FromStr") => found.push(Constructor {
+                kind: ConstructorKind::Conversion,
+                label: "impl FromStr".to_string(),
+                snippet: format!("let value: {} = \"...\".parse()?;", name),
+            }),
+            _ => {}
+        }
+    }
+    if has_default {
+        found.push(Constructor {
+            kind: ConstructorKind::Default,
+            label: "Default".to_string(),
+            snippet: format!("let value = {}::default();", name),
+        });
+    }
+    found.push(literal(st, has_default));
+    found.sort_by_key(|c| c.kind);
+    found
+}
+
+fn trait_base(path: &str) -> &str {
+    let head = path.split('<').next().unwrap_or(path).trim();
+    head.rsplit("::").next().unwrap_or(head)
+}
+
+/// `T` of `From<T>`
+fn generic_arg(path: &str) -> &str {
+    path.split_once('<')
+        .and_then(|(_, rest)| rest.strip_suffix('>'))
+        .unwrap_or("_")
+}
+
+/// Associated function (no `self`) returning `Self`, possibly in a `Result` / `Option`,
+/// or a `builder()`
+fn is_constructor(method: &FunctionInfo, type_name: &str) -> bool {
+    if method.parameters.iter().any(|p| p.is_self) || method.visibility != Visibility::Public {
+        return false;
+    }
+    let Some(ref ret) = method.return_type else {
+        return false;
+    };
+    method.name == "builder" || returns_self(ret, type_name)
+}
+
+fn returns_self(ret: &str, type_name: &str) -> bool {
+    let inner = ["Result<", "Option<", "io::Result<", "anyhow::Result<"]
+        .iter()
+        .find_map(|wrapper| ret.strip_prefix(wrapper))
+        .unwrap_or(ret);
+    let head = inner.split([',', '>', '<']).next().unwrap_or(inner).trim();
+    head == "Self" || head == type_name
+}
+
+fn associated(method: &FunctionInfo, type_name: &str, items: &[AnalyzedItem]) -> Constructor {
+    let args: Vec<&str> = method
+        .parameters
+        .iter()
+        .map(|p| p.name.trim_start_matches("mut "))
+        .collect();
+    let ret = method.return_type.as_deref().unwrap_or_default();
+    let suffix = if ret.starts_with("Result") || ret.contains("::Result") {
+        "?"
+    } else if ret.starts_with("Option") {
+        ".expect(\"...\")"
+    } else {
+        ""
+    };
+    let call = format!(
+        "{}::{}({}){}",
+        type_name,
+        method.name,
+        args.join(", "),
+        suffix
+    );
+
+    if method.name == "builder" {
+        let builder = base_type_name(ret).unwrap_or(ret);
+        return Constructor {
+            kind: ConstructorKind::Builder,
+            label: format!("{}::builder", type_name),
+            snippet: builder_chain(&call, builder, type_name, items),
+        };
+    }
+    let kind = if method.name == "new" || method.name.starts_with("new_") {
+        ConstructorKind::New
+    } else {
+        ConstructorKind::Associated
+    };
+    Constructor {
+        kind,
+        label: format!("{}::{}", type_name, method.name),
+        snippet: format!("let value = {};", call),
+    }
+}
+
+/// `Type::builder()` followed by the builder's setters and its finishing method
+fn builder_chain(call: &str, builder: &str, type_name: &str, items: &[AnalyzedItem]) -> String {
+    let mut setters = Vec::new();
+    let mut finish = None;
+    let methods = items.iter().filter_map(|item| match item {
+        AnalyzedItem::Impl(im)
+            if im.trait_name.is_none() && base_type_name(&im.self_ty) == Some(builder) =>
+        {
+            Some(&im.methods)
+        }
+        _ => None,
+    });
+    for method in methods.flatten() {
+        let takes_self = method.parameters.first().is_some_and(|p| p.is_self);
+        let Some(ref ret) = method.return_type else {
+            continue;
+        };
+        if !takes_self || method.visibility != Visibility::Public {
+            continue;
+        }
+        let args: Vec<&str> = method
+            .parameters
+            .iter()
+            .filter(|p| !p.is_self)
+            .map(|p| p.name.trim_start_matches("mut "))
+            .collect();
+        if returns_self(ret, builder) {
+            setters.push(format!("    .{}({})", method.name, args.join(", ")));
+        } else if returns_self(ret, type_name) && finish.is_none() {
+            let fallible = ret.starts_with("Result") || ret.contains("::Result");
+            finish = Some(format!(
+                "    .{}({}){}",
+                method.name,
+                args.join(", "),
+                if fallible { "?" } else { "" }
+            ));
+        }
+    }
+    let mut lines = vec![format!("let value = {}", call)];
+    lines.extend(setters);
+    lines.push(finish.unwrap_or_else(|| "    .build()".to_string()));
+    let mut snippet = lines.join("\n");
+    snippet.push(';');
+    snippet
+}
+
+/// Struct literal with every field, `..Default::default()` standing in for the
+/// private ones when the type is `Default`
+fn literal(st: &StructInfo, has_default: bool) -> Constructor {
+    let public: Vec<_> = st
+        .fields
+        .iter()
+        .filter(|f| f.visibility == Visibility::Public)
+        .collect();
+    let all_public = public.len() == st.fields.len();
+    let label = if all_public {
+        "struct literal".to_string()
+    } else {
+        "struct literal (private fields: only inside its module)".to_string()
+    };
+    let snippet = match st.kind {
+        StructKind::Unit => format!("let value = {};", st.name),
+        StructKind::Tuple => {
+            let fields: Vec<String> = st
+                .fields
+                .iter()
+                .map(|f| format!("todo!() /* {} */", f.ty))
+                .collect();
+            format!("let value = {}({});", st.name, fields.join(", "))
+        }
+        StructKind::Named => {
+            let shown: Vec<_> = if !all_public && has_default {
+                public
+            } else {
+                st.fields.iter().collect()
+            };
+            let mut lines = vec![format!("let value = {} {{", st.name)];
+            for field in &shown {
+                lines.push(format!("    {}: todo!(), // {}", field.name, field.ty));
+            }
+            if shown.len() < st.fields.len() {
+                lines.push("    ..Default::default()".to_string());
+            }
+            lines.push("};".to_string());
+            lines.join("\n")
+        }
+    };
+    Constructor {
+        kind: ConstructorKind::Literal,
+        label,
+        snippet,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    fn struct_constructors(source: &str, name: &str) -> Vec<Constructor> {
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        let st = items
+            .iter()
+            .find_map(|item| match item {
+                AnalyzedItem::Struct(s) if s.name == name => Some(s),
+                _ => None,
+            })
+            .expect("struct");
+        constructors(st, &items)
+    }
+
+    #[test]
+    fn test_constructors_and_snippets() {
+        let found = struct_constructors(
+            r#"
+            #[derive(Default)]
+            pub struct Client { pub url: String, timeout: u64 }
+            impl Client {
+                pub fn new(url: &str) -> Self { todo!() }
+                pub fn from_env() -> Result<Self, Error> { todo!() }
+                pub fn builder() -> ClientBuilder { todo!() }
+                pub fn url(&self) -> &str { &self.url }
+            }
+            pub struct ClientBuilder;
+            impl ClientBuilder {
+                pub fn timeout(mut self, secs: u64) -> Self { self }
+                pub fn build(self) -> Result<Client, Error> { todo!() }
+            }
+            impl From<Url> for Client { fn from(u: Url) -> Self { todo!() } }
+            "#,
+            "Client",
+        );
+        let labels: Vec<&str> = found.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Client::new",
+                "Client::builder",
+                "Default",
+                "Client::from_env",
+                "impl From<Url>",
+                "struct literal (private fields: only inside its module)",
+            ]
+        );
+        assert_eq!(found[0].snippet, "let value = Client::new(url);");
+        assert_eq!(
+            found[1].snippet,
+            "let value = Client::builder()\n    .timeout(secs)\n    .build()?;"
+        );
+        assert_eq!(found[3].snippet, "let value = Client::from_env()?;");
+        assert_eq!(
+            found[5].snippet,
+            "let value = Client {\n    url: todo!(), // String\n    ..Default::default()\n};"
+        );
+    }
+
+    #[test]
+    fn test_literal_only() {
+        let found = struct_constructors("pub struct Point(pub i32, pub i32);", "Point");
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].snippet,
+            "let value = Point(todo!() /* i32 */, todo!() /* i32 */);"
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! Test coverage from an LCOV file or a `cargo llvm-cov --json` export
+//!
+//! Both formats are reduced to execution counts per source line, then summed over
+//! an item's line range (`SourceLocation::line ..= end_line`). Report paths are
+//! matched to analyzed files by trailing components, so relative paths (tarpaulin)
+//! and absolute ones (llvm-cov) both work.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::types::{AnalyzedItem, FunctionInfo, ImplInfo, SourceLocation};
+use crate::error::{OracleError, Result};
+
+/// Covered / instrumented lines of an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Coverage {
+    pub covered: usize,
+    pub total: usize,
+}
+
+impl Coverage {
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.covered as f64 * 100.0 / self.total as f64
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.covered == self.total
+    }
+
+    fn add(&mut self, other: Coverage) {
+        self.covered += other.covered;
+        self.total += other.total;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CoverageData {
+    /// File as written in the report → line → execution count
+    files: HashMap<PathBuf, BTreeMap<usize, u64>>,
+    /// Report the data was read from, for the status line
+    pub source: PathBuf,
+}
+
+impl CoverageData {
+    /// Read an LCOV tracefile or llvm-cov JSON export (detected by content)
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut data = if text.trim_start().starts_with('{') {
+            Self::from_llvm_json(&text)?
+        } else {
+            Self::from_lcov(&text)
+        };
+        if data.files.is_empty() {
+            return Err(OracleError::Parse(format!(
+                "{}: no line coverage records",
+                path.display()
+            )));
+        }
+        data.source = path.to_path_buf();
+        Ok(data)
+    }
+
+    /// `SF:` / `DA:<line>,<count>` records; everything else is ignored
+    pub fn from_lcov(text: &str) -> Self {
+        let mut files: HashMap<PathBuf, BTreeMap<usize, u64>> = HashMap::new();
+        let mut current: Option<PathBuf> = None;
+        for line in text.lines().map(str::trim) {
+            if let Some(file) = line.strip_prefix("SF:") {
+                current = Some(PathBuf::from(file));
+            } else if line == "end_of_record" {
+                current = None;
+            } else if let (Some(da), Some(file)) = (line.strip_prefix("DA:"), &current) {
+                let mut parts = da.split(',');
+                let (Some(Ok(line)), Some(Ok(count))) = (
+                    parts.next().map(str::parse::<usize>),
+                    parts.next().map(str::parse::<u64>),
+                ) else {
+                    continue;
+                };
+                let hits = files
+                    .entry(file.clone())
+                    .or_default()
+                    .entry(line)
+                    .or_default();
+                *hits = (*hits).max(count);
+            }
+        }
+        Self {
+            files,
+            source: PathBuf::new(),
+        }
+    }
+
+    /// `llvm-cov export` JSON: line counts are derived from each file's segments the
+    /// way `llvm-cov report` does
+    pub fn from_llvm_json(text: &str) -> Result<Self> {
+        let json: Value = serde_json::from_str(text)
+            .map_err(|e| OracleError::Parse(format!("invalid llvm-cov JSON: {}", e)))?;
+        let mut files = HashMap::new();
+        let exports = json["data"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for file in exports
+            .iter()
+            .filter_map(|d| d["files"].as_array())
+            .flatten()
+        {
+            let Some(name) = file["filename"].as_str() else {
+                continue;
+            };
+            let segments: Vec<Segment> = file["segments"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Segment::from_json)
+                .collect();
+            files.insert(PathBuf::from(name), line_counts(&segments));
+        }
+        Ok(Self {
+            files,
+            source: PathBuf::new(),
+        })
+    }
+
+    /// Line counts of the report file that `file` refers to
+    fn lines_of(&self, file: &Path) -> Option<&BTreeMap<usize, u64>> {
+        self.files.get(file).or_else(|| {
+            self.files
+                .iter()
+                .find(|(path, _)| path.ends_with(file) || file.ends_with(path))
+                .map(|(_, lines)| lines)
+        })
+    }
+
+    /// Coverage of the lines `location` spans; `None` without a range or without
+    /// instrumented lines in it
+    pub fn for_location(&self, location: &SourceLocation) -> Option<Coverage> {
+        let (Some(file), Some(start), Some(end)) =
+            (&location.file, location.line, location.end_line)
+        else {
+            return None;
+        };
+        let lines = self.lines_of(file)?;
+        let mut coverage = Coverage::default();
+        for (_, &count) in lines.range(start..=end) {
+            coverage.total += 1;
+            if count > 0 {
+                coverage.covered += 1;
+            }
+        }
+        (coverage.total > 0).then_some(coverage)
+    }
+
+    pub fn for_function(&self, func: &FunctionInfo) -> Option<Coverage> {
+        self.for_location(&func.source_location)
+    }
+
+    /// Functions and impl blocks (all their methods); other items have no code to run
+    pub fn for_item(&self, item: &AnalyzedItem) -> Option<Coverage> {
+        match item {
+            AnalyzedItem::Function(f) => self.for_function(f),
+            AnalyzedItem::Impl(im) => self.for_impl(im),
+            _ => None,
+        }
+    }
+
+    /// All methods of the impl block together
+    pub fn for_impl(&self, im: &ImplInfo) -> Option<Coverage> {
+        let mut total = Coverage::default();
+        let mut any = false;
+        for method in &im.methods {
+            if let Some(c) = self.for_function(method) {
+                total.add(c);
+                any = true;
+            }
+        }
+        any.then_some(total)
+    }
+}
+
+/// One entry of a file's `segments`: `[line, col, count, hasCount, isRegionEntry, isGapRegion]`
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    line: usize,
+    count: u64,
+    has_count: bool,
+    is_region_entry: bool,
+    is_gap: bool,
+}
+
+impl Segment {
+    fn from_json(value: &Value) -> Option<Self> {
+        let fields = value.as_array()?;
+        Some(Self {
+            line: fields.first()?.as_u64()? as usize,
+            count: fields.get(2)?.as_u64()?,
+            has_count: fields.get(3)?.as_bool()?,
+            is_region_entry: fields.get(4)?.as_bool()?,
+            is_gap: fields.get(5).and_then(Value::as_bool).unwrap_or(false),
+        })
+    }
+
+    fn starts_region(&self) -> bool {
+        self.has_count && self.is_region_entry && !self.is_gap
+    }
+}
+
+/// Per-line counts from segments (sorted by position), following llvm's `LineCoverageStats`:
+/// a line is instrumented if a region starts on it or a counted region wraps into it,
+/// and its count is the largest of those
+fn line_counts(segments: &[Segment]) -> BTreeMap<usize, u64> {
+    let mut counts = BTreeMap::new();
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return counts;
+    };
+    let mut wrapped: Option<Segment> = None;
+    let mut next = 0;
+    for line in first.line..=last.line {
+        let start = next;
+        while next < segments.len() && segments[next].line == line {
+            next += 1;
+        }
+        let on_line = &segments[start..next];
+
+        let skipped = on_line
+            .first()
+            .is_some_and(|s| !s.has_count && s.is_region_entry);
+        let starts = on_line.iter().filter(|s| s.starts_region()).count();
+        let wrapped_count = wrapped.filter(|s| s.has_count).map(|s| s.count);
+        if !skipped && (wrapped_count.is_some() || starts > 0) {
+            let count = on_line
+                .iter()
+                .filter(|s| s.starts_region())
+                .map(|s| s.count)
+                .chain(wrapped_count)
+                .max()
+                .unwrap_or(0);
+            counts.insert(line, count);
+        }
+        if let Some(last) = on_line.last() {
+            wrapped = Some(*last);
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_lcov_item_coverage() {
+        let source = "pub fn tested() -> u8 {\n    1\n}\n\npub fn untested(x: u8) -> u8 {\n    if x > 0 {\n        x\n    } else {\n        0\n    }\n}\n";
+        let items = RustAnalyzer::new()
+            .analyze_source_with_path(source, Some(PathBuf::from("/work/proj/src/lib.rs")))
+            .unwrap();
+        let lcov = "TN:\nSF:src/lib.rs\nFN:1,tested\nDA:1,4\nDA:2,4\nDA:3,4\nDA:5,1\nDA:6,1\nDA:7,1\nDA:9,0\nDA:11,1\nend_of_record\n";
+        let data = CoverageData::from_lcov(lcov);
+
+        let tested = data.for_item(&items[0]).unwrap();
+        assert_eq!((tested.covered, tested.total), (3, 3));
+        assert!(tested.is_full());
+        let untested = data.for_item(&items[1]).unwrap();
+        assert_eq!((untested.covered, untested.total), (4, 5));
+        assert_eq!(untested.percent(), 80.0);
+    }
+
+    #[test]
+    fn test_llvm_json_segments() {
+        // The region starting on line 3 never runs, but the line still counts as
+        // executed because the enclosing region wraps into it (as in llvm-cov)
+        let json = r#"{"type": "llvm.coverage.json.export", "data": [{"files": [{
+            "filename": "/work/proj/src/lib.rs",
+            "segments": [
+                [1, 20, 2, true, true, false],
+                [3, 5, 0, true, true, false],
+                [3, 12, 2, true, false, false],
+                [5, 2, 0, false, false, false]
+            ]}]}]}"#;
+        let data = CoverageData::from_llvm_json(json).unwrap();
+        let lines = data.lines_of(Path::new("src/lib.rs")).unwrap();
+        assert_eq!(
+            lines.iter().map(|(l, c)| (*l, *c)).collect::<Vec<_>>(),
+            vec![(1, 2), (2, 2), (3, 2), (4, 2), (5, 2)]
+        );
+        let unrun = r#"{"data": [{"files": [{"filename": "a.rs", "segments": [
+            [1, 1, 0, true, true, false], [2, 2, 0, false, false, false]]}]}]}"#;
+        let data = CoverageData::from_llvm_json(unrun).unwrap();
+        let location = SourceLocation {
+            file: Some(PathBuf::from("/x/a.rs")),
+            line: Some(1),
+            end_line: Some(2),
+            column: None,
+        };
+        assert_eq!(
+            data.for_location(&location),
+            Some(Coverage {
+                covered: 0,
+                total: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Items nothing else in the project refers to
+//!
+//! Every identifier token of every source file is counted (comments and string
+//! literals aren't tokens). An item whose name occurs no more often than it is
+//! declared — its own definition, plus impl headers for types — is possibly unused.
+//! Name collisions only hide candidates, so the list errs towards too short.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use proc_macro2::{TokenStream, TokenTree};
+
+use super::types::{AnalyzedItem, SourceLocation, Visibility};
+use super::usage::base_type_name;
+
+/// How sure we are that an unreferenced item is dead
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    /// Not `pub`: only this crate could use it
+    High,
+    /// `pub` in a crate without a library target
+    Medium,
+    /// Part of a library's public API; dependents may use it
+    Low,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Confidence::High => write!(f, "high"),
+            Confidence::Medium => write!(f, "medium"),
+            Confidence::Low => write!(f, "low"),
+        }
+    }
+}
+
+/// An item (or inherent method) that is never referenced
+#[derive(Debug, Clone)]
+pub struct UnusedItem {
+    pub kind: &'static str,
+    /// Qualified name, e.g. `config::load` or `Parser::reset`
+    pub path: String,
+    pub confidence: Confidence,
+    pub location: SourceLocation,
+}
+
+/// Add the identifiers in `source` to `counts`; a file that doesn't tokenize adds none
+pub fn count_identifiers(source: &str, counts: &mut HashMap<String, usize>) {
+    if let Ok(tokens) = source.parse::<TokenStream>() {
+        count_tokens(tokens, counts);
+    }
+}
+
+fn count_tokens(tokens: TokenStream, counts: &mut HashMap<String, usize>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                let name = ident.to_string();
+                let name = name.strip_prefix("r#").map(String::from).unwrap_or(name);
+                *counts.entry(name).or_default() += 1;
+            }
+            TokenTree::Group(group) => count_tokens(group.stream(), counts),
+            _ => {}
+        }
+    }
+}
+
+/// Items of `items` whose names appear nowhere but in their declarations, by
+/// confidence then path. `main`, tests, trait impl methods and `#[no_mangle]` /
+/// `#[export_name]` functions are skipped; `library` lowers the confidence of `pub` items.
+pub fn find_unused(
+    items: &[AnalyzedItem],
+    identifiers: &HashMap<String, usize>,
+    library: bool,
+) -> Vec<UnusedItem> {
+    let declarations = declaration_counts(items);
+    let unreferenced = |name: &str| {
+        let uses = identifiers.get(name).copied().unwrap_or(0);
+        uses <= declarations.get(name).copied().unwrap_or(0)
+    };
+    let confidence = |visibility: Visibility| match visibility {
+        Visibility::Public if library => Confidence::Low,
+        Visibility::Public => Confidence::Medium,
+        _ => Confidence::High,
+    };
+
+    let mut unused = Vec::new();
+    for item in items {
+        if in_tests(item.module_path()) {
+            continue;
+        }
+        if let AnalyzedItem::Impl(im) = item {
+            if im.trait_name.is_some() {
+                continue;
+            }
+            let owner = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+            for method in &im.methods {
+                if unreferenced(&method.name) && !is_entry_point(&method.attributes) {
+                    unused.push(UnusedItem {
+                        kind: "fn",
+                        path: format!("{}::{}", owner, method.name),
+                        confidence: confidence(method.visibility),
+                        location: method.source_location.clone(),
+                    });
+                }
+            }
+            continue;
+        }
+        let attributes: &[String] = match item {
+            AnalyzedItem::Function(f) if f.name == "main" => continue,
+            AnalyzedItem::Function(f) => &f.attributes,
+            AnalyzedItem::Struct(s) => &s.attributes,
+            AnalyzedItem::Enum(e) => &e.attributes,
+            AnalyzedItem::Module(_) => continue,
+            _ => &[],
+        };
+        let (Some(visibility), Some(location)) = (item.visibility(), item.source_location()) else {
+            continue;
+        };
+        if unreferenced(item.name()) && !is_entry_point(attributes) {
+            unused.push(UnusedItem {
+                kind: item.kind(),
+                path: item.qualified_name(),
+                confidence: confidence(visibility),
+                location: location.clone(),
+            });
+        }
+    }
+    unused.sort_by(|a, b| (a.confidence, &a.path).cmp(&(b.confidence, &b.path)));
+    unused
+}
+
+/// How often each name is written by a declaration rather than a use
+fn declaration_counts(items: &[AnalyzedItem]) -> HashMap<&str, usize> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for item in items {
+        match item {
+            AnalyzedItem::Impl(im) => {
+                // `impl Trait for Type` names both without using either
+                let names = [Some(im.self_ty.as_str()), im.trait_name.as_deref()];
+                for name in names.into_iter().flatten().filter_map(base_type_name) {
+                    *counts.entry(name).or_default() += 1;
+                }
+                for method in &im.methods {
+                    *counts.entry(&method.name).or_default() += 1;
+                }
+            }
+            AnalyzedItem::Trait(t) => {
+                *counts.entry(&t.name).or_default() += 1;
+                for method in &t.methods {
+                    *counts.entry(&method.name).or_default() += 1;
+                }
+            }
+            _ => *counts.entry(item.name()).or_default() += 1,
+        }
+    }
+    counts
+}
+
+fn in_tests(module_path: &[String]) -> bool {
+    module_path.iter().any(|m| m == "tests" || m == "test")
+}
+
+/// Called by the test harness, the linker or a proc macro rather than by name
+fn is_entry_point(attributes: &[String]) -> bool {
+    attributes.iter().any(|a| {
+        let inner = a.trim_start_matches("#[").trim_end_matches(']');
+        let inner = inner.strip_prefix("unsafe(").unwrap_or(inner);
+        let name = inner.split(['(', ' ', '=', ']']).next().unwrap_or_default();
+        matches!(
+            name,
+            "test"
+                | "bench"
+                | "no_mangle"
+                | "export_name"
+                | "proc_macro"
+                | "proc_macro_derive"
+                | "proc_macro_attribute"
+        ) || name.ends_with("::test")
+            || inner.starts_with("cfg(test)")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_find_unused() {
+        let source = "pub fn api() {}\nfn helper() -> Config { Config }\nfn orphan() {}\n\
+                      fn main() { helper(); }\npub struct Config;\nstruct Ghost;\n\
+                      impl Ghost { fn spooky(&self) {} }\nimpl Default for Ghost { fn default() -> Self { Ghost } }\n\
+                      #[no_mangle]\npub extern \"C\" fn exported() {}\n\
+                      #[cfg(test)]\nmod tests { fn fixture() {} }\n";
+        let items = RustAnalyzer::new()
+            .with_private(true)
+            .analyze_source(source)
+            .unwrap();
+        let mut identifiers = HashMap::new();
+        count_identifiers(source, &mut identifiers);
+        assert_eq!(identifiers["Config"], 3);
+
+        let names = |library: bool| {
+            find_unused(&items, &identifiers, library)
+                .into_iter()
+                .map(|u| (u.path, u.confidence))
+                .collect::<Vec<_>>()
+        };
+        // `Ghost` is built by its own `Default` impl, which counts as a use
+        assert_eq!(
+            names(true),
+            vec![
+                ("Ghost::spooky".to_string(), Confidence::High),
+                ("orphan".to_string(), Confidence::High),
+                ("api".to_string(), Confidence::Low),
+            ]
+        );
+        assert_eq!(names(false)[2].1, Confidence::Medium);
+    }
+}
@@ -1,9 +1,15 @@
 //! Dependency analysis using cargo_metadata
 
+use super::duplicates::{find_duplicates, DuplicateCrate};
+use super::features::FeatureUnification;
+use super::msrv::MsrvReport;
+use super::no_std::{crate_attr, NoStdAttr, NoStdDependency, NoStdSupport};
+use super::wasm::cfg_applies_to_wasm;
 use crate::error::Result;
 use cargo_metadata::{DependencyKind as CargoDependencyKind, MetadataCommand, Package};
 use petgraph::graph::{DiGraph, NodeIndex};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
 
 /// Analyzer for crate dependencies using cargo_metadata
@@ -14,7 +20,7 @@ pub struct DependencyAnalyzer {
 }
 
 /// Information about a crate
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrateInfo {
     pub name: String,
     pub version: String,
@@ -32,7 +38,7 @@ pub struct CrateInfo {
 }
 
 /// Information about a dependency
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyInfo {
     pub name: String,
     pub version: String,
@@ -42,7 +48,8 @@ pub struct DependencyInfo {
 }
 
 /// Kind of dependency
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DependencyKind {
     Normal,
     Dev,
@@ -62,7 +69,14 @@ impl std::fmt::Display for DependencyKind {
 impl DependencyAnalyzer {
     /// Create a new dependency analyzer from a Cargo.toml path
     pub fn from_manifest(manifest_path: &Path) -> Result<Self> {
-        let metadata = MetadataCommand::new().manifest_path(manifest_path).exec()?;
+        tracing::debug!(manifest = %manifest_path.display(), "running cargo metadata");
+        let metadata = MetadataCommand::new()
+            .manifest_path(manifest_path)
+            .exec()
+            .map_err(|e| {
+                tracing::warn!(manifest = %manifest_path.display(), error = %e, "cargo metadata failed");
+                e
+            })?;
         Ok(Self::from_metadata(metadata))
     }
 
@@ -146,6 +160,236 @@ impl DependencyAnalyzer {
         }
     }
 
+    /// `rust-version` of every transitive dependency of `root`, against root's own
+    pub fn msrv_report(&self, root: &str) -> MsrvReport {
+        let names: HashSet<String> = self
+            .dependency_tree(root)
+            .into_iter()
+            .skip(1)
+            .map(|(name, _)| name)
+            .collect();
+        let declared = self
+            .metadata
+            .packages
+            .iter()
+            .find(|p| p.name == root)
+            .and_then(|p| p.rust_version.as_ref().map(|v| v.to_string()));
+        let deps = self
+            .metadata
+            .packages
+            .iter()
+            .filter(|p| names.contains(&p.name))
+            .map(|p| {
+                (
+                    p.name.clone(),
+                    p.version.to_string(),
+                    p.rust_version.as_ref().map(|v| v.to_string()),
+                )
+            });
+        MsrvReport::build(declared, deps)
+    }
+
+    /// Crates resolved at more than one version, with the packages pulling each version
+    pub fn duplicates(&self) -> Vec<DuplicateCrate> {
+        let Some(resolve) = self.metadata.resolve.as_ref() else {
+            return Vec::new();
+        };
+        let packages: HashMap<&cargo_metadata::PackageId, &Package> =
+            self.metadata.packages.iter().map(|p| (&p.id, p)).collect();
+        let mut dependents: HashMap<&cargo_metadata::PackageId, Vec<String>> = HashMap::new();
+        for node in &resolve.nodes {
+            let Some(parent) = packages.get(&node.id) else {
+                continue;
+            };
+            for dep in &node.dependencies {
+                dependents
+                    .entry(dep)
+                    .or_default()
+                    .push(format!("{} {}", parent.name, parent.version));
+            }
+        }
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for pkg in &self.metadata.packages {
+            *counts.entry(pkg.name.as_str()).or_default() += 1;
+        }
+        find_duplicates(
+            self.metadata
+                .packages
+                .iter()
+                .filter(|p| counts.get(p.name.as_str()).is_some_and(|&n| n > 1))
+                .map(|p| {
+                    let source_bytes = p
+                        .manifest_path
+                        .parent()
+                        .and_then(|dir| crate::utils::dir_size(dir.as_std_path()));
+                    (
+                        p.name.clone(),
+                        p.version.clone(),
+                        dependents.remove(&p.id).unwrap_or_default(),
+                        source_bytes,
+                    )
+                }),
+        )
+    }
+
+    /// Resolved features of every non-workspace package and who requested each one
+    pub fn feature_unification(&self) -> Vec<FeatureUnification> {
+        let Some(resolve) = self.metadata.resolve.as_ref() else {
+            return Vec::new();
+        };
+        let packages: HashMap<&cargo_metadata::PackageId, &Package> =
+            self.metadata.packages.iter().map(|p| (&p.id, p)).collect();
+        let mut result = Vec::new();
+        for node in &resolve.nodes {
+            if self.metadata.workspace_members.contains(&node.id) {
+                continue;
+            }
+            let Some(pkg) = packages.get(&node.id) else {
+                continue;
+            };
+            let mut enabled = node.features.clone();
+            enabled.sort();
+            let mut unification = FeatureUnification {
+                name: pkg.name.clone(),
+                version: pkg.version.to_string(),
+                enabled,
+                default: pkg.features.get("default").cloned().unwrap_or_default(),
+                available: pkg
+                    .features
+                    .keys()
+                    .filter(|f| *f != "default")
+                    .cloned()
+                    .collect(),
+                ..Default::default()
+            };
+            for parent in &resolve.nodes {
+                if !parent.dependencies.contains(&node.id) {
+                    continue;
+                }
+                let Some(parent_pkg) = packages.get(&parent.id) else {
+                    continue;
+                };
+                for dep in parent_pkg
+                    .dependencies
+                    .iter()
+                    .filter(|d| d.name == pkg.name && d.req.matches(&pkg.version))
+                {
+                    unification.add_request(
+                        &parent_pkg.name,
+                        &dep.features,
+                        dep.uses_default_features,
+                    );
+                }
+            }
+            result.push(unification);
+        }
+        result.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.version.cmp(&b.version)));
+        result
+    }
+
+    /// Crates `root` builds into a wasm32 binary, each with the direct dependency
+    /// pulling it in (itself for direct ones). Dev and build dependencies, proc
+    /// macros and those declared for targets other than wasm aren't followed.
+    pub fn wasm_dependencies(&self, root: &str) -> Vec<(String, String)> {
+        self.runtime_packages(root, cfg_applies_to_wasm)
+            .into_iter()
+            .map(|(pkg, _, direct)| (pkg.name.clone(), direct))
+            .collect()
+    }
+
+    /// Whether each crate `root` builds into its binary (as in
+    /// [`DependencyAnalyzer::wasm_dependencies`], platform-specific ones left out)
+    /// can do without std, from its root's `#![no_std]`, its `no-std` category and
+    /// whether its `std` feature is on
+    pub fn no_std_dependencies(&self, root: &str) -> Vec<NoStdDependency> {
+        self.runtime_packages(root, |_| false)
+            .into_iter()
+            .map(|(pkg, node, direct)| {
+                let attr = pkg
+                    .targets
+                    .iter()
+                    .find(|t| t.is_lib() || t.is_rlib())
+                    .and_then(|t| std::fs::read_to_string(&t.src_path).ok())
+                    .and_then(|source| crate_attr(&source));
+                let support = match attr {
+                    Some(NoStdAttr::Always) => NoStdSupport::Always,
+                    Some(NoStdAttr::When(_)) => NoStdSupport::Optional,
+                    None if pkg.categories.iter().any(|c| c == "no-std") => NoStdSupport::Optional,
+                    None => NoStdSupport::RequiresStd,
+                };
+                NoStdDependency {
+                    name: pkg.name.clone(),
+                    version: pkg.version.to_string(),
+                    support,
+                    std_enabled: node.features.iter().any(|f| f == "std"),
+                    direct,
+                }
+            })
+            .collect()
+    }
+
+    /// Normal dependencies of `root`, transitively and breadth first, with the
+    /// direct dependency each is reached through. Platform-specific ones are
+    /// followed when `target` accepts their `cfg`; proc macros are skipped since
+    /// they only run in the compiler.
+    fn runtime_packages(
+        &self,
+        root: &str,
+        target: impl Fn(&str) -> bool,
+    ) -> Vec<(&Package, &cargo_metadata::Node, String)> {
+        let Some(resolve) = self.metadata.resolve.as_ref() else {
+            return Vec::new();
+        };
+        let packages: HashMap<&cargo_metadata::PackageId, &Package> =
+            self.metadata.packages.iter().map(|p| (&p.id, p)).collect();
+        let nodes: HashMap<&cargo_metadata::PackageId, &cargo_metadata::Node> =
+            resolve.nodes.iter().map(|n| (&n.id, n)).collect();
+        let runtime_deps = |node: &'_ cargo_metadata::Node| -> Vec<cargo_metadata::PackageId> {
+            node.deps
+                .iter()
+                .filter(|d| {
+                    d.dep_kinds.iter().any(|k| {
+                        k.kind == CargoDependencyKind::Normal
+                            && k.target.as_ref().map_or(true, |t| target(&t.to_string()))
+                    })
+                })
+                .map(|d| d.pkg.clone())
+                .collect()
+        };
+        let Some(root) = self.metadata.packages.iter().find(|p| p.name == root) else {
+            return Vec::new();
+        };
+        let Some(root_node) = nodes.get(&root.id) else {
+            return Vec::new();
+        };
+
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        let mut queue: VecDeque<(cargo_metadata::PackageId, String)> = runtime_deps(root_node)
+            .into_iter()
+            .filter_map(|id| {
+                let name = packages.get(&id)?.name.clone();
+                Some((id, name))
+            })
+            .collect();
+        while let Some((id, direct)) = queue.pop_front() {
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            let (Some(&pkg), Some(&node)) = (packages.get(&id), nodes.get(&id)) else {
+                continue;
+            };
+            if pkg.targets.iter().any(|t| t.is_proc_macro()) {
+                continue;
+            }
+            for dep in runtime_deps(node) {
+                queue.push_back((dep, direct.clone()));
+            }
+            result.push((pkg, node, direct));
+        }
+        result
+    }
+
     /// Get total number of dependencies (transitive)
     pub fn total_dependency_count(&self, name: &str) -> usize {
         self.dependency_tree(name).len().saturating_sub(1)
@@ -247,4 +491,62 @@ mod tests {
             .iter()
             .any(|d| d.name == "ratatui" || d.name == "crossterm"));
     }
+
+    #[test]
+    fn test_msrv_report_uses_declared_rust_version() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        if !manifest.exists() {
+            return;
+        }
+        let analyzer = DependencyAnalyzer::from_manifest(&manifest).unwrap();
+        let root = analyzer.root_package().unwrap();
+        let report = analyzer.msrv_report(&root.name);
+        assert_eq!(report.declared, root.rust_version);
+        assert!(report.entries.iter().all(|e| e.name != root.name));
+    }
+
+    #[test]
+    fn test_feature_unification_tracks_requesters() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        if !manifest.exists() {
+            return;
+        }
+        let analyzer = DependencyAnalyzer::from_manifest(&manifest).unwrap();
+        let features = analyzer.feature_unification();
+        let serde = features.iter().find(|f| f.name == "serde").expect("serde");
+        assert!(serde.enabled.iter().any(|f| f == "derive"));
+        assert!(serde.requesters("derive").iter().any(|r| r == "oracle-tui"));
+    }
+
+    #[test]
+    fn test_wasm_dependencies_name_the_direct_dependency() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        if !manifest.exists() {
+            return;
+        }
+        let analyzer = DependencyAnalyzer::from_manifest(&manifest).unwrap();
+        let deps = analyzer.wasm_dependencies("oracle-tui");
+        assert!(deps
+            .iter()
+            .any(|(name, direct)| name == "serde" && direct == "serde"));
+        assert!(deps
+            .iter()
+            .any(|(name, direct)| name == "serde_core" && direct == "serde"));
+        // Proc macros only run in the compiler
+        assert!(deps.iter().all(|(name, _)| name != "serde_derive"));
+        assert!(deps.iter().all(|(name, _)| name != "oracle-tui"));
+    }
+
+    #[test]
+    fn test_no_std_dependencies_read_crate_roots() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        if !manifest.exists() {
+            return;
+        }
+        let analyzer = DependencyAnalyzer::from_manifest(&manifest).unwrap();
+        let deps = analyzer.no_std_dependencies("oracle-tui");
+        let serde = deps.iter().find(|d| d.name == "serde").expect("serde");
+        assert_eq!(serde.support, NoStdSupport::Optional);
+        assert!(serde.std_enabled && serde.needs_std());
+    }
 }
@@ -0,0 +1,465 @@
+//! Links in doc comments and whether they lead anywhere
+//!
+//! Intra-doc links (`[Type]`, `` [`module::func`] ``, `[text](crate::Type)`) are resolved
+//! against the analyzed items and the `use` declarations of the item's module;
+//! paths into other crates are left alone. Web links are collected here and checked
+//! over HTTP by the caller, since this module never touches the network.
+
+use std::collections::{HashMap, HashSet};
+
+use super::imports::ModuleImport;
+use super::types::{AnalyzedItem, SourceLocation};
+use super::usage::base_type_name;
+
+/// Types, traits and values in scope everywhere, plus primitive types
+const PRELUDE: [&str; 38] = [
+    "Option",
+    "Some",
+    "None",
+    "Result",
+    "Ok",
+    "Err",
+    "Vec",
+    "String",
+    "Box",
+    "ToString",
+    "ToOwned",
+    "Clone",
+    "Copy",
+    "Send",
+    "Sync",
+    "Sized",
+    "Unpin",
+    "Drop",
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "Iterator",
+    "IntoIterator",
+    "Default",
+    "Eq",
+    "PartialEq",
+    "Ord",
+    "PartialOrd",
+    "AsRef",
+    "AsMut",
+    "From",
+    "Into",
+    "Self",
+    "bool",
+    "char",
+    "str",
+    "usize",
+    "isize",
+];
+const NUMBERS: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "f32", "f64",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkStatus {
+    Resolved,
+    /// Points into another crate; not checked
+    External,
+    /// A web link not checked (yet)
+    Unchecked,
+    /// Why the link leads nowhere, e.g. `no item named Foo` or `HTTP 404`
+    Broken(String),
+}
+
+/// One link in an item's documentation
+#[derive(Debug, Clone)]
+pub struct DocLink {
+    /// Qualified name of the documented item, e.g. `config::Settings::load`
+    pub owner: String,
+    /// Link target as written, without backticks
+    pub target: String,
+    /// `http(s)://` link rather than an intra-doc one
+    pub is_url: bool,
+    pub location: SourceLocation,
+    pub status: LinkStatus,
+}
+
+impl DocLink {
+    pub fn is_broken(&self) -> bool {
+        matches!(self.status, LinkStatus::Broken(_))
+    }
+}
+
+/// Every link in the docs of `items` and their methods, intra-doc ones resolved and
+/// web links [`LinkStatus::Unchecked`]
+pub fn check_links(items: &[AnalyzedItem], imports: &[ModuleImport]) -> Vec<DocLink> {
+    let index = LinkIndex::build(items, imports);
+    let mut links = Vec::new();
+    for_each_doc(items, |owner, module, doc, location| {
+        for (target, is_url) in extract_links(doc) {
+            let status = if is_url {
+                LinkStatus::Unchecked
+            } else {
+                index.resolve(&target, module)
+            };
+            links.push(DocLink {
+                owner: owner.clone(),
+                target,
+                is_url,
+                location: location.clone(),
+                status,
+            });
+        }
+    });
+    links
+}
+
+/// Call `f` with the qualified owner name, module, doc comment and location of every
+/// documented item, inherent method and trait method
+pub(super) fn for_each_doc(
+    items: &[AnalyzedItem],
+    mut f: impl FnMut(String, &[String], &str, &SourceLocation),
+) {
+    for item in items {
+        let module = item.module_path();
+        if let Some(doc) = item.documentation() {
+            let location = item.source_location().cloned().unwrap_or_default();
+            f(item.qualified_name(), module, doc, &location);
+        }
+        match item {
+            AnalyzedItem::Impl(im) => {
+                let owner = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+                for method in &im.methods {
+                    if let Some(ref doc) = method.documentation {
+                        let name = qualify(module, &format!("{}::{}", owner, method.name));
+                        f(name, module, doc, &method.source_location);
+                    }
+                }
+            }
+            AnalyzedItem::Trait(t) => {
+                for method in &t.methods {
+                    if let Some(ref doc) = method.documentation {
+                        let name = format!("{}::{}", item.qualified_name(), method.name);
+                        f(name, module, doc, &t.source_location);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Mark web links with the HTTP status each URL answered with; `None` means it
+/// couldn't be reached. 404 and 410 are broken, other answers count as working.
+pub fn apply_url_statuses(links: &mut [DocLink], statuses: &HashMap<String, Option<u16>>) {
+    for link in links.iter_mut().filter(|l| l.is_url) {
+        link.status = match statuses.get(&link.target) {
+            None => LinkStatus::Unchecked,
+            Some(None) => LinkStatus::Broken("unreachable".to_string()),
+            Some(Some(code @ (404 | 410))) => LinkStatus::Broken(format!("HTTP {}", code)),
+            Some(Some(_)) => LinkStatus::Resolved,
+        };
+    }
+}
+
+/// Link targets in a doc comment as `(target, is_url)`, code blocks and inline code
+/// skipped. A bracketed word only counts when it looks like a path.
+pub fn extract_links(doc: &str) -> Vec<(String, bool)> {
+    let mut definitions = HashMap::new();
+    let mut prose = Vec::new();
+    let mut in_code = false;
+    for line in doc.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        // `[label]: target` reference definitions
+        if let Some((label, target)) = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once("]:"))
+        {
+            definitions.insert(label.trim().to_string(), target.trim().to_string());
+            continue;
+        }
+        prose.push(line);
+    }
+
+    let mut targets = Vec::new();
+    let mut push = |target: &str| {
+        let target = target.trim().trim_matches('`');
+        if target.starts_with("http://") || target.starts_with("https://") {
+            targets.push((target.to_string(), true));
+        } else if is_path(target) {
+            targets.push((target.to_string(), false));
+        }
+    };
+    for line in prose {
+        let line = strip_code_spans(line);
+        let mut rest = line.as_str();
+        while let Some(start) = rest.find(['[', '<']) {
+            let after = &rest[start + 1..];
+            if rest[start..].starts_with('<') {
+                // `<https://...>` autolinks
+                match after.split_once('>') {
+                    Some((url, tail)) if url.starts_with("http") => {
+                        push(url);
+                        rest = tail;
+                    }
+                    _ => rest = after,
+                }
+                continue;
+            }
+            let Some(end) = after.find(']') else {
+                break;
+            };
+            let text = &after[..end];
+            let tail = &after[end + 1..];
+            if let Some(inline) = tail.strip_prefix('(') {
+                let dest = inline.split(')').next().unwrap_or_default();
+                push(dest.split_whitespace().next().unwrap_or_default());
+                rest = &inline[dest.len().min(inline.len())..];
+            } else if let Some(reference) = tail.strip_prefix('[') {
+                let written = reference.split(']').next().unwrap_or_default();
+                let label = if written.is_empty() { text } else { written };
+                push(definitions.get(label).map_or(label, String::as_str));
+                rest = &reference[written.len()..];
+            } else {
+                push(definitions.get(text).map_or(text, String::as_str));
+                rest = tail;
+            }
+        }
+    }
+    targets
+}
+
+/// `a::b::C`, `C::new()`, `vec!` or `fn@foo`, not prose like `[default: stdout]`
+fn is_path(target: &str) -> bool {
+    let target = strip_disambiguator(target);
+    !target.is_empty()
+        && target.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && target
+            .split("::")
+            .all(|seg| !seg.is_empty() && seg.chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
+/// `struct@Foo` → `Foo`, `foo()` → `foo`, `vec!` → `vec`, `Vec<T>` → `Vec`
+fn strip_disambiguator(target: &str) -> &str {
+    let target = target.split_once('@').map_or(target, |(_, rest)| rest);
+    let target = target.split('<').next().unwrap_or(target);
+    target.trim_end_matches("()").trim_end_matches('!')
+}
+
+/// The line with the contents of `` `code` `` spans blanked, unless the whole link
+/// text is code (`` [`Foo`] ``)
+fn strip_code_spans(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(open) = rest.find('`') {
+        let Some(len) = rest[open + 1..].find('`') else {
+            break;
+        };
+        let end = open + len + 2;
+        let linked = rest[..open].ends_with('[') && rest[end..].starts_with(']');
+        out.push_str(&rest[..open]);
+        if linked {
+            out.push_str(&rest[open..end]);
+        } else {
+            out.push_str(&" ".repeat(rest[open..end].chars().count()));
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Names and paths intra-doc links can resolve to
+struct LinkIndex {
+    names: HashSet<String>,
+    /// Qualified names of items and `Type::method` paths
+    paths: HashSet<String>,
+    /// First segments that are local: modules and type names
+    local_roots: HashSet<String>,
+    /// Module (`a::b`, empty for the root) → names its `use` declarations bring in
+    imported: HashMap<String, HashSet<String>>,
+}
+
+impl LinkIndex {
+    fn build(items: &[AnalyzedItem], imports: &[ModuleImport]) -> Self {
+        let mut index = Self {
+            names: HashSet::new(),
+            paths: HashSet::new(),
+            local_roots: HashSet::new(),
+            imported: HashMap::new(),
+        };
+        for item in items {
+            if let Some(first) = item.module_path().first() {
+                index.local_roots.insert(first.clone());
+            }
+            let AnalyzedItem::Impl(im) = item else {
+                index.names.insert(item.name().to_string());
+                index.local_roots.insert(item.name().to_string());
+                index.paths.insert(item.qualified_name());
+                if let AnalyzedItem::Trait(t) = item {
+                    for method in &t.methods {
+                        index.add_member(item.module_path(), &t.name, &method.name);
+                    }
+                }
+                if let AnalyzedItem::Enum(e) = item {
+                    for variant in &e.variants {
+                        index.add_member(item.module_path(), &e.name, &variant.name);
+                    }
+                }
+                if let AnalyzedItem::Struct(s) = item {
+                    for field in &s.fields {
+                        index.add_member(item.module_path(), &s.name, &field.name);
+                    }
+                }
+                continue;
+            };
+            let owner = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+            for method in &im.methods {
+                index.names.insert(method.name.clone());
+                index.add_member(item.module_path(), owner, &method.name);
+            }
+        }
+        for import in imports {
+            if let Some(last) = import.path.last() {
+                index
+                    .imported
+                    .entry(import.from.join("::"))
+                    .or_default()
+                    .insert(last.clone());
+            }
+        }
+        index
+    }
+
+    fn add_member(&mut self, module: &[String], owner: &str, member: &str) {
+        self.paths
+            .insert(qualify(module, &format!("{}::{}", owner, member)));
+    }
+
+    fn resolve(&self, target: &str, module: &[String]) -> LinkStatus {
+        let target = strip_disambiguator(target);
+        let mut segments: Vec<&str> = target.split("::").collect();
+        let mut base: Vec<String> = Vec::new();
+        match segments[0] {
+            "crate" => {
+                segments.remove(0);
+            }
+            "self" | "super" => {
+                base = module.to_vec();
+                while let Some(&first) = segments.first() {
+                    match first {
+                        "self" => {}
+                        "super" => {
+                            base.pop();
+                        }
+                        _ => break,
+                    }
+                    segments.remove(0);
+                }
+            }
+            _ => {}
+        }
+        let Some(&first) = segments.first() else {
+            return LinkStatus::Resolved;
+        };
+        let imported = self
+            .imported
+            .get(&module.join("::"))
+            .is_some_and(|names| names.contains(first));
+        if segments.len() == 1 {
+            let known = self.names.contains(first)
+                || imported
+                || PRELUDE.contains(&first)
+                || NUMBERS.contains(&first);
+            return if known {
+                LinkStatus::Resolved
+            } else {
+                LinkStatus::Broken(format!("no item named {}", first))
+            };
+        }
+        if first == "Self" {
+            return LinkStatus::Resolved;
+        }
+        if !self.local_roots.contains(first) && base.is_empty() {
+            // `std::...`, `serde::...` or a module imported from another crate
+            return LinkStatus::External;
+        }
+        let path = [base.join("::"), segments.join("::")]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("::");
+        let suffix = format!("::{}", path);
+        let found = self.paths.contains(&path) || self.paths.iter().any(|p| p.ends_with(&suffix));
+        if found {
+            LinkStatus::Resolved
+        } else if imported && !self.names.contains(first) {
+            LinkStatus::External
+        } else {
+            LinkStatus::Broken(format!("{} not found", path))
+        }
+    }
+}
+
+fn qualify(module: &[String], name: &str) -> String {
+    if module.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", module.join("::"), name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_extract_links() {
+        let doc = "See [`Config`], [load](crate::config::load) and [the book][book].\n\
+                   Defaults to `[x]` [default: stdout], <https://example.com/a>.\n\
+                   ```\nlet v = [Foo];\n```\n\
+                   [book]: https://doc.rust-lang.org/book/\n";
+        assert_eq!(
+            extract_links(doc),
+            vec![
+                ("Config".to_string(), false),
+                ("crate::config::load".to_string(), false),
+                ("https://doc.rust-lang.org/book/".to_string(), true),
+                ("https://example.com/a".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_links() {
+        let source = "/// Built by [`Config::new`]; see [`Missing`] and [`std::fmt`].\n\
+                      pub struct Config { pub name: String }\n\
+                      impl Config {\n    /// Calls [`helper`] and [`Config::gone`], reads [`Config::name`]\n    \
+                      pub fn new() -> Self { todo!() }\n}\n\
+                      /// Returns an [`Option`] from [`crate::Config`], docs at <https://example.com>\n\
+                      pub fn helper() -> Option<Config> { None }\n";
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        let mut links = check_links(&items, &[]);
+        let broken: Vec<(&str, &str)> = links
+            .iter()
+            .filter(|l| l.is_broken())
+            .map(|l| (l.owner.as_str(), l.target.as_str()))
+            .collect();
+        assert_eq!(
+            broken,
+            vec![("Config", "Missing"), ("Config::new", "Config::gone")]
+        );
+        let std_link = links.iter().find(|l| l.target == "std::fmt").unwrap();
+        assert_eq!(std_link.status, LinkStatus::External);
+
+        let statuses = HashMap::from([("https://example.com".to_string(), Some(404))]);
+        apply_url_statuses(&mut links, &statuses);
+        let url = links.iter().find(|l| l.is_url).unwrap();
+        assert_eq!(url.status, LinkStatus::Broken("HTTP 404".into()));
+    }
+}
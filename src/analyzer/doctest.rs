@@ -0,0 +1,163 @@
+//! Doc examples (doctests) extracted from documentation comments
+//!
+//! Follows rustdoc's rules: a fenced block with no language, `rust`, or only
+//! doctest attributes (`ignore`, `no_run`, `should_panic`, ...) is a Rust example;
+//! lines starting with `# ` are hidden from rendered docs but still compiled.
+
+/// Fence attributes that keep a block a Rust doctest
+const DOCTEST_ATTRIBUTES: &[&str] = &[
+    "rust",
+    "ignore",
+    "no_run",
+    "should_panic",
+    "compile_fail",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+    "edition2024",
+    "test_harness",
+];
+
+/// A fenced code block from a doc comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocExample {
+    /// Fence info string split on `,` (e.g. `["rust", "no_run"]`); empty for a bare fence
+    pub attributes: Vec<String>,
+    /// Full code including hidden (`# `) lines
+    pub code: String,
+    /// Line offset of the opening fence within the doc text
+    pub doc_line: usize,
+}
+
+impl DocExample {
+    /// Whether rustdoc would treat the block as Rust code
+    pub fn is_rust(&self) -> bool {
+        self.attributes
+            .iter()
+            .all(|a| DOCTEST_ATTRIBUTES.contains(&a.as_str()))
+    }
+
+    /// Whether `cargo test --doc` runs (not just compiles) this example
+    pub fn is_run(&self) -> bool {
+        self.is_rust()
+            && !self
+                .attributes
+                .iter()
+                .any(|a| matches!(a.as_str(), "ignore" | "no_run" | "compile_fail"))
+    }
+
+    /// Lines as rendered by rustdoc (hidden `# ` lines removed)
+    pub fn visible_lines(&self) -> impl Iterator<Item = &str> {
+        self.code.lines().filter(|l| !is_hidden_line(l))
+    }
+
+    /// Code as compiled by rustdoc (hidden markers stripped)
+    pub fn compiled_code(&self) -> String {
+        if !self.is_rust() {
+            return self.code.clone();
+        }
+        self.code
+            .lines()
+            .map(|l| {
+                let trimmed = l.trim_start();
+                if trimmed == "#" {
+                    ""
+                } else if let Some(rest) = trimmed.strip_prefix("# ") {
+                    rest
+                } else {
+                    l
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Short label for lists (`rust`, `rust,no_run`, `text`, ...)
+    pub fn label(&self) -> String {
+        if self.attributes.is_empty() {
+            "rust".to_string()
+        } else {
+            self.attributes.join(",")
+        }
+    }
+}
+
+fn is_hidden_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed == "#" || trimmed.starts_with("# ")
+}
+
+/// Extract all fenced code blocks (```` ``` ```` or `~~~`) from documentation text
+pub fn extract_doc_examples(doc: &str) -> Vec<DocExample> {
+    let mut examples = Vec::new();
+    let mut open: Option<(String, Vec<String>, usize, Vec<&str>)> = None;
+
+    for (i, line) in doc.lines().enumerate() {
+        let trimmed = line.trim_start();
+        match open.take() {
+            None => {
+                if let Some(fence) = fence_marker(trimmed) {
+                    let info = trimmed[fence.len()..].trim();
+                    let attributes = info
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .map(str::trim)
+                        .filter(|a| !a.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    open = Some((fence, attributes, i, Vec::new()));
+                }
+            }
+            Some((fence, attributes, start, mut body)) => {
+                if trimmed.starts_with(&fence) && trimmed[fence.len()..].trim().is_empty() {
+                    examples.push(DocExample {
+                        attributes,
+                        code: body.join("\n"),
+                        doc_line: start,
+                    });
+                } else {
+                    body.push(line);
+                    open = Some((fence, attributes, start, body));
+                }
+            }
+        }
+    }
+    examples
+}
+
+/// Opening fence (three or more backticks or tildes) at the start of the line
+fn fence_marker(line: &str) -> Option<String> {
+    let ch = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|c| *c == ch).count();
+    (len >= 3).then(|| ch.to_string().repeat(len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_examples() {
+        let doc = "Adds numbers.\n\n```\n# use demo::add;\nassert_eq!(add(1, 2), 3);\n```\n\n```text\nnot code\n```\n\n```rust,no_run\nloop {}\n```";
+        let examples = extract_doc_examples(doc);
+        assert_eq!(examples.len(), 3);
+
+        assert!(examples[0].is_rust() && examples[0].is_run());
+        assert_eq!(
+            examples[0].visible_lines().collect::<Vec<_>>(),
+            vec!["assert_eq!(add(1, 2), 3);"]
+        );
+        assert_eq!(
+            examples[0].compiled_code(),
+            "use demo::add;\nassert_eq!(add(1, 2), 3);"
+        );
+
+        assert!(!examples[1].is_rust());
+        assert_eq!(examples[2].label(), "rust,no_run");
+        assert!(examples[2].is_rust() && !examples[2].is_run());
+    }
+
+    #[test]
+    fn test_unclosed_fence_is_ignored() {
+        assert!(extract_doc_examples("```\nfn main() {}").is_empty());
+    }
+}
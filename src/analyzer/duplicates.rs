@@ -0,0 +1,153 @@
+//! Crates resolved at more than one version
+//!
+//! Every extra version is compiled separately, so duplicates cost build time and
+//! binary size. Versions in the same semver-compatible range can be merged with
+//! `cargo update -p name@old`; incompatible ones need a dependent to upgrade.
+
+use std::collections::BTreeMap;
+
+use cargo_metadata::semver::Version;
+
+/// One resolved version of a duplicated crate
+#[derive(Debug, Clone)]
+pub struct DuplicateVersion {
+    pub version: Version,
+    /// Packages depending on this version (`name version`)
+    pub dependents: Vec<String>,
+    /// Size of the package sources on disk, as a rough proxy for compile cost
+    pub source_bytes: Option<u64>,
+}
+
+/// A crate present at multiple versions in the resolve graph
+#[derive(Debug, Clone)]
+pub struct DuplicateCrate {
+    pub name: String,
+    /// Oldest first
+    pub versions: Vec<DuplicateVersion>,
+}
+
+impl DuplicateCrate {
+    /// Source size of every version except the newest (what deduplicating would save)
+    pub fn duplicated_bytes(&self) -> u64 {
+        self.versions
+            .iter()
+            .rev()
+            .skip(1)
+            .filter_map(|v| v.source_bytes)
+            .sum()
+    }
+
+    /// Commands or hints to collapse older versions into newer ones
+    pub fn suggestions(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        for (i, older) in self.versions.iter().enumerate() {
+            let newer = self.versions[i + 1..]
+                .iter()
+                .rev()
+                .find(|v| compatible(&older.version, &v.version));
+            match newer {
+                Some(_) => out.push(format!("cargo update -p {}@{}", self.name, older.version)),
+                None if i + 1 < self.versions.len() => {
+                    let latest = &self.versions[self.versions.len() - 1].version;
+                    out.push(format!(
+                        "upgrade {} to use {} {}",
+                        older.dependents.join(", "),
+                        self.name,
+                        latest
+                    ));
+                }
+                None => {}
+            }
+        }
+        out
+    }
+}
+
+/// Group `(name, version, dependents, source bytes)` by crate name and keep those with 2+ versions
+pub fn find_duplicates<I>(packages: I) -> Vec<DuplicateCrate>
+where
+    I: IntoIterator<Item = (String, Version, Vec<String>, Option<u64>)>,
+{
+    let mut by_name: BTreeMap<String, Vec<DuplicateVersion>> = BTreeMap::new();
+    for (name, version, mut dependents, source_bytes) in packages {
+        dependents.sort();
+        dependents.dedup();
+        by_name.entry(name).or_default().push(DuplicateVersion {
+            version,
+            dependents,
+            source_bytes,
+        });
+    }
+    let mut duplicates: Vec<DuplicateCrate> = by_name
+        .into_iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .map(|(name, mut versions)| {
+            versions.sort_by(|a, b| a.version.cmp(&b.version));
+            DuplicateCrate { name, versions }
+        })
+        .collect();
+    // Most expensive first
+    duplicates.sort_by(|a, b| {
+        b.duplicated_bytes()
+            .cmp(&a.duplicated_bytes())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    duplicates
+}
+
+/// Whether cargo may unify the two versions (same major, or same minor for 0.x)
+fn compatible(a: &Version, b: &Version) -> bool {
+    match (a.major, b.major) {
+        (0, 0) => a.minor == b.minor && (a.minor != 0 || a.patch == b.patch),
+        (x, y) => x == y,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pkg(
+        name: &str,
+        version: &str,
+        dependents: &[&str],
+    ) -> (String, Version, Vec<String>, Option<u64>) {
+        (
+            name.to_string(),
+            Version::parse(version).unwrap(),
+            dependents.iter().map(|d| d.to_string()).collect(),
+            Some(1000),
+        )
+    }
+
+    #[test]
+    fn test_find_duplicates_and_suggestions() {
+        let duplicates = find_duplicates(vec![
+            pkg("syn", "1.0.109", &["clap_derive 3.2.0"]),
+            pkg("syn", "2.0.50", &["serde_derive 1.0.0"]),
+            pkg("bitflags", "2.4.0", &["crossterm 0.28.0"]),
+            pkg("bitflags", "2.6.0", &["ratatui 0.29.0"]),
+            pkg("serde", "1.0.200", &["app 0.1.0"]),
+        ]);
+        let names: Vec<&str> = duplicates.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["bitflags", "syn"]);
+
+        assert_eq!(
+            duplicates[0].suggestions(),
+            vec!["cargo update -p bitflags@2.4.0"]
+        );
+        assert_eq!(
+            duplicates[1].suggestions(),
+            vec!["upgrade clap_derive 3.2.0 to use syn 2.0.50"]
+        );
+        assert_eq!(duplicates[1].duplicated_bytes(), 1000);
+    }
+
+    #[test]
+    fn test_compatible_zero_versions() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert!(compatible(&v("0.3.1"), &v("0.3.9")));
+        assert!(!compatible(&v("0.3.1"), &v("0.4.0")));
+        assert!(!compatible(&v("0.0.1"), &v("0.0.2")));
+    }
+}
@@ -0,0 +1,370 @@
+//! Rust editions: the words each makes a keyword, how the parser reads code written
+//! for one, and code using syntax the crate's declared edition doesn't have
+//!
+//! syn parses a single language, close to the newest edition. Before parsing, the
+//! source is adjusted to read as rustc would under the crate's edition: in 2015,
+//! `async`, `await` and `try` used as names become raw identifiers; in 2024, `gen`
+//! blocks are read as `async` blocks, the nearest syntax syn knows. Lines stay where
+//! they were. The check works on tokens, so it sees into function bodies and into
+//! files that don't parse.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Delimiter, LineColumn, TokenStream, TokenTree};
+use serde::{Deserialize, Serialize};
+
+/// A Rust edition, ordered oldest first. Without a manifest the analyzer reads 2021.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edition {
+    E2015,
+    E2018,
+    #[default]
+    E2021,
+    E2024,
+}
+
+impl Edition {
+    pub const ALL: [Edition; 4] = [Self::E2015, Self::E2018, Self::E2021, Self::E2024];
+
+    /// `"2021"`, as `Cargo.toml` and `cargo metadata` write it
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.as_str() == s.trim())
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::E2015 => "2015",
+            Self::E2018 => "2018",
+            Self::E2021 => "2021",
+            Self::E2024 => "2024",
+        }
+    }
+
+    /// The `package.edition` of a `Cargo.toml`, 2015 when it has none as with cargo.
+    /// `None` if it can't be read, or inherits its edition from the workspace.
+    pub fn from_manifest(manifest: &Path) -> Option<Self> {
+        let toml: toml::Value = std::fs::read_to_string(manifest).ok()?.parse().ok()?;
+        match toml.get("package")?.get("edition") {
+            None => Some(Self::E2015),
+            Some(edition) => Self::parse(edition.as_str()?),
+        }
+    }
+
+    /// Words that are keywords in this edition but not in 2015, so can't name things
+    /// without `r#`
+    pub fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::E2015 => &[],
+            Self::E2018 | Self::E2021 => &["async", "await", "dyn", "try"],
+            Self::E2024 => &["async", "await", "dyn", "try", "gen"],
+        }
+    }
+
+    /// `source` as syn should read it in this edition; borrowed when nothing changes
+    pub fn prepare(self, source: &str) -> Cow<'_, str> {
+        let words: &[&str] = match self {
+            Self::E2015 => &["async", "await", "try"],
+            Self::E2024 => &["gen"],
+            Self::E2018 | Self::E2021 => return Cow::Borrowed(source),
+        };
+        if !words.iter().any(|word| source.contains(word)) {
+            return Cow::Borrowed(source);
+        }
+        let Some(tokens) = tokens(source) else {
+            return Cow::Borrowed(source);
+        };
+        let mut edits = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            let Tok::Ident(word) = &token.tok else {
+                continue;
+            };
+            let prev = before(&tokens, i, 1);
+            let rest = &tokens[i + 1..];
+            let replacement = match (self, word.as_str()) {
+                (Self::E2015, "async") if !starts_async(rest) => "r#async",
+                (Self::E2015, "await") if prev != Some(&Tok::Punct('.')) => "r#await",
+                (Self::E2015, "try") if !is(rest.first(), &Tok::Open(Delimiter::Brace)) => "r#try",
+                // `async gen { .. }` is an `async` block
+                (Self::E2024, "gen") if starts_gen_block(rest) => match prev {
+                    Some(Tok::Ident(w)) if w == "async" => "",
+                    _ => "async",
+                },
+                _ => continue,
+            };
+            edits.push((token.start, word.len(), replacement));
+        }
+        if edits.is_empty() {
+            return Cow::Borrowed(source);
+        }
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut out = String::with_capacity(source.len() + edits.len() * 2);
+        let mut copied = 0;
+        for (start, len, replacement) in edits {
+            let line_start = line_starts[start.line - 1];
+            let offset = source[line_start..]
+                .char_indices()
+                .nth(start.column)
+                .map_or(source.len(), |(i, _)| line_start + i);
+            out.push_str(&source[copied..offset]);
+            out.push_str(replacement);
+            copied = offset + len;
+        }
+        out.push_str(&source[copied..]);
+        Cow::Owned(out)
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Code the crate's edition doesn't accept
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditionIssue {
+    pub file: PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+/// Syntax in `source` that `edition` doesn't have: `async` and `.await` before 2018,
+/// `gen` blocks before 2024, names that are keywords in `edition`, and in 2024 the
+/// `extern` blocks and attributes that must be marked `unsafe`
+pub fn check(source: &str, file: &Path, edition: Edition) -> Vec<EditionIssue> {
+    let Some(tokens) = tokens(source) else {
+        return Vec::new();
+    };
+    let needs = |what: &str, needed: Edition| {
+        format!(
+            "{} needs edition {}, the crate is {}",
+            what, needed, edition
+        )
+    };
+    let mut issues = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let Tok::Ident(word) = &token.tok else {
+            continue;
+        };
+        let prev = before(&tokens, i, 1);
+        let rest = &tokens[i + 1..];
+        let message = match word.as_str() {
+            "async" if edition < Edition::E2018 && starts_async(rest) => {
+                needs("`async`", Edition::E2018)
+            }
+            "await" if edition < Edition::E2018 && prev == Some(&Tok::Punct('.')) => {
+                needs("`.await`", Edition::E2018)
+            }
+            "gen" if edition < Edition::E2024 && starts_gen_block(rest) => {
+                needs("a `gen` block", Edition::E2024)
+            }
+            "extern"
+                if edition >= Edition::E2024
+                    && !matches!(prev, Some(Tok::Ident(w)) if w == "unsafe")
+                    && starts_extern_block(rest) =>
+            {
+                "an `extern` block must be `unsafe extern` in edition 2024".to_string()
+            }
+            "no_mangle" | "export_name" | "link_section"
+                if edition >= Edition::E2024
+                    && prev == Some(&Tok::Open(Delimiter::Bracket))
+                    && before(&tokens, i, 2) == Some(&Tok::Punct('#')) =>
+            {
+                format!("`#[{0}]` must be `#[unsafe({0})]` in edition 2024", word)
+            }
+            _ if edition.keywords().contains(&word.as_str()) && names_item(&tokens, i) => {
+                format!(
+                    "`{0}` is a keyword in edition {1}; name it `r#{0}`",
+                    word, edition
+                )
+            }
+            _ => continue,
+        };
+        issues.push(EditionIssue {
+            file: file.to_path_buf(),
+            line: token.start.line,
+            message,
+        });
+    }
+    issues
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tok {
+    Ident(String),
+    Punct(char),
+    Literal,
+    Open(Delimiter),
+    Close,
+}
+
+struct Token {
+    tok: Tok,
+    start: LineColumn,
+}
+
+/// The tokens of `source` in order, groups opened and closed in place; `None` when
+/// the delimiters don't balance
+fn tokens(source: &str) -> Option<Vec<Token>> {
+    let stream: TokenStream = source.parse().ok()?;
+    let mut out = Vec::new();
+    flatten(stream, &mut out);
+    Some(out)
+}
+
+fn flatten(stream: TokenStream, out: &mut Vec<Token>) {
+    for tree in stream {
+        let start = tree.span().start();
+        let tok = match tree {
+            TokenTree::Group(group) => {
+                out.push(Token {
+                    tok: Tok::Open(group.delimiter()),
+                    start: group.span_open().start(),
+                });
+                flatten(group.stream(), out);
+                out.push(Token {
+                    tok: Tok::Close,
+                    start: group.span_close().start(),
+                });
+                continue;
+            }
+            TokenTree::Ident(ident) => Tok::Ident(ident.to_string()),
+            TokenTree::Punct(punct) => Tok::Punct(punct.as_char()),
+            TokenTree::Literal(_) => Tok::Literal,
+        };
+        out.push(Token { tok, start });
+    }
+}
+
+/// The token `n` places before `i`
+fn before(tokens: &[Token], i: usize, n: usize) -> Option<&Tok> {
+    i.checked_sub(n).map(|j| &tokens[j].tok)
+}
+
+fn is(token: Option<&Token>, tok: &Tok) -> bool {
+    token.is_some_and(|t| t.tok == *tok)
+}
+
+fn is_ident(token: Option<&Token>, word: &str) -> bool {
+    matches!(token, Some(Token { tok: Tok::Ident(w), .. }) if w == word)
+}
+
+/// After `async`: `fn`, `unsafe fn`, a block or a closure
+fn starts_async(rest: &[Token]) -> bool {
+    ["fn", "unsafe", "move"]
+        .iter()
+        .any(|word| is_ident(rest.first(), word))
+        || is(rest.first(), &Tok::Open(Delimiter::Brace))
+        || is(rest.first(), &Tok::Punct('|'))
+}
+
+/// After `gen`: `{` or `move {`
+fn starts_gen_block(rest: &[Token]) -> bool {
+    let brace = Tok::Open(Delimiter::Brace);
+    is(rest.first(), &brace) || (is_ident(rest.first(), "move") && is(rest.get(1), &brace))
+}
+
+/// After `extern`: `{` or an ABI and `{`; `extern crate` and `extern fn` aren't blocks
+fn starts_extern_block(rest: &[Token]) -> bool {
+    let brace = Tok::Open(Delimiter::Brace);
+    is(rest.first(), &brace) || (is(rest.first(), &Tok::Literal) && is(rest.get(1), &brace))
+}
+
+/// Whether the identifier at `i` is the name being declared by an item or `let`
+fn names_item(tokens: &[Token], i: usize) -> bool {
+    let Some(Tok::Ident(word)) = before(tokens, i, 1) else {
+        return false;
+    };
+    match word.as_str() {
+        "fn" | "let" | "struct" | "enum" | "union" | "trait" | "type" | "mod" | "const" => true,
+        // Not `&'static dyn Trait`
+        "static" => before(tokens, i, 2) != Some(&Tok::Punct('\'')),
+        // `let mut`, not `&mut dyn Trait`
+        "mut" => matches!(before(tokens, i, 2), Some(Tok::Ident(w)) if w == "let"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str, edition: Edition) -> Vec<(usize, String)> {
+        check(source, Path::new("lib.rs"), edition)
+            .into_iter()
+            .map(|issue| (issue.line, issue.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_check_by_edition() {
+        let source = "async fn fetch() { client().await; }\n\
+                      fn gen() -> u8 { 1 }\n\
+                      fn try_it(x: &mut dyn Run, y: &'static dyn Run) { let r#try = 1; }\n\
+                      extern \"C\" { fn abs(x: i32) -> i32; }\n\
+                      #[no_mangle]\npub extern \"C\" fn exported() {}\n\
+                      #[unsafe(export_name = \"e\")]\npub fn other() { let it = gen { yield 1; }; }\n";
+        assert_eq!(
+            messages(source, Edition::E2015),
+            vec![
+                (
+                    1,
+                    "`async` needs edition 2018, the crate is 2015".to_string()
+                ),
+                (
+                    1,
+                    "`.await` needs edition 2018, the crate is 2015".to_string()
+                ),
+                (
+                    8,
+                    "a `gen` block needs edition 2024, the crate is 2015".to_string()
+                ),
+            ]
+        );
+        assert_eq!(messages(source, Edition::E2021).len(), 1);
+        assert_eq!(
+            messages(source, Edition::E2024),
+            vec![
+                (
+                    2,
+                    "`gen` is a keyword in edition 2024; name it `r#gen`".to_string()
+                ),
+                (
+                    4,
+                    "an `extern` block must be `unsafe extern` in edition 2024".to_string()
+                ),
+                (
+                    5,
+                    "`#[no_mangle]` must be `#[unsafe(no_mangle)]` in edition 2024".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            messages("fn async() {}\nlet mut await = 0;\n", Edition::E2018)
+                .iter()
+                .map(|(line, _)| *line)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_prepare_keeps_lines() {
+        let source = "fn f() {\n    let g = gen move { yield 1; };\n    let a = async gen { yield 2; };\n}\n";
+        assert_eq!(
+            Edition::E2024.prepare(source),
+            "fn f() {\n    let g = async move { yield 1; };\n    let a = async  { yield 2; };\n}\n"
+        );
+        assert!(matches!(Edition::E2021.prepare(source), Cow::Borrowed(_)));
+        assert_eq!(
+            Edition::E2015.prepare("fn try() { x.await; async fn f() {} }"),
+            "fn r#try() { x.await; async fn f() {} }"
+        );
+        assert_eq!(Edition::parse("2024"), Some(Edition::E2024));
+        assert_eq!(Edition::parse("2030"), None);
+    }
+}
@@ -0,0 +1,333 @@
+//! Discriminants and representation of enums
+//!
+//! Works from the analyzed [`EnumInfo`] alone: `#[repr]` comes from its attribute
+//! text and discriminant values are evaluated as constant expressions (literals,
+//! arithmetic, shifts as in bit flags, and the crate's `const`s when a
+//! [`ConstTable`] is given). Anything else is left unevaluated and the variants
+//! after it get no computed value.
+
+use super::const_eval::{int_range, ConstTable};
+use super::types::{EnumInfo, VariantFields};
+
+/// Computed facts about an enum's discriminants and layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumLayout {
+    pub variant_count: usize,
+    /// Every variant is a unit variant (a C-like enum)
+    pub fieldless: bool,
+    pub repr: Repr,
+    /// One per variant, in declaration order
+    pub discriminants: Vec<Discriminant>,
+    pub warnings: Vec<String>,
+}
+
+/// Discriminant of a single variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discriminant {
+    pub variant: String,
+    /// `None` when the value depends on an expression that wasn't evaluated
+    pub value: Option<i128>,
+    /// Written out in the source rather than counted up from the previous variant
+    pub explicit: bool,
+}
+
+/// Interpreted `#[repr(..)]` attributes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Repr {
+    /// Primitive integer, e.g. `u8`
+    pub int: Option<String>,
+    pub c: bool,
+    pub transparent: bool,
+    pub align: Option<u64>,
+    pub packed: bool,
+}
+
+impl Repr {
+    /// Collect every `#[repr(..)]` among `attributes` (`#[...]` source text)
+    pub fn from_attributes(attributes: &[String]) -> Self {
+        let mut repr = Self::default();
+        for attr in attributes {
+            let Some(args) = attr
+                .strip_prefix("#[repr(")
+                .and_then(|rest| rest.strip_suffix(")]"))
+            else {
+                continue;
+            };
+            for arg in args.split(',').map(str::trim) {
+                match arg {
+                    "C" => repr.c = true,
+                    "transparent" => repr.transparent = true,
+                    "packed" => repr.packed = true,
+                    _ if arg.starts_with("align(") => {
+                        repr.align = arg
+                            .trim_start_matches("align(")
+                            .trim_end_matches(')')
+                            .parse()
+                            .ok();
+                    }
+                    _ if int_range(arg).is_some() => repr.int = Some(arg.to_string()),
+                    _ => {}
+                }
+            }
+        }
+        repr
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `repr(C, u8)` style summary; `None` for the default Rust representation
+    pub fn summary(&self) -> Option<String> {
+        let mut args = Vec::new();
+        if self.c {
+            args.push("C".to_string());
+        }
+        if self.transparent {
+            args.push("transparent".to_string());
+        }
+        if let Some(ref int) = self.int {
+            args.push(int.clone());
+        }
+        if let Some(align) = self.align {
+            args.push(format!("align({align})"));
+        }
+        if self.packed {
+            args.push("packed".to_string());
+        }
+        (!args.is_empty()).then(|| format!("repr({})", args.join(", ")))
+    }
+
+    /// What the representation means for this enum, one sentence per line
+    pub fn explain(&self, fieldless: bool) -> Vec<String> {
+        let mut lines = Vec::new();
+        match (&self.int, self.c) {
+            (Some(int), true) => lines.push(format!(
+                "Tag is a `{int}`; data-carrying variants are laid out as a `repr(C)` union of structs"
+            )),
+            (Some(int), false) if fieldless => {
+                lines.push(format!("Stored as a `{int}`; `as {int}` casts are lossless"))
+            }
+            (Some(int), false) => lines.push(format!(
+                "Tag is a `{int}` at offset 0; each variant is a `repr(C)` struct starting with it"
+            )),
+            (None, true) if fieldless => {
+                lines.push("Stored like a C enum (usually an `int`), safe to pass over FFI".into())
+            }
+            (None, true) => lines
+                .push("C-compatible tagged union: `int`-sized tag followed by a union".into()),
+            (None, false) => {}
+        }
+        if self.transparent {
+            lines.push("Same layout and ABI as its single non-zero-sized field".into());
+        }
+        if let Some(align) = self.align {
+            lines.push(format!("Aligned to at least {align} bytes"));
+        }
+        if self.packed {
+            lines.push("`packed` is not allowed on enums".into());
+        }
+        if lines.is_empty() {
+            lines.push("Default Rust layout: the compiler chooses tag size and field order".into());
+        }
+        lines
+    }
+}
+
+impl EnumLayout {
+    pub fn of(en: &EnumInfo) -> Self {
+        Self::with_consts(en, &ConstTable::default())
+    }
+
+    /// Like [`EnumLayout::of`], resolving discriminants that name a `const`
+    pub fn with_consts(en: &EnumInfo, consts: &ConstTable) -> Self {
+        let fieldless = en
+            .variants
+            .iter()
+            .all(|v| matches!(v.fields, VariantFields::Unit));
+        let repr = Repr::from_attributes(&en.attributes);
+
+        let mut discriminants: Vec<Discriminant> = Vec::with_capacity(en.variants.len());
+        let mut next = Some(0i128);
+        for variant in &en.variants {
+            let value = match variant.discriminant {
+                Some(ref expr) => consts.eval(expr),
+                None => next,
+            };
+            next = value.and_then(|v| v.checked_add(1));
+            discriminants.push(Discriminant {
+                variant: variant.name.clone(),
+                value,
+                explicit: variant.discriminant.is_some(),
+            });
+        }
+
+        let warnings = Self::check(&discriminants, fieldless, &repr);
+        Self {
+            variant_count: en.variants.len(),
+            fieldless,
+            repr,
+            discriminants,
+            warnings,
+        }
+    }
+
+    fn check(discriminants: &[Discriminant], fieldless: bool, repr: &Repr) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let explicit = discriminants.iter().filter(|d| d.explicit).count();
+
+        if explicit > 0 && explicit < discriminants.len() {
+            let implicit: Vec<&str> = discriminants
+                .iter()
+                .skip_while(|d| !d.explicit)
+                .filter(|d| !d.explicit)
+                .map(|d| d.variant.as_str())
+                .collect();
+            if !implicit.is_empty() {
+                warnings.push(format!(
+                    "Mixes explicit and implicit discriminants: {} count up from the variant before",
+                    implicit.join(", ")
+                ));
+            }
+        }
+
+        if explicit > 0 && !fieldless && repr.int.is_none() && !repr.c {
+            warnings.push(
+                "Explicit discriminants on data-carrying variants need a primitive `#[repr]`"
+                    .into(),
+            );
+        }
+
+        let mut seen: Vec<(i128, &str)> = Vec::new();
+        for d in discriminants {
+            let Some(value) = d.value else { continue };
+            if let Some((_, first)) = seen.iter().find(|(v, _)| *v == value) {
+                warnings.push(format!(
+                    "{} and {} share the discriminant {value}",
+                    first, d.variant
+                ));
+            } else {
+                seen.push((value, &d.variant));
+            }
+        }
+
+        if let Some((int, (min, max))) = repr.int.as_deref().and_then(|i| Some((i, int_range(i)?)))
+        {
+            for d in discriminants {
+                if let Some(value) = d.value.filter(|v| *v < min || *v > max) {
+                    warnings.push(format!("{} = {value} does not fit in `{int}`", d.variant));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Smallest and largest known discriminant
+    pub fn value_range(&self) -> Option<(i128, i128)> {
+        let mut values = self.discriminants.iter().filter_map(|d| d.value);
+        let first = values.next()?;
+        Some(values.fold((first, first), |(lo, hi), v| (lo.min(v), hi.max(v))))
+    }
+
+    /// Integer type holding the discriminant: the `repr` one, or for a default-repr
+    /// fieldless enum the smallest that fits all values (what rustc picks)
+    pub fn tag_type(&self) -> Option<String> {
+        if let Some(ref int) = self.repr.int {
+            return Some(int.clone());
+        }
+        if !self.fieldless || self.repr.c || self.variant_count == 0 {
+            return None;
+        }
+        let (lo, hi) = self.value_range()?;
+        [
+            "u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "u128", "i128",
+        ]
+        .into_iter()
+        .find(|ty| int_range(ty).is_some_and(|(min, max)| lo >= min && hi <= max))
+        .map(str::to_string)
+    }
+
+    /// Values of the tag type no variant uses; they are niches the compiler can use
+    /// to store e.g. the `None` of an `Option<Self>` at no extra size
+    pub fn niche_count(&self) -> Option<u128> {
+        if !self.fieldless || self.discriminants.iter().any(|d| d.value.is_none()) {
+            return None;
+        }
+        let (min, max) = int_range(&self.tag_type()?)?;
+        let span = (max - min) as u128 + 1;
+        let mut used: Vec<i128> = self.discriminants.iter().filter_map(|d| d.value).collect();
+        used.sort_unstable();
+        used.dedup();
+        Some(span.saturating_sub(used.len() as u128))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::{AnalyzedItem, RustAnalyzer};
+
+    fn layout(source: &str) -> EnumLayout {
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        let Some(AnalyzedItem::Enum(en)) = items.first() else {
+            panic!("expected an enum");
+        };
+        EnumLayout::of(en)
+    }
+
+    #[test]
+    fn test_fieldless_discriminants() {
+        let l = layout("#[repr(u8)] enum Op { Nop, Load = 0x10, Store, Jump = 1 << 6 }");
+        assert!(l.fieldless);
+        assert_eq!(l.repr.summary().as_deref(), Some("repr(u8)"));
+        let values: Vec<_> = l.discriminants.iter().map(|d| d.value).collect();
+        assert_eq!(values, vec![Some(0), Some(16), Some(17), Some(64)]);
+        assert!(!l.discriminants[2].explicit);
+        assert_eq!(l.tag_type().as_deref(), Some("u8"));
+        assert_eq!(l.niche_count(), Some(252));
+        assert_eq!(l.warnings.len(), 1);
+        assert!(l.warnings[0].contains(": Store count up"));
+
+        let plain = layout("enum Dir { N, E, S, W }");
+        assert!(plain.repr.is_default());
+        assert_eq!(plain.tag_type().as_deref(), Some("u8"));
+        assert!(plain.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_discriminant_warnings() {
+        let l = layout("#[repr(i8)] enum E { A = -1, B = 200, C = 0, D = -1 }");
+        assert_eq!(l.value_range(), Some((-1, 200)));
+        assert!(l
+            .warnings
+            .iter()
+            .any(|w| w == "A and D share the discriminant -1"));
+        assert!(l
+            .warnings
+            .iter()
+            .any(|w| w.contains("200 does not fit in `i8`")));
+
+        let data = layout("enum Msg { Quit = 1, Move { x: i32 } }");
+        assert!(!data.fieldless);
+        assert_eq!(data.tag_type(), None);
+        assert!(data
+            .warnings
+            .iter()
+            .any(|w| w.contains("primitive `#[repr]`")));
+
+        let unknown = layout("enum K { A = BASE, B, C = 7u32 }");
+        let values: Vec<_> = unknown.discriminants.iter().map(|d| d.value).collect();
+        assert_eq!(values, vec![None, None, Some(7)]);
+        assert_eq!(unknown.niche_count(), None);
+
+        let items = RustAnalyzer::new()
+            .analyze_source("const BASE: u32 = 1 << 4;\nenum K { A = BASE, B, C = BASE * 2 }")
+            .unwrap();
+        let Some(AnalyzedItem::Enum(en)) = items.get(1) else {
+            panic!("expected an enum");
+        };
+        let resolved = EnumLayout::with_consts(en, &ConstTable::new(&items));
+        let values: Vec<_> = resolved.discriminants.iter().map(|d| d.value).collect();
+        assert_eq!(values, vec![Some(16), Some(17), Some(32)]);
+    }
+}
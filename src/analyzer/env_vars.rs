@@ -0,0 +1,271 @@
+//! Environment variables the project reads
+//!
+//! Reads are found in the token stream of each file, so comments and doc comments
+//! don't count: `env!("X")` and `option_env!("X")` at compile time, and
+//! `env::var("X")` / `env::var_os("X")` (any path ending in those, e.g.
+//! `std::env::var` or `dotenvy::var`) at runtime. A name that isn't a string literal
+//! is kept as the expression text.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EnvReadKind {
+    /// `env!`: the build fails without it
+    Compile,
+    /// `option_env!`
+    CompileOptional,
+    /// `env::var` / `env::var_os`
+    Runtime,
+}
+
+impl EnvReadKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Compile => "env!",
+            Self::CompileOptional => "option_env!",
+            Self::Runtime => "env::var",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvRead {
+    /// Variable name, or the expression computing it
+    pub name: String,
+    /// `name` is a string literal
+    pub literal: bool,
+    pub kind: EnvReadKind,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// Reads in one file's source; nothing when it doesn't tokenize
+pub fn scan(source: &str, file: &Path) -> Vec<EnvRead> {
+    let Ok(tokens) = source.parse::<TokenStream>() else {
+        return Vec::new();
+    };
+    let mut reads = Vec::new();
+    scan_tokens(tokens, file, &mut reads);
+    reads
+}
+
+fn scan_tokens(tokens: TokenStream, file: &Path, reads: &mut Vec<EnvRead>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => scan_tokens(group.stream(), file, reads),
+            TokenTree::Ident(ident) => {
+                let ident = ident.to_string();
+                let kind = match (ident.as_str(), &tokens[i + 1..]) {
+                    ("env", [TokenTree::Punct(p), TokenTree::Group(_), ..])
+                        if p.as_char() == '!' =>
+                    {
+                        EnvReadKind::Compile
+                    }
+                    ("option_env", [TokenTree::Punct(p), TokenTree::Group(_), ..])
+                        if p.as_char() == '!' =>
+                    {
+                        EnvReadKind::CompileOptional
+                    }
+                    ("var" | "var_os", [TokenTree::Group(g), ..])
+                        if g.delimiter() == Delimiter::Parenthesis
+                            && after_env_path(&tokens[..i]) =>
+                    {
+                        EnvReadKind::Runtime
+                    }
+                    _ => continue,
+                };
+                let args = match kind {
+                    EnvReadKind::Runtime => &tokens[i + 1],
+                    _ => &tokens[i + 2],
+                };
+                let TokenTree::Group(args) = args else {
+                    continue;
+                };
+                // The name is the first argument (`env!` may have a message after it)
+                let name: Vec<TokenTree> = args
+                    .stream()
+                    .into_iter()
+                    .take_while(|t| !matches!(t, TokenTree::Punct(p) if p.as_char() == ','))
+                    .collect();
+                let (name, literal) = match name.as_slice() {
+                    [TokenTree::Literal(lit)] => {
+                        match syn::parse_str::<syn::LitStr>(&lit.to_string()) {
+                            Ok(s) => (s.value(), true),
+                            Err(_) => (lit.to_string(), false),
+                        }
+                    }
+                    [] => continue,
+                    _ => (name.into_iter().collect::<TokenStream>().to_string(), false),
+                };
+                reads.push(EnvRead {
+                    name,
+                    literal,
+                    kind,
+                    file: file.to_path_buf(),
+                    line: token.span().start().line,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `before` ends in `env ::` (or `dotenv ::` / `dotenvy ::`)
+fn after_env_path(before: &[TokenTree]) -> bool {
+    match before {
+        [.., TokenTree::Ident(module), TokenTree::Punct(a), TokenTree::Punct(b)] => {
+            a.as_char() == ':'
+                && b.as_char() == ':'
+                && matches!(module.to_string().as_str(), "env" | "dotenv" | "dotenvy")
+        }
+        _ => false,
+    }
+}
+
+/// One variable and every place it is read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVar {
+    pub name: String,
+    pub literal: bool,
+    pub reads: Vec<EnvRead>,
+}
+
+impl EnvVar {
+    /// Set by cargo while building, rather than configuration
+    pub fn set_by_cargo(&self) -> bool {
+        self.literal
+            && (self.name.starts_with("CARGO_")
+                || matches!(
+                    self.name.as_str(),
+                    "OUT_DIR" | "TARGET" | "HOST" | "PROFILE"
+                ))
+    }
+
+    /// Read with `env!` somewhere, so the build needs it
+    pub fn required_at_build(&self) -> bool {
+        self.reads.iter().any(|r| r.kind == EnvReadKind::Compile)
+    }
+
+    pub fn read_at_runtime(&self) -> bool {
+        self.reads.iter().any(|r| r.kind == EnvReadKind::Runtime)
+    }
+}
+
+/// Reads grouped by variable, sorted by name
+pub fn group(reads: &[EnvRead]) -> Vec<EnvVar> {
+    let mut vars: BTreeMap<(&str, bool), Vec<EnvRead>> = BTreeMap::new();
+    for read in reads {
+        vars.entry((&read.name, read.literal))
+            .or_default()
+            .push(read.clone());
+    }
+    vars.into_iter()
+        .map(|((name, literal), reads)| EnvVar {
+            name: name.to_string(),
+            literal,
+            reads,
+        })
+        .collect()
+}
+
+/// A Markdown table of the variables, for documenting a project's configuration;
+/// paths are shown relative to `root`
+pub fn markdown(vars: &[EnvVar], root: Option<&Path>) -> String {
+    let mut md =
+        String::from("# Environment variables\n\n| Variable | Read | Where |\n|---|---|---|\n");
+    for var in vars {
+        let name = if var.literal {
+            format!("`{}`", var.name)
+        } else {
+            format!("`{}` (computed)", var.name)
+        };
+        let mut kinds: Vec<EnvReadKind> = var.reads.iter().map(|r| r.kind).collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        let kinds: Vec<&str> = kinds.into_iter().map(EnvReadKind::label).collect();
+        let places: Vec<String> = var
+            .reads
+            .iter()
+            .map(|r| {
+                let file = root
+                    .and_then(|root| r.file.strip_prefix(root).ok())
+                    .unwrap_or(&r.file);
+                format!("{}:{}", file.display(), r.line)
+            })
+            .collect();
+        md.push_str(&format!(
+            "| {} | {} | {} |\n",
+            name,
+            kinds.join(", "),
+            places.join(", ")
+        ));
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+use std::env;
+
+// env::var("IN_A_COMMENT") is not a read
+/// Nor is `env!("IN_DOCS")`
+pub fn config() -> Option<String> {
+    let version = env!("CARGO_PKG_VERSION");
+    let commit = option_env!("GIT_HASH").unwrap_or("unknown");
+    let key = format!("{}_TOKEN", "APP");
+    let _ = std::env::var_os(&key);
+    let _ = dotenvy::var("DATABASE_URL");
+    let _ = env!("BUILD_ID", "set BUILD_ID in CI");
+    env::var("DATABASE_URL").ok()
+}
+"#;
+
+    #[test]
+    fn test_scan_and_group() {
+        let file = PathBuf::from("/p/src/config.rs");
+        let reads = scan(SOURCE, &file);
+        let found: Vec<(&str, EnvReadKind, usize)> = reads
+            .iter()
+            .map(|r| (r.name.as_str(), r.kind, r.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("CARGO_PKG_VERSION", EnvReadKind::Compile, 7),
+                ("GIT_HASH", EnvReadKind::CompileOptional, 8),
+                ("& key", EnvReadKind::Runtime, 10),
+                ("DATABASE_URL", EnvReadKind::Runtime, 11),
+                ("BUILD_ID", EnvReadKind::Compile, 12),
+                ("DATABASE_URL", EnvReadKind::Runtime, 13),
+            ]
+        );
+
+        let vars = group(&reads);
+        let names: Vec<&str> = vars.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "& key",
+                "BUILD_ID",
+                "CARGO_PKG_VERSION",
+                "DATABASE_URL",
+                "GIT_HASH"
+            ]
+        );
+        assert!(!vars[0].literal);
+        assert!(vars[1].required_at_build());
+        assert!(vars[2].set_by_cargo());
+        assert_eq!(vars[3].reads.len(), 2);
+        assert!(vars[3].read_at_runtime());
+
+        let md = markdown(&vars, Some(Path::new("/p")));
+        assert!(md.contains("| `DATABASE_URL` | env::var | src/config.rs:11, src/config.rs:13 |"));
+    }
+}
@@ -0,0 +1,223 @@
+//! Error type catalog: a crate's failure surface
+//!
+//! Error types are structs and enums named `*Error` or deriving `thiserror::Error`.
+//! Every function and inherent method returning `Result<_, E>` is mapped to `E`,
+//! resolving `Result<T>` through the crate's own `type Result<T> = ...` aliases and
+//! the usual `io::Result` / `anyhow::Result` / `fmt::Result` shorthands.
+
+use std::collections::BTreeMap;
+
+use super::types::{AnalyzedItem, FunctionInfo, TypeAliasInfo};
+use super::usage::base_type_name;
+use syn::{GenericArgument, PathArguments, Type};
+
+/// An error type defined in the crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorType {
+    pub name: String,
+    /// Qualified name, e.g. `config::ParseError`
+    pub path: String,
+    /// `Some(n)` for enums with `n` variants
+    pub variants: Option<usize>,
+    pub thiserror: bool,
+}
+
+/// Functions grouped by the error type they return
+#[derive(Debug, Clone, Default)]
+pub struct ErrorCatalog {
+    pub error_types: Vec<ErrorType>,
+    /// Error type (as written after resolving aliases) → functions returning it, sorted
+    pub returned_by: BTreeMap<String, Vec<String>>,
+}
+
+impl ErrorCatalog {
+    pub fn build(items: &[AnalyzedItem]) -> Self {
+        let mut error_types: Vec<ErrorType> = items
+            .iter()
+            .filter_map(|item| {
+                let (derives, variants) = match item {
+                    AnalyzedItem::Struct(s) => (&s.derives, None),
+                    AnalyzedItem::Enum(e) => (&e.derives, Some(e.variants.len())),
+                    _ => return None,
+                };
+                let thiserror = derives
+                    .iter()
+                    .any(|d| d == "thiserror::Error" || d == "Error");
+                (thiserror || item.name().ends_with("Error")).then(|| ErrorType {
+                    name: item.name().to_string(),
+                    path: item.qualified_name(),
+                    variants,
+                    thiserror,
+                })
+            })
+            .collect();
+        error_types.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let aliases: Vec<&TypeAliasInfo> = items
+            .iter()
+            .filter_map(|item| match item {
+                AnalyzedItem::TypeAlias(t) => Some(t),
+                _ => None,
+            })
+            .collect();
+
+        let mut returned_by: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut add = |func: &FunctionInfo, owner: String| {
+            let Some(error) = func
+                .return_type
+                .as_deref()
+                .and_then(|ret| result_error(ret, &aliases))
+            else {
+                return;
+            };
+            returned_by.entry(error).or_default().push(owner);
+        };
+        for item in items {
+            match item {
+                AnalyzedItem::Function(f) => add(f, item.qualified_name()),
+                AnalyzedItem::Impl(im) if im.trait_name.is_none() => {
+                    let owner = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+                    for method in &im.methods {
+                        add(method, format!("{}::{}", owner, method.name));
+                    }
+                }
+                _ => {}
+            }
+        }
+        for fns in returned_by.values_mut() {
+            fns.sort();
+            fns.dedup();
+        }
+
+        Self {
+            error_types,
+            returned_by,
+        }
+    }
+
+    /// Functions returning `error` (matched by last path segment, so `Error` and
+    /// `crate::Error` are the same entry)
+    pub fn functions_returning(&self, error: &str) -> Vec<&str> {
+        let mut fns: Vec<&str> = self
+            .returned_by
+            .iter()
+            .filter(|(e, _)| last_segment(e) == last_segment(error))
+            .flat_map(|(_, fns)| fns.iter().map(String::as_str))
+            .collect();
+        fns.sort_unstable();
+        fns.dedup();
+        fns
+    }
+
+    /// Returned error types that aren't defined in this crate (`io::Error`, ...)
+    pub fn external_errors(&self) -> impl Iterator<Item = (&String, &Vec<String>)> {
+        self.returned_by
+            .iter()
+            .filter(|(e, _)| !self.error_types.iter().any(|t| t.name == last_segment(e)))
+    }
+
+    pub fn fallible_fn_count(&self) -> usize {
+        let mut fns: Vec<&String> = self.returned_by.values().flatten().collect();
+        fns.sort_unstable();
+        fns.dedup();
+        fns.len()
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    let head = path.split('<').next().unwrap_or(path);
+    head.rsplit("::").next().unwrap_or(head).trim()
+}
+
+/// Error type of a `Result` return type, or `None` if it isn't one
+fn result_error(ret: &str, aliases: &[&TypeAliasInfo]) -> Option<String> {
+    let Ok(Type::Path(path)) = syn::parse_str::<Type>(ret) else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if last.ident != "Result" {
+        return None;
+    }
+    let args: Vec<&Type> = match &last.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                GenericArgument::Type(t) => Some(t),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    if let Some(error) = args.get(1) {
+        return Some(super::pretty::ty(error));
+    }
+
+    // One-argument `Result<T>`: a local alias, or a well-known module's
+    let qualifier = path
+        .path
+        .segments
+        .iter()
+        .rev()
+        .nth(1)
+        .map(|s| s.ident.to_string());
+    match qualifier.as_deref() {
+        Some("io") => return Some("std::io::Error".into()),
+        Some("fmt") => return Some("std::fmt::Error".into()),
+        Some("anyhow") => return Some("anyhow::Error".into()),
+        _ => {}
+    }
+    let alias = aliases.iter().find(|a| a.name == "Result")?;
+    match result_error(&alias.ty, &[]) {
+        Some(error) => Some(error),
+        None if alias.ty.starts_with("anyhow") => Some("anyhow::Error".into()),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_error_catalog() {
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                r#"
+                #[derive(Debug, thiserror::Error)]
+                pub enum ConfigError { Missing, Invalid(String) }
+                pub struct ParseError;
+                pub struct Parser;
+                pub type Result<T> = std::result::Result<T, ConfigError>;
+                pub fn load() -> Result<Config> { todo!() }
+                pub fn parse(s: &str) -> std::result::Result<u8, ParseError> { todo!() }
+                pub fn read() -> io::Result<String> { todo!() }
+                pub fn size() -> usize { 0 }
+                impl Parser {
+                    pub fn run(&self) -> Result<(), crate::ConfigError> { todo!() }
+                }
+                "#,
+            )
+            .unwrap();
+        let catalog = ErrorCatalog::build(&items);
+
+        let names: Vec<&str> = catalog
+            .error_types
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["ConfigError", "ParseError"]);
+        assert!(catalog.error_types[0].thiserror);
+        assert_eq!(catalog.error_types[0].variants, Some(2));
+
+        assert_eq!(
+            catalog.functions_returning("ConfigError"),
+            vec!["Parser::run", "load"]
+        );
+        assert_eq!(catalog.functions_returning("ParseError"), vec!["parse"]);
+        let external: Vec<&String> = catalog.external_errors().map(|(e, _)| e).collect();
+        assert_eq!(external, vec!["std::io::Error"]);
+        assert_eq!(catalog.fallible_fn_count(), 4);
+    }
+}
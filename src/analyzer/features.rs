@@ -0,0 +1,83 @@
+//! Feature unification: which features of a dependency end up enabled, and why
+//!
+//! Cargo builds each crate once with the union of every feature requested for it
+//! anywhere in the graph, so a feature can be on even if you never asked for it.
+
+use std::collections::BTreeMap;
+
+/// Resolved features of one dependency
+#[derive(Debug, Clone, Default)]
+pub struct FeatureUnification {
+    pub name: String,
+    pub version: String,
+    /// Features enabled in the final resolve (sorted)
+    pub enabled: Vec<String>,
+    /// The crate's `default` feature set
+    pub default: Vec<String>,
+    /// Every feature the crate defines except `default` (sorted)
+    pub available: Vec<String>,
+    /// Feature → packages whose dependency declaration requests it (`default`
+    /// included when default features are not disabled)
+    pub requested_by: BTreeMap<String, Vec<String>>,
+}
+
+impl FeatureUnification {
+    /// Packages requesting `feature` directly; empty when it is only implied by another feature
+    pub fn requesters(&self, feature: &str) -> &[String] {
+        self.requested_by
+            .get(feature)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Enabled features that are not part of the default set
+    pub fn non_default(&self) -> impl Iterator<Item = &String> {
+        self.enabled
+            .iter()
+            .filter(|f| f.as_str() != "default" && !self.default.contains(f))
+    }
+
+    /// Default features that ended up disabled
+    pub fn disabled_defaults(&self) -> impl Iterator<Item = &String> {
+        self.default.iter().filter(|f| !self.enabled.contains(f))
+    }
+
+    /// Record that `requester` asks for `features` (plus `default` if `uses_default`)
+    pub fn add_request(&mut self, requester: &str, features: &[String], uses_default: bool) {
+        let defaults = uses_default.then(|| "default".to_string());
+        for feature in features.iter().cloned().chain(defaults) {
+            let list = self.requested_by.entry(feature).or_default();
+            if !list.iter().any(|r| r == requester) {
+                list.push(requester.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_unification() {
+        let mut unification = FeatureUnification {
+            name: "tokio".into(),
+            version: "1.0.0".into(),
+            enabled: vec!["default".into(), "macros".into(), "rt".into()],
+            default: vec!["rt".into(), "time".into()],
+            ..Default::default()
+        };
+        unification.add_request("app", &["macros".into()], false);
+        unification.add_request("hyper", &[], true);
+        unification.add_request("hyper", &[], true);
+
+        assert_eq!(unification.requesters("macros"), ["app".to_string()]);
+        assert_eq!(unification.requesters("default"), ["hyper".to_string()]);
+        assert!(unification.requesters("rt").is_empty());
+        assert_eq!(unification.non_default().collect::<Vec<_>>(), ["macros"]);
+        assert_eq!(
+            unification.disabled_defaults().collect::<Vec<_>>(),
+            ["time"]
+        );
+    }
+}
@@ -0,0 +1,452 @@
+//! C-compatible surface of the crate
+//!
+//! Collects the functions exported over FFI (`extern "C" fn` definitions and
+//! `#[no_mangle]` / `#[export_name]` ones) and the `#[repr(C)]`, `repr(transparent)`
+//! and primitive-`repr` types, then checks every type in those signatures and
+//! fields against the rules of the `improper_ctypes` lints: no `str`, slices,
+//! trait objects, tuples, Rust-ABI function pointers or types without a defined
+//! layout. Types from other crates can't be checked and are assumed to be fine.
+
+use std::collections::HashMap;
+
+use super::enum_layout::Repr;
+use super::types::{AnalyzedItem, FunctionInfo, VariantFields, Visibility};
+use syn::{GenericArgument, PathArguments, ReturnType, Type};
+
+/// A function callable from C
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiFunction {
+    /// `module::name`
+    pub path: String,
+    /// `extern "C"` ABI string; `None` for a Rust-ABI `#[no_mangle]` fn
+    pub abi: Option<String>,
+    pub no_mangle: bool,
+    pub is_public: bool,
+    /// One per offending parameter or return type, e.g. "name: &str: fat pointer"
+    pub issues: Vec<String>,
+}
+
+/// A type with a C-compatible layout, or one used in an FFI signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiType {
+    pub path: String,
+    /// `repr(C)` and the like; `None` when the type has the default Rust layout
+    pub repr: Option<String>,
+    /// One per offending field
+    pub issues: Vec<String>,
+}
+
+/// Result of the audit over all analyzed items
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FfiSurface {
+    pub functions: Vec<FfiFunction>,
+    pub types: Vec<FfiType>,
+}
+
+/// The crate's own type definitions, as far as FFI safety goes
+enum Local<'a> {
+    /// Has a C-compatible `repr` (fields are checked separately)
+    Compatible,
+    /// `repr(transparent)` over this field type
+    Transparent(&'a str),
+    /// Default Rust layout
+    RustLayout,
+    /// A type alias for this type
+    Alias(&'a str),
+}
+
+impl FfiSurface {
+    pub fn build(items: &[AnalyzedItem]) -> Self {
+        let mut local: HashMap<&str, Local> = HashMap::new();
+        for item in items {
+            let entry = match item {
+                AnalyzedItem::Struct(s) => {
+                    let repr = Repr::from_attributes(&s.attributes);
+                    match (repr.transparent, s.fields.first()) {
+                        (true, Some(field)) => Local::Transparent(&field.ty),
+                        _ if repr.c => Local::Compatible,
+                        _ => Local::RustLayout,
+                    }
+                }
+                AnalyzedItem::Enum(e) => {
+                    let repr = Repr::from_attributes(&e.attributes);
+                    if repr.c || repr.int.is_some() {
+                        Local::Compatible
+                    } else {
+                        Local::RustLayout
+                    }
+                }
+                AnalyzedItem::TypeAlias(t) => Local::Alias(&t.ty),
+                _ => continue,
+            };
+            local.insert(item.name(), entry);
+        }
+        let checker = Checker { local: &local };
+
+        let mut functions = Vec::new();
+        let mut used: Vec<String> = Vec::new();
+        for item in items {
+            let AnalyzedItem::Function(f) = item else {
+                continue;
+            };
+            let abi = extern_abi(f);
+            let no_mangle = has_no_mangle(f);
+            if abi.is_none() && !no_mangle {
+                continue;
+            }
+            let mut issues = Vec::new();
+            let mut check = |label: String, ty: &str| {
+                let Ok(parsed) = syn::parse_str::<Type>(ty) else {
+                    return;
+                };
+                used.extend(checker.local_names(&parsed));
+                if let Some(problem) = checker.problem(&parsed) {
+                    issues.push(format!("{}: {}", label, problem));
+                }
+            };
+            for param in f.parameters.iter().filter(|p| !p.is_self) {
+                check(format!("{}: {}", param.name, param.ty), &param.ty);
+            }
+            if let Some(ref ret) = f.return_type {
+                check(format!("-> {}", ret), ret);
+            }
+            functions.push(FfiFunction {
+                path: qualified(&f.module_path, &f.name),
+                abi,
+                no_mangle,
+                is_public: f.visibility == Visibility::Public,
+                issues,
+            });
+        }
+
+        let mut types = Vec::new();
+        for item in items {
+            let (attributes, fields): (&[String], Vec<(String, &str)>) = match item {
+                AnalyzedItem::Struct(s) => (
+                    &s.attributes,
+                    s.fields.iter().map(|f| (f.name.clone(), &*f.ty)).collect(),
+                ),
+                AnalyzedItem::Enum(e) => (
+                    &e.attributes,
+                    e.variants
+                        .iter()
+                        .flat_map(|v| match &v.fields {
+                            VariantFields::Named(fields) => fields
+                                .iter()
+                                .map(|f| (format!("{}.{}", v.name, f.name), &*f.ty))
+                                .collect(),
+                            VariantFields::Unnamed(tys) => tys
+                                .iter()
+                                .enumerate()
+                                .map(|(i, ty)| (format!("{}.{}", v.name, i), ty.as_str()))
+                                .collect(),
+                            VariantFields::Unit => Vec::new(),
+                        })
+                        .collect(),
+                ),
+                _ => continue,
+            };
+            let repr = Repr::from_attributes(attributes);
+            let in_signature = used.iter().any(|u| u == item.name());
+            if !(repr.c || repr.transparent || repr.int.is_some() || in_signature) {
+                continue;
+            }
+            let issues = if repr.c || repr.transparent || repr.int.is_some() {
+                fields
+                    .iter()
+                    .filter_map(|(label, ty)| {
+                        let problem = checker.problem(&syn::parse_str(ty).ok()?)?;
+                        Some(format!("{}: {}: {}", label, ty, problem))
+                    })
+                    .collect()
+            } else {
+                vec!["used over FFI but has no #[repr(C)]".to_string()]
+            };
+            let module_path = match item {
+                AnalyzedItem::Struct(s) => &s.module_path,
+                AnalyzedItem::Enum(e) => &e.module_path,
+                _ => continue,
+            };
+            types.push(FfiType {
+                path: qualified(module_path, item.name()),
+                repr: repr.summary(),
+                issues,
+            });
+        }
+
+        functions.sort_by(|a, b| a.path.cmp(&b.path));
+        types.sort_by(|a, b| a.path.cmp(&b.path));
+        types.dedup_by(|a, b| a.path == b.path);
+        Self { functions, types }
+    }
+
+    /// Functions and types with at least one problem
+    pub fn issue_count(&self) -> usize {
+        self.functions
+            .iter()
+            .filter(|f| !f.issues.is_empty())
+            .count()
+            + self.types.iter().filter(|t| !t.issues.is_empty()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.types.is_empty()
+    }
+}
+
+fn qualified(module_path: &[String], name: &str) -> String {
+    if module_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", module_path.join("::"), name)
+    }
+}
+
+/// `#[no_mangle]` (plain or `unsafe(..)`) or `#[export_name = ".."]`
+pub(crate) fn has_no_mangle(f: &FunctionInfo) -> bool {
+    f.attributes
+        .iter()
+        .any(|a| a.contains("no_mangle") || a.starts_with("#[export_name"))
+}
+
+/// ABI of an `extern` fn (`extern fn` meaning `"C"`), `None` for Rust ABI
+pub(crate) fn extern_abi(f: &FunctionInfo) -> Option<String> {
+    let item = syn::parse_str::<syn::TraitItemFn>(&format!("{};", f.signature)).ok()?;
+    let abi = item.sig.abi?;
+    let name = abi.name.map_or_else(|| "C".to_string(), |n| n.value());
+    (name != "Rust").then_some(name)
+}
+
+struct Checker<'a> {
+    local: &'a HashMap<&'a str, Local<'a>>,
+}
+
+impl Checker<'_> {
+    /// Why `ty` can't cross an FFI boundary, `None` when it can (or can't be told)
+    fn problem(&self, ty: &Type) -> Option<String> {
+        self.problem_at(ty, 0)
+    }
+
+    fn problem_at(&self, ty: &Type, depth: usize) -> Option<String> {
+        if depth > 8 {
+            return None;
+        }
+        match ty {
+            Type::Ptr(p) => self.pointee(&p.elem, depth),
+            Type::Reference(r) => self.pointee(&r.elem, depth),
+            Type::Paren(p) => self.problem_at(&p.elem, depth),
+            Type::Group(g) => self.problem_at(&g.elem, depth),
+            Type::Array(a) => self.problem_at(&a.elem, depth + 1),
+            Type::Never(_) => None,
+            Type::Tuple(t) if t.elems.is_empty() => None,
+            Type::Tuple(_) => Some("tuples have no C layout".into()),
+            Type::Slice(_) => Some("slices have no C layout".into()),
+            Type::TraitObject(_) => Some("trait objects have no C layout".into()),
+            Type::ImplTrait(_) => Some("impl Trait has no C layout".into()),
+            Type::BareFn(f) => {
+                let abi = f.abi.as_ref().map(|a| a.name.as_ref().map(|n| n.value()));
+                match abi {
+                    Some(Some(name)) if name == "Rust" => {
+                        Some("fn pointer with the Rust ABI".into())
+                    }
+                    Some(_) => None,
+                    None => Some("fn pointer with the Rust ABI; use extern \"C\" fn".into()),
+                }
+            }
+            Type::Path(p) => {
+                let last = p.path.segments.last()?;
+                let name = last.ident.to_string();
+                let args: Vec<&Type> = match &last.arguments {
+                    PathArguments::AngleBracketed(args) => args
+                        .args
+                        .iter()
+                        .filter_map(|a| match a {
+                            GenericArgument::Type(t) => Some(t),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                match name.as_str() {
+                    "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64"
+                    | "isize" | "f32" | "f64" | "bool" | "NonNull" | "PhantomData"
+                    | "PhantomPinned" | "c_void" => None,
+                    _ if name.starts_with("c_") || name.starts_with("NonZero") => None,
+                    "u128" | "i128" => Some(format!("{} has no stable C ABI", name)),
+                    "char" => Some("char has no C equivalent; use u32".into()),
+                    "str" => Some("str has no C layout".into()),
+                    "String" | "Vec" | "CString" | "OsString" | "PathBuf" => Some(format!(
+                        "{} has no C layout; pass a pointer and length",
+                        name
+                    )),
+                    "CStr" => Some("CStr is unsized; pass *const c_char".into()),
+                    "Box" => args.first().and_then(|inner| self.pointee(inner, depth)),
+                    // Nullable pointers: `Option<&T>`, `Option<Box<T>>`, `Option<extern fn>`
+                    "Option" => match args.first() {
+                        Some(inner) if self.is_non_null(inner, depth) => {
+                            self.problem_at(inner, depth + 1)
+                        }
+                        _ => Some("Option of a non-pointer type has no C layout".into()),
+                    },
+                    _ => match self.local.get(name.as_str()) {
+                        Some(Local::Compatible) => None,
+                        Some(Local::Transparent(inner)) | Some(Local::Alias(inner)) => {
+                            let inner = syn::parse_str::<Type>(inner).ok()?;
+                            self.problem_at(&inner, depth + 1)
+                        }
+                        Some(Local::RustLayout) => Some(format!("{} has no #[repr(C)]", name)),
+                        None => None,
+                    },
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Behind a pointer only unsized pointees (fat pointers) are a problem
+    fn pointee(&self, ty: &Type, depth: usize) -> Option<String> {
+        match ty {
+            Type::Slice(_) | Type::TraitObject(_) => {
+                Some("fat pointer; pass a thin pointer and length".into())
+            }
+            Type::Path(p) if p.path.is_ident("str") => {
+                Some("fat pointer; pass a thin pointer and length".into())
+            }
+            Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "CStr") => {
+                Some("fat pointer; pass *const c_char".into())
+            }
+            _ => self
+                .problem_at(ty, depth + 1)
+                .filter(|p| !p.contains("no #[repr(C)]")),
+        }
+    }
+
+    /// Types the compiler guarantees are never null, so `Option` of them is a plain
+    /// pointer; the crate's type aliases are followed
+    fn is_non_null(&self, ty: &Type, depth: usize) -> bool {
+        match ty {
+            Type::Reference(_) | Type::BareFn(_) => true,
+            Type::Path(p) => {
+                let Some(last) = p.path.segments.last() else {
+                    return false;
+                };
+                let name = last.ident.to_string();
+                match self.local.get(name.as_str()) {
+                    Some(Local::Alias(inner)) if depth < 8 => syn::parse_str::<Type>(inner)
+                        .is_ok_and(|inner| self.is_non_null(&inner, depth + 1)),
+                    _ => name == "Box" || name == "NonNull" || name.starts_with("NonZero"),
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// The crate's own type names mentioned in `ty`
+    fn local_names(&self, ty: &Type) -> Vec<String> {
+        let mut names = Vec::new();
+        collect_names(ty, &mut names);
+        names.retain(|n| self.local.contains_key(n.as_str()));
+        names
+    }
+}
+
+pub(crate) fn collect_names(ty: &Type, out: &mut Vec<String>) {
+    match ty {
+        Type::Path(p) => {
+            for segment in &p.path.segments {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    for arg in &args.args {
+                        if let GenericArgument::Type(t) = arg {
+                            collect_names(t, out);
+                        }
+                    }
+                }
+            }
+            if let Some(last) = p.path.segments.last() {
+                out.push(last.ident.to_string());
+            }
+        }
+        Type::Ptr(p) => collect_names(&p.elem, out),
+        Type::Reference(r) => collect_names(&r.elem, out),
+        Type::Array(a) => collect_names(&a.elem, out),
+        Type::Slice(s) => collect_names(&s.elem, out),
+        Type::Paren(p) => collect_names(&p.elem, out),
+        Type::Group(g) => collect_names(&g.elem, out),
+        Type::Tuple(t) => t.elems.iter().for_each(|e| collect_names(e, out)),
+        Type::BareFn(f) => {
+            for input in &f.inputs {
+                collect_names(&input.ty, out);
+            }
+            if let ReturnType::Type(_, ret) = &f.output {
+                collect_names(ret, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_surface_and_issues() {
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                r#"
+                #[repr(C)]
+                pub struct Point { pub x: f64, pub y: f64 }
+                #[repr(C)]
+                pub struct Named { pub name: String, pub len: usize }
+                #[repr(u8)]
+                pub enum Mode { Read, Write }
+                pub struct Opaque { inner: Vec<u8> }
+                #[repr(transparent)]
+                pub struct Handle(*mut Opaque);
+                pub type Callback = extern "C" fn(*const Point);
+
+                #[no_mangle]
+                pub extern "C" fn point_len(p: *const Point, mode: Mode, cb: Option<Callback>) -> f64 { 0.0 }
+                #[no_mangle]
+                pub extern "C" fn greet(name: &str, tag: char) -> Opaque { todo!() }
+                pub extern "C" fn open(h: Handle, hook: fn(u8)) -> Option<Box<Point>> { todo!() }
+                pub fn rust_only(s: String) {}
+                "#,
+            )
+            .unwrap();
+        let surface = FfiSurface::build(&items);
+
+        let names: Vec<&str> = surface.functions.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(names, vec!["greet", "open", "point_len"]);
+        let point_len = &surface.functions[2];
+        assert!(point_len.no_mangle && point_len.issues.is_empty());
+        assert_eq!(point_len.abi.as_deref(), Some("C"));
+        let greet = &surface.functions[0];
+        assert_eq!(greet.issues.len(), 3);
+        assert!(greet.issues[0].starts_with("name: &str: fat pointer"));
+        assert!(greet.issues[1].contains("char has no C equivalent"));
+        assert!(greet.issues[2].contains("Opaque has no #[repr(C)]"));
+        let open = &surface.functions[1];
+        assert!(!open.no_mangle);
+        assert_eq!(open.issues.len(), 1);
+        assert!(open.issues[0].starts_with("hook: fn(u8): fn pointer"));
+
+        let types: Vec<(&str, Option<&str>, usize)> = surface
+            .types
+            .iter()
+            .map(|t| (t.path.as_str(), t.repr.as_deref(), t.issues.len()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("Handle", Some("repr(transparent)"), 0),
+                ("Mode", Some("repr(u8)"), 0),
+                ("Named", Some("repr(C)"), 1),
+                ("Opaque", None, 1),
+                ("Point", Some("repr(C)"), 0),
+            ]
+        );
+        assert_eq!(surface.issue_count(), 4);
+    }
+}
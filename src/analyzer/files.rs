@@ -0,0 +1,165 @@
+//! Per-file metrics: lines of code, items, doc coverage and `unsafe` use
+//!
+//! Lines and `unsafe` keywords come from the raw source (tokens, so comments and
+//! strings don't count); item counts and docs are added from the analyzed items.
+
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{TokenStream, TokenTree};
+use serde::{Deserialize, Serialize};
+
+use super::types::AnalyzedItem;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetrics {
+    pub path: PathBuf,
+    /// Non-blank lines that aren't only a `//` comment
+    pub lines: usize,
+    /// Top-level analyzed items, impl blocks included
+    pub items: usize,
+    /// Items (and inherent methods) that take docs, and how many have them
+    pub documentable: usize,
+    pub documented: usize,
+    /// `unsafe` blocks, functions, traits and impls
+    pub unsafe_count: usize,
+}
+
+impl FileMetrics {
+    /// Lines and `unsafe` keywords of one file; items are counted by [`attach_items`]
+    pub fn scan(source: &str, path: &Path) -> Self {
+        let lines = source
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+            .count();
+        let unsafe_count = source.parse::<TokenStream>().map_or(0, count_unsafe);
+        Self {
+            path: path.to_path_buf(),
+            lines,
+            unsafe_count,
+            ..Self::default()
+        }
+    }
+
+    /// Share of documentable items with docs, `None` when there are none
+    pub fn doc_coverage(&self) -> Option<f64> {
+        (self.documentable > 0).then(|| self.documented as f64 / self.documentable as f64)
+    }
+}
+
+/// Order of the Files view, cycled with `s`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileSort {
+    #[default]
+    Path,
+    /// Longest first
+    Lines,
+    Items,
+    /// Least documented first
+    Docs,
+    /// Most `unsafe` first
+    Unsafe,
+}
+
+impl FileSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Path => Self::Lines,
+            Self::Lines => Self::Items,
+            Self::Items => Self::Docs,
+            Self::Docs => Self::Unsafe,
+            Self::Unsafe => Self::Path,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::Lines => "lines",
+            Self::Items => "items",
+            Self::Docs => "doc coverage",
+            Self::Unsafe => "unsafe",
+        }
+    }
+
+    /// `files` in this order; ties stay in path order
+    pub fn sorted(self, files: &[FileMetrics]) -> Vec<&FileMetrics> {
+        let mut sorted: Vec<&FileMetrics> = files.iter().collect();
+        sorted.sort_by(|a, b| a.path.cmp(&b.path));
+        match self {
+            Self::Path => {}
+            Self::Lines => sorted.sort_by_key(|f| std::cmp::Reverse(f.lines)),
+            Self::Items => sorted.sort_by_key(|f| std::cmp::Reverse(f.items)),
+            Self::Docs => sorted.sort_by(|a, b| {
+                let coverage = |f: &FileMetrics| f.doc_coverage().unwrap_or(1.0);
+                coverage(a).total_cmp(&coverage(b))
+            }),
+            Self::Unsafe => sorted.sort_by_key(|f| std::cmp::Reverse(f.unsafe_count)),
+        }
+        sorted
+    }
+}
+
+fn count_unsafe(tokens: TokenStream) -> usize {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) if ident == "unsafe" => 1,
+            TokenTree::Group(group) => count_unsafe(group.stream()),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Count each file's items and how many of them are documented. Trait impl
+/// methods are documented on the trait, so they're left out.
+pub fn attach_items(files: &mut [FileMetrics], items: &[AnalyzedItem]) {
+    for item in items {
+        let Some(file) = item
+            .source_location()
+            .and_then(|loc| loc.file.as_deref())
+            .and_then(|path| files.iter_mut().find(|f| f.path == path))
+        else {
+            continue;
+        };
+        file.items += 1;
+        match item {
+            AnalyzedItem::Impl(im) if im.trait_name.is_none() => {
+                for method in &im.methods {
+                    file.documentable += 1;
+                    file.documented += usize::from(method.documentation.is_some());
+                }
+            }
+            AnalyzedItem::Impl(_) => {}
+            _ => {
+                file.documentable += 1;
+                file.documented += usize::from(item.documentation().is_some());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_file_metrics() {
+        let source = "//! Crate docs\n\n/// Documented\npub struct S;\n\n\
+                      impl S {\n    pub fn f(&self) {\n        // \"unsafe\" in a comment\n        \
+                      unsafe { g() }\n    }\n}\n\nunsafe fn g() {}\n";
+        let path = Path::new("/p/src/lib.rs");
+        let items = RustAnalyzer::new()
+            .analyze_source_with_path(source, Some(path.to_path_buf()))
+            .unwrap();
+        let mut files = vec![FileMetrics::scan(source, path)];
+        attach_items(&mut files, &items);
+        let file = &files[0];
+        assert_eq!(file.lines, 7);
+        assert_eq!(file.unsafe_count, 2);
+        assert_eq!(file.items, 3);
+        assert_eq!((file.documented, file.documentable), (1, 3));
+        assert_eq!(file.doc_coverage(), Some(1.0 / 3.0));
+    }
+}
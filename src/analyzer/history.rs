@@ -0,0 +1,175 @@
+//! How an item changed over the commits of its file
+//!
+//! Each revision of the file is parsed again and the item found in it by kind and
+//! qualified name, as for the item diff. A commit is a step in the item's history
+//! when the item's lines (with docs and attributes) differ from the step before;
+//! the walk back stops at the first revision without the item.
+
+use std::path::Path;
+
+use super::compare::find_counterpart;
+use super::item_diff::{line_diff, DiffLine, DiffTag};
+use super::parser::RustAnalyzer;
+use super::types::AnalyzedItem;
+use crate::utils::git::Commit;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    /// The oldest revision with the item
+    Introduced,
+    /// The signature, fields or variants changed
+    Definition,
+    /// Only the body, docs or attributes changed
+    Body,
+}
+
+impl StepKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Introduced => "introduced",
+            Self::Definition => "definition changed",
+            Self::Body => "body changed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryStep {
+    pub commit: Commit,
+    pub kind: StepKind,
+    /// The item's definition at this commit (see [`AnalyzedItem::definition`])
+    pub definition: String,
+    /// The definition against the step before, unchanged lines included; empty for
+    /// the first step
+    pub changes: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemHistory {
+    /// Qualified name of the item
+    pub name: String,
+    /// Oldest first
+    pub steps: Vec<HistoryStep>,
+}
+
+impl ItemHistory {
+    /// `revisions` are the commits that touched `file`, newest first, each with the
+    /// file's contents at that commit (`None` where it didn't exist). They're only
+    /// consumed until the item is missing.
+    pub fn build(
+        item: &AnalyzedItem,
+        file: &Path,
+        revisions: impl IntoIterator<Item = (Commit, Option<String>)>,
+    ) -> Self {
+        let mut found: Vec<(Commit, String, String)> = Vec::new();
+        for (commit, source) in revisions {
+            let Some((text, definition)) = source.and_then(|source| {
+                let items = RustAnalyzer::new()
+                    .with_private(true)
+                    .analyze_source_with_path(&source, Some(file.to_path_buf()))
+                    .ok()?;
+                let old = find_counterpart(item, &items)?;
+                let (_, text) = old.source_location()?.source_in(&source)?;
+                Some((text, old.definition()))
+            }) else {
+                break;
+            };
+            found.push((commit, text, definition));
+        }
+
+        let mut steps: Vec<HistoryStep> = Vec::new();
+        let mut last_text: Option<String> = None;
+        for (commit, text, definition) in found.into_iter().rev() {
+            if last_text.as_ref() == Some(&text) {
+                continue;
+            }
+            last_text = Some(text);
+            let step = match steps.last() {
+                None => HistoryStep {
+                    commit,
+                    kind: StepKind::Introduced,
+                    definition,
+                    changes: Vec::new(),
+                },
+                Some(previous) => {
+                    let changes = line_diff(&previous.definition, &definition);
+                    let kind = if changes.iter().all(|l| l.tag == DiffTag::Context) {
+                        StepKind::Body
+                    } else {
+                        StepKind::Definition
+                    };
+                    HistoryStep {
+                        commit,
+                        kind,
+                        definition,
+                        changes,
+                    }
+                }
+            };
+            steps.push(step);
+        }
+        Self {
+            name: item.qualified_name(),
+            steps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(n: u32) -> Commit {
+        Commit {
+            hash: format!("{:040}", n),
+            author: "Ada".to_string(),
+            date: format!("2024-01-{:02}", n),
+            subject: format!("change {}", n),
+        }
+    }
+
+    #[test]
+    fn test_history_keeps_commits_that_changed_the_item() {
+        let file = Path::new("/p/src/lib.rs");
+        let v1 = "pub struct Config { pub name: String }\n";
+        let v2 = "pub struct Other;\n\npub struct Config { pub name: String }\n";
+        let v3 = "/// Settings\npub struct Config { pub name: String }\n";
+        let v4 =
+            "/// Settings\npub struct Config {\n    pub name: String,\n    pub port: u16,\n}\n";
+        let items = RustAnalyzer::new()
+            .analyze_source_with_path(v4, Some(file.to_path_buf()))
+            .unwrap();
+        let revisions = vec![
+            (commit(5), Some(v4.to_string())),
+            (commit(4), Some(v3.to_string())),
+            (commit(3), Some(v2.to_string())),
+            (commit(2), Some(v1.to_string())),
+            (commit(1), None),
+            (commit(0), Some(v4.to_string())),
+        ];
+        let history = ItemHistory::build(&items[0], file, revisions);
+
+        assert_eq!(history.name, "Config");
+        let steps: Vec<(&str, StepKind)> = history
+            .steps
+            .iter()
+            .map(|s| (s.commit.subject.as_str(), s.kind))
+            .collect();
+        // Moving the item (3) isn't a change; before 2 it didn't exist
+        assert_eq!(
+            steps,
+            vec![
+                ("change 2", StepKind::Introduced),
+                ("change 4", StepKind::Body),
+                ("change 5", StepKind::Definition),
+            ]
+        );
+        let added: Vec<&str> = history.steps[2]
+            .changes
+            .iter()
+            .filter(|l| l.tag == DiffTag::Added)
+            .map(|l| l.text.as_str())
+            .collect();
+        assert!(added.iter().any(|l| l.contains("port: u16")));
+    }
+}
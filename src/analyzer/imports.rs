@@ -0,0 +1,349 @@
+//! Which modules of the crate `use` which, and the cycles between them
+//!
+//! `use` declarations are read per file with their module path resolved to the
+//! crate root (`crate::`, `self::`, `super::`). [`ImportGraph::build`] then maps each
+//! path to the deepest module it names and drops items, external crates and imports
+//! of a module's own ancestors (re-exports through `mod.rs` aren't a dependency).
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::{Item, UseTree};
+
+/// Name of the crate root in module paths
+pub const ROOT: &str = "crate";
+
+/// One path brought into scope by a `use` declaration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleImport {
+    /// Module the declaration is in, e.g. `["app", "state"]`; empty for the crate root
+    pub from: Vec<String>,
+    /// Imported path from the crate root, `crate::` stripped
+    pub path: Vec<String>,
+    /// Written without `crate::`/`self::`/`super::`: either a child module of `from`
+    /// or an external crate
+    pub bare: bool,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// `use` declarations of one file, which is `module`, inline `mod` blocks included;
+/// an unparsable file has none
+pub fn scan(source: &str, file: &Path, module: &[String]) -> Vec<ModuleImport> {
+    let Ok(ast) = syn::parse_file(source) else {
+        return Vec::new();
+    };
+    let mut imports = Vec::new();
+    scan_items(&ast.items, module, file, &mut imports);
+    imports
+}
+
+/// Imports among `items`, which are in `module`; inline `mod` blocks nest a level
+fn scan_items(items: &[Item], module: &[String], file: &Path, out: &mut Vec<ModuleImport>) {
+    for item in items {
+        match item {
+            Item::Use(u) => {
+                let line = u.span().start().line;
+                let mut paths = Vec::new();
+                flatten(&u.tree, Vec::new(), &mut paths);
+                for path in paths {
+                    if let Some((path, bare)) = resolve(module, path) {
+                        out.push(ModuleImport {
+                            from: module.to_vec(),
+                            path,
+                            bare,
+                            file: file.to_path_buf(),
+                            line,
+                        });
+                    }
+                }
+            }
+            Item::Mod(m) => {
+                if let Some((_, ref content)) = m.content {
+                    let mut inner = module.to_vec();
+                    inner.push(m.ident.to_string());
+                    scan_items(content, &inner, file, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Every path a use tree imports: `a::{b, c::*}` is `a::b` and `a::c`
+fn flatten(tree: &UseTree, prefix: Vec<String>, out: &mut Vec<Vec<String>>) {
+    match tree {
+        UseTree::Path(p) => {
+            let mut prefix = prefix;
+            prefix.push(p.ident.to_string());
+            flatten(&p.tree, prefix, out);
+        }
+        UseTree::Name(n) if n.ident == "self" => out.push(prefix),
+        UseTree::Name(n) => out.push([prefix, vec![n.ident.to_string()]].concat()),
+        UseTree::Rename(r) => out.push([prefix, vec![r.ident.to_string()]].concat()),
+        UseTree::Glob(_) => out.push(prefix),
+        UseTree::Group(g) => {
+            for tree in &g.items {
+                flatten(tree, prefix.clone(), out);
+            }
+        }
+    }
+}
+
+/// `path` from the crate root, and whether it was bare; `None` for `::std` style paths
+/// and a `super` above the root
+fn resolve(module: &[String], path: Vec<String>) -> Option<(Vec<String>, bool)> {
+    let first = path.first()?;
+    match first.as_str() {
+        "crate" => Some((path[1..].to_vec(), false)),
+        "self" | "super" => {
+            let mut base = module.to_vec();
+            let mut rest = path.as_slice();
+            if rest[0] == "self" {
+                rest = &rest[1..];
+            }
+            while rest.first().is_some_and(|s| s == "super") {
+                base.pop()?;
+                rest = &rest[1..];
+            }
+            base.extend(rest.iter().cloned());
+            Some((base, false))
+        }
+        _ => Some(([module, &path].concat(), true)),
+    }
+}
+
+/// Module-to-module `use` edges of one crate
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportGraph {
+    /// Every module, `crate` for the root and `a::b` below it, with the modules it
+    /// imports from
+    pub edges: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ImportGraph {
+    /// Graph over `modules` (paths from the crate root) from `imports`; a module that
+    /// declares imports but isn't in `modules` is added
+    pub fn build(modules: impl IntoIterator<Item = Vec<String>>, imports: &[ModuleImport]) -> Self {
+        let mut known: BTreeSet<Vec<String>> = modules.into_iter().collect();
+        known.extend(imports.iter().map(|i| i.from.clone()));
+        known.insert(Vec::new());
+        let mut edges: BTreeMap<String, BTreeSet<String>> = known
+            .iter()
+            .map(|m| (display(m), BTreeSet::new()))
+            .collect();
+        for import in imports {
+            let Some(target) = deepest_module(&known, import) else {
+                continue;
+            };
+            // Importing from an ancestor (re-exports, shared types) or from itself
+            if import.from.starts_with(&target) {
+                continue;
+            }
+            if let Some(targets) = edges.get_mut(&display(&import.from)) {
+                targets.insert(display(&target));
+            }
+        }
+        Self { edges }
+    }
+
+    /// Modules that import `module`
+    pub fn importers(&self, module: &str) -> Vec<&str> {
+        self.edges
+            .iter()
+            .filter(|(_, targets)| targets.contains(module))
+            .map(|(m, _)| m.as_str())
+            .collect()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.values().map(BTreeSet::len).sum()
+    }
+
+    /// One cycle per group of mutually dependent modules, as a closed path
+    /// (`a → b → a`) through the group's first module, shortest first
+    pub fn cycles(&self) -> Vec<Vec<&str>> {
+        let mut cycles: Vec<Vec<&str>> = self
+            .strongly_connected()
+            .into_iter()
+            .filter(|group| group.len() > 1)
+            .filter_map(|group| self.shortest_cycle(&group))
+            .collect();
+        cycles.sort_by_key(|c| (c.len(), c.first().copied()));
+        cycles
+    }
+
+    /// Tarjan's algorithm, iteratively over the sorted module names
+    fn strongly_connected(&self) -> Vec<BTreeSet<&str>> {
+        let names: Vec<&str> = self.edges.keys().map(String::as_str).collect();
+        let position: BTreeMap<&str, usize> =
+            names.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        let successors: Vec<Vec<usize>> = self
+            .edges
+            .values()
+            .map(|ts| {
+                ts.iter()
+                    .filter_map(|t| position.get(t.as_str()).copied())
+                    .collect()
+            })
+            .collect();
+
+        let mut index = vec![usize::MAX; names.len()];
+        let mut low = vec![0; names.len()];
+        let mut on_stack = vec![false; names.len()];
+        let mut stack = Vec::new();
+        let mut groups = Vec::new();
+        let mut next = 0;
+        for start in 0..names.len() {
+            if index[start] != usize::MAX {
+                continue;
+            }
+            // (node, next successor to visit)
+            let mut work = vec![(start, 0)];
+            while let Some(&mut (node, ref mut child)) = work.last_mut() {
+                if *child == 0 {
+                    index[node] = next;
+                    low[node] = next;
+                    next += 1;
+                    stack.push(node);
+                    on_stack[node] = true;
+                }
+                if let Some(&succ) = successors[node].get(*child) {
+                    *child += 1;
+                    if index[succ] == usize::MAX {
+                        work.push((succ, 0));
+                    } else if on_stack[succ] {
+                        low[node] = low[node].min(index[succ]);
+                    }
+                    continue;
+                }
+                work.pop();
+                if let Some(&(parent, _)) = work.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+                if low[node] == index[node] {
+                    let mut group = BTreeSet::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        group.insert(names[member]);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    groups.push(group);
+                }
+            }
+        }
+        groups
+    }
+
+    /// Breadth-first from the group's first module back to itself, within the group
+    fn shortest_cycle<'a>(&'a self, group: &BTreeSet<&'a str>) -> Option<Vec<&'a str>> {
+        let start = *group.first()?;
+        let mut came_from: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for next in self.edges.get(node).into_iter().flatten() {
+                let next = next.as_str();
+                if next == start {
+                    let mut path = vec![start];
+                    let mut at = node;
+                    while at != start {
+                        path.push(at);
+                        at = came_from[at];
+                    }
+                    path.push(start);
+                    path.reverse();
+                    return Some(path);
+                }
+                if group.contains(next) && !came_from.contains_key(next) {
+                    came_from.insert(next, node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Deepest known module `import` names: `crate::a::b::Item` is `a::b` when that is a
+/// module. A bare path only counts when its first segment is a child module.
+fn deepest_module(known: &BTreeSet<Vec<String>>, import: &ModuleImport) -> Option<Vec<String>> {
+    let shortest = if import.bare {
+        import.from.len() + 1
+    } else {
+        0
+    };
+    (shortest..=import.path.len())
+        .rev()
+        .map(|n| &import.path[..n])
+        .find(|prefix| known.contains(*prefix))
+        .map(<[String]>::to_vec)
+}
+
+fn display(module: &[String]) -> String {
+    if module.is_empty() {
+        ROOT.to_string()
+    } else {
+        module.join("::")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modules(paths: &[&str]) -> Vec<Vec<String>> {
+        paths
+            .iter()
+            .map(|p| p.split("::").map(String::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_import_graph_and_cycles() {
+        let mut imports = scan(
+            "use crate::ui::{render, Theme};\nuse super::error::Error;\nuse std::fmt;\n\
+             use helpers::*;\nmod inner { use crate::app::state as s; }\n",
+            Path::new("/p/src/app/mod.rs"),
+            &["app".to_string()],
+        );
+        assert_eq!(imports[0].from, vec!["app"]);
+        assert_eq!(imports[2].path, vec!["error", "Error"]);
+        assert!(imports[3].bare);
+        assert_eq!(imports.last().unwrap().from, vec!["app", "inner"]);
+        imports.extend(scan(
+            "use crate::app::App;\nuse crate::Result;\n",
+            Path::new("/p/src/ui/render.rs"),
+            &["ui".to_string(), "render".to_string()],
+        ));
+        imports.extend(scan(
+            "use super::*;\n",
+            Path::new("/p/src/app/helpers.rs"),
+            &["app".to_string(), "helpers".to_string()],
+        ));
+
+        let known = modules(&[
+            "app",
+            "app::state",
+            "app::helpers",
+            "error",
+            "ui",
+            "ui::render",
+        ]);
+        let graph = ImportGraph::build(known, &imports);
+        let targets = |m: &str| graph.edges[m].iter().cloned().collect::<Vec<_>>();
+        // `Theme` counts as `ui`, `std` isn't in the crate, `super::*` is an ancestor
+        assert_eq!(
+            targets("app"),
+            vec!["app::helpers", "error", "ui", "ui::render"]
+        );
+        assert_eq!(targets("app::inner"), vec!["app::state"]);
+        assert_eq!(targets("app::helpers"), Vec::<String>::new());
+        assert_eq!(targets("ui::render"), vec!["app"]);
+        assert_eq!(graph.importers("app"), vec!["ui::render"]);
+        assert_eq!(graph.cycles(), vec![vec!["app", "ui::render", "app"]]);
+    }
+}
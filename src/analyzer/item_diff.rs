@@ -0,0 +1,231 @@
+//! One item's source against another revision
+//!
+//! The item is found in the old version of its file by kind and qualified name
+//! (as in comparison mode), so it still matches after moving within the file, and
+//! the two source spans are diffed line by line.
+
+use std::path::Path;
+
+use super::compare::find_counterpart;
+use super::parser::RustAnalyzer;
+use super::types::AnalyzedItem;
+
+/// Unchanged lines kept around each change
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffTag {
+    Context,
+    Added,
+    Removed,
+    /// A run of unchanged lines left out; the text says how many
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    pub text: String,
+}
+
+impl DiffLine {
+    fn new(tag: DiffTag, text: &str) -> Self {
+        Self {
+            tag,
+            text: text.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemChange {
+    Unchanged,
+    /// Not in the old file (or the file is new); every line is added
+    Added(Vec<DiffLine>),
+    Modified(Vec<DiffLine>),
+}
+
+/// Compare `item` in `source` (its file now) with the same item in `old_source`
+/// (the file at the other revision, `None` if it didn't exist)
+pub fn item_change(
+    item: &AnalyzedItem,
+    file: &Path,
+    source: &str,
+    old_source: Option<&str>,
+) -> Option<ItemChange> {
+    let current = span(item, source)?;
+    let old = old_source.and_then(|old_source| {
+        let old_items = RustAnalyzer::new()
+            .with_private(true)
+            .analyze_source_with_path(old_source, Some(file.to_path_buf()))
+            .ok()?;
+        let old_item = find_counterpart(item, &old_items)?;
+        span(old_item, old_source)
+    });
+    Some(match old {
+        None => ItemChange::Added(
+            current
+                .lines()
+                .map(|l| DiffLine::new(DiffTag::Added, l))
+                .collect(),
+        ),
+        Some(old) if old == current => ItemChange::Unchanged,
+        Some(old) => ItemChange::Modified(unified(&line_diff(&old, &current), CONTEXT)),
+    })
+}
+
+/// The item's lines in `source`, with its doc comments and attributes
+fn span(item: &AnalyzedItem, source: &str) -> Option<String> {
+    item.source_location()?
+        .source_in(source)
+        .map(|(_, text)| text)
+}
+
+/// Every line of both texts, tagged, by longest common subsequence
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // common[i][j]: LCS length of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(DiffLine::new(DiffTag::Context, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            // Removals first, as in `git diff`
+            lines.push(DiffLine::new(DiffTag::Removed, old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::new(DiffTag::Added, new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// `lines` with unchanged runs longer than `context` lines on each side of a change
+/// folded into one [`DiffTag::Skipped`] line
+pub fn unified(lines: &[DiffLine], context: usize) -> Vec<DiffLine> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.tag != DiffTag::Context)
+        .map(|(i, _)| i)
+        .collect();
+    let near_change = |i: usize| changed.iter().any(|&c| c.abs_diff(i) <= context);
+    let mut out = Vec::new();
+    let mut skipped = 0;
+    for (i, line) in lines.iter().enumerate() {
+        if line.tag != DiffTag::Context || near_change(i) {
+            if skipped > 0 {
+                out.push(skipped_line(skipped));
+                skipped = 0;
+            }
+            out.push(line.clone());
+        } else {
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        out.push(skipped_line(skipped));
+    }
+    out
+}
+
+fn skipped_line(count: usize) -> DiffLine {
+    let text = match count {
+        1 => "1 unchanged line".to_string(),
+        n => format!("{} unchanged lines", n),
+    };
+    DiffLine {
+        tag: DiffTag::Skipped,
+        text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "use std::fmt;
+
+pub fn greet(name: &str) -> String {
+    let greeting = \"Hello\";
+    let a = 1;
+    let b = 2;
+    let c = 3;
+    let d = 4;
+    let e = 5;
+    format!(\"{}, {}!\", greeting, name)
+}
+
+pub fn same() {}
+";
+
+    const NEW: &str = "use std::fmt;
+
+/// Greets
+pub fn same() {}
+
+pub fn greet(name: &str) -> String {
+    let greeting = \"Hi\";
+    let a = 1;
+    let b = 2;
+    let c = 3;
+    let d = 4;
+    let e = 5;
+    format!(\"{}, {}!\", greeting, name)
+}
+
+pub fn added() {}
+";
+
+    #[test]
+    fn test_item_change() {
+        let file = Path::new("/p/src/lib.rs");
+        let items = RustAnalyzer::new()
+            .analyze_source_with_path(NEW, Some(file.to_path_buf()))
+            .unwrap();
+        let change = |name: &str| {
+            let item = items.iter().find(|i| i.name() == name).unwrap();
+            item_change(item, file, NEW, Some(OLD)).unwrap()
+        };
+
+        let ItemChange::Modified(lines) = change("greet") else {
+            panic!("greet changed");
+        };
+        let tagged: Vec<(DiffTag, &str)> = lines.iter().map(|l| (l.tag, l.text.as_str())).collect();
+        assert_eq!(
+            tagged,
+            vec![
+                (DiffTag::Context, "pub fn greet(name: &str) -> String {"),
+                (DiffTag::Removed, "    let greeting = \"Hello\";"),
+                (DiffTag::Added, "    let greeting = \"Hi\";"),
+                (DiffTag::Context, "    let a = 1;"),
+                (DiffTag::Context, "    let b = 2;"),
+                (DiffTag::Context, "    let c = 3;"),
+                (DiffTag::Skipped, "4 unchanged lines"),
+            ]
+        );
+        // Moved, and the doc comment is part of its span
+        assert!(matches!(change("same"), ItemChange::Modified(_)));
+        assert!(matches!(change("added"), ItemChange::Added(ref l) if l.len() == 1));
+        let item = items.iter().find(|i| i.name() == "added").unwrap();
+        assert!(matches!(
+            item_change(item, file, NEW, None),
+            Some(ItemChange::Added(_))
+        ));
+    }
+}
@@ -0,0 +1,261 @@
+//! Module paths of a crate's files, found by following `mod` declarations from its
+//! targets as rustc does
+//!
+//! A file's place in the module tree isn't always its place on disk: `#[path = ".."]`
+//! loads a module from anywhere, `mod foo;` can be `foo.rs` or `foo/mod.rs`, and each
+//! binary under `src/bin/` is a crate of its own. In a file with syntax errors, the
+//! declarations outside the broken items are still followed. Files no declaration
+//! reaches aren't in the layout; callers fall back to the directories they're in.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use syn::Item;
+
+use super::edition::Edition;
+use super::parser::item_chunks;
+
+/// Module path of each file reached from the targets under a source directory
+#[derive(Debug, Clone, Default)]
+pub struct ModuleLayout {
+    modules: HashMap<PathBuf, Vec<String>>,
+    /// Edition the files are read in
+    edition: Edition,
+}
+
+impl ModuleLayout {
+    /// Follow the `mod` declarations of the targets in `src`: `lib.rs` and `main.rs`
+    /// are the crate root, each `bin/<name>.rs` and `bin/<name>/main.rs` a root named
+    /// after its binary. Files are read as `edition` code.
+    pub fn scan(src: &Path, edition: Edition) -> Self {
+        let mut layout = Self {
+            edition,
+            ..Self::default()
+        };
+        for root in ["lib.rs", "main.rs"] {
+            layout.visit(&src.join(root), Vec::new(), true);
+        }
+        let Ok(entries) = std::fs::read_dir(src.join("bin")) else {
+            return layout;
+        };
+        let mut bins: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        bins.sort();
+        for bin in bins {
+            let (file, name) = if bin.is_dir() {
+                (bin.join("main.rs"), bin.file_name())
+            } else if bin.extension().is_some_and(|ext| ext == "rs") {
+                (bin.clone(), bin.file_stem())
+            } else {
+                continue;
+            };
+            let name = name.map(|n| n.to_string_lossy().into_owned());
+            layout.visit(&file, name.into_iter().collect(), true);
+        }
+        layout
+    }
+
+    /// Where `file` is in the module tree, if a declaration reaches it
+    pub fn module_path(&self, file: &Path) -> Option<&[String]> {
+        self.modules.get(&normalize(file)).map(Vec::as_slice)
+    }
+
+    /// Every file in the layout, sorted; `#[path]` can reach files outside `src`
+    pub fn files(&self) -> Vec<&Path> {
+        let mut files: Vec<&Path> = self.modules.keys().map(PathBuf::as_path).collect();
+        files.sort();
+        files
+    }
+
+    /// Record `file` as `module` and follow its declarations. In a `mod.rs`-like file
+    /// (crate roots, `mod.rs` and `#[path]` targets) child modules are looked up next
+    /// to it; in `foo.rs` they're under `foo/`.
+    fn visit(&mut self, file: &Path, module: Vec<String>, mod_rs: bool) {
+        let file = normalize(file);
+        if self.modules.contains_key(&file) {
+            return;
+        }
+        let Ok(source) = std::fs::read_to_string(&file) else {
+            return;
+        };
+        self.modules.insert(file.clone(), module.clone());
+        let source = self.edition.prepare(&source);
+        let items = match syn::parse_file(&source) {
+            Ok(ast) => ast.items,
+            Err(_) => recovered_items(&source),
+        };
+        let parent = file.parent().unwrap_or(Path::new("")).to_path_buf();
+        let dir = match file.file_stem() {
+            Some(stem) if !mod_rs => parent.join(stem),
+            _ => parent.clone(),
+        };
+        self.visit_items(&items, &parent, &dir, &module);
+    }
+
+    /// `mod` declarations among `items`, in `module`. `#[path]` is relative to
+    /// `path_base`; other files are found in `dir`.
+    fn visit_items(&mut self, items: &[Item], path_base: &Path, dir: &Path, module: &[String]) {
+        for item in items {
+            let Item::Mod(md) = item else {
+                continue;
+            };
+            let name = md.ident.to_string();
+            let mut child = module.to_vec();
+            child.push(name.clone());
+            let path_attr = path_attribute(&md.attrs);
+            match (&md.content, path_attr) {
+                (Some((_, content)), path_attr) => {
+                    // Inside an inline module both are its directory
+                    let inner = dir.join(path_attr.unwrap_or(name));
+                    self.visit_items(content, &inner, &inner, &child);
+                }
+                (None, Some(path)) => self.visit(&path_base.join(path), child, true),
+                (None, None) => {
+                    let flat = dir.join(format!("{}.rs", name));
+                    if flat.is_file() {
+                        self.visit(&flat, child, false);
+                    } else {
+                        self.visit(&dir.join(&name).join("mod.rs"), child, true);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The top-level items of a file that doesn't parse, each parsed on its own as
+/// [`RustAnalyzer::analyze_source_recovering`](super::RustAnalyzer::analyze_source_recovering)
+/// does; the broken ones are left out
+fn recovered_items(source: &str) -> Vec<Item> {
+    let lines: Vec<&str> = source.lines().collect();
+    item_chunks(source)
+        .into_iter()
+        .filter_map(|(start, end)| syn::parse_file(&lines[start - 1..end].join("\n")).ok())
+        .flat_map(|file| file.items)
+        .collect()
+}
+
+/// The value of `#[path = "..."]`
+fn path_attribute(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("path") {
+            return None;
+        }
+        match &attr.meta.require_name_value().ok()?.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        }
+    })
+}
+
+/// `path` with `.` and `dir/..` removed, so files reached through `#[path = "../x.rs"]`
+/// match the same files found by walking the directory
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(out.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_follows_declarations() {
+        let dir = std::env::temp_dir().join(format!("oracle-layout-{}", std::process::id()));
+        let src = dir.join("src");
+        for sub in ["net", "generated", "bin/tool", "platform", "inline"] {
+            std::fs::create_dir_all(src.join(sub)).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("gen")).unwrap();
+        let write = |path: &str, text: &str| std::fs::write(dir.join(path), text).unwrap();
+        write(
+            "src/lib.rs",
+            "mod net;\n#[path = \"platform/unix.rs\"]\nmod sys;\nmod inline { mod deep; }\n",
+        );
+        write(
+            "src/net.rs",
+            "mod tcp;\n#[path = \"../gen/api.rs\"]\nmod api;\n",
+        );
+        write("src/net/tcp.rs", "pub struct Stream;\n");
+        write("src/platform/unix.rs", "mod fd;\n");
+        write("src/platform/fd.rs", "");
+        write("src/inline/deep.rs", "");
+        write("gen/api.rs", "");
+        write("src/bin/tool/main.rs", "mod cli;\n");
+        write("src/bin/tool/cli.rs", "");
+        write("src/generated/orphan.rs", "");
+
+        let layout = ModuleLayout::scan(&src, Edition::default());
+        let path = |file: &str| layout.module_path(&dir.join(file)).map(|m| m.join("::"));
+        assert_eq!(path("src/lib.rs").as_deref(), Some(""));
+        assert_eq!(path("src/net/tcp.rs").as_deref(), Some("net::tcp"));
+        assert_eq!(path("src/platform/unix.rs").as_deref(), Some("sys"));
+        assert_eq!(path("src/platform/fd.rs").as_deref(), Some("sys::fd"));
+        assert_eq!(path("src/inline/deep.rs").as_deref(), Some("inline::deep"));
+        assert_eq!(path("gen/api.rs").as_deref(), Some("net::api"));
+        assert_eq!(path("src/bin/tool/cli.rs").as_deref(), Some("tool::cli"));
+        assert_eq!(path("src/generated/orphan.rs"), None);
+        assert!(layout.files().contains(&dir.join("gen/api.rs").as_path()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_layout_recovers_around_syntax_errors() {
+        let dir = std::env::temp_dir().join(format!("oracle-layout-broken-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src/net")).unwrap();
+        std::fs::write(
+            dir.join("src/lib.rs"),
+            "mod net;\n\nfn broken() {\n    let = 1;\n}\n\n#[path = \"sys_unix.rs\"]\nmod sys;\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("src/net.rs"), "mod tcp;\nfn also_broken( {\n").unwrap();
+        std::fs::write(dir.join("src/net/tcp.rs"), "").unwrap();
+        std::fs::write(dir.join("src/sys_unix.rs"), "").unwrap();
+
+        let layout = ModuleLayout::scan(&dir.join("src"), Edition::default());
+        let path = |file: &str| layout.module_path(&dir.join(file)).map(|m| m.join("::"));
+        assert_eq!(path("src/net/tcp.rs").as_deref(), Some("net::tcp"));
+        assert_eq!(path("src/sys_unix.rs").as_deref(), Some("sys"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_layout_reads_the_edition() {
+        let dir = std::env::temp_dir().join(format!("oracle-layout-2015-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("other")).unwrap();
+        // `async` is a name in 2015, and a syntax error in later editions
+        std::fs::write(
+            dir.join("src/lib.rs"),
+            "pub fn async() {}\n#[path = \"../other/weird.rs\"]\npub mod renamed;\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("other/weird.rs"), "pub struct Weird;\n").unwrap();
+
+        let layout = ModuleLayout::scan(&dir.join("src"), Edition::E2015);
+        let weird = dir.join("other/weird.rs");
+        assert_eq!(
+            layout.module_path(&weird),
+            Some(&["renamed".to_string()][..])
+        );
+
+        let analyzer = crate::analyzer::RustAnalyzer::new().with_edition(Edition::E2015);
+        let analysis = crate::report::analyze_sources(&analyzer, &dir).unwrap();
+        let names: Vec<String> = analysis.items.iter().map(|i| i.qualified_name()).collect();
+        assert!(names.contains(&"renamed::Weird".to_string()), "{:?}", names);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+//! Lifetimes in function signatures
+//!
+//! Re-parses the parameter and return types of a [`FunctionInfo`] and applies the
+//! lifetime elision rules to work out what a returned reference borrows from. This
+//! is best-effort: lifetimes hidden in a path (`Ref<T>` for a `struct Ref<'a, T>`)
+//! can't be seen without resolving the type, and closures' `Fn(&T)` arguments are
+//! higher-ranked, so neither is counted.
+
+use super::types::FunctionInfo;
+use syn::{GenericArgument, PathArguments, Type, TypeParamBound};
+
+/// Lifetime positions of one parameter (or of the return type)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LifetimeUse {
+    /// Named lifetimes, in order of appearance (`'a`, `'static`)
+    pub named: Vec<String>,
+    /// References and `'_` without a name
+    pub elided: usize,
+}
+
+impl LifetimeUse {
+    pub fn is_empty(&self) -> bool {
+        self.named.is_empty() && self.elided == 0
+    }
+
+    fn of_type(ty: &str) -> Self {
+        let mut uses = Self::default();
+        if let Ok(ty) = syn::parse_str::<Type>(ty) {
+            uses.walk(&ty);
+        }
+        uses
+    }
+
+    fn lifetime(&mut self, lifetime: Option<&syn::Lifetime>) {
+        match lifetime {
+            Some(lt) if lt.ident != "_" => self.named.push(format!("'{}", lt.ident)),
+            _ => self.elided += 1,
+        }
+    }
+
+    fn walk(&mut self, ty: &Type) {
+        match ty {
+            Type::Reference(r) => {
+                self.lifetime(r.lifetime.as_ref());
+                self.walk(&r.elem);
+            }
+            Type::Path(p) => {
+                if let Some(ref qself) = p.qself {
+                    self.walk(&qself.ty);
+                }
+                for segment in &p.path.segments {
+                    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                        for arg in &args.args {
+                            match arg {
+                                GenericArgument::Lifetime(lt) => self.lifetime(Some(lt)),
+                                GenericArgument::Type(ty) => self.walk(ty),
+                                GenericArgument::AssocType(assoc) => self.walk(&assoc.ty),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+            Type::TraitObject(t) => self.bounds(t.bounds.iter()),
+            Type::ImplTrait(t) => self.bounds(t.bounds.iter()),
+            Type::Slice(s) => self.walk(&s.elem),
+            Type::Array(a) => self.walk(&a.elem),
+            Type::Ptr(p) => self.walk(&p.elem),
+            Type::Paren(p) => self.walk(&p.elem),
+            Type::Group(g) => self.walk(&g.elem),
+            Type::Tuple(t) => t.elems.iter().for_each(|ty| self.walk(ty)),
+            _ => {}
+        }
+    }
+
+    fn bounds<'b>(&mut self, bounds: impl Iterator<Item = &'b TypeParamBound>) {
+        for bound in bounds {
+            match bound {
+                TypeParamBound::Lifetime(lt) => self.lifetime(Some(lt)),
+                TypeParamBound::Trait(t) => self.walk(&Type::Path(syn::TypePath {
+                    qself: None,
+                    path: t.path.clone(),
+                })),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Where a returned borrow comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BorrowSource {
+    /// The return type holds no lifetimes
+    Owned,
+    /// Only `'static`
+    Static,
+    /// `&self` / `&mut self` (third elision rule)
+    SelfRef,
+    /// These parameters, by name
+    Params(Vec<String>),
+    /// Elided output lifetime with several input lifetimes and no `&self`
+    /// (`rustc` asks for an annotation)
+    Ambiguous,
+    /// A named lifetime no parameter mentions
+    Unknown,
+}
+
+/// Lifetimes of a function signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifetimeSummary {
+    /// Lifetime parameters in `<..>`
+    pub declared: Vec<String>,
+    /// `(parameter name, its lifetimes)` for parameters that have any
+    pub params: Vec<(String, LifetimeUse)>,
+    pub output: LifetimeUse,
+    pub borrows_from: BorrowSource,
+}
+
+impl LifetimeSummary {
+    pub fn of(func: &FunctionInfo) -> Self {
+        let declared = func
+            .generics_info
+            .lifetimes()
+            .map(|p| p.name.clone())
+            .collect();
+
+        let self_ref = func.parameters.iter().any(|p| p.is_self && p.is_ref);
+        let params: Vec<(String, LifetimeUse)> = func
+            .parameters
+            .iter()
+            .map(|p| {
+                let uses = if p.is_self {
+                    LifetimeUse {
+                        named: Vec::new(),
+                        elided: usize::from(p.is_ref),
+                    }
+                } else {
+                    LifetimeUse::of_type(&p.ty)
+                };
+                (p.name.clone(), uses)
+            })
+            .filter(|(_, uses)| !uses.is_empty())
+            .collect();
+        let output = func
+            .return_type
+            .as_deref()
+            .map(LifetimeUse::of_type)
+            .unwrap_or_default();
+
+        let borrows_from = Self::borrow_source(&params, &output, self_ref);
+        Self {
+            declared,
+            params,
+            output,
+            borrows_from,
+        }
+    }
+
+    fn borrow_source(
+        params: &[(String, LifetimeUse)],
+        output: &LifetimeUse,
+        self_ref: bool,
+    ) -> BorrowSource {
+        if output.is_empty() {
+            return BorrowSource::Owned;
+        }
+        let mut sources: Vec<String> = Vec::new();
+        if output.elided > 0 {
+            let input_positions: usize = params.iter().map(|(_, u)| u.named.len() + u.elided).sum();
+            if self_ref {
+                sources.push("self".to_string());
+            } else if input_positions == 1 {
+                sources.push(params[0].0.clone());
+            } else {
+                return BorrowSource::Ambiguous;
+            }
+        }
+        let mut unmatched = false;
+        for lifetime in output.named.iter().filter(|lt| *lt != "'static") {
+            let mut found = false;
+            for (name, _) in params.iter().filter(|(_, u)| u.named.contains(lifetime)) {
+                found = true;
+                if !sources.contains(name) {
+                    sources.push(name.clone());
+                }
+            }
+            unmatched |= !found;
+        }
+        match sources.as_slice() {
+            [] if unmatched => BorrowSource::Unknown,
+            [] => BorrowSource::Static,
+            [only] if only == "self" => BorrowSource::SelfRef,
+            _ => BorrowSource::Params(sources),
+        }
+    }
+
+    /// Positions where a lifetime was left out, as `param: n` (`->` for the return type)
+    pub fn elided_positions(&self) -> Vec<(String, usize)> {
+        self.params
+            .iter()
+            .map(|(name, uses)| (name.clone(), uses.elided))
+            .chain(std::iter::once(("->".to_string(), self.output.elided)))
+            .filter(|(_, n)| *n > 0)
+            .collect()
+    }
+
+    pub fn returns_borrow(&self) -> bool {
+        !matches!(
+            self.borrows_from,
+            BorrowSource::Owned | BorrowSource::Static
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::{AnalyzedItem, RustAnalyzer};
+
+    fn summary(source: &str) -> LifetimeSummary {
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        let Some(AnalyzedItem::Function(f)) = items.first() else {
+            panic!("expected a function");
+        };
+        LifetimeSummary::of(f)
+    }
+
+    #[test]
+    fn test_elision_rules() {
+        let one = summary("fn first(words: &[String]) -> &str { todo!() }");
+        assert_eq!(one.borrows_from, BorrowSource::Params(vec!["words".into()]));
+        assert_eq!(
+            one.elided_positions(),
+            vec![("words".to_string(), 1), ("->".to_string(), 1)]
+        );
+
+        let two = summary("fn pick(a: &str, b: &str) -> &str { a }");
+        assert_eq!(two.borrows_from, BorrowSource::Ambiguous);
+
+        let owned = summary("fn len(s: &str) -> usize { 0 }");
+        assert_eq!(owned.borrows_from, BorrowSource::Owned);
+        assert!(!owned.returns_borrow());
+
+        let fixed = summary("fn name() -> &'static str { \"\" }");
+        assert_eq!(fixed.borrows_from, BorrowSource::Static);
+    }
+
+    #[test]
+    fn test_named_lifetimes() {
+        let s = summary(
+            "fn longest<'a, 'b>(x: &'a str, y: &'a str, z: Cow<'b, str>) -> Option<&'a str> { None }",
+        );
+        assert_eq!(s.declared, vec!["'a", "'b"]);
+        assert_eq!(s.params.len(), 3);
+        assert_eq!(s.params[2].1.named, vec!["'b"]);
+        assert_eq!(
+            s.borrows_from,
+            BorrowSource::Params(vec!["x".into(), "y".into()])
+        );
+        assert!(s.returns_borrow());
+
+        let fmt = summary("fn show(f: &mut Formatter<'_>) -> Box<dyn Display + '_> { todo!() }");
+        assert_eq!(fmt.params[0].1.elided, 2);
+        assert_eq!(fmt.borrows_from, BorrowSource::Ambiguous);
+    }
+}
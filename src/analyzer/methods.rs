@@ -0,0 +1,265 @@
+//! Every method callable on a type, like rustdoc's "Methods" section
+//!
+//! Inherent impl methods, methods of trait impls, and the provided (default)
+//! methods of the crate's own traits the impl doesn't override. Traits from other
+//! crates only contribute the methods their impl spells out, since their
+//! definitions aren't analyzed.
+
+use super::types::{AnalyzedItem, FunctionInfo, Parameter, TraitInfo};
+use super::usage::base_type_name;
+
+/// Where a method comes from
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MethodOrigin {
+    Inherent,
+    /// Defined in `impl Trait for Type`
+    Trait(String),
+    /// Default body of a trait method the impl leaves alone
+    Provided(String),
+}
+
+impl MethodOrigin {
+    /// Column text: `inherent`, `impl Display`, `Iterator (provided)`
+    pub fn label(&self) -> String {
+        match self {
+            Self::Inherent => "inherent".to_string(),
+            Self::Trait(name) => format!("impl {}", name),
+            Self::Provided(name) => format!("{} (provided)", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodEntry {
+    pub name: String,
+    /// `&self`, `&mut self`, `self`, or empty for associated functions
+    pub receiver: String,
+    /// Signature without the leading qualifiers and `fn`
+    pub signature: String,
+    pub origin: MethodOrigin,
+}
+
+/// Order of the method table, cycled from the inspector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MethodSort {
+    /// Inherent first, then trait by trait (rustdoc order)
+    #[default]
+    Origin,
+    Name,
+    /// Associated functions, then `&self`, `&mut self`, `self`
+    Receiver,
+}
+
+impl MethodSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Origin => Self::Name,
+            Self::Name => Self::Receiver,
+            Self::Receiver => Self::Origin,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Origin => "origin",
+            Self::Name => "name",
+            Self::Receiver => "receiver",
+        }
+    }
+}
+
+/// All methods of the type called `type_name`, ordered by `sort`
+pub fn method_table(type_name: &str, items: &[AnalyzedItem], sort: MethodSort) -> Vec<MethodEntry> {
+    let mut methods = Vec::new();
+    for item in items {
+        let AnalyzedItem::Impl(im) = item else {
+            continue;
+        };
+        if im.is_negative || base_type_name(&im.self_ty) != Some(type_name) {
+            continue;
+        }
+        let trait_name = im.trait_name.as_deref().map(trait_display);
+        let origin = match trait_name {
+            Some(ref t) => MethodOrigin::Trait(t.clone()),
+            None => MethodOrigin::Inherent,
+        };
+        for method in &im.methods {
+            methods.push(MethodEntry {
+                name: method.name.clone(),
+                receiver: receiver(&method.parameters),
+                signature: short_signature(method),
+                origin: origin.clone(),
+            });
+        }
+        let Some(trait_name) = trait_name else {
+            continue;
+        };
+        if let Some(tr) = find_trait(items, &trait_name) {
+            for provided in tr.methods.iter().filter(|m| m.has_default) {
+                if im.methods.iter().any(|m| m.name == provided.name) {
+                    continue;
+                }
+                let signature = provided
+                    .signature
+                    .split_once("fn ")
+                    .map_or(provided.signature.as_str(), |(_, rest)| rest);
+                methods.push(MethodEntry {
+                    name: provided.name.clone(),
+                    receiver: signature_receiver(signature),
+                    signature: signature.to_string(),
+                    origin: MethodOrigin::Provided(trait_name.clone()),
+                });
+            }
+        }
+    }
+    sort_methods(&mut methods, sort);
+    methods
+}
+
+pub fn sort_methods(methods: &mut [MethodEntry], sort: MethodSort) {
+    match sort {
+        MethodSort::Origin => {
+            methods.sort_by(|a, b| a.origin.cmp(&b.origin).then_with(|| a.name.cmp(&b.name)))
+        }
+        MethodSort::Name => {
+            methods.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.origin.cmp(&b.origin)))
+        }
+        MethodSort::Receiver => {
+            let rank = |r: &str| match r {
+                "" => 0,
+                "&self" => 1,
+                "&mut self" => 2,
+                _ => 3,
+            };
+            methods.sort_by(|a, b| {
+                rank(&a.receiver)
+                    .cmp(&rank(&b.receiver))
+                    .then_with(|| a.name.cmp(&b.name))
+            })
+        }
+    }
+}
+
+/// `Display`, `Iterator`, `From<u8>` (path prefix dropped)
+fn trait_display(path: &str) -> String {
+    let (head, args) = match path.find('<') {
+        Some(i) => path.split_at(i),
+        None => (path, ""),
+    };
+    let base = head.rsplit("::").next().unwrap_or(head);
+    format!("{}{}", base, args)
+}
+
+fn find_trait<'a>(items: &'a [AnalyzedItem], name: &str) -> Option<&'a TraitInfo> {
+    let base = name.split('<').next().unwrap_or(name);
+    items.iter().find_map(|item| match item {
+        AnalyzedItem::Trait(t) if t.name == base => Some(t),
+        _ => None,
+    })
+}
+
+fn receiver(params: &[Parameter]) -> String {
+    params
+        .first()
+        .filter(|p| p.is_self)
+        .map(|p| p.to_string())
+        .unwrap_or_default()
+}
+
+/// Receiver from a signature text such as `next(&mut self) -> Option<u8>`
+fn signature_receiver(signature: &str) -> String {
+    let args = signature
+        .split_once('(')
+        .map_or("", |(_, rest)| rest.split([',', ')']).next().unwrap_or(""));
+    let args = args.trim();
+    if args.ends_with("self") {
+        args.to_string()
+    } else {
+        String::new()
+    }
+}
+
+fn short_signature(method: &FunctionInfo) -> String {
+    method
+        .signature
+        .split_once("fn ")
+        .map_or(method.signature.as_str(), |(_, rest)| rest)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    const SOURCE: &str = r#"
+        pub struct Counter { n: u32 }
+        impl Counter {
+            pub fn new() -> Self { todo!() }
+            pub fn reset(&mut self) {}
+        }
+        pub trait Describe {
+            fn name(&self) -> String;
+            fn describe(&self) -> String { self.name() }
+            fn shout(&self) -> String { todo!() }
+        }
+        impl Describe for Counter {
+            fn name(&self) -> String { todo!() }
+            fn shout(&self) -> String { todo!() }
+        }
+        impl std::fmt::Display for Counter {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { todo!() }
+        }
+        impl Counter {
+            fn bump(self) -> Self { self }
+        }
+    "#;
+
+    fn table(sort: MethodSort) -> Vec<MethodEntry> {
+        let items = RustAnalyzer::new().analyze_source(SOURCE).unwrap();
+        method_table("Counter", &items, sort)
+    }
+
+    #[test]
+    fn test_method_table_by_origin() {
+        let methods = table(MethodSort::Origin);
+        let rows: Vec<(&str, String)> = methods
+            .iter()
+            .map(|m| (m.name.as_str(), m.origin.label()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("bump", "inherent".to_string()),
+                ("new", "inherent".to_string()),
+                ("reset", "inherent".to_string()),
+                ("name", "impl Describe".to_string()),
+                ("shout", "impl Describe".to_string()),
+                ("fmt", "impl Display".to_string()),
+                ("describe", "Describe (provided)".to_string()),
+            ]
+        );
+        assert_eq!(methods[2].receiver, "&mut self");
+        assert_eq!(methods[2].signature, "reset(&mut self)");
+        assert_eq!(methods[6].receiver, "&self");
+    }
+
+    #[test]
+    fn test_method_table_sorts() {
+        let by_name: Vec<String> = table(MethodSort::Name)
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(
+            by_name,
+            vec!["bump", "describe", "fmt", "name", "new", "reset", "shout"]
+        );
+        let by_receiver: Vec<String> = table(MethodSort::Receiver)
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(by_receiver[0], "new");
+        assert_eq!(by_receiver.last().unwrap(), "bump");
+        assert_eq!(MethodSort::Receiver.next(), MethodSort::Origin);
+    }
+}
@@ -1,11 +1,83 @@
 //! Rust code analyzer module
 
+pub mod allocations;
+pub mod c_header;
+pub mod cfg;
+pub mod compare;
+pub mod const_eval;
+pub mod constructors;
+pub mod coverage;
+pub mod dead_code;
 pub mod dependency;
+pub mod doc_links;
+pub mod doctest;
+pub mod duplicates;
+pub mod edition;
+pub mod enum_layout;
+pub mod env_vars;
+pub mod errors;
+pub mod features;
+pub mod ffi;
+pub mod files;
+pub mod history;
+pub mod imports;
+pub mod item_diff;
+pub mod layout;
+pub mod lifetimes;
+pub mod methods;
+pub mod module_tree;
+pub mod msrv;
+pub mod no_std;
+pub mod notes;
+pub mod object_safety;
+pub mod panics;
 pub mod parser;
+pub mod pretty;
+pub mod proc_macros;
+pub mod query;
 pub mod registry;
+pub mod spelling;
+pub mod thread_safety;
 pub mod types;
+pub mod usage;
+pub mod wasm;
 
+pub use allocations::{AllocKind, Allocation, Hotspot};
+pub use c_header::{c_declaration, c_header};
+pub use cfg::{Cfg, CfgGroup, CfgTarget, FeatureSet};
+pub use compare::{diff_status, find_counterpart, DiffStatus};
+pub use const_eval::ConstTable;
+pub use constructors::{constructors, Constructor, ConstructorKind};
+pub use coverage::{Coverage, CoverageData};
+pub use dead_code::{find_unused, Confidence, UnusedItem};
 pub use dependency::{CrateInfo, DependencyAnalyzer, DependencyInfo, DependencyKind};
-pub use parser::RustAnalyzer;
+pub use doc_links::{apply_url_statuses, check_links, DocLink, LinkStatus};
+pub use doctest::{extract_doc_examples, DocExample};
+pub use duplicates::{DuplicateCrate, DuplicateVersion};
+pub use edition::{Edition, EditionIssue};
+pub use enum_layout::{Discriminant, EnumLayout, Repr};
+pub use env_vars::{EnvRead, EnvReadKind, EnvVar};
+pub use errors::{ErrorCatalog, ErrorType};
+pub use features::FeatureUnification;
+pub use ffi::{FfiFunction, FfiSurface, FfiType};
+pub use files::{FileMetrics, FileSort};
+pub use imports::{ImportGraph, ModuleImport};
+pub use item_diff::{DiffLine, DiffTag, ItemChange};
+pub use layout::ModuleLayout;
+pub use lifetimes::{BorrowSource, LifetimeSummary, LifetimeUse};
+pub use methods::{method_table, MethodEntry, MethodOrigin, MethodSort};
+pub use module_tree::{ModuleNode, ModuleTree, TreeRow};
+pub use msrv::{MsrvEntry, MsrvReport};
+pub use no_std::{NoStdAttr, NoStdDependency, NoStdReport, NoStdSupport, StdImport};
+pub use notes::{Note, NoteTag};
+pub use object_safety::ObjectSafety;
+pub use panics::{PanicKind, PanicPoints};
+pub use parser::{FileItems, ParseFailure, RustAnalyzer};
+pub use proc_macros::{ProcMacro, ProcMacroKind};
+pub use query::{Filter, Query};
 pub use registry::{CrateRegistry, InstalledCrate};
+pub use spelling::{check_spelling, Misspelling, SpellChecker};
+pub use thread_safety::{AsyncAudit, AsyncFn, AutoTraits, ThreadSafety, TypeVerdict, Verdict};
 pub use types::*;
+pub use usage::{TypeUsage, TypeUsageIndex, UsageKind};
+pub use wasm::{Outlook, WasmImpact, WasmIssue, WasmScan, WasmTarget};
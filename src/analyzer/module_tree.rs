@@ -0,0 +1,169 @@
+//! Module hierarchy of analyzed items, mapped to source files
+//!
+//! Built from each item's `module_path`; a node's file is the file its items were
+//! parsed from, so `mod foo;` declarations resolve to `foo.rs` / `foo/mod.rs`.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::types::AnalyzedItem;
+
+/// One module in the tree
+#[derive(Debug, Clone, Default)]
+pub struct ModuleNode {
+    pub name: String,
+    /// Full module path from the crate root (empty for the root)
+    pub path: Vec<String>,
+    pub file: Option<PathBuf>,
+    /// Items declared directly in this module
+    pub item_count: usize,
+    /// Items in this module and all descendants
+    pub total_count: usize,
+    pub children: Vec<ModuleNode>,
+}
+
+impl ModuleNode {
+    fn child_mut(&mut self, name: &str) -> &mut ModuleNode {
+        if let Some(i) = self.children.iter().position(|c| c.name == name) {
+            return &mut self.children[i];
+        }
+        let mut path = self.path.clone();
+        path.push(name.to_string());
+        self.children.push(ModuleNode {
+            name: name.to_string(),
+            path,
+            ..Default::default()
+        });
+        self.children.last_mut().expect("just pushed")
+    }
+
+    fn finish(&mut self) -> usize {
+        self.children.sort_by(|a, b| a.name.cmp(&b.name));
+        let nested: usize = self.children.iter_mut().map(ModuleNode::finish).sum();
+        self.total_count = self.item_count + nested;
+        self.total_count
+    }
+}
+
+/// A visible row of the flattened tree
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    pub path: Vec<String>,
+    pub name: String,
+    pub depth: usize,
+    pub file: Option<PathBuf>,
+    pub item_count: usize,
+    pub total_count: usize,
+    pub has_children: bool,
+    pub expanded: bool,
+}
+
+/// Crate module tree
+#[derive(Debug, Clone, Default)]
+pub struct ModuleTree {
+    pub root: ModuleNode,
+}
+
+impl ModuleTree {
+    pub fn build(items: &[AnalyzedItem], root_name: &str) -> Self {
+        let mut root = ModuleNode {
+            name: root_name.to_string(),
+            ..Default::default()
+        };
+        for item in items {
+            let mut node = &mut root;
+            for segment in item.module_path() {
+                node = node.child_mut(segment);
+            }
+            node.item_count += 1;
+            if node.file.is_none() {
+                node.file = item.source_location().and_then(|l| l.file.clone());
+            }
+            if let AnalyzedItem::Module(m) = item {
+                // Make sure empty modules still show up
+                node.child_mut(&m.name);
+            }
+        }
+        root.finish();
+        Self { root }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.total_count == 0
+    }
+
+    /// Flatten into visible rows, skipping children of collapsed nodes
+    pub fn rows(&self, collapsed: &HashSet<Vec<String>>) -> Vec<TreeRow> {
+        fn walk(
+            node: &ModuleNode,
+            depth: usize,
+            collapsed: &HashSet<Vec<String>>,
+            out: &mut Vec<TreeRow>,
+        ) {
+            let expanded = !collapsed.contains(&node.path);
+            out.push(TreeRow {
+                path: node.path.clone(),
+                name: node.name.clone(),
+                depth,
+                file: node.file.clone(),
+                item_count: node.item_count,
+                total_count: node.total_count,
+                has_children: !node.children.is_empty(),
+                expanded,
+            });
+            if expanded {
+                for child in &node.children {
+                    walk(child, depth + 1, collapsed, out);
+                }
+            }
+        }
+        let mut out = Vec::new();
+        walk(&self.root, 0, collapsed, &mut out);
+        out
+    }
+}
+
+/// Module path an item belongs to for scoping purposes; a module item belongs to itself
+pub fn scope_path(item: &AnalyzedItem) -> Vec<String> {
+    let mut path = item.module_path().to_vec();
+    if let AnalyzedItem::Module(m) = item {
+        path.push(m.name.clone());
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+    use std::path::Path;
+
+    #[test]
+    fn test_build_tree_with_counts_and_files() {
+        let analyzer = RustAnalyzer::new();
+        let mut items = analyzer
+            .analyze_source_with_path("pub mod ui; pub fn main() {}", Some("src/main.rs".into()))
+            .unwrap();
+        items.extend(
+            analyzer
+                .analyze_source_with_path(
+                    "pub struct App; pub fn run() {}",
+                    Some("src/ui/app.rs".into()),
+                )
+                .unwrap(),
+        );
+
+        let tree = ModuleTree::build(&items, "demo");
+        assert_eq!(tree.root.total_count, 4);
+        assert_eq!(tree.root.item_count, 2);
+
+        let rows = tree.rows(&HashSet::new());
+        let names: Vec<(&str, usize)> = rows.iter().map(|r| (r.name.as_str(), r.depth)).collect();
+        assert_eq!(names, vec![("demo", 0), ("ui", 1), ("app", 2)]);
+        assert_eq!(rows[2].item_count, 2);
+        assert_eq!(rows[2].file.as_deref(), Some(Path::new("src/ui/app.rs")));
+
+        let collapsed: HashSet<Vec<String>> = [vec!["ui".to_string()]].into_iter().collect();
+        assert_eq!(tree.rows(&collapsed).len(), 2);
+    }
+}
@@ -0,0 +1,121 @@
+//! Minimum supported Rust version (MSRV) across the dependency graph
+//!
+//! A project can only build on toolchains at least as new as the highest
+//! `rust-version` declared by any of its dependencies, so that maximum is the
+//! effective MSRV bound regardless of what the project itself declares.
+
+use std::cmp::Ordering;
+
+/// `rust-version` of a single dependency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsrvEntry {
+    pub name: String,
+    pub version: String,
+    pub rust_version: String,
+}
+
+/// Aggregated MSRV information for a project
+#[derive(Debug, Clone, Default)]
+pub struct MsrvReport {
+    /// The project's own declared `rust-version`
+    pub declared: Option<String>,
+    /// Dependencies declaring a `rust-version`, highest first
+    pub entries: Vec<MsrvEntry>,
+    /// Dependencies without a `rust-version`
+    pub undeclared: usize,
+}
+
+impl MsrvReport {
+    /// Build from the project's declared MSRV and `(name, version, rust-version)` per dependency
+    pub fn build<I>(declared: Option<String>, deps: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String, Option<String>)>,
+    {
+        let mut entries = Vec::new();
+        let mut undeclared = 0;
+        for (name, version, rust_version) in deps {
+            match rust_version {
+                Some(rust_version) => entries.push(MsrvEntry {
+                    name,
+                    version,
+                    rust_version,
+                }),
+                None => undeclared += 1,
+            }
+        }
+        entries.sort_by(|a, b| {
+            compare_rust_versions(&b.rust_version, &a.rust_version)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Self {
+            declared,
+            entries,
+            undeclared,
+        }
+    }
+
+    /// Highest dependency `rust-version` (the effective lower bound on the toolchain)
+    pub fn effective(&self) -> Option<&MsrvEntry> {
+        self.entries.first()
+    }
+
+    /// Dependencies requiring a newer toolchain than the project's declared `rust-version`
+    pub fn exceeding(&self) -> Vec<&MsrvEntry> {
+        let Some(declared) = self.declared.as_deref() else {
+            return Vec::new();
+        };
+        self.entries
+            .iter()
+            .filter(|e| compare_rust_versions(&e.rust_version, declared) == Ordering::Greater)
+            .collect()
+    }
+}
+
+/// Compare `rust-version` strings numerically; missing components count as 0 (`1.70` == `1.70.0`)
+pub fn compare_rust_versions(a: &str, b: &str) -> Ordering {
+    fn parts(v: &str) -> [u64; 3] {
+        let mut out = [0; 3];
+        for (slot, part) in out.iter_mut().zip(v.trim().split('.')) {
+            *slot = part
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+                .parse()
+                .unwrap_or(0);
+        }
+        out
+    }
+    parts(a).cmp(&parts(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(name: &str, msrv: Option<&str>) -> (String, String, Option<String>) {
+        (name.into(), "1.0.0".into(), msrv.map(String::from))
+    }
+
+    #[test]
+    fn test_msrv_report() {
+        let report = MsrvReport::build(
+            Some("1.70".into()),
+            vec![
+                dep("serde", Some("1.61")),
+                dep("ratatui", Some("1.74.0")),
+                dep("syn", Some("1.70.0")),
+                dep("libc", None),
+            ],
+        );
+        assert_eq!(report.effective().unwrap().name, "ratatui");
+        assert_eq!(report.undeclared, 1);
+        let exceeding: Vec<&str> = report.exceeding().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(exceeding, vec!["ratatui"]);
+    }
+
+    #[test]
+    fn test_compare_rust_versions() {
+        assert_eq!(compare_rust_versions("1.70", "1.70.0"), Ordering::Equal);
+        assert_eq!(compare_rust_versions("1.9", "1.10"), Ordering::Less);
+    }
+}
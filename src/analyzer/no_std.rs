@@ -0,0 +1,299 @@
+//! `no_std` compatibility of the crate and its dependencies
+//!
+//! A crate supports `no_std` when its root has `#![no_std]`, unconditionally or
+//! behind a `cfg_attr` (usually a `std` feature being off). Dependencies are judged
+//! the same way from their library's root file, plus the `no-std` crates.io
+//! category. The project's `use std::..` declarations are matched against the parts
+//! of std that are re-exports of `core` or `alloc`; the rest only exist with std.
+
+use std::path::PathBuf;
+
+use syn::punctuated::Punctuated;
+use syn::{Meta, Token};
+
+use super::imports::ModuleImport;
+
+/// How a crate root opts out of std
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoStdAttr {
+    /// `#![no_std]`
+    Always,
+    /// `#![cfg_attr(<cfg>, no_std)]`, with the `cfg`
+    When(String),
+}
+
+impl NoStdAttr {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Always => "#![no_std]".to_string(),
+            Self::When(cfg) => format!("#![cfg_attr({}, no_std)]", cfg),
+        }
+    }
+}
+
+/// The `#![no_std]` (or `cfg_attr`'d one) among the inner attributes of a crate root
+pub fn crate_attr(source: &str) -> Option<NoStdAttr> {
+    for (start, _) in source.match_indices("#![") {
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        if !source[line_start..start].trim().is_empty() {
+            continue;
+        }
+        // The attribute runs to the `]` closing the `#![`
+        let body = &source[start + 3..];
+        let mut depth = 0;
+        let Some(end) = body.find(|c| {
+            match c {
+                '[' | '(' => depth += 1,
+                ')' => depth -= 1,
+                ']' if depth == 0 => return true,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            false
+        }) else {
+            continue;
+        };
+        match syn::parse_str::<Meta>(&body[..end]) {
+            Ok(Meta::Path(p)) if p.is_ident("no_std") => return Some(NoStdAttr::Always),
+            Ok(Meta::List(list)) if list.path.is_ident("cfg_attr") => {
+                let Ok(args) =
+                    list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                else {
+                    continue;
+                };
+                let mut args = args.into_iter();
+                let cfg = args.next();
+                if let (Some(cfg), true) = (cfg, args.any(|m| m.path().is_ident("no_std"))) {
+                    let cfg = quote::ToTokens::to_token_stream(&cfg)
+                        .to_string()
+                        .replace(" (", "(");
+                    return Some(NoStdAttr::When(cfg));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether a dependency can be built without std
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoStdSupport {
+    /// `#![no_std]`
+    Always,
+    /// `#![cfg_attr(.., no_std)]` or the `no-std` category
+    Optional,
+    RequiresStd,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoStdDependency {
+    pub name: String,
+    pub version: String,
+    pub support: NoStdSupport,
+    /// The crate has a `std` feature and it is on in the resolved graph
+    pub std_enabled: bool,
+    /// Direct dependency that pulls it in (itself for direct ones)
+    pub direct: String,
+}
+
+impl NoStdDependency {
+    /// Rules out a `no_std` build: it needs std, or std is switched on for it
+    pub fn needs_std(&self) -> bool {
+        match self.support {
+            NoStdSupport::Always => false,
+            NoStdSupport::Optional => self.std_enabled,
+            NoStdSupport::RequiresStd => true,
+        }
+    }
+}
+
+/// A `use std::..` of the project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StdImport {
+    pub path: String,
+    /// `core::..` or `alloc::..` path to use instead; `None` when std-only
+    pub replacement: Option<String>,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NoStdReport {
+    /// The project's own `no_std` attribute
+    pub attr: Option<NoStdAttr>,
+    /// Dependencies needing std first, then by name
+    pub dependencies: Vec<NoStdDependency>,
+    pub std_imports: Vec<StdImport>,
+}
+
+impl NoStdReport {
+    pub fn build(
+        attr: Option<NoStdAttr>,
+        mut dependencies: Vec<NoStdDependency>,
+        imports: &[ModuleImport],
+    ) -> Self {
+        dependencies.sort_by(|a, b| {
+            b.needs_std()
+                .cmp(&a.needs_std())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let std_imports = imports
+            .iter()
+            .filter(|i| i.bare && i.path.first().is_some_and(|p| p == "std"))
+            .map(|i| StdImport {
+                path: i.path.join("::"),
+                replacement: replacement(&i.path[1..]),
+                file: i.file.clone(),
+                line: i.line,
+            })
+            .collect();
+        Self {
+            attr,
+            dependencies,
+            std_imports,
+        }
+    }
+
+    pub fn needing_std(&self) -> impl Iterator<Item = &NoStdDependency> {
+        self.dependencies.iter().filter(|d| d.needs_std())
+    }
+
+    /// Imports with no `core` / `alloc` equivalent
+    pub fn std_only(&self) -> impl Iterator<Item = &StdImport> {
+        self.std_imports.iter().filter(|i| i.replacement.is_none())
+    }
+}
+
+/// Std modules that are re-exports of `core`
+const CORE_MODULES: &[&str] = &[
+    "any",
+    "array",
+    "ascii",
+    "borrow",
+    "cell",
+    "char",
+    "clone",
+    "cmp",
+    "convert",
+    "default",
+    "error",
+    "f32",
+    "f64",
+    "fmt",
+    "future",
+    "hash",
+    "hint",
+    "iter",
+    "marker",
+    "mem",
+    "num",
+    "ops",
+    "option",
+    "panic",
+    "pin",
+    "primitive",
+    "ptr",
+    "result",
+    "slice",
+    "str",
+    "task",
+];
+
+/// Std modules that are re-exports of `alloc`
+const ALLOC_MODULES: &[&str] = &["boxed", "format", "rc", "string", "vec"];
+
+/// `core::` / `alloc::` equivalent of `std::<path>`
+fn replacement(path: &[String]) -> Option<String> {
+    let rest = path.join("::");
+    let module = path.first()?.as_str();
+    let item = path.get(1).map(String::as_str);
+    let krate = match (module, item) {
+        ("collections", Some("HashMap" | "HashSet" | "hash_map" | "hash_set")) => return None,
+        ("collections", _) => "alloc",
+        ("sync", Some("Arc" | "Weak")) => "alloc",
+        ("sync", Some("atomic")) => "core",
+        ("time", Some("Duration")) => "core",
+        ("borrow", Some("Cow" | "ToOwned")) => "alloc",
+        ("ffi", Some("CString")) => "alloc",
+        ("ffi", Some("OsStr" | "OsString")) => return None,
+        ("ffi", _) => "core",
+        ("hash", Some("RandomState" | "DefaultHasher")) => return None,
+        _ if ALLOC_MODULES.contains(&module) => "alloc",
+        _ if CORE_MODULES.contains(&module) => "core",
+        _ => return None,
+    };
+    Some(format!("{}::{}", krate, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::imports;
+    use std::path::Path;
+
+    #[test]
+    fn test_crate_attr() {
+        assert_eq!(
+            crate_attr("//! Docs with #![no_std] in them\n#![no_std]\n"),
+            Some(NoStdAttr::Always)
+        );
+        assert_eq!(
+            crate_attr("#![warn(missing_docs)]\n#![cfg_attr(not(feature = \"std\"), no_std)]\n"),
+            Some(NoStdAttr::When("not(feature = \"std\")".to_string()))
+        );
+        assert_eq!(
+            crate_attr("#![cfg_attr(docsrs, feature(doc_cfg))]\nfn main() {}\n"),
+            None
+        );
+        assert_eq!(crate_attr("/// #![no_std]\nfn f() {}\n"), None);
+    }
+
+    #[test]
+    fn test_report_sorts_and_maps_imports() {
+        let dep = |name: &str, support, std_enabled| NoStdDependency {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            support,
+            std_enabled,
+            direct: name.to_string(),
+        };
+        let imports = imports::scan(
+            "use std::collections::{BTreeMap, HashMap};\nuse std::sync::Arc;\n\
+             use std::fmt;\nuse std::fs::File;\n",
+            Path::new("src/lib.rs"),
+            &[],
+        );
+        let report = NoStdReport::build(
+            Some(NoStdAttr::Always),
+            vec![
+                dep("libm", NoStdSupport::Always, false),
+                dep("serde", NoStdSupport::Optional, true),
+                dep("regex", NoStdSupport::RequiresStd, false),
+                dep("hashbrown", NoStdSupport::Optional, false),
+            ],
+            &imports,
+        );
+        let names: Vec<&str> = report.needing_std().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["regex", "serde"]);
+        let replacements: Vec<(&str, Option<&str>)> = report
+            .std_imports
+            .iter()
+            .map(|i| (i.path.as_str(), i.replacement.as_deref()))
+            .collect();
+        assert_eq!(
+            replacements,
+            vec![
+                (
+                    "std::collections::BTreeMap",
+                    Some("alloc::collections::BTreeMap")
+                ),
+                ("std::collections::HashMap", None),
+                ("std::sync::Arc", Some("alloc::sync::Arc")),
+                ("std::fmt", Some("core::fmt")),
+                ("std::fs::File", None),
+            ]
+        );
+        assert_eq!(report.std_only().count(), 2);
+    }
+}
@@ -0,0 +1,219 @@
+//! `TODO` / `FIXME` / `HACK` / `SAFETY:` comments
+//!
+//! Comments are found line by line in the raw source (syn drops non-doc comments),
+//! then attributed to the innermost analyzed item whose line range contains them.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::AnalyzedItem;
+use super::usage::base_type_name;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum NoteTag {
+    Fixme,
+    Hack,
+    Todo,
+    Safety,
+}
+
+impl NoteTag {
+    pub const ALL: [NoteTag; 4] = [Self::Fixme, Self::Hack, Self::Todo, Self::Safety];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Fixme => "FIXME",
+            Self::Hack => "HACK",
+            Self::Todo => "TODO",
+            Self::Safety => "SAFETY",
+        }
+    }
+
+    /// Next tag filter: all → FIXME → HACK → TODO → SAFETY → all
+    pub fn cycle(filter: Option<NoteTag>) -> Option<NoteTag> {
+        match filter {
+            None => Some(Self::ALL[0]),
+            Some(tag) => Self::ALL
+                .iter()
+                .position(|&t| t == tag)
+                .and_then(|i| Self::ALL.get(i + 1))
+                .copied(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub tag: NoteTag,
+    /// Comment text after the tag
+    pub text: String,
+    pub file: PathBuf,
+    pub line: usize,
+    /// Innermost enclosing item, e.g. `config::load` or `Parser::next`
+    pub item: Option<String>,
+}
+
+/// Notes in one file's source, without enclosing items
+pub fn scan(source: &str, file: &Path) -> Vec<Note> {
+    let mut notes = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let Some(comment) = comment_text(line) else {
+            continue;
+        };
+        let comment = comment.trim_start_matches(['/', '!', '*']).trim_start();
+        let Some((tag, rest)) = NoteTag::ALL.iter().find_map(|&tag| {
+            let rest = comment.strip_prefix(tag.label())?;
+            let boundary = match tag {
+                NoteTag::Safety => rest.starts_with(':'),
+                _ => !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'),
+            };
+            boundary.then_some((tag, rest))
+        }) else {
+            continue;
+        };
+        let text = rest
+            .trim_start_matches(':')
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end();
+        notes.push(Note {
+            tag,
+            text: text.to_string(),
+            file: file.to_path_buf(),
+            line: i + 1,
+            item: None,
+        });
+    }
+    notes
+}
+
+/// Comment part of a line: after `//` or `/*` outside a string literal, or the whole
+/// line when it continues a block comment (`* ...`)
+fn comment_text(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("* ") || trimmed == "*" {
+        return Some(trimmed);
+    }
+    let mut in_string = false;
+    let mut prev = '\0';
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' if prev != '\\' => in_string = !in_string,
+            '/' | '*' if !in_string && prev == '/' => return Some(&line[i + 1..]),
+            _ => {}
+        }
+        prev = c;
+    }
+    None
+}
+
+/// Fill in `Note::item` with the innermost item (or impl method) around each note
+pub fn attach_items(notes: &mut [Note], items: &[AnalyzedItem]) {
+    let mut spans: Vec<(&Path, usize, usize, String)> = Vec::new();
+    for item in items {
+        let Some(loc) = item.source_location() else {
+            continue;
+        };
+        let (Some(file), Some(start), Some(end)) = (&loc.file, loc.line, loc.end_line) else {
+            continue;
+        };
+        let name = match item {
+            AnalyzedItem::Impl(im) => {
+                let ty = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+                for method in &im.methods {
+                    let m = &method.source_location;
+                    if let (Some(start), Some(end)) = (m.line, m.end_line) {
+                        spans.push((file, start, end, format!("{}::{}", ty, method.name)));
+                    }
+                }
+                match im.trait_name {
+                    Some(ref t) => format!("impl {} for {}", t, im.self_ty),
+                    None => format!("impl {}", im.self_ty),
+                }
+            }
+            _ => item.qualified_name(),
+        };
+        spans.push((file, start, end, name));
+    }
+    for note in notes {
+        note.item = spans
+            .iter()
+            .filter(|(file, start, end, _)| {
+                *file == note.file && (*start..=*end).contains(&note.line)
+            })
+            .min_by_key(|(_, start, end, _)| end - start)
+            .map(|(_, _, _, name)| name.clone());
+    }
+}
+
+/// Notes as pretty-printed JSON, for issue triage scripts
+pub fn notes_json(notes: &[&Note]) -> String {
+    serde_json::to_string_pretty(notes).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    const SOURCE: &str = r#"// TODO: split this module
+pub struct Parser { pos: usize }
+
+impl Parser {
+    pub fn next(&mut self) -> Option<u8> {
+        // FIXME(lexer): handles ASCII only
+        let url = "http://example.com // TODO not a note";
+        /* HACK skip BOM */
+        None
+    }
+}
+
+pub fn raw(p: *const u8) -> u8 {
+    // SAFETY: caller guarantees `p` is valid
+    unsafe { *p }
+    // TODOS is not a tag, neither is SAFETY without a colon
+}
+"#;
+
+    #[test]
+    fn test_scan_and_attach() {
+        let file = PathBuf::from("/p/src/lib.rs");
+        let items = RustAnalyzer::new()
+            .analyze_source_with_path(SOURCE, Some(file.clone()))
+            .unwrap();
+        let mut notes = scan(SOURCE, &file);
+        attach_items(&mut notes, &items);
+
+        let found: Vec<(NoteTag, usize, &str, Option<&str>)> = notes
+            .iter()
+            .map(|n| (n.tag, n.line, n.text.as_str(), n.item.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (NoteTag::Todo, 1, "split this module", None),
+                (
+                    NoteTag::Fixme,
+                    6,
+                    "(lexer): handles ASCII only",
+                    Some("Parser::next")
+                ),
+                (NoteTag::Hack, 8, "skip BOM", Some("Parser::next")),
+                (
+                    NoteTag::Safety,
+                    14,
+                    "caller guarantees `p` is valid",
+                    Some("raw")
+                ),
+            ]
+        );
+
+        let json = notes_json(&notes.iter().take(1).collect::<Vec<_>>());
+        assert!(json.contains("\"tag\": \"TODO\""));
+        assert!(json.contains("\"line\": 1"));
+        assert_eq!(NoteTag::cycle(Some(NoteTag::Safety)), None);
+        assert_eq!(NoteTag::cycle(None), Some(NoteTag::Fixme));
+    }
+}
@@ -0,0 +1,248 @@
+//! Object safety (dyn compatibility) of traits
+//!
+//! A trait can be used as `dyn Trait` only when every method can be called through
+//! a vtable: no type parameters, a `self` receiver, and `Self` nowhere else in the
+//! signature. Methods opting out with `where Self: Sized` don't count. The trait
+//! itself must not require `Sized` (directly or through `Clone`, `Copy`,
+//! `Default`) nor have associated consts. Method signatures are re-parsed from
+//! their pretty-printed form; supertraits are only checked by name.
+
+use super::types::TraitInfo;
+use syn::{
+    FnArg, GenericArgument, PathArguments, ReturnType, TraitBoundModifier, Type, TypeParamBound,
+    WherePredicate,
+};
+
+/// Std supertraits that imply `Self: Sized`
+const SIZED_SUPERTRAITS: &[&str] = &["Sized", "Clone", "Copy", "Default"];
+
+/// Std supertraits defaulting a type parameter to `Self`
+const SELF_PARAM_SUPERTRAITS: &[&str] = &["PartialEq", "Eq", "PartialOrd", "Ord"];
+
+/// Whether a trait can be made into `dyn Trait`, and if not, why
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectSafety {
+    /// One short reason per violation, e.g. "`map` is generic"; empty when safe
+    pub reasons: Vec<String>,
+}
+
+impl ObjectSafety {
+    pub fn of(tr: &TraitInfo) -> Self {
+        let mut reasons = Vec::new();
+
+        for supertrait in &tr.supertraits {
+            let name = last_segment(supertrait);
+            if SIZED_SUPERTRAITS.contains(&name) {
+                reasons.push(format!(
+                    "requires `Self: Sized` (supertrait `{supertrait}`)"
+                ));
+            } else if SELF_PARAM_SUPERTRAITS.contains(&name) {
+                reasons.push(format!(
+                    "supertrait `{supertrait}` uses `Self` as a type parameter"
+                ));
+            }
+        }
+        let trait_sized = tr
+            .where_clause
+            .as_deref()
+            .and_then(|clause| syn::parse_str::<syn::WhereClause>(clause).ok())
+            .is_some_and(|clause| requires_sized(&clause));
+        if trait_sized {
+            reasons.push("requires `Self: Sized` (where clause)".to_string());
+        }
+
+        for c in &tr.associated_consts {
+            reasons.push(format!("associated const `{}`", c.name));
+        }
+
+        for method in &tr.methods {
+            let Ok(item) = syn::parse_str::<syn::TraitItemFn>(&format!("{};", method.signature))
+            else {
+                continue;
+            };
+            let sig = &item.sig;
+            if sig
+                .generics
+                .where_clause
+                .as_ref()
+                .is_some_and(requires_sized)
+            {
+                continue;
+            }
+            let name = &method.name;
+            if sig.asyncness.is_some() {
+                reasons.push(format!("`{name}` is async"));
+            }
+            if sig.generics.type_params().next().is_some()
+                || sig.generics.const_params().next().is_some()
+            {
+                reasons.push(format!("`{name}` is generic"));
+            }
+            let mut args = sig.inputs.iter();
+            match sig.inputs.first() {
+                Some(FnArg::Receiver(_)) => {
+                    args.next();
+                }
+                _ => reasons.push(format!("`{name}` has no `self` receiver")),
+            }
+            let mut uses = SelfUses::default();
+            for arg in args {
+                if let FnArg::Typed(pat) = arg {
+                    uses.walk(&pat.ty);
+                }
+            }
+            if uses.impl_trait {
+                reasons.push(format!("`{name}` takes `impl Trait`"));
+            }
+            if uses.self_type {
+                reasons.push(format!("`{name}` takes `Self` as an argument"));
+            }
+            if let ReturnType::Type(_, ty) = &sig.output {
+                let mut uses = SelfUses::default();
+                uses.walk(ty);
+                if uses.self_type {
+                    reasons.push(format!("`{name}` returns `Self`"));
+                }
+                if uses.impl_trait {
+                    reasons.push(format!("`{name}` returns `impl Trait`"));
+                }
+            }
+        }
+
+        Self { reasons }
+    }
+
+    pub fn is_safe(&self) -> bool {
+        self.reasons.is_empty()
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    let path = path.split('<').next().unwrap_or(path).trim();
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// `where Self: Sized` (or `Self: Sized + ...`), not `?Sized`
+fn requires_sized(clause: &syn::WhereClause) -> bool {
+    clause.predicates.iter().any(|predicate| {
+        let WherePredicate::Type(pt) = predicate else {
+            return false;
+        };
+        is_self(&pt.bounded_ty)
+            && pt.bounds.iter().any(|bound| {
+                matches!(bound, TypeParamBound::Trait(tb)
+                    if matches!(tb.modifier, TraitBoundModifier::None)
+                        && tb.path.segments.last().is_some_and(|s| s.ident == "Sized"))
+            })
+    })
+}
+
+fn is_self(ty: &Type) -> bool {
+    matches!(ty, Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self"))
+}
+
+/// Bare `Self` (not `Self::Assoc`) and `impl Trait` inside a type
+#[derive(Default)]
+struct SelfUses {
+    self_type: bool,
+    impl_trait: bool,
+}
+
+impl SelfUses {
+    fn walk(&mut self, ty: &Type) {
+        match ty {
+            Type::Path(p) => {
+                if is_self(ty) {
+                    self.self_type = true;
+                }
+                if let Some(ref qself) = p.qself {
+                    self.walk(&qself.ty);
+                }
+                for segment in &p.path.segments {
+                    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                        for arg in &args.args {
+                            match arg {
+                                GenericArgument::Type(ty) => self.walk(ty),
+                                GenericArgument::AssocType(assoc) => self.walk(&assoc.ty),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+            Type::ImplTrait(_) => self.impl_trait = true,
+            Type::Reference(r) => self.walk(&r.elem),
+            Type::Ptr(p) => self.walk(&p.elem),
+            Type::Slice(s) => self.walk(&s.elem),
+            Type::Array(a) => self.walk(&a.elem),
+            Type::Paren(p) => self.walk(&p.elem),
+            Type::Group(g) => self.walk(&g.elem),
+            Type::Tuple(t) => t.elems.iter().for_each(|ty| self.walk(ty)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::{AnalyzedItem, RustAnalyzer};
+
+    fn check(source: &str) -> ObjectSafety {
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        match items.first() {
+            Some(AnalyzedItem::Trait(tr)) => ObjectSafety::of(tr),
+            other => panic!("expected a trait, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_object_safe_traits() {
+        assert!(check("pub trait Draw { fn draw(&self, out: &mut String); }").is_safe());
+        assert!(check(
+            r#"
+            pub trait Shape: std::fmt::Debug {
+                type Unit;
+                fn area(&self) -> Self::Unit;
+                fn boxed(self: Box<Self>) -> usize;
+                fn new() -> Self where Self: Sized;
+                fn map<F: Fn(f64)>(&self, f: F) where Self: Sized;
+            }
+            "#
+        )
+        .is_safe());
+    }
+
+    #[test]
+    fn test_violations_are_named() {
+        let safety = check(
+            r#"
+            pub trait Store: Clone {
+                const VERSION: u32;
+                fn get<K: AsRef<str>>(&self, key: K);
+                fn open(path: &str) -> Self;
+                fn merge(&mut self, other: &Self);
+                fn keys(&self) -> impl Iterator<Item = String>;
+                async fn flush(&self);
+            }
+            "#,
+        );
+        assert!(!safety.is_safe());
+        assert_eq!(
+            safety.reasons,
+            vec![
+                "requires `Self: Sized` (supertrait `Clone`)",
+                "associated const `VERSION`",
+                "`get` is generic",
+                "`open` has no `self` receiver",
+                "`open` returns `Self`",
+                "`merge` takes `Self` as an argument",
+                "`keys` returns `impl Trait`",
+                "`flush` is async",
+            ]
+        );
+
+        let sized = check("pub trait Value where Self: Sized + Send { fn get(&self); }");
+        assert_eq!(sized.reasons, vec!["requires `Self: Sized` (where clause)"]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Places a function body can panic
+//!
+//! Counted from the body's tokens while parsing: `.unwrap()`, `.expect(..)`,
+//! `panic!` / `unreachable!`, `todo!` / `unimplemented!`, and indexing
+//! (`v[i]`, `map[&key]`, `s[1..]`; `s[..]` can't panic and isn't counted). Closures
+//! and macro arguments in the body count toward the function; asserts don't, since
+//! they state invariants rather than unfinished error handling.
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use serde::{Deserialize, Serialize};
+
+use super::types::{AnalyzedItem, FunctionInfo, Visibility};
+use super::usage::base_type_name;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicKind {
+    Unwrap,
+    Expect,
+    /// `panic!` / `unreachable!`
+    Panic,
+    /// `todo!` / `unimplemented!`
+    Todo,
+    Index,
+}
+
+impl PanicKind {
+    pub const ALL: [PanicKind; 5] = [
+        Self::Unwrap,
+        Self::Expect,
+        Self::Panic,
+        Self::Todo,
+        Self::Index,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Unwrap => "unwrap",
+            Self::Expect => "expect",
+            Self::Panic => "panic",
+            Self::Todo => "todo",
+            Self::Index => "index",
+        }
+    }
+
+    /// Next kind filter: all → unwrap → expect → panic → todo → index → all
+    pub fn cycle(filter: Option<PanicKind>) -> Option<PanicKind> {
+        match filter {
+            None => Some(Self::ALL[0]),
+            Some(kind) => Self::ALL
+                .iter()
+                .position(|&k| k == kind)
+                .and_then(|i| Self::ALL.get(i + 1))
+                .copied(),
+        }
+    }
+}
+
+/// Panic points of one function body, by kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicPoints {
+    pub unwrap: usize,
+    pub expect: usize,
+    pub panic: usize,
+    pub todo: usize,
+    pub index: usize,
+}
+
+impl PanicPoints {
+    pub fn scan(body: TokenStream) -> Self {
+        let mut points = Self::default();
+        points.scan_tokens(body);
+        points
+    }
+
+    fn scan_tokens(&mut self, tokens: TokenStream) {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        for (i, token) in tokens.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|p| &tokens[p]);
+            let next = tokens.get(i + 1);
+            match token {
+                TokenTree::Ident(ident) => {
+                    let is_method =
+                        is_punct(prev, '.') && is_group(next, |d| d == Delimiter::Parenthesis);
+                    let is_macro = is_punct(next, '!') && is_group(tokens.get(i + 2), |_| true);
+                    match ident.to_string().as_str() {
+                        "unwrap" if is_method => self.unwrap += 1,
+                        "expect" if is_method => self.expect += 1,
+                        "panic" | "unreachable" if is_macro => self.panic += 1,
+                        "todo" | "unimplemented" if is_macro => self.todo += 1,
+                        _ => {}
+                    }
+                }
+                TokenTree::Group(group) => {
+                    if group.delimiter() == Delimiter::Bracket
+                        && indexes(prev)
+                        && group.stream().to_string() != ".."
+                    {
+                        self.index += 1;
+                    }
+                    self.scan_tokens(group.stream());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn count(&self, kind: PanicKind) -> usize {
+        match kind {
+            PanicKind::Unwrap => self.unwrap,
+            PanicKind::Expect => self.expect,
+            PanicKind::Panic => self.panic,
+            PanicKind::Todo => self.todo,
+            PanicKind::Index => self.index,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        PanicKind::ALL.iter().map(|&k| self.count(k)).sum()
+    }
+
+    /// e.g. `2 unwrap, 1 index`
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = PanicKind::ALL
+            .iter()
+            .filter(|&&k| self.count(k) > 0)
+            .map(|&k| format!("{} {}", self.count(k), k.label()))
+            .collect();
+        parts.join(", ")
+    }
+}
+
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == c)
+}
+
+fn is_group(token: Option<&TokenTree>, delimiter: impl Fn(Delimiter) -> bool) -> bool {
+    matches!(token, Some(TokenTree::Group(g)) if delimiter(g.delimiter()))
+}
+
+/// Keywords a `[..]` can follow without indexing: `let [a, b] = ..`, `&mut [u8]`
+const NOT_INDEXED: &[&str] = &[
+    "as", "break", "const", "dyn", "else", "for", "if", "impl", "in", "let", "match", "move",
+    "mut", "return", "static", "where", "while", "yield",
+];
+
+/// A `[..]` after this token indexes into it: an expression such as `v`, `f()` or
+/// `m[a]`, rather than a keyword, an attribute's `#`, a macro's `!` or a type
+fn indexes(prev: Option<&TokenTree>) -> bool {
+    match prev {
+        Some(TokenTree::Ident(ident)) => !NOT_INDEXED.contains(&ident.to_string().as_str()),
+        Some(TokenTree::Group(g)) => g.delimiter() != Delimiter::Brace,
+        _ => false,
+    }
+}
+
+/// A function (or impl method) with panic points
+#[derive(Debug, Clone)]
+pub struct PanickingFn<'a> {
+    /// `name` or `Type::name`
+    pub name: String,
+    /// Public and, for methods, on a public path as far as the impl shows
+    pub public: bool,
+    pub function: &'a FunctionInfo,
+}
+
+/// Functions and methods with panic points of `kind` (any kind for `None`), most
+/// first
+pub fn panicking_functions(
+    items: &[AnalyzedItem],
+    kind: Option<PanicKind>,
+) -> Vec<PanickingFn<'_>> {
+    let count = |f: &FunctionInfo| kind.map_or(f.panics.total(), |k| f.panics.count(k));
+    let mut found = Vec::new();
+    for item in items {
+        match item {
+            AnalyzedItem::Function(f) => found.push(PanickingFn {
+                name: f.name.clone(),
+                public: f.visibility == Visibility::Public,
+                function: f,
+            }),
+            AnalyzedItem::Impl(im) => {
+                let ty = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+                for method in &im.methods {
+                    found.push(PanickingFn {
+                        name: format!("{}::{}", ty, method.name),
+                        // Trait methods are as public as the trait
+                        public: im.trait_name.is_some() || method.visibility == Visibility::Public,
+                        function: method,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    found.retain(|f| count(f.function) > 0);
+    found.sort_by(|a, b| {
+        count(b.function)
+            .cmp(&count(a.function))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_scan_counts_panic_points() {
+        let body: TokenStream = r#"{
+            #[allow(unused)]
+            let arr: [u8; 4] = [1, 2, 3, 4];
+            let v = vec![1, 2];
+            let first = v[0] + arr[1..].len() as u8 + arr[..].len() as u8;
+            let parsed: u8 = "1".parse().unwrap();
+            let _ = std::env::var("X").expect("X is set");
+            let _ = |s: &mut [u8]| s.first().copied().unwrap_or(0);
+            println!("{}", grid()[0][1]);
+            assert!(first > 0);
+            if parsed == 0 { unreachable!() }
+            todo!("the rest")
+        }"#
+        .parse()
+        .unwrap();
+        let points = PanicPoints::scan(body);
+        assert_eq!(
+            points,
+            PanicPoints {
+                unwrap: 1,
+                expect: 1,
+                panic: 1,
+                todo: 1,
+                index: 4,
+            }
+        );
+        assert_eq!(points.total(), 8);
+        assert_eq!(
+            points.summary(),
+            "1 unwrap, 1 expect, 1 panic, 1 todo, 4 index"
+        );
+    }
+
+    #[test]
+    fn test_panicking_functions() {
+        let source = r#"
+            pub fn load(path: &str) -> String {
+                std::fs::read_to_string(path).unwrap()
+            }
+            fn pick(v: &[u8]) -> u8 { v[0] + v[1] }
+            pub fn safe() -> Option<u8> { None }
+            pub struct Parser;
+            impl Parser {
+                pub fn next(&mut self) -> u8 { todo!() }
+            }
+        "#;
+        let items = RustAnalyzer::new()
+            .with_private(true)
+            .analyze_source(source)
+            .unwrap();
+        let found = panicking_functions(&items, None);
+        let found: Vec<(&str, bool)> = found.iter().map(|f| (f.name.as_str(), f.public)).collect();
+        assert_eq!(
+            found,
+            vec![("pick", false), ("Parser::next", true), ("load", true)]
+        );
+        let todos = panicking_functions(&items, Some(PanicKind::Todo));
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].name, "Parser::next");
+    }
+}
@@ -1,10 +1,18 @@
 //! Rust source code parser using syn
 
+use crate::analyzer::cfg::Cfg;
+use crate::analyzer::edition::Edition;
+use crate::analyzer::panics::PanicPoints;
+use crate::analyzer::pretty;
 use crate::analyzer::types::*;
 use crate::error::Result;
+use proc_macro2::{Delimiter, TokenTree};
 use quote::ToTokens;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use syn::spanned::Spanned;
 use syn::{
     File, Item, ItemConst, ItemEnum, ItemFn, ItemImpl, ItemStatic, ItemStruct, ItemTrait, ItemType,
 };
@@ -12,12 +20,24 @@ use syn::{
 /// Rust source code analyzer using syn for parsing
 pub struct RustAnalyzer {
     include_private: bool,
+    /// Paths relative to the analyzed directory, or file and directory names, to skip
+    exclude: Vec<String>,
+    /// Skip what only the inspector's detail sections show, see [`Self::with_light`]
+    light: bool,
+    /// Field and parameter types seen so far, shared by the items of every file read
+    types: Mutex<HashSet<Arc<str>>>,
+    /// Edition the sources are read in, see [`Self::with_edition`]
+    edition: Edition,
 }
 
 impl RustAnalyzer {
     pub fn new() -> Self {
         Self {
             include_private: true,
+            exclude: Vec::new(),
+            light: false,
+            types: Mutex::default(),
+            edition: Edition::default(),
         }
     }
 
@@ -26,6 +46,42 @@ impl RustAnalyzer {
         self
     }
 
+    /// Skip files matching any of `patterns` when analyzing a directory: a path
+    /// relative to it (`generated`, `bin/tool.rs`) or a single file or directory name
+    pub fn with_excludes(mut self, patterns: Vec<String>) -> Self {
+        self.exclude = patterns;
+        self
+    }
+
+    /// Light mode, for installed crates too big to hold in full: attributes other
+    /// than `#[repr(..)]` are dropped and function bodies aren't scanned for panic
+    /// points. Names, signatures, docs and locations are kept.
+    pub fn with_light(mut self, light: bool) -> Self {
+        self.light = light;
+        self
+    }
+
+    /// Read sources as written for `edition`: `async`, `await` and `try` are names in
+    /// 2015, and `gen` starts a block in 2024
+    pub fn with_edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self
+    }
+
+    pub fn edition(&self) -> Edition {
+        self.edition
+    }
+
+    /// Whether `relative` (a path under the analyzed directory) is excluded
+    pub fn is_excluded(&self, relative: &Path) -> bool {
+        let path = relative.to_string_lossy().replace('\\', "/");
+        self.exclude.iter().any(|pattern| {
+            path == *pattern
+                || path.starts_with(&format!("{}/", pattern))
+                || (!pattern.contains('/') && path.split('/').any(|part| part == pattern))
+        })
+    }
+
     /// Analyze a Rust source file
     pub fn analyze_file(&self, path: &Path) -> Result<Vec<AnalyzedItem>> {
         let content = fs::read_to_string(path)?;
@@ -68,80 +124,92 @@ impl RustAnalyzer {
         path: Option<PathBuf>,
         module_path: Vec<String>,
     ) -> Result<Vec<AnalyzedItem>> {
-        let syntax_tree: File = syn::parse_str(source)?;
-        let mut items = Vec::new();
-
-        for item in syntax_tree.items {
-            // Inline modules: expand inner items as first-class AnalyzedItems with synthetic path
-            if let Item::Mod(md) = &item {
-                if let Some((_, ref content)) = &md.content {
-                    let child_path: Vec<String> = {
-                        let mut p = module_path.clone();
-                        p.push(md.ident.to_string());
-                        p
-                    };
-                    let inner = self.collect_inline_module_items(content, &path, child_path);
-                    items.extend(inner);
-                }
-            }
+        Ok(self
+            .analyze_source_iter(source, path, module_path)?
+            .collect())
+    }
 
-            if let Some(mut analyzed) = self.analyze_item(&item, &path) {
-                Self::set_module_path(&mut analyzed, module_path.clone());
+    /// Analyze a Rust source file one item at a time, see [`FileItems`]
+    pub fn analyze_file_iter(&self, path: &Path) -> Result<FileItems<'_>> {
+        let content = fs::read_to_string(path)?;
+        let module_path = Self::derive_module_path(path);
+        self.analyze_source_iter(&content, Some(path.to_path_buf()), module_path)
+    }
 
-                if let Some(ref file_path) = path {
-                    if let Some(span) = Self::get_item_span(&item) {
-                        let line = span.start().line;
-                        Self::set_source_location(&mut analyzed, file_path.clone(), line);
-                    }
-                }
+    /// Parse `source` and return its items as an iterator that analyzes each when
+    /// it's asked for, in the order [`Self::analyze_source_with_module`] returns them
+    pub fn analyze_source_iter(
+        &self,
+        source: &str,
+        path: Option<PathBuf>,
+        module_path: Vec<String>,
+    ) -> Result<FileItems<'_>> {
+        let syntax_tree: File = syn::parse_str(&self.edition.prepare(source))?;
+        Ok(FileItems {
+            analyzer: self,
+            path,
+            stack: vec![ModuleItems {
+                items: syntax_tree.items.into_iter(),
+                // One allocation for the path, shared by every item of the module
+                module_path: module_path.into(),
+                module: None,
+            }],
+        })
+    }
 
-                if self.include_private || self.is_public(&analyzed) {
-                    items.push(analyzed);
-                }
+    /// Analyze `source` as [`Self::analyze_source_with_module`] does, but when the file
+    /// doesn't parse, split it into its top-level items and parse each on its own so
+    /// one syntax error only loses the items it's in. Returns the items that parsed
+    /// and the line ranges that didn't.
+    pub fn analyze_source_recovering(
+        &self,
+        source: &str,
+        path: Option<PathBuf>,
+        module_path: Vec<String>,
+    ) -> (Vec<AnalyzedItem>, Vec<ParseFailure>) {
+        let error = match self.analyze_source_with_module(source, path.clone(), module_path.clone())
+        {
+            Ok(items) => return (items, Vec::new()),
+            Err(e) => e,
+        };
+        tracing::debug!(path = ?path, error = %error, "recovering items");
+        let lines: Vec<&str> = source.lines().collect();
+        let mut items = Vec::new();
+        let mut failures = Vec::new();
+        for (start, end) in item_chunks(source) {
+            // Blank lines ahead of the chunk keep its line numbers those of the file
+            let chunk = "\n".repeat(start - 1) + &lines[start - 1..end].join("\n");
+            match self.analyze_source_with_module(&chunk, path.clone(), module_path.clone()) {
+                Ok(chunk_items) => items.extend(chunk_items),
+                Err(e) => failures.push(ParseFailure {
+                    lines: (start, end),
+                    message: e.to_string(),
+                }),
             }
         }
-
-        Ok(items)
+        (items, failures)
     }
 
-    /// Recursively collect items from inline module content as first-class AnalyzedItems.
-    fn collect_inline_module_items(
+    /// `item` analyzed as part of `module_path`, if it's kept
+    fn analyze_in_module(
         &self,
-        content: &[Item],
+        item: &Item,
         path: &Option<PathBuf>,
-        module_path: Vec<String>,
-    ) -> Vec<AnalyzedItem> {
-        let mut items = Vec::new();
-        for item in content {
-            if let Item::Mod(md) = item {
-                if let Some((_, ref inner_content)) = &md.content {
-                    let child_path: Vec<String> = {
-                        let mut p = module_path.clone();
-                        p.push(md.ident.to_string());
-                        p
-                    };
-                    let inner = self.collect_inline_module_items(inner_content, path, child_path);
-                    items.extend(inner);
-                }
-            }
-            if let Some(mut analyzed) = self.analyze_item(item, path) {
-                Self::set_module_path(&mut analyzed, module_path.clone());
-                if let Some(ref file_path) = path {
-                    if let Some(span) = Self::get_item_span(item) {
-                        let line = span.start().line;
-                        Self::set_source_location(&mut analyzed, file_path.clone(), line);
-                    }
-                }
-                if self.include_private || self.is_public(&analyzed) {
-                    items.push(analyzed);
-                }
+        module_path: &Arc<[String]>,
+    ) -> Option<AnalyzedItem> {
+        let mut analyzed = self.analyze_item(item, path)?;
+        Self::set_module_path(&mut analyzed, Arc::clone(module_path));
+        if let Some(ref file_path) = path {
+            if let Some(span) = Self::get_item_span(item) {
+                let end_line = item.span().end().line;
+                Self::set_source_location(&mut analyzed, file_path.clone(), span, end_line);
             }
         }
-        items
+        (self.include_private || self.is_public(&analyzed)).then_some(analyzed)
     }
 
     /// Derive module path from file path (e.g., src/analyzer/parser.rs -> ["analyzer", "parser"])
-    fn derive_module_path(path: &Path) -> Vec<String> {
+    pub(crate) fn derive_module_path(path: &Path) -> Vec<String> {
         let mut components: Vec<String> = path
             .iter()
             .filter_map(|c| c.to_str())
@@ -166,7 +234,7 @@ impl RustAnalyzer {
         components
     }
 
-    fn set_module_path(item: &mut AnalyzedItem, path: Vec<String>) {
+    fn set_module_path(item: &mut AnalyzedItem, path: Arc<[String]>) {
         match item {
             AnalyzedItem::Function(f) => f.module_path = path,
             AnalyzedItem::Struct(s) => s.module_path = path,
@@ -195,14 +263,28 @@ impl RustAnalyzer {
         }
     }
 
-    fn set_source_location(item: &mut AnalyzedItem, file: PathBuf, line: usize) {
-        let loc = SourceLocation::new(file, line);
+    /// `name` is the span of the item's name (`impl` for impl blocks)
+    fn set_source_location(
+        item: &mut AnalyzedItem,
+        file: PathBuf,
+        name: proc_macro2::Span,
+        end_line: usize,
+    ) {
+        let mut loc = SourceLocation::new(file, name.start().line);
+        loc.column = Some(name.start().column + 1);
+        loc.end_line = Some(end_line);
         match item {
             AnalyzedItem::Function(f) => f.source_location = loc,
             AnalyzedItem::Struct(s) => s.source_location = loc,
             AnalyzedItem::Enum(e) => e.source_location = loc,
             AnalyzedItem::Trait(t) => t.source_location = loc,
-            AnalyzedItem::Impl(i) => i.source_location = loc,
+            AnalyzedItem::Impl(i) => {
+                // Methods got their lines from `extract_impl_method`; only the file is known here
+                for method in &mut i.methods {
+                    method.source_location.file = loc.file.clone();
+                }
+                i.source_location = loc
+            }
             AnalyzedItem::Module(m) => m.source_location = loc,
             AnalyzedItem::TypeAlias(t) => t.source_location = loc,
             AnalyzedItem::Const(c) => c.source_location = loc,
@@ -231,18 +313,19 @@ impl RustAnalyzer {
 
     fn analyze_function(&self, func: &ItemFn) -> AnalyzedItem {
         let name = func.sig.ident.to_string();
-        let signature = func.sig.to_token_stream().to_string();
+        let signature = pretty::signature(&func.sig);
         let visibility = Self::parse_visibility(&func.vis);
         let is_async = func.sig.asyncness.is_some();
         let is_const = func.sig.constness.is_some();
         let is_unsafe = func.sig.unsafety.is_some();
 
         let generics = Self::extract_generics(&func.sig.generics);
-        let parameters = Self::extract_parameters(&func.sig.inputs);
+        let generics_info = Self::extract_generics_info(&func.sig.generics);
+        let parameters = self.extract_parameters(&func.sig.inputs);
         let return_type = Self::extract_return_type(&func.sig.output);
         let where_clause = Self::extract_where_clause(&func.sig.generics.where_clause);
         let documentation = Self::extract_docs(&func.attrs);
-        let attributes = Self::extract_attributes(&func.attrs);
+        let attributes = self.extract_attributes(&func.attrs);
 
         AnalyzedItem::Function(FunctionInfo {
             name,
@@ -252,13 +335,16 @@ impl RustAnalyzer {
             is_const,
             is_unsafe,
             generics,
+            generics_info,
             parameters,
             return_type,
             documentation,
             attributes,
             where_clause,
+            panics: self.panic_points(&func.block),
+            cfg: Self::extract_cfg(&func.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
@@ -266,6 +352,7 @@ impl RustAnalyzer {
         let name = st.ident.to_string();
         let visibility = Self::parse_visibility(&st.vis);
         let generics = Self::extract_generics(&st.generics);
+        let generics_info = Self::extract_generics_info(&st.generics);
         let where_clause = Self::extract_where_clause(&st.generics.where_clause);
 
         let (fields, kind) = match &st.fields {
@@ -275,7 +362,7 @@ impl RustAnalyzer {
                     .iter()
                     .map(|f| Field {
                         name: f.ident.as_ref().map(|i| i.to_string()).unwrap_or_default(),
-                        ty: f.ty.to_token_stream().to_string(),
+                        ty: self.intern(pretty::ty(&f.ty)),
                         visibility: Self::parse_visibility(&f.vis),
                         documentation: Self::extract_docs(&f.attrs),
                     })
@@ -289,7 +376,7 @@ impl RustAnalyzer {
                     .enumerate()
                     .map(|(i, f)| Field {
                         name: i.to_string(),
-                        ty: f.ty.to_token_stream().to_string(),
+                        ty: self.intern(pretty::ty(&f.ty)),
                         visibility: Self::parse_visibility(&f.vis),
                         documentation: Self::extract_docs(&f.attrs),
                     })
@@ -301,20 +388,22 @@ impl RustAnalyzer {
 
         let derives = Self::extract_derives(&st.attrs);
         let documentation = Self::extract_docs(&st.attrs);
-        let attributes = Self::extract_attributes(&st.attrs);
+        let attributes = self.extract_attributes(&st.attrs);
 
         AnalyzedItem::Struct(StructInfo {
             name,
             visibility,
             generics,
+            generics_info,
             fields,
             kind,
             documentation,
             derives,
             attributes,
             where_clause,
+            cfg: Self::extract_cfg(&st.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
@@ -322,6 +411,7 @@ impl RustAnalyzer {
         let name = en.ident.to_string();
         let visibility = Self::parse_visibility(&en.vis);
         let generics = Self::extract_generics(&en.generics);
+        let generics_info = Self::extract_generics_info(&en.generics);
         let where_clause = Self::extract_where_clause(&en.generics.where_clause);
 
         let variants = en
@@ -335,7 +425,7 @@ impl RustAnalyzer {
                             .iter()
                             .map(|f| Field {
                                 name: f.ident.as_ref().map(|i| i.to_string()).unwrap_or_default(),
-                                ty: f.ty.to_token_stream().to_string(),
+                                ty: self.intern(pretty::ty(&f.ty)),
                                 visibility: Self::parse_visibility(&f.vis),
                                 documentation: Self::extract_docs(&f.attrs),
                             })
@@ -343,20 +433,13 @@ impl RustAnalyzer {
                         VariantFields::Named(fields)
                     }
                     syn::Fields::Unnamed(unnamed) => {
-                        let types = unnamed
-                            .unnamed
-                            .iter()
-                            .map(|f| f.ty.to_token_stream().to_string())
-                            .collect();
+                        let types = unnamed.unnamed.iter().map(|f| pretty::ty(&f.ty)).collect();
                         VariantFields::Unnamed(types)
                     }
                     syn::Fields::Unit => VariantFields::Unit,
                 };
 
-                let discriminant = v
-                    .discriminant
-                    .as_ref()
-                    .map(|(_, expr)| expr.to_token_stream().to_string());
+                let discriminant = v.discriminant.as_ref().map(|(_, expr)| pretty::expr(expr));
 
                 Variant {
                     name: v.ident.to_string(),
@@ -369,19 +452,21 @@ impl RustAnalyzer {
 
         let derives = Self::extract_derives(&en.attrs);
         let documentation = Self::extract_docs(&en.attrs);
-        let attributes = Self::extract_attributes(&en.attrs);
+        let attributes = self.extract_attributes(&en.attrs);
 
         AnalyzedItem::Enum(EnumInfo {
             name,
             visibility,
             generics,
+            generics_info,
             variants,
             documentation,
             derives,
             attributes,
             where_clause,
+            cfg: Self::extract_cfg(&en.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
@@ -391,13 +476,10 @@ impl RustAnalyzer {
         let is_unsafe = tr.unsafety.is_some();
         let is_auto = tr.auto_token.is_some();
         let generics = Self::extract_generics(&tr.generics);
+        let generics_info = Self::extract_generics_info(&tr.generics);
         let where_clause = Self::extract_where_clause(&tr.generics.where_clause);
 
-        let supertraits = tr
-            .supertraits
-            .iter()
-            .map(|t| t.to_token_stream().to_string())
-            .collect();
+        let supertraits = tr.supertraits.iter().map(pretty::bound).collect();
 
         let mut methods = Vec::new();
         let mut associated_types = Vec::new();
@@ -408,7 +490,7 @@ impl RustAnalyzer {
                 syn::TraitItem::Fn(method) => {
                     methods.push(TraitMethod {
                         name: method.sig.ident.to_string(),
-                        signature: method.sig.to_token_stream().to_string(),
+                        signature: pretty::signature(&method.sig),
                         has_default: method.default.is_some(),
                         is_async: method.sig.asyncness.is_some(),
                         documentation: Self::extract_docs(&method.attrs),
@@ -417,25 +499,15 @@ impl RustAnalyzer {
                 syn::TraitItem::Type(ty) => {
                     associated_types.push(AssociatedType {
                         name: ty.ident.to_string(),
-                        bounds: ty
-                            .bounds
-                            .iter()
-                            .map(|b| b.to_token_stream().to_string())
-                            .collect(),
-                        default: ty
-                            .default
-                            .as_ref()
-                            .map(|(_, t)| t.to_token_stream().to_string()),
+                        bounds: ty.bounds.iter().map(pretty::bound).collect(),
+                        default: ty.default.as_ref().map(|(_, t)| pretty::ty(t)),
                     });
                 }
                 syn::TraitItem::Const(c) => {
                     associated_consts.push(AssociatedConst {
                         name: c.ident.to_string(),
-                        ty: c.ty.to_token_stream().to_string(),
-                        default: c
-                            .default
-                            .as_ref()
-                            .map(|(_, e)| e.to_token_stream().to_string()),
+                        ty: pretty::ty(&c.ty),
+                        default: c.default.as_ref().map(|(_, e)| pretty::expr(e)),
                     });
                 }
                 _ => {}
@@ -448,6 +520,7 @@ impl RustAnalyzer {
             name,
             visibility,
             generics,
+            generics_info,
             supertraits,
             methods,
             associated_types,
@@ -456,23 +529,22 @@ impl RustAnalyzer {
             is_unsafe,
             is_auto,
             where_clause,
+            cfg: Self::extract_cfg(&tr.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
     fn analyze_impl(&self, im: &ItemImpl) -> AnalyzedItem {
-        let self_ty = im.self_ty.to_token_stream().to_string();
-        let trait_name = im
-            .trait_
-            .as_ref()
-            .map(|(_, path, _)| path.to_token_stream().to_string());
+        let self_ty = pretty::ty(&im.self_ty);
+        let trait_name = im.trait_.as_ref().map(|(_, path, _)| pretty::path(path));
         let is_unsafe = im.unsafety.is_some();
         let is_negative = im
             .trait_
             .as_ref()
             .is_some_and(|(bang, _, _)| bang.is_some());
         let generics = Self::extract_generics(&im.generics);
+        let generics_info = Self::extract_generics_info(&im.generics);
         let where_clause = Self::extract_where_clause(&im.generics.where_clause);
 
         let methods = im
@@ -491,12 +563,14 @@ impl RustAnalyzer {
             self_ty,
             trait_name,
             generics,
+            generics_info,
             methods,
             is_unsafe,
             is_negative,
             where_clause,
+            cfg: Self::extract_cfg(&im.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
@@ -518,9 +592,9 @@ impl RustAnalyzer {
                     Item::Enum(e) => item_names.push(format!("enum {}", e.ident)),
                     Item::Trait(t) => item_names.push(format!("trait {}", t.ident)),
                     Item::Impl(i) => {
-                        let ty = i.self_ty.to_token_stream().to_string();
+                        let ty = pretty::ty(&i.self_ty);
                         if let Some((_, path, _)) = &i.trait_ {
-                            item_names.push(format!("impl {} for {}", path.to_token_stream(), ty));
+                            item_names.push(format!("impl {} for {}", pretty::path(path), ty));
                         } else {
                             item_names.push(format!("impl {}", ty));
                         }
@@ -545,8 +619,9 @@ impl RustAnalyzer {
             submodules,
             documentation,
             is_inline,
+            cfg: Self::extract_cfg(&md.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
@@ -555,11 +630,13 @@ impl RustAnalyzer {
             name: ty.ident.to_string(),
             visibility: Self::parse_visibility(&ty.vis),
             generics: Self::extract_generics(&ty.generics),
-            ty: ty.ty.to_token_stream().to_string(),
+            generics_info: Self::extract_generics_info(&ty.generics),
+            ty: pretty::ty(&ty.ty),
             documentation: Self::extract_docs(&ty.attrs),
             where_clause: Self::extract_where_clause(&ty.generics.where_clause),
+            cfg: Self::extract_cfg(&ty.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
@@ -567,11 +644,12 @@ impl RustAnalyzer {
         AnalyzedItem::Const(ConstInfo {
             name: c.ident.to_string(),
             visibility: Self::parse_visibility(&c.vis),
-            ty: c.ty.to_token_stream().to_string(),
-            value: Some(c.expr.to_token_stream().to_string()),
+            ty: pretty::ty(&c.ty),
+            value: Some(pretty::expr(&c.expr)),
             documentation: Self::extract_docs(&c.attrs),
+            cfg: Self::extract_cfg(&c.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
@@ -580,33 +658,61 @@ impl RustAnalyzer {
         AnalyzedItem::Static(StaticInfo {
             name: s.ident.to_string(),
             visibility: Self::parse_visibility(&s.vis),
-            ty: s.ty.to_token_stream().to_string(),
+            ty: pretty::ty(&s.ty),
             is_mut,
             documentation: Self::extract_docs(&s.attrs),
+            cfg: Self::extract_cfg(&s.attrs),
             source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            module_path: Vec::new().into(),
         })
     }
 
     fn extract_impl_method(&self, method: &syn::ImplItemFn) -> FunctionInfo {
         FunctionInfo {
             name: method.sig.ident.to_string(),
-            signature: method.sig.to_token_stream().to_string(),
+            signature: pretty::signature(&method.sig),
             visibility: Self::parse_visibility(&method.vis),
             is_async: method.sig.asyncness.is_some(),
             is_const: method.sig.constness.is_some(),
             is_unsafe: method.sig.unsafety.is_some(),
             generics: Self::extract_generics(&method.sig.generics),
-            parameters: Self::extract_parameters(&method.sig.inputs),
+            generics_info: Self::extract_generics_info(&method.sig.generics),
+            parameters: self.extract_parameters(&method.sig.inputs),
             return_type: Self::extract_return_type(&method.sig.output),
             documentation: Self::extract_docs(&method.attrs),
-            attributes: Self::extract_attributes(&method.attrs),
+            attributes: self.extract_attributes(&method.attrs),
             where_clause: Self::extract_where_clause(&method.sig.generics.where_clause),
-            source_location: SourceLocation::default(),
-            module_path: Vec::new(),
+            panics: self.panic_points(&method.block),
+            cfg: Self::extract_cfg(&method.attrs),
+            source_location: SourceLocation {
+                line: Some(method.sig.ident.span().start().line),
+                column: Some(method.sig.ident.span().start().column + 1),
+                end_line: Some(method.span().end().line),
+                ..SourceLocation::default()
+            },
+            module_path: Vec::new().into(),
+        }
+    }
+
+    fn panic_points(&self, block: &syn::Block) -> PanicPoints {
+        if self.light {
+            PanicPoints::default()
+        } else {
+            PanicPoints::scan(block.to_token_stream())
         }
     }
 
+    /// The shared copy of `ty`, added on first sight
+    fn intern(&self, ty: String) -> Arc<str> {
+        let mut types = self.types.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(interned) = types.get(ty.as_str()) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = ty.into();
+        types.insert(Arc::clone(&interned));
+        interned
+    }
+
     fn parse_visibility(vis: &syn::Visibility) -> Visibility {
         match vis {
             syn::Visibility::Public(_) => Visibility::Public,
@@ -626,14 +732,82 @@ impl RustAnalyzer {
     }
 
     fn extract_generics(generics: &syn::Generics) -> Vec<String> {
-        generics
+        generics.params.iter().map(pretty::generic_param).collect()
+    }
+
+    /// Split generics into lifetime/type/const parameters and attach each `where`
+    /// predicate to the parameter it bounds (when it bounds exactly one).
+    fn extract_generics_info(generics: &syn::Generics) -> GenericsInfo {
+        let bounds_to_strings = |bounds: &syn::punctuated::Punctuated<
+            syn::TypeParamBound,
+            syn::Token![+],
+        >| { bounds.iter().map(pretty::bound).collect::<Vec<_>>() };
+
+        let mut params: Vec<GenericParam> = generics
             .params
             .iter()
-            .map(|p| p.to_token_stream().to_string())
-            .collect()
+            .map(|p| match p {
+                syn::GenericParam::Lifetime(lt) => GenericParam {
+                    name: lt.lifetime.to_string(),
+                    kind: GenericParamKind::Lifetime,
+                    bounds: lt.bounds.iter().map(|b| b.to_string()).collect(),
+                    where_bounds: Vec::new(),
+                    default: None,
+                },
+                syn::GenericParam::Type(tp) => GenericParam {
+                    name: tp.ident.to_string(),
+                    kind: GenericParamKind::Type,
+                    bounds: bounds_to_strings(&tp.bounds),
+                    where_bounds: Vec::new(),
+                    default: tp.default.as_ref().map(pretty::ty),
+                },
+                syn::GenericParam::Const(cp) => GenericParam {
+                    name: cp.ident.to_string(),
+                    kind: GenericParamKind::Const(pretty::ty(&cp.ty)),
+                    bounds: Vec::new(),
+                    where_bounds: Vec::new(),
+                    default: cp.default.as_ref().map(pretty::expr),
+                },
+            })
+            .collect();
+
+        let mut other_predicates = Vec::new();
+        if let Some(ref wc) = generics.where_clause {
+            for predicate in &wc.predicates {
+                let target = match predicate {
+                    syn::WherePredicate::Type(pt) => match &pt.bounded_ty {
+                        syn::Type::Path(tp) if tp.qself.is_none() => tp
+                            .path
+                            .get_ident()
+                            .map(|i| (i.to_string(), bounds_to_strings(&pt.bounds))),
+                        _ => None,
+                    },
+                    syn::WherePredicate::Lifetime(pl) => Some((
+                        pl.lifetime.to_string(),
+                        pl.bounds.iter().map(|b| b.to_string()).collect(),
+                    )),
+                    _ => None,
+                };
+                match target.and_then(|(name, bounds)| {
+                    params
+                        .iter_mut()
+                        .find(|p| p.name == name)
+                        .map(|p| (p, bounds))
+                }) {
+                    Some((param, bounds)) => param.where_bounds.extend(bounds),
+                    None => other_predicates.push(pretty::where_predicate(predicate)),
+                }
+            }
+        }
+
+        GenericsInfo {
+            params,
+            other_predicates,
+        }
     }
 
     fn extract_parameters(
+        &self,
         inputs: &syn::punctuated::Punctuated<syn::FnArg, syn::Token![,]>,
     ) -> Vec<Parameter> {
         inputs
@@ -641,14 +815,14 @@ impl RustAnalyzer {
             .map(|arg| match arg {
                 syn::FnArg::Receiver(recv) => Parameter {
                     name: "self".to_string(),
-                    ty: "Self".to_string(),
+                    ty: self.intern("Self".to_string()),
                     is_self: true,
                     is_mut: recv.mutability.is_some(),
                     is_ref: recv.reference.is_some(),
                 },
                 syn::FnArg::Typed(pat_type) => Parameter {
-                    name: pat_type.pat.to_token_stream().to_string(),
-                    ty: pat_type.ty.to_token_stream().to_string(),
+                    name: pretty::pat(&pat_type.pat),
+                    ty: self.intern(pretty::ty(&pat_type.ty)),
                     is_self: false,
                     is_mut: false,
                     is_ref: false,
@@ -660,17 +834,15 @@ impl RustAnalyzer {
     fn extract_return_type(output: &syn::ReturnType) -> Option<String> {
         match output {
             syn::ReturnType::Default => None,
-            syn::ReturnType::Type(_, ty) => Some(ty.to_token_stream().to_string()),
+            syn::ReturnType::Type(_, ty) => Some(pretty::ty(ty)),
         }
     }
 
     fn extract_where_clause(where_clause: &Option<syn::WhereClause>) -> Option<String> {
-        where_clause
-            .as_ref()
-            .map(|w| w.to_token_stream().to_string())
+        where_clause.as_ref().map(pretty::where_clause)
     }
 
-    fn extract_docs(attrs: &[syn::Attribute]) -> Option<String> {
+    fn extract_docs(attrs: &[syn::Attribute]) -> Option<Arc<str>> {
         let docs: Vec<String> = attrs
             .iter()
             .filter_map(|attr| {
@@ -678,7 +850,11 @@ impl RustAnalyzer {
                     attr.meta.require_name_value().ok().and_then(|nv| {
                         if let syn::Expr::Lit(expr_lit) = &nv.value {
                             if let syn::Lit::Str(lit_str) = &expr_lit.lit {
-                                return Some(lit_str.value().trim().to_string());
+                                // Drop the conventional space after `///` but keep
+                                // indentation (code blocks in docs rely on it)
+                                let value = lit_str.value();
+                                let line = value.strip_prefix(' ').unwrap_or(&value);
+                                return Some(line.trim_end().to_string());
                             }
                         }
                         None
@@ -692,7 +868,7 @@ impl RustAnalyzer {
         if docs.is_empty() {
             None
         } else {
-            Some(docs.join("\n"))
+            Some(docs.join("\n").into())
         }
     }
 
@@ -705,12 +881,7 @@ impl RustAnalyzer {
                         syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
                     )
                     .ok()
-                    .map(|paths| {
-                        paths
-                            .iter()
-                            .map(|p| p.to_token_stream().to_string())
-                            .collect::<Vec<_>>()
-                    })
+                    .map(|paths| paths.iter().map(pretty::path).collect::<Vec<_>>())
                 } else {
                     None
                 }
@@ -719,15 +890,150 @@ impl RustAnalyzer {
             .collect()
     }
 
-    fn extract_attributes(attrs: &[syn::Attribute]) -> Vec<String> {
+    /// Predicates of `#[cfg(..)]` attributes, normalized
+    fn extract_cfg(attrs: &[syn::Attribute]) -> Vec<String> {
+        attrs
+            .iter()
+            .filter_map(Cfg::from_attribute)
+            .map(|cfg| cfg.to_string())
+            .collect()
+    }
+
+    fn extract_attributes(&self, attrs: &[syn::Attribute]) -> Vec<String> {
         attrs
             .iter()
             .filter(|attr| !attr.path().is_ident("doc") && !attr.path().is_ident("derive"))
-            .map(|attr| attr.to_token_stream().to_string())
+            .filter(|attr| !self.light || attr.path().is_ident("repr"))
+            .map(pretty::attribute)
             .collect()
     }
 }
 
+/// Top-level code that didn't parse, see [`RustAnalyzer::analyze_source_recovering`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailure {
+    /// First and last line, 1-based
+    pub lines: (usize, usize),
+    pub message: String,
+}
+
+/// Line ranges of the top-level items of `source`, with their attributes and docs.
+/// Items end at a `;` or a `{ .. }` block outside any delimiters; items sharing a line
+/// are one range. When the delimiters don't balance, so the source can't be split
+/// into token trees, items start at unindented lines instead.
+pub(crate) fn item_chunks(source: &str) -> Vec<(usize, usize)> {
+    let Ok(tokens) = source.parse::<proc_macro2::TokenStream>() else {
+        return line_chunks(source);
+    };
+    let mut chunks: Vec<(usize, usize)> = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let span = token.span();
+        let chunk = current.get_or_insert((span.start().line, span.end().line));
+        chunk.1 = span.end().line;
+        let ends = match &token {
+            TokenTree::Punct(punct) => punct.as_char() == ';',
+            // `const X: T = T { .. };` goes on to its `;`
+            TokenTree::Group(group) => {
+                group.delimiter() == Delimiter::Brace
+                    && !matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ';')
+            }
+            _ => false,
+        };
+        if ends {
+            push_chunk(&mut chunks, current.take());
+        }
+    }
+    push_chunk(&mut chunks, current);
+    chunks
+}
+
+fn push_chunk(chunks: &mut Vec<(usize, usize)>, chunk: Option<(usize, usize)>) {
+    let Some((start, end)) = chunk else {
+        return;
+    };
+    match chunks.last_mut() {
+        Some(last) if last.1 >= start => last.1 = last.1.max(end),
+        _ => chunks.push((start, end)),
+    }
+}
+
+/// Items as rustfmt lays them out: each starts at an unindented line that doesn't
+/// close a block or continue a signature, after the attributes and docs above it
+fn line_chunks(source: &str) -> Vec<(usize, usize)> {
+    let mut chunks: Vec<(usize, usize)> = Vec::new();
+    // The last unindented line was an attribute or doc comment of the next item
+    let mut attached = false;
+    for (i, line) in source.lines().enumerate() {
+        let top = line.starts_with(|c: char| !c.is_whitespace() && !"{}()[]".contains(c))
+            && !line.starts_with("where")
+            && (!line.starts_with("//") || line.starts_with("///"));
+        if top && !attached {
+            chunks.push((i + 1, i + 1));
+        } else if let Some(last) = chunks.last_mut().filter(|_| !line.trim().is_empty()) {
+            last.1 = i + 1;
+        }
+        if top {
+            attached = line.starts_with("#[") || line.starts_with("///");
+        }
+    }
+    chunks
+}
+
+/// Items of one parsed file, analyzed as they're taken: only the syntax tree is held,
+/// so a caller that handles each item and drops it stays at one file's worth of memory.
+/// Items of an inline module come first, as first-class items with the module in
+/// their path, then the module itself.
+pub struct FileItems<'a> {
+    analyzer: &'a RustAnalyzer,
+    path: Option<PathBuf>,
+    /// Items left at each level of inline modules, innermost last
+    stack: Vec<ModuleItems>,
+}
+
+struct ModuleItems {
+    items: std::vec::IntoIter<Item>,
+    module_path: Arc<[String]>,
+    /// The inline module these are the items of, returned after them
+    module: Option<AnalyzedItem>,
+}
+
+impl Iterator for FileItems<'_> {
+    type Item = AnalyzedItem;
+
+    fn next(&mut self) -> Option<AnalyzedItem> {
+        loop {
+            let level = self.stack.last_mut()?;
+            let Some(mut item) = level.items.next() else {
+                if let Some(module) = self.stack.pop().and_then(|level| level.module) {
+                    return Some(module);
+                }
+                continue;
+            };
+            let module_path = Arc::clone(&level.module_path);
+            let analyzed = self
+                .analyzer
+                .analyze_in_module(&item, &self.path, &module_path);
+            if let Item::Mod(md) = &mut item {
+                if let Some((_, content)) = md.content.take() {
+                    let mut child_path = module_path.to_vec();
+                    child_path.push(md.ident.to_string());
+                    self.stack.push(ModuleItems {
+                        items: content.into_iter(),
+                        module_path: child_path.into(),
+                        module: analyzed,
+                    });
+                    continue;
+                }
+            }
+            if analyzed.is_some() {
+                return analyzed;
+            }
+        }
+    }
+}
+
 impl Default for RustAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -755,6 +1061,8 @@ mod tests {
             assert_eq!(f.name, "hello");
             assert_eq!(f.visibility, Visibility::Public);
             assert!(f.documentation.is_some());
+            assert_eq!(f.signature, "fn hello(name: &str) -> String");
+            assert_eq!(&*f.parameters[0].ty, "&str");
         } else {
             panic!("Expected function");
         }
@@ -803,6 +1111,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generics_info_resolves_where_bounds() {
+        let source = r#"
+            pub fn run<'a, T: Clone, const N: usize>(x: &'a T) -> [T; N]
+            where
+                T: Send + 'a,
+                Vec<T>: Default,
+            { todo!() }
+        "#;
+
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        let AnalyzedItem::Function(f) = &items[0] else {
+            panic!("Expected function");
+        };
+        let g = &f.generics_info;
+        assert_eq!(g.lifetimes().count(), 1);
+        assert_eq!(
+            g.consts().next().unwrap().kind,
+            GenericParamKind::Const("usize".into())
+        );
+        let t = g.types().next().unwrap();
+        assert_eq!(t.bounds, vec!["Clone"]);
+        assert_eq!(t.where_bounds, vec!["Send", "'a"]);
+        assert_eq!(g.other_predicates.len(), 1);
+    }
+
     #[test]
     fn test_analyze_module_path_from_path() {
         use std::path::Path;
@@ -826,9 +1160,137 @@ mod tests {
         assert_eq!(items.len(), 1);
         if let AnalyzedItem::Function(f) = &items[0] {
             assert_eq!(f.name, "util");
-            assert_eq!(f.module_path.as_slice(), &["mymod"]);
+            assert_eq!(&f.module_path[..], &["mymod"]);
         } else {
             panic!("Expected function");
         }
     }
+
+    #[test]
+    fn test_excluded_paths() {
+        let analyzer = RustAnalyzer::new()
+            .with_excludes(vec!["generated".to_string(), "bin/tool.rs".to_string()]);
+        assert!(analyzer.is_excluded(Path::new("generated/api.rs")));
+        assert!(analyzer.is_excluded(Path::new("net/generated/mod.rs")));
+        assert!(analyzer.is_excluded(Path::new("bin/tool.rs")));
+        assert!(!analyzer.is_excluded(Path::new("other/bin/tool.rs")));
+        assert!(!analyzer.is_excluded(Path::new("generated_types.rs")));
+    }
+
+    #[test]
+    fn test_shared_strings_and_light_mode() {
+        let source = "
+            /// Modes
+            #[repr(u8)]
+            #[non_exhaustive]
+            pub enum Mode { A, B }
+            #[inline]
+            pub fn first(name: &str) -> Option<u8> { name.parse().ok().unwrap() }
+            pub fn second(other: &str) {}
+        ";
+        let full = RustAnalyzer::new().analyze_source(source).unwrap();
+        let (AnalyzedItem::Function(first), AnalyzedItem::Function(second)) = (&full[1], &full[2])
+        else {
+            panic!("Expected functions");
+        };
+        assert!(Arc::ptr_eq(
+            &first.parameters[0].ty,
+            &second.parameters[0].ty
+        ));
+        assert!(Arc::ptr_eq(&first.module_path, &second.module_path));
+        assert_eq!(first.attributes, ["#[inline]"]);
+        assert_eq!(first.panics.total(), 1);
+
+        let light = RustAnalyzer::new()
+            .with_light(true)
+            .analyze_source(source)
+            .unwrap();
+        let AnalyzedItem::Enum(mode) = &light[0] else {
+            panic!("Expected enum");
+        };
+        assert_eq!(mode.attributes, ["#[repr(u8)]"]);
+        let AnalyzedItem::Function(first) = &light[1] else {
+            panic!("Expected function");
+        };
+        assert!(first.attributes.is_empty());
+        assert_eq!(first.panics.total(), 0);
+        assert_eq!(first.signature, "fn first(name: &str) -> Option<u8>");
+        assert_eq!(light[0].documentation(), Some("Modes"));
+    }
+
+    #[test]
+    fn test_items_stream_in_order() {
+        let source = "
+            pub mod outer {
+                pub mod inner { pub fn deep() {} }
+                fn hidden() {}
+            }
+            pub struct After;
+        ";
+        let analyzer = RustAnalyzer::new().with_private(false);
+        let mut items = analyzer
+            .analyze_source_iter(source, None, vec!["root".to_string()])
+            .unwrap();
+        let first = items.next().unwrap();
+        assert_eq!(first.qualified_name(), "root::outer::inner::deep");
+        let rest: Vec<String> = items.map(|i| i.qualified_name()).collect();
+        assert_eq!(rest, ["root::outer::inner", "root::outer", "root::After"]);
+    }
+
+    #[test]
+    fn test_recovers_items_around_syntax_errors() {
+        let source = "/// Kept\n#[derive(Debug)]\npub struct A;\n\nfn broken() {\n    let = 1;\n}\n\nconst B: S = S { x: 1 };\nimpl A {\n    fn ok(&self) {}\n}\n";
+        assert_eq!(item_chunks(source), [(1, 3), (5, 7), (9, 9), (10, 12)]);
+        let (items, failures) = RustAnalyzer::new().analyze_source_recovering(
+            source,
+            Some(PathBuf::from("src/lib.rs")),
+            Vec::new(),
+        );
+        let names: Vec<&str> = items.iter().map(|i| i.name()).collect();
+        assert_eq!(names, ["A", "B", "A"]);
+        assert_eq!(items[0].documentation(), Some("Kept"));
+        // Lines are those of the file, not of the chunk
+        assert_eq!(items[2].source_location().and_then(|l| l.line), Some(10));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].lines, (5, 7));
+
+        // Unbalanced braces can't be split into token trees; unindented lines start items
+        let unbalanced = "pub fn open( {\n\n/// Kept\npub fn after()\nwhere\n    u8: Copy,\n{\n}\n";
+        assert_eq!(item_chunks(unbalanced), [(1, 1), (3, 8)]);
+        let (items, failures) =
+            RustAnalyzer::new().analyze_source_recovering(unbalanced, None, Vec::new());
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name(), "after");
+        assert_eq!(failures[0].lines, (1, 1));
+    }
+
+    #[test]
+    fn test_parses_by_edition() {
+        // 2024: `gen` blocks, `unsafe extern` with `safe` items, unsafe attributes,
+        // precise capturing
+        let source =
+            "pub fn numbers() -> impl Iterator<Item = u8> {\n    gen move { yield 1; }\n}\n\
+                      pub async fn stream() { let _ = async gen { yield 2; }; }\n\
+                      unsafe extern \"C\" { pub safe fn abs(x: i32) -> i32; }\n\
+                      #[unsafe(no_mangle)]\npub extern \"C\" fn exported() {}\n\
+                      pub fn capture<'a>(x: &'a u8) -> impl Sized + use<'a> { x }\n\
+                      pub fn after() {}\n";
+        assert!(RustAnalyzer::new().analyze_source(source).is_err());
+        let analyzer = RustAnalyzer::new().with_edition(Edition::E2024);
+        let items = analyzer
+            .analyze_source_with_path(source, Some(PathBuf::from("src/lib.rs")))
+            .unwrap();
+        let names: Vec<&str> = items.iter().map(|i| i.name()).collect();
+        assert_eq!(names, ["numbers", "stream", "exported", "capture", "after"]);
+        assert_eq!(items[4].source_location().and_then(|l| l.line), Some(9));
+
+        // 2015: `try` and `async` are names
+        let source = "pub fn try() {}\npub struct async;\npub fn run() { try!(go()); }\n";
+        assert!(RustAnalyzer::new().analyze_source(source).is_err());
+        let items = RustAnalyzer::new()
+            .with_edition(Edition::E2015)
+            .analyze_source(source)
+            .unwrap();
+        assert_eq!(items.len(), 3);
+    }
 }
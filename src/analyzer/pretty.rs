@@ -0,0 +1,159 @@
+//! Source text for syntax nodes, printed with `prettyplease`
+//!
+//! `to_token_stream().to_string()` puts a space between every token
+//! (`Vec < String >`, `& 'a mut Foo`). Each node is printed inside a throwaway
+//! item, then cut back out, so the text reads like rustfmt output.
+
+use quote::ToTokens;
+use syn::{
+    parse_quote, Attribute, Expr, File, GenericParam, Item, Pat, Path, Signature, Type,
+    TypeParamBound, WhereClause, WherePredicate,
+};
+
+/// `fn name<..>(..) -> R` plus its `where` clause, without a body. Long signatures
+/// are split over several lines the way rustfmt would.
+pub fn signature(sig: &Signature) -> String {
+    let text = unparse(parse_quote!(#sig {}));
+    let text = text.trim_end();
+    text.strip_suffix("{}")
+        .unwrap_or(text)
+        .trim_end()
+        .to_string()
+}
+
+pub fn ty(ty: &Type) -> String {
+    cut(parse_quote!(type __T = #ty;), "type __T = ", ";").unwrap_or_else(|| tokens(ty))
+}
+
+pub fn expr(expr: &Expr) -> String {
+    cut(
+        parse_quote!(const __C: () = #expr;),
+        "const __C: () = ",
+        ";",
+    )
+    .unwrap_or_else(|| tokens(expr))
+}
+
+pub fn path(path: &Path) -> String {
+    cut(parse_quote!(type __T = #path;), "type __T = ", ";").unwrap_or_else(|| tokens(path))
+}
+
+pub fn pat(pat: &Pat) -> String {
+    cut(parse_quote!(fn __f(#pat: __T) {}), "fn __f(", ": __T) {}").unwrap_or_else(|| tokens(pat))
+}
+
+pub fn bound(bound: &TypeParamBound) -> String {
+    cut(
+        parse_quote!(fn __f() where __T: #bound {}),
+        "fn __f() where __T: ",
+        ", {}",
+    )
+    .unwrap_or_else(|| tokens(bound))
+}
+
+pub fn generic_param(param: &GenericParam) -> String {
+    cut(parse_quote!(fn __f<#param>() {}), "fn __f<", ">() {}").unwrap_or_else(|| tokens(param))
+}
+
+pub fn where_predicate(predicate: &WherePredicate) -> String {
+    cut(
+        parse_quote!(fn __f() where #predicate {}),
+        "fn __f() where ",
+        ", {}",
+    )
+    .unwrap_or_else(|| tokens(predicate))
+}
+
+/// `where A: B, C: D` on one line
+pub fn where_clause(clause: &WhereClause) -> String {
+    let predicates: Vec<String> = clause.predicates.iter().map(where_predicate).collect();
+    format!("where {}", predicates.join(", "))
+}
+
+/// `#[...]` / `#![...]`
+pub fn attribute(attr: &Attribute) -> String {
+    cut(parse_quote!(#attr struct __S;), "", " struct __S;").unwrap_or_else(|| tokens(attr))
+}
+
+fn tokens(node: &impl ToTokens) -> String {
+    node.to_token_stream().to_string()
+}
+
+fn unparse(item: Item) -> String {
+    prettyplease::unparse(&File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: vec![item],
+    })
+}
+
+/// The text between `prefix` and `suffix` of `item` printed on one line
+fn cut(item: Item, prefix: &str, suffix: &str) -> Option<String> {
+    let text = one_line(&unparse(item));
+    let inner = text.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    Some(inner.to_string())
+}
+
+/// Join lines that prettyplease broke, dropping the trailing comma it adds before a
+/// closing delimiter on its own line
+fn one_line(text: &str) -> String {
+    let mut out = String::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let closes = line.starts_with(['>', ')', ']', '}']);
+        if closes && out.ends_with(',') && !line.starts_with('}') {
+            out.pop();
+        }
+        let glue =
+            !(out.is_empty() || out.ends_with(['<', '(', '[']) || closes && !line.starts_with('}'));
+        if glue {
+            out.push(' ');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_fragments() {
+        let sig: Signature = parse_quote!(fn get<'a, T: Clone>(map: &'a HashMap<String, Vec<T>>, key: &str) -> Option<&'a T> where T: Send);
+        assert_eq!(
+            signature(&sig),
+            "fn get<'a, T: Clone>(map: &'a HashMap<String, Vec<T>>, key: &str) -> Option<&'a T>\nwhere\n    T: Send,"
+        );
+        assert_eq!(
+            ty(&parse_quote!(Result<Vec<&'a mut Foo>, crate::Error>)),
+            "Result<Vec<&'a mut Foo>, crate::Error>"
+        );
+        assert_eq!(expr(&parse_quote!(1 << 4)), "1 << 4");
+        assert_eq!(path(&parse_quote!(serde::Serialize)), "serde::Serialize");
+        assert_eq!(pat(&parse_quote!(mut buf)), "mut buf");
+        assert_eq!(bound(&parse_quote!(Fn(u8) -> u8)), "Fn(u8) -> u8");
+        assert_eq!(
+            generic_param(&parse_quote!(const N: usize = 4)),
+            "const N: usize = 4"
+        );
+        let clause: WhereClause = parse_quote!(where T: Into<String>, 'a: 'b);
+        assert_eq!(where_clause(&clause), "where T: Into<String>, 'a: 'b");
+        let attr: Attribute = parse_quote!(#[tokio::main(flavor = "current_thread")]);
+        assert_eq!(
+            attribute(&attr),
+            "#[tokio::main(flavor = \"current_thread\")]"
+        );
+    }
+
+    #[test]
+    fn test_long_types_stay_on_one_line() {
+        let long: Type = syn::parse_str(
+            "Box<dyn Fn(VeryLongArgumentTypeName, AnotherVeryLongArgumentTypeName) \
+             -> Result<SomeOutputType, SomeErrorType> + Send + Sync>",
+        )
+        .unwrap();
+        let text = ty(&long);
+        assert!(!text.contains('\n'));
+        assert_eq!(syn::parse_str::<Type>(&text).unwrap(), long);
+    }
+}
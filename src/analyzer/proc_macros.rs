@@ -0,0 +1,167 @@
+//! Macros exported by a proc-macro crate
+//!
+//! A proc-macro crate's public API is its `#[proc_macro]`, `#[proc_macro_derive]`
+//! and `#[proc_macro_attribute]` functions, but callers never see those functions:
+//! they write `#[derive(Name)]`, `#[name]` or `name!(..)`. [`ProcMacro::of`] reads
+//! what a function exports from its attributes, including the helper attributes a
+//! derive accepts (`attributes(serde)` lets the input use `#[serde(..)]`).
+
+use syn::punctuated::Punctuated;
+use syn::{Meta, Token};
+
+use super::types::{AnalyzedItem, FunctionInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcMacroKind {
+    /// `#[proc_macro_derive(Name)]`
+    Derive,
+    /// `#[proc_macro_attribute]`
+    Attribute,
+    /// `#[proc_macro]`
+    FunctionLike,
+}
+
+impl ProcMacroKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Derive => "derive macro",
+            Self::Attribute => "attribute macro",
+            Self::FunctionLike => "function-like macro",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcMacro {
+    /// The name callers use, e.g. `Serialize` for `#[derive(Serialize)]`
+    pub name: String,
+    pub kind: ProcMacroKind,
+    /// The function implementing it
+    pub function: String,
+    /// Helper attributes a derive allows on its input
+    pub helpers: Vec<String>,
+}
+
+impl ProcMacro {
+    /// The macro `func` exports, if it is a proc macro
+    pub fn of(func: &FunctionInfo) -> Option<Self> {
+        func.attributes.iter().find_map(|attr| {
+            let meta = attr.strip_prefix("#[")?.strip_suffix(']')?;
+            Self::from_meta(&syn::parse_str(meta).ok()?, &func.name)
+        })
+    }
+
+    fn from_meta(meta: &Meta, function: &str) -> Option<Self> {
+        let exported = |kind| Self {
+            name: function.to_string(),
+            kind,
+            function: function.to_string(),
+            helpers: Vec::new(),
+        };
+        let path = meta.path();
+        if path.is_ident("proc_macro") {
+            return Some(exported(ProcMacroKind::FunctionLike));
+        }
+        if path.is_ident("proc_macro_attribute") {
+            return Some(exported(ProcMacroKind::Attribute));
+        }
+        if !path.is_ident("proc_macro_derive") {
+            return None;
+        }
+        // `proc_macro_derive(Name, attributes(a, b))`
+        let Meta::List(list) = meta else {
+            return None;
+        };
+        let args = list
+            .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .ok()?;
+        let mut args = args.iter();
+        let name = args.next()?.path().get_ident()?.to_string();
+        let mut helpers = Vec::new();
+        for arg in args {
+            if let Meta::List(list) = arg {
+                if list.path.is_ident("attributes") {
+                    if let Ok(names) =
+                        list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                    {
+                        helpers.extend(
+                            names
+                                .iter()
+                                .filter_map(|m| m.path().get_ident())
+                                .map(|i| i.to_string()),
+                        );
+                    }
+                }
+            }
+        }
+        Some(Self {
+            name,
+            kind: ProcMacroKind::Derive,
+            function: function.to_string(),
+            helpers,
+        })
+    }
+
+    /// How a caller invokes it
+    pub fn usage(&self) -> String {
+        match self.kind {
+            ProcMacroKind::Derive => format!("#[derive({})]", self.name),
+            ProcMacroKind::Attribute => format!("#[{}]", self.name),
+            ProcMacroKind::FunctionLike => format!("{}!(..)", self.name),
+        }
+    }
+}
+
+/// Every macro exported by the items' functions, in item order
+pub fn exported(items: &[AnalyzedItem]) -> Vec<ProcMacro> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            AnalyzedItem::Function(f) => ProcMacro::of(f),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_exported_macros() {
+        let source = r#"
+            use proc_macro::TokenStream;
+
+            #[proc_macro_derive(Serialize, attributes(serde))]
+            pub fn derive_serialize(input: TokenStream) -> TokenStream { input }
+
+            #[proc_macro_derive(Builder)]
+            pub fn derive_builder(input: TokenStream) -> TokenStream { input }
+
+            #[proc_macro_attribute]
+            pub fn route(attr: TokenStream, item: TokenStream) -> TokenStream { item }
+
+            #[proc_macro]
+            pub fn sql(input: TokenStream) -> TokenStream { input }
+
+            pub fn helper() {}
+        "#;
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        let macros = exported(&items);
+        let usages: Vec<String> = macros.iter().map(ProcMacro::usage).collect();
+        assert_eq!(
+            usages,
+            vec![
+                "#[derive(Serialize)]",
+                "#[derive(Builder)]",
+                "#[route]",
+                "sql!(..)"
+            ]
+        );
+        assert_eq!(macros[0].function, "derive_serialize");
+        assert_eq!(macros[0].helpers, vec!["serde"]);
+        assert!(macros[1].helpers.is_empty());
+        assert_eq!(macros[2].kind, ProcMacroKind::Attribute);
+    }
+}
@@ -0,0 +1,369 @@
+//! Structured search queries
+//!
+//! The search bar accepts free text mixed with `key:value` filters, e.g.
+//! `derive:Serialize vis:pub Config`. [`Query::parse`] turns the raw input into
+//! a small AST that [`Query::matches`] evaluates against analyzed items. The TUI
+//! search bar and `oracle inspect --query` share this parser.
+
+use super::types::{AnalyzedItem, Visibility};
+
+/// Filter keys understood by the query parser, with a short hint for completion.
+pub const FILTER_KEYS: &[(&str, &str)] = &[
+    ("derive:", "derived trait, e.g. derive:Serialize"),
+    ("attr:", "attribute, e.g. attr:tokio::main"),
+    ("vis:", "visibility: pub, pub(crate), pub(super), private"),
+    ("async:", "async functions: true / false"),
+    ("returns:", "return type contains, e.g. returns:Result"),
+    ("doc:", "has doc comments: true / false"),
+    (
+        "kind:",
+        "item kind: fn, struct, enum, trait, impl, mod, type, const, static",
+    ),
+    ("mod:", "inside a module, e.g. mod:analyzer or mod:ui::app"),
+    ("file:", "source path contains, e.g. file:parser.rs"),
+    ("cfg:", "compiled under a cfg containing, e.g. cfg:windows"),
+];
+
+/// A single structured filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// `derive:Name` — struct/enum derives a trait (last path segment compared)
+    Derive(String),
+    /// `attr:path` — item carries an attribute containing the path
+    Attr(String),
+    /// `vis:pub(crate)` — exact visibility
+    Vis(Visibility),
+    /// `async:true|false` — async-ness of functions
+    Async(bool),
+    /// `returns:Type` — function return type contains the text
+    Returns(String),
+    /// `doc:true|false` — whether the item has doc comments (impl blocks never match)
+    Doc(bool),
+    /// `kind:trait` — item kind, as [`AnalyzedItem::kind`] names it
+    Kind(&'static str),
+    /// `mod:ui::app` — the module path contains these consecutive segments
+    Module(String),
+    /// `file:parser.rs` — the source file's path contains the text
+    File(String),
+    /// `cfg:windows` — one of the item's `#[cfg(..)]` predicates (spaces ignored)
+    /// contains the text
+    Cfg(String),
+}
+
+impl Filter {
+    /// Parse a `key:value` token. Returns `None` for unknown keys or empty values,
+    /// so such tokens are treated as plain text.
+    pub fn parse(token: &str) -> Option<Self> {
+        let (key, value) = token.split_once(':')?;
+        if value.is_empty() || value.starts_with(':') {
+            return None;
+        }
+        match key.to_lowercase().as_str() {
+            "derive" => Some(Filter::Derive(value.to_string())),
+            "attr" => Some(Filter::Attr(value.to_string())),
+            "vis" => parse_visibility(value).map(Filter::Vis),
+            "async" => parse_bool(value).map(Filter::Async),
+            "returns" => Some(Filter::Returns(value.to_string())),
+            "doc" => parse_bool(value).map(Filter::Doc),
+            "kind" => parse_kind(value).map(Filter::Kind),
+            "mod" => Some(Filter::Module(value.to_lowercase())),
+            "file" => Some(Filter::File(value.to_lowercase())),
+            "cfg" => Some(Filter::Cfg(value.to_lowercase())),
+            _ => None,
+        }
+    }
+
+    /// Whether the item satisfies this filter
+    pub fn matches(&self, item: &AnalyzedItem) -> bool {
+        match self {
+            Filter::Derive(name) => {
+                let derives = match item {
+                    AnalyzedItem::Struct(s) => &s.derives,
+                    AnalyzedItem::Enum(e) => &e.derives,
+                    _ => return false,
+                };
+                let wanted = last_segment(name).to_lowercase();
+                derives
+                    .iter()
+                    .any(|d| last_segment(d).to_lowercase() == wanted)
+            }
+            Filter::Attr(path) => {
+                let attrs = match item {
+                    AnalyzedItem::Function(f) => &f.attributes,
+                    AnalyzedItem::Struct(s) => &s.attributes,
+                    _ => return false,
+                };
+                let wanted = compact(path);
+                attrs.iter().any(|a| compact(a).contains(&wanted))
+            }
+            Filter::Vis(vis) => item.visibility() == Some(*vis),
+            Filter::Async(wanted) => match item {
+                AnalyzedItem::Function(f) => f.is_async == *wanted,
+                _ => !*wanted,
+            },
+            Filter::Returns(ty) => match item {
+                AnalyzedItem::Function(f) => {
+                    let ret = f.return_type.as_deref().unwrap_or("()");
+                    compact(ret).contains(&compact(ty))
+                }
+                _ => false,
+            },
+            Filter::Doc(wanted) => {
+                !matches!(item, AnalyzedItem::Impl(_))
+                    && item.documentation().is_some_and(|d| !d.trim().is_empty()) == *wanted
+            }
+            Filter::Kind(kind) => item.kind() == *kind,
+            Filter::Module(path) => {
+                let wanted: Vec<&str> = path.split("::").collect();
+                item.module_path().windows(wanted.len()).any(|w| {
+                    w.iter()
+                        .zip(&wanted)
+                        .all(|(a, b)| a.eq_ignore_ascii_case(b))
+                })
+            }
+            Filter::File(text) => item
+                .source_location()
+                .and_then(|loc| loc.file.as_deref())
+                .is_some_and(|file| {
+                    let file = file.to_string_lossy().replace('\\', "/");
+                    file.to_lowercase().contains(text.as_str())
+                }),
+            Filter::Cfg(text) => item.cfg().iter().any(|cfg| {
+                let cfg: String = cfg.split_whitespace().collect();
+                cfg.to_lowercase().contains(text.as_str())
+            }),
+        }
+    }
+}
+
+/// Parsed search query: free-text terms plus structured filters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    /// Lowercased free-text terms; every term must occur in the item name
+    pub terms: Vec<String>,
+    pub filters: Vec<Filter>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let mut query = Query::default();
+        for token in input.split_whitespace() {
+            // A bare key (`derive:`) is still being typed; ignore it
+            if FILTER_KEYS
+                .iter()
+                .any(|(key, _)| key.eq_ignore_ascii_case(token))
+            {
+                continue;
+            }
+            match Filter::parse(token) {
+                Some(filter) => query.filters.push(filter),
+                None => query.terms.push(token.to_lowercase()),
+            }
+        }
+        query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.filters.is_empty()
+    }
+
+    pub fn has_filters(&self) -> bool {
+        !self.filters.is_empty()
+    }
+
+    /// Free-text part of the query, used for fuzzy completion
+    pub fn text(&self) -> String {
+        self.terms.join(" ")
+    }
+
+    pub fn matches(&self, item: &AnalyzedItem) -> bool {
+        let name = item.name().to_lowercase();
+        self.terms.iter().all(|t| name.contains(t.as_str()))
+            && self.filters.iter().all(|f| f.matches(item))
+    }
+}
+
+/// Filter keys whose name starts with the last (partial) token of `input`.
+/// Returns nothing once the token already contains a `:`.
+pub fn suggest_filter_keys(input: &str) -> Vec<(&'static str, &'static str)> {
+    if input.ends_with(char::is_whitespace) {
+        return Vec::new();
+    }
+    let Some(token) = input.split_whitespace().last() else {
+        return Vec::new();
+    };
+    if token.contains(':') {
+        return Vec::new();
+    }
+    let token = token.to_lowercase();
+    FILTER_KEYS
+        .iter()
+        .filter(|(key, _)| key.starts_with(&token))
+        .copied()
+        .collect()
+}
+
+/// Replace the last whitespace-separated token of `input` with `replacement`.
+pub fn replace_last_token(input: &str, replacement: &str) -> String {
+    let trimmed = input.trim_end();
+    let start = trimmed
+        .rfind(char::is_whitespace)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    format!("{}{}", &trimmed[..start], replacement)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_visibility(value: &str) -> Option<Visibility> {
+    match compact(value).to_lowercase().as_str() {
+        "pub" | "public" => Some(Visibility::Public),
+        "pub(crate)" | "crate" => Some(Visibility::Crate),
+        "pub(super)" | "super" => Some(Visibility::Super),
+        "pub(self)" | "self" => Some(Visibility::SelfOnly),
+        "private" | "priv" => Some(Visibility::Private),
+        _ => None,
+    }
+}
+
+fn parse_kind(value: &str) -> Option<&'static str> {
+    match value.to_lowercase().as_str() {
+        "fn" | "function" => Some("fn"),
+        "struct" => Some("struct"),
+        "enum" => Some("enum"),
+        "trait" => Some("trait"),
+        "impl" => Some("impl"),
+        "mod" | "module" => Some("mod"),
+        "type" | "alias" => Some("type"),
+        "const" => Some("const"),
+        "static" => Some("static"),
+        _ => None,
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+/// Strip whitespace so `# [tokio :: main]` (token-stream output) matches `tokio::main`.
+fn compact(s: &str) -> String {
+    s.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    fn items() -> Vec<AnalyzedItem> {
+        let source = r#"
+            /// Settings
+            #[derive(Debug, serde::Serialize)]
+            pub struct Config {}
+
+            #[cfg(all(unix, feature = "tui"))]
+            pub(crate) struct Inner {}
+
+            #[tokio::main]
+            pub async fn main() {}
+
+            pub fn load() -> Result<Config, String> { todo!() }
+        "#;
+        RustAnalyzer::new()
+            .with_private(true)
+            .analyze_source(source)
+            .unwrap()
+    }
+
+    fn names(query: &str) -> Vec<String> {
+        let query = Query::parse(query);
+        items()
+            .iter()
+            .filter(|i| query.matches(i))
+            .map(|i| i.name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_mixed_query() {
+        let q = Query::parse("derive:Serialize Conf vis:pub unknown:x");
+        assert_eq!(q.terms, vec!["conf".to_string(), "unknown:x".to_string()]);
+        assert_eq!(
+            q.filters,
+            vec![
+                Filter::Derive("Serialize".into()),
+                Filter::Vis(Visibility::Public)
+            ]
+        );
+        // Qualified paths are plain text, not filters
+        assert!(Query::parse("serde::de").filters.is_empty());
+        assert!(Query::parse("derive:").is_empty());
+    }
+
+    #[test]
+    fn test_filters_match_items() {
+        assert_eq!(names("derive:Serialize"), vec!["Config"]);
+        assert_eq!(names("vis:pub(crate)"), vec!["Inner"]);
+        assert_eq!(names("attr:tokio::main"), vec!["main"]);
+        assert_eq!(names("async:true"), vec!["main"]);
+        assert_eq!(names("returns:Result"), vec!["load"]);
+        assert_eq!(names("vis:pub lo"), vec!["load"]);
+        assert_eq!(names("doc:true"), vec!["Config"]);
+        assert!(names("doc:false").contains(&"load".to_string()));
+        assert_eq!(names("cfg:unix"), vec!["Inner"]);
+        assert_eq!(names(r#"cfg:feature="tui""#), vec!["Inner"]);
+    }
+
+    #[test]
+    fn test_scoping_filters() {
+        let analyzer = RustAnalyzer::new();
+        let mut items = analyzer
+            .analyze_source_with_path(
+                "pub trait Parse {}\npub fn parse() {}",
+                Some("/p/src/analyzer/parser.rs".into()),
+            )
+            .unwrap();
+        items.extend(
+            analyzer
+                .analyze_source_with_path(
+                    "pub trait Render {}",
+                    Some("/p/src/ui/app/mod.rs".into()),
+                )
+                .unwrap(),
+        );
+        let names = |query: &str| {
+            let query = Query::parse(query);
+            items
+                .iter()
+                .filter(|i| query.matches(i))
+                .map(|i| i.name().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("kind:trait"), vec!["Parse", "Render"]);
+        assert_eq!(names("kind:function"), vec!["parse"]);
+        assert_eq!(names("mod:analyzer kind:trait"), vec!["Parse"]);
+        assert_eq!(names("mod:ui::app"), vec!["Render"]);
+        assert!(names("mod:ui::analyzer").is_empty());
+        assert_eq!(names("file:parser.rs vis:pub pa"), vec!["Parse", "parse"]);
+        // An unknown kind is plain text
+        assert_eq!(Query::parse("kind:gadget").terms, vec!["kind:gadget"]);
+    }
+
+    #[test]
+    fn test_suggest_and_replace() {
+        let keys: Vec<_> = suggest_filter_keys("Foo de")
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec!["derive:"]);
+        assert!(suggest_filter_keys("derive:").is_empty());
+        assert_eq!(replace_last_token("Foo de", "derive:"), "Foo derive:");
+        assert_eq!(replace_last_token("de", "derive:"), "derive:");
+    }
+}
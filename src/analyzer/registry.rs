@@ -2,11 +2,13 @@
 //!
 //! Scans ~/.cargo/registry to find and analyze installed crates
 
-use crate::analyzer::{AnalyzedItem, RustAnalyzer};
+use crate::analyzer::{AnalyzedItem, Edition, ModuleLayout, RustAnalyzer};
 use crate::error::Result;
+use crate::utils::rust_files;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Parsed Cargo.toml package metadata: description, authors, license, repository, documentation, keywords, categories
 type CargoTomlMeta = (
@@ -39,6 +41,8 @@ pub struct InstalledCrate {
 pub struct CrateRegistry {
     crates: HashMap<String, Vec<InstalledCrate>>,
     registry_path: PathBuf,
+    /// [`Self::scan`] has run, even if it found nothing
+    scanned: bool,
 }
 
 impl CrateRegistry {
@@ -50,6 +54,7 @@ impl CrateRegistry {
         Self {
             crates: HashMap::new(),
             registry_path,
+            scanned: false,
         }
     }
 
@@ -58,14 +63,17 @@ impl CrateRegistry {
         Self {
             crates: HashMap::new(),
             registry_path: path,
+            scanned: false,
         }
     }
 
     /// Scan the registry for installed crates
     pub fn scan(&mut self) -> Result<()> {
         self.crates.clear();
+        self.scanned = true;
 
         if !self.registry_path.exists() {
+            tracing::debug!(path = %self.registry_path.display(), "no cargo registry");
             return Ok(());
         }
 
@@ -79,6 +87,7 @@ impl CrateRegistry {
             }
         }
 
+        tracing::debug!(crates = self.crates.len(), "scanned cargo registry");
         Ok(())
     }
 
@@ -312,6 +321,11 @@ impl CrateRegistry {
         self.crates.len()
     }
 
+    /// Whether the registry was scanned, so an empty one isn't walked again
+    pub fn is_scanned(&self) -> bool {
+        self.scanned
+    }
+
     /// Analyze a specific installed crate
     pub fn analyze_crate(&self, name: &str, version: Option<&str>) -> Result<Vec<AnalyzedItem>> {
         let crate_info = match version {
@@ -324,42 +338,72 @@ impl CrateRegistry {
             None => return Ok(vec![]),
         };
 
-        let analyzer = RustAnalyzer::new();
-        let src_path = crate_info.path.join("src");
-
         let mut items = Vec::new();
-        // Use crate name (with underscores instead of hyphens) as base module path
-        let crate_module_name = name.replace('-', "_");
-
-        if src_path.exists() {
-            Self::analyze_directory(&analyzer, &src_path, &mut items, &crate_module_name)?;
-        }
-
+        let never = AtomicBool::new(false);
+        Self::analyze_sources(&crate_info.path, name, false, &never, |file_items| {
+            items.extend(file_items)
+        });
         Ok(items)
     }
 
-    fn analyze_directory(
-        analyzer: &RustAnalyzer,
-        dir: &Path,
-        items: &mut Vec<AnalyzedItem>,
-        crate_name: &str,
-    ) -> Result<()> {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                Self::analyze_directory(analyzer, &path, items, crate_name)?;
-            } else if path.extension().is_some_and(|ext| ext == "rs") {
-                // Build module path: crate_name + path from src/
-                let module_path = Self::build_module_path(&path, crate_name);
-                if let Ok(file_items) = analyzer.analyze_file_with_module(&path, module_path) {
-                    items.extend(file_items);
+    /// Analyze the `src/` files of a crate at `crate_path` one file at a time, passing each
+    /// file's items to `on_file`. Returns `false` if `cancel` was set before all files were read.
+    /// `light` analyzes in [light mode](RustAnalyzer::with_light).
+    pub fn analyze_sources(
+        crate_path: &Path,
+        name: &str,
+        light: bool,
+        cancel: &AtomicBool,
+        mut on_file: impl FnMut(Vec<AnalyzedItem>),
+    ) -> bool {
+        let src_path = crate_path.join("src");
+        if !src_path.exists() {
+            return true;
+        }
+        let edition = Edition::from_manifest(&crate_path.join("Cargo.toml"));
+        let analyzer = RustAnalyzer::new()
+            .with_light(light)
+            .with_edition(edition.unwrap_or_default());
+        let layout = ModuleLayout::scan(&src_path, analyzer.edition());
+        // Use crate name (with underscores instead of hyphens) as base module path
+        let crate_module_name = name.replace('-', "_");
+        // Unreadable files in a registry crate are only logged
+        let (files, skipped) = rust_files(&src_path);
+        for s in skipped {
+            tracing::debug!(path = %s.path.display(), reason = %s.reason, "skipped");
+        }
+        for path in files {
+            if cancel.load(Ordering::Relaxed) {
+                return false;
+            }
+            // Crate name, then the file's module as declared or the path from src/
+            let module_path = match layout.module_path(&path) {
+                Some(module) => std::iter::once(crate_module_name.clone())
+                    .chain(module.iter().cloned())
+                    .collect(),
+                None => Self::build_module_path(&path, &crate_module_name),
+            };
+            let source = match fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(e) => {
+                    tracing::debug!(path = %path.display(), error = %e, "skipped");
+                    continue;
                 }
+            };
+            // Code that doesn't parse only loses the items it's in
+            let (file_items, failures) =
+                analyzer.analyze_source_recovering(&source, Some(path.clone()), module_path);
+            for failure in failures {
+                tracing::debug!(
+                    path = %path.display(),
+                    lines = ?failure.lines,
+                    error = %failure.message,
+                    "skipped"
+                );
             }
+            on_file(file_items);
         }
-
-        Ok(())
+        true
     }
 
     /// Build module path from file path relative to src/
@@ -442,4 +486,56 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_empty_registry_counts_as_scanned() {
+        let mut registry = CrateRegistry::with_path(PathBuf::from("/nonexistent/registry"));
+        assert!(!registry.is_scanned());
+        registry.scan().unwrap();
+        assert!(registry.is_scanned());
+        assert_eq!(registry.count(), 0);
+    }
+
+    #[test]
+    fn test_analyze_sources_cancellation() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut files = 0;
+        let finished = CrateRegistry::analyze_sources(
+            root,
+            "oracle-tui",
+            false,
+            &AtomicBool::new(false),
+            |_| files += 1,
+        );
+        assert!(finished);
+        assert!(files > 10);
+
+        let mut called = false;
+        let finished = CrateRegistry::analyze_sources(
+            root,
+            "oracle-tui",
+            false,
+            &AtomicBool::new(true),
+            |_| called = true,
+        );
+        assert!(!finished);
+        assert!(!called);
+    }
+
+    #[test]
+    fn test_analyze_sources_recovers_around_syntax_errors() {
+        let dir = std::env::temp_dir().join(format!("oracle-registry-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join("src/lib.rs"),
+            "pub fn before() {}\n\nfn broken() {\n    let = 1;\n}\n\npub fn after() {}\n",
+        )
+        .unwrap();
+        let mut names = Vec::new();
+        CrateRegistry::analyze_sources(&dir, "demo", false, &AtomicBool::new(false), |items| {
+            names.extend(items.iter().map(AnalyzedItem::qualified_name))
+        });
+        assert_eq!(names, ["demo::before", "demo::after"]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
@@ -0,0 +1,168 @@
+//! Spell check of doc comments
+//!
+//! Rather than a full dictionary, which would flag every identifier and bit of
+//! jargon, prose words are looked up in a bundled list of common misspellings
+//! (`misspellings.txt`, in the spirit of codespell). Code, links and anything that
+//! looks like an identifier are skipped; words in the project dictionary are
+//! never flagged.
+
+use std::collections::{HashMap, HashSet};
+
+use super::doc_links::for_each_doc;
+use super::types::{AnalyzedItem, SourceLocation};
+
+const MISSPELLINGS: &str = include_str!("misspellings.txt");
+
+/// A misspelled word in an item's documentation
+#[derive(Debug, Clone)]
+pub struct Misspelling {
+    /// Qualified name of the documented item, e.g. `config::Settings::load`
+    pub owner: String,
+    /// The word as written
+    pub word: String,
+    /// Correction with the word's capitalization
+    pub suggestion: String,
+    pub location: SourceLocation,
+}
+
+/// Misspelling list plus the words the project accepts
+#[derive(Debug, Clone)]
+pub struct SpellChecker {
+    corrections: HashMap<&'static str, &'static str>,
+    dictionary: HashSet<String>,
+}
+
+impl SpellChecker {
+    /// Checker with the bundled list; `dictionary` words are never flagged
+    pub fn new(dictionary: &[String]) -> Self {
+        let corrections = MISSPELLINGS
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(' '))
+            .collect();
+        Self {
+            corrections,
+            dictionary: dictionary.iter().map(|w| w.to_lowercase()).collect(),
+        }
+    }
+
+    /// Correction for `word`, or `None` when it's spelled fine or in the dictionary
+    pub fn correction(&self, word: &str) -> Option<String> {
+        let lower = word.to_lowercase();
+        if self.dictionary.contains(&lower) {
+            return None;
+        }
+        let right = *self.corrections.get(lower.as_str())?;
+        let mut chars = right.chars();
+        Some(match (word.chars().next(), chars.next()) {
+            (Some(c), Some(first)) if c.is_uppercase() => {
+                first.to_uppercase().chain(chars).collect()
+            }
+            _ => right.to_string(),
+        })
+    }
+
+    /// Misspelled words of one doc comment as `(word, suggestion)`, in order
+    pub fn check_doc(&self, doc: &str) -> Vec<(String, String)> {
+        prose_words(doc)
+            .into_iter()
+            .filter_map(|word| {
+                let suggestion = self.correction(word)?;
+                Some((word.to_string(), suggestion))
+            })
+            .collect()
+    }
+}
+
+/// Misspellings in the docs of `items` and their methods
+pub fn check_spelling(items: &[AnalyzedItem], checker: &SpellChecker) -> Vec<Misspelling> {
+    let mut found = Vec::new();
+    for_each_doc(items, |owner, _, doc, location| {
+        for (word, suggestion) in checker.check_doc(doc) {
+            found.push(Misspelling {
+                owner: owner.clone(),
+                word,
+                suggestion,
+                location: location.clone(),
+            });
+        }
+    });
+    found
+}
+
+/// Words of the prose in a doc comment: code blocks, inline code, link
+/// destinations, URLs and identifier-like tokens (`snake_case`, `CamelCase`,
+/// `a::b`, `x86`) left out
+fn prose_words(doc: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut in_code = false;
+    for line in doc.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        // Every other piece between backticks is inline code
+        for part in line.split('`').step_by(2) {
+            for token in part.split_whitespace() {
+                if token.contains("://") || token.contains("](") || token.starts_with('(') {
+                    continue;
+                }
+                let token = token.trim_matches(|c: char| !c.is_alphanumeric());
+                if is_prose_word(token) {
+                    words.push(token);
+                }
+            }
+        }
+    }
+    words
+}
+
+/// Letters (and inner apostrophes) only, with no capitals after the first
+fn is_prose_word(token: &str) -> bool {
+    token.len() > 1
+        && token.chars().all(|c| c.is_alphabetic() || c == '\'')
+        && !token.chars().skip(1).any(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_check_doc_skips_code_and_identifiers() {
+        let checker = SpellChecker::new(&[]);
+        let doc = "Recieve the `recieve_all` result, seperate from\n\
+                   [the docs](https://example.com/teh) and tehStruct.\n\
+                   ```\nlet occured = 1;\n```\n\
+                   It occured twice.";
+        assert_eq!(
+            checker.check_doc(doc),
+            vec![
+                ("Recieve".to_string(), "Receive".to_string()),
+                ("seperate".to_string(), "separate".to_string()),
+                ("occured".to_string(), "occurred".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dictionary_and_items() {
+        let source = "/// Wich one\npub struct Picker;\n\
+                      impl Picker {\n    /// Returns teh choice\n    pub fn pick(&self) {}\n}\n";
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        let found = check_spelling(&items, &SpellChecker::new(&[]));
+        let pairs: Vec<(&str, &str)> = found
+            .iter()
+            .map(|m| (m.owner.as_str(), m.word.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("Picker", "Wich"), ("Picker::pick", "teh")]);
+
+        let found = check_spelling(&items, &SpellChecker::new(&["TEH".to_string()]));
+        assert_eq!(found.len(), 1);
+    }
+}
@@ -0,0 +1,876 @@
+//! `Send` / `Sync` audit of the public API
+//!
+//! Auto traits are structural, so a type's thread safety follows from its fields.
+//! Field types are re-parsed and checked against the well-known `!Send` / `!Sync`
+//! std types (`Rc`, `RefCell`, raw pointers, lock guards, `dyn Trait` without the
+//! bound), then propagated through the crate's own types until nothing changes.
+//! Generic parameters are assumed thread-safe, so the verdicts are "likely".
+//!
+//! An `async fn` keeps its arguments alive for its whole body, so one taking a
+//! `!Send` argument (or `&T` of a `!Sync` type, `&self` included) returns a
+//! future that can't be spawned on a multi-threaded runtime.
+//!
+//! [`AutoTraits`] answers the same questions for one type, plus `Unpin`, with a
+//! third "unknown" answer when a field is a generic parameter or a type from
+//! another crate, and names the fields with interior mutability.
+
+use std::collections::{HashMap, HashSet};
+
+use super::pretty;
+use super::types::{
+    AnalyzedItem, EnumInfo, FunctionInfo, GenericsInfo, StructInfo, VariantFields, Visibility,
+};
+use super::usage::base_type_name;
+use syn::{GenericArgument, PathArguments, Type, TypeParamBound};
+
+/// Why a type is not `Send` / not `Sync` (`None` when it likely is)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadSafety {
+    pub not_send: Option<String>,
+    pub not_sync: Option<String>,
+}
+
+impl ThreadSafety {
+    fn both(reason: &str) -> Self {
+        Self {
+            not_send: Some(reason.to_string()),
+            not_sync: Some(reason.to_string()),
+        }
+    }
+
+    pub fn is_send(&self) -> bool {
+        self.not_send.is_none()
+    }
+
+    pub fn is_sync(&self) -> bool {
+        self.not_sync.is_none()
+    }
+
+    /// Keep the first reason found for each trait
+    fn merge(&mut self, other: Self) {
+        if self.not_send.is_none() {
+            self.not_send = other.not_send;
+        }
+        if self.not_sync.is_none() {
+            self.not_sync = other.not_sync;
+        }
+    }
+}
+
+/// A public struct or enum that is likely `!Send` and/or `!Sync`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeVerdict {
+    pub name: String,
+    pub safety: ThreadSafety,
+}
+
+/// Likely answer for one auto trait
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Yes,
+    /// Not implemented, with the field type responsible
+    No(String),
+    /// Hinges on something not visible here, e.g. "depends on `T`"
+    Unknown(String),
+}
+
+impl Verdict {
+    /// `No` beats `Unknown` beats `Yes`; the first reason is kept
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (no @ Verdict::No(_), _) | (_, no @ Verdict::No(_)) => no,
+            (unknown @ Verdict::Unknown(_), _) | (_, unknown @ Verdict::Unknown(_)) => unknown,
+            _ => Verdict::Yes,
+        }
+    }
+}
+
+/// `Send`, `Sync` and `Unpin` of one struct or enum, derived from its fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoTraits {
+    pub send: Verdict,
+    pub sync: Verdict,
+    pub unpin: Verdict,
+    /// Fields with interior mutability, e.g. `cache: RefCell`
+    pub interior_mutability: Vec<String>,
+}
+
+/// Std types with interior mutability, matched on the last path segment
+const INTERIOR_MUTABILITY: &[&str] = &[
+    "Cell",
+    "RefCell",
+    "UnsafeCell",
+    "OnceCell",
+    "Mutex",
+    "RwLock",
+    "OnceLock",
+];
+
+/// Std types whose auto traits follow their type arguments (or that have none)
+const KNOWN_STD: &[&str] = &[
+    "bool",
+    "char",
+    "str",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "f32",
+    "f64",
+    "String",
+    "Vec",
+    "VecDeque",
+    "HashMap",
+    "HashSet",
+    "BTreeMap",
+    "BTreeSet",
+    "BinaryHeap",
+    "LinkedList",
+    "Option",
+    "Result",
+    "Box",
+    "Rc",
+    "Arc",
+    "Weak",
+    "Cell",
+    "RefCell",
+    "UnsafeCell",
+    "OnceCell",
+    "Mutex",
+    "RwLock",
+    "OnceLock",
+    "MutexGuard",
+    "RwLockReadGuard",
+    "RwLockWriteGuard",
+    "NonNull",
+    "Pin",
+    "PhantomData",
+    "PhantomPinned",
+    "Cow",
+    "Path",
+    "PathBuf",
+    "OsStr",
+    "OsString",
+    "Duration",
+    "Instant",
+    "SystemTime",
+    "Range",
+    "Ordering",
+    "Self",
+];
+
+impl AutoTraits {
+    /// Verdicts for a struct or enum (`None` for other items), looking up the
+    /// crate's own field types in `items`
+    pub fn of(item: &AnalyzedItem, items: &[AnalyzedItem]) -> Option<Self> {
+        match item {
+            AnalyzedItem::Struct(s) => Some(Self::of_struct(s, items)),
+            AnalyzedItem::Enum(e) => Some(Self::of_enum(e, items)),
+            _ => None,
+        }
+    }
+
+    pub fn of_struct(st: &StructInfo, items: &[AnalyzedItem]) -> Self {
+        Self::from_fields(&st.name, &st.generics_info, struct_fields(st), items)
+    }
+
+    pub fn of_enum(en: &EnumInfo, items: &[AnalyzedItem]) -> Self {
+        Self::from_fields(&en.name, &en.generics_info, enum_fields(en), items)
+    }
+
+    fn from_fields(
+        name: &str,
+        generics: &GenericsInfo,
+        fields: Vec<(String, String)>,
+        items: &[AnalyzedItem],
+    ) -> Self {
+        let generics: HashSet<&str> = generics.types().map(|p| p.name.as_str()).collect();
+        let parsed: Vec<(String, Type)> = fields
+            .into_iter()
+            .filter_map(|(label, ty)| Some((label, syn::parse_str(&ty).ok()?)))
+            .collect();
+        let local_names: HashSet<&str> = items
+            .iter()
+            .filter(|i| {
+                matches!(
+                    i,
+                    AnalyzedItem::Struct(_) | AnalyzedItem::Enum(_) | AnalyzedItem::TypeAlias(_)
+                )
+            })
+            .map(|i| i.name())
+            .collect();
+        let scope = Scope {
+            generics: &generics,
+            local: &local_names,
+        };
+
+        let local = local_type_safety(items);
+        let safety = local.get(name).cloned().unwrap_or_else(|| {
+            let mut safety = ThreadSafety::default();
+            for (_, ty) in &parsed {
+                safety.merge(type_safety(ty, &local));
+            }
+            safety
+        });
+        let unknown = parsed.iter().find_map(|(_, ty)| scope.unknown(ty, false));
+        let overrides = explicit_impls(items);
+        let explicit = overrides.get(name).copied().unwrap_or_default();
+        let verdict = |implemented: Option<bool>, not: &Option<String>| match implemented {
+            Some(true) => Verdict::Yes,
+            _ => match (not, &unknown) {
+                (Some(reason), _) => Verdict::No(reason.clone()),
+                (None, Some(reason)) => Verdict::Unknown(reason.clone()),
+                (None, None) => Verdict::Yes,
+            },
+        };
+        let send = verdict(explicit.send, &safety.not_send);
+        let sync = verdict(explicit.sync, &safety.not_sync);
+
+        let not_unpin = local_not_unpin(items);
+        let unpin = match explicit.unpin {
+            Some(true) => Verdict::Yes,
+            Some(false) => Verdict::No("impl !Unpin".into()),
+            None => parsed.iter().fold(Verdict::Yes, |acc, (_, ty)| {
+                acc.merge(scope.unpin(ty, &not_unpin))
+            }),
+        };
+
+        let interior_mutability = parsed
+            .iter()
+            .filter_map(|(label, ty)| Some(format!("{}: {}", label, interior_mutability(ty)?)))
+            .collect();
+
+        Self {
+            send,
+            sync,
+            unpin,
+            interior_mutability,
+        }
+    }
+}
+
+/// Names the inspected type can see: its own type parameters and the crate's types
+struct Scope<'a> {
+    generics: &'a HashSet<&'a str>,
+    local: &'a HashSet<&'a str>,
+}
+
+impl Scope<'_> {
+    /// First part of `ty` the analysis can't judge. With `pinned_ok`, anything behind
+    /// a pointer is skipped, since pointers are `Unpin` whatever they point to.
+    fn unknown(&self, ty: &Type, pinned_ok: bool) -> Option<String> {
+        match ty {
+            Type::Path(p) => {
+                let first = p.path.segments.first()?.ident.to_string();
+                if p.qself.is_some() || self.generics.contains(first.as_str()) {
+                    let name = if self.generics.contains(first.as_str()) {
+                        first
+                    } else {
+                        pretty::ty(ty)
+                    };
+                    return Some(format!("depends on `{}`", name));
+                }
+                let last = p.path.segments.last()?;
+                let name = last.ident.to_string();
+                let std_path = matches!(first.as_str(), "std" | "core" | "alloc");
+                let known = std_path
+                    || KNOWN_STD.contains(&name.as_str())
+                    || name.starts_with("Atomic")
+                    || name.starts_with("NonZero");
+                if !known && !self.local.contains(name.as_str()) {
+                    return Some(format!("`{}` is from another crate", name));
+                }
+                if pinned_ok && matches!(name.as_str(), "Box" | "Rc" | "Arc" | "Pin") {
+                    return None;
+                }
+                type_args(&last.arguments)
+                    .into_iter()
+                    .find_map(|arg| self.unknown(arg, pinned_ok))
+            }
+            Type::Reference(_) | Type::Ptr(_) if pinned_ok => None,
+            Type::Reference(r) => self.unknown(&r.elem, pinned_ok),
+            Type::Ptr(p) => self.unknown(&p.elem, pinned_ok),
+            Type::Slice(s) => self.unknown(&s.elem, pinned_ok),
+            Type::Array(a) => self.unknown(&a.elem, pinned_ok),
+            Type::Paren(p) => self.unknown(&p.elem, pinned_ok),
+            Type::Group(g) => self.unknown(&g.elem, pinned_ok),
+            Type::Tuple(t) => t.elems.iter().find_map(|ty| self.unknown(ty, pinned_ok)),
+            _ => None,
+        }
+    }
+
+    fn unpin(&self, ty: &Type, not_unpin: &HashMap<String, String>) -> Verdict {
+        if let Some(reason) = pinning(ty, not_unpin) {
+            return Verdict::No(reason);
+        }
+        match self.unknown(ty, true) {
+            Some(reason) => Verdict::Unknown(reason),
+            None => Verdict::Yes,
+        }
+    }
+}
+
+/// `(label, type)` of every field of a struct or enum, e.g. `("Open.0", "File")`
+fn field_types(item: &AnalyzedItem) -> Option<Vec<(String, String)>> {
+    match item {
+        AnalyzedItem::Struct(s) => Some(struct_fields(s)),
+        AnalyzedItem::Enum(e) => Some(enum_fields(e)),
+        _ => None,
+    }
+}
+
+fn struct_fields(st: &StructInfo) -> Vec<(String, String)> {
+    st.fields
+        .iter()
+        .map(|f| (f.name.clone(), f.ty.to_string()))
+        .collect()
+}
+
+fn enum_fields(en: &EnumInfo) -> Vec<(String, String)> {
+    en.variants
+        .iter()
+        .flat_map(|v| match &v.fields {
+            VariantFields::Named(fields) => fields
+                .iter()
+                .map(|f| (format!("{}.{}", v.name, f.name), f.ty.to_string()))
+                .collect(),
+            VariantFields::Unnamed(types) => types
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| (format!("{}.{}", v.name, i), ty.clone()))
+                .collect(),
+            VariantFields::Unit => Vec::new(),
+        })
+        .collect()
+}
+
+fn type_args(arguments: &PathArguments) -> Vec<&Type> {
+    match arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                GenericArgument::Type(t) => Some(t),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Field types of every struct and enum, parsed, by type name
+fn parsed_fields(items: &[AnalyzedItem]) -> Vec<(&str, Vec<Type>)> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let tys = field_types(item)?
+                .iter()
+                .filter_map(|(_, ty)| syn::parse_str(ty).ok())
+                .collect();
+            Some((item.name(), tys))
+        })
+        .collect()
+}
+
+/// The interior-mutable std type inside `ty`, outermost first
+fn interior_mutability(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(p) => {
+            let last = p.path.segments.last()?;
+            let name = last.ident.to_string();
+            if INTERIOR_MUTABILITY.contains(&name.as_str()) || name.starts_with("Atomic") {
+                return Some(name);
+            }
+            type_args(&last.arguments)
+                .into_iter()
+                .find_map(interior_mutability)
+        }
+        Type::Reference(r) => interior_mutability(&r.elem),
+        Type::Slice(s) => interior_mutability(&s.elem),
+        Type::Array(a) => interior_mutability(&a.elem),
+        Type::Paren(p) => interior_mutability(&p.elem),
+        Type::Group(g) => interior_mutability(&g.elem),
+        Type::Tuple(t) => t.elems.iter().find_map(interior_mutability),
+        _ => None,
+    }
+}
+
+/// Why `ty` is `!Unpin`: `PhantomPinned` held by value, directly or via the
+/// crate's own types in `not_unpin`
+fn pinning(ty: &Type, not_unpin: &HashMap<String, String>) -> Option<String> {
+    match ty {
+        Type::Path(p) => {
+            let last = p.path.segments.last()?;
+            let name = last.ident.to_string();
+            match name.as_str() {
+                "PhantomPinned" => Some(name),
+                "Box" | "Rc" | "Arc" | "Pin" => None,
+                _ if not_unpin.contains_key(&name) => Some(format!("{} is !Unpin", name)),
+                _ => type_args(&last.arguments)
+                    .into_iter()
+                    .find_map(|arg| pinning(arg, not_unpin)),
+            }
+        }
+        Type::Slice(s) => pinning(&s.elem, not_unpin),
+        Type::Array(a) => pinning(&a.elem, not_unpin),
+        Type::Paren(p) => pinning(&p.elem, not_unpin),
+        Type::Group(g) => pinning(&g.elem, not_unpin),
+        Type::Tuple(t) => t.elems.iter().find_map(|ty| pinning(ty, not_unpin)),
+        _ => None,
+    }
+}
+
+/// The crate's structs and enums that are `!Unpin`, with the reason
+fn local_not_unpin(items: &[AnalyzedItem]) -> HashMap<String, String> {
+    let overrides = explicit_impls(items);
+    let parsed = parsed_fields(items);
+    let mut not_unpin: HashMap<String, String> = HashMap::new();
+    for (name, explicit) in &overrides {
+        if explicit.unpin == Some(false) {
+            not_unpin.insert(name.to_string(), "impl !Unpin".into());
+        }
+    }
+    for _ in 0..=parsed.len() {
+        let mut changed = false;
+        for (name, tys) in &parsed {
+            if not_unpin.contains_key(*name)
+                || overrides.get(name).is_some_and(|e| e.unpin.is_some())
+            {
+                continue;
+            }
+            if let Some(reason) = tys.iter().find_map(|ty| pinning(ty, &not_unpin)) {
+                not_unpin.insert(name.to_string(), reason);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    not_unpin
+}
+
+/// An `async fn` and, when its future is likely `!Send`, why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsyncFn {
+    /// `name` or `Type::name`
+    pub name: String,
+    pub is_public: bool,
+    /// Argument keeping the future from being `Send`, e.g. `cache: Rc<Cache>`
+    pub not_send: Option<String>,
+}
+
+/// Result of the audit over all analyzed items
+#[derive(Debug, Clone, Default)]
+pub struct AsyncAudit {
+    /// Async fns grouped by module path (`crate` for the root), modules sorted
+    pub async_fns: Vec<(String, Vec<AsyncFn>)>,
+    /// Public types likely missing `Send` or `Sync`, by name
+    pub types: Vec<TypeVerdict>,
+}
+
+impl AsyncAudit {
+    pub fn run(items: &[AnalyzedItem]) -> Self {
+        let local = local_type_safety(items);
+
+        let mut by_module: HashMap<String, Vec<AsyncFn>> = HashMap::new();
+        let mut add = |module: &[String], func: &FunctionInfo, owner: Option<&str>| {
+            if !func.is_async {
+                return;
+            }
+            let module = if module.is_empty() {
+                "crate".to_string()
+            } else {
+                module.join("::")
+            };
+            let name = match owner {
+                Some(owner) => format!("{}::{}", owner, func.name),
+                None => func.name.clone(),
+            };
+            by_module.entry(module).or_default().push(AsyncFn {
+                name,
+                is_public: func.visibility == Visibility::Public,
+                not_send: future_not_send(func, owner, &local),
+            });
+        };
+        for item in items {
+            match item {
+                AnalyzedItem::Function(f) => add(&f.module_path, f, None),
+                AnalyzedItem::Impl(im) if im.trait_name.is_none() => {
+                    let owner = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+                    for method in &im.methods {
+                        add(&im.module_path, method, Some(owner));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut async_fns: Vec<(String, Vec<AsyncFn>)> = by_module.into_iter().collect();
+        async_fns.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, fns) in &mut async_fns {
+            fns.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+
+        let mut types: Vec<TypeVerdict> = items
+            .iter()
+            .filter(|item| {
+                matches!(item, AnalyzedItem::Struct(_) | AnalyzedItem::Enum(_))
+                    && item.visibility() == Some(Visibility::Public)
+            })
+            .filter_map(|item| {
+                let safety = local.get(item.name())?;
+                (!safety.is_send() || !safety.is_sync()).then(|| TypeVerdict {
+                    name: item.name().to_string(),
+                    safety: safety.clone(),
+                })
+            })
+            .collect();
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+        types.dedup_by(|a, b| a.name == b.name);
+
+        Self { async_fns, types }
+    }
+
+    /// Public async fns whose future is likely `!Send`
+    pub fn flagged(&self) -> impl Iterator<Item = &AsyncFn> {
+        self.async_fns
+            .iter()
+            .flat_map(|(_, fns)| fns)
+            .filter(|f| f.is_public && f.not_send.is_some())
+    }
+
+    pub fn async_fn_count(&self) -> usize {
+        self.async_fns.iter().map(|(_, fns)| fns.len()).sum()
+    }
+}
+
+/// Explicit `unsafe impl Send for T` / `impl !Unpin for T` and the like, by type
+#[derive(Debug, Clone, Copy, Default)]
+struct ExplicitImpls {
+    send: Option<bool>,
+    sync: Option<bool>,
+    unpin: Option<bool>,
+}
+
+fn explicit_impls(items: &[AnalyzedItem]) -> HashMap<&str, ExplicitImpls> {
+    let mut overrides: HashMap<&str, ExplicitImpls> = HashMap::new();
+    for item in items {
+        let AnalyzedItem::Impl(im) = item else {
+            continue;
+        };
+        let Some(base) = base_type_name(&im.self_ty) else {
+            continue;
+        };
+        let entry = overrides.entry(base).or_default();
+        match im
+            .trait_name
+            .as_deref()
+            .map(|t| t.rsplit("::").next().unwrap_or(t))
+        {
+            Some("Send") => entry.send = Some(!im.is_negative),
+            Some("Sync") => entry.sync = Some(!im.is_negative),
+            Some("Unpin") => entry.unpin = Some(!im.is_negative),
+            _ => {}
+        }
+    }
+    overrides
+}
+
+/// Thread safety of every struct and enum in `items`, by name
+fn local_type_safety(items: &[AnalyzedItem]) -> HashMap<String, ThreadSafety> {
+    let parsed = parsed_fields(items);
+
+    // Explicit impls override the fields
+    let overrides = explicit_impls(items);
+
+    let mut safety: HashMap<String, ThreadSafety> = parsed
+        .iter()
+        .map(|(name, _)| (name.to_string(), ThreadSafety::default()))
+        .collect();
+    // Reasons spread outward one field per pass, so this settles within one pass per type
+    for _ in 0..=parsed.len() {
+        let mut changed = false;
+        for (name, tys) in &parsed {
+            let mut computed = ThreadSafety::default();
+            for ty in tys {
+                computed.merge(type_safety(ty, &safety));
+            }
+            if let Some(&ExplicitImpls { send, sync, .. }) = overrides.get(name) {
+                match send {
+                    Some(true) => computed.not_send = None,
+                    Some(false) => computed.not_send = Some("impl !Send".into()),
+                    None => {}
+                }
+                match sync {
+                    Some(true) => computed.not_sync = None,
+                    Some(false) => computed.not_sync = Some("impl !Sync".into()),
+                    None => {}
+                }
+            }
+            let current = safety.get_mut(*name).expect("seeded above");
+            if *current != computed {
+                *current = computed;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    safety
+}
+
+/// Why `func`'s future is likely `!Send`, naming the argument responsible
+fn future_not_send(
+    func: &FunctionInfo,
+    owner: Option<&str>,
+    local: &HashMap<String, ThreadSafety>,
+) -> Option<String> {
+    for param in &func.parameters {
+        if param.is_self {
+            let Some(safety) = owner.and_then(|o| local.get(o)) else {
+                continue;
+            };
+            let reason = if param.is_ref && !param.is_mut {
+                safety.not_sync.as_ref()
+            } else {
+                safety.not_send.as_ref()
+            };
+            if let Some(reason) = reason {
+                return Some(format!("{} ({})", param, reason));
+            }
+            continue;
+        }
+        let Ok(ty) = syn::parse_str::<Type>(&param.ty) else {
+            continue;
+        };
+        if let Some(reason) = type_safety(&ty, local).not_send {
+            return Some(format!("{}: {} ({})", param.name, param.ty, reason));
+        }
+    }
+    None
+}
+
+fn type_safety(ty: &Type, local: &HashMap<String, ThreadSafety>) -> ThreadSafety {
+    match ty {
+        Type::Reference(r) => {
+            let inner = type_safety(&r.elem, local);
+            if r.mutability.is_some() {
+                inner
+            } else {
+                // `&T: Send` needs `T: Sync`
+                ThreadSafety {
+                    not_send: inner.not_sync.clone(),
+                    not_sync: inner.not_sync,
+                }
+            }
+        }
+        Type::Ptr(_) => ThreadSafety::both("raw pointer"),
+        Type::Path(p) => {
+            let Some(last) = p.path.segments.last() else {
+                return ThreadSafety::default();
+            };
+            let args: Vec<&Type> = match &last.arguments {
+                PathArguments::AngleBracketed(args) => args
+                    .args
+                    .iter()
+                    .filter_map(|a| match a {
+                        GenericArgument::Type(t) => Some(t),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let mut inner = ThreadSafety::default();
+            for arg in &args {
+                inner.merge(type_safety(arg, local));
+            }
+            let name = last.ident.to_string();
+            let from_rc_module = p.path.segments.iter().any(|s| s.ident == "rc");
+            match name.as_str() {
+                "Rc" => ThreadSafety::both("Rc"),
+                "Weak" if from_rc_module => ThreadSafety::both("rc::Weak"),
+                "NonNull" => ThreadSafety::both("NonNull"),
+                "Cell" | "RefCell" | "UnsafeCell" | "OnceCell" => ThreadSafety {
+                    not_send: inner.not_send,
+                    not_sync: Some(name),
+                },
+                // `Mutex<T>: Sync` only needs `T: Send`
+                "Mutex" => ThreadSafety {
+                    not_send: inner.not_send.clone(),
+                    not_sync: inner.not_send,
+                },
+                "RwLock" => {
+                    let not_sync = inner.not_send.clone().or(inner.not_sync);
+                    ThreadSafety {
+                        not_send: inner.not_send,
+                        not_sync,
+                    }
+                }
+                // `Arc<T>` shares `T` across threads: both need `T: Send + Sync`
+                "Arc" => {
+                    let reason = inner.not_sync.or(inner.not_send);
+                    ThreadSafety {
+                        not_send: reason.clone(),
+                        not_sync: reason,
+                    }
+                }
+                "MutexGuard" | "RwLockReadGuard" | "RwLockWriteGuard" => ThreadSafety {
+                    not_send: Some(name),
+                    not_sync: inner.not_sync,
+                },
+                _ => match local.get(&name) {
+                    Some(own) => {
+                        let mut safety = ThreadSafety {
+                            not_send: own.not_send.as_ref().map(|_| format!("{} is !Send", name)),
+                            not_sync: own.not_sync.as_ref().map(|_| format!("{} is !Sync", name)),
+                        };
+                        safety.merge(inner);
+                        safety
+                    }
+                    None => inner,
+                },
+            }
+        }
+        Type::TraitObject(t) => {
+            let has = |marker: &str| {
+                t.bounds.iter().any(|b| match b {
+                    TypeParamBound::Trait(tb) => {
+                        tb.path.segments.last().is_some_and(|s| s.ident == marker)
+                    }
+                    _ => false,
+                })
+            };
+            ThreadSafety {
+                not_send: (!has("Send")).then(|| "dyn Trait without + Send".to_string()),
+                not_sync: (!has("Sync")).then(|| "dyn Trait without + Sync".to_string()),
+            }
+        }
+        Type::Slice(s) => type_safety(&s.elem, local),
+        Type::Array(a) => type_safety(&a.elem, local),
+        Type::Paren(p) => type_safety(&p.elem, local),
+        Type::Group(g) => type_safety(&g.elem, local),
+        Type::Tuple(t) => {
+            let mut safety = ThreadSafety::default();
+            for elem in &t.elems {
+                safety.merge(type_safety(elem, local));
+            }
+            safety
+        }
+        _ => ThreadSafety::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    fn audit(source: &str) -> AsyncAudit {
+        AsyncAudit::run(&RustAnalyzer::new().analyze_source(source).unwrap())
+    }
+
+    #[test]
+    fn test_types_propagate_through_fields() {
+        let a = audit(
+            r#"
+            pub struct Cache { map: RefCell<HashMap<String, u8>> }
+            pub struct Shared { cache: Arc<Cache> }
+            pub struct Locked { cache: Mutex<Cache> }
+            pub struct Local { counter: Rc<u8> }
+            pub struct Wrapper(Local);
+            pub struct Fine { names: Vec<String>, hook: Box<dyn Fn() + Send + Sync> }
+            pub struct Forced(*mut u8);
+            unsafe impl Send for Forced {}
+            "#,
+        );
+        let names: Vec<&str> = a.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Cache", "Forced", "Local", "Shared", "Wrapper"]);
+        let cache = &a.types[0].safety;
+        assert!(cache.is_send());
+        assert_eq!(cache.not_sync.as_deref(), Some("RefCell"));
+        assert_eq!(
+            a.types[3].safety.not_send.as_deref(),
+            Some("Cache is !Sync")
+        );
+        let forced = &a.types[1].safety;
+        assert!(forced.is_send() && !forced.is_sync());
+        assert_eq!(
+            a.types[4].safety.not_send.as_deref(),
+            Some("Local is !Send")
+        );
+    }
+
+    #[test]
+    fn test_async_fns_flagged_per_module() {
+        let a = audit(
+            r#"
+            pub struct Session { state: RefCell<u8> }
+            impl Session {
+                pub async fn refresh(&self) {}
+                pub async fn close(self) {}
+            }
+            pub async fn fetch(url: &str) -> String { todo!() }
+            pub async fn replay(log: Rc<Vec<u8>>) {}
+            async fn private(log: Rc<Vec<u8>>) {}
+            pub fn sync_fn() {}
+            "#,
+        );
+        assert_eq!(a.async_fn_count(), 5);
+        let flagged: Vec<&str> = a.flagged().map(|f| f.name.as_str()).collect();
+        assert_eq!(flagged, vec!["Session::refresh", "replay"]);
+        let refresh = a.flagged().next().unwrap();
+        assert_eq!(refresh.not_send.as_deref(), Some("&self (RefCell)"));
+        assert_eq!(a.async_fns[0].0, "crate");
+    }
+
+    #[test]
+    fn test_auto_traits_with_unknowns() {
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                r#"
+                pub struct Cache { hits: AtomicUsize, map: RefCell<HashMap<String, u8>> }
+                pub struct Holder<T> { value: Box<T>, name: String }
+                pub struct Pinned { _pin: PhantomPinned, data: [u8; 4] }
+                pub struct Outer(Pinned);
+                pub struct Remote { client: reqwest::Client }
+                pub enum State { Idle, Busy(Rc<u8>) }
+                "#,
+            )
+            .unwrap();
+        let of = |name: &str| {
+            let item = items.iter().find(|i| i.name() == name).unwrap();
+            AutoTraits::of(item, &items).unwrap()
+        };
+
+        let cache = of("Cache");
+        assert_eq!(cache.send, Verdict::Yes);
+        assert_eq!(cache.sync, Verdict::No("RefCell".into()));
+        assert_eq!(cache.unpin, Verdict::Yes);
+        assert_eq!(
+            cache.interior_mutability,
+            vec!["hits: AtomicUsize", "map: RefCell"]
+        );
+
+        let holder = of("Holder");
+        assert_eq!(holder.send, Verdict::Unknown("depends on `T`".into()));
+        assert_eq!(holder.unpin, Verdict::Yes);
+        assert_eq!(of("Pinned").unpin, Verdict::No("PhantomPinned".into()));
+        assert_eq!(of("Outer").unpin, Verdict::No("Pinned is !Unpin".into()));
+        assert_eq!(
+            of("Remote").sync,
+            Verdict::Unknown("`Client` is from another crate".into())
+        );
+        assert_eq!(of("State").send, Verdict::No("Rc".into()));
+        let func = RustAnalyzer::new().analyze_source("fn f() {}").unwrap();
+        assert!(AutoTraits::of(&func[0], &func).is_none());
+    }
+}
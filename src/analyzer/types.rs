@@ -2,12 +2,20 @@
 
 use std::fmt;
 use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::panics::PanicPoints;
 
 /// Source location information
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SourceLocation {
     pub file: Option<PathBuf>,
     pub line: Option<usize>,
+    /// Last line of the item, e.g. a function's closing brace
+    pub end_line: Option<usize>,
+    /// Column of the item's name, 1-based and in characters
     pub column: Option<usize>,
 }
 
@@ -16,9 +24,37 @@ impl SourceLocation {
         Self {
             file: Some(file),
             line: Some(line),
+            end_line: None,
             column: None,
         }
     }
+
+    /// The item's text as written, with the doc comments and attributes directly
+    /// above it, and the number of its first line. `None` without a file and line
+    /// or when the file can't be read.
+    pub fn read_source(&self) -> Option<(usize, String)> {
+        let content = std::fs::read_to_string(self.file.as_ref()?).ok()?;
+        self.source_in(&content)
+    }
+
+    /// Like [`read_source`](Self::read_source), from the file's `content`
+    pub fn source_in(&self, content: &str) -> Option<(usize, String)> {
+        let line = self.line?;
+        let lines: Vec<&str> = content.lines().collect();
+        let end = self.end_line.unwrap_or(line).max(line).min(lines.len());
+        let mut start = line.min(end);
+        if start == 0 {
+            return None;
+        }
+        while start > 1 {
+            let above = lines[start - 2].trim_start();
+            if !(above.starts_with("///") || above.starts_with("#[")) {
+                break;
+            }
+            start -= 1;
+        }
+        Some((start, lines.get(start - 1..end)?.join("\n")))
+    }
 }
 
 impl fmt::Display for SourceLocation {
@@ -32,7 +68,8 @@ impl fmt::Display for SourceLocation {
 }
 
 /// Visibility of a Rust item
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Visibility {
     Public,
     Crate,
@@ -55,16 +92,28 @@ impl fmt::Display for Visibility {
 }
 
 /// Analyzed item from Rust source code
-#[derive(Debug, Clone)]
+///
+/// Serialized as `{"kind": "fn", "item": {...}}`, `kind` being [`AnalyzedItem::kind`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "item")]
 pub enum AnalyzedItem {
+    #[serde(rename = "fn")]
     Function(FunctionInfo),
+    #[serde(rename = "struct")]
     Struct(StructInfo),
+    #[serde(rename = "enum")]
     Enum(EnumInfo),
+    #[serde(rename = "trait")]
     Trait(TraitInfo),
+    #[serde(rename = "impl")]
     Impl(ImplInfo),
+    #[serde(rename = "mod")]
     Module(ModuleInfo),
+    #[serde(rename = "type")]
     TypeAlias(TypeAliasInfo),
+    #[serde(rename = "const")]
     Const(ConstInfo),
+    #[serde(rename = "static")]
     Static(StaticInfo),
 }
 
@@ -139,6 +188,34 @@ impl AnalyzedItem {
         }
     }
 
+    pub fn cfg(&self) -> &[String] {
+        match self {
+            AnalyzedItem::Function(f) => &f.cfg,
+            AnalyzedItem::Struct(s) => &s.cfg,
+            AnalyzedItem::Enum(e) => &e.cfg,
+            AnalyzedItem::Trait(t) => &t.cfg,
+            AnalyzedItem::Impl(i) => &i.cfg,
+            AnalyzedItem::Module(m) => &m.cfg,
+            AnalyzedItem::TypeAlias(t) => &t.cfg,
+            AnalyzedItem::Const(c) => &c.cfg,
+            AnalyzedItem::Static(s) => &s.cfg,
+        }
+    }
+
+    pub fn cfg_mut(&mut self) -> &mut Vec<String> {
+        match self {
+            AnalyzedItem::Function(f) => &mut f.cfg,
+            AnalyzedItem::Struct(s) => &mut s.cfg,
+            AnalyzedItem::Enum(e) => &mut e.cfg,
+            AnalyzedItem::Trait(t) => &mut t.cfg,
+            AnalyzedItem::Impl(i) => &mut i.cfg,
+            AnalyzedItem::Module(m) => &mut m.cfg,
+            AnalyzedItem::TypeAlias(t) => &mut t.cfg,
+            AnalyzedItem::Const(c) => &mut c.cfg,
+            AnalyzedItem::Static(s) => &mut s.cfg,
+        }
+    }
+
     /// Get the module path for this item (e.g., ["serde", "de"])
     pub fn module_path(&self) -> &[String] {
         match self {
@@ -183,8 +260,70 @@ impl AnalyzedItem {
     }
 }
 
+/// Kind of a generic parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenericParamKind {
+    Lifetime,
+    Type,
+    /// `const N: usize` — carries the const's type
+    Const(String),
+}
+
+/// A generic parameter with its inline bounds and the `where` bounds targeting it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenericParam {
+    /// Name as written (`'a`, `T`, `N`)
+    pub name: String,
+    pub kind: GenericParamKind,
+    /// Bounds declared inline (`T: Clone`, `'a: 'b`)
+    pub bounds: Vec<String>,
+    /// Bounds from the `where` clause whose bounded type is exactly this parameter
+    pub where_bounds: Vec<String>,
+    pub default: Option<String>,
+}
+
+impl GenericParam {
+    /// Inline and `where` bounds together
+    pub fn all_bounds(&self) -> impl Iterator<Item = &String> {
+        self.bounds.iter().chain(self.where_bounds.iter())
+    }
+}
+
+/// Structured generics of an item
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenericsInfo {
+    pub params: Vec<GenericParam>,
+    /// `where` predicates not targeting a single parameter (e.g. `Vec<T>: Debug`)
+    pub other_predicates: Vec<String>,
+}
+
+impl GenericsInfo {
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty() && self.other_predicates.is_empty()
+    }
+
+    pub fn lifetimes(&self) -> impl Iterator<Item = &GenericParam> {
+        self.params
+            .iter()
+            .filter(|p| p.kind == GenericParamKind::Lifetime)
+    }
+
+    pub fn types(&self) -> impl Iterator<Item = &GenericParam> {
+        self.params
+            .iter()
+            .filter(|p| p.kind == GenericParamKind::Type)
+    }
+
+    pub fn consts(&self) -> impl Iterator<Item = &GenericParam> {
+        self.params
+            .iter()
+            .filter(|p| matches!(p.kind, GenericParamKind::Const(_)))
+    }
+}
+
 /// Information about a function
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionInfo {
     pub name: String,
     pub signature: String,
@@ -193,21 +332,40 @@ pub struct FunctionInfo {
     pub is_const: bool,
     pub is_unsafe: bool,
     pub generics: Vec<String>,
+    pub generics_info: GenericsInfo,
     pub parameters: Vec<Parameter>,
     pub return_type: Option<String>,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
     pub attributes: Vec<String>,
     pub where_clause: Option<String>,
+    /// `unwrap`, `expect`, `panic!`, `todo!` and indexing in the body
+    #[serde(default)]
+    pub panics: PanicPoints,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming (e.g., ["serde", "de"])
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
+}
+
+impl FunctionInfo {
+    /// Return type holds a reference or lifetime; a quick text check for list rows, see
+    /// [`LifetimeSummary`](super::LifetimeSummary) for what it borrows from
+    pub fn returns_reference(&self) -> bool {
+        self.return_type
+            .as_deref()
+            .is_some_and(|ret| ret.contains('&') || ret.contains('\''))
+    }
 }
 
 /// Function parameter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Parameter {
     pub name: String,
-    pub ty: String,
+    /// Interned, as [`Field::ty`]
+    pub ty: Arc<str>,
     pub is_self: bool,
     pub is_mut: bool,
     pub is_ref: bool,
@@ -232,20 +390,25 @@ impl fmt::Display for Parameter {
 }
 
 /// Information about a struct
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructInfo {
     pub name: String,
     pub visibility: Visibility,
     pub generics: Vec<String>,
+    pub generics_info: GenericsInfo,
     pub fields: Vec<Field>,
     pub kind: StructKind,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
     pub derives: Vec<String>,
     pub attributes: Vec<String>,
     pub where_clause: Option<String>,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
 }
 
 impl StructInfo {
@@ -309,7 +472,8 @@ impl StructInfo {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StructKind {
     Named,
     Tuple,
@@ -317,28 +481,34 @@ pub enum StructKind {
 }
 
 /// Struct/enum field
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Field {
     pub name: String,
-    pub ty: String,
+    /// Interned: the same type text is shared by every field and parameter of a scan
+    pub ty: Arc<str>,
     pub visibility: Visibility,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
 }
 
 /// Information about an enum
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnumInfo {
     pub name: String,
     pub visibility: Visibility,
     pub generics: Vec<String>,
+    pub generics_info: GenericsInfo,
     pub variants: Vec<Variant>,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
     pub derives: Vec<String>,
     pub attributes: Vec<String>,
     pub where_clause: Option<String>,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
 }
 
 impl EnumInfo {
@@ -384,15 +554,16 @@ impl EnumInfo {
 }
 
 /// Enum variant
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Variant {
     pub name: String,
     pub fields: VariantFields,
     pub discriminant: Option<String>,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum VariantFields {
     Named(Vec<Field>),
     Unnamed(Vec<String>),
@@ -400,22 +571,27 @@ pub enum VariantFields {
 }
 
 /// Information about a trait
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraitInfo {
     pub name: String,
     pub visibility: Visibility,
     pub generics: Vec<String>,
+    pub generics_info: GenericsInfo,
     pub supertraits: Vec<String>,
     pub methods: Vec<TraitMethod>,
     pub associated_types: Vec<AssociatedType>,
     pub associated_consts: Vec<AssociatedConst>,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
     pub is_unsafe: bool,
     pub is_auto: bool,
     pub where_clause: Option<String>,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
 }
 
 impl TraitInfo {
@@ -443,7 +619,8 @@ impl TraitInfo {
             items.push(format!("    type {};", at.name));
         }
         for method in &self.methods {
-            items.push(format!("    {};", method.signature));
+            // Long signatures span several lines; indent them all into the trait body
+            items.push(format!("    {};", method.signature.replace('\n', "\n    ")));
         }
 
         format!(
@@ -460,17 +637,17 @@ impl TraitInfo {
 }
 
 /// Trait method signature
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraitMethod {
     pub name: String,
     pub signature: String,
     pub has_default: bool,
     pub is_async: bool,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
 }
 
 /// Associated type in a trait
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssociatedType {
     pub name: String,
     pub bounds: Vec<String>,
@@ -478,7 +655,7 @@ pub struct AssociatedType {
 }
 
 /// Associated const in a trait
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssociatedConst {
     pub name: String,
     pub ty: String,
@@ -486,18 +663,23 @@ pub struct AssociatedConst {
 }
 
 /// Information about an impl block
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImplInfo {
     pub self_ty: String,
     pub trait_name: Option<String>,
     pub generics: Vec<String>,
+    pub generics_info: GenericsInfo,
     pub methods: Vec<FunctionInfo>,
     pub is_unsafe: bool,
     pub is_negative: bool,
     pub where_clause: Option<String>,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
 }
 
 impl ImplInfo {
@@ -521,56 +703,73 @@ impl ImplInfo {
 }
 
 /// Information about a module
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleInfo {
     pub name: String,
     pub path: String,
     pub visibility: Visibility,
     pub items: Vec<String>,
     pub submodules: Vec<String>,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
     pub is_inline: bool,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
 }
 
 /// Type alias information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeAliasInfo {
     pub name: String,
     pub visibility: Visibility,
     pub generics: Vec<String>,
+    pub generics_info: GenericsInfo,
     pub ty: String,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
     pub where_clause: Option<String>,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
 }
 
 /// Const item information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConstInfo {
     pub name: String,
     pub visibility: Visibility,
     pub ty: String,
     pub value: Option<String>,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
 }
 
 /// Static item information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StaticInfo {
     pub name: String,
     pub visibility: Visibility,
     pub ty: String,
     pub is_mut: bool,
-    pub documentation: Option<String>,
+    pub documentation: Option<Arc<str>>,
+    /// `#[cfg(..)]` predicates the item is compiled under: those of the modules
+    /// around it, outermost first, then its own
+    #[serde(default)]
+    pub cfg: Vec<String>,
     pub source_location: SourceLocation,
     /// Module path for fully qualified naming
-    pub module_path: Vec<String>,
+    pub module_path: Arc<[String]>,
 }
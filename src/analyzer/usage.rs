@@ -0,0 +1,329 @@
+//! Reverse type-reference index ("where is type X used?")
+//!
+//! Built once after parsing: every type string on function signatures, struct and
+//! variant fields, and impl headers is tokenized into identifiers, and each
+//! identifier maps back to the items that mention it. Trait impls and derives are
+//! also filed under the trait, so a trait from a dependency finds its implementors.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::types::{AnalyzedItem, FunctionInfo, SourceLocation, VariantFields};
+
+/// How an item refers to a type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum UsageKind {
+    /// `impl Type` or `impl Trait for Type`
+    Impl,
+    /// `#[derive(Trait)]` on a struct or enum
+    Derive,
+    /// Struct field or enum variant field
+    Field,
+    /// Function or method parameter
+    Parameter,
+    /// Function or method return type
+    Return,
+}
+
+impl fmt::Display for UsageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UsageKind::Impl => write!(f, "impl"),
+            UsageKind::Derive => write!(f, "derive"),
+            UsageKind::Field => write!(f, "field"),
+            UsageKind::Parameter => write!(f, "param"),
+            UsageKind::Return => write!(f, "returns"),
+        }
+    }
+}
+
+/// One reference to a type from another item
+#[derive(Debug, Clone)]
+pub struct TypeUsage {
+    pub kind: UsageKind,
+    /// Qualified name of the referring item (e.g. `config::load`, `Foo::new`)
+    pub owner: String,
+    /// Short description of the reference (e.g. `cfg: &Config`)
+    pub detail: String,
+    pub location: SourceLocation,
+}
+
+/// Map from type name to the items referring to it
+#[derive(Debug, Clone, Default)]
+pub struct TypeUsageIndex {
+    usages: HashMap<String, Vec<TypeUsage>>,
+}
+
+impl TypeUsageIndex {
+    pub fn build(items: &[AnalyzedItem]) -> Self {
+        let mut index = Self::default();
+        for item in items {
+            match item {
+                AnalyzedItem::Function(f) => index.add_function(f, item.qualified_name()),
+                AnalyzedItem::Struct(s) => {
+                    index.add_derives(&s.derives, item.qualified_name(), &s.source_location);
+                    for field in &s.fields {
+                        index.add_type(
+                            &field.ty,
+                            UsageKind::Field,
+                            item.qualified_name(),
+                            format!("{}: {}", field.name, field.ty),
+                            &s.source_location,
+                        );
+                    }
+                }
+                AnalyzedItem::Enum(e) => {
+                    index.add_derives(&e.derives, item.qualified_name(), &e.source_location);
+                    for variant in &e.variants {
+                        let types: Vec<&str> = match &variant.fields {
+                            VariantFields::Named(fields) => fields.iter().map(|f| &*f.ty).collect(),
+                            VariantFields::Unnamed(types) => {
+                                types.iter().map(String::as_str).collect()
+                            }
+                            VariantFields::Unit => Vec::new(),
+                        };
+                        for ty in types {
+                            index.add_type(
+                                ty,
+                                UsageKind::Field,
+                                item.qualified_name(),
+                                format!("{}({})", variant.name, ty),
+                                &e.source_location,
+                            );
+                        }
+                    }
+                }
+                AnalyzedItem::Impl(im) => {
+                    if let Some(base) = base_type_name(&im.self_ty) {
+                        let detail = match &im.trait_name {
+                            Some(t) => format!("impl {} for {}", t, im.self_ty),
+                            None => format!("impl {}", im.self_ty),
+                        };
+                        index.push(
+                            base,
+                            TypeUsage {
+                                kind: UsageKind::Impl,
+                                owner: item.qualified_name(),
+                                detail,
+                                location: im.source_location.clone(),
+                            },
+                        );
+                    }
+                    if let Some(trait_name) = im.trait_name.as_deref().and_then(base_type_name) {
+                        index.push(
+                            trait_name,
+                            TypeUsage {
+                                kind: UsageKind::Impl,
+                                owner: item.qualified_name(),
+                                detail: format!(
+                                    "impl {} for {}",
+                                    im.trait_name.as_deref().unwrap_or_default(),
+                                    im.self_ty
+                                ),
+                                location: im.source_location.clone(),
+                            },
+                        );
+                    }
+                    let owner_prefix = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+                    for method in &im.methods {
+                        index.add_function(method, format!("{}::{}", owner_prefix, method.name));
+                    }
+                }
+                _ => {}
+            }
+        }
+        for list in index.usages.values_mut() {
+            list.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.owner.cmp(&b.owner)));
+        }
+        index
+    }
+
+    /// Usages of a type by its (unqualified) name, sorted by kind then owner
+    pub fn usages_of(&self, type_name: &str) -> &[TypeUsage] {
+        self.usages
+            .get(type_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.usages.is_empty()
+    }
+
+    fn add_function(&mut self, func: &FunctionInfo, owner: String) {
+        for param in func.parameters.iter().filter(|p| !p.is_self) {
+            self.add_type(
+                &param.ty,
+                UsageKind::Parameter,
+                owner.clone(),
+                format!("{}: {}", param.name, param.ty),
+                &func.source_location,
+            );
+        }
+        if let Some(ref ret) = func.return_type {
+            self.add_type(
+                ret,
+                UsageKind::Return,
+                owner,
+                format!("-> {}", ret),
+                &func.source_location,
+            );
+        }
+    }
+
+    fn add_derives(&mut self, derives: &[String], owner: String, location: &SourceLocation) {
+        for derive in derives {
+            let Some(name) = base_type_name(derive) else {
+                continue;
+            };
+            self.push(
+                name,
+                TypeUsage {
+                    kind: UsageKind::Derive,
+                    owner: owner.clone(),
+                    detail: format!("#[derive({})]", derive),
+                    location: location.clone(),
+                },
+            );
+        }
+    }
+
+    fn add_type(
+        &mut self,
+        ty: &str,
+        kind: UsageKind,
+        owner: String,
+        detail: String,
+        location: &SourceLocation,
+    ) {
+        for ident in type_idents(ty) {
+            self.push(
+                ident,
+                TypeUsage {
+                    kind,
+                    owner: owner.clone(),
+                    detail: detail.clone(),
+                    location: location.clone(),
+                },
+            );
+        }
+    }
+
+    fn push(&mut self, name: &str, usage: TypeUsage) {
+        let list = self.usages.entry(name.to_string()).or_default();
+        let duplicate = list
+            .iter()
+            .any(|u| u.kind == usage.kind && u.owner == usage.owner && u.detail == usage.detail);
+        if !duplicate {
+            list.push(usage);
+        }
+    }
+}
+
+/// Distinct identifiers in a type string (`Result < Vec < Foo > , E >` → Result, Vec, Foo, E)
+pub fn type_idents(ty: &str) -> Vec<&str> {
+    let mut idents: Vec<&str> = Vec::new();
+    for token in ty.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        let is_ident = token
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_');
+        if is_ident && !is_keyword(token) && !idents.contains(&token) {
+            idents.push(token);
+        }
+    }
+    idents
+}
+
+/// Name of the type an impl targets: `&'a mut foo::Bar<T>` → `Bar`
+pub(crate) fn base_type_name(ty: &str) -> Option<&str> {
+    let head = ty.split('<').next().unwrap_or(ty);
+    head.rsplit("::")
+        .next()
+        .and_then(|last| type_idents(last).into_iter().last())
+}
+
+fn is_keyword(s: &str) -> bool {
+    matches!(
+        s,
+        "mut" | "dyn" | "impl" | "const" | "fn" | "for" | "unsafe" | "extern" | "crate" | "self"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_type_idents() {
+        assert_eq!(
+            type_idents("Result < Vec < & 'a mut Foo > , crate :: Error >"),
+            vec!["Result", "Vec", "a", "Foo", "Error"]
+        );
+        assert_eq!(base_type_name("& 'a mut foo :: Bar < T >"), Some("Bar"));
+    }
+
+    #[test]
+    fn test_usage_index() {
+        let source = r#"
+            pub struct Config { pub name: String }
+            pub struct App { config: Config }
+            pub enum Event { Reload(Config), Quit }
+            pub fn load(path: &str) -> Result<Config, String> { todo!() }
+            impl Config {
+                pub fn merge(&mut self, other: &Config) {}
+            }
+            impl Default for Config {
+                fn default() -> Self { todo!() }
+            }
+        "#;
+        let items = RustAnalyzer::new()
+            .with_private(true)
+            .analyze_source(source)
+            .unwrap();
+        let index = TypeUsageIndex::build(&items);
+        let usages = index.usages_of("Config");
+        let summary: Vec<(UsageKind, &str)> =
+            usages.iter().map(|u| (u.kind, u.owner.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (UsageKind::Impl, "Config"),
+                (UsageKind::Impl, "Config"),
+                (UsageKind::Field, "App"),
+                (UsageKind::Field, "Event"),
+                (UsageKind::Parameter, "Config::merge"),
+                (UsageKind::Return, "load"),
+            ]
+        );
+        assert!(index.usages_of("Missing").is_empty());
+    }
+
+    #[test]
+    fn test_traits_find_implementors() {
+        let source = r#"
+            #[derive(Debug, serde::Deserialize)]
+            pub struct Config {}
+            pub struct Raw;
+            impl<'de> serde::de::Deserialize<'de> for Raw {}
+            pub fn parse<T: Deserialize>(input: &str) -> Box<dyn Deserialize> { todo!() }
+        "#;
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        let index = TypeUsageIndex::build(&items);
+        let summary: Vec<(UsageKind, &str)> = index
+            .usages_of("Deserialize")
+            .iter()
+            .map(|u| (u.kind, u.owner.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (UsageKind::Impl, "Raw"),
+                (UsageKind::Derive, "Config"),
+                (UsageKind::Return, "parse"),
+            ]
+        );
+        assert_eq!(index.usages_of("Debug")[0].detail, "#[derive(Debug)]");
+    }
+}
@@ -0,0 +1,446 @@
+//! Whether the project is likely to build for WebAssembly
+//!
+//! Two targets are considered: `wasm32-unknown-unknown` (the browser, with no OS at
+//! all) and WASI (`wasm32-wasip1`/`wasip2`, which has files, a clock and the
+//! environment but no threads, processes or native libraries). Dependencies are
+//! matched by name against crates known to need an OS or a C toolchain, and the
+//! project's `use` declarations against std modules that don't compile or only fail
+//! at run time there. It is a heuristic: a crate can gate the problem behind a
+//! feature the project doesn't enable, and paths written out in full without a
+//! `use` aren't seen.
+
+use std::path::PathBuf;
+
+use super::imports::ModuleImport;
+
+/// How badly something breaks a WASM build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WasmImpact {
+    /// Compiles, but needs extra setup or fails at run time
+    Caution,
+    /// Doesn't compile (or link) for the target
+    Blocks,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasmTarget {
+    /// `wasm32-unknown-unknown`
+    Unknown,
+    /// `wasm32-wasip1` / `wasm32-wasip2`
+    Wasi,
+}
+
+impl WasmTarget {
+    pub const ALL: [WasmTarget; 2] = [Self::Unknown, Self::Wasi];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Unknown => "wasm32-unknown-unknown",
+            Self::Wasi => "WASI",
+        }
+    }
+}
+
+/// How likely the project is to build for a target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outlook {
+    Likely,
+    /// Builds, but something needs setup or will fail at run time
+    WithChanges,
+    Unlikely,
+}
+
+impl Outlook {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Likely => "likely builds",
+            Self::WithChanges => "builds with changes",
+            Self::Unlikely => "unlikely to build",
+        }
+    }
+}
+
+/// A dependency or std module that is a problem on at least one WASM target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmIssue {
+    /// Crate name or std module path, e.g. `openssl-sys` or `std::thread`
+    pub subject: String,
+    /// Direct dependency that pulls in a transitive one
+    pub via: Option<String>,
+    pub unknown: Option<WasmImpact>,
+    pub wasi: Option<WasmImpact>,
+    pub reason: &'static str,
+    /// `use` declarations of a std module
+    pub locations: Vec<(PathBuf, usize)>,
+}
+
+impl WasmIssue {
+    pub fn impact(&self, target: WasmTarget) -> Option<WasmImpact> {
+        match target {
+            WasmTarget::Unknown => self.unknown,
+            WasmTarget::Wasi => self.wasi,
+        }
+    }
+
+    /// Dependency to drop or replace to get rid of this one
+    pub fn direct(&self) -> &str {
+        self.via.as_deref().unwrap_or(&self.subject)
+    }
+}
+
+/// Crates known to break WASM builds: `(name, wasm32-unknown-unknown, WASI, why)`
+const KNOWN_CRATES: &[(&str, Option<WasmImpact>, Option<WasmImpact>, &str)] = {
+    use WasmImpact::{Blocks, Caution};
+    &[
+        (
+            "openssl",
+            Some(Blocks),
+            Some(Blocks),
+            "links the system OpenSSL",
+        ),
+        (
+            "openssl-sys",
+            Some(Blocks),
+            Some(Blocks),
+            "links the system OpenSSL",
+        ),
+        (
+            "native-tls",
+            Some(Blocks),
+            Some(Blocks),
+            "uses the platform TLS library; rustls works",
+        ),
+        (
+            "hyper-tls",
+            Some(Blocks),
+            Some(Blocks),
+            "native TLS; rustls works",
+        ),
+        (
+            "tokio-native-tls",
+            Some(Blocks),
+            Some(Blocks),
+            "native TLS; rustls works",
+        ),
+        (
+            "mio",
+            Some(Blocks),
+            Some(Caution),
+            "OS event loop; WASI support is partial",
+        ),
+        ("socket2", Some(Blocks), Some(Blocks), "raw OS sockets"),
+        ("nix", Some(Blocks), Some(Blocks), "Unix system calls"),
+        ("crossterm", Some(Blocks), Some(Blocks), "terminal I/O"),
+        ("termion", Some(Blocks), Some(Blocks), "terminal I/O"),
+        (
+            "notify",
+            Some(Blocks),
+            Some(Blocks),
+            "OS file system events",
+        ),
+        ("memmap2", Some(Blocks), Some(Blocks), "memory-mapped files"),
+        ("signal-hook", Some(Blocks), Some(Blocks), "Unix signals"),
+        ("ctrlc", Some(Blocks), Some(Blocks), "OS signal handlers"),
+        ("git2", Some(Blocks), Some(Blocks), "links libgit2"),
+        (
+            "rusqlite",
+            Some(Blocks),
+            Some(Caution),
+            "builds SQLite from C",
+        ),
+        (
+            "ring",
+            Some(Caution),
+            Some(Caution),
+            "C and assembly; needs clang with a wasm32 target",
+        ),
+        (
+            "getrandom",
+            Some(Caution),
+            None,
+            "needs the `js` feature (or `wasm_js` backend) in the browser",
+        ),
+        (
+            "rayon",
+            Some(Caution),
+            Some(Caution),
+            "thread pool; threads aren't available",
+        ),
+        (
+            "num_cpus",
+            Some(Caution),
+            Some(Caution),
+            "always reports one CPU",
+        ),
+        (
+            "tokio",
+            Some(Caution),
+            Some(Caution),
+            "only `sync`, `macros`, `io-util`, `rt` and `time` work on wasm",
+        ),
+        (
+            "dirs",
+            Some(Caution),
+            None,
+            "no home or config directories in the browser",
+        ),
+        (
+            "home",
+            Some(Caution),
+            None,
+            "no home directory in the browser",
+        ),
+    ]
+};
+
+/// Std modules that are a problem on WASM: `(path, wasm32-unknown-unknown, WASI, why)`
+const STD_MODULES: &[(&str, Option<WasmImpact>, Option<WasmImpact>, &str)] = {
+    use WasmImpact::{Blocks, Caution};
+    &[
+        (
+            "std::os::unix",
+            Some(Blocks),
+            Some(Blocks),
+            "Unix-only extensions",
+        ),
+        (
+            "std::os::linux",
+            Some(Blocks),
+            Some(Blocks),
+            "Linux-only extensions",
+        ),
+        (
+            "std::os::windows",
+            Some(Blocks),
+            Some(Blocks),
+            "Windows-only extensions",
+        ),
+        (
+            "std::thread",
+            Some(Caution),
+            Some(Caution),
+            "spawning a thread panics",
+        ),
+        (
+            "std::process",
+            Some(Caution),
+            Some(Caution),
+            "can't run processes",
+        ),
+        (
+            "std::net",
+            Some(Caution),
+            Some(Caution),
+            "every socket operation returns an error",
+        ),
+        (
+            "std::fs",
+            Some(Caution),
+            None,
+            "every file operation returns an error",
+        ),
+        (
+            "std::env",
+            Some(Caution),
+            None,
+            "no arguments or environment variables",
+        ),
+        (
+            "std::time::Instant",
+            Some(Caution),
+            None,
+            "`Instant::now` panics",
+        ),
+        (
+            "std::time::SystemTime",
+            Some(Caution),
+            None,
+            "`SystemTime::now` panics",
+        ),
+    ]
+};
+
+/// Dependency and std API findings for both WASM targets
+#[derive(Debug, Clone, Default)]
+pub struct WasmScan {
+    /// Flagged dependencies, those that block a build first
+    pub dependencies: Vec<WasmIssue>,
+    /// Flagged std modules the project imports
+    pub apis: Vec<WasmIssue>,
+    /// Dependencies looked at
+    pub checked: usize,
+}
+
+impl WasmScan {
+    /// Scan `(crate, direct dependency pulling it in)` pairs, a crate being its own
+    /// direct dependency, and the project's `use` declarations
+    pub fn build<I>(dependencies: I, imports: &[ModuleImport]) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut scan = Self::default();
+        for (name, direct) in dependencies {
+            scan.checked += 1;
+            let known = KNOWN_CRATES.iter().find(|(n, ..)| *n == name);
+            let (unknown, wasi, reason) = match known {
+                Some(&(_, unknown, wasi, reason)) => (unknown, wasi, reason),
+                None if name.ends_with("-sys") => (
+                    Some(WasmImpact::Blocks),
+                    Some(WasmImpact::Caution),
+                    "builds or links a C library",
+                ),
+                None => continue,
+            };
+            scan.dependencies.push(WasmIssue {
+                via: (direct != name).then_some(direct),
+                subject: name,
+                unknown,
+                wasi,
+                reason,
+                locations: Vec::new(),
+            });
+        }
+        scan.dependencies.sort_by(|a, b| {
+            worst(b)
+                .cmp(&worst(a))
+                .then_with(|| a.subject.cmp(&b.subject))
+        });
+
+        for &(module, unknown, wasi, reason) in STD_MODULES {
+            let prefix: Vec<&str> = module.split("::").collect();
+            let locations: Vec<(PathBuf, usize)> = imports
+                .iter()
+                .filter(|i| i.bare && i.path.len() >= prefix.len())
+                .filter(|i| i.path.iter().zip(&prefix).all(|(a, b)| a == b))
+                .map(|i| (i.file.clone(), i.line))
+                .collect();
+            if !locations.is_empty() {
+                scan.apis.push(WasmIssue {
+                    subject: module.to_string(),
+                    via: None,
+                    unknown,
+                    wasi,
+                    reason,
+                    locations,
+                });
+            }
+        }
+        scan
+    }
+
+    pub fn outlook(&self, target: WasmTarget) -> Outlook {
+        match self
+            .dependencies
+            .iter()
+            .chain(&self.apis)
+            .filter_map(|i| i.impact(target))
+            .max()
+        {
+            Some(WasmImpact::Blocks) => Outlook::Unlikely,
+            Some(WasmImpact::Caution) => Outlook::WithChanges,
+            None => Outlook::Likely,
+        }
+    }
+
+    /// Direct dependencies that keep the project from building for `target`
+    pub fn blocking_dependencies(&self, target: WasmTarget) -> Vec<&str> {
+        let mut direct: Vec<&str> = self
+            .dependencies
+            .iter()
+            .filter(|i| i.impact(target) == Some(WasmImpact::Blocks))
+            .map(WasmIssue::direct)
+            .collect();
+        direct.sort_unstable();
+        direct.dedup();
+        direct
+    }
+}
+
+fn worst(issue: &WasmIssue) -> Option<WasmImpact> {
+    issue.unknown.max(issue.wasi)
+}
+
+/// Whether a dependency declared under `[target.'<cfg>'.dependencies]` is built for
+/// wasm32: `cfg(unix)`, `cfg(windows)` and other OS-specific targets aren't, nor
+/// is anything negating wasm
+pub fn cfg_applies_to_wasm(cfg: &str) -> bool {
+    let negated = cfg.contains("not(");
+    if cfg.contains("wasm") || cfg.contains("wasi") {
+        return !negated;
+    }
+    if negated {
+        return true;
+    }
+    ![
+        "unix",
+        "windows",
+        "target_os",
+        "target_env",
+        "target_vendor",
+        "-",
+    ]
+    .iter()
+    .any(|os| cfg.contains(os))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::imports;
+    use std::path::Path;
+
+    #[test]
+    fn test_dependencies_and_std_apis_are_flagged() {
+        let deps = [
+            ("serde", "serde"),
+            ("reqwest", "reqwest"),
+            ("native-tls", "reqwest"),
+            ("openssl-sys", "reqwest"),
+            ("getrandom", "rand"),
+            ("zstd-sys", "zstd"),
+        ]
+        .map(|(a, b)| (a.to_string(), b.to_string()));
+        let imports = imports::scan(
+            "use std::thread;\nuse std::{fs, os::unix::fs::PermissionsExt};\nuse crate::fs;\n",
+            Path::new("src/lib.rs"),
+            &[],
+        );
+        let scan = WasmScan::build(deps, &imports);
+
+        assert_eq!(scan.checked, 6);
+        let subjects: Vec<&str> = scan
+            .dependencies
+            .iter()
+            .map(|i| i.subject.as_str())
+            .collect();
+        assert_eq!(
+            subjects,
+            vec!["native-tls", "openssl-sys", "zstd-sys", "getrandom"]
+        );
+        assert_eq!(scan.dependencies[0].via.as_deref(), Some("reqwest"));
+        let apis: Vec<&str> = scan.apis.iter().map(|i| i.subject.as_str()).collect();
+        assert_eq!(apis, vec!["std::os::unix", "std::thread", "std::fs"]);
+
+        assert_eq!(scan.outlook(WasmTarget::Unknown), Outlook::Unlikely);
+        assert_eq!(
+            scan.blocking_dependencies(WasmTarget::Unknown),
+            vec!["reqwest", "zstd"]
+        );
+        assert_eq!(
+            scan.blocking_dependencies(WasmTarget::Wasi),
+            vec!["reqwest"]
+        );
+
+        let clean = WasmScan::build([("serde".to_string(), "serde".to_string())], &[]);
+        assert_eq!(clean.outlook(WasmTarget::Wasi), Outlook::Likely);
+    }
+
+    #[test]
+    fn test_target_cfgs() {
+        assert!(cfg_applies_to_wasm("cfg(target_arch = \"wasm32\")"));
+        assert!(!cfg_applies_to_wasm("cfg(not(target_arch = \"wasm32\"))"));
+        assert!(!cfg_applies_to_wasm("cfg(unix)"));
+        assert!(!cfg_applies_to_wasm("x86_64-pc-windows-msvc"));
+        assert!(cfg_applies_to_wasm("cfg(not(windows))"));
+    }
+}
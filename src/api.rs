@@ -0,0 +1,232 @@
+//! A crate's public API as sorted text, for reviewing and gating API changes
+//!
+//! Each public item becomes one line starting with its kind and qualified path,
+//! e.g. `fn config::load(path: &Path) -> Result<Config>` or `field config::Config::theme: String`.
+//! `oracle api --write` saves the listing to [`API_FILE`]; `oracle api --check` fails
+//! when the current API no longer matches it.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::analyzer::module_tree::scope_path;
+use crate::analyzer::usage::base_type_name;
+use crate::analyzer::{AnalyzedItem, FunctionInfo, VariantFields, Visibility};
+
+/// Default file name of the saved listing, next to `Cargo.toml`
+pub const API_FILE: &str = "api-surface.txt";
+
+/// One line per public item, field, variant and method, sorted. Items inside a
+/// module known to be private are left out, as are `tests` modules.
+pub fn surface(items: &[AnalyzedItem]) -> Vec<String> {
+    let modules: HashMap<Vec<String>, Visibility> = items
+        .iter()
+        .filter_map(|item| match item {
+            AnalyzedItem::Module(m) => Some((scope_path(item), m.visibility)),
+            _ => None,
+        })
+        .collect();
+    let exported = |path: &[String]| {
+        (1..=path.len()).all(|n| {
+            path[n - 1] != "tests"
+                && modules
+                    .get(&path[..n])
+                    .map_or(true, |v| *v == Visibility::Public)
+        })
+    };
+
+    // Impls of these aren't reachable from outside
+    let private_types: BTreeSet<String> = items
+        .iter()
+        .filter(|item| matches!(item, AnalyzedItem::Struct(_) | AnalyzedItem::Enum(_)))
+        .filter(|item| {
+            item.visibility() != Some(Visibility::Public) || !exported(item.module_path())
+        })
+        .map(AnalyzedItem::qualified_name)
+        .collect();
+
+    let mut lines = BTreeSet::new();
+    for item in items {
+        if !exported(item.module_path()) {
+            continue;
+        }
+        let path = item.qualified_name();
+        match item {
+            AnalyzedItem::Impl(im) => {
+                let base = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+                if private_types.contains(&qualify(item.module_path(), base)) {
+                    continue;
+                }
+                let self_ty = qualify(item.module_path(), &im.self_ty);
+                if let Some(ref trait_name) = im.trait_name {
+                    let negative = if im.is_negative { "!" } else { "" };
+                    lines.insert(format!("impl {}{} for {}", negative, trait_name, self_ty));
+                    continue;
+                }
+                let owner = qualify(item.module_path(), base);
+                for method in &im.methods {
+                    if method.visibility == Visibility::Public {
+                        lines.insert(function(method, &format!("{}::{}", owner, method.name)));
+                    }
+                }
+                continue;
+            }
+            _ if item.visibility() != Some(Visibility::Public) => continue,
+            AnalyzedItem::Function(f) => {
+                lines.insert(function(f, &path));
+            }
+            AnalyzedItem::Struct(s) => {
+                lines.insert(format!("struct {}{}", path, generics(&s.generics)));
+                for field in s
+                    .fields
+                    .iter()
+                    .filter(|f| f.visibility == Visibility::Public)
+                {
+                    lines.insert(format!("field {}::{}: {}", path, field.name, field.ty));
+                }
+            }
+            AnalyzedItem::Enum(e) => {
+                lines.insert(format!("enum {}{}", path, generics(&e.generics)));
+                for variant in &e.variants {
+                    let fields = match variant.fields {
+                        VariantFields::Named(ref fields) => {
+                            let fields: Vec<String> = fields
+                                .iter()
+                                .map(|f| format!("{}: {}", f.name, f.ty))
+                                .collect();
+                            format!(" {{ {} }}", fields.join(", "))
+                        }
+                        VariantFields::Unnamed(ref types) => format!("({})", types.join(", ")),
+                        VariantFields::Unit => String::new(),
+                    };
+                    lines.insert(format!("variant {}::{}{}", path, variant.name, fields));
+                }
+            }
+            AnalyzedItem::Trait(t) => {
+                let mut line = format!("trait {}{}", path, generics(&t.generics));
+                if !t.supertraits.is_empty() {
+                    line.push_str(&format!(": {}", t.supertraits.join(" + ")));
+                }
+                lines.insert(line);
+                for method in &t.methods {
+                    let qualified = format!("{}::{}", path, method.name);
+                    lines.insert(rename_fn(&method.signature, &method.name, &qualified));
+                }
+                for ty in &t.associated_types {
+                    lines.insert(format!("type {}::{}", path, ty.name));
+                }
+                for c in &t.associated_consts {
+                    lines.insert(format!("const {}::{}: {}", path, c.name, c.ty));
+                }
+            }
+            AnalyzedItem::Module(_) => {
+                lines.insert(format!("mod {}", path));
+            }
+            AnalyzedItem::TypeAlias(t) => {
+                lines.insert(format!("type {}{} = {}", path, generics(&t.generics), t.ty));
+            }
+            AnalyzedItem::Const(c) => {
+                lines.insert(format!("const {}: {}", path, c.ty));
+            }
+            AnalyzedItem::Static(s) => {
+                let mutability = if s.is_mut { "mut " } else { "" };
+                lines.insert(format!("static {}{}: {}", mutability, path, s.ty));
+            }
+        }
+    }
+    lines.into_iter().collect()
+}
+
+/// The listing as file contents: one line each, newline-terminated
+pub fn to_text(surface: &[String]) -> String {
+    surface.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Lines of a saved listing missing from `current`, and lines of `current` missing
+/// from it. Blank lines and `#` comments in the saved file are ignored.
+pub fn diff(saved: &str, current: &[String]) -> (Vec<String>, Vec<String>) {
+    let saved: BTreeSet<&str> = saved
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect();
+    let current_set: BTreeSet<&str> = current.iter().map(String::as_str).collect();
+    let removed = saved
+        .difference(&current_set)
+        .map(|l| l.to_string())
+        .collect();
+    let added = current_set
+        .difference(&saved)
+        .map(|l| l.to_string())
+        .collect();
+    (removed, added)
+}
+
+fn function(f: &FunctionInfo, path: &str) -> String {
+    rename_fn(&f.signature, &f.name, path)
+}
+
+/// `fn name(..)` → `fn path(..)`, keeping qualifiers such as `async` or `unsafe`
+fn rename_fn(signature: &str, name: &str, path: &str) -> String {
+    signature.replacen(&format!("fn {}", name), &format!("fn {}", path), 1)
+}
+
+fn qualify(module_path: &[String], name: &str) -> String {
+    if module_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", module_path.join("::"), name)
+    }
+}
+
+fn generics(generics: &[String]) -> String {
+    if generics.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", generics.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_surface_and_diff() {
+        let source = "pub struct Point<T> { pub x: T, y: T }\n\
+                      impl<T> Point<T> { pub fn new(x: T, y: T) -> Self { todo!() } fn hidden(&self) {} }\n\
+                      impl Default for Point<i32> { fn default() -> Self { todo!() } }\n\
+                      pub enum Shape { Dot, Circle(f64), Rect { w: f64, h: f64 } }\n\
+                      pub trait Area { fn area(&self) -> f64; }\n\
+                      struct Hidden;\nimpl Area for Hidden { fn area(&self) -> f64 { 0.0 } }\n\
+                      pub async fn load() {}\nfn private() {}\npub const MAX: usize = 3;\n\
+                      mod internal { pub fn leaked() {} }\n";
+        let items = RustAnalyzer::new()
+            .with_private(true)
+            .analyze_source(source)
+            .unwrap();
+        let api = surface(&items);
+        assert_eq!(
+            api,
+            vec![
+                "async fn load()",
+                "const MAX: usize",
+                "enum Shape",
+                "field Point::x: T",
+                "fn Area::area(&self) -> f64",
+                "fn Point::new(x: T, y: T) -> Self",
+                "impl Default for Point<i32>",
+                "struct Point<T>",
+                "trait Area",
+                "variant Shape::Circle(f64)",
+                "variant Shape::Dot",
+                "variant Shape::Rect { w: f64, h: f64 }",
+            ]
+        );
+
+        let saved = format!("# public API\n{}", to_text(&api)).replace("const MAX", "const MIN");
+        let (removed, added) = diff(&saved, &api);
+        assert_eq!(removed, vec!["const MIN: usize"]);
+        assert_eq!(added, vec!["const MAX: usize"]);
+        assert_eq!(diff(&to_text(&api), &api), (vec![], vec![]));
+    }
+}
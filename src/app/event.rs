@@ -0,0 +1,1054 @@
+//! Events and the `App::update` reducer
+//!
+//! Everything that changes application state arrives as an [`AppEvent`]: terminal
+//! input, frame ticks, and results sent back by background threads. The terminal
+//! loop in `main.rs` only translates crossterm events and draws; tests and
+//! headless drivers feed events directly.
+
+use super::App;
+use crate::analyzer::AnalyzedItem;
+use crate::crates_io::{CrateDocInfo, CrateSearchResult, GitHubActivity, ReleaseNotes};
+use crate::lsp::{Location, LspClient, Semantics};
+use crate::ui::{completion_at, completion_rect, search_rect_for_area, tabs_rect_for_area, Tab};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
+};
+use ratatui::layout::Rect;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+/// Reported in place of a background task's result when it panicked
+pub(super) const TASK_PANICKED: &str = "internal error, details in panic.log";
+
+/// Longest the event loop sleeps when nothing is moving and no work is in flight
+pub const IDLE_WAIT: Duration = Duration::from_secs(60);
+
+/// Input to [`App::update`]
+#[derive(Debug)]
+pub enum AppEvent {
+    /// Key press
+    Key(KeyEvent),
+    /// Left click at a terminal cell; `area` is the full screen size
+    Click { column: u16, row: u16, area: Rect },
+    /// The terminal was resized; nothing changes but the next frame
+    Resize,
+    /// Once per frame: advance animations, apply finished background tasks, start pending fetches
+    Tick,
+    /// A background task finished
+    Task(TaskResult),
+}
+
+impl AppEvent {
+    /// Key press with no modifiers
+    pub fn key(code: KeyCode) -> Self {
+        Self::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    /// Translate a terminal event; `None` for events the app ignores (key releases, ...)
+    pub fn from_terminal(event: Event, area: Rect) -> Option<Self> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => Some(Self::Key(key)),
+            Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+                Some(Self::Click {
+                    column: mouse.column,
+                    row: mouse.row,
+                    area,
+                })
+            }
+            Event::Resize(..) => Some(Self::Resize),
+            _ => None,
+        }
+    }
+}
+
+/// Result sent back by a background thread
+#[derive(Debug)]
+pub enum TaskResult {
+    /// crates.io metadata for a dependency (`None` when the fetch failed)
+    CrateDocs(String, Option<Box<CrateDocInfo>>),
+    /// crates.io search results for a query (`None` when the search failed)
+    CrateSearch(String, Option<Vec<CrateSearchResult>>),
+    /// Release notes for a dependency upgrade
+    ReleaseNotes(String, Option<ReleaseNotes>),
+    /// Open issues and recent pull requests of a dependency's repository
+    GitHubActivity(String, Option<GitHubActivity>),
+    /// HTTP status of each web link in the docs (`None` when unreachable)
+    UrlStatuses(Vec<(String, Option<u16>)>),
+    /// Copilot reply (or error text)
+    Copilot(String),
+    /// Doc test summary for the status bar
+    Doctest(String),
+    /// `cargo update -p` after applying the upgrade plan finished
+    CargoUpdate { ok: bool, message: String },
+    /// Items from the next few files of the installed crate being analyzed
+    InstalledItems {
+        generation: u64,
+        items: Vec<AnalyzedItem>,
+    },
+    /// Installed crate analysis reached the last file
+    InstalledDone { generation: u64 },
+    /// Every item of an installed crate, for `name::` path completions
+    CratePathItems {
+        name: String,
+        items: Vec<AnalyzedItem>,
+    },
+    /// rust-analyzer started for `root` and loaded its workspace, or why it couldn't
+    RustAnalyzer {
+        root: PathBuf,
+        client: std::result::Result<Box<LspClient>, String>,
+    },
+    /// What rust-analyzer resolved for the item named `item`
+    Semantics {
+        item: String,
+        result: std::result::Result<Semantics, String>,
+    },
+    /// rust-analyzer's hover text and definition for the source cursor at `at`
+    Hover {
+        at: Location,
+        result: std::result::Result<(Option<String>, Vec<Location>), String>,
+    },
+    /// Background filter of the project (or open installed crate) items
+    Filtered {
+        generation: u64,
+        installed: bool,
+        indices: Vec<usize>,
+    },
+}
+
+/// Sender handed to background threads. Every clone holds a token, so the app can
+/// tell that a thread is still running (and may yet send) without tracking each one.
+#[derive(Debug, Clone)]
+pub(super) struct TaskSender {
+    tx: mpsc::Sender<TaskResult>,
+    alive: Arc<()>,
+}
+
+impl TaskSender {
+    pub(super) fn new(tx: mpsc::Sender<TaskResult>) -> Self {
+        Self {
+            tx,
+            alive: Arc::new(()),
+        }
+    }
+
+    /// Results sent after the app has gone are dropped
+    pub(super) fn send(&self, result: TaskResult) {
+        let _ = self.tx.send(result);
+    }
+
+    /// Run `work` on a new thread with a clone of this sender. Should it panic,
+    /// `on_panic` is sent in place of its result so nothing keeps waiting for it
+    /// (`None` when nothing does)
+    pub(super) fn spawn(
+        &self,
+        on_panic: Option<TaskResult>,
+        work: impl FnOnce(&TaskSender) + Send + 'static,
+    ) {
+        let tx = self.clone();
+        thread::spawn(move || {
+            if panic::catch_unwind(AssertUnwindSafe(|| work(&tx))).is_err() {
+                if let Some(result) = on_panic {
+                    tx.send(result);
+                }
+            }
+        });
+    }
+
+    /// Clones held by threads that haven't finished
+    pub(super) fn in_flight(&self) -> usize {
+        Arc::strong_count(&self.alive) - 1
+    }
+}
+
+impl App {
+    /// Apply one event to the application state
+    pub fn update(&mut self, event: AppEvent) {
+        let changed = match event {
+            AppEvent::Key(key) => {
+                self.handle_key(key.code, key.modifiers);
+                true
+            }
+            AppEvent::Click { column, row, area } => {
+                self.handle_click(column, row, area);
+                true
+            }
+            AppEvent::Resize => true,
+            AppEvent::Tick => self.tick(),
+            AppEvent::Task(result) => {
+                self.apply_task(result);
+                true
+            }
+        };
+        self.needs_redraw |= changed;
+        self.log_status();
+    }
+
+    /// Whether anything changed since the last call, i.e. the screen is stale
+    pub fn take_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.needs_redraw)
+    }
+
+    /// Background threads are running or a debounced search is waiting
+    pub fn is_busy(&self) -> bool {
+        self.project.task_tx.in_flight() > 0 || self.project.search_pending_since.is_some()
+    }
+
+    /// How long the event loop may block on input: a frame while animating, the idle
+    /// poll while work is in flight (results are only picked up on a tick), and a
+    /// minute otherwise, since then only input can change anything
+    pub fn poll_interval(&self) -> Duration {
+        if self.animation.is_animating() || self.is_busy() {
+            self.animation.poll_interval()
+        } else {
+            IDLE_WAIT
+        }
+    }
+
+    /// Returns whether the tick changed anything on screen
+    fn tick(&mut self) -> bool {
+        let mut changed = self.animation.is_animating();
+        self.animation.update();
+
+        // Reset inspector scroll on selection change
+        let selected = self.project.list_state.selected();
+        if selected != self.project.last_selected {
+            self.project.inspector_scroll = 0;
+            self.project.code_hscroll = 0;
+            self.project.doc_example_selected = 0;
+            self.animation.on_selection_change();
+            self.project.last_selected = selected;
+            changed = true;
+        }
+
+        self.flush_pending_search();
+        while let Ok(result) = self.project.task_rx.try_recv() {
+            self.apply_task(result);
+            changed = true;
+        }
+        self.maybe_start_crate_doc_fetch();
+        // Spinners move while work is in flight
+        changed || self.is_busy()
+    }
+
+    fn apply_task(&mut self, result: TaskResult) {
+        match result {
+            TaskResult::CrateDocs(name, doc) => self.finish_crate_doc_fetch(name, doc.map(|d| *d)),
+            TaskResult::CrateSearch(query, results) => self.finish_registry_search(query, results),
+            TaskResult::ReleaseNotes(name, notes) => self.finish_release_notes_fetch(name, notes),
+            TaskResult::GitHubActivity(name, activity) => {
+                self.finish_github_activity(name, activity)
+            }
+            TaskResult::UrlStatuses(statuses) => self.finish_link_check(statuses),
+            TaskResult::Copilot(response) => {
+                self.project
+                    .copilot_chat_messages
+                    .push(("assistant".to_string(), response));
+                self.project.copilot_chat_loading = false;
+            }
+            TaskResult::Doctest(message) => {
+                self.project.doctest_running = false;
+                self.status_message = message;
+            }
+            TaskResult::CargoUpdate { ok, message } => self.finish_cargo_update(ok, message),
+            TaskResult::InstalledItems { generation, items } => {
+                self.add_installed_items(generation, items)
+            }
+            TaskResult::InstalledDone { generation } => self.finish_installed_analysis(generation),
+            TaskResult::CratePathItems { name, items } => self.finish_crate_path_index(name, items),
+            TaskResult::RustAnalyzer { root, client } => {
+                self.finish_rust_analyzer_start(root, client)
+            }
+            TaskResult::Semantics { item, result } => self.finish_semantics(item, result),
+            TaskResult::Hover { at, result } => self.finish_hover(at, result),
+            TaskResult::Filtered {
+                generation,
+                installed,
+                indices,
+            } => self.finish_background_filter(generation, installed, indices),
+        }
+    }
+
+    /// Clicking a completion candidate picks it; clicking a tab title switches to it
+    fn handle_click(&mut self, column: u16, row: u16, area: Rect) {
+        if self.project.show_completion && !self.project.filtered_candidates.is_empty() {
+            let len = self.project.filtered_candidates.len();
+            let dropdown = completion_rect(search_rect_for_area(area), len, area);
+            if let Some(index) =
+                completion_at(dropdown, column, row, self.project.completion_offset, len)
+            {
+                self.project.completion_selected = index;
+                self.select_completion();
+                return;
+            }
+        }
+        let Some(tabs_rect) = tabs_rect_for_area(area) else {
+            return;
+        };
+        if column < tabs_rect.x
+            || column >= tabs_rect.x + tabs_rect.width
+            || row < tabs_rect.y
+            || row >= tabs_rect.y + tabs_rect.height
+        {
+            return;
+        }
+        let tab_count = 4u16;
+        let inner_w = tabs_rect.width.saturating_sub(2);
+        if inner_w < tab_count {
+            return;
+        }
+        let tab_width = inner_w / tab_count;
+        let rel = column.saturating_sub(tabs_rect.x + 1);
+        let idx = (rel / tab_width).min(3) as usize;
+        let new_tab = Tab::from_index(idx);
+        if self.project.current_tab != new_tab {
+            self.switch_tab(new_tab);
+        }
+    }
+
+    /// Switch tab, reset the selection and re-filter
+    pub fn switch_tab(&mut self, tab: Tab) {
+        self.project.current_tab = tab;
+        self.project.list_state.select(Some(0));
+        if tab == Tab::Crates && self.installed_crates_list.is_empty() {
+            let _ = self.scan_installed_crates();
+        }
+        self.filter_items();
+        self.animation.on_tab_change();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+    use crate::ui::{CandidateKind, Focus, RegistrySearch};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    fn app_with_items() -> App {
+        let mut app = App::new();
+        app.project.items = RustAnalyzer::new()
+            .analyze_source("pub struct Config; pub fn load() {} pub fn save() {}")
+            .unwrap()
+            .into();
+        app.filter_items();
+        app.project.list_state.select(Some(0));
+        app
+    }
+
+    #[test]
+    fn test_keys_drive_search_and_tabs() {
+        let mut app = app_with_items();
+        app.project.focus = Focus::Search;
+        for c in "load".chars() {
+            app.update(AppEvent::key(KeyCode::Char(c)));
+        }
+        app.project.show_completion = false;
+        app.update(AppEvent::key(KeyCode::Enter));
+        assert_eq!(app.project.search_input, "load");
+        assert_eq!(app.project.focus, Focus::List);
+
+        app.update(AppEvent::key(KeyCode::Char('2')));
+        assert_eq!(app.project.current_tab, Tab::Functions);
+        assert_eq!(app.get_filtered_items()[0].name(), "load");
+
+        app.update(AppEvent::key(KeyCode::Char('q')));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_completion_scrolls_and_takes_clicks() {
+        let mut app = App::new();
+        let source: String = (0..10)
+            .map(|i| format!("pub fn load_{}() {{}}\n", i))
+            .collect();
+        app.project.items = RustAnalyzer::new().analyze_source(&source).unwrap().into();
+        app.update_candidates();
+        app.project.current_tab = Tab::Functions;
+        app.project.focus = Focus::Search;
+        for c in "lo".chars() {
+            app.update(AppEvent::key(KeyCode::Char(c)));
+        }
+        app.filter_items();
+        assert!(app.project.show_completion);
+        assert_eq!(app.project.filtered_candidates.len(), 10);
+        for _ in 0..7 {
+            app.update(AppEvent::key(KeyCode::Down));
+        }
+        assert_eq!(app.project.completion_selected, 7);
+        assert_eq!(app.project.completion_offset, 2);
+
+        // The top row of the dropdown now shows the third candidate
+        let area = Rect::new(0, 0, 120, 40);
+        let dropdown = completion_rect(search_rect_for_area(area), 10, area);
+        let picked = app.project.filtered_candidates[2].primary.clone();
+        app.update(AppEvent::Click {
+            column: dropdown.x + 4,
+            row: dropdown.y + 1,
+            area,
+        });
+        assert_eq!(app.project.search_input, picked);
+        assert!(!app.project.show_completion);
+    }
+
+    #[test]
+    fn test_tick_resets_inspector_scroll_on_selection_change() {
+        let mut app = app_with_items();
+        app.project.focus = Focus::List;
+        app.update(AppEvent::key(KeyCode::Char('2')));
+        app.update(AppEvent::Tick);
+        app.project.inspector_scroll = 5;
+        app.update(AppEvent::Tick);
+        assert_eq!(app.project.inspector_scroll, 5);
+        app.update(AppEvent::key(KeyCode::Char('j')));
+        app.update(AppEvent::Tick);
+        assert_eq!(app.project.inspector_scroll, 0);
+    }
+
+    #[test]
+    fn test_search_is_debounced() {
+        let mut app = app_with_items();
+        app.project.current_tab = Tab::Functions;
+        app.filter_items();
+        app.project.focus = Focus::Search;
+        for c in "sav".chars() {
+            app.update(AppEvent::key(KeyCode::Char(c)));
+        }
+        app.update(AppEvent::Tick);
+        assert!(app.search_pending());
+        assert_eq!(app.get_filtered_items().len(), 2);
+
+        app.project.search_pending_since = Some(std::time::Instant::now() - Duration::from_secs(1));
+        app.update(AppEvent::Tick);
+        assert!(!app.search_pending());
+        assert_eq!(app.get_filtered_items()[0].name(), "save");
+        assert_eq!(app.get_filtered_items().len(), 1);
+    }
+
+    #[test]
+    fn test_signature_horizontal_scroll() {
+        let mut app = App::new();
+        app.project.items = RustAnalyzer::new()
+            .analyze_source("pub fn f(a: u8, b: u8) {}")
+            .unwrap()
+            .into();
+        app.project.current_tab = Tab::Functions;
+        app.filter_items();
+        app.project.list_state.select(Some(0));
+        app.project.focus = Focus::Inspector;
+
+        // Wrapped: → does nothing, ← returns to the list
+        app.update(AppEvent::key(KeyCode::Right));
+        assert_eq!(app.project.code_hscroll, 0);
+        app.update(AppEvent::key(KeyCode::Char('z')));
+        assert!(!app.project.code_wrap);
+        for _ in 0..20 {
+            app.update(AppEvent::key(KeyCode::Right));
+        }
+        let AnalyzedItem::Function(ref f) = app.project.items[0] else {
+            panic!("expected a function");
+        };
+        let longest = f.signature.chars().count();
+        assert_eq!(app.project.code_hscroll, longest - 1);
+        app.update(AppEvent::key(KeyCode::Left));
+        assert_eq!(app.project.code_hscroll, longest - 5);
+        app.project.code_hscroll = 0;
+        app.update(AppEvent::key(KeyCode::Left));
+        assert_eq!(app.project.focus, Focus::List);
+    }
+
+    #[test]
+    fn test_async_audit_overlay() {
+        let mut app = App::new();
+        app.project.items = RustAnalyzer::new()
+            .analyze_source("pub struct Db { conn: Rc<u8> } pub async fn query(db: Db) {}")
+            .unwrap()
+            .into();
+        app.project.focus = Focus::List;
+        let shift_a = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        app.update(AppEvent::Key(shift_a));
+        let report = app.project.report.as_ref().expect("audit opens");
+        assert_eq!(report.sections[0].rows[0].text, "query()");
+        assert_eq!(report.sections[1].rows[0].text, "Db  !Send + !Sync");
+
+        // Keys scroll the overlay instead of reaching the list
+        app.update(AppEvent::key(KeyCode::Char('j')));
+        assert_eq!(app.project.report_scroll, 1);
+        app.update(AppEvent::key(KeyCode::Char('q')));
+        assert!(app.project.report.is_none());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_doc_links_overlay() {
+        let mut app = App::new();
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                "/// Loads a [`Missing`], see <https://example.com/gone>\npub fn load() {}",
+            )
+            .unwrap();
+        app.project.doc_links = crate::analyzer::check_links(&items, &[]);
+        app.project.items = items.into();
+        app.offline = true;
+        app.project.focus = Focus::List;
+        let shift_l = KeyEvent::new(KeyCode::Char('L'), KeyModifiers::SHIFT);
+        app.update(AppEvent::Key(shift_l));
+        let report = app.project.report.as_ref().expect("doc links open");
+        assert_eq!(report.sections[0].rows[0].text, "load  → Missing");
+        assert!(report.summary.ends_with("(offline: not checked)"));
+        assert_eq!(report.sections[2].rows.len(), 1);
+
+        app.update(AppEvent::Task(TaskResult::UrlStatuses(vec![(
+            "https://example.com/gone".into(),
+            Some(404),
+        )])));
+        let report = app.project.report.as_ref().unwrap();
+        assert_eq!(
+            report.sections[1].rows[0].detail.as_deref(),
+            Some("HTTP 404")
+        );
+        assert!(report.sections[2].rows.is_empty());
+        app.update(AppEvent::Key(shift_l));
+        assert!(app.project.report.is_none());
+    }
+
+    #[test]
+    fn test_spelling_view() {
+        let mut app = App::new();
+        app.project.items = RustAnalyzer::new()
+            .analyze_source("/// Teh first\npub fn a() {}\n/// Wich one, seperate\npub fn b() {}")
+            .unwrap()
+            .into();
+        app.project.focus = Focus::List;
+        let shift_w = || AppEvent::Key(KeyEvent::new(KeyCode::Char('W'), KeyModifiers::SHIFT));
+        app.update(shift_w());
+        assert!(app.project.spelling_view.is_none());
+        assert!(app.status_message.starts_with("Spell check is off"));
+
+        app.settings.spelling.enabled = true;
+        app.settings.spelling.dictionary = vec!["wich".into()];
+        app.check_spelling();
+        let words: Vec<&str> = app
+            .project
+            .misspellings
+            .iter()
+            .map(|m| m.word.as_str())
+            .collect();
+        assert_eq!(words, vec!["Teh", "seperate"]);
+        app.update(shift_w());
+        app.update(AppEvent::key(KeyCode::Char('k')));
+        assert_eq!(app.project.spelling_view.as_ref().unwrap().selected, 1);
+        app.update(shift_w());
+        assert!(app.project.spelling_view.is_none());
+    }
+
+    #[test]
+    fn test_message_log_keeps_overwritten_errors() {
+        let mut app = App::new();
+        app.project.focus = Focus::List;
+        app.notify(crate::ui::Severity::Error, "Copy failed: no clipboard");
+        app.status_message = "Found 3 items".into();
+        app.update(AppEvent::Tick);
+        assert_eq!(app.messages.len(), 2);
+        assert_eq!(
+            app.messages.toast().map(|e| e.text.as_str()),
+            Some("Copy failed: no clipboard")
+        );
+
+        app.update(AppEvent::key(KeyCode::Char('!')));
+        let report = app.project.report.as_ref().expect("history opens");
+        assert!(report.sections[0].rows[0].text.ends_with("Found 3 items"));
+        assert!(report.sections[0].rows[1]
+            .text
+            .contains("error  Copy failed"));
+        app.update(AppEvent::key(KeyCode::Char('!')));
+        assert!(app.project.report.is_none());
+    }
+
+    #[test]
+    fn test_search_history_recall() {
+        let mut app = app_with_items();
+        app.project.project_path = Some("/p".into());
+        app.project.focus = Focus::Search;
+        for query in ["load", "Config"] {
+            app.project.search_input = query.to_string();
+            app.project.show_completion = false;
+            app.update(AppEvent::key(KeyCode::Enter));
+            app.project.focus = Focus::Search;
+        }
+        app.clear_search();
+
+        app.update(AppEvent::key(KeyCode::Up));
+        assert_eq!(app.project.search_input, "Config");
+        assert_eq!(app.get_filtered_items()[0].name(), "Config");
+        app.update(AppEvent::key(KeyCode::Up));
+        assert_eq!(app.project.search_input, "load");
+        app.update(AppEvent::key(KeyCode::Down));
+        app.update(AppEvent::key(KeyCode::Down));
+        assert_eq!(app.project.search_input, "");
+        assert_eq!(app.project.focus, Focus::Search);
+
+        // Recent searches are offered while typing
+        app.project.search_input = "lo".to_string();
+        app.filter_items();
+        assert!(app
+            .project
+            .filtered_candidates
+            .iter()
+            .any(|c| c.kind == CandidateKind::History && c.primary == "load"));
+    }
+
+    #[test]
+    fn test_saved_view_picker() {
+        let mut app = App::new();
+        app.project.items = RustAnalyzer::new()
+            .with_private(true)
+            .analyze_source("pub struct Zeta; struct Hidden; pub enum Alpha {} pub fn f() {}")
+            .unwrap()
+            .into();
+        app.filter_items();
+        app.project.focus = Focus::List;
+        let names = |app: &App| -> Vec<String> {
+            app.get_filtered_items()
+                .iter()
+                .map(|i| i.name().to_string())
+                .collect()
+        };
+        let shift = |c| AppEvent::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::SHIFT));
+
+        // Row 1 is the first default view, "Public API"
+        app.update(shift('V'));
+        app.update(AppEvent::key(KeyCode::Down));
+        app.update(AppEvent::key(KeyCode::Enter));
+        assert!(app.project.view_picker.is_none());
+        assert_eq!(app.project.active_view.as_deref(), Some("Public API"));
+        assert_eq!(names(&app), vec!["Alpha", "Zeta"]);
+
+        app.update(shift('V'));
+        app.update(AppEvent::key(KeyCode::Enter));
+        assert_eq!(app.project.active_view, None);
+        assert_eq!(names(&app), vec!["Zeta", "Hidden", "Alpha"]);
+
+        app.project.current_tab = Tab::Functions;
+        let view = app.current_view("Fns");
+        assert_eq!(view.tab, Tab::Functions);
+        assert_eq!(view.summary(), "Functions");
+    }
+
+    #[test]
+    fn test_recent_projects_picker() {
+        let dir = std::env::temp_dir().join(format!("oracle-recent-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.rs"), "pub struct Other;").unwrap();
+        let mut app = App::new();
+        app.project.focus = Focus::List;
+        let shift = |c| AppEvent::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::SHIFT));
+
+        app.update(shift('O'));
+        assert!(app.project_picker.is_none());
+        assert_eq!(app.status_message, "No recent projects yet");
+
+        app.project.search_input = "stale".to_string();
+        app.session.record_open(Path::new("/gone"), "gone", 1);
+        app.session.record_open(&dir, "other", 2);
+        app.update(shift('O'));
+        assert_eq!(app.project_picker.as_ref().unwrap().projects.len(), 2);
+        app.update(AppEvent::key(KeyCode::Enter));
+        assert!(app.project_picker.is_none());
+        assert_eq!(app.project.project_path.as_deref(), Some(dir.as_path()));
+        assert_eq!(app.project.items[0].name(), "Other");
+        assert!(app.project.search_input.is_empty());
+
+        app.project.focus = Focus::List;
+        app.update(shift('O'));
+        app.update(AppEvent::key(KeyCode::Down));
+        app.update(AppEvent::key(KeyCode::Char('d')));
+        assert_eq!(app.session.recent().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_project_tabs() {
+        let dirs: Vec<PathBuf> = ["a", "b"]
+            .iter()
+            .map(|name| {
+                let dir = std::env::temp_dir()
+                    .join(format!("oracle-tabs-test-{}", std::process::id()))
+                    .join(name);
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(dir.join("lib.rs"), format!("pub struct In{};", name)).unwrap();
+                dir
+            })
+            .collect();
+        let mut app = App::new();
+        app.analyze_project(&dirs[0]).unwrap();
+        app.project.search_input = "Ina".to_string();
+        app.open_project_tab(&dirs[1]).unwrap();
+        assert_eq!(app.project.items[0].name(), "Inb");
+        assert_eq!(app.project_tab_names(), (vec!["a".into(), "b".into()], 1));
+
+        app.project.focus = Focus::List;
+        app.update(AppEvent::key(KeyCode::Char(']')));
+        assert_eq!(app.project.project_path.as_deref(), Some(dirs[0].as_path()));
+        assert_eq!(app.project.search_input, "Ina");
+        assert_eq!(app.project_tab_names().1, 0);
+
+        app.project.focus = Focus::List;
+        app.update(AppEvent::Key(KeyEvent::new(
+            KeyCode::Char('X'),
+            KeyModifiers::SHIFT,
+        )));
+        assert_eq!(app.project.project_path.as_deref(), Some(dirs[1].as_path()));
+        assert!(app.project_tabs.is_empty());
+        std::fs::remove_dir_all(dirs[0].parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_files_view_scopes_list() {
+        let dir = std::env::temp_dir().join(format!("oracle-files-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/a.rs"), "pub struct A;\npub struct B;\n").unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "mod a;\nunsafe fn c() {}\n").unwrap();
+        let mut app = App::new();
+        app.analyze_project(&dir).unwrap();
+        app.project.focus = Focus::List;
+        let shift = |c| AppEvent::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::SHIFT));
+
+        app.update(shift('F'));
+        assert!(app.project.files_view.is_some());
+        // By item count, src/a.rs comes first
+        app.update(AppEvent::key(KeyCode::Char('s')));
+        app.update(AppEvent::key(KeyCode::Char('s')));
+        app.update(AppEvent::key(KeyCode::Enter));
+        assert!(app.project.files_view.is_none());
+        assert_eq!(app.project.file_scope, Some(dir.join("src/a.rs")));
+        let names: Vec<&str> = app.get_filtered_items().iter().map(|i| i.name()).collect();
+        assert_eq!(names, vec!["A", "B"]);
+
+        app.update(AppEvent::key(KeyCode::Esc));
+        assert!(app.project.file_scope.is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_notes_view_filter() {
+        let mut app = App::new();
+        let source = "// TODO: one\n// FIXME: two\nfn f() {}\n";
+        app.project.notes = crate::analyzer::notes::scan(source, std::path::Path::new("lib.rs"));
+        app.project.focus = Focus::List;
+        app.update(AppEvent::Key(KeyEvent::new(
+            KeyCode::Char('N'),
+            KeyModifiers::SHIFT,
+        )));
+        let report = app.project.report.as_ref().expect("notes open");
+        assert_eq!(report.sections.len(), 4);
+        assert_eq!(report.sections[0].rows[0].text, "lib.rs:2");
+
+        // `t` narrows to one tag: FIXME first
+        app.update(AppEvent::key(KeyCode::Char('t')));
+        let report = app.project.report.as_ref().unwrap();
+        assert_eq!(report.sections.len(), 1);
+        assert_eq!(report.sections[0].heading, "FIXME");
+        app.update(AppEvent::key(KeyCode::Esc));
+        assert!(app.project.report.is_none());
+        // `t` outside the Notes view is the theme key again
+        assert!(!app.project.notes_open);
+    }
+
+    #[test]
+    fn test_review_notes() {
+        let dir = std::env::temp_dir().join(format!("oracle-review-app-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut app = app_with_items();
+        app.project.project_path = Some(dir.clone());
+        app.project.focus = Focus::List;
+        app.update(AppEvent::key(KeyCode::Char('n')));
+        for c in "Needs docs".chars() {
+            app.update(AppEvent::key(KeyCode::Char(c)));
+        }
+        app.update(AppEvent::key(KeyCode::Enter));
+        assert!(app.project.review_input.is_none());
+        let saved = crate::config::ReviewNotes::load(&dir).unwrap();
+        assert_eq!(saved.notes.len(), 1);
+        assert_eq!(saved.notes[0].note, "Needs docs");
+
+        app.update(AppEvent::key(KeyCode::Char('#')));
+        let report = app.project.report.as_ref().expect("review open");
+        assert_eq!(report.sections[0].rows.len(), 1);
+        assert_eq!(
+            report.sections[0].rows[0].detail.as_deref(),
+            Some("Needs docs")
+        );
+        app.update(AppEvent::key(KeyCode::Char('#')));
+        assert!(app.project.report.is_none());
+
+        // Editing starts from the note, and clearing it removes the note
+        app.update(AppEvent::key(KeyCode::Char('n')));
+        assert_eq!(app.project.review_input.as_deref(), Some("Needs docs"));
+        for _ in 0.."Needs docs".len() {
+            app.update(AppEvent::key(KeyCode::Backspace));
+        }
+        app.update(AppEvent::key(KeyCode::Enter));
+        assert!(app.project.review_notes.notes.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_semantics_without_rust_analyzer() {
+        let mut app = app_with_items();
+        app.project.focus = Focus::List;
+        app.update(AppEvent::key(KeyCode::Char('*')));
+        let report = app.project.report.as_ref().expect("semantics open");
+        assert_eq!(report.title, "Semantics of Config");
+        assert!(report.summary.contains("rust-analyzer is off in settings"));
+        assert_eq!(report.sections[0].rows[0].text, "pub struct Config;");
+
+        // Results arriving after another view opened are dropped
+        app.update(AppEvent::key(KeyCode::Char('*')));
+        assert!(app.project.report.is_none());
+        app.update(AppEvent::key(KeyCode::Char('#')));
+        app.update(AppEvent::Task(TaskResult::Semantics {
+            item: "Config".into(),
+            result: Ok(crate::lsp::Semantics::default()),
+        }));
+        assert_eq!(app.project.report.as_ref().unwrap().title, "Review");
+    }
+
+    #[test]
+    fn test_source_cursor_resolves_and_goes_to_definition() {
+        let dir = std::env::temp_dir().join(format!("oracle-cursor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("lib.rs");
+        std::fs::write(
+            &file,
+            "/// Settings\npub struct Config;\n\npub fn load() -> Config {\n    Config\n}\n",
+        )
+        .unwrap();
+        let mut app = App::new();
+        app.project.items = RustAnalyzer::new()
+            .analyze_file_with_module(&file, Vec::new())
+            .unwrap()
+            .into();
+        app.project.current_tab = Tab::Functions;
+        app.filter_items();
+        app.project.list_state.select(Some(0));
+        app.project.focus = Focus::Inspector;
+        app.update(AppEvent::key(KeyCode::Char('v')));
+        app.update(AppEvent::key(KeyCode::Enter));
+        let cursor = app
+            .project
+            .source_cursor
+            .as_ref()
+            .expect("cursor on the source");
+        assert_eq!(cursor.view().line, 0);
+        assert_eq!(cursor.view().hover[0], "fn load");
+
+        // → moves to `Config` in the return type, resolved from the index
+        app.update(AppEvent::key(KeyCode::Right));
+        let view = app.project.source_cursor.as_ref().unwrap().view();
+        assert_eq!((view.line, view.columns), (0, (17, 23)));
+        assert_eq!(view.source, "oracle's index");
+        assert_eq!(view.hover[0], "struct Config");
+        assert!(view.hover.contains(&"  Settings".to_string()));
+
+        // A late answer for another position is dropped
+        app.update(AppEvent::Task(TaskResult::Hover {
+            at: Location {
+                file: file.clone(),
+                line: 1,
+                column: 1,
+            },
+            result: Ok((Some("pub struct Other".into()), Vec::new())),
+        }));
+        assert_eq!(
+            app.project.source_cursor.as_ref().unwrap().view().hover[0],
+            "struct Config"
+        );
+
+        // Enter goes to the struct in the Types tab, cursor on its name
+        app.update(AppEvent::key(KeyCode::Enter));
+        assert_eq!(app.project.current_tab, Tab::Types);
+        assert_eq!(app.selected_item().unwrap().name(), "Config");
+        let view = app.project.source_cursor.as_ref().unwrap().view();
+        assert_eq!((view.line, view.columns), (1, (11, 17)));
+        assert_eq!(app.project.focus, Focus::Inspector);
+
+        app.update(AppEvent::key(KeyCode::Esc));
+        assert!(app.project.source_cursor.is_none());
+        assert!(!app.should_quit);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_share_session() {
+        let dir = std::env::temp_dir().join(format!("oracle-share-app-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut app = app_with_items();
+        app.project.project_path = Some(dir.clone());
+        app.project.current_tab = Tab::Functions;
+        app.filter_items();
+        app.project.list_state.select(Some(1));
+        app.project.focus = Focus::List;
+        app.update(AppEvent::key(KeyCode::Char('p')));
+        app.project.list_state.select(Some(0));
+        app.update(AppEvent::key(KeyCode::Char('&')));
+        let file = dir.join("target").join("oracle-session.json");
+        let session = crate::config::SharedSession::load(&file).unwrap();
+        assert_eq!(session.selected.as_ref().unwrap().name, "load");
+        assert_eq!(session.pinned.as_ref().unwrap().name, "save");
+
+        let mut teammate = app_with_items();
+        teammate.import_session(session);
+        assert_eq!(teammate.project.current_tab, Tab::Functions);
+        assert_eq!(teammate.selected_item().unwrap().name(), "load");
+        assert_eq!(
+            teammate.project.pinned_item.as_ref().unwrap().name(),
+            "save"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_task_results() {
+        let mut app = App::new();
+        app.project.crate_docs_loading = Some("serde".into());
+        app.update(AppEvent::Task(TaskResult::CrateDocs("serde".into(), None)));
+        assert!(app.project.crate_docs_loading.is_none());
+        assert!(app.project.crate_docs_failed.contains("serde"));
+
+        app.project.copilot_chat_loading = true;
+        app.update(AppEvent::Task(TaskResult::Copilot("hi".into())));
+        assert!(!app.project.copilot_chat_loading);
+        assert_eq!(app.project.copilot_chat_messages.last().unwrap().1, "hi");
+
+        app.project.github_activity_loading = Some("serde".into());
+        let activity = GitHubActivity {
+            repo: "serde-rs/serde".into(),
+            issues: vec![crate::crates_io::GitHubIssue {
+                number: 1,
+                title: "Bug".into(),
+                labels: vec!["bug".into()],
+                created_at: Some("2024-01-01T00:00:00Z".into()),
+                state: "open".into(),
+                ..Default::default()
+            }],
+            pulls: Vec::new(),
+        };
+        app.update(AppEvent::Task(TaskResult::GitHubActivity(
+            "serde".into(),
+            Some(activity),
+        )));
+        assert!(app.project.github_activity_loading.is_none());
+        let report = app.project.report.as_ref().unwrap();
+        assert_eq!(report.title, "serde on GitHub");
+        let row = &report.sections[0].rows[0];
+        assert_eq!(row.text, "#1 Bug");
+        assert!(row.detail.as_deref().unwrap().ends_with("old · bug"));
+    }
+
+    #[test]
+    fn test_registry_search_results() {
+        let mut app = App::new();
+        app.project.current_tab = Tab::Crates;
+        app.project.registry_search = Some(RegistrySearch {
+            query: "serde".into(),
+            results: Vec::new(),
+            loading: true,
+        });
+        let hit = |name: &str| CrateSearchResult {
+            name: name.into(),
+            version: "1.0.0".into(),
+            ..Default::default()
+        };
+        // A result for an older query is dropped
+        app.update(AppEvent::Task(TaskResult::CrateSearch(
+            "ser".into(),
+            Some(vec![hit("ser")]),
+        )));
+        assert!(app.project.registry_search.as_ref().unwrap().loading);
+        app.update(AppEvent::Task(TaskResult::CrateSearch(
+            "serde".into(),
+            Some(vec![hit("serde"), hit("serde_json")]),
+        )));
+        assert!(app.registry_mode());
+        assert_eq!(app.get_current_list_len(), 2);
+        app.project.list_state.select(Some(1));
+        assert_eq!(
+            app.selected_dependency_name().as_deref(),
+            Some("serde_json")
+        );
+
+        app.project.search_input.clear();
+        app.filter_items();
+        assert!(!app.registry_mode());
+    }
+
+    #[test]
+    fn test_pin_and_focus_cycle() {
+        let mut app = app_with_items();
+        app.project.focus = Focus::List;
+        app.update(AppEvent::key(KeyCode::Char('2')));
+        app.update(AppEvent::key(KeyCode::Char('p')));
+        assert_eq!(app.project.pinned_item.as_ref().unwrap().name(), "load");
+
+        app.update(AppEvent::key(KeyCode::Char('j')));
+        assert_eq!(app.selected_item().unwrap().name(), "save");
+        app.update(AppEvent::key(KeyCode::Tab));
+        app.update(AppEvent::key(KeyCode::Tab));
+        assert_eq!(app.project.focus, Focus::Pinned);
+        app.update(AppEvent::key(KeyCode::BackTab));
+        assert_eq!(app.project.focus, Focus::Inspector);
+
+        app.update(AppEvent::key(KeyCode::Tab));
+        app.update(AppEvent::key(KeyCode::Char('p')));
+        assert!(app.project.pinned_item.is_none());
+        assert_eq!(app.project.focus, Focus::Inspector);
+    }
+
+    #[test]
+    fn test_redraw_only_when_dirty() {
+        let mut app = app_with_items();
+        // Settle the initial selection flash
+        for _ in 0..20 {
+            app.update(AppEvent::Tick);
+        }
+        app.take_redraw();
+        app.update(AppEvent::Tick);
+        assert!(!app.take_redraw());
+        assert_eq!(app.poll_interval(), IDLE_WAIT);
+
+        app.update(AppEvent::Resize);
+        assert!(app.take_redraw());
+
+        let tx = app.project.task_tx.clone();
+        assert!(app.is_busy());
+        assert!(app.poll_interval() < IDLE_WAIT);
+        tx.send(TaskResult::Doctest("done".into()));
+        drop(tx);
+        assert!(!app.is_busy());
+        app.update(AppEvent::Tick);
+        assert!(app.take_redraw());
+        assert_eq!(app.status_message, "done");
+    }
+
+    #[test]
+    fn test_panicking_task_reports_failure() {
+        let mut app = App::new();
+        app.project.doctest_running = true;
+        app.project
+            .task_tx
+            .spawn(Some(TaskResult::Doctest(TASK_PANICKED.into())), |_| {
+                panic!("worker failed")
+            });
+        while app.is_busy() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        app.update(AppEvent::Tick);
+        assert!(!app.project.doctest_running);
+        assert_eq!(app.status_message, TASK_PANICKED);
+    }
+}
@@ -0,0 +1,1012 @@
+//! Key bindings: translate key presses into state changes
+
+use super::App;
+use crate::analyzer::AnalyzedItem;
+use crate::ui::{CandidateKind, Focus, Severity, Tab};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Columns moved per ←/→ when signatures scroll sideways
+const CODE_HSCROLL_STEP: usize = 4;
+
+impl App {
+    pub(super) fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        // Release notes popup captures keys until closed
+        if self.project.show_release_notes {
+            match code {
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.project.release_notes_scroll =
+                        self.project.release_notes_scroll.saturating_add(1);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.project.release_notes_scroll =
+                        self.project.release_notes_scroll.saturating_sub(1);
+                }
+                KeyCode::PageDown => {
+                    self.project.release_notes_scroll =
+                        self.project.release_notes_scroll.saturating_add(10);
+                }
+                KeyCode::PageUp => {
+                    self.project.release_notes_scroll =
+                        self.project.release_notes_scroll.saturating_sub(10);
+                }
+                KeyCode::Home | KeyCode::Char('g') => self.project.release_notes_scroll = 0,
+                KeyCode::Esc | KeyCode::Char('q' | 'r') => self.close_release_notes(),
+                _ => {}
+            }
+            return;
+        }
+
+        // Report overlay captures keys the same way
+        if self.project.report.is_some() {
+            match code {
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.project.report_scroll = self.project.report_scroll.saturating_add(1);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.project.report_scroll = self.project.report_scroll.saturating_sub(1);
+                }
+                KeyCode::PageDown => {
+                    self.project.report_scroll = self.project.report_scroll.saturating_add(10)
+                }
+                KeyCode::PageUp => {
+                    self.project.report_scroll = self.project.report_scroll.saturating_sub(10)
+                }
+                KeyCode::Home | KeyCode::Char('g') => self.project.report_scroll = 0,
+                KeyCode::Char('t') if self.project.notes_open => self.cycle_notes_filter(),
+                KeyCode::Char('x') if self.project.notes_open => self.export_notes(),
+                KeyCode::Char('x') if self.project.ffi_open => self.export_ffi_header(),
+                KeyCode::Char('x') if self.project.env_open => self.export_env_vars(),
+                KeyCode::Char('x') if self.project.review_open => self.export_review(),
+                KeyCode::Char('t') if self.project.cfg_open => self.cycle_cfg_target(),
+                KeyCode::Char('t') if self.project.panics_open => self.cycle_panics_filter(),
+                KeyCode::Char('f') if self.project.cfg_open => self.cycle_cfg_features(),
+                KeyCode::Left | KeyCode::Char('h') if self.project.item_history.is_some() => {
+                    self.step_item_history(-1)
+                }
+                KeyCode::Right | KeyCode::Char('l') if self.project.item_history.is_some() => {
+                    self.step_item_history(1)
+                }
+                KeyCode::Char('a' | 'c') if self.project.upgrade_plan_open => {
+                    self.apply_upgrades(code == KeyCode::Char('c'))
+                }
+                KeyCode::Char('z') if self.project.upgrade_plan_open => self.undo_upgrades(),
+                KeyCode::Esc
+                | KeyCode::Char(
+                    'q' | 'A' | 'B' | 'D' | 'E' | 'I' | 'J' | 'K' | 'L' | 'N' | 'Q' | 'R' | 'T'
+                    | 'U' | '!' | '$' | '%' | '@' | '#' | '*',
+                ) => self.close_report(),
+                _ => {}
+            }
+            return;
+        }
+
+        if self.project.view_picker.is_some() {
+            self.handle_view_picker_key(code);
+            return;
+        }
+
+        if let Some(input) = self.project.review_input.as_mut() {
+            match code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => self.save_review_note(),
+                KeyCode::Esc => self.project.review_input = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(picker) = self.project_picker.as_mut() {
+            let rows = picker.projects.len().max(1);
+            match code {
+                KeyCode::Down | KeyCode::Char('j') => {
+                    picker.selected = (picker.selected + 1) % rows
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    picker.selected = picker.selected.checked_sub(1).unwrap_or(rows - 1)
+                }
+                KeyCode::Enter => self.open_picked_project(false),
+                KeyCode::Char('t') => self.open_picked_project(true),
+                KeyCode::Char('d') => self.forget_picked_project(),
+                KeyCode::Esc | KeyCode::Char('q' | 'O') => self.project_picker = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if self.project.feature_picker.is_some() {
+            self.handle_feature_picker_key(code);
+            return;
+        }
+
+        if self.show_settings {
+            self.handle_settings_key(code);
+            return;
+        }
+
+        if self.project.files_view.is_some() {
+            match code {
+                KeyCode::Down | KeyCode::Char('j') => self.move_files_selection(1),
+                KeyCode::Up | KeyCode::Char('k') => self.move_files_selection(-1),
+                KeyCode::Char('s') => self.cycle_file_sort(),
+                KeyCode::Enter => self.scope_to_selected_file(),
+                KeyCode::Esc | KeyCode::Char('q' | 'F') => self.project.files_view = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if self.project.spelling_view.is_some() {
+            match code {
+                KeyCode::Down | KeyCode::Char('j') => self.move_spelling_selection(1),
+                KeyCode::Up | KeyCode::Char('k') => self.move_spelling_selection(-1),
+                KeyCode::Char('a') => self.add_word_to_dictionary(),
+                KeyCode::Esc | KeyCode::Char('q' | 'W') => self.project.spelling_view = None,
+                _ => {}
+            }
+            return;
+        }
+
+        // When Copilot chat panel is open: PgDn/PgUp/arrows/Home/End always scroll the chat (no need to focus chat first)
+        if self.project.copilot_chat_open {
+            match code {
+                KeyCode::PageDown => {
+                    self.project.copilot_chat_scroll =
+                        self.project.copilot_chat_scroll.saturating_add(10);
+                    return;
+                }
+                KeyCode::PageUp => {
+                    self.project.copilot_chat_scroll =
+                        self.project.copilot_chat_scroll.saturating_sub(10);
+                    return;
+                }
+                KeyCode::Down => {
+                    self.project.copilot_chat_scroll =
+                        self.project.copilot_chat_scroll.saturating_add(1);
+                    return;
+                }
+                KeyCode::Up => {
+                    self.project.copilot_chat_scroll =
+                        self.project.copilot_chat_scroll.saturating_sub(1);
+                    return;
+                }
+                KeyCode::Home => {
+                    self.project.copilot_chat_scroll = 0;
+                    return;
+                }
+                KeyCode::End => {
+                    self.project.copilot_chat_scroll =
+                        self.project.copilot_chat_scroll.saturating_add(9999);
+                    return;
+                }
+                KeyCode::Char(c) => {
+                    if modifiers == KeyModifiers::SHIFT && c == 'C' {
+                        // Let Shift+C fall through to toggle panel
+                    } else {
+                        self.project.focus = Focus::CopilotChat;
+                        self.project.copilot_chat_input.push(c);
+                        return;
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.project.focus = Focus::CopilotChat;
+                    self.project.copilot_chat_input.pop();
+                    return;
+                }
+                KeyCode::Enter if modifiers.is_empty() => {
+                    self.project.focus = Focus::CopilotChat;
+                    self.submit_copilot_message();
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        // The source view's cursor takes the arrows, Enter and Esc while it's shown
+        if self.project.focus == Focus::Inspector
+            && self.project.source_cursor.is_some()
+            && !self.show_help
+        {
+            if !self.source_cursor_live() {
+                self.project.source_cursor = None;
+            } else if self.handle_source_cursor_key(code) {
+                return;
+            }
+        }
+
+        // Global shortcuts — never run when focus is CopilotChat or Search
+        let global =
+            self.project.focus != Focus::CopilotChat && self.project.focus != Focus::Search;
+        match code {
+            KeyCode::Char('q') if modifiers.is_empty() && global => {
+                self.should_quit = true;
+                return;
+            }
+            KeyCode::Char('?') if modifiers.is_empty() && global => {
+                self.toggle_help();
+                return;
+            }
+            KeyCode::Char('t') if modifiers.is_empty() && global => {
+                self.cycle_theme();
+                return;
+            }
+            KeyCode::Char('S') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_settings();
+                return;
+            }
+            KeyCode::Char('g') if modifiers.is_empty() && global => {
+                let _ = webbrowser::open("https://github.com/yashksaini-coder/oracle");
+                return;
+            }
+            KeyCode::Char('C') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                if self.selected_item().is_some() {
+                    self.toggle_copilot_chat();
+                } else {
+                    self.status_message =
+                        "Select an item in the list to ask Copilot about it".into();
+                }
+                return;
+            }
+            KeyCode::Char('A') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_async_audit();
+                return;
+            }
+            KeyCode::Char('E') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_error_catalog();
+                return;
+            }
+            KeyCode::Char('B') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_ffi_audit();
+                return;
+            }
+            KeyCode::Char('T') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_wasm_scan();
+                return;
+            }
+            KeyCode::Char('K') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_no_std();
+                return;
+            }
+            KeyCode::Char('J') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_cfg_surface();
+                return;
+            }
+            KeyCode::Char('Q') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_panics();
+                return;
+            }
+            KeyCode::Char('N') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_notes();
+                return;
+            }
+            KeyCode::Char('R') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_unused_items();
+                return;
+            }
+            KeyCode::Char('L') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_doc_links();
+                return;
+            }
+            KeyCode::Char('W') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_spelling();
+                return;
+            }
+            KeyCode::Char('F') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_files_view();
+                return;
+            }
+            KeyCode::Char('!') if global => {
+                self.toggle_message_log();
+                return;
+            }
+            KeyCode::Char('$') if global => {
+                self.toggle_env_vars();
+                return;
+            }
+            KeyCode::Char('%') if global => {
+                self.toggle_allocations();
+                return;
+            }
+            KeyCode::Char('@') if global => {
+                self.toggle_item_history();
+                return;
+            }
+            KeyCode::Char('#') if global => {
+                self.toggle_review();
+                return;
+            }
+            KeyCode::Char('&') if global => {
+                self.export_session();
+                return;
+            }
+            KeyCode::Char('*') if global => {
+                self.toggle_semantics();
+                return;
+            }
+            KeyCode::Char('D') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_debug_log();
+                return;
+            }
+            KeyCode::Char('V') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_view_picker();
+                return;
+            }
+            KeyCode::Char('O') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_project_picker();
+                return;
+            }
+            KeyCode::Char(']') if global => {
+                self.switch_project_tab(1);
+                return;
+            }
+            KeyCode::Char('[') if global => {
+                self.switch_project_tab(-1);
+                return;
+            }
+            KeyCode::Char('X') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.close_project_tab();
+                return;
+            }
+            KeyCode::Char('U') if modifiers.contains(KeyModifiers::SHIFT) && global => {
+                self.toggle_upgrade_plan();
+                return;
+            }
+            KeyCode::Char('s') if modifiers.is_empty() && global => {
+                let _ = webbrowser::open("https://github.com/sponsors/yashksaini-coder");
+                return;
+            }
+            KeyCode::Char('w')
+                if modifiers.is_empty() && global && !self.project.analysis_warnings.is_empty() =>
+            {
+                self.project.show_warnings = !self.project.show_warnings;
+                return;
+            }
+            KeyCode::Char('m')
+                if modifiers.is_empty()
+                    && global
+                    && (self.project.current_tab != Tab::Crates
+                        || self.viewing_installed_crate()) =>
+            {
+                self.toggle_module_tree();
+                return;
+            }
+            KeyCode::Esc => {
+                if self.show_help {
+                    self.show_help = false;
+                } else if self.project.show_completion {
+                    self.project.show_completion = false;
+                } else if self.project.show_module_tree {
+                    self.project.show_module_tree = false;
+                } else if self.project.show_warnings {
+                    self.project.show_warnings = false;
+                } else if self.project.focus == Focus::CopilotChat {
+                    self.toggle_copilot_chat();
+                } else if self.viewing_installed_crate() {
+                    // Up one module level, then back to the dependency list
+                    if !self.leave_installed_module() {
+                        self.clear_installed_crate();
+                    }
+                } else if !self.project.search_input.is_empty() {
+                    self.clear_search();
+                } else if self.project.module_scope.is_some() {
+                    self.clear_module_scope();
+                } else if self.project.file_scope.is_some() {
+                    self.clear_file_scope();
+                } else {
+                    self.should_quit = true;
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        // Help is open - any key closes it
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+
+        // Tab switching with number keys (not when typing)
+        if let KeyCode::Char(c @ '1'..='4') = code {
+            if modifiers.is_empty() && global {
+                self.switch_tab(Tab::from_index(c as usize - '1' as usize));
+                return;
+            }
+        }
+
+        // vim keymap: Ctrl+d / Ctrl+f page down, Ctrl+u / Ctrl+b page up
+        let scrolls = matches!(
+            self.project.focus,
+            Focus::List | Focus::Inspector | Focus::Pinned
+        );
+        let code = match code {
+            KeyCode::Char(c)
+                if self.settings.ui.vim_mode
+                    && scrolls
+                    && modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                match c {
+                    'd' | 'f' => KeyCode::PageDown,
+                    'u' | 'b' => KeyCode::PageUp,
+                    _ => code,
+                }
+            }
+            _ => code,
+        };
+
+        // Focus-specific handling
+        match self.project.focus {
+            Focus::Search => self.handle_search_key(code, modifiers),
+            Focus::List => self.handle_list_key(code, modifiers),
+            Focus::Inspector => self.handle_inspector_key(code, modifiers),
+            Focus::Pinned => self.handle_pinned_key(code, modifiers),
+            Focus::CopilotChat => self.handle_copilot_chat_key(code, modifiers),
+        }
+    }
+
+    fn handle_copilot_chat_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Esc => {
+                self.toggle_copilot_chat();
+            }
+            KeyCode::Enter if modifiers.is_empty() => {
+                self.submit_copilot_message();
+            }
+            KeyCode::Backspace if modifiers.is_empty() => {
+                self.project.copilot_chat_input.pop();
+            }
+            KeyCode::Char(c) if modifiers.is_empty() || modifiers == KeyModifiers::SHIFT => {
+                self.project.copilot_chat_input.push(c);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.project.copilot_chat_scroll =
+                    self.project.copilot_chat_scroll.saturating_add(1);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.project.copilot_chat_scroll =
+                    self.project.copilot_chat_scroll.saturating_sub(1);
+            }
+            KeyCode::PageDown => {
+                self.project.copilot_chat_scroll =
+                    self.project.copilot_chat_scroll.saturating_add(10);
+            }
+            KeyCode::PageUp => {
+                self.project.copilot_chat_scroll =
+                    self.project.copilot_chat_scroll.saturating_sub(10);
+            }
+            KeyCode::Home | KeyCode::Char('g') => {
+                self.project.copilot_chat_scroll = 0;
+            }
+            KeyCode::Tab if modifiers.is_empty() => {
+                self.next_focus();
+            }
+            KeyCode::BackTab => {
+                self.prev_focus();
+            }
+            _ => {}
+        }
+    }
+
+    /// Keys while the settings overlay is open; typing goes to the inline edit
+    fn handle_settings_key(&mut self, code: KeyCode) {
+        if let Some(input) = self.settings_form.editing.as_mut() {
+            match code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => self.commit_setting_edit(),
+                KeyCode::Esc => {
+                    self.settings_form.editing = None;
+                    self.settings_form.error = None;
+                }
+                _ => {}
+            }
+            return;
+        }
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => self.settings_form.move_by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.settings_form.move_by(-1),
+            KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Right | KeyCode::Left => {
+                self.activate_setting()
+            }
+            KeyCode::Char('t') => self.cycle_theme(),
+            KeyCode::Esc | KeyCode::Char('q' | 'S') => self.toggle_settings(),
+            _ => {}
+        }
+    }
+
+    /// Keys while the saved view picker is open; typing goes to the name while saving
+    fn handle_view_picker_key(&mut self, code: KeyCode) {
+        let Some(picker) = self.project.view_picker.as_mut() else {
+            return;
+        };
+        if let Some(name) = picker.naming.as_mut() {
+            match code {
+                KeyCode::Char(c) => name.push(c),
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Enter => {
+                    let name = name.clone();
+                    self.project.view_picker = None;
+                    self.save_view(&name);
+                }
+                KeyCode::Esc => picker.naming = None,
+                _ => {}
+            }
+            return;
+        }
+        let rows = self.settings.views.len() + 1;
+        let selected = picker.selected;
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => picker.selected = (selected + 1) % rows,
+            KeyCode::Up | KeyCode::Char('k') => {
+                picker.selected = selected.checked_sub(1).unwrap_or(rows - 1);
+            }
+            KeyCode::Char('a') => picker.naming = Some(String::new()),
+            KeyCode::Char('d') if selected > 0 => {
+                picker.selected = selected - 1;
+                self.delete_view(selected - 1);
+            }
+            KeyCode::Enter => {
+                let view = selected
+                    .checked_sub(1)
+                    .and_then(|i| self.settings.views.get(i).cloned());
+                self.apply_view(view);
+            }
+            KeyCode::Esc | KeyCode::Char('q' | 'V') => self.project.view_picker = None,
+            _ => {}
+        }
+    }
+
+    fn handle_feature_picker_key(&mut self, code: KeyCode) {
+        let Some(picker) = self.project.feature_picker.as_ref() else {
+            return;
+        };
+        let rows = self.available_features(&picker.crate_name).len() + 1;
+        let selected = picker.selected;
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => self.set_feature_row((selected + 1) % rows),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.set_feature_row(selected.checked_sub(1).unwrap_or(rows - 1))
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle_selected_feature(),
+            KeyCode::Esc | KeyCode::Char('q' | 'f') => self.project.feature_picker = None,
+            _ => {}
+        }
+    }
+
+    fn set_feature_row(&mut self, row: usize) {
+        if let Some(picker) = self.project.feature_picker.as_mut() {
+            picker.selected = row;
+        }
+    }
+
+    fn handle_search_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Char(c) => {
+                self.on_char(c);
+            }
+            KeyCode::Backspace => {
+                self.on_backspace();
+            }
+            KeyCode::Down => {
+                if self.project.show_completion {
+                    self.next_completion();
+                } else if self.project.history_cursor.is_some() {
+                    self.recall_search(false);
+                } else {
+                    self.project.focus = Focus::List;
+                }
+            }
+            KeyCode::Up if self.project.show_completion => {
+                self.prev_completion();
+            }
+            KeyCode::Up
+                if self.project.search_input.is_empty()
+                    || self.project.history_cursor.is_some() =>
+            {
+                self.recall_search(true);
+            }
+            KeyCode::Tab | KeyCode::BackTab if modifiers.is_empty() => {
+                if code == KeyCode::Tab {
+                    if self.project.show_completion {
+                        let picked_filter = self
+                            .project
+                            .filtered_candidates
+                            .get(self.project.completion_selected)
+                            .is_some_and(|c| c.kind == CandidateKind::Filter);
+                        self.select_completion();
+                        if picked_filter {
+                            return; // stay in search so the filter value can be typed
+                        }
+                    }
+                    self.next_focus(); // Tab: search -> list -> inspector
+                } else {
+                    self.prev_focus(); // BackTab: search -> inspector -> list
+                }
+            }
+            KeyCode::Enter => {
+                if self.project.show_completion {
+                    self.select_completion();
+                } else {
+                    // Crates tab: `serde::de::Deserialize` opens serde at that path
+                    self.record_search();
+                    if self.project.current_tab == Tab::Crates {
+                        self.search_qualified_path();
+                    }
+                    self.filter_items();
+                    if self.project.current_tab == Tab::Crates
+                        && self.project.selected_installed_crate.is_none()
+                        && self.project.search_input.starts_with('@')
+                    {
+                        self.start_registry_search();
+                    }
+                    self.project.focus = Focus::List;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_list_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if self.project.show_module_tree
+            && (self.project.current_tab != Tab::Crates || self.viewing_installed_crate())
+        {
+            self.handle_module_tree_key(code, modifiers);
+            return;
+        }
+
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.next_item();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.prev_item();
+            }
+            KeyCode::Tab if modifiers.is_empty() => {
+                self.next_focus();
+            }
+            KeyCode::BackTab => {
+                self.prev_focus();
+            }
+            KeyCode::Char('/') => {
+                self.project.focus = Focus::Search;
+            }
+            KeyCode::Char('I')
+                if modifiers.contains(KeyModifiers::SHIFT)
+                    && self.project.current_tab == Tab::Modules =>
+            {
+                self.toggle_import_graph();
+            }
+            KeyCode::Char('a') if modifiers.is_empty() && self.registry_mode() => {
+                self.add_selected_to_manifest();
+            }
+            // Search results aren't analyzed locally; their docs are in the inspector
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') if self.registry_mode() => {
+                self.project.focus = Focus::Inspector;
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                // Dependencies: Enter on a dep opens that crate's items (from registry)
+                if self.project.current_tab == Tab::Crates
+                    && self.project.selected_installed_crate.is_none()
+                {
+                    if let Some(name) = self.selected_dependency_name() {
+                        if self.dependency_root_name() != Some(name.as_str()) {
+                            let _ = self.select_installed_crate(&name);
+                            self.project.list_state.select(Some(0));
+                        } else {
+                            self.project.focus = Focus::Inspector;
+                        }
+                    } else {
+                        self.project.focus = Focus::Inspector;
+                    }
+                } else if !self.drill_into_selected_module() {
+                    self.project.focus = Focus::Inspector;
+                }
+            }
+            KeyCode::Char('r')
+                if modifiers.is_empty()
+                    && self.project.current_tab == Tab::Crates
+                    && self.project.selected_installed_crate.is_none() =>
+            {
+                self.open_release_notes();
+            }
+            KeyCode::Char('u')
+                if modifiers.is_empty()
+                    && self.project.current_tab == Tab::Crates
+                    && self.project.selected_installed_crate.is_none() =>
+            {
+                self.toggle_upgrade_mark();
+            }
+            KeyCode::Char('f')
+                if modifiers.is_empty()
+                    && self.project.current_tab == Tab::Crates
+                    && self.project.selected_installed_crate.is_none() =>
+            {
+                self.toggle_feature_picker();
+            }
+            KeyCode::Char('H')
+                if self.project.current_tab == Tab::Crates
+                    && self.project.selected_installed_crate.is_none()
+                    && !self.registry_mode() =>
+            {
+                self.toggle_health_sort();
+            }
+            KeyCode::Char('i')
+                if modifiers.is_empty()
+                    && self.project.current_tab == Tab::Crates
+                    && self.project.selected_installed_crate.is_none() =>
+            {
+                self.open_github_activity();
+            }
+            KeyCode::Char('o' | 'c')
+                if modifiers.is_empty() && self.project.current_tab == Tab::Crates =>
+            {
+                self.open_crate_page(code == KeyCode::Char('c'));
+            }
+            KeyCode::Char('u') if modifiers.is_empty() && self.viewing_installed_crate() => {
+                self.show_where_used();
+            }
+            KeyCode::Char('y' | 'Y')
+                if self.project.current_tab == Tab::Crates
+                    && self.project.selected_installed_crate.is_none() =>
+            {
+                self.copy_dependency_line(code == KeyCode::Char('Y'));
+            }
+            KeyCode::Char('p') if modifiers.is_empty() => self.toggle_pin(),
+            KeyCode::Char('v') if modifiers.is_empty() => self.toggle_inspector_raw(),
+            KeyCode::Char('d') if modifiers.is_empty() => self.toggle_inspector_diff(),
+            KeyCode::Char('n') if modifiers.is_empty() => self.edit_review_note(),
+            KeyCode::Char('P') => self.export_item_html(),
+            KeyCode::Left | KeyCode::Char('h') => {
+                if self.viewing_installed_crate() {
+                    if !self.leave_installed_module() {
+                        self.clear_installed_crate();
+                    }
+                } else {
+                    self.project.focus = Focus::Search;
+                }
+            }
+            KeyCode::Home | KeyCode::Char('g') => {
+                let len = self.get_current_list_len();
+                if len > 0 {
+                    self.project.list_state.select(Some(0));
+                }
+            }
+            KeyCode::End | KeyCode::Char('G') => {
+                let len = self.get_current_list_len();
+                if len > 0 {
+                    self.project.list_state.select(Some(len - 1));
+                }
+            }
+            KeyCode::PageDown => {
+                // Jump 10 items
+                for _ in 0..10 {
+                    self.next_item();
+                }
+            }
+            KeyCode::PageUp => {
+                for _ in 0..10 {
+                    self.prev_item();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_module_tree_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => self.module_tree_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.module_tree_prev(),
+            KeyCode::Right | KeyCode::Char('l') => self.module_tree_set_expanded(true),
+            KeyCode::Left | KeyCode::Char('h') => self.module_tree_set_expanded(false),
+            KeyCode::Enter => self.scope_to_selected_module(),
+            KeyCode::Tab if modifiers.is_empty() => self.next_focus(),
+            KeyCode::BackTab => self.prev_focus(),
+            KeyCode::Char('/') => self.project.focus = Focus::Search,
+            _ => {}
+        }
+    }
+
+    fn handle_inspector_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Tab if modifiers.is_empty() => {
+                self.next_focus();
+            }
+            KeyCode::BackTab => {
+                self.prev_focus();
+            }
+            // Unwrapped signatures scroll sideways; at the left edge ← goes back to the list
+            KeyCode::Left | KeyCode::Char('h')
+                if !self.project.code_wrap && self.project.code_hscroll > 0 =>
+            {
+                self.project.code_hscroll =
+                    self.project.code_hscroll.saturating_sub(CODE_HSCROLL_STEP);
+            }
+            KeyCode::Right | KeyCode::Char('l') if !self.project.code_wrap => {
+                let longest = self.selected_item().map_or(0, longest_code_line);
+                self.project.code_hscroll =
+                    (self.project.code_hscroll + CODE_HSCROLL_STEP).min(longest.saturating_sub(1));
+            }
+            KeyCode::Char('z') if modifiers.is_empty() => self.toggle_code_wrap(),
+            KeyCode::Char('v') if modifiers.is_empty() => self.toggle_inspector_raw(),
+            KeyCode::Enter if self.project.inspector_raw => self.start_source_cursor(),
+            KeyCode::Char('d') if modifiers.is_empty() => self.toggle_inspector_diff(),
+            KeyCode::Char('n') if modifiers.is_empty() => self.edit_review_note(),
+            KeyCode::Char('P') => self.export_item_html(),
+            KeyCode::Char('M') => {
+                self.project.method_sort = self.project.method_sort.next();
+                self.status_message =
+                    format!("Methods sorted by {}", self.project.method_sort.label());
+            }
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Esc => {
+                self.project.focus = Focus::List;
+            }
+            KeyCode::Char('/') => {
+                self.project.focus = Focus::Search;
+            }
+            // Scroll the inspector content
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_inspector(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_inspector(-1),
+            KeyCode::PageDown => self.scroll_inspector(10),
+            KeyCode::PageUp => self.scroll_inspector(-10),
+            KeyCode::Home | KeyCode::Char('g') => {
+                self.project.inspector_scroll = 0;
+            }
+            KeyCode::End | KeyCode::Char('G') => {
+                if let Some(max) = self.project.inspector_max_scroll.get() {
+                    self.project.inspector_scroll = max;
+                }
+            }
+            // Doc examples: cycle, copy, run
+            KeyCode::Char('e') if modifiers.is_empty() => self.next_doc_example(),
+            KeyCode::Char('y') if modifiers.is_empty() => self.copy_doc_example(),
+            KeyCode::Char('x') if modifiers.is_empty() => self.run_doc_tests(),
+            KeyCode::Char('p') if modifiers.is_empty() => self.toggle_pin(),
+            KeyCode::Char(' ') => self.toggle_inspector_section(),
+            KeyCode::Char('Z') => self.expand_inspector_sections(),
+            KeyCode::Char('r')
+                if modifiers.is_empty()
+                    && self.project.current_tab == Tab::Crates
+                    && self.project.selected_installed_crate.is_none() =>
+            {
+                self.open_release_notes();
+            }
+            KeyCode::Char('o' | 'c')
+                if modifiers.is_empty() && self.project.current_tab == Tab::Crates =>
+            {
+                self.open_crate_page(code == KeyCode::Char('c'));
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_pinned_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Tab if modifiers.is_empty() => self.next_focus(),
+            KeyCode::BackTab => self.prev_focus(),
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Esc => {
+                self.project.focus = Focus::Inspector
+            }
+            KeyCode::Char('/') => self.project.focus = Focus::Search,
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_pinned(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_pinned(-1),
+            KeyCode::PageDown => self.scroll_pinned(10),
+            KeyCode::PageUp => self.scroll_pinned(-10),
+            KeyCode::Home | KeyCode::Char('g') => self.project.pinned_scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => {
+                if let Some(max) = self.project.pinned_max_scroll.get() {
+                    self.project.pinned_scroll = max;
+                }
+            }
+            KeyCode::Char('p') if modifiers.is_empty() => self.unpin(),
+            _ => {}
+        }
+    }
+
+    /// Keys of the source cursor; `false` leaves the key to the usual handlers
+    fn handle_source_cursor_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Right | KeyCode::Char('l') => self.move_source_cursor(0, 1),
+            KeyCode::Left | KeyCode::Char('h') => self.move_source_cursor(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_source_cursor(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => self.move_source_cursor(-1, 0),
+            KeyCode::PageDown => self.move_source_cursor(10, 0),
+            KeyCode::PageUp => self.move_source_cursor(-10, 0),
+            KeyCode::Enter => self.source_cursor_definition(),
+            KeyCode::Esc => self.close_source_cursor(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn toggle_inspector_raw(&mut self) {
+        self.project.source_cursor = None;
+        self.project.inspector_raw = !self.project.inspector_raw;
+        self.project.inspector_diff = false;
+        self.project.inspector_scroll = 0;
+        self.status_message = if self.project.inspector_raw {
+            "Inspector: source as written".to_string()
+        } else {
+            "Inspector: analysis".to_string()
+        };
+    }
+
+    fn toggle_inspector_diff(&mut self) {
+        self.project.inspector_diff = !self.project.inspector_diff;
+        self.project.inspector_raw = false;
+        self.project.inspector_scroll = 0;
+        self.status_message = if self.project.inspector_diff {
+            format!(
+                "Inspector: changes since {}",
+                self.settings.inspector.diff_ref
+            )
+        } else {
+            "Inspector: analysis".to_string()
+        };
+    }
+
+    fn toggle_code_wrap(&mut self) {
+        self.project.code_wrap = !self.project.code_wrap;
+        self.project.code_hscroll = 0;
+        self.status_message = if self.project.code_wrap {
+            "Signatures: soft-wrap".to_string()
+        } else {
+            "Signatures: scroll with ←/→".to_string()
+        };
+    }
+
+    fn scroll_inspector(&mut self, delta: isize) {
+        self.project.inspector_scroll = scroll_by(
+            self.project.inspector_scroll,
+            delta,
+            self.project.inspector_max_scroll.get(),
+        );
+    }
+
+    fn scroll_pinned(&mut self, delta: isize) {
+        self.project.pinned_scroll = scroll_by(
+            self.project.pinned_scroll,
+            delta,
+            self.project.pinned_max_scroll.get(),
+        );
+    }
+
+    /// Open the selected crate on crates.io (`crates_io`) or docs.rs
+    fn open_crate_page(&mut self, crates_io: bool) {
+        let Some(name) = self.selected_crate_name_for_display() else {
+            return;
+        };
+        let url = if crates_io {
+            format!("https://crates.io/crates/{}", name)
+        } else {
+            format!("https://docs.rs/{}", name)
+        };
+        if webbrowser::open(&url).is_ok() {
+            self.status_message = format!("Opened {} in browser", name);
+        } else {
+            self.notify(Severity::Error, format!("Failed to open {}", url));
+        }
+    }
+}
+
+/// Widest line of the signature / definition the inspector lays out for `item`
+fn longest_code_line(item: &AnalyzedItem) -> usize {
+    let code = match item {
+        AnalyzedItem::Function(f) => f.signature.clone(),
+        AnalyzedItem::Struct(s) => s.full_definition(),
+        _ => return 0,
+    };
+    code.lines().map(|l| l.chars().count()).max().unwrap_or(0)
+}
+
+/// `scroll` moved by `delta` rows, kept within `0..=max` when the limit is known
+fn scroll_by(scroll: usize, delta: isize, max: Option<usize>) -> usize {
+    let moved = scroll.saturating_add_signed(delta);
+    max.map_or(moved, |max| moved.min(max))
+}
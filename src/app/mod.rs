@@ -1,5 +1,9 @@
 //! Application module
 
+mod event;
+mod input;
 mod state;
+mod view;
 
-pub use state::App;
+pub use event::{AppEvent, TaskResult};
+pub use state::{App, CompareProject};
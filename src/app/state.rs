@@ -1,39 +1,193 @@
 //! Application state management
 
+use super::event::{TaskResult, TaskSender, TASK_PANICKED};
+use crate::analyzer::allocations;
+use crate::analyzer::cfg;
+use crate::analyzer::env_vars::{self, EnvRead};
+use crate::analyzer::history::ItemHistory;
+use crate::analyzer::module_tree::scope_path;
+use crate::analyzer::no_std;
+use crate::analyzer::notes;
+use crate::analyzer::proc_macros;
+use crate::analyzer::query::{replace_last_token, suggest_filter_keys};
+use crate::analyzer::Visibility;
 use crate::analyzer::{
-    AnalyzedItem, CrateInfo, CrateRegistry, DependencyAnalyzer, InstalledCrate, RustAnalyzer,
+    apply_url_statuses, c_header, check_links, check_spelling, extract_doc_examples, find_unused,
+    AnalyzedItem, AsyncAudit, CfgTarget, CoverageData, CrateInfo, CrateRegistry,
+    DependencyAnalyzer, DocExample, DocLink, DuplicateCrate, Edition, EditionIssue, ErrorCatalog,
+    FeatureSet, FeatureUnification, FfiSurface, FileMetrics, ImportGraph, InstalledCrate,
+    LinkStatus, MethodSort, Misspelling, ModuleImport, ModuleTree, MsrvReport, NoStdDependency,
+    NoStdReport, Note, NoteTag, PanicKind, Query, RustAnalyzer, SourceLocation, SpellChecker,
+    TreeRow, TypeUsageIndex, WasmScan,
 };
-use crate::config::Settings;
-use crate::crates_io::CrateDocInfo;
-use crate::error::Result;
-use crate::ui::theme::Theme;
-use crate::ui::{filter_candidates, CandidateKind, CompletionCandidate, Focus, Tab};
-use crate::utils::dir_size;
+use crate::config::{
+    ItemRef, ListSort, ReviewNote, ReviewNotes, SavedView, Session, SettingField, Settings,
+    SettingsForm, SharedSession,
+};
+use crate::crates_io::{
+    health_score, CrateDocInfo, CrateSearchResult, GitHubActivity, HealthScore, ReleaseNotes,
+};
+use crate::error::{OracleError, Result};
+use crate::lsp::{self, LspClient, Semantics};
+use crate::report::{analyze_sources, SourceAnalysis};
+use crate::ui::highlight::identifiers;
+use crate::ui::html;
+use crate::ui::inspector::{InspectorCache, SourceCursorView};
+use crate::ui::theme::{terminal_needs_compat, Theme};
+use crate::ui::{
+    completion_offset, filter_candidates, path_candidates, AnimationState, CandidateKind,
+    CompletionCandidate, FeaturePicker, FilesView, Focus, MessageLog, ProjectPicker,
+    RegistrySearch, Report, Severity, SpellingView, Tab, ViewPicker, COMPLETION_VISIBLE,
+};
+use crate::utils::git;
+use crate::utils::manifest::{self, DeclaredFeatures, VersionBump};
+use crate::utils::{copy_to_clipboard, dir_size, logging, SkippedPath};
 
+use cargo_metadata::semver::Version;
 use ratatui::widgets::ListState;
-use std::collections::{HashMap, HashSet};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::mpsc;
-use std::thread;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
 
-/// Main application state
-pub struct App {
-    // Analysis data
+/// Second project loaded for side-by-side comparison (`oracle <path> <other>`)
+pub struct CompareProject {
+    pub path: PathBuf,
+    /// Crate name from Cargo.toml, or the directory name
+    pub name: String,
     pub items: Vec<AnalyzedItem>,
+    /// Indices into `items` matching the current search, tab and scope
+    pub filtered: Vec<usize>,
+}
+
+/// rust-analyzer for the open project, with `analyzer.rust_analyzer` on
+#[derive(Debug, Default)]
+pub enum LspState {
+    #[default]
+    Off,
+    /// Started for this project root and loading its workspace
+    Starting(PathBuf),
+    Ready(PathBuf, Arc<Mutex<LspClient>>),
+    /// Couldn't be started or exited; why
+    Failed(String),
+}
+
+impl LspState {
+    /// Why an item can't be looked up in rust-analyzer right now
+    fn unavailable(&self) -> String {
+        match self {
+            Self::Off => "rust-analyzer is off in settings".to_string(),
+            Self::Starting(_) => "rust-analyzer is still loading".to_string(),
+            Self::Ready(..) => "no source position".to_string(),
+            Self::Failed(reason) => reason.clone(),
+        }
+    }
+}
+
+/// Cursor over the identifiers of the selected item's source (`Enter` in the source
+/// view), with what the one under it resolves to
+#[derive(Debug, Clone)]
+pub(super) struct SourceCursor {
+    /// The item it was put on; it goes away with the selection
+    item: ItemRef,
+    file: PathBuf,
+    /// Line of `file` the item's source starts on
+    first: usize,
+    lines: Vec<String>,
+    /// Line from `first`, and char columns of the identifier on it
+    line: usize,
+    columns: (usize, usize),
+    hover: Vec<String>,
+    /// Where `hover` came from
+    source: &'static str,
+    /// Where rust-analyzer says the identifier is defined, for `Enter`
+    definition: Option<lsp::Location>,
+}
+
+impl SourceCursor {
+    fn word(&self) -> String {
+        let (start, end) = self.columns;
+        self.lines[self.line]
+            .chars()
+            .skip(start)
+            .take(end - start)
+            .collect()
+    }
+
+    fn at(&self) -> lsp::Location {
+        lsp::Location {
+            file: self.file.clone(),
+            line: self.first + self.line,
+            column: self.columns.0 + 1,
+        }
+    }
+
+    pub(super) fn view(&self) -> SourceCursorView<'_> {
+        SourceCursorView {
+            line: self.line,
+            columns: self.columns,
+            hover: &self.hover,
+            source: self.source,
+        }
+    }
+}
+
+/// Items listed by name in the source cursor's popup before "and N more"
+const HOVER_MATCHES: usize = 3;
+/// Lines of rust-analyzer's hover text kept for the popup
+const HOVER_LINES: usize = 8;
+
+/// Manifest and lockfile contents restored by undoing an upgrade plan
+pub(super) struct ManifestBackup {
+    manifest_path: PathBuf,
+    manifest: String,
+    lock: Option<String>,
+}
+
+/// Everything about one open project: its analysis, the list, selection and views
+/// over it, and the background work for it. [`App`] shows one; the other open
+/// projects wait in its project tabs, each keeping its own state.
+pub struct Project {
+    /// Directory or `.rs` file analyzed
+    pub project_path: Option<PathBuf>,
+    /// Line coverage from `--coverage <lcov.info | llvm-cov.json>`
+    pub coverage: Option<CoverageData>,
+
+    // Analysis data
+    /// Shared so a background filter can work on a snapshot while the UI keeps drawing
+    pub items: Arc<Vec<AnalyzedItem>>,
     pub filtered_items: Vec<usize>,
+    /// Reverse type-reference index over `items` (rebuilt after analysis)
+    pub usage_index: TypeUsageIndex,
     pub crate_info: Option<CrateInfo>,
     pub dependency_tree: Vec<(String, usize)>,
+    pub msrv_report: Option<MsrvReport>,
+    /// Code the crate's edition doesn't accept
+    pub edition_issues: Vec<EditionIssue>,
+    pub duplicates: Vec<DuplicateCrate>,
+    pub feature_unification: Vec<FeatureUnification>,
+    /// Crates built into a wasm32 binary, with the direct dependency pulling each in
+    pub wasm_dependencies: Vec<(String, String)>,
+    /// Whether each of those can be built without std
+    pub no_std_dependencies: Vec<NoStdDependency>,
     /// Indices into dependency_tree for Crates tab list (filtered by search). Empty = not computed.
     pub filtered_dependency_indices: Vec<usize>,
 
-    // Installed crates registry
-    pub crate_registry: CrateRegistry,
-    pub installed_crates_list: Vec<String>,
+    // Installed crate open in the Crates tab
     pub selected_installed_crate: Option<InstalledCrate>,
-    pub installed_crate_items: Vec<AnalyzedItem>,
+    pub installed_crate_items: Arc<Vec<AnalyzedItem>>,
     pub installed_crate_filtered: Vec<usize>,
+    pub installed_usage_index: TypeUsageIndex,
+    /// Installed crate items are still streaming in from a background analysis
+    pub installed_loading: bool,
+    /// Set to stop the running installed crate analysis
+    installed_cancel: Arc<AtomicBool>,
+    /// Bumped per analysis so results from a cancelled one are dropped
+    installed_generation: u64,
 
     // UI state
     pub search_input: String,
@@ -41,23 +195,65 @@ pub struct App {
     pub focus: Focus,
     pub list_state: ListState,
     pub completion_selected: usize,
+    /// First candidate shown in the completion dropdown
+    pub completion_offset: usize,
     pub show_completion: bool,
-    pub show_help: bool,
-    pub show_settings: bool,
-    pub status_message: String,
+    /// Files and directories skipped during analysis (unreadable, unparsable, cycles)
+    pub analysis_warnings: Vec<SkippedPath>,
+    /// Warnings panel expanded (`w`)
+    pub show_warnings: bool,
+    /// Vertical scroll of the inspector panel; reset when the selection changes
+    pub inspector_scroll: usize,
+    /// Last scrollable row of the inspector as of the last frame; `None` when the
+    /// right panel isn't an item inspector (dependency views scroll unclamped)
+    pub(super) inspector_max_scroll: Cell<Option<usize>>,
+    /// Section at the top of the item inspector as of the last frame (`Space` collapses it)
+    pub(super) inspector_top_section: RefCell<Option<String>>,
+    /// Inspector lines from earlier frames; cleared whenever items are replaced
+    pub(super) inspector_cache: InspectorCache,
+    /// Long signatures soft-wrap; when off they scroll sideways by `code_hscroll` (`z`)
+    pub code_wrap: bool,
+    pub code_hscroll: usize,
+    /// Inspector shows the selected item's source as written instead of the analysis (`v`)
+    pub inspector_raw: bool,
+    /// Inspector shows the selected item's changes against `inspector.diff_ref` (`d`)
+    pub inspector_diff: bool,
+    /// Order of the struct/enum method table (`M` cycles)
+    pub method_sort: MethodSort,
+    /// Item list order and flags set by the active saved view
+    pub list_sort: ListSort,
+    pub public_only: bool,
+    /// Name of the saved view last applied (shown in the list title)
+    pub active_view: Option<String>,
+    pub(super) view_picker: Option<ViewPicker>,
+    /// Feature toggles of a direct dependency (`f` in the Crates tab)
+    pub(super) feature_picker: Option<FeaturePicker>,
+    /// Crates list ordered by health score (`H`) instead of by name
+    pub(super) sort_crates_by_health: bool,
+    /// crates.io search of the Crates tab, while the query starts with `@`
+    pub(super) registry_search: Option<RegistrySearch>,
+    /// List selection seen by the last tick (to detect selection changes)
+    pub(super) last_selected: Option<usize>,
+
+    // Module tree sidebar (`m`)
+    pub show_module_tree: bool,
+    pub module_tree: ModuleTree,
+    pub module_tree_collapsed: HashSet<Vec<String>>,
+    pub module_tree_selected: usize,
+    /// When set, the results list only shows items inside this module subtree
+    pub module_scope: Option<Vec<String>>,
+    /// Module tree of the open installed crate (rebuilt as its items stream in)
+    pub installed_module_tree: ModuleTree,
+    /// Module the open installed crate is drilled into
+    pub installed_module_scope: Option<Vec<String>>,
+
+    // Comparison mode
+    pub compare: Option<CompareProject>,
 
     // Search
     pub candidates: Vec<CompletionCandidate>,
     pub filtered_candidates: Vec<CompletionCandidate>,
 
-    // Config
-    pub settings: Settings,
-    pub theme: Theme,
-
-    // Control
-    pub should_quit: bool,
-    pub project_path: Option<PathBuf>,
-
     // In-TUI Copilot chat (panel to the right of inspector)
     pub copilot_chat_open: bool,
     /// (role, content) with role "user" or "assistant"
@@ -68,49 +264,228 @@ pub struct App {
     /// Size of target/ directory in bytes (build artifacts), if computed.
     pub target_size_bytes: Option<u64>,
 
-    // Dependency tab: fetched docs from crates.io (background thread, bounded cache)
-    pub crate_docs_cache: HashMap<String, CrateDocInfo>,
+    // Dependency tab: crates.io docs being fetched (the cache is on `App`)
     pub crate_docs_loading: Option<String>,
     pub crate_docs_failed: HashSet<String>,
-    crate_docs_tx: mpsc::Sender<(String, Option<CrateDocInfo>)>,
-    pub crate_docs_rx: mpsc::Receiver<(String, Option<CrateDocInfo>)>,
 
-    pub copilot_tx: mpsc::Sender<String>,
-    pub copilot_rx: mpsc::Receiver<String>,
+    // Release notes between installed and latest version of a dependency (Crates tab, `r`)
+    pub show_release_notes: bool,
+    pub release_notes: Option<ReleaseNotes>,
+    pub release_notes_loading: Option<String>,
+    pub release_notes_scroll: usize,
+    /// Dependency whose GitHub issues and pull requests are being fetched (`i`)
+    pub(super) github_activity_loading: Option<String>,
+    /// Project-wide report overlay (`A` async audit, `E` errors, `B` FFI, `T` WASM,
+    /// `K` no_std, `J` cfg, `N` notes); captures keys while open
+    pub report: Option<Report>,
+    pub report_scroll: usize,
+    /// TODO / FIXME / HACK / SAFETY comments found while analyzing the project
+    pub notes: Vec<Note>,
+    /// Tag shown in the Notes view (`t` cycles; `None` = all)
+    pub notes_filter: Option<NoteTag>,
+    /// The open report is the Notes view, so `t` / `x` filter and export it
+    pub(super) notes_open: bool,
+    /// The open report is the FFI view, so `x` exports a C header
+    pub(super) ffi_open: bool,
+    /// The open report is the Panics view, so `t` filters it
+    pub(super) panics_open: bool,
+    /// Panic kind shown in the Panics view (`t` cycles; `None` = all)
+    pub(super) panics_filter: Option<PanicKind>,
+    /// The open report is the environment view, so `x` exports it as Markdown
+    pub(super) env_open: bool,
+    /// The open report is the cfg view, so `t` / `f` pick the target and features
+    pub(super) cfg_open: bool,
+    /// Index into [`cfg::TARGETS`] the cfg view evaluates against
+    pub(super) cfg_target: usize,
+    pub(super) cfg_features: FeatureSet,
+    /// History of the selected item while its view is open, and the step shown
+    /// (`←`/`→` step through it)
+    pub(super) item_history: Option<(ItemHistory, usize)>,
+    /// Review notes of the project, from `.oracle-review.yaml`
+    pub review_notes: ReviewNotes,
+    /// Text of the review note being written for the selected item (`n`)
+    pub(super) review_input: Option<String>,
+    /// The open report is the review summary, so `x` exports it as Markdown
+    pub(super) review_open: bool,
+    /// rust-analyzer, when enabled; `*` asks it about the selected item
+    pub lsp: LspState,
+    /// Cursor of the source view, resolving identifiers through `lsp` or the index
+    pub(super) source_cursor: Option<SourceCursor>,
+    /// `use` declarations of the project, for the import graph (`I` in the Modules tab)
+    pub imports: Vec<ModuleImport>,
+    /// Environment variables the project reads (`$`)
+    pub env_reads: Vec<EnvRead>,
+    /// Identifier counts of the project's sources, for the unused-items view (`R`)
+    pub identifiers: HashMap<String, usize>,
+    /// Links in the project's doc comments (`L` lists the broken ones)
+    pub doc_links: Vec<DocLink>,
+    /// The open report is the doc links view, rebuilt when the web link check ends
+    pub(super) doc_links_open: bool,
+    pub(super) link_check_running: bool,
+    /// Misspelled words in the project's doc comments, when spell checking is on
+    pub misspellings: Vec<Misspelling>,
+    /// Lines, items, docs and `unsafe` per analyzed file (`F` lists them)
+    pub file_metrics: Vec<FileMetrics>,
+    pub(super) files_view: Option<FilesView>,
+    /// When set, the list only shows items defined in this file
+    pub file_scope: Option<PathBuf>,
+    pub(super) spelling_view: Option<SpellingView>,
+
+    /// Direct dependencies marked for upgrade (`u` in the Crates tab): name → latest version
+    pub upgrade_marks: BTreeMap<String, String>,
+    /// The open report is the upgrade plan (`U`), so `a` / `c` / `z` act on it
+    pub(super) upgrade_plan_open: bool,
+    /// `Cargo.toml` and `Cargo.lock` as they were before the last applied plan
+    pub(super) upgrade_backup: Option<ManifestBackup>,
+    pub cargo_update_running: bool,
+
+    /// Item pinned with `p`, shown in a second inspector next to the selection
+    pub pinned_item: Option<AnalyzedItem>,
+    pub pinned_scroll: usize,
+    pub(super) pinned_max_scroll: Cell<Option<usize>>,
+
+    /// Doc example of the selected item targeted by copy/run (inspector `e`/`y`/`x`)
+    pub doc_example_selected: usize,
+    pub doctest_running: bool,
+
+    /// Last search keystroke not yet applied to the list (debounced in `tick`)
+    pub(super) search_pending_since: Option<Instant>,
+    /// Position in the search history while recalling with ↑/↓
+    pub(super) history_cursor: Option<usize>,
+    /// The list is being re-filtered for the current query on a background thread
+    pub filtering: bool,
+    filter_cancel: Arc<AtomicBool>,
+    filter_generation: u64,
+
+    /// Results of background threads, applied on the next `AppEvent::Tick`
+    pub(super) task_tx: TaskSender,
+    pub(super) task_rx: mpsc::Receiver<TaskResult>,
+}
+
+/// Main application state: the shown project and what belongs to the user rather
+/// than a project
+pub struct App {
+    pub project: Project,
+    /// The other open projects in tab order, `None` marking the shown one; empty
+    /// while only one project is open
+    pub(super) project_tabs: Vec<Option<Project>>,
+    /// Recently opened projects to switch to (`O`)
+    pub(super) project_picker: Option<ProjectPicker>,
+
+    // Installed crates registry
+    pub crate_registry: CrateRegistry,
+    pub installed_crates_list: Vec<String>,
+    /// Items of installed crates indexed for `name::` completions; `None` while one
+    /// is still being analyzed
+    crate_path_items: HashMap<String, Option<Arc<Vec<AnalyzedItem>>>>,
+
+    // UI state
+    pub status_message: String,
+    /// Every status message with its severity and time (`!` shows the history)
+    pub messages: MessageLog,
+    pub show_help: bool,
+    pub show_settings: bool,
+    /// Row, inline edit and validation error of the settings overlay
+    pub(super) settings_form: SettingsForm,
+    /// An analyzer setting changed in the overlay; the project is analyzed again on close
+    pub(super) settings_need_reanalysis: bool,
+    pub animation: AnimationState,
+
+    // Config
+    pub settings: Settings,
+    pub theme: Theme,
+    /// Per-project state kept between runs (search history)
+    pub session: Session,
+
+    // Dependency tab: fetched docs from crates.io (background thread, bounded cache)
+    pub crate_docs_cache: HashMap<String, CrateDocInfo>,
+
+    // Control
+    pub should_quit: bool,
+    /// No network access (crates.io, GitHub, Copilot); from settings or `--offline`
+    pub offline: bool,
+    /// `--include-private`: private items are analyzed whatever the settings say;
+    /// kept out of the settings so it is never saved
+    pub include_private: bool,
+    /// Set by [`App::update`] when the screen may be stale; see [`App::take_redraw`]
+    pub(super) needs_redraw: bool,
 }
 
 /// Max crates to keep in docs cache (memory bound).
 const CRATE_DOCS_CACHE_MAX: usize = 50;
+/// Results requested per crates.io search
+const REGISTRY_SEARCH_RESULTS: usize = 20;
+/// How often streamed installed crate items are handed to the UI thread.
+const INSTALLED_BATCH_INTERVAL: Duration = Duration::from_millis(100);
+/// Typing pause after which the list is re-filtered.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(80);
+/// Lists at least this long are re-filtered off the UI thread while typing.
+const BACKGROUND_FILTER_MIN: usize = 10_000;
+/// Commits of a file the item history goes back through
+const HISTORY_COMMITS: usize = 100;
+/// How long rust-analyzer may load the workspace before it's asked anyway
+const RUST_ANALYZER_LOAD: Duration = Duration::from_secs(180);
 
-impl App {
+impl Project {
     pub fn new() -> Self {
-        let (crate_docs_tx, crate_docs_rx) = mpsc::channel();
-        let (copilot_tx, copilot_rx) = mpsc::channel();
+        let (task_tx, task_rx) = mpsc::channel();
         Self {
-            items: Vec::new(),
+            items: Arc::default(),
             filtered_items: Vec::new(),
+            usage_index: TypeUsageIndex::default(),
             crate_info: None,
             dependency_tree: Vec::new(),
+            msrv_report: None,
+            edition_issues: Vec::new(),
+            duplicates: Vec::new(),
+            feature_unification: Vec::new(),
+            wasm_dependencies: Vec::new(),
+            no_std_dependencies: Vec::new(),
             filtered_dependency_indices: Vec::new(),
-            crate_registry: CrateRegistry::new(),
-            installed_crates_list: Vec::new(),
             selected_installed_crate: None,
-            installed_crate_items: Vec::new(),
+            installed_crate_items: Arc::default(),
             installed_crate_filtered: Vec::new(),
+            installed_usage_index: TypeUsageIndex::default(),
+            installed_loading: false,
+            installed_cancel: Arc::new(AtomicBool::new(false)),
+            installed_generation: 0,
             search_input: String::new(),
             current_tab: Tab::default(),
             focus: Focus::default(),
             list_state: ListState::default(),
             completion_selected: 0,
+            completion_offset: 0,
             show_completion: false,
-            show_help: false,
-            show_settings: false,
-            status_message: String::from("Ready"),
+            analysis_warnings: Vec::new(),
+            show_warnings: false,
+            inspector_scroll: 0,
+            inspector_max_scroll: Cell::new(None),
+            inspector_top_section: RefCell::new(None),
+            inspector_cache: InspectorCache::default(),
+            code_wrap: true,
+            code_hscroll: 0,
+            inspector_raw: false,
+            inspector_diff: false,
+            method_sort: MethodSort::default(),
+            list_sort: ListSort::default(),
+            public_only: false,
+            active_view: None,
+            view_picker: None,
+            feature_picker: None,
+            registry_search: None,
+            sort_crates_by_health: false,
+            last_selected: None,
+            show_module_tree: false,
+            module_tree: ModuleTree::default(),
+            module_tree_collapsed: HashSet::new(),
+            module_tree_selected: 0,
+            module_scope: None,
+            installed_module_tree: ModuleTree::default(),
+            installed_module_scope: None,
+            compare: None,
             candidates: Vec::new(),
             filtered_candidates: Vec::new(),
-            settings: Settings::default(),
-            theme: Theme::default(),
-            should_quit: false,
+            coverage: None,
             project_path: None,
             target_size_bytes: None,
             copilot_chat_open: false,
@@ -118,34 +493,297 @@ impl App {
             copilot_chat_input: String::new(),
             copilot_chat_loading: false,
             copilot_chat_scroll: 0,
-            crate_docs_cache: HashMap::new(),
             crate_docs_loading: None,
             crate_docs_failed: HashSet::new(),
-            crate_docs_tx,
-            crate_docs_rx,
-            copilot_tx,
-            copilot_rx,
+            show_release_notes: false,
+            release_notes: None,
+            release_notes_loading: None,
+            github_activity_loading: None,
+            release_notes_scroll: 0,
+            report: None,
+            report_scroll: 0,
+            notes: Vec::new(),
+            notes_filter: None,
+            notes_open: false,
+            ffi_open: false,
+            panics_open: false,
+            panics_filter: None,
+            env_open: false,
+            cfg_open: false,
+            cfg_target: 0,
+            cfg_features: FeatureSet::default(),
+            item_history: None,
+            review_notes: ReviewNotes::default(),
+            review_input: None,
+            review_open: false,
+            lsp: LspState::Off,
+            source_cursor: None,
+            imports: Vec::new(),
+            env_reads: Vec::new(),
+            identifiers: HashMap::new(),
+            doc_links: Vec::new(),
+            doc_links_open: false,
+            link_check_running: false,
+            misspellings: Vec::new(),
+            file_metrics: Vec::new(),
+            files_view: None,
+            file_scope: None,
+            spelling_view: None,
+            upgrade_marks: BTreeMap::new(),
+            upgrade_plan_open: false,
+            upgrade_backup: None,
+            cargo_update_running: false,
+            pinned_item: None,
+            pinned_scroll: 0,
+            pinned_max_scroll: Cell::new(None),
+            doc_example_selected: 0,
+            doctest_running: false,
+            search_pending_since: None,
+            history_cursor: None,
+            filtering: false,
+            filter_cancel: Arc::new(AtomicBool::new(false)),
+            filter_generation: 0,
+            task_tx: TaskSender::new(task_tx),
+            task_rx,
+        }
+    }
+
+    /// Stop the background analysis and filtering started for the project
+    fn cancel(&self) {
+        self.installed_cancel.store(true, Ordering::Relaxed);
+        self.filter_cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Crate name, or the directory name for a folder of `.rs` files
+    pub fn project_name(&self) -> String {
+        match (&self.crate_info, &self.project_path) {
+            (Some(info), _) => info.name.clone(),
+            (None, Some(path)) => path
+                .file_name()
+                .map_or_else(String::new, |n| n.to_string_lossy().into_owned()),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            project: Project::new(),
+            project_tabs: Vec::new(),
+            project_picker: None,
+            crate_registry: CrateRegistry::new(),
+            installed_crates_list: Vec::new(),
+            crate_path_items: HashMap::new(),
+            status_message: String::from("Ready"),
+            messages: MessageLog::new(),
+            show_help: false,
+            show_settings: false,
+            settings_form: SettingsForm::default(),
+            settings_need_reanalysis: false,
+            animation: AnimationState::new(),
+            settings: Settings::default(),
+            theme: Theme::default(),
+            session: Session::default(),
+            crate_docs_cache: HashMap::new(),
+            should_quit: false,
+            offline: false,
+            include_private: false,
+            needs_redraw: true,
         }
     }
 
     /// Load settings from config file
     pub fn load_settings(&mut self) -> Result<()> {
         self.settings = Settings::load()?;
-        self.theme = Theme::from_name(&self.settings.ui.theme);
+        self.theme = Theme::from_name(&self.settings.ui.theme)
+            .with_compat(self.settings.ui.compat || terminal_needs_compat());
+        let ui = &self.settings.ui;
+        self.animation
+            .configure(ui.animations, ui.easing, ui.max_fps);
+        self.offline |= self.settings.network.offline;
         Ok(())
     }
 
+    /// Private items are analyzed, per the settings or `--include-private`
+    pub fn include_private(&self) -> bool {
+        self.include_private || self.settings.analyzer.include_private
+    }
+
+    /// Merge the `.oracle.yaml` of `root` over the user's settings; a broken file
+    /// is reported and ignored
+    pub fn load_project_config(&mut self, root: &Path) {
+        let user = self.settings.user();
+        match user.clone().with_project(root) {
+            Ok(settings) => self.settings = settings,
+            Err(e) => {
+                self.settings = user;
+                self.notify(Severity::Warning, format!("Ignoring .oracle.yaml: {}", e));
+            }
+        }
+        self.project.review_notes = ReviewNotes::load(root).unwrap_or_else(|e| {
+            self.notify(
+                Severity::Warning,
+                format!("Ignoring {}: {}", ReviewNotes::FILE_NAME, e),
+            );
+            ReviewNotes::default()
+        });
+    }
+
+    /// Load the per-project state saved by earlier runs
+    pub fn load_session(&mut self) {
+        self.session = Session::load();
+    }
+
+    /// Submitted queries for the current project, oldest first
+    pub fn search_history(&self) -> &[String] {
+        self.project
+            .project_path
+            .as_deref()
+            .and_then(|root| self.session.project(root))
+            .map_or(&[], |p| p.search_history.as_slice())
+    }
+
+    /// Remember the current query for this project
+    pub(super) fn record_search(&mut self) {
+        self.project.history_cursor = None;
+        let Some(ref root) = self.project.project_path else {
+            return;
+        };
+        self.session
+            .project_mut(root)
+            .record_search(&self.project.search_input);
+        let _ = self.session.save();
+    }
+
+    /// Step through the search history: ↑ (`older`) from the newest query back, ↓
+    /// forward again and past the newest to an empty search
+    pub(super) fn recall_search(&mut self, older: bool) {
+        let len = self.search_history().len();
+        let cursor = match (self.project.history_cursor, older) {
+            (None, true) => len.checked_sub(1),
+            (None, false) => return,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) => Some(i + 1).filter(|&i| i < len),
+        };
+        self.project.history_cursor = cursor;
+        self.project.search_input = cursor
+            .and_then(|i| self.search_history().get(i).cloned())
+            .unwrap_or_default();
+        self.project.show_completion = false;
+        self.filter_items();
+    }
+
     /// Cycle to the next theme and persist to config
     pub fn cycle_theme(&mut self) {
         let next = self.theme.kind().next();
-        self.theme = Theme::from_kind(next);
+        self.theme = Theme::from_kind(next).with_compat(self.theme.compat);
         self.settings.ui.theme = next.name().to_string();
         self.status_message = format!("Theme: {}", next.display_name());
         let _ = self.settings.save();
     }
 
+    /// Open or close the settings overlay; closing it re-analyzes the project if
+    /// include-private or the excludes changed
     pub fn toggle_settings(&mut self) {
         self.show_settings = !self.show_settings;
+        self.settings_form = SettingsForm::default();
+        if self.show_settings || !std::mem::take(&mut self.settings_need_reanalysis) {
+            return;
+        }
+        if let Some(path) = self.project.project_path.clone() {
+            if let Err(e) = self.analyze_project(&path) {
+                self.notify(Severity::Error, format!("Re-analysis failed: {}", e));
+            }
+        }
+    }
+
+    /// Settings overlay: flip or cycle the highlighted setting, or start editing
+    /// it inline if it's typed
+    pub fn activate_setting(&mut self) {
+        let field = self.settings_form.field();
+        if field.set_by_project(&self.settings) {
+            self.settings_form.error = Some("Set by the project's .oracle.yaml".to_string());
+            return;
+        }
+        if field.kind() == crate::config::FieldKind::Text {
+            self.settings_form.editing = Some(field.value(&self.settings));
+            self.settings_form.error = None;
+            return;
+        }
+        field.advance(&mut self.settings);
+        self.setting_changed(field);
+    }
+
+    /// Settings overlay: validate the inline edit and store it, or keep editing
+    /// with the reason it was rejected
+    pub fn commit_setting_edit(&mut self) {
+        let Some(input) = self.settings_form.editing.take() else {
+            return;
+        };
+        let field = self.settings_form.field();
+        match field.set(&mut self.settings, &input) {
+            Ok(()) => self.setting_changed(field),
+            Err(reason) => {
+                self.settings_form.editing = Some(input);
+                self.settings_form.error = Some(reason);
+            }
+        }
+    }
+
+    /// Apply a setting edited in the overlay to the running app and save the config
+    fn setting_changed(&mut self, field: SettingField) {
+        self.settings_form.error = None;
+        let ui = &self.settings.ui;
+        match field {
+            SettingField::Theme | SettingField::Compat => {
+                self.theme =
+                    Theme::from_name(&ui.theme).with_compat(ui.compat || terminal_needs_compat());
+            }
+            SettingField::Animations | SettingField::MaxFps => {
+                self.animation
+                    .configure(ui.animations, ui.easing, ui.max_fps)
+            }
+            SettingField::Offline => self.offline = self.settings.network.offline,
+            SettingField::Spelling => self.check_spelling(),
+            SettingField::RustAnalyzer => self.start_rust_analyzer(),
+            SettingField::IncludePrivate
+            | SettingField::LightInstalled
+            | SettingField::Exclude
+            | SettingField::SectionOrder
+            | SettingField::HiddenSections
+            | SettingField::DiffRef
+            | SettingField::TerminalTitle
+            | SettingField::Keymap => {}
+        }
+        self.settings_need_reanalysis |= field.needs_reanalysis();
+        match self.settings.save() {
+            Ok(()) => {
+                self.status_message = format!("{}: {}", field.label(), field.value(&self.settings))
+            }
+            Err(e) => self.notify(Severity::Error, format!("Saving settings failed: {}", e)),
+        }
+    }
+
+    /// Dependency tree, MSRV, duplicates, feature unification and the dependencies
+    /// built for wasm32 or `no_std` from `cargo metadata`
+    fn load_dependencies(&mut self, manifest_path: &Path) {
+        match DependencyAnalyzer::from_manifest(manifest_path) {
+            Ok(analyzer) => {
+                if let Some(root) = analyzer.root_package() {
+                    self.project.dependency_tree = analyzer.dependency_tree(&root.name);
+                    self.project.msrv_report = Some(analyzer.msrv_report(&root.name));
+                    self.project.duplicates = analyzer.duplicates();
+                    self.project.feature_unification = analyzer.feature_unification();
+                    self.project.wasm_dependencies = analyzer.wasm_dependencies(&root.name);
+                    self.project.no_std_dependencies = analyzer.no_std_dependencies(&root.name);
+                    self.project.crate_info = Some(root);
+                }
+            }
+            Err(e) => {
+                self.notify(Severity::Error, format!("Cargo analysis failed: {e}"));
+            }
+        }
     }
 
     /// Analyze a Rust project
@@ -156,195 +794,641 @@ impl App {
                 path.display()
             )));
         }
-        self.project_path = Some(path.to_path_buf());
+        if !self.settings.has_project(path) {
+            self.load_project_config(path);
+        }
+        if self.project.project_path.as_deref() != Some(path) {
+            self.project.current_tab = self.settings.ui.default_tab;
+        }
+        self.project.project_path = Some(path.to_path_buf());
         self.status_message = format!("Analyzing {}...", path.display());
 
         // Try to analyze Cargo.toml for dependencies
         let manifest_path = path.join("Cargo.toml");
         if manifest_path.exists() {
-            match DependencyAnalyzer::from_manifest(&manifest_path) {
-                Ok(analyzer) => {
-                    if let Some(root) = analyzer.root_package() {
-                        self.dependency_tree = analyzer.dependency_tree(&root.name);
-                        self.crate_info = Some(root);
-                    }
-                }
-                Err(e) => {
-                    self.status_message = format!("Cargo analysis failed: {e}");
-                }
-            }
+            self.load_dependencies(&manifest_path);
         }
 
-        // Analyze Rust source files
-        let analyzer = RustAnalyzer::new().with_private(self.settings.analyzer.include_private);
+        // Analyze Rust source files, read as the crate's edition
+        let edition = self
+            .project
+            .crate_info
+            .as_ref()
+            .and_then(|info| Edition::parse(&info.edition));
+        let analyzer = RustAnalyzer::new()
+            .with_private(self.include_private())
+            .with_excludes(self.settings.analyzer.exclude.clone())
+            .with_edition(edition.unwrap_or_default());
 
-        let src_path = path.join("src");
-        if path.is_file() && path.extension().is_some_and(|ext| ext == "rs") {
-            self.items = analyzer.analyze_file(path)?;
-        } else if src_path.exists() {
-            self.analyze_directory(&analyzer, &src_path)?;
-        } else if path.is_dir() {
-            // No src/ (e.g. flat layout): analyze directory for .rs files
-            self.analyze_directory(&analyzer, &path.to_path_buf())?;
+        let SourceAnalysis {
+            items,
+            mut notes,
+            imports,
+            env_reads,
+            identifiers,
+            skipped: warnings,
+            files,
+            edition_issues,
+        } = analyze_sources(&analyzer, path)?;
+        notes::attach_items(&mut notes, &items);
+        self.project.notes = notes;
+        self.project.imports = imports;
+        self.project.env_reads = env_reads;
+        self.project.identifiers = identifiers;
+        self.project.file_metrics = files;
+        if self
+            .project
+            .file_scope
+            .as_ref()
+            .is_some_and(|scope| !self.project.file_metrics.iter().any(|f| &f.path == scope))
+        {
+            self.project.file_scope = None;
         }
+        self.project.doc_links = check_links(&items, &self.project.imports);
+        self.project.items = Arc::new(items);
+        self.check_spelling();
+        self.project.analysis_warnings = warnings;
+        self.project.edition_issues = edition_issues;
+        self.project.inspector_cache.clear();
 
+        self.project.usage_index = TypeUsageIndex::build(&self.project.items);
+        let root_name = self
+            .project
+            .crate_info
+            .as_ref()
+            .map(|c| c.name.clone())
+            .unwrap_or_else(|| "crate".to_string());
+        self.project.module_tree = ModuleTree::build(&self.project.items, &root_name);
         self.update_candidates();
         self.filter_items();
-        self.status_message = if self.items.is_empty() {
+        if let Some(first) = self.project.analysis_warnings.first() {
+            self.notify(
+                Severity::Warning,
+                format!(
+                    "{} files or spans skipped during analysis, e.g. {} (w lists them)",
+                    self.project.analysis_warnings.len(),
+                    first
+                ),
+            );
+        }
+        if let Some(first) = self.project.edition_issues.first() {
+            self.notify(
+                Severity::Warning,
+                format!(
+                    "{} places don't fit edition {}, e.g. {}:{}: {}",
+                    self.project.edition_issues.len(),
+                    analyzer.edition(),
+                    first.file.display(),
+                    first.line,
+                    first.message
+                ),
+            );
+        }
+        self.status_message = if self.project.items.is_empty() {
             format!("No Rust files found in {}", path.display())
         } else {
-            format!("Found {} items", self.items.len())
+            format!("Found {} items", self.project.items.len())
         };
 
+        // Only directories with Rust code are offered as recent projects
+        if !self.project.items.is_empty() {
+            let name = self.project.project_name();
+            self.session.record_open(path, &name, unix_now());
+            let _ = self.session.save();
+        }
+
+        self.start_rust_analyzer();
+
         // Best-effort target/ directory size (non-blocking, ignore errors)
         let target_dir = path.join("target");
         if target_dir.is_dir() {
-            self.target_size_bytes = dir_size(&target_dir);
+            self.project.target_size_bytes = dir_size(&target_dir);
         } else {
-            self.target_size_bytes = None;
+            self.project.target_size_bytes = None;
         }
 
         Ok(())
     }
 
-    fn analyze_directory(&mut self, analyzer: &RustAnalyzer, dir: &PathBuf) -> Result<()> {
-        for entry in std::fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                self.analyze_directory(analyzer, &path)?;
-            } else if path.extension().is_some_and(|ext| ext == "rs") {
-                match analyzer.analyze_file(&path) {
-                    Ok(items) => self.items.extend(items),
-                    Err(e) => {
-                        // Log but continue
-                        eprintln!("Warning: Failed to analyze {}: {}", path.display(), e);
-                    }
-                }
-            }
+    /// Switch to another project: everything about the current one is dropped,
+    /// while settings, theme, session and the message log carry over
+    pub fn open_project(&mut self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Err(OracleError::Other(format!(
+                "Path does not exist: {}",
+                path.display()
+            )));
         }
-        Ok(())
+        self.project.cancel();
+        self.show(Project::new());
+        self.analyze_project(path)
     }
 
-    /// Update completion candidates from analyzed items
-    pub fn update_candidates(&mut self) {
-        self.candidates = self
-            .items
-            .iter()
-            .map(|item| {
-                let kind = match item {
-                    AnalyzedItem::Function(_) => CandidateKind::Function,
-                    AnalyzedItem::Struct(_) => CandidateKind::Struct,
-                    AnalyzedItem::Enum(_) => CandidateKind::Enum,
-                    AnalyzedItem::Trait(_) => CandidateKind::Trait,
-                    AnalyzedItem::Module(_) => CandidateKind::Module,
-                    AnalyzedItem::TypeAlias(_) => CandidateKind::Type,
-                    AnalyzedItem::Const(_) | AnalyzedItem::Static(_) => CandidateKind::Const,
-                    _ => CandidateKind::Other,
-                };
-
-                let secondary = item.documentation().map(|d| {
-                    let first_line = d.lines().next().unwrap_or("");
-                    if first_line.len() > 40 {
-                        format!("{}...", &first_line[..37])
-                    } else {
-                        first_line.to_string()
-                    }
-                });
-
-                CompletionCandidate {
-                    primary: item.name().to_string(),
-                    secondary,
-                    kind,
-                    score: 0,
-                }
-            })
-            .collect();
-
-        self.filtered_candidates = self.candidates.clone();
+    /// Show `project` in place of the current one, which is returned. Settings
+    /// go back to the user's, with `project`'s own `.oracle.yaml` on top
+    fn show(&mut self, project: Project) -> Project {
+        let previous = std::mem::replace(&mut self.project, project);
+        self.settings = self.settings.user();
+        if let Some(root) = self.project.project_path.clone() {
+            self.load_project_config(&root);
+        }
+        self.needs_redraw = true;
+        previous
     }
 
-    /// Filter items based on search input and current tab
-    pub fn filter_items(&mut self) {
-        let query = self.search_input.to_lowercase();
+    /// Open `path` next to the current project, in a new project tab
+    pub fn open_project_tab(&mut self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Err(OracleError::Other(format!(
+                "Path does not exist: {}",
+                path.display()
+            )));
+        }
+        if self.project_tabs.is_empty() {
+            self.project_tabs.push(None);
+        }
+        let slot = self.shown_tab();
+        let previous = self.show(Project::new());
+        self.project_tabs[slot] = Some(previous);
+        self.project_tabs.insert(slot + 1, None);
+        self.analyze_project(path)
+    }
 
-        // Crates tab: when inside a crate, filter its items
-        if self.current_tab == Tab::Crates && self.selected_installed_crate.is_some() {
-            self.filter_installed_crates();
+    /// Switch to the project tab `delta` places away, wrapping around
+    pub fn switch_project_tab(&mut self, delta: isize) {
+        if self.project_tabs.len() < 2 {
+            self.status_message = "Only one project open (t in the O picker opens another)".into();
             return;
         }
+        let slot = self.shown_tab();
+        let len = self.project_tabs.len() as isize;
+        let target = (slot as isize + delta).rem_euclid(len) as usize;
+        let Some(next) = self.project_tabs[target].take() else {
+            return;
+        };
+        let previous = self.show(next);
+        self.project_tabs[slot] = Some(previous);
+        self.status_message = format!("Project: {}", self.project.project_name());
+    }
 
-        // Crates tab (top level): filter crate list by name, keep alphabetical order
-        if self.current_tab == Tab::Crates {
-            let mut indices: Vec<usize> = self
-                .dependency_tree
-                .iter()
-                .enumerate()
-                .filter(|(_, (name, _))| {
-                    query.is_empty()
-                        || name.to_lowercase().contains(&query)
-                        || name.to_lowercase().replace('-', "_").contains(&query)
-                })
-                .map(|(i, _)| i)
-                .collect();
-            indices.sort_by(|&a, &b| {
-                self.dependency_tree[a]
-                    .0
-                    .to_lowercase()
-                    .cmp(&self.dependency_tree[b].0.to_lowercase())
-            });
-            self.filtered_dependency_indices = indices;
-            if self
-                .list_state
-                .selected()
-                .is_some_and(|s| s >= self.filtered_dependency_indices.len())
-            {
-                self.list_state.select(Some(0));
-            }
-            self.filtered_candidates = Vec::new();
-            self.completion_selected = 0;
+    /// Close the current project tab and show its neighbour
+    pub fn close_project_tab(&mut self) {
+        if self.project_tabs.len() < 2 {
+            self.status_message = "Only one project open".into();
+            return;
+        }
+        let slot = self.shown_tab();
+        let target = if slot + 1 < self.project_tabs.len() {
+            slot + 1
+        } else {
+            slot - 1
+        };
+        let Some(next) = self.project_tabs[target].take() else {
             return;
+        };
+        self.show(next).cancel();
+        self.project_tabs.remove(slot);
+        if self.project_tabs.len() < 2 {
+            self.project_tabs.clear();
         }
+        self.status_message = format!("Project: {}", self.project.project_name());
+    }
 
-        self.filtered_items = self
-            .items
+    /// Index of the shown project among the project tabs
+    fn shown_tab(&self) -> usize {
+        self.project_tabs
             .iter()
-            .enumerate()
-            .filter(|(_, item)| {
-                // Filter by tab
-                let tab_match = match self.current_tab {
-                    Tab::Types => matches!(
-                        item,
-                        AnalyzedItem::Struct(_)
-                            | AnalyzedItem::Enum(_)
-                            | AnalyzedItem::TypeAlias(_)
-                    ),
-                    Tab::Functions => matches!(item, AnalyzedItem::Function(_)),
-                    Tab::Modules => matches!(item, AnalyzedItem::Module(_)),
-                    Tab::Crates => true, // Handled by crate list or filter_installed_crates
-                };
-
-                // Filter by search
-                let search_match = query.is_empty() || item.name().to_lowercase().contains(&query);
+            .position(Option::is_none)
+            .unwrap_or(0)
+    }
 
-                tab_match && search_match
+    /// Names of the open projects in tab order, and which one is shown
+    pub fn project_tab_names(&self) -> (Vec<String>, usize) {
+        let mut active = 0;
+        let names = self
+            .project_tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| match tab {
+                Some(project) => project.project_name(),
+                None => {
+                    active = i;
+                    self.project.project_name()
+                }
             })
-            .map(|(i, _)| i)
             .collect();
+        (names, active)
+    }
 
-        // Reset selection if out of bounds
-        if self
-            .list_state
-            .selected()
-            .is_some_and(|s| s >= self.filtered_items.len())
+    /// Open or close the recent projects picker
+    pub fn toggle_project_picker(&mut self) {
+        if self.project_picker.take().is_some() {
+            return;
+        }
+        let projects = self.session.recent();
+        if projects.is_empty() {
+            self.status_message = "No recent projects yet".to_string();
+            return;
+        }
+        self.project_picker = Some(ProjectPicker {
+            selected: 0,
+            projects,
+            now: unix_now(),
+        });
+    }
+
+    /// Open the project highlighted in the picker, in place or in a new project tab
+    pub(super) fn open_picked_project(&mut self, new_tab: bool) {
+        let Some(picker) = self.project_picker.take() else {
+            return;
+        };
+        let Some(project) = picker.projects.get(picker.selected) else {
+            return;
+        };
+        if self.project.project_path.as_deref() == Some(project.path.as_path()) {
+            return;
+        }
+        let opened = if new_tab {
+            self.open_project_tab(&project.path)
+        } else {
+            self.open_project(&project.path)
+        };
+        if let Err(e) = opened {
+            self.notify(Severity::Error, format!("Could not open project: {}", e));
+        }
+    }
+
+    /// Take the highlighted project off the picker
+    pub(super) fn forget_picked_project(&mut self) {
+        let Some(picker) = self.project_picker.as_mut() else {
+            return;
+        };
+        if picker.selected >= picker.projects.len() {
+            return;
+        }
+        let project = picker.projects.remove(picker.selected);
+        picker.selected = picker.selected.min(picker.projects.len().saturating_sub(1));
+        if picker.projects.is_empty() {
+            self.project_picker = None;
+        }
+        self.session.forget(&project.path);
+        let _ = self.session.save();
+    }
+
+    /// Load a second project to compare against the current one
+    pub fn load_comparison(&mut self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Err(crate::error::OracleError::Other(format!(
+                "Path does not exist: {}",
+                path.display()
+            )));
+        }
+        let edition = Edition::from_manifest(&path.join("Cargo.toml"));
+        let analyzer = RustAnalyzer::new()
+            .with_private(self.include_private())
+            .with_excludes(self.settings.analyzer.exclude.clone())
+            .with_edition(edition.unwrap_or_default());
+        let SourceAnalysis {
+            items,
+            skipped: warnings,
+            ..
+        } = analyze_sources(&analyzer, path)?;
+        self.project.analysis_warnings.extend(warnings);
+
+        let manifest_path = path.join("Cargo.toml");
+        let name = manifest_path
+            .exists()
+            .then(|| DependencyAnalyzer::from_manifest(&manifest_path).ok())
+            .flatten()
+            .and_then(|a| a.root_package().map(|p| p.name))
+            .or_else(|| path.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| path.display().to_string());
+
+        self.status_message = format!(
+            "Comparing {} items with {} ({} items)",
+            self.project.items.len(),
+            name,
+            items.len()
+        );
+        self.project.compare = Some(CompareProject {
+            path: path.to_path_buf(),
+            name,
+            items,
+            filtered: Vec::new(),
+        });
+        self.project.inspector_cache.clear();
+        self.filter_items();
+        Ok(())
+    }
+
+    /// Load an LCOV tracefile or `cargo llvm-cov --json` export for per-item coverage
+    pub fn load_coverage(&mut self, path: &Path) -> Result<()> {
+        let data = CoverageData::load(path)?;
+        let with_data = self
+            .project
+            .items
+            .iter()
+            .filter(|item| data.for_item(item).is_some())
+            .count();
+        self.status_message = format!(
+            "Coverage from {}: {} functions/impls with data",
+            path.display(),
+            with_data
+        );
+        self.project.coverage = Some(data);
+        self.project.inspector_cache.clear();
+        Ok(())
+    }
+
+    /// Position in the comparison list of the counterpart of the selected item
+    pub fn compare_selected(&self) -> Option<usize> {
+        let compare = self.project.compare.as_ref()?;
+        let item = self.selected_item()?;
+        compare.filtered.iter().position(|&i| {
+            let other = &compare.items[i];
+            other.kind() == item.kind() && other.qualified_name() == item.qualified_name()
+        })
+    }
+
+    /// Update completion candidates from analyzed items
+    pub fn update_candidates(&mut self) {
+        self.project.candidates = self
+            .project
+            .items
+            .iter()
+            .map(|item| {
+                let kind = CandidateKind::of(item);
+
+                let secondary = item
+                    .documentation()
+                    .map(|d| d.lines().next().unwrap_or("").trim().to_string());
+                let module_path = item.module_path();
+
+                CompletionCandidate {
+                    primary: item.name().to_string(),
+                    secondary,
+                    path: (!module_path.is_empty()).then(|| module_path.join("::")),
+                    kind,
+                    score: 0,
+                }
+            })
+            .collect();
+
+        self.project.filtered_candidates = self.project.candidates.clone();
+    }
+
+    /// Filter items based on search input and current tab
+    pub fn filter_items(&mut self) {
+        // A synchronous filter supersedes anything typed or running in the background
+        self.project.search_pending_since = None;
+        self.cancel_background_filter();
+        let query = self.project.search_input.to_lowercase();
+
+        // Crates tab: when inside a crate, filter its items
+        if self.project.current_tab == Tab::Crates
+            && self.project.selected_installed_crate.is_some()
+        {
+            self.filter_installed_crates();
+            self.refresh_path_candidates();
+            return;
+        }
+
+        // Crates tab (top level): filter crate list by name, keep alphabetical order
+        if self.project.current_tab == Tab::Crates {
+            if !self.project.search_input.starts_with('@') {
+                self.project.registry_search = None;
+            }
+            let mut indices: Vec<usize> = self
+                .project
+                .dependency_tree
+                .iter()
+                .enumerate()
+                .filter(|(_, (name, _))| {
+                    query.is_empty()
+                        || name.to_lowercase().contains(&query)
+                        || name.to_lowercase().replace('-', "_").contains(&query)
+                })
+                .map(|(i, _)| i)
+                .collect();
+            let by_name = |a: usize, b: usize| {
+                self.project.dependency_tree[a]
+                    .0
+                    .to_lowercase()
+                    .cmp(&self.project.dependency_tree[b].0.to_lowercase())
+            };
+            if self.project.sort_crates_by_health {
+                // Best first; crates without crates.io info yet go last
+                let now = unix_now();
+                let scores: HashMap<usize, u8> = indices
+                    .iter()
+                    .filter_map(|&i| {
+                        let name = &self.project.dependency_tree[i].0;
+                        Some((i, self.dependency_health(name, now)?.score))
+                    })
+                    .collect();
+                indices.sort_by(|&a, &b| {
+                    scores
+                        .get(&b)
+                        .cmp(&scores.get(&a))
+                        .then_with(|| by_name(a, b))
+                });
+            } else {
+                indices.sort_by(|&a, &b| by_name(a, b));
+            }
+            self.project.filtered_dependency_indices = indices;
+            if self
+                .project
+                .list_state
+                .selected()
+                .is_some_and(|s| s >= self.project.filtered_dependency_indices.len())
+            {
+                self.project.list_state.select(Some(0));
+            }
+            self.refresh_path_candidates();
+            return;
+        }
+
+        let parsed = Query::parse(&self.project.search_input);
+        self.project.filtered_items = filter_project_items(
+            &self.project.items,
+            &parsed,
+            self.project.current_tab,
+            self.project.module_scope.as_deref(),
+            &AtomicBool::new(false),
+        )
+        .unwrap_or_default();
+        self.refine_filtered();
+
+        // Reset selection if out of bounds
+        if self
+            .project
+            .list_state
+            .selected()
+            .is_some_and(|s| s >= self.project.filtered_items.len())
         {
-            self.list_state.select(Some(0));
+            self.project.list_state.select(Some(0));
+        }
+        self.filter_compare_and_candidates(&parsed);
+    }
+
+    /// Apply what the query string can't express: the public-only flag and list order
+    fn refine_filtered(&mut self) {
+        let items = Arc::clone(&self.project.items);
+        if self.project.public_only {
+            self.project
+                .filtered_items
+                .retain(|&i| items[i].visibility() == Some(Visibility::Public));
+        }
+        if let Some(ref file) = self.project.file_scope {
+            self.project.filtered_items.retain(|&i| {
+                items[i]
+                    .source_location()
+                    .is_some_and(|loc| loc.file.as_ref() == Some(file))
+            });
+        }
+        match self.project.list_sort {
+            ListSort::Source => {}
+            ListSort::Name => self
+                .project
+                .filtered_items
+                .sort_by_cached_key(|&i| items[i].name().to_lowercase()),
+            ListSort::Kind => self
+                .project
+                .filtered_items
+                .sort_by_key(|&i| items[i].kind()),
+        }
+    }
+
+    /// Open or close the saved view picker
+    pub fn toggle_view_picker(&mut self) {
+        self.project.view_picker = match self.project.view_picker {
+            Some(_) => None,
+            None => Some(ViewPicker::default()),
+        };
+    }
+
+    /// Query, tab, sort and flags of the current list, to save under `name`
+    pub fn current_view(&self, name: &str) -> SavedView {
+        SavedView {
+            name: name.to_string(),
+            query: self.project.search_input.trim().to_string(),
+            tab: self.project.current_tab,
+            sort: self.project.list_sort,
+            public_only: self.project.public_only,
+        }
+    }
+
+    /// Switch to a saved view; `None` goes back to the plain list
+    pub fn apply_view(&mut self, view: Option<SavedView>) {
+        self.project.view_picker = None;
+        match view {
+            Some(view) => {
+                self.project.current_tab = view.tab;
+                self.project.search_input = view.query;
+                self.project.list_sort = view.sort;
+                self.project.public_only = view.public_only;
+                self.status_message = format!("View: {}", view.name);
+                self.project.active_view = Some(view.name);
+            }
+            None => {
+                self.project.search_input.clear();
+                self.project.list_sort = ListSort::Source;
+                self.project.public_only = false;
+                self.project.active_view = None;
+                self.status_message = "Showing all items".into();
+            }
+        }
+        self.project.show_completion = false;
+        self.project.list_state.select(Some(0));
+        self.filter_items();
+    }
+
+    /// Save the current list as a view in the config file; replaces a view of the
+    /// same name
+    pub fn save_view(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let view = self.current_view(name);
+        match self.settings.views.iter_mut().find(|v| v.name == name) {
+            Some(existing) => *existing = view,
+            None => self.settings.views.push(view),
+        }
+        self.project.active_view = Some(name.to_string());
+        match self.settings.save() {
+            Ok(()) => self.status_message = format!("Saved view {}", name),
+            Err(e) => self.notify(Severity::Error, format!("Saving view failed: {}", e)),
+        }
+    }
+
+    /// Delete a saved view from the config file
+    pub fn delete_view(&mut self, index: usize) {
+        if index >= self.settings.views.len() {
+            return;
+        }
+        let view = self.settings.views.remove(index);
+        if self.project.active_view.as_deref() == Some(view.name.as_str()) {
+            self.project.active_view = None;
+        }
+        match self.settings.save() {
+            Ok(()) => self.status_message = format!("Deleted view {}", view.name),
+            Err(e) => self.notify(Severity::Error, format!("Saving views failed: {}", e)),
+        }
+    }
+
+    /// Comparison list and completion candidates for `parsed` (cheap next to the item list)
+    fn filter_compare_and_candidates(&mut self, parsed: &Query) {
+        // Keep the comparison list in sync with the same search, tab and scope
+        if let Some(compare) = self.project.compare.as_mut() {
+            let (tab, scope) = (self.project.current_tab, self.project.module_scope.as_ref());
+            compare.filtered = compare
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| {
+                    tab_matches(tab, item)
+                        && parsed.matches(item)
+                        && scope.map_or(true, |scope| scope_path(item).starts_with(scope))
+                })
+                .map(|(i, _)| i)
+                .collect();
+        }
+
+        // `tokio::sy` completes from that installed crate, not the project
+        if let Some(candidates) = self.crate_path_candidates() {
+            self.project.filtered_candidates = candidates;
+            self.project.completion_selected = 0;
+            self.project.completion_offset = 0;
+            return;
         }
 
         // Update completion candidates; only show candidates relevant to the active tab
-        let matched = filter_candidates(&self.candidates, &self.search_input);
-        self.filtered_candidates = match self.current_tab {
+        // While a `key:value` filter is being typed, item names are not useful completions
+        let typing_filter = !self.project.search_input.ends_with(char::is_whitespace)
+            && self
+                .project
+                .search_input
+                .split_whitespace()
+                .last()
+                .is_some_and(|t| t.contains(':') && !t.contains("::"));
+        let matched = if typing_filter {
+            Vec::new()
+        } else {
+            filter_candidates(&self.project.candidates, &parsed.text())
+        };
+        let mut candidates: Vec<CompletionCandidate> =
+            suggest_filter_keys(&self.project.search_input)
+                .into_iter()
+                .map(|(key, hint)| CompletionCandidate {
+                    primary: key.to_string(),
+                    secondary: Some(hint.to_string()),
+                    path: None,
+                    kind: CandidateKind::Filter,
+                    score: 0,
+                })
+                .collect();
+        let matched: Vec<CompletionCandidate> = match self.project.current_tab {
             Tab::Types => matched
                 .into_iter()
                 .filter(|c| {
@@ -364,7 +1448,141 @@ impl App {
                 .collect(),
             Tab::Crates => Vec::new(),
         };
-        self.completion_selected = 0;
+        // Earlier queries that fuzzy-match what's typed so far, newest first
+        let typed = self.project.search_input.trim();
+        let history: Vec<CompletionCandidate> = self
+            .search_history()
+            .iter()
+            .rev()
+            .filter(|q| q.as_str() != typed)
+            .map(|q| CompletionCandidate {
+                primary: q.clone(),
+                secondary: Some("recent search".to_string()),
+                path: None,
+                kind: CandidateKind::History,
+                score: 0,
+            })
+            .collect();
+        let mut history = filter_candidates(&history, typed);
+        history.truncate(3);
+        candidates.extend(history);
+        candidates.extend(matched);
+        self.project.filtered_candidates = candidates;
+        self.project.completion_selected = 0;
+        self.project.completion_offset = 0;
+    }
+
+    /// In the Crates tab the only completions are paths into installed crates
+    fn refresh_path_candidates(&mut self) {
+        self.project.filtered_candidates = self.crate_path_candidates().unwrap_or_default();
+        self.project.completion_selected = 0;
+        self.project.completion_offset = 0;
+    }
+
+    /// Installed crate (registry name) that the token being typed starts with, as in
+    /// `tokio::sync::Mu`; the project's own crate and `crate::`/`self::` don't count
+    fn typed_crate_path(&self) -> Option<(String, &str)> {
+        if self.project.search_input.ends_with(char::is_whitespace) {
+            return None;
+        }
+        let token = self.project.search_input.split_whitespace().last()?;
+        let (first, _) = token.split_once("::")?;
+        if matches!(first, "crate" | "self" | "super")
+            || self
+                .dependency_root_name()
+                .is_some_and(|root| root.replace('-', "_") == first)
+        {
+            return None;
+        }
+        let name = self
+            .crate_registry
+            .crate_names()
+            .into_iter()
+            .find(|name| name.replace('-', "_") == first)?;
+        Some((name.to_string(), token))
+    }
+
+    /// Completions for a path into an installed crate, or `None` when the search
+    /// isn't one. The crate is analyzed in the background the first time; until
+    /// it's done there are no candidates.
+    fn crate_path_candidates(&mut self) -> Option<Vec<CompletionCandidate>> {
+        if !self.project.search_input.contains("::") {
+            return None;
+        }
+        // Walked once: on every keystroke it would stall the UI
+        if !self.crate_registry.is_scanned() {
+            let _ = self.crate_registry.scan();
+        }
+        let (name, token) = self.typed_crate_path()?;
+        let open = self
+            .project
+            .selected_installed_crate
+            .as_ref()
+            .is_some_and(|c| c.name == name)
+            && !self.project.installed_loading;
+        let items = if open {
+            Arc::clone(&self.project.installed_crate_items)
+        } else {
+            match self.crate_path_items.get(&name) {
+                Some(Some(items)) => Arc::clone(items),
+                Some(None) => return Some(Vec::new()),
+                None => {
+                    self.index_crate_for_paths(name);
+                    return Some(Vec::new());
+                }
+            }
+        };
+        Some(path_candidates(&items, token))
+    }
+
+    /// Analyze an installed crate in the background for path completions
+    fn index_crate_for_paths(&mut self, name: String) {
+        let Some(path) = self.crate_registry.latest(&name).map(|c| c.path.clone()) else {
+            return;
+        };
+        self.status_message = format!("Indexing {} for completion...", name);
+        self.crate_path_items.insert(name.clone(), None);
+        self.project.task_tx.spawn(
+            Some(TaskResult::CratePathItems {
+                name: name.clone(),
+                items: Vec::new(),
+            }),
+            move |tx| {
+                let mut items = Vec::new();
+                // Completions only need names and paths
+                let never = AtomicBool::new(false);
+                CrateRegistry::analyze_sources(&path, &name, true, &never, |file_items| {
+                    items.extend(file_items)
+                });
+                tx.send(TaskResult::CratePathItems { name, items });
+            },
+        );
+    }
+
+    /// Store an indexed crate and refresh the completions if they're for it
+    pub(super) fn finish_crate_path_index(&mut self, name: String, items: Vec<AnalyzedItem>) {
+        self.status_message = format!("{}: {} items indexed", name, items.len());
+        self.crate_path_items
+            .insert(name.clone(), Some(Arc::new(items)));
+        if self
+            .typed_crate_path()
+            .is_some_and(|(typed, _)| typed == name)
+        {
+            self.refresh_path_candidates();
+        }
+    }
+
+    /// Open the installed crate a `name::module::Item` path starts with, with its
+    /// list filtered to the rest of the path
+    fn open_crate_path(&mut self, path: &str) {
+        self.project.show_completion = false;
+        if self.project.current_tab != Tab::Crates {
+            self.switch_tab(Tab::Crates);
+        }
+        self.project.search_input = path.to_string();
+        if self.search_qualified_path() {
+            self.project.focus = Focus::List;
+        }
     }
 
     /// Scan for installed crates
@@ -387,48 +1605,33 @@ impl App {
     /// Filter installed crates based on search
     /// Supports qualified path search like "serde::de::Deserialize"
     fn filter_installed_crates(&mut self) {
-        let query = self.search_input.to_lowercase();
+        let parsed = Query::parse(&self.project.search_input);
 
-        if self.selected_installed_crate.is_some() {
-            // Filter items within selected crate by qualified path or name
-            self.installed_crate_filtered = self
-                .installed_crate_items
-                .iter()
-                .enumerate()
-                .filter(|(_, item)| {
-                    if query.is_empty() {
-                        return true;
-                    }
-                    // Check if query contains :: for path matching
-                    if query.contains("::") {
-                        // Match against qualified path
-                        item.qualified_name().to_lowercase().contains(&query) ||
-                        // Or match partial module path
-                        item.module_path().iter()
-                            .any(|p| p.to_lowercase().contains(&query.replace("::", "")))
-                    } else {
-                        // Simple name match
-                        item.name().to_lowercase().contains(&query)
-                    }
-                })
-                .map(|(i, _)| i)
-                .collect();
+        if self.project.selected_installed_crate.is_some() {
+            self.project.installed_crate_filtered = filter_installed_items(
+                &self.project.installed_crate_items,
+                &parsed,
+                self.project.installed_module_scope.as_deref(),
+                &AtomicBool::new(false),
+            )
+            .unwrap_or_default();
         }
 
         // Reset selection if out of bounds
         if self
+            .project
             .list_state
             .selected()
             .is_some_and(|s| s >= self.get_current_list_len())
         {
-            self.list_state.select(Some(0));
+            self.project.list_state.select(Some(0));
         }
     }
 
     /// Parse qualified path and navigate to crate + filter items
     /// E.g., "serde::de::Deserialize" -> select serde crate, filter for de::Deserialize
     pub fn search_qualified_path(&mut self) -> bool {
-        let query = self.search_input.clone();
+        let query = self.project.search_input.clone();
         let query = query.trim();
 
         // Check for qualified path (contains ::)
@@ -450,7 +1653,10 @@ impl App {
         });
 
         if !crate_exists {
-            self.status_message = format!("Crate '{}' not found", crate_name);
+            self.notify(
+                Severity::Warning,
+                format!("Crate '{}' not found", crate_name),
+            );
             return false;
         }
 
@@ -466,6 +1672,7 @@ impl App {
 
         // Select the crate if not already selected
         let already_selected = self
+            .project
             .selected_installed_crate
             .as_ref()
             .map(|c| c.name.to_lowercase() == crate_name.to_lowercase())
@@ -480,7 +1687,7 @@ impl App {
         // Set search to remaining path for filtering
         if parts.len() > 1 {
             // Keep the module path part for filtering
-            self.search_input = parts[1..].join("::");
+            self.project.search_input = parts[1..].join("::");
             self.filter_installed_crates();
         }
 
@@ -488,37 +1695,124 @@ impl App {
     }
 
     /// Select an installed crate and analyze it
+    ///
+    /// Analysis runs in the background; items stream into `installed_crate_items` on each tick.
     pub fn select_installed_crate(&mut self, name: &str) -> Result<()> {
-        if let Some(crate_info) = self.crate_registry.latest(name) {
-            self.selected_installed_crate = Some(crate_info.clone());
-            self.status_message = format!("Analyzing {}...", name);
-
-            match self.crate_registry.analyze_crate(name, None) {
-                Ok(items) => {
-                    self.installed_crate_items = items;
-                    self.installed_crate_filtered = (0..self.installed_crate_items.len()).collect();
-                    self.status_message =
-                        format!("{}: {} items", name, self.installed_crate_items.len());
-                }
-                Err(e) => {
-                    self.status_message = format!("Analysis failed: {e}");
+        let Some(crate_info) = self.crate_registry.latest(name).cloned() else {
+            return Ok(());
+        };
+        self.cancel_installed_analysis();
+        self.project.installed_crate_items = Arc::default();
+        self.project.installed_crate_filtered.clear();
+        self.project.installed_usage_index = TypeUsageIndex::default();
+        self.project.installed_module_tree = ModuleTree::default();
+        self.project.installed_module_scope = None;
+        self.project.installed_loading = true;
+        self.status_message = format!("Analyzing {}...", name);
+
+        let generation = self.project.installed_generation;
+        let cancel = Arc::clone(&self.project.installed_cancel);
+        let path = crate_info.path.clone();
+        let crate_name = crate_info.name.clone();
+        let light = self.settings.analyzer.light_installed;
+        self.project.selected_installed_crate = Some(crate_info);
+        self.project
+            .task_tx
+            .spawn(Some(TaskResult::InstalledDone { generation }), move |tx| {
+                // Batch files so the UI thread re-filters a few times per second, not per file
+                let mut batch = Vec::new();
+                let mut last_send = Instant::now();
+                CrateRegistry::analyze_sources(&path, &crate_name, light, &cancel, |items| {
+                    batch.extend(items);
+                    if last_send.elapsed() >= INSTALLED_BATCH_INTERVAL {
+                        let items = std::mem::take(&mut batch);
+                        tx.send(TaskResult::InstalledItems { generation, items });
+                        last_send = Instant::now();
+                    }
+                });
+                if !batch.is_empty() {
+                    tx.send(TaskResult::InstalledItems {
+                        generation,
+                        items: batch,
+                    });
                 }
-            }
-        }
+                tx.send(TaskResult::InstalledDone { generation });
+            });
         Ok(())
     }
 
     /// Clear selected installed crate (go back to list)
     pub fn clear_installed_crate(&mut self) {
-        self.selected_installed_crate = None;
-        self.installed_crate_items.clear();
-        self.installed_crate_filtered.clear();
-        self.list_state.select(Some(0));
+        self.cancel_installed_analysis();
+        self.project.selected_installed_crate = None;
+        self.project.installed_crate_items = Arc::default();
+        self.project.installed_crate_filtered.clear();
+        self.project.installed_usage_index = TypeUsageIndex::default();
+        self.project.installed_module_tree = ModuleTree::default();
+        self.project.installed_module_scope = None;
+        self.project.show_module_tree = false;
+        self.project.list_state.select(Some(0));
+    }
+
+    /// Stop the running installed crate analysis and ignore anything it already sent
+    fn cancel_installed_analysis(&mut self) {
+        self.project.installed_cancel.store(true, Ordering::Relaxed);
+        self.project.installed_cancel = Arc::new(AtomicBool::new(false));
+        self.project.installed_generation += 1;
+        self.project.installed_loading = false;
+    }
+
+    /// Append streamed items of the installed crate being analyzed
+    pub(super) fn add_installed_items(&mut self, generation: u64, items: Vec<AnalyzedItem>) {
+        if generation != self.project.installed_generation {
+            return;
+        }
+        // Copies only if a background filter still holds the previous snapshot
+        Arc::make_mut(&mut self.project.installed_crate_items).extend(items);
+        self.project.inspector_cache.clear();
+        if let Some(ref krate) = self.project.selected_installed_crate {
+            let root_name = krate.name.replace('-', "_");
+            self.project.installed_module_tree =
+                ModuleTree::build(&self.project.installed_crate_items, &root_name);
+        }
+        self.filter_installed_crates();
+        if self.project.list_state.selected().is_none() {
+            self.project.list_state.select(Some(0));
+        }
+    }
+
+    pub(super) fn finish_installed_analysis(&mut self, generation: u64) {
+        if generation != self.project.installed_generation {
+            return;
+        }
+        self.project.installed_loading = false;
+        self.project.installed_usage_index =
+            TypeUsageIndex::build(&self.project.installed_crate_items);
+        self.project.inspector_cache.clear();
+        if let Some(ref krate) = self.project.selected_installed_crate {
+            let macros = proc_macros::exported(&self.project.installed_crate_items);
+            self.status_message = if macros.is_empty() {
+                format!(
+                    "{}: {} items",
+                    krate.name,
+                    self.project.installed_crate_items.len()
+                )
+            } else {
+                let names: Vec<String> = macros.iter().map(|m| m.usage()).collect();
+                format!(
+                    "{}: {} items · proc macros: {}",
+                    krate.name,
+                    self.project.installed_crate_items.len(),
+                    names.join(", ")
+                )
+            };
+        }
     }
 
     /// Crates to show in Crates tab: project dependencies when we have a Cargo project, else all installed.
     pub fn installed_crates_display_list(&self) -> Vec<String> {
         let project_dep_names: HashSet<String> = self
+            .project
             .dependency_tree
             .iter()
             .filter(|(_, depth)| *depth > 0)
@@ -537,10 +1831,10 @@ impl App {
 
     /// Crate name for "open in browser" (o key): current crate when inside one, or selected dep from list.
     pub fn selected_crate_name_for_display(&self) -> Option<String> {
-        if self.current_tab != Tab::Crates {
+        if self.project.current_tab != Tab::Crates {
             return None;
         }
-        if let Some(ref c) = self.selected_installed_crate {
+        if let Some(ref c) = self.project.selected_installed_crate {
             return Some(c.name.clone());
         }
         self.selected_dependency_name()
@@ -548,223 +1842,2672 @@ impl App {
 
     /// Selected crate name in Crates tab (root or a dep). None if inside a crate, empty list, or wrong tab.
     pub fn selected_dependency_name(&self) -> Option<String> {
-        if self.current_tab != Tab::Crates
-            || self.selected_installed_crate.is_some()
-            || self.dependency_tree.is_empty()
+        if self.project.current_tab != Tab::Crates
+            || self.project.selected_installed_crate.is_some()
         {
             return None;
         }
-        let list_idx = self.list_state.selected().unwrap_or(0);
-        let tree_idx = self.filtered_dependency_indices.get(list_idx).copied()?;
-        self.dependency_tree
+        if let Some(ref search) = self.project.registry_search {
+            return self
+                .project
+                .list_state
+                .selected()
+                .and_then(|i| search.results.get(i))
+                .map(|r| r.name.clone());
+        }
+        if self.project.dependency_tree.is_empty() {
+            return None;
+        }
+        let list_idx = self.project.list_state.selected().unwrap_or(0);
+        let tree_idx = self
+            .project
+            .filtered_dependency_indices
+            .get(list_idx)
+            .copied()?;
+        self.project
+            .dependency_tree
             .get(tree_idx)
             .map(|(name, _)| name.clone())
     }
 
     /// Root crate name in dependency tree (first entry, depth 0). None if no tree.
     pub fn dependency_root_name(&self) -> Option<&str> {
-        self.dependency_tree.first().map(|(n, _)| n.as_str())
+        self.project
+            .dependency_tree
+            .first()
+            .map(|(n, _)| n.as_str())
     }
 
-    /// Process any received crate doc fetch results (call each frame).
-    pub fn poll_crate_docs_rx(&mut self) {
-        while let Ok((name, doc)) = self.crate_docs_rx.try_recv() {
-            if self.crate_docs_loading.as_deref() == Some(name.as_str()) {
-                self.crate_docs_loading = None;
-            }
-            if let Some(info) = doc {
-                if self.crate_docs_cache.len() >= CRATE_DOCS_CACHE_MAX {
-                    if let Some(key) = self.crate_docs_cache.keys().next().cloned() {
-                        self.crate_docs_cache.remove(&key);
-                    }
-                }
-                self.crate_docs_cache.insert(name.clone(), info);
-            } else {
-                self.crate_docs_failed.insert(name);
-            }
-        }
+    /// The Crates list shows crates.io search results instead of the dependencies
+    pub fn registry_mode(&self) -> bool {
+        self.project.current_tab == Tab::Crates
+            && self.project.selected_installed_crate.is_none()
+            && self.project.registry_search.is_some()
     }
 
-    /// If on Crates tab and selected crate is not root and not cached/loading/failed, start fetch in background.
-    pub fn maybe_start_crate_doc_fetch(&mut self) {
-        if self.current_tab != Tab::Crates {
+    /// Search crates.io for the query after `@` in the background
+    pub fn start_registry_search(&mut self) {
+        let query = self
+            .project
+            .search_input
+            .trim_start_matches('@')
+            .trim()
+            .to_string();
+        if query.is_empty() {
             return;
         }
-        let Some(name) = self.selected_dependency_name() else {
+        if self.offline {
+            self.status_message = "Offline: crates.io search is unavailable".to_string();
             return;
-        };
-        if self.dependency_root_name() == Some(name.as_str()) {
-            return; // selected root: show local crate_info, no fetch
         }
-        if self.crate_docs_cache.contains_key(&name)
-            || self.crate_docs_loading.as_deref() == Some(name.as_str())
-            || self.crate_docs_failed.contains(&name)
-        {
+        let search = self
+            .project
+            .registry_search
+            .get_or_insert_with(Default::default);
+        search.query = query.clone();
+        search.loading = true;
+        self.project.list_state.select(Some(0));
+        self.project.task_tx.spawn(
+            Some(TaskResult::CrateSearch(query.clone(), None)),
+            move |tx| {
+                let results = crate::crates_io::search_crates(&query, REGISTRY_SEARCH_RESULTS);
+                tx.send(TaskResult::CrateSearch(query, results));
+            },
+        );
+    }
+
+    /// Store finished search results, unless a newer search replaced them
+    pub(super) fn finish_registry_search(
+        &mut self,
+        query: String,
+        results: Option<Vec<CrateSearchResult>>,
+    ) {
+        let Some(search) = self
+            .project
+            .registry_search
+            .as_mut()
+            .filter(|s| s.query == query)
+        else {
             return;
+        };
+        search.loading = false;
+        match results {
+            Some(results) => {
+                self.status_message = format!("{} crates.io results for {}", results.len(), query);
+                search.results = results;
+            }
+            None => self.notify(
+                Severity::Warning,
+                format!("crates.io search for {} failed", query),
+            ),
+        }
+        self.project.list_state.select(Some(0));
+    }
+
+    /// Add the selected search result to `[dependencies]` at its newest version
+    pub fn add_selected_to_manifest(&mut self) {
+        let Some(result) = self.project.registry_search.as_ref().and_then(|search| {
+            self.project
+                .list_state
+                .selected()
+                .and_then(|i| search.results.get(i))
+                .cloned()
+        }) else {
+            return;
+        };
+        let Some(manifest_path) = self.manifest_path() else {
+            self.status_message = "No Cargo.toml to add to".to_string();
+            return;
+        };
+        let written = std::fs::read_to_string(&manifest_path)
+            .map_err(OracleError::from)
+            .and_then(|before| manifest::add_dependency(&before, &result.name, &result.version))
+            .and_then(|after| Ok(std::fs::write(&manifest_path, after)?));
+        match written {
+            Ok(()) => {
+                self.load_dependencies(&manifest_path);
+                self.status_message = format!(
+                    "Added {} = \"{}\" to Cargo.toml",
+                    result.name, result.version
+                );
+            }
+            Err(e) => self.notify(
+                Severity::Error,
+                format!("Adding {} failed: {}", result.name, e),
+            ),
+        }
+    }
+
+    /// Copy the selected crate as a `cargo add` command, or as a `Cargo.toml`
+    /// line when `toml`, with the features the manifest turns on
+    pub fn copy_dependency_line(&mut self, toml: bool) {
+        let Some(name) = self.selected_dependency_name() else {
+            return;
+        };
+        if self.dependency_root_name() == Some(name.as_str()) && !self.registry_mode() {
+            self.status_message = "Select a dependency to copy".to_string();
+            return;
+        }
+        let registry_version = self.project.registry_search.as_ref().and_then(|search| {
+            let i = self.project.list_state.selected()?;
+            Some(search.results.get(i)?.version.clone())
+        });
+        let Some(version) = registry_version.or_else(|| self.installed_version(&name)) else {
+            self.status_message = format!("No resolved version of {} yet", name);
+            return;
+        };
+        let declared = match self.declared_features(&name) {
+            Ok(Some(declared)) => declared,
+            _ => DeclaredFeatures {
+                features: Vec::new(),
+                default_features: true,
+            },
+        };
+        let line = if toml {
+            declared.toml_entry(&name, &version)
+        } else {
+            declared.cargo_add(&name, &version)
+        };
+        match copy_to_clipboard(&line) {
+            Ok(method) => self.status_message = format!("Copied {} ({})", line, method),
+            Err(e) => self.notify(Severity::Error, format!("Copy failed: {e}")),
+        }
+    }
+
+    /// Health score of a dependency whose crates.io info has been fetched
+    pub fn dependency_health(&self, name: &str, now: u64) -> Option<HealthScore> {
+        let doc = self.crate_docs_cache.get(name)?;
+        let versions = self
+            .project
+            .duplicates
+            .iter()
+            .find(|d| d.name == name)
+            .map_or(1, |d| d.versions.len());
+        let installed = self.installed_version(name);
+        Some(health_score(doc, installed.as_deref(), versions, now))
+    }
+
+    /// Order the Crates list by health score or by name; sorting by health fetches
+    /// the crates.io info of every direct dependency
+    pub fn toggle_health_sort(&mut self) {
+        self.project.sort_crates_by_health = !self.project.sort_crates_by_health;
+        self.resort_dependencies();
+        if !self.project.sort_crates_by_health {
+            self.status_message = "Crates sorted by name".to_string();
+            return;
+        }
+        self.status_message = "Crates sorted by health score".to_string();
+        if self.offline {
+            return;
+        }
+        let missing: Vec<String> = self
+            .project
+            .crate_info
+            .iter()
+            .flat_map(|info| &info.dependencies)
+            .map(|d| d.name.clone())
+            .filter(|n| {
+                !self.crate_docs_cache.contains_key(n)
+                    && !self.project.crate_docs_failed.contains(n)
+            })
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        self.status_message = format!(
+            "Crates sorted by health score; fetching {} from crates.io...",
+            missing.len()
+        );
+        self.project.task_tx.spawn(None, move |tx| {
+            for name in missing {
+                let result = crate::crates_io::fetch_crate_docs(&name);
+                tx.send(TaskResult::CrateDocs(name, result.map(Box::new)));
+            }
+        });
+    }
+
+    /// Re-filter the Crates list, keeping the selected crate selected
+    fn resort_dependencies(&mut self) {
+        let selected = self.selected_dependency_name();
+        self.filter_items();
+        let Some(selected) = selected else {
+            return;
+        };
+        if let Some(pos) = self
+            .project
+            .filtered_dependency_indices
+            .iter()
+            .position(|&i| self.project.dependency_tree[i].0 == selected)
+        {
+            self.project.list_state.select(Some(pos));
+        }
+    }
+
+    /// Store a finished crate doc fetch
+    pub(super) fn finish_crate_doc_fetch(&mut self, name: String, doc: Option<CrateDocInfo>) {
+        if self.project.crate_docs_loading.as_deref() == Some(name.as_str()) {
+            self.project.crate_docs_loading = None;
+        }
+        if let Some(info) = doc {
+            if self.crate_docs_cache.len() >= CRATE_DOCS_CACHE_MAX {
+                if let Some(key) = self.crate_docs_cache.keys().next().cloned() {
+                    self.crate_docs_cache.remove(&key);
+                }
+            }
+            self.crate_docs_cache.insert(name, info);
+        } else {
+            self.project.crate_docs_failed.insert(name);
+        }
+        if self.project.sort_crates_by_health
+            && self.project.current_tab == Tab::Crates
+            && self.project.selected_installed_crate.is_none()
+            && self.project.registry_search.is_none()
+        {
+            self.resort_dependencies();
+        }
+    }
+
+    /// If on Crates tab and selected crate is not root and not cached/loading/failed, start fetch in background.
+    pub fn maybe_start_crate_doc_fetch(&mut self) {
+        if self.project.current_tab != Tab::Crates || self.offline {
+            return;
+        }
+        let Some(name) = self.selected_dependency_name() else {
+            return;
+        };
+        if self.dependency_root_name() == Some(name.as_str()) {
+            return; // selected root: show local crate_info, no fetch
+        }
+        if self.crate_docs_cache.contains_key(&name)
+            || self.project.crate_docs_loading.as_deref() == Some(name.as_str())
+            || self.project.crate_docs_failed.contains(&name)
+        {
+            return;
+        }
+        self.project.crate_docs_loading = Some(name.clone());
+        self.project
+            .task_tx
+            .spawn(Some(TaskResult::CrateDocs(name.clone(), None)), move |tx| {
+                let result = crate::crates_io::fetch_crate_docs(&name);
+                tx.send(TaskResult::CrateDocs(name, result.map(Box::new)));
+            });
+    }
+
+    /// Resolved (installed) version of a dependency; the highest if several are resolved
+    pub fn installed_version(&self, name: &str) -> Option<String> {
+        let normalized = name.replace('-', "_");
+        self.project
+            .feature_unification
+            .iter()
+            .filter(|f| f.name.replace('-', "_") == normalized)
+            .filter_map(|f| cargo_metadata::semver::Version::parse(&f.version).ok())
+            .max()
+            .map(|v| v.to_string())
+    }
+
+    /// Open the release notes panel for the selected dependency and fetch notes in the background
+    pub fn open_release_notes(&mut self) {
+        let Some(name) = self.selected_dependency_name() else {
+            return;
+        };
+        if self.dependency_root_name() == Some(name.as_str()) {
+            return;
+        }
+        if self.offline {
+            if self
+                .project
+                .release_notes
+                .as_ref()
+                .is_some_and(|n| n.crate_name == name)
+            {
+                self.project.show_release_notes = true;
+            } else {
+                self.status_message = "Offline: release notes are unavailable".to_string();
+            }
+            return;
+        }
+        let Some(doc) = self.crate_docs_cache.get(&name) else {
+            self.status_message = format!("Waiting for crates.io info on {}", name);
+            return;
+        };
+        let Some(repository) = doc.repository.clone() else {
+            self.status_message = format!("{} has no repository URL", name);
+            return;
+        };
+        let Some(installed) = self.installed_version(&name) else {
+            self.status_message = format!("Installed version of {} is unknown", name);
+            return;
+        };
+        let latest = doc.version.clone();
+        if installed == latest {
+            self.status_message = format!("{} {} is the latest version", name, installed);
+            return;
+        }
+        self.project.show_release_notes = true;
+        self.project.release_notes_scroll = 0;
+        if self
+            .project
+            .release_notes
+            .as_ref()
+            .is_some_and(|n| n.crate_name == name)
+            || self.project.release_notes_loading.as_deref() == Some(name.as_str())
+        {
+            return;
+        }
+        self.project.release_notes = None;
+        self.project.release_notes_loading = Some(name.clone());
+        self.project.task_tx.spawn(
+            Some(TaskResult::ReleaseNotes(name.clone(), None)),
+            move |tx| {
+                let notes =
+                    crate::crates_io::fetch_release_notes(&name, &repository, &installed, &latest);
+                tx.send(TaskResult::ReleaseNotes(name, notes));
+            },
+        );
+    }
+
+    /// Fetch the open issues and recent pull requests of the selected dependency's
+    /// GitHub repository; they open in the report overlay
+    pub fn open_github_activity(&mut self) {
+        let Some(name) = self.selected_dependency_name() else {
+            return;
+        };
+        if self.dependency_root_name() == Some(name.as_str()) {
+            return;
+        }
+        if self.offline {
+            self.status_message = "Offline: GitHub issues are unavailable".to_string();
+            return;
+        }
+        let Some(doc) = self.crate_docs_cache.get(&name) else {
+            self.status_message = format!("Waiting for crates.io info on {}", name);
+            return;
+        };
+        let Some(repository) = doc.repository.clone() else {
+            self.status_message = format!("{} has no repository URL", name);
+            return;
+        };
+        if self.project.github_activity_loading.as_deref() == Some(name.as_str()) {
+            return;
+        }
+        self.status_message = format!("Fetching GitHub issues of {}...", name);
+        self.project.github_activity_loading = Some(name.clone());
+        self.project.task_tx.spawn(
+            Some(TaskResult::GitHubActivity(name.clone(), None)),
+            move |tx| {
+                let activity = crate::crates_io::fetch_github_activity(&repository);
+                tx.send(TaskResult::GitHubActivity(name, activity));
+            },
+        );
+    }
+
+    /// Show a finished issues fetch, unless another dependency was asked for since
+    pub(super) fn finish_github_activity(
+        &mut self,
+        name: String,
+        activity: Option<GitHubActivity>,
+    ) {
+        if self.project.github_activity_loading.as_deref() != Some(name.as_str()) {
+            return;
+        }
+        self.project.github_activity_loading = None;
+        let Some(activity) = activity else {
+            self.notify(
+                Severity::Warning,
+                format!(
+                    "No GitHub issues for {} (not a GitHub repository, or rate limited)",
+                    name
+                ),
+            );
+            return;
+        };
+        self.status_message.clear();
+        self.open_report(Report::github_activity(&name, &activity, unix_now()));
+    }
+
+    pub fn close_release_notes(&mut self) {
+        self.project.show_release_notes = false;
+    }
+
+    /// Items the project-wide reports cover: the open installed crate, else the project
+    fn report_items(&self) -> &[AnalyzedItem] {
+        if self.viewing_installed_crate() {
+            &self.project.installed_crate_items
+        } else {
+            &self.project.items
+        }
+    }
+
+    /// Toggle the async / `Send` audit of the current items
+    pub fn toggle_async_audit(&mut self) {
+        if self.project.report.take().is_none() {
+            let report = Report::async_audit(&AsyncAudit::run(self.report_items()));
+            self.open_report(report);
+        }
+    }
+
+    /// Toggle the error catalog of the current items
+    pub fn toggle_error_catalog(&mut self) {
+        if self.project.report.take().is_none() {
+            let report = Report::error_catalog(&ErrorCatalog::build(self.report_items()));
+            self.open_report(report);
+        }
+    }
+
+    /// Toggle the FFI view: the C-compatible surface of the current items
+    pub fn toggle_ffi_audit(&mut self) {
+        if self.project.report.take().is_none() {
+            let report = Report::ffi_audit(&FfiSurface::build(self.report_items()));
+            self.open_report(report);
+            self.project.ffi_open = true;
+        }
+    }
+
+    /// Toggle the WASM view: how likely the project builds for wasm32 and WASI
+    pub fn toggle_wasm_scan(&mut self) {
+        if self.project.report.take().is_none() {
+            let scan = WasmScan::build(
+                self.project.wasm_dependencies.iter().cloned(),
+                &self.project.imports,
+            );
+            self.open_report(Report::wasm_scan(
+                &scan,
+                self.project.project_path.as_deref(),
+            ));
+        }
+    }
+
+    /// Toggle the cfg view: items grouped by their `#[cfg(..)]`s, showing which
+    /// exist for the selected target and feature set
+    pub fn toggle_cfg_surface(&mut self) {
+        if self.project.report.take().is_none() {
+            self.open_report(self.cfg_report());
+            self.project.cfg_open = true;
+        }
+    }
+
+    pub fn cycle_cfg_target(&mut self) {
+        self.project.cfg_target = (self.project.cfg_target + 1) % cfg::TARGETS.len();
+        self.project.report = Some(self.cfg_report());
+    }
+
+    pub fn cycle_cfg_features(&mut self) {
+        self.project.cfg_features = self.project.cfg_features.next();
+        self.project.report = Some(self.cfg_report());
+    }
+
+    fn cfg_report(&self) -> Report {
+        let features = match self.project.crate_info {
+            Some(ref info) if !self.viewing_installed_crate() => {
+                self.project.cfg_features.features(info)
+            }
+            _ => Vec::new(),
+        };
+        let target = CfgTarget::new(cfg::TARGETS[self.project.cfg_target], features);
+        Report::cfg_surface(
+            self.report_items(),
+            &target,
+            self.project.cfg_features.label(),
+        )
+    }
+
+    /// Toggle the `no_std` view: the crate's own `#![no_std]`, dependencies
+    /// needing std and imports of std-only APIs
+    pub fn toggle_no_std(&mut self) {
+        if self.project.report.take().is_some() {
+            return;
+        }
+        let root = self.project.project_path.as_ref().and_then(|p| {
+            if p.is_file() {
+                return Some(p.clone());
+            }
+            ["lib.rs", "main.rs"]
+                .iter()
+                .map(|f| p.join("src").join(f))
+                .find(|f| f.exists())
+        });
+        let attr = root
+            .and_then(|f| std::fs::read_to_string(f).ok())
+            .and_then(|source| no_std::crate_attr(&source));
+        let report = NoStdReport::build(
+            attr,
+            self.project.no_std_dependencies.clone(),
+            &self.project.imports,
+        );
+        self.open_report(Report::no_std(
+            &report,
+            self.project.project_path.as_deref(),
+        ));
+    }
+
+    /// Toggle the allocations view: functions ranked by clones and allocations
+    pub fn toggle_allocations(&mut self) {
+        if self.project.report.take().is_none() {
+            let hotspots =
+                allocations::hotspots(self.report_items(), |f| std::fs::read_to_string(f).ok());
+            self.open_report(Report::allocations(
+                &hotspots,
+                self.project.project_path.as_deref(),
+            ));
+        }
+    }
+
+    /// Toggle the Panics view: functions with `unwrap`, `expect`, `panic!`, `todo!`
+    /// or indexing, public ones apart
+    pub fn toggle_panics(&mut self) {
+        if self.project.report.take().is_none() {
+            self.open_report(self.panics_report());
+            self.project.panics_open = true;
+        }
+    }
+
+    pub fn cycle_panics_filter(&mut self) {
+        self.project.panics_filter = PanicKind::cycle(self.project.panics_filter);
+        self.project.report = Some(self.panics_report());
+        self.project.report_scroll = 0;
+    }
+
+    fn panics_report(&self) -> Report {
+        Report::panics(
+            self.report_items(),
+            self.project.panics_filter,
+            self.project.project_path.as_deref(),
+        )
+    }
+
+    /// Toggle the time machine: the selected item at each commit that changed it,
+    /// with what changed in its definition
+    pub fn toggle_item_history(&mut self) {
+        if self.project.report.take().is_some() {
+            self.close_report();
+            return;
+        }
+        let Some(item) = self.selected_item() else {
+            self.status_message = "Select an item to see its history".into();
+            return;
+        };
+        let Some(file) = item.source_location().and_then(|l| l.file.clone()) else {
+            self.status_message = "No source file for this item".into();
+            return;
+        };
+        let commits = match git::file_log(&file, HISTORY_COMMITS) {
+            Ok(commits) => commits,
+            Err(e) => {
+                self.notify(
+                    Severity::Warning,
+                    format!("No history for {}: {}", item.name(), e),
+                );
+                return;
+            }
+        };
+        let history = ItemHistory::build(
+            item,
+            &file,
+            commits.into_iter().map(|commit| {
+                let source = git::show_file(&file, &commit.hash).ok().flatten();
+                (commit, source)
+            }),
+        );
+        let step = history.steps.len().saturating_sub(1);
+        self.open_report(Report::item_history(&history, step));
+        self.project.item_history = Some((history, step));
+    }
+
+    /// Show the item history's step `delta` commits newer (or older when negative)
+    pub fn step_item_history(&mut self, delta: isize) {
+        let Some((history, step)) = self.project.item_history.as_mut() else {
+            return;
+        };
+        let last = history.steps.len().saturating_sub(1);
+        *step = step.saturating_add_signed(delta).min(last);
+        self.project.report = Some(Report::item_history(history, *step));
+    }
+
+    /// Start rust-analyzer for the open project in the background when it's enabled
+    /// and not already running there; stop it when it's disabled
+    pub(super) fn start_rust_analyzer(&mut self) {
+        if !self.settings.analyzer.rust_analyzer {
+            self.project.lsp = LspState::Off;
+            return;
+        }
+        let Some(root) = self.project.project_path.clone() else {
+            return;
+        };
+        if matches!(&self.project.lsp, LspState::Starting(r) | LspState::Ready(r, _) if *r == root)
+        {
+            return;
+        }
+        if !root.join("Cargo.toml").is_file() {
+            self.project.lsp = LspState::Failed("rust-analyzer needs a Cargo project".to_string());
+            return;
+        }
+        self.project.lsp = LspState::Starting(root.clone());
+        self.project.task_tx.spawn(
+            Some(TaskResult::RustAnalyzer {
+                root: root.clone(),
+                client: Err(TASK_PANICKED.to_string()),
+            }),
+            move |tx| {
+                let client = LspClient::start(lsp::COMMAND, &root).and_then(|mut client| {
+                    if !client.wait_until_ready(RUST_ANALYZER_LOAD)? {
+                        tracing::info!("rust-analyzer hasn't reported the workspace loaded");
+                    }
+                    Ok(Box::new(client))
+                });
+                tx.send(TaskResult::RustAnalyzer {
+                    root,
+                    client: client.map_err(|e| e.to_string()),
+                });
+            },
+        );
+    }
+
+    pub(super) fn finish_rust_analyzer_start(
+        &mut self,
+        root: PathBuf,
+        client: std::result::Result<Box<LspClient>, String>,
+    ) {
+        // Turned off or another project opened meanwhile
+        if !matches!(&self.project.lsp, LspState::Starting(r) if *r == root) {
+            return;
+        }
+        match client {
+            Ok(client) => {
+                self.project.lsp = LspState::Ready(root, Arc::new(Mutex::new(*client)));
+                self.status_message = "rust-analyzer is ready (* on an item)".to_string();
+            }
+            Err(e) => {
+                self.notify(
+                    Severity::Warning,
+                    format!("rust-analyzer unavailable: {}", e),
+                );
+                self.project.lsp = LspState::Failed(e);
+            }
+        }
+    }
+
+    /// Toggle the selected item's resolved type, definition and references, asked of
+    /// rust-analyzer; without it, the view comes from oracle's own index
+    pub fn toggle_semantics(&mut self) {
+        if self.project.report.take().is_some() {
+            self.close_report();
+            return;
+        }
+        let Some(item) = self.selected_item() else {
+            self.status_message = "Select an item to resolve".into();
+            return;
+        };
+        let name = item.qualified_name();
+        match (&self.project.lsp, lsp::Location::of_item(item)) {
+            (LspState::Ready(_, client), Some(at)) => {
+                let client = Arc::clone(client);
+                self.status_message = format!("Asking rust-analyzer about {}...", name);
+                self.project.task_tx.spawn(
+                    Some(TaskResult::Semantics {
+                        item: name.clone(),
+                        result: Err(TASK_PANICKED.to_string()),
+                    }),
+                    move |tx| {
+                        let result = client
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .semantics(&at)
+                            .map_err(|e| e.to_string());
+                        tx.send(TaskResult::Semantics { item: name, result });
+                    },
+                );
+            }
+            (state, _) => {
+                let reason = state.unavailable();
+                let semantics = self.index_semantics(item);
+                let report = self.semantics_report(&name, &semantics, Some(&reason));
+                self.open_report(report);
+            }
+        }
+    }
+
+    pub(super) fn finish_semantics(
+        &mut self,
+        item: String,
+        result: std::result::Result<Semantics, String>,
+    ) {
+        // Another view was opened while rust-analyzer was answering
+        if self.project.report.is_some() {
+            return;
+        }
+        let report = match result {
+            Ok(semantics) => self.semantics_report(&item, &semantics, None),
+            Err(e) => {
+                self.notify(Severity::Warning, format!("rust-analyzer: {}", e));
+                let Some(selected) = self.selected_item().filter(|i| i.qualified_name() == item)
+                else {
+                    return;
+                };
+                let semantics = self.index_semantics(selected);
+                self.semantics_report(&item, &semantics, Some(&e))
+            }
+        };
+        self.open_report(report);
+    }
+
+    /// The item's definition, its location and (for types) the places naming it,
+    /// from the syn index
+    fn index_semantics(&self, item: &AnalyzedItem) -> Semantics {
+        let location = |loc: &SourceLocation| {
+            Some(lsp::Location {
+                file: loc.file.clone()?,
+                line: loc.line?,
+                column: loc.column.unwrap_or(1),
+            })
+        };
+        Semantics {
+            hover: Some(item.definition()),
+            definitions: item
+                .source_location()
+                .and_then(location)
+                .into_iter()
+                .collect(),
+            references: self
+                .project
+                .usage_index
+                .usages_of(item.name())
+                .iter()
+                .filter_map(|usage| location(&usage.location))
+                .collect(),
+        }
+    }
+
+    fn semantics_report(
+        &self,
+        name: &str,
+        semantics: &Semantics,
+        fallback: Option<&str>,
+    ) -> Report {
+        let place = |loc: &lsp::Location| {
+            let file = self.project_relative(&loc.file);
+            format!("{}:{}:{}", file.display(), loc.line, loc.column)
+        };
+        let references = semantics
+            .references
+            .iter()
+            .map(|loc| (place(loc), self.enclosing_item(loc)))
+            .collect();
+        Report::semantics(
+            name,
+            semantics.hover.as_deref(),
+            semantics.definitions.iter().map(place).collect(),
+            references,
+            fallback,
+        )
+    }
+
+    /// Qualified name of the innermost project item whose lines contain `loc`
+    fn enclosing_item(&self, loc: &lsp::Location) -> Option<String> {
+        self.project
+            .items
+            .iter()
+            .filter_map(|item| {
+                let source = item.source_location()?;
+                let (start, end) = (source.line?, source.end_line?);
+                (source.file.as_deref() == Some(loc.file.as_path())
+                    && (start..=end).contains(&loc.line))
+                .then_some((end - start, item))
+            })
+            .min_by_key(|(span, _)| *span)
+            .map(|(_, item)| item.qualified_name())
+    }
+
+    /// `file` relative to the project root when it's inside it
+    fn project_relative<'p>(&self, file: &'p Path) -> &'p Path {
+        self.project
+            .project_path
+            .as_deref()
+            .and_then(|root| file.strip_prefix(root).ok())
+            .unwrap_or(file)
+    }
+
+    /// Put a cursor on the selected item's name in the source view; the popup under
+    /// it says what the identifier it's on resolves to
+    pub fn start_source_cursor(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        let location = item.source_location();
+        let Some((file, (first, source))) = location
+            .and_then(|l| l.file.clone())
+            .zip(location.and_then(|l| l.read_source()))
+        else {
+            self.status_message = "No source file for this item".into();
+            return;
+        };
+        let lines: Vec<String> = source.lines().map(str::to_string).collect();
+        let word = |line: &str, (start, end): (usize, usize)| -> String {
+            line.chars().skip(start).take(end - start).collect()
+        };
+        let name_line = location
+            .and_then(|l| l.line)
+            .map_or(0, |l| l.saturating_sub(first));
+        let on_name = lines
+            .iter()
+            .enumerate()
+            .skip(name_line)
+            .find_map(|(n, line)| {
+                identifiers(line)
+                    .into_iter()
+                    .find(|&columns| word(line, columns) == item.name())
+                    .map(|columns| (n, columns))
+            });
+        let Some((line, columns)) = on_name.or_else(|| {
+            lines
+                .iter()
+                .enumerate()
+                .find_map(|(n, line)| identifiers(line).first().map(|&columns| (n, columns)))
+        }) else {
+            self.status_message = "No identifiers in this item's source".into();
+            return;
+        };
+        self.project.source_cursor = Some(SourceCursor {
+            item: ItemRef::of(item),
+            file,
+            first,
+            lines,
+            line,
+            columns,
+            hover: Vec::new(),
+            source: "",
+            definition: None,
+        });
+        self.status_message =
+            "Source cursor: ←/→ identifiers, ↑/↓ lines, Enter goes to the definition".into();
+        self.resolve_source_cursor();
+    }
+
+    /// The source cursor is on the selected item and its source is on screen
+    pub(super) fn source_cursor_live(&self) -> bool {
+        self.project.inspector_raw
+            && !self.project.inspector_diff
+            && self.project.source_cursor.as_ref().is_some_and(|cursor| {
+                self.selected_item()
+                    .is_some_and(|item| cursor.item.matches(item))
+            })
+    }
+
+    /// Leave the cursor, keeping its line in view
+    pub(super) fn close_source_cursor(&mut self) {
+        if let Some(cursor) = self.project.source_cursor.take() {
+            self.project.inspector_scroll = cursor.line;
+        }
+    }
+
+    /// Move the source cursor `words` identifiers along, or to the identifier nearest
+    /// its column `lines` lines down (up when negative)
+    pub(super) fn move_source_cursor(&mut self, lines: isize, words: isize) {
+        let Some(cursor) = self.project.source_cursor.as_mut() else {
+            return;
+        };
+        let found: Vec<Vec<(usize, usize)>> =
+            cursor.lines.iter().map(|line| identifiers(line)).collect();
+        let target = if words != 0 {
+            let all: Vec<(usize, (usize, usize))> = found
+                .iter()
+                .enumerate()
+                .flat_map(|(n, line)| line.iter().map(move |&columns| (n, columns)))
+                .collect();
+            all.iter()
+                .position(|&(n, columns)| n == cursor.line && columns == cursor.columns)
+                .and_then(|i| all.get(i.checked_add_signed(words)?))
+                .copied()
+        } else {
+            let column = cursor.columns.0;
+            let mut line = cursor.line;
+            std::iter::from_fn(|| {
+                line = line.checked_add_signed(lines.signum())?;
+                found.get(line).map(|ids| (line, ids))
+            })
+            .filter(|(_, ids)| !ids.is_empty())
+            .nth(lines.unsigned_abs().saturating_sub(1))
+            .and_then(|(n, ids)| {
+                ids.iter()
+                    .min_by_key(|(start, _)| start.abs_diff(column))
+                    .map(|&columns| (n, columns))
+            })
+        };
+        if let Some((line, columns)) = target {
+            cursor.line = line;
+            cursor.columns = columns;
+            self.resolve_source_cursor();
+        }
+    }
+
+    /// Fill the popup from the index, then ask rust-analyzer when it's ready
+    fn resolve_source_cursor(&mut self) {
+        let Some(cursor) = self.project.source_cursor.as_ref() else {
+            return;
+        };
+        let (word, at) = (cursor.word(), cursor.at());
+        let hover = self.index_hover(&word);
+        if let Some(cursor) = self.project.source_cursor.as_mut() {
+            cursor.hover = hover;
+            cursor.source = "oracle's index";
+            cursor.definition = None;
+        }
+        if let LspState::Ready(_, client) = &self.project.lsp {
+            let client = Arc::clone(client);
+            self.project.task_tx.spawn(
+                Some(TaskResult::Hover {
+                    at: at.clone(),
+                    result: Err(TASK_PANICKED.to_string()),
+                }),
+                move |tx| {
+                    let mut client = client.lock().unwrap_or_else(|e| e.into_inner());
+                    let result = client
+                        .hover(&at)
+                        .and_then(|hover| Ok((hover, client.definition(&at)?)))
+                        .map_err(|e| e.to_string());
+                    tx.send(TaskResult::Hover { at, result });
+                },
+            );
+        }
+    }
+
+    /// Items of the list's source named `name`: kind and path, location and the
+    /// first line of their docs
+    fn index_hover(&self, name: &str) -> Vec<String> {
+        let items = if self.viewing_installed_crate() {
+            &self.project.installed_crate_items
+        } else {
+            self.project.items.as_slice()
+        };
+        let found: Vec<&AnalyzedItem> = items.iter().filter(|i| i.name() == name).collect();
+        if found.is_empty() {
+            return vec![
+                name.to_string(),
+                "Not an item oracle indexed: a local, field, method or another crate's item"
+                    .to_string(),
+            ];
+        }
+        let mut hover = Vec::new();
+        for item in found.iter().take(HOVER_MATCHES) {
+            hover.push(format!("{} {}", item.kind(), item.qualified_name()));
+            if let Some((file, line)) = item
+                .source_location()
+                .and_then(|l| l.file.as_deref().zip(l.line))
+            {
+                hover.push(format!(
+                    "  defined at {}:{}",
+                    self.project_relative(file).display(),
+                    line
+                ));
+            }
+            if let Some(doc) = item.documentation().and_then(|d| d.lines().next()) {
+                hover.push(format!("  {}", doc));
+            }
+        }
+        if found.len() > HOVER_MATCHES {
+            hover.push(format!(
+                "… and {} more named {}",
+                found.len() - HOVER_MATCHES,
+                name
+            ));
+        }
+        hover
+    }
+
+    /// rust-analyzer's hover text and definition for the source cursor, unless it has
+    /// moved on; an empty answer keeps what the index said
+    pub(super) fn finish_hover(
+        &mut self,
+        at: lsp::Location,
+        result: std::result::Result<(Option<String>, Vec<lsp::Location>), String>,
+    ) {
+        if !self
+            .project
+            .source_cursor
+            .as_ref()
+            .is_some_and(|c| c.at() == at)
+        {
+            return;
+        }
+        let (hover, definitions) = match result {
+            Ok(answer) => answer,
+            Err(e) => {
+                self.status_message = format!("rust-analyzer: {}", e);
+                return;
+            }
+        };
+        let mut lines: Vec<String> = hover
+            .iter()
+            .flat_map(|text| text.lines())
+            .filter(|line| !line.trim().is_empty())
+            .take(HOVER_LINES)
+            .map(str::to_string)
+            .collect();
+        let definition = definitions.into_iter().next();
+        if let Some(def) = &definition {
+            lines.push(format!(
+                "defined at {}:{}",
+                self.project_relative(&def.file).display(),
+                def.line
+            ));
+        }
+        if lines.is_empty() {
+            return;
+        }
+        if let Some(cursor) = self.project.source_cursor.as_mut() {
+            cursor.hover = lines;
+            cursor.source = "rust-analyzer";
+            cursor.definition = definition;
+        }
+    }
+
+    /// Select the item the identifier under the source cursor refers to, where
+    /// rust-analyzer says it's defined or else the first item of that name, and put
+    /// the cursor on its name
+    pub(super) fn source_cursor_definition(&mut self) {
+        let Some(cursor) = self.project.source_cursor.as_ref() else {
+            return;
+        };
+        let word = cursor.word();
+        let items = if self.viewing_installed_crate() {
+            &self.project.installed_crate_items
+        } else {
+            self.project.items.as_slice()
+        };
+        let defined_at = |item: &AnalyzedItem, def: &lsp::Location| {
+            item.source_location().is_some_and(|l| {
+                l.file.as_deref() == Some(def.file.as_path()) && l.line == Some(def.line)
+            })
+        };
+        let target = cursor
+            .definition
+            .as_ref()
+            .and_then(|def| items.iter().position(|i| defined_at(i, def)))
+            .or_else(|| items.iter().position(|i| i.name() == word));
+        let Some(index) = target else {
+            self.status_message = format!("No item named {} to go to", word);
+            return;
+        };
+        let name = items[index].qualified_name();
+        if !self.reveal_item(index) {
+            self.status_message = format!("{} isn't in the list", name);
+            return;
+        }
+        self.project.inspector_scroll = 0;
+        self.start_source_cursor();
+        self.status_message = format!("Went to {}", name);
+    }
+
+    /// Select item `index` of the list's source, clearing the search and trying each
+    /// tab when it's filtered out
+    fn reveal_item(&mut self, index: usize) -> bool {
+        let installed = self.viewing_installed_crate();
+        let position = |app: &App| {
+            let list = if installed {
+                &app.project.installed_crate_filtered
+            } else {
+                &app.project.filtered_items
+            };
+            list.iter().position(|&i| i == index)
+        };
+        if position(self).is_none() {
+            self.project.search_input.clear();
+            self.project.active_view = None;
+            if !installed {
+                self.project.module_scope = None;
+                self.project.file_scope = None;
+                self.project.public_only = false;
+            }
+            self.filter_items();
+            let tabs: &[Tab] = if installed {
+                &[]
+            } else {
+                &[Tab::Types, Tab::Functions, Tab::Modules]
+            };
+            for &tab in tabs {
+                if position(self).is_some() {
+                    break;
+                }
+                self.project.current_tab = tab;
+                self.filter_items();
+            }
+        }
+        let Some(position) = position(self) else {
+            return false;
+        };
+        self.project.list_state.select(Some(position));
+        true
+    }
+
+    /// Start writing a review note on the selected item, from its current note
+    pub fn edit_review_note(&mut self) {
+        if self.project.project_path.is_none() || self.viewing_installed_crate() {
+            self.status_message = "Review notes are for the open project's items".into();
+            return;
+        }
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        let current = self
+            .project
+            .review_notes
+            .get(item.kind(), &item.qualified_name())
+            .map(|n| n.note.clone());
+        self.project.review_input = Some(current.unwrap_or_default());
+    }
+
+    /// Store the note being written on the selected item (a blank one removes it)
+    /// and save `.oracle-review.yaml`
+    pub(super) fn save_review_note(&mut self) {
+        let Some(text) = self.project.review_input.take() else {
+            return;
+        };
+        let (Some(root), Some(item)) = (self.project.project_path.clone(), self.selected_item())
+        else {
+            return;
+        };
+        let location = item.source_location().and_then(|loc| {
+            let file = loc.file.as_ref()?;
+            let file = file.strip_prefix(&root).unwrap_or(file);
+            Some(format!("{}:{}", file.display(), loc.line?))
+        });
+        let note = ReviewNote {
+            item: item.qualified_name(),
+            kind: item.kind().to_string(),
+            note: text.trim().to_string(),
+            location,
+        };
+        let removed = note.note.is_empty();
+        let name = note.item.clone();
+        self.project.review_notes.set(note);
+        self.project.inspector_cache.clear();
+        match self.project.review_notes.save(&root) {
+            Ok(()) if removed => self.status_message = format!("Removed the note on {}", name),
+            Ok(()) => {
+                self.status_message = format!(
+                    "Noted {} · {} in {}",
+                    name,
+                    self.project.review_notes.notes.len(),
+                    ReviewNotes::FILE_NAME
+                )
+            }
+            Err(e) => self.notify(
+                Severity::Error,
+                format!("Saving {} failed: {}", ReviewNotes::FILE_NAME, e),
+            ),
+        }
+    }
+
+    /// Toggle the review summary: every note with its item and location
+    pub fn toggle_review(&mut self) {
+        if self.project.report.take().is_none() {
+            self.open_report(Report::review(&self.project.review_notes));
+            self.project.review_open = true;
+        }
+    }
+
+    /// Write the review notes as one Markdown comment to `target/oracle-review.md`
+    pub fn export_review(&mut self) {
+        let Some(dir) = self.target_dir() else {
+            self.status_message = "No project to export a review from".into();
+            return;
+        };
+        let path = dir.join("oracle-review.md");
+        let markdown = self.project.review_notes.markdown();
+        match std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, markdown)) {
+            Ok(()) => {
+                self.status_message = format!(
+                    "Exported {} notes to {}",
+                    self.project.review_notes.notes.len(),
+                    path.display()
+                )
+            }
+            Err(e) => self.notify(Severity::Error, format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Write the project, the list, the selected and pinned items, the saved views
+    /// and the review notes to `target/oracle-session.json` for a teammate
+    pub fn export_session(&mut self) {
+        let (Some(dir), Some(project)) = (self.target_dir(), self.project.project_path.clone())
+        else {
+            self.status_message = "No project to share".into();
+            return;
+        };
+        let session = SharedSession {
+            version: SharedSession::VERSION,
+            project,
+            list: self.current_view("shared"),
+            selected: self.selected_item().map(ItemRef::of),
+            pinned: self.project.pinned_item.as_ref().map(ItemRef::of),
+            views: self.settings.views.clone(),
+            review: self.project.review_notes.clone(),
+        };
+        let path = dir.join("oracle-session.json");
+        let result = std::fs::create_dir_all(&dir)
+            .map_err(OracleError::from)
+            .and_then(|()| session.save(&path));
+        match result {
+            Ok(()) => {
+                self.status_message = format!(
+                    "Session written to {} · open it with oracle --import",
+                    path.display()
+                )
+            }
+            Err(e) => self.notify(Severity::Error, format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Land on a shared session in the open project: its list, selected and pinned
+    /// items, and its views and review notes next to the local ones (local ones win
+    /// and nothing is saved until they're edited)
+    pub fn import_session(&mut self, session: SharedSession) {
+        for view in session.views {
+            if !self.settings.views.iter().any(|v| v.name == view.name) {
+                self.settings.views.push(view);
+            }
+        }
+        let mut imported_notes = 0;
+        for note in session.review.notes {
+            if self
+                .project
+                .review_notes
+                .get(&note.kind, &note.item)
+                .is_none()
+            {
+                self.project.review_notes.set(note);
+                imported_notes += 1;
+            }
+        }
+        self.project.inspector_cache.clear();
+
+        let list = session.list;
+        self.project.current_tab = list.tab;
+        self.project.search_input = list.query;
+        self.project.list_sort = list.sort;
+        self.project.public_only = list.public_only;
+        self.project.active_view = None;
+        self.project.list_state.select(Some(0));
+        self.filter_items();
+
+        let mut missing = Vec::new();
+        if let Some(pinned) = session.pinned {
+            match self.project.items.iter().find(|i| pinned.matches(i)) {
+                Some(item) => {
+                    self.project.pinned_item = Some(item.clone());
+                    self.project.pinned_scroll = 0;
+                }
+                None => missing.push(pinned.name),
+            }
+        }
+        if let Some(selected) = session.selected {
+            let position = self
+                .project
+                .filtered_items
+                .iter()
+                .position(|&i| selected.matches(&self.project.items[i]));
+            match position {
+                Some(position) => {
+                    self.project.list_state.select(Some(position));
+                    self.project.focus = Focus::List;
+                }
+                None => missing.push(selected.name),
+            }
+        }
+        self.status_message = format!("Imported session · {} review notes", imported_notes);
+        if !missing.is_empty() {
+            self.notify(
+                Severity::Warning,
+                format!("Not in this project: {}", missing.join(", ")),
+            );
+        }
+    }
+
+    /// Toggle the environment view: every variable the project reads and where
+    pub fn toggle_env_vars(&mut self) {
+        if self.project.report.take().is_none() {
+            let vars = env_vars::group(&self.project.env_reads);
+            self.open_report(Report::env_vars(
+                &vars,
+                self.project.project_path.as_deref(),
+            ));
+            self.project.env_open = true;
+        }
+    }
+
+    /// Toggle the Notes view of the project's tagged comments
+    pub fn toggle_notes(&mut self) {
+        if self.project.report.take().is_none() {
+            self.open_report(self.notes_report());
+            self.project.notes_open = true;
+        }
+    }
+
+    /// Toggle the graph of which modules import which, cycles first
+    pub fn toggle_import_graph(&mut self) {
+        if self.project.report.take().is_none() {
+            let graph = ImportGraph::build(
+                self.project.items.iter().map(scope_path),
+                &self.project.imports,
+            );
+            self.open_report(Report::import_graph(&graph));
+        }
+    }
+
+    /// Toggle the view of items nothing else in the project refers to
+    pub fn toggle_unused_items(&mut self) {
+        if self.project.report.take().is_none() {
+            let library = self
+                .project
+                .project_path
+                .as_ref()
+                .is_some_and(|p| p.join("src").join("lib.rs").exists());
+            let unused = find_unused(&self.project.items, &self.project.identifiers, library);
+            self.open_report(Report::unused_items(
+                &unused,
+                self.project.project_path.as_deref(),
+                self.include_private(),
+            ));
+        }
+    }
+
+    /// Show the project's impls, derives and references of the type or trait
+    /// selected in the open installed crate
+    pub fn show_where_used(&mut self) {
+        let (Some(krate), Some(item)) =
+            (&self.project.selected_installed_crate, self.selected_item())
+        else {
+            return;
+        };
+        if !matches!(
+            item,
+            AnalyzedItem::Struct(_)
+                | AnalyzedItem::Enum(_)
+                | AnalyzedItem::Trait(_)
+                | AnalyzedItem::TypeAlias(_)
+        ) {
+            self.status_message = "Where-used works for types and traits".to_string();
+            return;
+        }
+        let path = format!(
+            "{}::{}",
+            krate.name.replace('-', "_"),
+            item.qualified_name()
+        );
+        let usages = self.project.usage_index.usages_of(item.name());
+        let report = Report::where_used(&path, usages, self.project.project_path.as_deref());
+        self.open_report(report);
+    }
+
+    /// Toggle the doc links view. Web links are checked in the background the first
+    /// time it opens, unless offline.
+    pub fn toggle_doc_links(&mut self) {
+        if self.project.report.take().is_some() {
+            self.close_report();
+            return;
+        }
+        let unchecked: BTreeSet<String> = self
+            .project
+            .doc_links
+            .iter()
+            .filter(|l| l.status == LinkStatus::Unchecked)
+            .map(|l| l.target.clone())
+            .collect();
+        if !unchecked.is_empty() && !self.offline && !self.project.link_check_running {
+            self.project.link_check_running = true;
+            let urls: Vec<String> = unchecked.into_iter().collect();
+            self.status_message = format!("Checking {} web links...", urls.len());
+            self.project
+                .task_tx
+                .spawn(Some(TaskResult::UrlStatuses(Vec::new())), move |tx| {
+                    tx.send(TaskResult::UrlStatuses(crate::crates_io::url_statuses(
+                        &urls,
+                    )));
+                });
+        }
+        self.open_report(self.doc_links_report());
+        self.project.doc_links_open = true;
+    }
+
+    fn doc_links_report(&self) -> Report {
+        let unchecked = self
+            .project
+            .doc_links
+            .iter()
+            .any(|l| l.status == LinkStatus::Unchecked);
+        let web_state = if self.project.link_check_running {
+            Some("checking…")
+        } else if unchecked && self.offline {
+            Some("offline: not checked")
+        } else {
+            None
+        };
+        Report::doc_links(
+            &self.project.doc_links,
+            self.project.project_path.as_deref(),
+            web_state,
+        )
+    }
+
+    /// Record the web link check and refresh the doc links view if it's open
+    pub(super) fn finish_link_check(&mut self, statuses: Vec<(String, Option<u16>)>) {
+        self.project.link_check_running = false;
+        apply_url_statuses(&mut self.project.doc_links, &statuses.into_iter().collect());
+        self.project.inspector_cache.clear();
+        let broken = self
+            .project
+            .doc_links
+            .iter()
+            .filter(|l| l.is_url && l.is_broken())
+            .count();
+        if broken > 0 {
+            self.notify(
+                Severity::Warning,
+                format!("{} broken web links in docs", broken),
+            );
+        } else {
+            self.status_message = "Web links in docs all answered".to_string();
+        }
+        if self.project.doc_links_open {
+            self.project.report = Some(self.doc_links_report());
+        }
+    }
+
+    /// Spell check the project's docs against the bundled list and the dictionary
+    /// in the config file; a no-op unless `spelling.enabled` is set
+    pub(super) fn check_spelling(&mut self) {
+        self.project.misspellings = if self.settings.spelling.enabled {
+            let checker = SpellChecker::new(&self.settings.spelling.dictionary);
+            check_spelling(&self.project.items, &checker)
+        } else {
+            Vec::new()
+        };
+    }
+
+    /// Open or close the spelling view
+    pub fn toggle_spelling(&mut self) {
+        if self.project.spelling_view.take().is_some() {
+            return;
+        }
+        if !self.settings.spelling.enabled {
+            self.status_message =
+                "Spell check is off: set spelling.enabled in the config file".to_string();
+            return;
+        }
+        self.project.spelling_view = Some(SpellingView::default());
+    }
+
+    /// Spelling view: move the highlight by `delta`, wrapping around
+    pub fn move_spelling_selection(&mut self, delta: isize) {
+        let len = self.project.misspellings.len();
+        if let Some(view) = self.project.spelling_view.as_mut().filter(|_| len > 0) {
+            view.selected = (view.selected as isize + delta).rem_euclid(len as isize) as usize;
+        }
+    }
+
+    /// Add the highlighted word to the dictionary in the config file, so it's no
+    /// longer flagged anywhere
+    pub fn add_word_to_dictionary(&mut self) {
+        let Some(selected) = self.project.spelling_view.as_ref().map(|v| v.selected) else {
+            return;
+        };
+        let Some(word) = self
+            .project
+            .misspellings
+            .get(selected)
+            .map(|m| m.word.to_lowercase())
+        else {
+            return;
+        };
+        if !self.settings.spelling.dictionary.contains(&word) {
+            self.settings.spelling.dictionary.push(word.clone());
+        }
+        self.check_spelling();
+        if let Some(view) = self.project.spelling_view.as_mut() {
+            view.selected = selected.min(self.project.misspellings.len().saturating_sub(1));
+        }
+        match self.settings.save() {
+            Ok(()) => self.status_message = format!("Added \"{}\" to the dictionary", word),
+            Err(e) => self.notify(Severity::Error, format!("Saving dictionary failed: {}", e)),
+        }
+    }
+
+    /// Open or close the Files view
+    pub fn toggle_files_view(&mut self) {
+        if self.project.files_view.take().is_some() {
+            return;
+        }
+        if self.project.file_metrics.is_empty() {
+            self.status_message = "No files analyzed".to_string();
+            return;
+        }
+        self.project.files_view = Some(FilesView::default());
+    }
+
+    /// Files view: move the highlight by `delta`, wrapping around
+    pub fn move_files_selection(&mut self, delta: isize) {
+        let len = self.project.file_metrics.len();
+        if let Some(view) = self.project.files_view.as_mut().filter(|_| len > 0) {
+            view.selected = (view.selected as isize + delta).rem_euclid(len as isize) as usize;
+        }
+    }
+
+    /// Files view: next order, keeping the highlighted file
+    pub fn cycle_file_sort(&mut self) {
+        let Some(view) = self.project.files_view.as_mut() else {
+            return;
+        };
+        let path = view
+            .sort
+            .sorted(&self.project.file_metrics)
+            .get(view.selected)
+            .map(|f| f.path.clone());
+        view.sort = view.sort.next();
+        view.selected = view
+            .sort
+            .sorted(&self.project.file_metrics)
+            .iter()
+            .position(|f| Some(&f.path) == path.as_ref())
+            .unwrap_or(0);
+    }
+
+    /// Scope the list to the file highlighted in the Files view
+    pub fn scope_to_selected_file(&mut self) {
+        let Some(view) = self.project.files_view.take() else {
+            return;
+        };
+        let Some(file) = view
+            .sort
+            .sorted(&self.project.file_metrics)
+            .get(view.selected)
+            .copied()
+        else {
+            return;
+        };
+        self.status_message = format!(
+            "Items of {} (Esc shows all)",
+            self.relative_path(&file.path).display()
+        );
+        self.project.file_scope = Some(file.path.clone());
+        self.project.focus = Focus::List;
+        self.project.list_state.select(Some(0));
+        self.filter_items();
+    }
+
+    pub fn clear_file_scope(&mut self) {
+        self.project.file_scope = None;
+        self.project.list_state.select(Some(0));
+        self.filter_items();
+    }
+
+    /// `path` relative to the project root, when it's inside it
+    pub fn relative_path<'p>(&self, path: &'p Path) -> &'p Path {
+        self.project
+            .project_path
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path)
+    }
+
+    /// Notes view: next tag filter
+    pub fn cycle_notes_filter(&mut self) {
+        self.project.notes_filter = NoteTag::cycle(self.project.notes_filter);
+        self.project.report = Some(self.notes_report());
+        self.project.report_scroll = 0;
+    }
+
+    fn filtered_notes(&self) -> Vec<&Note> {
+        self.project
+            .notes
+            .iter()
+            .filter(|n| self.project.notes_filter.map_or(true, |tag| n.tag == tag))
+            .collect()
+    }
+
+    fn notes_report(&self) -> Report {
+        Report::notes(
+            &self.filtered_notes(),
+            self.project.notes_filter,
+            self.project.project_path.as_deref(),
+        )
+    }
+
+    /// `target/` of the open project (next to it for a single file)
+    fn target_dir(&self) -> Option<PathBuf> {
+        let project = self.project.project_path.as_ref()?;
+        Some(if project.is_dir() {
+            project.join("target")
+        } else {
+            project.parent().unwrap_or(project).join("target")
+        })
+    }
+
+    /// Write the notes shown in the Notes view to `target/oracle-notes.json`
+    pub fn export_notes(&mut self) {
+        let Some(dir) = self.target_dir() else {
+            self.status_message = "No project to export notes from".into();
+            return;
+        };
+        let path = dir.join("oracle-notes.json");
+        let notes = self.filtered_notes();
+        let json = notes::notes_json(&notes);
+        match std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, json)) {
+            Ok(()) => {
+                self.status_message =
+                    format!("Exported {} notes to {}", notes.len(), path.display())
+            }
+            Err(e) => self.notify(Severity::Error, format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Write the environment view as a Markdown table to `target/oracle-env.md`
+    pub fn export_env_vars(&mut self) {
+        let Some(dir) = self.target_dir() else {
+            self.status_message = "No project to export variables from".into();
+            return;
+        };
+        let path = dir.join("oracle-env.md");
+        let vars = env_vars::group(&self.project.env_reads);
+        let markdown = env_vars::markdown(&vars, self.project.project_path.as_deref());
+        match std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, markdown)) {
+            Ok(()) => {
+                self.status_message =
+                    format!("Exported {} variables to {}", vars.len(), path.display())
+            }
+            Err(e) => self.notify(Severity::Error, format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Write C declarations of the FFI surface to `target/oracle-ffi/<crate>.h`
+    pub fn export_ffi_header(&mut self) {
+        let Some(dir) = self.target_dir().map(|t| t.join("oracle-ffi")) else {
+            self.status_message = "No project to export a header from".into();
+            return;
+        };
+        let name = self.project.project_name().replace('-', "_");
+        let path = dir.join(format!("{}.h", name));
+        let header = c_header(self.report_items(), &name);
+        match std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, header)) {
+            Ok(()) => self.status_message = format!("Exported C header to {}", path.display()),
+            Err(e) => self.notify(Severity::Error, format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Write the selected item as a standalone HTML page to
+    /// `target/oracle-doc/<path>.html`, styled with the current theme
+    pub fn export_item_html(&mut self) {
+        let Some(item) = self.selected_item() else {
+            self.status_message = "Select an item to export".into();
+            return;
+        };
+        let Some(dir) = self.target_dir().map(|t| t.join("oracle-doc")) else {
+            self.status_message = "No project to export into".into();
+            return;
+        };
+        let all_items = if self.viewing_installed_crate() {
+            &self.project.installed_crate_items
+        } else {
+            &self.project.items
+        };
+        let page = html::item_page(item, all_items, &self.theme);
+        let name: String = item
+            .qualified_name()
+            .replace("::", ".")
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '.' | '-' => c,
+                _ => '_',
+            })
+            .collect();
+        let path = dir.join(format!("{}.html", name));
+        match std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, page)) {
+            Ok(()) => self.status_message = format!("Exported {}", path.display()),
+            Err(e) => self.notify(Severity::Error, format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Show `message` in the status bar and log it; warnings and errors also pop up
+    /// as a toast. Plain assignments to `status_message` are logged as info.
+    pub fn notify(&mut self, severity: Severity, message: impl Into<String>) {
+        self.status_message = message.into();
+        self.messages.push(severity, self.status_message.clone());
+    }
+
+    /// Log a status message set by direct assignment since the last event
+    pub(super) fn log_status(&mut self) {
+        if !self.status_message.is_empty()
+            && self
+                .messages
+                .latest()
+                .map_or(true, |e| e.text != self.status_message)
+        {
+            self.messages
+                .push(Severity::Info, self.status_message.clone());
+        }
+    }
+
+    /// Toggle the message history overlay
+    pub fn toggle_message_log(&mut self) {
+        if self.project.report.take().is_none() {
+            self.log_status();
+            let report = self.messages.report();
+            self.open_report(report);
+        }
+    }
+
+    /// Toggle the overlay with recent log lines
+    pub fn toggle_debug_log(&mut self) {
+        if self.project.report.take().is_none() {
+            let lines = logging::recent_lines();
+            let report = Report::debug_log(&lines, logging::log_file().map(PathBuf::as_path));
+            self.open_report(report);
+        }
+    }
+
+    fn open_report(&mut self, report: Report) {
+        self.project.report = Some(report);
+        self.project.report_scroll = 0;
+        self.project.notes_open = false;
+        self.project.ffi_open = false;
+        self.project.panics_open = false;
+        self.project.env_open = false;
+        self.project.cfg_open = false;
+        self.project.item_history = None;
+        self.project.review_open = false;
+        self.project.upgrade_plan_open = false;
+        self.project.doc_links_open = false;
+    }
+
+    pub fn close_report(&mut self) {
+        self.project.report = None;
+        self.project.notes_open = false;
+        self.project.ffi_open = false;
+        self.project.panics_open = false;
+        self.project.env_open = false;
+        self.project.cfg_open = false;
+        self.project.item_history = None;
+        self.project.review_open = false;
+        self.project.upgrade_plan_open = false;
+        self.project.doc_links_open = false;
+    }
+
+    /// Mark or unmark the selected direct dependency for upgrade to its latest
+    /// crates.io version (fetched when it was selected)
+    pub fn toggle_upgrade_mark(&mut self) {
+        let Some(name) = self.selected_dependency_name() else {
+            return;
+        };
+        if self.project.upgrade_marks.remove(&name).is_some() {
+            self.status_message = format!("Unmarked {}", name);
+            return;
+        }
+        let direct = self
+            .project
+            .crate_info
+            .as_ref()
+            .is_some_and(|info| info.dependencies.iter().any(|d| d.name == name));
+        if !direct {
+            self.status_message = format!("{} is not a direct dependency", name);
+            return;
+        }
+        let Some(latest) = self.crate_docs_cache.get(&name).map(|d| d.version.clone()) else {
+            self.status_message = if self.offline {
+                format!("Offline: latest version of {} unknown", name)
+            } else {
+                format!("Latest version of {} not fetched yet", name)
+            };
+            return;
+        };
+        let newer = match (
+            self.installed_version(&name)
+                .and_then(|v| Version::parse(&v).ok()),
+            Version::parse(&latest),
+        ) {
+            (Some(installed), Ok(latest)) => latest > installed,
+            _ => true,
+        };
+        if !newer {
+            self.status_message = format!("{} {} is the latest version", name, latest);
+            return;
+        }
+        self.status_message = format!(
+            "Marked {} → {} ({} marked, U: review)",
+            name,
+            latest,
+            self.project.upgrade_marks.len() + 1
+        );
+        self.project.upgrade_marks.insert(name, latest);
+    }
+
+    /// Toggle the upgrade plan: marked dependencies and the `Cargo.toml` diff
+    pub fn toggle_upgrade_plan(&mut self) {
+        if self.project.report.take().is_some() {
+            self.close_report();
+            return;
+        }
+        if self.project.upgrade_marks.is_empty() && self.project.upgrade_backup.is_none() {
+            self.status_message = "Mark dependencies in the Crates tab with u first".into();
+            return;
+        }
+        match self.upgrade_plan_report() {
+            Ok(report) => {
+                self.open_report(report);
+                self.project.upgrade_plan_open = true;
+            }
+            Err(e) => self.notify(Severity::Error, format!("Upgrade plan failed: {}", e)),
+        }
+    }
+
+    fn manifest_path(&self) -> Option<PathBuf> {
+        let manifest = self.project.project_path.as_ref()?.join("Cargo.toml");
+        manifest.is_file().then_some(manifest)
+    }
+
+    /// The marked upgrades as `(name, version)` and the manifest rewritten for them
+    fn planned_manifest(&self) -> Result<(PathBuf, String, String, Vec<VersionBump>)> {
+        let manifest_path = self
+            .manifest_path()
+            .ok_or_else(|| OracleError::Other("No Cargo.toml in the project".into()))?;
+        let before = std::fs::read_to_string(&manifest_path)?;
+        let upgrades: Vec<(String, Version)> = self
+            .project
+            .upgrade_marks
+            .iter()
+            .filter_map(|(name, latest)| Some((name.clone(), Version::parse(latest).ok()?)))
+            .collect();
+        let (after, bumps) = manifest::upgrade_requirements(&before, &upgrades)?;
+        Ok((manifest_path, before, after, bumps))
+    }
+
+    fn upgrade_plan_report(&self) -> Result<Report> {
+        let marked: Vec<(String, String, String)> = self
+            .project
+            .upgrade_marks
+            .iter()
+            .map(|(name, latest)| {
+                let installed = self.installed_version(name).unwrap_or_else(|| "?".into());
+                (name.clone(), installed, latest.clone())
+            })
+            .collect();
+        let (_, before, after, bumps) = if marked.is_empty() {
+            Default::default()
+        } else {
+            self.planned_manifest()?
+        };
+        let diff = manifest::changed_lines(&before, &after);
+        Ok(Report::upgrade_plan(
+            &marked,
+            &bumps,
+            &diff,
+            self.project.upgrade_backup.is_some(),
+        ))
+    }
+
+    /// Write the upgraded `Cargo.toml`, keeping the old manifest and lockfile for
+    /// undo; `cargo_update` then runs `cargo update -p` for the marked crates
+    pub fn apply_upgrades(&mut self, cargo_update: bool) {
+        if self.project.upgrade_marks.is_empty() {
+            self.status_message = "Nothing marked for upgrade".into();
+            return;
+        }
+        if self.project.cargo_update_running {
+            self.status_message = "cargo update is still running".into();
+            return;
+        }
+        let (manifest_path, before, after, bumps) = match self.planned_manifest() {
+            Ok(plan) => plan,
+            Err(e) => {
+                self.notify(Severity::Error, format!("Upgrade failed: {}", e));
+                return;
+            }
+        };
+        let lock_path = manifest_path.with_file_name("Cargo.lock");
+        let lock = std::fs::read_to_string(&lock_path).ok();
+        if let Err(e) = std::fs::write(&manifest_path, &after) {
+            self.notify(Severity::Error, format!("Writing Cargo.toml failed: {}", e));
+            return;
+        }
+        self.project.upgrade_backup = Some(ManifestBackup {
+            manifest_path: manifest_path.clone(),
+            manifest: before,
+            lock,
+        });
+        let names: Vec<String> = std::mem::take(&mut self.project.upgrade_marks)
+            .into_keys()
+            .collect();
+        self.close_report();
+        self.status_message = format!(
+            "Updated {} requirement(s) in Cargo.toml (U, z: undo)",
+            bumps.len()
+        );
+        if !cargo_update {
+            self.load_dependencies(&manifest_path);
+            return;
+        }
+        self.status_message = format!("Running cargo update -p {}...", names.join(" -p "));
+        self.project.cargo_update_running = true;
+        self.project.task_tx.spawn(
+            Some(TaskResult::CargoUpdate {
+                ok: false,
+                message: format!("cargo update: {}", TASK_PANICKED),
+            }),
+            move |tx| {
+                let mut cmd = Command::new("cargo");
+                cmd.arg("update").arg("--manifest-path").arg(&manifest_path);
+                for name in &names {
+                    cmd.args(["-p", name]);
+                }
+                let (ok, message) = match cmd.output() {
+                    Ok(o) if o.status.success() => (
+                        true,
+                        format!("cargo update -p {}: done", names.join(" -p ")),
+                    ),
+                    Ok(o) => {
+                        let stderr = String::from_utf8_lossy(&o.stderr);
+                        let last = stderr.lines().rev().find(|l| !l.trim().is_empty());
+                        (
+                            false,
+                            format!("cargo update failed: {}", last.unwrap_or("no output")),
+                        )
+                    }
+                    Err(e) => (false, format!("Failed to run cargo: {e}")),
+                };
+                tx.send(TaskResult::CargoUpdate { ok, message });
+            },
+        );
+    }
+
+    /// `cargo update` finished: reload the dependency tree from the new lockfile
+    pub(super) fn finish_cargo_update(&mut self, ok: bool, message: String) {
+        self.project.cargo_update_running = false;
+        if let Some(manifest_path) = self.manifest_path() {
+            self.load_dependencies(&manifest_path);
+        }
+        if ok {
+            self.status_message = message;
+        } else {
+            self.notify(Severity::Warning, message);
         }
-        self.crate_docs_loading = Some(name.clone());
-        let tx = self.crate_docs_tx.clone();
-        thread::spawn(move || {
-            let result = crate::crates_io::fetch_crate_docs(&name);
-            let _ = tx.send((name, result));
+    }
+
+    /// Open the feature toggles of the selected direct dependency, or close them
+    pub fn toggle_feature_picker(&mut self) {
+        if self.project.feature_picker.take().is_some() {
+            return;
+        }
+        let Some(name) = self.selected_dependency_name() else {
+            return;
+        };
+        if self.dependency_root_name() == Some(name.as_str()) {
+            return;
+        }
+        match self.declared_features(&name) {
+            Ok(Some(declared)) => {
+                self.project.feature_picker = Some(FeaturePicker {
+                    crate_name: name,
+                    selected: 0,
+                    declared,
+                });
+            }
+            Ok(None) => {
+                self.status_message = format!("{} is not in [dependencies] of Cargo.toml", name)
+            }
+            Err(e) => self.notify(Severity::Error, format!("Reading Cargo.toml failed: {}", e)),
+        }
+    }
+
+    fn declared_features(&self, name: &str) -> Result<Option<DeclaredFeatures>> {
+        let Some(manifest_path) = self.manifest_path() else {
+            return Ok(None);
+        };
+        manifest::declared_features(&std::fs::read_to_string(manifest_path)?, name)
+    }
+
+    /// Features listed in the picker for `name`, after the `default-features` row
+    pub(super) fn available_features(&self, name: &str) -> Vec<String> {
+        self.project
+            .feature_unification
+            .iter()
+            .rfind(|f| f.name == name || f.name.replace('-', "_") == name)
+            .map(|f| f.available.clone())
+            .unwrap_or_default()
+    }
+
+    /// Flip the highlighted feature (or `default-features`) in `Cargo.toml` and
+    /// re-resolve the dependency graph
+    pub fn toggle_selected_feature(&mut self) {
+        let Some(picker) = self.project.feature_picker.as_ref() else {
+            return;
+        };
+        let Some(manifest_path) = self.manifest_path() else {
+            return;
+        };
+        let name = picker.crate_name.clone();
+        let (feature, enabled) = match picker.selected.checked_sub(1) {
+            None => (None, !picker.declared.default_features),
+            Some(i) => {
+                let Some(feature) = self.available_features(&name).get(i).cloned() else {
+                    return;
+                };
+                let enabled = !picker.declared.features.contains(&feature);
+                (Some(feature), enabled)
+            }
+        };
+        let written = std::fs::read_to_string(&manifest_path)
+            .map_err(OracleError::from)
+            .and_then(|before| match feature {
+                Some(ref feature) => manifest::set_feature(&before, &name, feature, enabled),
+                None => manifest::set_default_features(&before, &name, enabled),
+            })
+            .and_then(|after| Ok(std::fs::write(&manifest_path, after)?));
+        if let Err(e) = written {
+            self.notify(
+                Severity::Error,
+                format!("Updating Cargo.toml failed: {}", e),
+            );
+            return;
+        }
+        self.load_dependencies(&manifest_path);
+        self.filter_items();
+        if let Ok(Some(declared)) = self.declared_features(&name) {
+            if let Some(picker) = self.project.feature_picker.as_mut() {
+                picker.declared = declared;
+            }
+        }
+        let what = feature.unwrap_or_else(|| "default features".to_string());
+        self.status_message = format!(
+            "{} {} of {} in Cargo.toml",
+            if enabled { "Enabled" } else { "Disabled" },
+            what,
+            name
+        );
+    }
+
+    /// Restore `Cargo.toml` and `Cargo.lock` from before the last applied plan
+    pub fn undo_upgrades(&mut self) {
+        if self.project.cargo_update_running {
+            self.status_message = "cargo update is still running".into();
+            return;
+        }
+        let Some(backup) = self.project.upgrade_backup.take() else {
+            self.status_message = "No upgrade to undo".into();
+            return;
+        };
+        let lock_path = backup.manifest_path.with_file_name("Cargo.lock");
+        let restored = std::fs::write(&backup.manifest_path, &backup.manifest).and_then(|()| {
+            match backup.lock {
+                Some(ref lock) => std::fs::write(&lock_path, lock),
+                None => Ok(()),
+            }
         });
+        match restored {
+            Ok(()) => {
+                self.close_report();
+                self.load_dependencies(&backup.manifest_path);
+                self.status_message = "Restored Cargo.toml and Cargo.lock".into();
+            }
+            Err(e) => {
+                self.notify(Severity::Error, format!("Undo failed: {}", e));
+                self.project.upgrade_backup = Some(backup);
+            }
+        }
+    }
+
+    /// Store a finished release notes fetch
+    pub(super) fn finish_release_notes_fetch(&mut self, name: String, notes: Option<ReleaseNotes>) {
+        if self.project.release_notes_loading.as_deref() != Some(name.as_str()) {
+            return; // superseded by another request
+        }
+        self.project.release_notes_loading = None;
+        if notes.is_none() {
+            self.notify(
+                Severity::Warning,
+                format!("No release notes found for {}", name),
+            );
+            self.project.show_release_notes = false;
+        }
+        self.project.release_notes = notes;
     }
 
     /// Get current list length based on tab and selection state
     pub fn get_current_list_len(&self) -> usize {
-        if self.current_tab == Tab::Crates {
-            if self.selected_installed_crate.is_some() {
-                self.installed_crate_filtered.len()
+        if self.project.current_tab == Tab::Crates {
+            if self.project.selected_installed_crate.is_some() {
+                self.project.installed_crate_filtered.len()
+            } else if let Some(ref search) = self.project.registry_search {
+                search.results.len().max(1)
             } else {
-                let n = self.filtered_dependency_indices.len();
-                if self.dependency_tree.is_empty() || n == 0 {
+                let n = self.project.filtered_dependency_indices.len();
+                if self.project.dependency_tree.is_empty() || n == 0 {
                     1
                 } else {
                     n
                 }
             }
         } else {
-            self.filtered_items.len()
+            self.project.filtered_items.len()
         }
     }
 
     /// Get the currently selected item
     pub fn selected_item(&self) -> Option<&AnalyzedItem> {
-        if self.current_tab == Tab::Crates && self.selected_installed_crate.is_some() {
+        if self.project.current_tab == Tab::Crates
+            && self.project.selected_installed_crate.is_some()
+        {
             return self
+                .project
                 .list_state
                 .selected()
-                .and_then(|i| self.installed_crate_filtered.get(i))
-                .and_then(|&idx| self.installed_crate_items.get(idx));
+                .and_then(|i| self.project.installed_crate_filtered.get(i))
+                .and_then(|&idx| self.project.installed_crate_items.get(idx));
         }
-        if self.current_tab == Tab::Crates {
+        if self.project.current_tab == Tab::Crates {
             return None; // Inspector shows root/crate docs, not an item
         }
-        self.list_state
+        self.project
+            .list_state
+            .selected()
+            .and_then(|i| self.project.filtered_items.get(i))
+            .and_then(|&idx| self.project.items.get(idx))
+    }
+
+    /// Get filtered items as references
+    pub fn get_filtered_items(&self) -> Vec<&AnalyzedItem> {
+        if self.project.current_tab == Tab::Crates
+            && self.project.selected_installed_crate.is_some()
+        {
+            self.project
+                .installed_crate_filtered
+                .iter()
+                .filter_map(|&i| self.project.installed_crate_items.get(i))
+                .collect()
+        } else {
+            self.project
+                .filtered_items
+                .iter()
+                .filter_map(|&i| self.project.items.get(i))
+                .collect()
+        }
+    }
+
+    // Navigation methods
+    pub fn next_item(&mut self) {
+        let len = self.get_current_list_len();
+        if len == 0 {
+            return;
+        }
+        let i = match self.project.list_state.selected() {
+            Some(i) => (i + 1) % len,
+            None => 0,
+        };
+        self.project.list_state.select(Some(i));
+    }
+
+    pub fn prev_item(&mut self) {
+        let len = self.get_current_list_len();
+        if len == 0 {
+            return;
+        }
+        let i = match self.project.list_state.selected() {
+            Some(i) => i.checked_sub(1).unwrap_or(len - 1),
+            None => 0,
+        };
+        self.project.list_state.select(Some(i));
+    }
+
+    pub fn next_tab(&mut self) {
+        self.project.current_tab = self.project.current_tab.next();
+        self.project.list_state.select(Some(0));
+        self.project.show_completion = false; // Hide completions when switching tabs
+        self.filter_items();
+
+        // Scan crates if switching to installed crates tab
+        if self.project.current_tab == Tab::Crates && self.installed_crates_list.is_empty() {
+            let _ = self.scan_installed_crates();
+        }
+    }
+
+    pub fn prev_tab(&mut self) {
+        self.project.current_tab = self.project.current_tab.prev();
+        self.project.list_state.select(Some(0));
+        self.project.show_completion = false; // Hide completions when switching tabs
+        self.filter_items();
+
+        if self.project.current_tab == Tab::Crates && self.installed_crates_list.is_empty() {
+            let _ = self.scan_installed_crates();
+        }
+    }
+
+    pub fn next_focus(&mut self) {
+        self.project.focus = self.project.focus.next(
+            self.project.copilot_chat_open,
+            self.project.pinned_item.is_some(),
+        );
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.project.focus = self.project.focus.prev(
+            self.project.copilot_chat_open,
+            self.project.pinned_item.is_some(),
+        );
+    }
+
+    pub fn next_completion(&mut self) {
+        if !self.project.filtered_candidates.is_empty() {
+            self.project.completion_selected =
+                (self.project.completion_selected + 1) % self.project.filtered_candidates.len();
+            self.scroll_completion();
+        }
+    }
+
+    pub fn prev_completion(&mut self) {
+        if !self.project.filtered_candidates.is_empty() {
+            self.project.completion_selected = self
+                .project
+                .completion_selected
+                .checked_sub(1)
+                .unwrap_or(self.project.filtered_candidates.len() - 1);
+            self.scroll_completion();
+        }
+    }
+
+    /// Scroll the dropdown just enough to show the selected candidate
+    fn scroll_completion(&mut self) {
+        self.project.completion_offset = completion_offset(
+            self.project.completion_offset,
+            self.project.completion_selected,
+            COMPLETION_VISIBLE,
+        );
+    }
+
+    pub fn select_completion(&mut self) {
+        let Some(candidate) = self
+            .project
+            .filtered_candidates
+            .get(self.project.completion_selected)
+            .cloned()
+        else {
+            return;
+        };
+        // In another crate's path a module keeps completing and an item opens there
+        if self.typed_crate_path().is_some() {
+            if candidate.primary.ends_with("::") {
+                self.project.search_input =
+                    replace_last_token(&self.project.search_input, &candidate.primary);
+                self.filter_items();
+            } else {
+                self.open_crate_path(&candidate.primary);
+            }
+            return;
+        }
+        // Only the token being typed is replaced, so filters before it survive;
+        // a recent search replaces the whole query
+        self.project.search_input = if candidate.kind == CandidateKind::History {
+            candidate.primary
+        } else {
+            replace_last_token(&self.project.search_input, &candidate.primary)
+        };
+        self.project.show_completion = false;
+        self.filter_items();
+    }
+
+    /// Inside an installed crate plain text filters its items directly, so only
+    /// `name::` paths complete there
+    fn wants_completion(&self) -> bool {
+        self.project.search_input.len() >= 2
+            && (!self.viewing_installed_crate() || self.project.search_input.contains("::"))
+    }
+
+    // Input handling
+    pub fn on_char(&mut self, c: char) {
+        self.project.search_input.push(c);
+        self.project.history_cursor = None;
+        self.project.search_pending_since = Some(Instant::now());
+        self.project.show_completion = self.wants_completion();
+    }
+
+    pub fn on_backspace(&mut self) {
+        self.project.search_input.pop();
+        self.project.history_cursor = None;
+        self.project.search_pending_since = Some(Instant::now());
+        self.project.show_completion = self.wants_completion();
+    }
+
+    /// Apply the typed query once typing has paused for `SEARCH_DEBOUNCE`
+    pub(super) fn flush_pending_search(&mut self) {
+        if self
+            .project
+            .search_pending_since
+            .map_or(true, |since| since.elapsed() < SEARCH_DEBOUNCE)
+        {
+            return;
+        }
+        let len = if self.viewing_installed_crate() {
+            self.project.installed_crate_items.len()
+        } else if self.project.current_tab == Tab::Crates {
+            0 // dependency list: always small
+        } else {
+            self.project.items.len()
+        };
+        if len >= BACKGROUND_FILTER_MIN {
+            self.start_background_filter();
+        } else {
+            self.filter_items();
+        }
+    }
+
+    /// Filter a snapshot of the current item list on a worker thread; the result
+    /// arrives as `TaskResult::Filtered` and is dropped if the query changed meanwhile
+    pub(super) fn start_background_filter(&mut self) {
+        self.project.search_pending_since = None;
+        self.cancel_background_filter();
+        self.project.filtering = true;
+
+        let installed = self.viewing_installed_crate();
+        let items = Arc::clone(if installed {
+            &self.project.installed_crate_items
+        } else {
+            &self.project.items
+        });
+        let query = self.project.search_input.clone();
+        let tab = self.project.current_tab;
+        let scope = self.current_module_scope().map(<[String]>::to_vec);
+        let cancel = Arc::clone(&self.project.filter_cancel);
+        let generation = self.project.filter_generation;
+        self.project.task_tx.spawn(
+            Some(TaskResult::Filtered {
+                generation,
+                installed,
+                indices: Vec::new(),
+            }),
+            move |tx| {
+                let parsed = Query::parse(&query);
+                let indices = if installed {
+                    filter_installed_items(&items, &parsed, scope.as_deref(), &cancel)
+                } else {
+                    filter_project_items(&items, &parsed, tab, scope.as_deref(), &cancel)
+                };
+                if let Some(indices) = indices {
+                    tx.send(TaskResult::Filtered {
+                        generation,
+                        installed,
+                        indices,
+                    });
+                }
+            },
+        );
+
+        if installed {
+            self.refresh_path_candidates();
+        } else {
+            let parsed = Query::parse(&self.project.search_input);
+            self.filter_compare_and_candidates(&parsed);
+        }
+    }
+
+    fn cancel_background_filter(&mut self) {
+        self.project.filter_cancel.store(true, Ordering::Relaxed);
+        self.project.filter_cancel = Arc::new(AtomicBool::new(false));
+        self.project.filter_generation += 1;
+        self.project.filtering = false;
+    }
+
+    pub(super) fn finish_background_filter(
+        &mut self,
+        generation: u64,
+        installed: bool,
+        indices: Vec<usize>,
+    ) {
+        if generation != self.project.filter_generation {
+            return;
+        }
+        self.project.filtering = false;
+        if installed {
+            self.project.installed_crate_filtered = indices;
+        } else {
+            self.project.filtered_items = indices;
+            self.refine_filtered();
+        }
+        if self
+            .project
+            .list_state
             .selected()
-            .and_then(|i| self.filtered_items.get(i))
-            .and_then(|&idx| self.items.get(idx))
+            .map_or(true, |s| s >= self.get_current_list_len())
+        {
+            self.project.list_state.select(Some(0));
+        }
+    }
+
+    /// A typed query has not been applied to the list yet
+    pub fn search_pending(&self) -> bool {
+        self.project.search_pending_since.is_some() || self.project.filtering
+    }
+
+    pub fn clear_search(&mut self) {
+        self.project.search_input.clear();
+        self.project.show_completion = false;
+        self.filter_items();
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    /// Show or hide the module tree in place of the results list
+    pub fn toggle_module_tree(&mut self) {
+        self.project.show_module_tree = !self.project.show_module_tree;
+        if self.project.show_module_tree {
+            self.project.focus = Focus::List;
+            let rows = self.module_tree_rows();
+            self.project.module_tree_selected = self
+                .current_module_scope()
+                .and_then(|scope| rows.iter().position(|r| r.path == scope))
+                .unwrap_or(0);
+        }
+    }
+
+    /// Whether the Crates tab is showing the items of an installed crate
+    pub fn viewing_installed_crate(&self) -> bool {
+        self.project.current_tab == Tab::Crates && self.project.selected_installed_crate.is_some()
     }
 
-    /// Get filtered items as references
-    pub fn get_filtered_items(&self) -> Vec<&AnalyzedItem> {
-        if self.current_tab == Tab::Crates && self.selected_installed_crate.is_some() {
-            self.installed_crate_filtered
-                .iter()
-                .filter_map(|&i| self.installed_crate_items.get(i))
-                .collect()
+    /// Module scope of whatever the list shows: the project, or the open installed crate
+    pub fn current_module_scope(&self) -> Option<&[String]> {
+        if self.viewing_installed_crate() {
+            self.project.installed_module_scope.as_deref()
         } else {
-            self.filtered_items
-                .iter()
-                .filter_map(|&i| self.items.get(i))
-                .collect()
+            self.project.module_scope.as_deref()
         }
     }
 
-    // Navigation methods
-    pub fn next_item(&mut self) {
-        let len = self.get_current_list_len();
-        if len == 0 {
-            return;
+    fn set_module_scope(&mut self, scope: Option<Vec<String>>) {
+        if self.viewing_installed_crate() {
+            self.project.installed_module_scope = scope;
+        } else {
+            self.project.module_scope = scope;
         }
-        let i = match self.list_state.selected() {
-            Some(i) => (i + 1) % len,
-            None => 0,
-        };
-        self.list_state.select(Some(i));
+        self.project.list_state.select(Some(0));
+        self.filter_items();
     }
 
-    pub fn prev_item(&mut self) {
-        let len = self.get_current_list_len();
-        if len == 0 {
-            return;
-        }
-        let i = match self.list_state.selected() {
-            Some(i) => i.checked_sub(1).unwrap_or(len - 1),
-            None => 0,
+    /// Visible rows of the module tree (children of collapsed nodes hidden)
+    pub fn module_tree_rows(&self) -> Vec<TreeRow> {
+        let tree = if self.viewing_installed_crate() {
+            &self.project.installed_module_tree
+        } else {
+            &self.project.module_tree
         };
-        self.list_state.select(Some(i));
+        tree.rows(&self.project.module_tree_collapsed)
     }
 
-    pub fn next_tab(&mut self) {
-        self.current_tab = self.current_tab.next();
-        self.list_state.select(Some(0));
-        self.show_completion = false; // Hide completions when switching tabs
-        self.filter_items();
-
-        // Scan crates if switching to installed crates tab
-        if self.current_tab == Tab::Crates && self.installed_crates_list.is_empty() {
-            let _ = self.scan_installed_crates();
+    pub fn module_tree_next(&mut self) {
+        let len = self.module_tree_rows().len();
+        if len > 0 {
+            self.project.module_tree_selected = (self.project.module_tree_selected + 1) % len;
         }
     }
 
-    pub fn prev_tab(&mut self) {
-        self.current_tab = self.current_tab.prev();
-        self.list_state.select(Some(0));
-        self.show_completion = false; // Hide completions when switching tabs
-        self.filter_items();
-
-        if self.current_tab == Tab::Crates && self.installed_crates_list.is_empty() {
-            let _ = self.scan_installed_crates();
+    pub fn module_tree_prev(&mut self) {
+        let len = self.module_tree_rows().len();
+        if len > 0 {
+            self.project.module_tree_selected = self
+                .project
+                .module_tree_selected
+                .checked_sub(1)
+                .unwrap_or(len - 1);
         }
     }
 
-    pub fn next_focus(&mut self) {
-        self.focus = self.focus.next(self.copilot_chat_open);
+    /// Expand (`expand = true`) or collapse the selected node
+    pub fn module_tree_set_expanded(&mut self, expand: bool) {
+        let rows = self.module_tree_rows();
+        let Some(row) = rows.get(self.project.module_tree_selected) else {
+            return;
+        };
+        if !row.has_children {
+            return;
+        }
+        if expand {
+            self.project.module_tree_collapsed.remove(&row.path);
+        } else {
+            self.project.module_tree_collapsed.insert(row.path.clone());
+        }
     }
 
-    pub fn prev_focus(&mut self) {
-        self.focus = self.focus.prev(self.copilot_chat_open);
+    /// Scope the results list to the selected node's subtree and close the tree
+    pub fn scope_to_selected_module(&mut self) {
+        let rows = self.module_tree_rows();
+        let Some(row) = rows.get(self.project.module_tree_selected) else {
+            return;
+        };
+        if row.path.is_empty() {
+            self.status_message = "Showing all modules".to_string();
+            self.set_module_scope(None);
+        } else {
+            self.status_message = format!("Scoped to {}", row.path.join("::"));
+            self.set_module_scope(Some(row.path.clone()));
+        }
+        self.project.show_module_tree = false;
     }
 
-    pub fn next_completion(&mut self) {
-        if !self.filtered_candidates.is_empty() {
-            self.completion_selected =
-                (self.completion_selected + 1) % self.filtered_candidates.len();
-        }
+    pub fn clear_module_scope(&mut self) {
+        self.set_module_scope(None);
     }
 
-    pub fn prev_completion(&mut self) {
-        if !self.filtered_candidates.is_empty() {
-            self.completion_selected = self
-                .completion_selected
-                .checked_sub(1)
-                .unwrap_or(self.filtered_candidates.len() - 1);
+    /// Drill into the selected item when it is a module of the open installed crate;
+    /// false when there is nothing to drill into
+    pub fn drill_into_selected_module(&mut self) -> bool {
+        if !self.viewing_installed_crate() {
+            return false;
         }
+        let Some(path) = self
+            .selected_item()
+            .filter(|item| matches!(item, AnalyzedItem::Module(_)))
+            .map(scope_path)
+        else {
+            return false;
+        };
+        self.status_message = format!("Scoped to {}", path.join("::"));
+        self.set_module_scope(Some(path));
+        true
     }
 
-    pub fn select_completion(&mut self) {
-        if let Some(candidate) = self.filtered_candidates.get(self.completion_selected) {
-            self.search_input = candidate.primary.clone();
-            self.show_completion = false;
-            self.filter_items();
+    /// Go up one module level in the open installed crate; false when already at its root
+    pub fn leave_installed_module(&mut self) -> bool {
+        if !self.viewing_installed_crate() {
+            return false;
         }
+        let Some(mut path) = self.project.installed_module_scope.clone() else {
+            return false;
+        };
+        path.pop();
+        self.set_module_scope((!path.is_empty()).then_some(path));
+        true
     }
 
-    // Input handling
-    pub fn on_char(&mut self, c: char) {
-        self.search_input.push(c);
-        self.filter_items();
-        // Don't show completions in Crates tab - use direct qualified path search
-        self.show_completion = self.search_input.len() >= 2
-            && !(self.current_tab == Tab::Crates && self.selected_installed_crate.is_some());
+    /// Fenced code blocks in the selected item's docs
+    pub fn selected_doc_examples(&self) -> Vec<DocExample> {
+        self.selected_item()
+            .and_then(|item| item.documentation())
+            .map(extract_doc_examples)
+            .unwrap_or_default()
     }
 
-    pub fn on_backspace(&mut self) {
-        self.search_input.pop();
-        self.filter_items();
-        self.show_completion = self.search_input.len() >= 2
-            && !(self.current_tab == Tab::Crates && self.selected_installed_crate.is_some());
+    pub fn next_doc_example(&mut self) {
+        let count = self.selected_doc_examples().len();
+        if count > 0 {
+            self.project.doc_example_selected = (self.project.doc_example_selected + 1) % count;
+            self.status_message = format!(
+                "Doc example {}/{}",
+                self.project.doc_example_selected + 1,
+                count
+            );
+        }
     }
 
-    pub fn clear_search(&mut self) {
-        self.search_input.clear();
-        self.show_completion = false;
-        self.filter_items();
+    /// Copy the selected doc example (with hidden `# ` lines restored) to the clipboard
+    pub fn copy_doc_example(&mut self) {
+        let examples = self.selected_doc_examples();
+        if examples.is_empty() {
+            self.status_message = "No doc examples on this item".to_string();
+            return;
+        }
+        let example = &examples[self.project.doc_example_selected % examples.len()];
+        match copy_to_clipboard(&example.compiled_code()) {
+            Ok(method) => self.status_message = format!("Copied doc example ({})", method),
+            Err(e) => self.notify(Severity::Error, format!("Copy failed: {e}")),
+        }
     }
 
-    pub fn toggle_help(&mut self) {
-        self.show_help = !self.show_help;
+    /// Run `cargo test --doc` filtered to the selected item, in the background
+    pub fn run_doc_tests(&mut self) {
+        if self.project.doctest_running {
+            self.status_message = "Doc tests already running...".to_string();
+            return;
+        }
+        if self.project.current_tab == Tab::Crates {
+            self.status_message = "Doc tests can only run for project items".to_string();
+            return;
+        }
+        let (Some(item), Some(project)) = (self.selected_item(), self.project.project_path.clone())
+        else {
+            return;
+        };
+        let filter = item.qualified_name();
+        self.status_message = format!("Running cargo test --doc {}...", filter);
+        self.project.doctest_running = true;
+        self.project.task_tx.spawn(
+            Some(TaskResult::Doctest(format!(
+                "cargo test --doc: {}",
+                TASK_PANICKED
+            ))),
+            move |tx| {
+                let output = Command::new("cargo")
+                    .args(["test", "--doc", &filter])
+                    .current_dir(&project)
+                    .output();
+                let message = match output {
+                    Ok(o) => {
+                        let stdout = String::from_utf8_lossy(&o.stdout);
+                        let summary = stdout
+                            .lines()
+                            .rev()
+                            .find(|l| l.starts_with("test result:"))
+                            .map(|l| l.trim_start_matches("test result: ").to_string());
+                        match (o.status.success(), summary) {
+                            (true, Some(s)) => format!("Doc tests for {}: {}", filter, s),
+                            (false, Some(s)) => format!("Doc tests for {} FAILED: {}", filter, s),
+                            (_, None) => {
+                                let stderr = String::from_utf8_lossy(&o.stderr);
+                                let last = stderr.lines().rev().find(|l| !l.trim().is_empty());
+                                format!("cargo test --doc: {}", last.unwrap_or("no output"))
+                            }
+                        }
+                    }
+                    Err(e) => format!("Failed to run cargo: {e}"),
+                };
+                tx.send(TaskResult::Doctest(message));
+            },
+        );
     }
 
     /// Build context string for the currently selected item (for Copilot).
@@ -801,63 +4544,225 @@ impl App {
 
     /// Submit the current chat input to Copilot (spawns thread, sets loading).
     pub fn submit_copilot_message(&mut self) {
-        let input = self.copilot_chat_input.trim().to_string();
+        let input = self.project.copilot_chat_input.trim().to_string();
         if input.is_empty() {
             return;
         }
-        self.copilot_chat_input.clear();
-        self.copilot_chat_messages
+        self.project.copilot_chat_input.clear();
+        self.project
+            .copilot_chat_messages
             .push(("user".to_string(), input.clone()));
 
+        if self.offline {
+            self.project.copilot_chat_messages.push((
+                "assistant".to_string(),
+                "Offline mode: Copilot is disabled.".to_string(),
+            ));
+            return;
+        }
+
         let context = if let Some(c) = self.build_copilot_context() {
             c
         } else {
-            self.copilot_chat_messages
+            self.project
+                .copilot_chat_messages
                 .push(("assistant".to_string(), "No item selected.".to_string()));
             return;
         };
 
         let mut full_prompt = context;
         full_prompt.push_str("\n\n**Conversation:**\n");
-        for (role, content) in &self.copilot_chat_messages {
+        for (role, content) in &self.project.copilot_chat_messages {
             let label = if role == "user" { "User" } else { "Assistant" };
             let _ = writeln!(full_prompt, "{}: {}", label, content);
         }
         full_prompt.push_str("\nRespond to the user's latest message above.");
 
-        let tx = self.copilot_tx.clone();
-        let project_path = self.project_path.clone();
-        thread::spawn(move || {
-            let mut cmd = Command::new("copilot");
-            cmd.arg("-p").arg(&full_prompt).arg("--allow-all").arg("-s");
-            if let Some(ref p) = project_path {
-                cmd.arg("--add-dir").arg(p);
-            }
-            let output = cmd.output();
-            let response = match output {
-                Ok(o) if o.status.success() => {
-                    String::from_utf8_lossy(&o.stdout).trim().to_string()
+        let project_path = self.project.project_path.clone();
+        self.project.task_tx.spawn(
+            Some(TaskResult::Copilot(format!("Copilot: {}", TASK_PANICKED))),
+            move |tx| {
+                let mut cmd = Command::new("copilot");
+                cmd.arg("-p").arg(&full_prompt).arg("--allow-all").arg("-s");
+                if let Some(ref p) = project_path {
+                    cmd.arg("--add-dir").arg(p);
                 }
-                Ok(o) => format!(
-                    "Copilot error (exit {}): {}",
-                    o.status,
-                    String::from_utf8_lossy(&o.stderr)
-                ),
-                Err(e) => format!("Failed to run copilot: {}", e),
-            };
-            let _ = tx.send(response);
+                let output = cmd.output();
+                let response = match output {
+                    Ok(o) if o.status.success() => {
+                        String::from_utf8_lossy(&o.stdout).trim().to_string()
+                    }
+                    Ok(o) => format!(
+                        "Copilot error (exit {}): {}",
+                        o.status,
+                        String::from_utf8_lossy(&o.stderr)
+                    ),
+                    Err(e) => format!("Failed to run copilot: {}", e),
+                };
+                tx.send(TaskResult::Copilot(response));
+            },
+        );
+        self.project.copilot_chat_loading = true;
+    }
+
+    /// Pin the selected item, or unpin if it is already pinned
+    pub fn toggle_pin(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        let same = self.project.pinned_item.as_ref().is_some_and(|p| {
+            p.kind() == item.kind() && p.qualified_name() == item.qualified_name()
         });
-        self.copilot_chat_loading = true;
+        if same {
+            self.unpin();
+        } else {
+            self.status_message =
+                format!("Pinned {} · select another item to compare", item.name());
+            self.project.pinned_item = Some(item);
+            self.project.pinned_scroll = 0;
+        }
+    }
+
+    /// Collapse the inspector section at the top of the view, or expand it; remembered
+    /// for every item of the same kind
+    pub fn toggle_inspector_section(&mut self) {
+        let Some(kind) = self.selected_item().map(|i| i.kind()) else {
+            return;
+        };
+        let Some(section) = self.project.inspector_top_section.borrow().clone() else {
+            return;
+        };
+        let collapsed = self.session.toggle_section(kind, &section);
+        let _ = self.session.save();
+        self.status_message = format!(
+            "{} {} for every {} item",
+            if collapsed { "Collapsed" } else { "Expanded" },
+            section,
+            kind
+        );
+    }
+
+    /// Expand the collapsed inspector sections of the selected item's kind
+    pub fn expand_inspector_sections(&mut self) {
+        let Some(kind) = self.selected_item().map(|i| i.kind()) else {
+            return;
+        };
+        if self.session.expand_sections(kind) {
+            let _ = self.session.save();
+            self.status_message = format!("Expanded all sections for {} items", kind);
+        }
+    }
+
+    pub fn unpin(&mut self) {
+        self.project.pinned_item = None;
+        if self.project.focus == Focus::Pinned {
+            self.project.focus = Focus::Inspector;
+        }
     }
 
     /// Toggle Copilot chat panel; when opening with an item selected, focus chat.
     pub fn toggle_copilot_chat(&mut self) {
-        self.copilot_chat_open = !self.copilot_chat_open;
-        if self.copilot_chat_open && self.selected_item().is_some() {
-            self.focus = Focus::CopilotChat;
-        } else if !self.copilot_chat_open && self.focus == Focus::CopilotChat {
-            self.focus = Focus::Inspector;
+        self.project.copilot_chat_open = !self.project.copilot_chat_open;
+        if self.project.copilot_chat_open && self.selected_item().is_some() {
+            self.project.focus = Focus::CopilotChat;
+        } else if !self.project.copilot_chat_open && self.project.focus == Focus::CopilotChat {
+            self.project.focus = Focus::Inspector;
+        }
+    }
+}
+
+/// Items checked between cancellation checks in a background filter
+const FILTER_CANCEL_STRIDE: usize = 1024;
+
+/// Indices of project items on `tab` matching `parsed` within `scope`; `None` if cancelled
+/// Seconds since the Unix epoch, for ages of releases and issues
+pub(super) fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn filter_project_items(
+    items: &[AnalyzedItem],
+    parsed: &Query,
+    tab: Tab,
+    scope: Option<&[String]>,
+    cancel: &AtomicBool,
+) -> Option<Vec<usize>> {
+    filter_indices(items, cancel, |item| {
+        tab_matches(tab, item)
+            && parsed.matches(item)
+            && scope.map_or(true, |scope| scope_path(item).starts_with(scope))
+    })
+}
+
+/// Indices of installed crate items matching `parsed` by name or qualified path
+/// (`serde::de::Deserialize`) within `scope`; `None` if cancelled
+fn filter_installed_items(
+    items: &[AnalyzedItem],
+    parsed: &Query,
+    scope: Option<&[String]>,
+    cancel: &AtomicBool,
+) -> Option<Vec<usize>> {
+    let query = parsed.text();
+    let path_query = query.replace("::", "");
+    filter_indices(items, cancel, |item| {
+        if !parsed.filters.iter().all(|f| f.matches(item)) {
+            return false;
+        }
+        if scope.is_some_and(|scope| !scope_path(item).starts_with(scope)) {
+            return false;
+        }
+        if query.is_empty() {
+            return true;
         }
+        if query.contains("::") {
+            // Match against qualified path, or a partial module path
+            item.qualified_name().to_lowercase().contains(&query)
+                || item
+                    .module_path()
+                    .iter()
+                    .any(|p| p.to_lowercase().contains(&path_query))
+        } else {
+            item.name().to_lowercase().contains(&query)
+        }
+    })
+}
+
+fn filter_indices(
+    items: &[AnalyzedItem],
+    cancel: &AtomicBool,
+    keep: impl Fn(&AnalyzedItem) -> bool,
+) -> Option<Vec<usize>> {
+    let mut indices = Vec::new();
+    for (chunk_start, chunk) in (0..)
+        .step_by(FILTER_CANCEL_STRIDE)
+        .zip(items.chunks(FILTER_CANCEL_STRIDE))
+    {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        indices.extend(
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| keep(item))
+                .map(|(i, _)| chunk_start + i),
+        );
+    }
+    Some(indices)
+}
+
+/// Whether an item belongs on a (non-Crates) tab
+fn tab_matches(tab: Tab, item: &AnalyzedItem) -> bool {
+    match tab {
+        Tab::Types => matches!(
+            item,
+            AnalyzedItem::Struct(_) | AnalyzedItem::Enum(_) | AnalyzedItem::TypeAlias(_)
+        ),
+        Tab::Functions => matches!(item, AnalyzedItem::Function(_)),
+        Tab::Modules => matches!(item, AnalyzedItem::Module(_)),
+        Tab::Crates => true, // Handled by crate list or filter_installed_crates
     }
 }
 
@@ -880,16 +4785,16 @@ mod tests {
         "#;
         let items = RustAnalyzer::new().analyze_source(source).unwrap();
         let mut app = App::new();
-        app.items = items;
-        app.filtered_items = vec![0, 1, 2];
-        app.list_state.select(Some(0));
+        app.project.items = items.into();
+        app.project.filtered_items = vec![0, 1, 2];
+        app.project.list_state.select(Some(0));
         app
     }
 
     #[test]
     fn test_get_current_list_len_types_tab() {
         let mut app = make_app_with_items();
-        app.current_tab = Tab::Types;
+        app.project.current_tab = Tab::Types;
         app.filter_items();
         assert_eq!(app.get_current_list_len(), 1);
     }
@@ -897,7 +4802,7 @@ mod tests {
     #[test]
     fn test_get_current_list_len_functions_tab() {
         let mut app = make_app_with_items();
-        app.current_tab = Tab::Functions;
+        app.project.current_tab = Tab::Functions;
         app.filter_items();
         assert_eq!(app.get_current_list_len(), 1);
     }
@@ -905,68 +4810,113 @@ mod tests {
     #[test]
     fn test_get_current_list_len_crates_tab_empty_tree() {
         let mut app = App::new();
-        app.current_tab = Tab::Crates;
-        app.dependency_tree = vec![];
-        app.filtered_dependency_indices = vec![];
+        app.project.current_tab = Tab::Crates;
+        app.project.dependency_tree = vec![];
+        app.project.filtered_dependency_indices = vec![];
         assert_eq!(app.get_current_list_len(), 1);
     }
 
     #[test]
     fn test_get_current_list_len_crates_tab_with_deps() {
         let mut app = App::new();
-        app.current_tab = Tab::Crates;
-        app.dependency_tree = vec![
+        app.project.current_tab = Tab::Crates;
+        app.project.dependency_tree = vec![
             ("oracle".to_string(), 0),
             ("serde".to_string(), 1),
             ("ratatui".to_string(), 1),
         ];
-        app.filtered_dependency_indices = vec![0, 1, 2];
+        app.project.filtered_dependency_indices = vec![0, 1, 2];
         assert_eq!(app.get_current_list_len(), 3);
     }
 
     #[test]
     fn test_selected_dependency_name_none_when_wrong_tab() {
         let mut app = App::new();
-        app.current_tab = Tab::Types;
-        app.dependency_tree = vec![("oracle".to_string(), 0)];
-        app.filtered_dependency_indices = vec![0];
-        app.list_state.select(Some(0));
+        app.project.current_tab = Tab::Types;
+        app.project.dependency_tree = vec![("oracle".to_string(), 0)];
+        app.project.filtered_dependency_indices = vec![0];
+        app.project.list_state.select(Some(0));
         assert!(app.selected_dependency_name().is_none());
     }
 
     #[test]
     fn test_selected_dependency_name_returns_selected() {
         let mut app = App::new();
-        app.current_tab = Tab::Crates;
-        app.dependency_tree = vec![("oracle".to_string(), 0), ("serde".to_string(), 1)];
-        app.filtered_dependency_indices = vec![0, 1];
-        app.list_state.select(Some(1));
+        app.project.current_tab = Tab::Crates;
+        app.project.dependency_tree = vec![("oracle".to_string(), 0), ("serde".to_string(), 1)];
+        app.project.filtered_dependency_indices = vec![0, 1];
+        app.project.list_state.select(Some(1));
         assert_eq!(app.selected_dependency_name(), Some("serde".to_string()));
     }
 
     #[test]
     fn test_dependency_root_name() {
         let mut app = App::new();
-        app.dependency_tree = vec![("oracle".to_string(), 0), ("serde".to_string(), 1)];
+        app.project.dependency_tree = vec![("oracle".to_string(), 0), ("serde".to_string(), 1)];
         assert_eq!(app.dependency_root_name(), Some("oracle"));
-        app.dependency_tree.clear();
+        app.project.dependency_tree.clear();
         assert!(app.dependency_root_name().is_none());
     }
 
     #[test]
     fn test_selected_item_types_tab() {
         let mut app = make_app_with_items();
-        app.current_tab = Tab::Types;
+        app.project.current_tab = Tab::Types;
         app.filter_items();
-        app.list_state.select(Some(0));
+        app.project.list_state.select(Some(0));
         let item = app.selected_item().unwrap();
         assert_eq!(item.name(), "Foo");
     }
 
+    #[test]
+    fn test_filter_items_structured_query() {
+        let mut app = make_app_with_items();
+        app.project.current_tab = Tab::Functions;
+        app.project.search_input = "vis:pub ba".to_string();
+        app.filter_items();
+        assert_eq!(app.get_filtered_items()[0].name(), "bar");
+        app.project.search_input = "async:true".to_string();
+        app.filter_items();
+        assert!(app.get_filtered_items().is_empty());
+        // Partial filter key suggests keys in the completion dropdown
+        app.project.search_input = "bar as".to_string();
+        app.filter_items();
+        assert_eq!(
+            app.project.filtered_candidates[0].kind,
+            CandidateKind::Filter
+        );
+    }
+
+    #[test]
+    fn test_module_scope_filters_subtree() {
+        let source = r#"
+            pub fn top() {}
+            pub mod net {
+                pub fn connect() {}
+            }
+        "#;
+        let mut app = App::new();
+        app.project.items = RustAnalyzer::new().analyze_source(source).unwrap().into();
+        app.project.module_tree = ModuleTree::build(&app.project.items, "demo");
+        app.project.current_tab = Tab::Functions;
+        app.filter_items();
+        assert_eq!(app.get_filtered_items().len(), 2);
+
+        app.toggle_module_tree();
+        app.module_tree_next(); // demo -> net
+        app.scope_to_selected_module();
+        assert!(!app.project.show_module_tree);
+        let names: Vec<_> = app.get_filtered_items().iter().map(|i| i.name()).collect();
+        assert_eq!(names, vec!["connect"]);
+
+        app.clear_module_scope();
+        assert_eq!(app.get_filtered_items().len(), 2);
+    }
+
     #[test]
     fn test_get_filtered_items() {
         let mut app = make_app_with_items();
-        app.current_tab = Tab::Types;
+        app.project.current_tab = Tab::Types;
         app.filter_items();
         let filtered = app.get_filtered_items();
         assert_eq!(filtered.len(), 1);
@@ -976,7 +4926,7 @@ mod tests {
     #[test]
     fn test_installed_crates_display_list_empty_tree_returns_all_installed() {
         let mut app = App::new();
-        app.dependency_tree = vec![];
+        app.project.dependency_tree = vec![];
         app.installed_crates_list = vec!["foo".into(), "bar".into()];
         let list = app.installed_crates_display_list();
         assert_eq!(list, vec!["foo", "bar"]);
@@ -985,9 +4935,416 @@ mod tests {
     #[test]
     fn test_installed_crates_display_list_filters_by_project_deps() {
         let mut app = App::new();
-        app.dependency_tree = vec![("oracle".to_string(), 0), ("serde".to_string(), 1)];
+        app.project.dependency_tree = vec![("oracle".to_string(), 0), ("serde".to_string(), 1)];
         app.installed_crates_list = vec!["serde".into(), "other".into()];
         let list = app.installed_crates_display_list();
         assert_eq!(list, vec!["serde"]);
     }
+
+    #[test]
+    fn test_comparison_filters_in_sync() {
+        let mut app = make_app_with_items();
+        let other = RustAnalyzer::new()
+            .analyze_source("pub struct Foo { pub x: u8 } pub struct Qux; pub fn bar() {}")
+            .unwrap();
+        app.project.compare = Some(CompareProject {
+            path: PathBuf::from("other"),
+            name: "other".to_string(),
+            items: other,
+            filtered: Vec::new(),
+        });
+        app.project.current_tab = Tab::Types;
+        app.project.search_input = "foo".to_string();
+        app.filter_items();
+
+        let compare = app.project.compare.as_ref().unwrap();
+        assert_eq!(compare.filtered.len(), 1);
+        assert_eq!(compare.items[compare.filtered[0]].name(), "Foo");
+        app.project.list_state.select(Some(0));
+        assert_eq!(app.compare_selected(), Some(0));
+    }
+
+    #[test]
+    fn test_offline_skips_network() {
+        let mut app = App::new();
+        app.offline = true;
+        app.project.current_tab = Tab::Crates;
+        app.project.dependency_tree = vec![("oracle".to_string(), 0), ("serde".to_string(), 1)];
+        app.project.filtered_dependency_indices = vec![0, 1];
+        app.project.list_state.select(Some(1));
+        app.maybe_start_crate_doc_fetch();
+        assert!(app.project.crate_docs_loading.is_none());
+
+        app.open_release_notes();
+        assert!(!app.project.show_release_notes);
+    }
+
+    #[test]
+    fn test_unparsable_files_become_warnings() {
+        let dir = std::env::temp_dir().join(format!("oracle-warn-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("good.rs"), "pub fn ok() {}").unwrap();
+        std::fs::write(dir.join("bad.rs"), "pub fn broken( {").unwrap();
+        std::fs::write(
+            dir.join("mixed.rs"),
+            "pub fn before() {}\n\npub fn broken() {\n    let = ;\n}\n\npub struct After;\n",
+        )
+        .unwrap();
+
+        let mut app = App::new();
+        app.analyze_project(&dir).unwrap();
+        let names: Vec<&str> = app.project.items.iter().map(|i| i.name()).collect();
+        assert_eq!(names, ["ok", "before", "After"]);
+        assert_eq!(app.project.analysis_warnings.len(), 2);
+        assert!(app.project.analysis_warnings[0].path.ends_with("bad.rs"));
+        assert!(app.project.analysis_warnings[1]
+            .location()
+            .ends_with("mixed.rs:3-5"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_crate_path_completion() {
+        let registry = std::env::temp_dir().join(format!("oracle-paths-{}", std::process::id()));
+        let src = registry.join("index.test/demo-kit-1.0.0/src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "pub mod sync;\npub fn spawn() {}\n").unwrap();
+        std::fs::write(
+            src.join("sync.rs"),
+            "pub struct Mutex;\npub struct RwLock;\n",
+        )
+        .unwrap();
+
+        let mut app = App::new();
+        app.crate_registry = CrateRegistry::with_path(registry.clone());
+        app.project.current_tab = Tab::Types;
+        app.project.search_input = "demo_kit::".to_string();
+        app.filter_items();
+        assert!(app.project.filtered_candidates.is_empty());
+        let Ok(TaskResult::CratePathItems { name, items }) =
+            app.project.task_rx.recv_timeout(Duration::from_secs(5))
+        else {
+            panic!("expected the crate index");
+        };
+        app.finish_crate_path_index(name, items);
+        let primaries: Vec<_> = app
+            .project
+            .filtered_candidates
+            .iter()
+            .map(|c| c.primary.as_str())
+            .collect();
+        assert_eq!(primaries, vec!["demo_kit::spawn", "demo_kit::sync::"]);
+
+        // A module continues the path; an item opens the crate in the Crates tab
+        app.project.completion_selected = 1;
+        app.select_completion();
+        assert_eq!(app.project.search_input, "demo_kit::sync::");
+        app.project.search_input.push_str("mu");
+        app.filter_items();
+        assert_eq!(
+            app.project.filtered_candidates[0].primary,
+            "demo_kit::sync::Mutex"
+        );
+        app.select_completion();
+        assert_eq!(app.project.current_tab, Tab::Crates);
+        assert_eq!(
+            app.project
+                .selected_installed_crate
+                .as_ref()
+                .map(|c| c.name.as_str()),
+            Some("demo-kit")
+        );
+        assert_eq!(app.project.search_input, "sync::Mutex");
+        let _ = std::fs::remove_dir_all(registry);
+    }
+
+    #[test]
+    fn test_background_filter_drops_superseded_results() {
+        let mut app = App::new();
+        app.project.items = RustAnalyzer::new()
+            .analyze_source("pub fn alpha() {} pub fn beta() {} pub fn alphabet() {}")
+            .unwrap()
+            .into();
+        app.project.current_tab = Tab::Functions;
+        app.filter_items();
+        let wait = |app: &App| match app.project.task_rx.recv_timeout(Duration::from_secs(5)) {
+            Ok(TaskResult::Filtered {
+                generation,
+                installed,
+                indices,
+            }) => (generation, installed, indices),
+            other => panic!("unexpected task result: {other:?}"),
+        };
+
+        app.project.search_input = "alpha".to_string();
+        app.start_background_filter();
+        assert!(app.search_pending());
+        let stale = wait(&app);
+
+        app.project.search_input = "beta".to_string();
+        app.start_background_filter();
+        let fresh = wait(&app);
+        app.finish_background_filter(stale.0, stale.1, stale.2);
+        assert!(app.project.filtering);
+        app.finish_background_filter(fresh.0, fresh.1, fresh.2);
+        assert!(!app.project.filtering);
+        let names: Vec<_> = app.get_filtered_items().iter().map(|i| i.name()).collect();
+        assert_eq!(names, vec!["beta"]);
+    }
+
+    #[test]
+    fn test_filter_indices_cancelled() {
+        let items = RustAnalyzer::new().analyze_source("pub fn a() {}").unwrap();
+        let parsed = Query::parse("");
+        let cancel = AtomicBool::new(true);
+        assert!(filter_project_items(&items, &parsed, Tab::Functions, None, &cancel).is_none());
+        cancel.store(false, Ordering::Relaxed);
+        assert_eq!(
+            filter_project_items(&items, &parsed, Tab::Functions, None, &cancel),
+            Some(vec![0])
+        );
+    }
+
+    fn demo_installed_crate() -> InstalledCrate {
+        InstalledCrate {
+            name: "demo".into(),
+            version: "1.0.0".into(),
+            path: PathBuf::from("demo-1.0.0"),
+            readme: None,
+            license: None,
+            description: None,
+            authors: Vec::new(),
+            repository: None,
+            documentation: None,
+            keywords: Vec::new(),
+            categories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_drill_into_installed_crate_modules() {
+        let source = r#"
+            pub fn top() {}
+            pub mod net {
+                pub fn connect() {}
+                pub mod tcp {
+                    pub fn bind() {}
+                }
+            }
+        "#;
+        let mut app = App::new();
+        app.project.current_tab = Tab::Crates;
+        app.project.selected_installed_crate = Some(demo_installed_crate());
+        let generation = app.project.installed_generation;
+        let items = RustAnalyzer::new().analyze_source(source).unwrap();
+        app.add_installed_items(generation, items);
+        assert_eq!(app.project.installed_module_tree.root.name, "demo");
+
+        let net = app
+            .project
+            .installed_crate_filtered
+            .iter()
+            .position(|&i| app.project.installed_crate_items[i].name() == "net")
+            .unwrap();
+        app.project.list_state.select(Some(net));
+        assert!(app.drill_into_selected_module());
+        assert_eq!(app.current_module_scope(), Some(&["net".to_string()][..]));
+        let names: Vec<_> = app.get_filtered_items().iter().map(|i| i.name()).collect();
+        assert!(names.contains(&"bind") && !names.contains(&"top"));
+
+        // The project scope is untouched, and Left walks back up to the crate root
+        assert!(app.project.module_scope.is_none());
+        assert!(app.leave_installed_module());
+        assert!(app.current_module_scope().is_none());
+        assert!(!app.leave_installed_module());
+    }
+
+    #[test]
+    fn test_where_used_searches_the_project() {
+        let analyzer = RustAnalyzer::new();
+        let mut app = App::new();
+        app.project.project_path = Some(PathBuf::from("/p"));
+        let project = analyzer
+            .analyze_source(
+                "#[derive(demo::Render)]\npub struct Page;\n\
+                 pub fn draw(r: &dyn Render) {}\npub fn other() {}",
+            )
+            .unwrap();
+        app.project.usage_index = TypeUsageIndex::build(&project);
+
+        app.project.current_tab = Tab::Crates;
+        app.project.selected_installed_crate = Some(demo_installed_crate());
+        let generation = app.project.installed_generation;
+        let items = analyzer
+            .analyze_source("pub trait Render {}\npub fn helper() {}")
+            .unwrap();
+        app.add_installed_items(generation, items);
+        let position = |app: &App, name: &str| {
+            app.project
+                .installed_crate_filtered
+                .iter()
+                .position(|&i| app.project.installed_crate_items[i].name() == name)
+        };
+        app.project.list_state.select(position(&app, "helper"));
+        app.show_where_used();
+        assert!(app.project.report.is_none());
+
+        app.project.list_state.select(position(&app, "Render"));
+        app.show_where_used();
+        let report = app.project.report.as_ref().unwrap();
+        assert_eq!(report.title, "Where demo::Render is used");
+        assert!(report.sections[0].rows[0].text.ends_with("Page"));
+        assert!(report.sections[1].rows[0].text.ends_with("draw"));
+    }
+
+    #[test]
+    fn test_installed_items_stream_and_drop_stale() {
+        let analyzer = RustAnalyzer::new();
+        let mut app = App::new();
+        app.project.selected_installed_crate = Some(demo_installed_crate());
+        app.project.installed_loading = true;
+        let generation = app.project.installed_generation;
+        app.add_installed_items(
+            generation,
+            analyzer.analyze_source("pub fn a() {}").unwrap(),
+        );
+        app.add_installed_items(
+            generation,
+            analyzer.analyze_source("pub fn b() {}").unwrap(),
+        );
+        assert_eq!(app.project.installed_crate_filtered.len(), 2);
+
+        app.clear_installed_crate();
+        assert!(!app.project.installed_loading);
+        app.add_installed_items(
+            generation,
+            analyzer.analyze_source("pub fn c() {}").unwrap(),
+        );
+        app.finish_installed_analysis(generation);
+        assert!(app.project.installed_crate_items.is_empty());
+    }
+
+    #[test]
+    fn test_sort_crates_by_health() {
+        let mut app = App::new();
+        app.project.current_tab = Tab::Crates;
+        app.project.dependency_tree = vec![
+            ("demo".to_string(), 0),
+            ("alpha".to_string(), 1),
+            ("beta".to_string(), 1),
+            ("gamma".to_string(), 1),
+        ];
+        for (name, recent) in [("alpha", 10), ("beta", 5_000_000)] {
+            app.crate_docs_cache.insert(
+                name.to_string(),
+                CrateDocInfo {
+                    name: name.to_string(),
+                    recent_downloads: Some(recent),
+                    ..Default::default()
+                },
+            );
+        }
+        app.filter_items();
+        app.project.list_state.select(Some(0)); // alpha
+        app.toggle_health_sort();
+        let order: Vec<&str> = app
+            .project
+            .filtered_dependency_indices
+            .iter()
+            .map(|&i| app.project.dependency_tree[i].0.as_str())
+            .collect();
+        // Unscored crates go last
+        assert_eq!(order, vec!["beta", "alpha", "demo", "gamma"]);
+        assert_eq!(app.project.list_state.selected(), Some(1));
+        app.toggle_health_sort();
+        assert_eq!(app.selected_dependency_name().as_deref(), Some("alpha"));
+        assert_eq!(app.project.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_upgrade_mark_and_plan() {
+        let dir = std::env::temp_dir().join(format!("oracle-upgrade-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"0.9\" # old\n",
+        )
+        .unwrap();
+        let mut app = App::new();
+        app.project.project_path = Some(dir.clone());
+        app.project.current_tab = Tab::Crates;
+        app.project.dependency_tree = vec![
+            ("demo".to_string(), 0),
+            ("serde".to_string(), 1),
+            ("itoa".to_string(), 2),
+        ];
+        app.project.filtered_dependency_indices = vec![0, 1, 2];
+        app.project.crate_info = Some(CrateInfo {
+            name: "demo".into(),
+            version: "0.1.0".into(),
+            authors: Vec::new(),
+            license: None,
+            description: None,
+            homepage: None,
+            repository: None,
+            documentation: None,
+            dependencies: vec![crate::analyzer::DependencyInfo {
+                name: "serde".into(),
+                version: "^0.9".into(),
+                optional: false,
+                features: Vec::new(),
+                kind: crate::analyzer::DependencyKind::Normal,
+            }],
+            features: Vec::new(),
+            default_features: Vec::new(),
+            edition: "2021".into(),
+            rust_version: None,
+        });
+
+        app.project.list_state.select(Some(1));
+        app.toggle_upgrade_mark();
+        assert!(
+            app.project.upgrade_marks.is_empty(),
+            "latest version not fetched yet"
+        );
+        app.crate_docs_cache.insert(
+            "serde".into(),
+            CrateDocInfo {
+                version: "1.0.200".into(),
+                ..Default::default()
+            },
+        );
+        app.toggle_upgrade_mark();
+        assert_eq!(
+            app.project.upgrade_marks.get("serde").map(String::as_str),
+            Some("1.0.200")
+        );
+        app.project.list_state.select(Some(2));
+        app.toggle_upgrade_mark();
+        assert_eq!(
+            app.project.upgrade_marks.len(),
+            1,
+            "transitive dependencies can't be marked"
+        );
+
+        app.toggle_upgrade_plan();
+        assert!(app.project.upgrade_plan_open);
+        let report = app.project.report.as_ref().unwrap();
+        assert_eq!(report.title, "Upgrade plan");
+        let diff: Vec<&str> = report.sections[1]
+            .rows
+            .iter()
+            .map(|r| r.text.as_str())
+            .collect();
+        assert_eq!(
+            diff,
+            vec![
+                "-   5  serde = \"0.9\" # old",
+                "+   5  serde = \"1.0\" # old"
+            ]
+        );
+        app.close_report();
+        assert!(!app.project.upgrade_plan_open);
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
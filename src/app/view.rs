@@ -0,0 +1,233 @@
+//! Rendering the application state with [`OracleUi`]
+
+use super::state::{unix_now, SourceCursor};
+use super::App;
+use crate::analyzer::AnalyzedItem;
+use crate::crates_io::HealthScore;
+use crate::ui::{CompareView, FilteredItems, OracleUi, SectionLayout, Tab};
+
+use ratatui::Frame;
+use std::collections::HashMap;
+use std::path::Path;
+
+impl App {
+    /// Render the whole UI into `frame`
+    pub fn draw(&self, frame: &mut Frame) {
+        // Inspectors drawn this frame report how far they can scroll
+        self.project.inspector_max_scroll.set(None);
+        self.project.pinned_max_scroll.set(None);
+        self.project.inspector_top_section.replace(None);
+        let selected_dep_name = self.selected_dependency_name();
+        let crate_doc = selected_dep_name
+            .as_ref()
+            .and_then(|n| self.crate_docs_cache.get(n));
+        let crate_doc_loading =
+            self.project.crate_docs_loading.as_deref() == selected_dep_name.as_deref();
+        let crate_doc_failed = selected_dep_name
+            .as_ref()
+            .is_some_and(|n| self.project.crate_docs_failed.contains(n));
+        let selected = self.project.list_state.selected();
+
+        // Lists index into the items; only the visible rows are formatted
+        let installed_items = FilteredItems::new(
+            &self.project.installed_crate_items,
+            &self.project.installed_crate_filtered,
+        );
+        let filtered = if self.viewing_installed_crate() {
+            installed_items
+        } else {
+            FilteredItems::new(&self.project.items, &self.project.filtered_items)
+        };
+
+        let all_items_impl = if self.project.current_tab == Tab::Crates
+            && self.project.selected_installed_crate.is_some()
+        {
+            Some(self.project.installed_crate_items.as_slice())
+        } else {
+            Some(self.project.items.as_slice())
+        };
+        let usage_index = if self.project.current_tab == Tab::Crates
+            && self.project.selected_installed_crate.is_some()
+        {
+            &self.project.installed_usage_index
+        } else {
+            &self.project.usage_index
+        };
+        let module_tree_rows = self
+            .project
+            .show_module_tree
+            .then(|| self.module_tree_rows());
+        let (project_names, active_project) = self.project_tab_names();
+        let compare_filtered: Vec<&AnalyzedItem> = self
+            .project
+            .compare
+            .as_ref()
+            .map(|c| c.filtered.iter().map(|&i| &c.items[i]).collect())
+            .unwrap_or_default();
+        let compare = self.project.compare.as_ref().map(|c| CompareView {
+            left_name: self
+                .project
+                .crate_info
+                .as_ref()
+                .map_or("current", |i| i.name.as_str()),
+            name: &c.name,
+            items: &c.items,
+            filtered: &compare_filtered,
+            selected: self.compare_selected(),
+        });
+        let now = unix_now();
+        let health_scores: HashMap<String, HealthScore> = self
+            .crate_docs_cache
+            .keys()
+            .filter_map(|name| Some((name.clone(), self.dependency_health(name, now)?)))
+            .collect();
+        let ui = OracleUi::new(&self.theme)
+            .items(&self.project.items)
+            .all_items_impl_lookup(all_items_impl)
+            .usage_index(Some(usage_index))
+            .filtered_items(filtered)
+            .list_selected(selected)
+            .module_tree(
+                module_tree_rows.as_deref(),
+                self.project.module_tree_selected,
+            )
+            .module_scope(self.current_module_scope())
+            .compare(compare)
+            .candidates(&self.project.filtered_candidates)
+            .crate_info(self.project.crate_info.as_ref())
+            .dependency_tree(&self.project.dependency_tree)
+            .msrv_report(self.project.msrv_report.as_ref())
+            .edition_issues(&self.project.edition_issues)
+            .duplicates(&self.project.duplicates)
+            .feature_unification(&self.project.feature_unification)
+            .filtered_dependency_indices(&self.project.filtered_dependency_indices)
+            .upgrade_marks(&self.project.upgrade_marks)
+            .health_scores(&health_scores, self.project.sort_crates_by_health)
+            .crate_doc(crate_doc)
+            .crate_doc_loading(crate_doc_loading)
+            .crate_doc_failed(crate_doc_failed)
+            .selected_installed_crate(self.project.selected_installed_crate.as_ref())
+            .installed_crate_items(installed_items)
+            .installed_loading(self.project.installed_loading)
+            .filtering(self.search_pending())
+            .offline(self.offline)
+            .project_tabs(&project_names, active_project)
+            .target_size_bytes(self.project.target_size_bytes)
+            .search_input(&self.project.search_input)
+            .current_tab(self.project.current_tab)
+            .focus(self.project.focus)
+            .selected_item(self.selected_item())
+            .pinned(
+                self.project.pinned_item.as_ref(),
+                self.project.pinned_scroll,
+            )
+            .completion_selected(self.project.completion_selected)
+            .completion_offset(self.project.completion_offset)
+            .show_completion(self.project.show_completion)
+            .show_help(self.show_help)
+            .settings_form(
+                Some(&self.settings_form).filter(|_| self.show_settings),
+                &self.settings,
+            )
+            .release_notes(
+                self.project.show_release_notes,
+                self.project.release_notes.as_ref(),
+                self.project.release_notes_scroll,
+            )
+            .report(self.project.report.as_ref(), self.project.report_scroll)
+            .warnings(&self.project.analysis_warnings, self.project.show_warnings)
+            .status_message(&self.status_message)
+            .toast(self.messages.toast())
+            .views(
+                &self.settings.views,
+                self.project.view_picker.as_ref(),
+                self.project.active_view.as_deref(),
+            )
+            .feature_picker(self.project.feature_picker.as_ref())
+            .review(
+                &self.project.review_notes,
+                self.project.review_input.as_deref(),
+            )
+            .project_picker(self.project_picker.as_ref())
+            .spelling(
+                self.project.spelling_view.as_ref(),
+                &self.project.misspellings,
+            )
+            .files_view(
+                self.project.files_view.as_ref(),
+                &self.project.file_metrics,
+                self.project.project_path.as_deref(),
+            )
+            .file_scope(self.project.file_scope.as_deref())
+            .registry_search(
+                self.project
+                    .registry_search
+                    .as_ref()
+                    .filter(|_| self.registry_mode()),
+            )
+            .inspector_scroll(self.project.inspector_scroll)
+            .report_max_scroll(
+                &self.project.inspector_max_scroll,
+                &self.project.pinned_max_scroll,
+            )
+            .report_top_section(&self.project.inspector_top_section)
+            .inspector_sections(SectionLayout {
+                settings: Some(&self.settings.inspector),
+                collapsed: Some(&self.session.collapsed_sections),
+            })
+            .inspector_raw(self.project.inspector_raw)
+            .source_cursor(
+                self.project
+                    .source_cursor
+                    .as_ref()
+                    .filter(|_| self.source_cursor_live())
+                    .map(SourceCursor::view),
+            )
+            .inspector_diff(
+                self.project
+                    .inspector_diff
+                    .then_some(self.settings.inspector.diff_ref.as_str()),
+            )
+            .inspector_cache(&self.project.inspector_cache)
+            .code_layout(self.project.code_wrap, self.project.code_hscroll)
+            .method_sort(self.project.method_sort)
+            .coverage(self.project.coverage.as_ref())
+            .doc_links(&self.project.doc_links)
+            .doc_example_selected(self.project.doc_example_selected)
+            .animation_state(&self.animation)
+            .show_copilot_chat(self.project.copilot_chat_open)
+            .copilot_chat_messages(&self.project.copilot_chat_messages)
+            .copilot_chat_input(&self.project.copilot_chat_input)
+            .copilot_chat_loading(self.project.copilot_chat_loading)
+            .copilot_chat_scroll(self.project.copilot_chat_scroll);
+
+        frame.render_widget(ui, frame.area());
+    }
+
+    /// `oracle: <crate> — <selected item>` for the terminal title; `None` when the
+    /// title setting is off
+    pub fn terminal_title(&self) -> Option<String> {
+        if !self.settings.ui.terminal_title {
+            return None;
+        }
+        let mut title = format!("oracle: {}", self.project.project_name());
+        let selected = self
+            .selected_item()
+            .map(|item| item.qualified_name())
+            .or_else(|| self.selected_dependency_name());
+        if let Some(name) = selected {
+            title.push_str(" — ");
+            title.push_str(&name);
+        }
+        Some(title)
+    }
+
+    /// Directory reported to the terminal (OSC 7), with the title setting
+    pub fn terminal_cwd(&self) -> Option<&Path> {
+        self.settings
+            .ui
+            .terminal_title
+            .then_some(self.project.project_path.as_deref())
+            .flatten()
+    }
+}
@@ -0,0 +1,412 @@
+//! Lint-style findings: undocumented public items, `unsafe` code, outdated dependencies,
+//! syntax the crate's edition doesn't have
+//!
+//! `oracle audit` prints them as text or as a SARIF 2.1.0 log, which GitHub code
+//! scanning and most CI dashboards accept as is.
+
+use std::path::{Path, PathBuf};
+
+use cargo_metadata::semver::Version;
+use serde_json::{json, Value};
+
+use crate::analyzer::{
+    AnalyzedItem, DependencyAnalyzer, DependencyKind, EditionIssue, FunctionInfo, Visibility,
+};
+use crate::report::ProjectReport;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rule {
+    MissingDocs,
+    Unsafe,
+    OutdatedDependency,
+    Edition,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 4] = [
+        Self::MissingDocs,
+        Self::Unsafe,
+        Self::OutdatedDependency,
+        Self::Edition,
+    ];
+
+    /// Stable id, the SARIF `ruleId`
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::MissingDocs => "missing-docs",
+            Self::Unsafe => "unsafe",
+            Self::OutdatedDependency => "outdated-dependency",
+            Self::Edition => "edition",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::MissingDocs => "Public item without documentation",
+            Self::Unsafe => "Unsafe function, trait, impl or mutable static",
+            Self::OutdatedDependency => "Direct dependency with a newer release on crates.io",
+            Self::Edition => "Syntax the crate's edition doesn't accept",
+        }
+    }
+
+    /// SARIF level: `warning` or `note`
+    pub fn level(self) -> &'static str {
+        match self {
+            Self::MissingDocs | Self::OutdatedDependency | Self::Edition => "warning",
+            Self::Unsafe => "note",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: Rule,
+    pub message: String,
+    pub file: Option<PathBuf>,
+    pub line: Option<usize>,
+}
+
+impl Finding {
+    fn at_item(rule: Rule, message: String, item: &AnalyzedItem) -> Self {
+        let loc = item.source_location();
+        Self {
+            rule,
+            message,
+            file: loc.and_then(|l| l.file.clone()),
+            line: loc.and_then(|l| l.line),
+        }
+    }
+
+    fn at_method(rule: Rule, message: String, method: &FunctionInfo) -> Self {
+        Self {
+            rule,
+            message,
+            file: method.source_location.file.clone(),
+            line: method.source_location.line,
+        }
+    }
+}
+
+/// Findings of the offline rules: missing docs and `unsafe`
+pub fn audit_items(items: &[AnalyzedItem]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for item in items {
+        let name = item.qualified_name();
+        if item.visibility() == Some(Visibility::Public) && item.documentation().is_none() {
+            let message = format!("{} `{}` is public but undocumented", item.kind(), name);
+            findings.push(Finding::at_item(Rule::MissingDocs, message, item));
+        }
+        let unsafe_what = match item {
+            AnalyzedItem::Function(f) if f.is_unsafe => Some("unsafe fn"),
+            AnalyzedItem::Trait(t) if t.is_unsafe => Some("unsafe trait"),
+            AnalyzedItem::Impl(i) if i.is_unsafe => Some("unsafe impl"),
+            AnalyzedItem::Static(s) if s.is_mut => Some("static mut"),
+            _ => None,
+        };
+        if let Some(what) = unsafe_what {
+            let message = format!("{} `{}`", what, name);
+            findings.push(Finding::at_item(Rule::Unsafe, message, item));
+        }
+        let AnalyzedItem::Impl(im) = item else {
+            continue;
+        };
+        for method in &im.methods {
+            let path = format!("{}::{}", name, method.name);
+            // Trait impl methods are documented on the trait
+            if im.trait_name.is_none()
+                && method.visibility == Visibility::Public
+                && method.documentation.is_none()
+            {
+                let message = format!("fn `{}` is public but undocumented", path);
+                findings.push(Finding::at_method(Rule::MissingDocs, message, method));
+            }
+            if method.is_unsafe {
+                let message = format!("unsafe fn `{}`", path);
+                findings.push(Finding::at_method(Rule::Unsafe, message, method));
+            }
+        }
+    }
+    findings
+}
+
+/// One finding per edition issue, naming the innermost item it's in
+pub fn edition_findings(items: &[AnalyzedItem], issues: &[EditionIssue]) -> Vec<Finding> {
+    issues
+        .iter()
+        .map(|issue| {
+            let item = items
+                .iter()
+                .filter(|item| {
+                    item.source_location().is_some_and(|loc| {
+                        loc.file.as_deref() == Some(issue.file.as_path())
+                            && loc.line.is_some_and(|line| line <= issue.line)
+                            && loc.end_line.is_some_and(|end| issue.line <= end)
+                    })
+                })
+                .min_by_key(|item| {
+                    let loc = item.source_location();
+                    let (start, end) =
+                        loc.map_or((0, 0), |l| (l.line.unwrap_or(0), l.end_line.unwrap_or(0)));
+                    end - start
+                });
+            let message = match item {
+                Some(item) => format!(
+                    "{} `{}`: {}",
+                    item.kind(),
+                    item.qualified_name(),
+                    issue.message
+                ),
+                None => issue.message.clone(),
+            };
+            Finding {
+                rule: Rule::Edition,
+                message,
+                file: Some(issue.file.clone()),
+                line: Some(issue.line),
+            }
+        })
+        .collect()
+}
+
+/// Direct dependencies whose newest crates.io release is newer than the resolved
+/// version. Makes one crates.io request per dependency.
+pub fn outdated_dependencies(root: &Path) -> Vec<Finding> {
+    let manifest = root.join("Cargo.toml");
+    let Ok(analyzer) = DependencyAnalyzer::from_manifest(&manifest) else {
+        return Vec::new();
+    };
+    let Some(package) = analyzer.root_package() else {
+        return Vec::new();
+    };
+    let manifest_text = std::fs::read_to_string(&manifest).unwrap_or_default();
+    let resolved = analyzer.feature_unification();
+    let mut findings = Vec::new();
+    for dep in package
+        .dependencies
+        .iter()
+        .filter(|d| d.kind == DependencyKind::Normal)
+    {
+        let Some(installed) = resolved
+            .iter()
+            .filter(|f| f.name == dep.name)
+            .filter_map(|f| Version::parse(&f.version).ok())
+            .max()
+        else {
+            continue;
+        };
+        let Some(latest) = crate::crates_io::fetch_crate_docs(&dep.name)
+            .and_then(|info| Version::parse(&info.version).ok())
+        else {
+            continue;
+        };
+        findings.extend(outdated_finding(
+            &dep.name,
+            &installed,
+            &latest,
+            &manifest,
+            &manifest_text,
+        ));
+    }
+    findings
+}
+
+fn outdated_finding(
+    name: &str,
+    installed: &Version,
+    latest: &Version,
+    manifest: &Path,
+    manifest_text: &str,
+) -> Option<Finding> {
+    if latest <= installed {
+        return None;
+    }
+    let line = manifest_text.lines().position(|l| {
+        l.trim_start()
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.trim_start().starts_with(['=', '.']))
+    });
+    Some(Finding {
+        rule: Rule::OutdatedDependency,
+        message: format!(
+            "{} {} is resolved, {} is available",
+            name, installed, latest
+        ),
+        file: Some(manifest.to_path_buf()),
+        line: line.map(|i| i + 1),
+    })
+}
+
+/// Every finding for `report`; `offline` skips the crates.io lookups
+pub fn audit(report: &ProjectReport, offline: bool) -> Vec<Finding> {
+    let mut findings = audit_items(&report.items);
+    findings.extend(edition_findings(&report.items, &report.edition_issues));
+    if !offline && report.crate_info.is_some() {
+        findings.extend(outdated_dependencies(&report.root));
+    }
+    findings
+}
+
+/// `file:line: rule: message` per finding
+pub fn to_text(findings: &[Finding], root: &Path) -> String {
+    let mut text = String::new();
+    for f in findings {
+        let location = match (&f.file, f.line) {
+            (Some(file), Some(line)) => format!("{}:{}: ", relative_uri(file, root), line),
+            (Some(file), None) => format!("{}: ", relative_uri(file, root)),
+            _ => String::new(),
+        };
+        text.push_str(&format!("{}{}: {}\n", location, f.rule.id(), f.message));
+    }
+    text
+}
+
+/// A SARIF 2.1.0 log with one run; file URIs are relative to `root`
+pub fn to_sarif(findings: &[Finding], root: &Path) -> String {
+    let rules: Vec<Value> = Rule::ALL
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id(),
+                "shortDescription": { "text": rule.description() },
+                "defaultConfiguration": { "level": rule.level() },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|f| {
+            let mut result = json!({
+                "ruleId": f.rule.id(),
+                "ruleIndex": Rule::ALL.iter().position(|r| *r == f.rule),
+                "level": f.rule.level(),
+                "message": { "text": f.message },
+            });
+            if let Some(ref file) = f.file {
+                let mut location = json!({
+                    "artifactLocation": { "uri": relative_uri(file, root) },
+                });
+                if let Some(line) = f.line {
+                    location["region"] = json!({ "startLine": line });
+                }
+                result["locations"] = json!([{ "physicalLocation": location }]);
+            }
+            result
+        })
+        .collect();
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "oracle",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+    let mut sarif = serde_json::to_string_pretty(&log).unwrap_or_else(|_| "{}".into());
+    sarif.push('\n');
+    sarif
+}
+
+/// `file` relative to `root` with `/` separators, as SARIF and editors expect
+fn relative_uri(file: &Path, root: &Path) -> String {
+    let root = if root.is_file() {
+        root.parent().unwrap_or(root)
+    } else {
+        root
+    };
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::edition::{self, Edition};
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_audit_items_and_sarif() {
+        let source = "/// Documented\npub fn ok() {}\npub unsafe fn raw() {}\nfn private() {}\n\
+                      pub struct S;\nimpl S { pub fn undocumented(&self) {} }\n\
+                      unsafe impl Send for S {}\nstatic mut COUNT: u32 = 0;\n";
+        let items = RustAnalyzer::new()
+            .with_private(true)
+            .analyze_source_with_path(source, Some(PathBuf::from("/p/src/lib.rs")))
+            .unwrap();
+        let findings = audit_items(&items);
+        let ids: Vec<(&str, &str)> = findings
+            .iter()
+            .map(|f| (f.rule.id(), f.message.as_str()))
+            .collect();
+        assert!(ids.contains(&("missing-docs", "fn `raw` is public but undocumented")));
+        assert!(ids.contains(&("missing-docs", "struct `S` is public but undocumented")));
+        assert!(ids.contains(&(
+            "missing-docs",
+            "fn `S::undocumented` is public but undocumented"
+        )));
+        assert!(ids.contains(&("unsafe", "unsafe fn `raw`")));
+        assert!(ids.contains(&("unsafe", "static mut `COUNT`")));
+        assert!(ids
+            .iter()
+            .any(|(id, m)| *id == "unsafe" && m.starts_with("unsafe impl")));
+        assert!(!ids
+            .iter()
+            .any(|(_, m)| m.contains("private") || m.contains("`ok`")));
+
+        let sarif: Value = serde_json::from_str(&to_sarif(&findings, Path::new("/p"))).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 4);
+        let first = &run["results"][0];
+        assert_eq!(
+            first["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/lib.rs"
+        );
+        assert!(first["locations"][0]["physicalLocation"]["region"]["startLine"].is_u64());
+        assert!(to_text(&findings, Path::new("/p")).starts_with("src/lib.rs:"));
+    }
+
+    #[test]
+    fn test_edition_findings() {
+        let source = "pub fn fetch() {\n    client().await;\n}\n";
+        let path = PathBuf::from("/p/src/lib.rs");
+        let items = RustAnalyzer::new()
+            .with_edition(Edition::E2015)
+            .analyze_source_with_path(source, Some(path.clone()))
+            .unwrap();
+        let issues = edition::check(source, &path, Edition::E2015);
+        let findings = edition_findings(&items, &issues);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].line, Some(2));
+        assert_eq!(
+            findings[0].message,
+            "fn `fetch`: `.await` needs edition 2018, the crate is 2015"
+        );
+    }
+
+    #[test]
+    fn test_outdated_finding() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let manifest = "[dependencies]\nserde_json = \"1\"\nserde = { version = \"1\" }\n";
+        let path = Path::new("Cargo.toml");
+        let finding = outdated_finding("serde", &v("1.0.1"), &v("1.0.2"), path, manifest).unwrap();
+        assert_eq!(finding.line, Some(3));
+        assert_eq!(
+            finding.message,
+            "serde 1.0.1 is resolved, 1.0.2 is available"
+        );
+        assert!(outdated_finding("serde", &v("1.0.2"), &v("1.0.2"), path, manifest).is_none());
+    }
+}
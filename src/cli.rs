@@ -0,0 +1,933 @@
+//! Command-line interface
+//!
+//! `oracle [PATH] [COMPARE]` opens the TUI. The subcommands analyze the same way
+//! but print to stdout, for scripts and quick lookups without the terminal UI;
+//! `--serve` keeps the analysis loaded and answers queries (see [`crate::server`]);
+//! `completions` and `man` are generated from these definitions for packagers.
+
+use std::borrow::Borrow;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::{AnalyzedItem, DependencyAnalyzer, Edition, Query, RustAnalyzer};
+use crate::api;
+use crate::app::App;
+use crate::audit;
+use crate::config::Settings;
+use crate::error::{OracleError, Result};
+use crate::report::{self, ProjectReport};
+use crate::scaffold::{self, NewCrate, NewDependency};
+use crate::server::Server;
+use crate::tags;
+use crate::ui::theme::{Theme, ThemeKind};
+use clap::builder::PossibleValuesParser;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "oracle",
+    version,
+    about = "Inspect Rust code and dependencies in the terminal",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Project directory or `.rs` file [default: current directory]
+    pub path: Option<PathBuf>,
+    /// Second project to compare side by side
+    pub compare: Option<PathBuf>,
+    /// Color theme, instead of the one in the config file
+    #[arg(long, value_name = "NAME", value_parser = theme_names())]
+    pub theme: Option<String>,
+    /// 16 colors and ASCII glyphs, for terminals without truecolor or emoji
+    #[arg(long)]
+    pub compat: bool,
+    /// Leave the mouse to the terminal, so its own text selection works
+    #[arg(long)]
+    pub no_mouse: bool,
+    /// Show the first-run tour of panels and keys again
+    #[arg(long)]
+    pub tour: bool,
+    /// LCOV tracefile or `cargo llvm-cov --json` export to show per-item coverage
+    #[arg(long, value_name = "REPORT")]
+    pub coverage: Option<PathBuf>,
+    /// Session shared with `&` to land on: its list, selected and pinned items,
+    /// views and review notes; opens its project when no path is given
+    #[arg(long, value_name = "FILE")]
+    pub import: Option<PathBuf>,
+    /// Answer JSON-RPC queries about the project on stdin/stdout instead of
+    /// opening the TUI
+    #[arg(long)]
+    pub serve: bool,
+    /// Serve on a Unix socket at this path instead of stdin/stdout
+    #[arg(long, value_name = "PATH", requires = "serve")]
+    pub socket: Option<PathBuf>,
+    #[command(flatten)]
+    pub global: GlobalArgs,
+}
+
+/// Flags accepted by the TUI and every subcommand
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// Make no crates.io, GitHub or Copilot requests
+    #[arg(long, global = true)]
+    pub offline: bool,
+    /// Analyze private items even if the config file turns them off
+    #[arg(long, global = true)]
+    pub include_private: bool,
+    /// Log debug detail to oracle.log
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List a project's items, or print the definition and docs of matching ones
+    Inspect {
+        /// Project directory or `.rs` file
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Item name or path suffix, e.g. `App` or `app::App`
+        #[arg(short, long)]
+        item: Option<String>,
+        /// Only items of this kind: fn, struct, enum, trait, impl, mod, type, const, static
+        #[arg(short, long)]
+        kind: Option<String>,
+        /// Search as in the TUI, e.g. `kind:trait mod:analyzer vis:pub Parse`
+        #[arg(short, long)]
+        query: Option<String>,
+    },
+    /// Print the dependency tree from `cargo metadata`
+    Deps {
+        /// Project directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Deepest level to print (direct dependencies are level 1)
+        #[arg(short, long)]
+        depth: Option<usize>,
+    },
+    /// Write the analysis as a versioned JSON report or the items as Markdown
+    Export {
+        /// Project directory or `.rs` file
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// Output file [default: stdout]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write Markdown docs for a module and every module below it, one file each
+    ///
+    /// e.g. `oracle doc --module analyzer --out docs/analyzer.md` writes
+    /// `docs/analyzer.md`, `docs/analyzer/parser.md`, ... with each module's items,
+    /// signatures and doc comments, and links from every module to its submodules.
+    Doc {
+        /// Project directory or `.rs` file
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Module path, e.g. `analyzer` or `analyzer::parser` [default: the crate root]
+        #[arg(short, long)]
+        module: Option<String>,
+        /// Page of the module (default: `docs/<module>.md`, `docs/crate.md` for the root)
+        #[arg(short, long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Report undocumented public items, `unsafe` code, syntax the crate's edition
+    /// doesn't accept and outdated dependencies
+    ///
+    /// `--format sarif` writes a SARIF 2.1.0 log for GitHub code scanning;
+    /// `--offline` skips the crates.io lookups for outdated dependencies.
+    Audit {
+        /// Project directory or `.rs` file
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        #[arg(short, long, value_enum, default_value_t = AuditFormat::Text)]
+        format: AuditFormat,
+        /// Output file [default: stdout]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the public API as sorted lines, save it, or check it against the saved copy
+    ///
+    /// `--write` saves the listing to `api-surface.txt`; `--check` prints what was
+    /// added and removed since, and fails if anything was, to gate API changes in CI.
+    Api {
+        /// Project directory or `.rs` file
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Save the listing instead of printing it
+        #[arg(long, conflicts_with = "check")]
+        write: bool,
+        /// Compare with the saved listing and fail if the API changed
+        #[arg(long)]
+        check: bool,
+        /// Saved listing [default: api-surface.txt in the project directory]
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+    /// Write a `tags` file for vim and other ctags readers, or `TAGS` for Emacs
+    ///
+    /// Every item is tagged with its kind, scope and line, impl methods included,
+    /// private items too: `oracle tags`, then `:tag Settings` in vim, or
+    /// `oracle tags -f etags`, then `M-.` in Emacs.
+    Tags {
+        /// Project directory or `.rs` file
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        #[arg(short, long, value_enum, default_value_t = TagsFormat::Ctags)]
+        format: TagsFormat,
+        /// Output file, `-` for stdout [default: tags, or TAGS for etags, in the project directory]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Update a persistent SQLite index of the items, then look items up in it
+    ///
+    /// The index is kept in `target/oracle-index.sqlite`; each run re-parses only the
+    /// files changed since the last. `--search` matches words of names, paths and doc
+    /// comments, best first; `--item` prints definitions and docs like `inspect`,
+    /// without analyzing the whole project again.
+    #[cfg(feature = "sqlite")]
+    Index {
+        /// Project directory or `.rs` file
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Full-text search, e.g. `parse config`
+        #[arg(short, long, conflicts_with = "item")]
+        search: Option<String>,
+        /// Item name or path suffix, e.g. `App` or `app::App`
+        #[arg(short, long)]
+        item: Option<String>,
+        /// Most search results to print
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Create a crate with chosen dependencies, license and edition
+    ///
+    /// Dependencies are `name[@version][:feature,...]`; without a version the newest
+    /// release on crates.io is used. `--interactive` asks for everything instead,
+    /// picking dependencies from crates.io search results.
+    New {
+        /// Directory to create the crate in
+        path: PathBuf,
+        /// Package name [default: the directory name]
+        #[arg(long)]
+        name: Option<String>,
+        /// Library crate (`src/lib.rs`) instead of a binary
+        #[arg(long)]
+        lib: bool,
+        #[arg(long, default_value = scaffold::DEFAULT_EDITION, value_parser = PossibleValuesParser::new(scaffold::EDITIONS))]
+        edition: String,
+        /// SPDX license expression, e.g. `MIT OR Apache-2.0`
+        #[arg(long)]
+        license: Option<String>,
+        /// Dependency to add, e.g. `serde@1:derive` (repeatable)
+        #[arg(short, long = "dep", value_name = "SPEC")]
+        deps: Vec<NewDependency>,
+        /// Ask for each setting and search crates.io for dependencies
+        #[arg(short, long)]
+        interactive: bool,
+    },
+    /// Print a shell completion script
+    ///
+    /// e.g. `oracle completions zsh > ~/.zfunc/_oracle` or
+    /// `oracle completions fish > ~/.config/fish/completions/oracle.fish`
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Print the man page, or write one page per subcommand to a directory
+    Man {
+        /// Write `oracle.1`, `oracle-inspect.1`, ... here instead of printing `oracle.1`
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TagsFormat {
+    /// universal-ctags `tags`, for vim, neovim and most editors
+    Ctags,
+    /// Emacs `TAGS`
+    Etags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AuditFormat {
+    Text,
+    Sarif,
+}
+
+fn theme_names() -> PossibleValuesParser {
+    PossibleValuesParser::new(ThemeKind::ALL.iter().map(|k| k.name()))
+}
+
+impl Cli {
+    /// Apply the flags that override the config file
+    pub fn apply(&self, app: &mut App) {
+        self.global.apply(app);
+        if let Some(ref name) = self.theme {
+            app.theme = Theme::from_name(name).with_compat(app.theme.compat);
+        }
+        if self.compat {
+            app.theme = std::mem::take(&mut app.theme).with_compat(true);
+        }
+    }
+}
+
+impl GlobalArgs {
+    pub fn apply(&self, app: &mut App) {
+        app.offline |= self.offline;
+        app.include_private |= self.include_private;
+    }
+}
+
+/// Run a subcommand, writing its output to `out`
+pub fn run(command: &Command, global: &GlobalArgs, out: &mut dyn Write) -> Result<()> {
+    match command {
+        Command::Inspect {
+            path,
+            item,
+            kind,
+            query,
+        } => {
+            // Streamed file by file: printing doesn't need the whole project in memory
+            let path = canonical(path);
+            let settings = settings(&path, global)?;
+            let edition = Edition::from_manifest(&path.join("Cargo.toml"));
+            let analyzer = RustAnalyzer::new()
+                .with_private(settings.analyzer.include_private)
+                .with_excludes(settings.analyzer.exclude)
+                .with_edition(edition.unwrap_or_default());
+            let query = query.as_deref().map(Query::parse);
+            inspect(
+                report::source_items(&analyzer, &path),
+                item.as_deref(),
+                kind.as_deref(),
+                query.as_ref(),
+                out,
+            )
+        }
+        Command::Deps { path, depth } => {
+            let manifest = path.join("Cargo.toml");
+            let analyzer = DependencyAnalyzer::from_manifest(&manifest)?;
+            let root = analyzer.root_package().ok_or_else(|| {
+                OracleError::Other(format!("No root package in {}", manifest.display()))
+            })?;
+            writeln!(out, "{} v{}", root.name, root.version)?;
+            for (name, level) in analyzer.dependency_tree(&root.name) {
+                if level == 0 || depth.is_some_and(|d| level > d) {
+                    continue;
+                }
+                writeln!(out, "{}{}", "  ".repeat(level), name)?;
+            }
+            Ok(())
+        }
+        Command::Export {
+            path,
+            format,
+            output,
+        } => {
+            let path = canonical(path);
+            let report = analyze(&path, &settings(&path, global)?)?;
+            let text = match format {
+                ExportFormat::Json => report.to_json(),
+                ExportFormat::Markdown => report.to_markdown(),
+            };
+            write_output(&text, output.as_deref(), out)
+        }
+        Command::Doc {
+            path,
+            module,
+            out: file,
+        } => {
+            let path = canonical(path);
+            let report = analyze(&path, &settings(&path, global)?)?;
+            let module: Vec<String> = module
+                .as_deref()
+                .map(|m| {
+                    m.trim_start_matches("crate::")
+                        .split("::")
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let file = file.clone().unwrap_or_else(|| {
+                let name = module.last().map_or("crate", String::as_str);
+                Path::new("docs").join(format!("{}.md", name))
+            });
+            let pages = report::module_pages(&report.items, &module, &file).ok_or_else(|| {
+                OracleError::Other(format!("No items in module `{}`", module.join("::")))
+            })?;
+            let dir = file.parent().unwrap_or(Path::new(""));
+            for page in &pages {
+                let target = dir.join(&page.file);
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&target, &page.markdown)?;
+            }
+            writeln!(
+                out,
+                "Wrote {} module pages to {}",
+                pages.len(),
+                dir.join(&pages[0].file).display()
+            )?;
+            Ok(())
+        }
+        Command::Audit {
+            path,
+            format,
+            output,
+        } => {
+            let path = canonical(path);
+            let settings = settings(&path, global)?;
+            let report = analyze(&path, &settings)?;
+            let findings = audit::audit(&report, settings.network.offline);
+            let text = match format {
+                AuditFormat::Text => audit::to_text(&findings, &report.root),
+                AuditFormat::Sarif => audit::to_sarif(&findings, &report.root),
+            };
+            write_output(&text, output.as_deref(), out)
+        }
+        Command::Api {
+            path,
+            write,
+            check,
+            file,
+        } => {
+            // Private modules decide which `pub` items are reachable
+            let path = canonical(path);
+            let exclude = settings(&path, global)?.analyzer.exclude;
+            let report = ProjectReport::analyze_excluding(&path, true, exclude)?;
+            let surface = api::surface(&report.items);
+            let file = file.clone().unwrap_or_else(|| {
+                let dir = if path.is_file() {
+                    path.parent().unwrap_or(&path)
+                } else {
+                    &path
+                };
+                dir.join(api::API_FILE)
+            });
+            if *write {
+                std::fs::write(&file, api::to_text(&surface))?;
+                writeln!(
+                    out,
+                    "Wrote {} public items to {}",
+                    surface.len(),
+                    file.display()
+                )?;
+                return Ok(());
+            }
+            if !*check {
+                return write_output(&api::to_text(&surface), None, out);
+            }
+            let saved = std::fs::read_to_string(&file).map_err(|e| {
+                OracleError::Other(format!(
+                    "{}: {} (create it with `oracle api --write`)",
+                    file.display(),
+                    e
+                ))
+            })?;
+            let (removed, added) = api::diff(&saved, &surface);
+            for line in &removed {
+                writeln!(out, "- {}", line)?;
+            }
+            for line in &added {
+                writeln!(out, "+ {}", line)?;
+            }
+            if removed.is_empty() && added.is_empty() {
+                writeln!(out, "Public API matches {}", file.display())?;
+                return Ok(());
+            }
+            Err(OracleError::Other(format!(
+                "public API changed: {} removed, {} added; run `oracle api --write` to accept",
+                removed.len(),
+                added.len()
+            )))
+        }
+        Command::Tags {
+            path,
+            format,
+            output,
+        } => {
+            let path = canonical(path);
+            let exclude = settings(&path, global)?.analyzer.exclude;
+            let report = ProjectReport::analyze_excluding(&path, true, exclude)?;
+            let dir = if path.is_file() {
+                path.parent().unwrap_or(&path)
+            } else {
+                &path
+            };
+            let file = output.clone().unwrap_or_else(|| {
+                dir.join(match format {
+                    TagsFormat::Ctags => tags::CTAGS_FILE,
+                    TagsFormat::Etags => tags::ETAGS_FILE,
+                })
+            });
+            // Paths are written relative to the directory of the tags file
+            let stdout = file == Path::new("-");
+            let base = match file.parent().filter(|_| !stdout) {
+                Some(parent) => std::fs::canonicalize(if parent.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    parent
+                })?,
+                None => dir.to_path_buf(),
+            };
+            let tags = tags::tags(&report.items);
+            let text = match format {
+                TagsFormat::Ctags => tags::to_ctags(&tags, &base),
+                TagsFormat::Etags => tags::to_etags(&tags, &base),
+            };
+            if stdout {
+                return write_output(&text, None, out);
+            }
+            std::fs::write(&file, text)?;
+            writeln!(out, "Wrote {} tags to {}", tags.len(), file.display())?;
+            Ok(())
+        }
+        #[cfg(feature = "sqlite")]
+        Command::Index {
+            path,
+            search,
+            item,
+            limit,
+        } => {
+            let path = canonical(path);
+            let settings = settings(&path, global)?;
+            let mut index = crate::index::ItemIndex::open(
+                &path,
+                settings.analyzer.include_private,
+                settings.analyzer.exclude,
+            )?;
+            let update = index.update()?;
+            for s in &update.skipped {
+                tracing::warn!(path = %s.path.display(), reason = %s.reason, "not indexed");
+            }
+            let found = match (search, item) {
+                (Some(text), _) => index.search(text, *limit)?,
+                (None, Some(name)) => index.find(name)?,
+                (None, None) => {
+                    writeln!(
+                        out,
+                        "Indexed {} items: {} files parsed, {} unchanged, {} removed",
+                        index.len()?,
+                        update.parsed,
+                        update.unchanged,
+                        update.removed
+                    )?;
+                    return Ok(());
+                }
+            };
+            let items = found
+                .iter()
+                .map(|found| index.load(found))
+                .collect::<Result<Vec<_>>>()?;
+            inspect(&items, item.as_deref(), None, None, out)
+        }
+        Command::New {
+            path,
+            name,
+            lib,
+            edition,
+            license,
+            deps,
+            interactive,
+        } => {
+            let mut new = NewCrate {
+                name: name.clone().unwrap_or_default(),
+                lib: *lib,
+                edition: edition.clone(),
+                license: license.clone(),
+                dependencies: deps.clone(),
+            };
+            if new.name.is_empty() {
+                new.name = NewCrate::for_dir(path).name;
+            }
+            let offline = settings(path, global)?.network.offline;
+            if *interactive {
+                let search = |query: &str| {
+                    (!offline)
+                        .then(|| crate::crates_io::search_crates(query, 8))
+                        .flatten()
+                        .unwrap_or_default()
+                };
+                let features = |name: &str, version: &str| {
+                    (!offline)
+                        .then(|| crate::crates_io::fetch_crate_features(name, version))
+                        .flatten()
+                        .unwrap_or_default()
+                };
+                let mut input = std::io::stdin().lock();
+                new = scaffold::wizard(new, &mut input, out, &search, &features)?;
+            }
+            resolve_versions(&mut new.dependencies, offline)?;
+            for file in new.create(path)? {
+                writeln!(out, "     Created {}", file.display())?;
+            }
+            Ok(())
+        }
+        Command::Completions { shell } => {
+            // Buffered: the generator panics on write errors such as a closed pipe
+            let mut script = Vec::new();
+            clap_complete::generate(*shell, &mut Cli::command(), "oracle", &mut script);
+            out.write_all(&script)?;
+            Ok(())
+        }
+        Command::Man { out_dir } => {
+            match out_dir {
+                Some(dir) => {
+                    std::fs::create_dir_all(dir)?;
+                    clap_mangen::generate_to(Cli::command(), dir)?;
+                }
+                None => clap_mangen::Man::new(Cli::command()).render(out)?,
+            }
+            Ok(())
+        }
+    }
+}
+
+/// `oracle --serve`: analyze `path`, then answer queries on `socket` or on
+/// stdin/stdout until the input ends
+pub fn serve(path: &Path, socket: Option<&Path>, global: &GlobalArgs) -> Result<()> {
+    let settings = settings(path, global)?;
+    let server = Server::new(
+        path,
+        settings.analyzer.include_private,
+        settings.analyzer.exclude,
+    )?;
+    match socket {
+        None => server.serve(io::stdin().lock(), io::stdout().lock()),
+        #[cfg(unix)]
+        Some(socket) => server.serve_socket(socket),
+        #[cfg(not(unix))]
+        Some(_) => Err(OracleError::Config(
+            "--socket needs a platform with Unix sockets".to_string(),
+        )),
+    }
+}
+
+/// The config file's settings with the `.oracle.yaml` of `root` merged over them,
+/// as the TUI reads them for that project, and the global flags on top
+fn settings(root: &Path, global: &GlobalArgs) -> Result<Settings> {
+    let mut settings = Settings::load()?.with_project(root)?;
+    settings.analyzer.include_private |= global.include_private;
+    settings.network.offline |= global.offline;
+    Ok(settings)
+}
+
+/// Analyze `path` with the private items and excludes of `settings`; never
+/// touches the network
+fn analyze(path: &Path, settings: &Settings) -> Result<ProjectReport> {
+    ProjectReport::analyze_excluding(
+        path,
+        settings.analyzer.include_private,
+        settings.analyzer.exclude.clone(),
+    )
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Fill in the newest crates.io release for dependencies given without a version
+fn resolve_versions(deps: &mut [NewDependency], offline: bool) -> Result<()> {
+    for dep in deps.iter_mut().filter(|d| d.version.is_none()) {
+        if offline {
+            return Err(OracleError::Config(format!(
+                "{} needs a version (`{}@1`) with --offline",
+                dep.name, dep.name
+            )));
+        }
+        let info = crate::crates_io::fetch_crate_docs(&dep.name).ok_or_else(|| {
+            OracleError::Other(format!("{} was not found on crates.io", dep.name))
+        })?;
+        dep.version = Some(info.version);
+    }
+    Ok(())
+}
+
+fn write_output(text: &str, output: Option<&Path>, out: &mut dyn Write) -> Result<()> {
+    match output {
+        Some(file) => File::create(file)?.write_all(text.as_bytes())?,
+        None => out.write_all(text.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn matches_item(item: &AnalyzedItem, query: &str) -> bool {
+    item.name() == query || item.qualified_name().ends_with(&format!("::{}", query))
+}
+
+fn location(item: &AnalyzedItem) -> String {
+    item.source_location()
+        .and_then(|loc| Some(format!("{}:{}", loc.file.as_ref()?.display(), loc.line?)))
+        .unwrap_or_default()
+}
+
+/// Items are printed as they come, so `items` can be a stream
+fn inspect<I: Borrow<AnalyzedItem>>(
+    items: impl IntoIterator<Item = I>,
+    query: Option<&str>,
+    kind: Option<&str>,
+    search: Option<&Query>,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let selected = items.into_iter().filter(|i| {
+        let i = i.borrow();
+        kind.map_or(true, |k| i.kind() == k)
+            && search.map_or(true, |q| q.matches(i))
+            && query.map_or(true, |q| matches_item(i, q))
+    });
+    let Some(query) = query else {
+        for item in selected {
+            let item = item.borrow();
+            writeln!(
+                out,
+                "{:<7} {:<50} {}",
+                item.kind(),
+                item.qualified_name(),
+                location(item)
+            )?;
+        }
+        return Ok(());
+    };
+    let mut found = 0;
+    for item in selected {
+        let item = item.borrow();
+        if found > 0 {
+            writeln!(out)?;
+        }
+        found += 1;
+        writeln!(
+            out,
+            "{} {}  {}",
+            item.kind(),
+            item.qualified_name(),
+            location(item)
+        )?;
+        writeln!(out, "\n{}", item.definition())?;
+        if let Some(docs) = item.documentation() {
+            writeln!(out, "\n{}", docs.trim_end())?;
+        }
+    }
+    if found == 0 {
+        return Err(OracleError::Other(format!("No item named {}", query)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+        let cli = Cli::parse_from(["oracle", "a", "b", "--theme", "nord", "--no-mouse"]);
+        assert_eq!(cli.path.as_deref(), Some(Path::new("a")));
+        assert_eq!(cli.compare.as_deref(), Some(Path::new("b")));
+        assert_eq!(cli.theme.as_deref(), Some("nord"));
+        assert!(cli.no_mouse && cli.command.is_none());
+
+        let cli = Cli::parse_from(["oracle", "export", "--offline", "-f", "markdown"]);
+        assert!(cli.global.offline);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Export {
+                format: ExportFormat::Markdown,
+                ..
+            })
+        ));
+        assert!(Cli::try_parse_from(["oracle", "--theme", "solarized"]).is_err());
+
+        let cli = Cli::parse_from(["oracle", "audit", "--format", "sarif"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::Audit {
+                format: AuditFormat::Sarif,
+                ..
+            })
+        ));
+
+        let cli = Cli::parse_from(["oracle", "new", "demo", "--lib", "-d", "serde@1:derive"]);
+        let Some(Command::New { lib, deps, .. }) = cli.command else {
+            panic!("expected new");
+        };
+        assert!(lib);
+        assert_eq!(deps[0].features, vec!["derive"]);
+        assert!(Cli::try_parse_from(["oracle", "new", "demo", "--edition", "2030"]).is_err());
+        assert!(Cli::try_parse_from(["oracle", "new", "demo", "-d", "1bad"]).is_err());
+    }
+
+    #[test]
+    fn test_flags_stay_out_of_the_settings() {
+        let cli = Cli::parse_from(["oracle", "--include-private", "--offline"]);
+        let mut app = App::new();
+        app.settings.analyzer.include_private = false;
+        cli.apply(&mut app);
+        assert!(app.include_private() && app.offline);
+        assert!(!app.settings.analyzer.include_private);
+        assert!(!app.settings.network.offline);
+    }
+
+    #[test]
+    fn test_completions_and_man() {
+        let global = GlobalArgs {
+            offline: false,
+            include_private: false,
+            verbose: false,
+        };
+        let mut out = Vec::new();
+        let fish = Command::Completions { shell: Shell::Fish };
+        run(&fish, &global, &mut out).unwrap();
+        let script = String::from_utf8(out).unwrap();
+        assert!(script.contains("complete -c oracle"));
+        assert!(script.contains("inspect"));
+
+        let mut out = Vec::new();
+        run(&Command::Man { out_dir: None }, &global, &mut out).unwrap();
+        let page = String::from_utf8(out).unwrap();
+        assert!(page.starts_with(".ie"));
+        assert!(page.contains(".TH oracle 1"));
+    }
+
+    #[test]
+    fn test_inspect() {
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                "/// A point\npub struct Point { x: i32 }\npub fn origin() -> Point { todo!() }",
+            )
+            .unwrap();
+        let mut out = Vec::new();
+        inspect(&items, Some("Point"), None, None, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("struct Point"));
+        assert!(text.contains("A point"));
+
+        let mut out = Vec::new();
+        inspect(&items, None, Some("fn"), None, &mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("fn      origin"));
+        assert!(inspect(&items, Some("Nope"), None, None, &mut Vec::new()).is_err());
+
+        let mut out = Vec::new();
+        let query = Query::parse("kind:struct doc:true");
+        inspect(&items, None, None, Some(&query), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.starts_with("struct  Point"));
+    }
+
+    #[test]
+    fn test_subcommands_read_project_excludes() {
+        let dir = std::env::temp_dir().join(format!("oracle-cli-exclude-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("generated")).unwrap();
+        std::fs::write(dir.join(".oracle.yaml"), "exclude: [generated]\n").unwrap();
+        std::fs::write(dir.join("kept.rs"), "pub struct Kept;\n").unwrap();
+        std::fs::write(dir.join("generated/bindings.rs"), "pub struct Skipped;\n").unwrap();
+        let global = GlobalArgs {
+            offline: true,
+            include_private: false,
+            verbose: false,
+        };
+        let commands = [
+            Command::Inspect {
+                path: dir.clone(),
+                item: None,
+                kind: None,
+                query: None,
+            },
+            Command::Export {
+                path: dir.clone(),
+                format: ExportFormat::Json,
+                output: None,
+            },
+        ];
+        for command in &commands {
+            let mut out = Vec::new();
+            run(command, &global, &mut out).unwrap();
+            let text = String::from_utf8(out).unwrap();
+            assert!(text.contains("Kept") && !text.contains("Skipped"));
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_doc_writes_module_tree() {
+        let global = GlobalArgs {
+            offline: true,
+            include_private: false,
+            verbose: false,
+        };
+        let dir = std::env::temp_dir().join(format!("oracle-doc-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src/analyzer")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "pub mod analyzer;\n").unwrap();
+        std::fs::write(
+            dir.join("src/analyzer/mod.rs"),
+            "pub mod parser;\n/// Runs it\npub fn run() {}\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("src/analyzer/parser.rs"), "pub fn parse() {}\n").unwrap();
+        let doc = |module: &str| Command::Doc {
+            path: dir.clone(),
+            module: Some(module.to_string()),
+            out: Some(dir.join("docs/analyzer.md")),
+        };
+        let mut out = Vec::new();
+        run(&doc("analyzer"), &global, &mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("Wrote 2 module pages"));
+        let top = std::fs::read_to_string(dir.join("docs/analyzer.md")).unwrap();
+        assert!(top.contains("Runs it") && top.contains("(analyzer/parser.md)"));
+        let parser = std::fs::read_to_string(dir.join("docs/analyzer/parser.md")).unwrap();
+        assert!(parser.contains("### `analyzer::parser::parse`"));
+        assert!(run(&doc("missing"), &global, &mut Vec::new()).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_api_write_and_check() {
+        let global = GlobalArgs {
+            offline: true,
+            include_private: false,
+            verbose: false,
+        };
+        let dir = std::env::temp_dir().join(format!("oracle-api-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "pub fn one() {}\n").unwrap();
+        let api = |write: bool, check: bool| Command::Api {
+            path: dir.clone(),
+            write,
+            check,
+            file: None,
+        };
+        let mut out = Vec::new();
+        assert!(run(&api(false, true), &global, &mut out).is_err());
+        run(&api(true, false), &global, &mut out).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("api-surface.txt")).unwrap(),
+            "fn one()\n"
+        );
+        run(&api(false, true), &global, &mut Vec::new()).unwrap();
+
+        std::fs::write(dir.join("src/lib.rs"), "pub fn two() {}\n").unwrap();
+        let mut out = Vec::new();
+        assert!(run(&api(false, true), &global, &mut out).is_err());
+        assert_eq!(String::from_utf8(out).unwrap(), "- fn one()\n+ fn two()\n");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
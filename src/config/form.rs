@@ -0,0 +1,298 @@
+//! Settings overlay form: the editable settings, how each is shown, and how an
+//! edit is validated and written back into [`Settings`]
+
+use super::Settings;
+use crate::ui::theme::ThemeKind;
+
+/// Rows of the settings overlay, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingField {
+    Theme,
+    Compat,
+    Animations,
+    MaxFps,
+    TerminalTitle,
+    IncludePrivate,
+    Exclude,
+    RustAnalyzer,
+    LightInstalled,
+    Offline,
+    Spelling,
+    SectionOrder,
+    HiddenSections,
+    DiffRef,
+    Keymap,
+}
+
+/// How a field is edited: flipped, cycled through fixed values, or typed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Toggle,
+    Choice,
+    Text,
+}
+
+impl SettingField {
+    pub const ALL: [SettingField; 15] = [
+        SettingField::Theme,
+        SettingField::Compat,
+        SettingField::Animations,
+        SettingField::MaxFps,
+        SettingField::TerminalTitle,
+        SettingField::IncludePrivate,
+        SettingField::Exclude,
+        SettingField::RustAnalyzer,
+        SettingField::LightInstalled,
+        SettingField::Offline,
+        SettingField::Spelling,
+        SettingField::SectionOrder,
+        SettingField::HiddenSections,
+        SettingField::DiffRef,
+        SettingField::Keymap,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Theme => "Theme",
+            Self::Compat => "16 colors, no emoji",
+            Self::Animations => "Animations",
+            Self::MaxFps => "Max FPS",
+            Self::TerminalTitle => "Terminal title",
+            Self::IncludePrivate => "Include private items",
+            Self::Exclude => "Exclude paths",
+            Self::RustAnalyzer => "rust-analyzer",
+            Self::LightInstalled => "Light installed crates",
+            Self::Offline => "Offline",
+            Self::Spelling => "Spell check docs",
+            Self::SectionOrder => "Inspector section order",
+            Self::HiddenSections => "Hidden inspector sections",
+            Self::DiffRef => "Diff against",
+            Self::Keymap => "Keymap",
+        }
+    }
+
+    /// One-line explanation shown under the form for the highlighted row
+    pub fn help(self) -> &'static str {
+        match self {
+            Self::Theme => "Color theme (t cycles it anywhere)",
+            Self::Compat => "16-color palette and ASCII glyphs for limited terminals",
+            Self::Animations => "Selection flash and transitions",
+            Self::MaxFps => "Redraw cap while animating, 1-240",
+            Self::TerminalTitle => "Crate and selected item in the window or tmux title",
+            Self::IncludePrivate => "List private items too; re-analyzes the project",
+            Self::Exclude => {
+                "Comma-separated paths under src/ or directory names to skip; re-analyzes"
+            }
+            Self::RustAnalyzer => "Resolve types and references with rust-analyzer (*)",
+            Self::LightInstalled => {
+                "Less memory for huge installed crates: no attributes or panic points"
+            }
+            Self::Offline => "No crates.io, GitHub or Copilot requests",
+            Self::Spelling => "Flag common misspellings in doc comments (W)",
+            Self::SectionOrder => {
+                "Comma-separated section titles shown first, e.g. Docs, Signature"
+            }
+            Self::HiddenSections => "Comma-separated section titles never shown, e.g. Source",
+            Self::DiffRef => "Git revision the item diff (d) compares with, e.g. HEAD or main",
+            Self::Keymap => "vim adds Ctrl+d/u and Ctrl+f/b to scroll lists and the inspector",
+        }
+    }
+
+    pub fn kind(self) -> FieldKind {
+        match self {
+            Self::Theme | Self::Keymap => FieldKind::Choice,
+            Self::MaxFps
+            | Self::Exclude
+            | Self::SectionOrder
+            | Self::HiddenSections
+            | Self::DiffRef => FieldKind::Text,
+            _ => FieldKind::Toggle,
+        }
+    }
+
+    /// Current value as shown (and as the starting text of an inline edit)
+    pub fn value(self, settings: &Settings) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        match self {
+            Self::Theme => ThemeKind::from_name(&settings.ui.theme)
+                .display_name()
+                .to_string(),
+            Self::Compat => on_off(settings.ui.compat),
+            Self::Animations => on_off(settings.ui.animations),
+            Self::MaxFps => settings.ui.max_fps.to_string(),
+            Self::TerminalTitle => on_off(settings.ui.terminal_title),
+            Self::IncludePrivate => on_off(settings.analyzer.include_private),
+            Self::Exclude => settings.analyzer.exclude.join(", "),
+            Self::RustAnalyzer => on_off(settings.analyzer.rust_analyzer),
+            Self::LightInstalled => on_off(settings.analyzer.light_installed),
+            Self::Offline => on_off(settings.network.offline),
+            Self::Spelling => on_off(settings.spelling.enabled),
+            Self::SectionOrder => settings.inspector.order.join(", "),
+            Self::HiddenSections => settings.inspector.hidden.join(", "),
+            Self::DiffRef => settings.inspector.diff_ref.clone(),
+            Self::Keymap if settings.ui.vim_mode => "vim".to_string(),
+            Self::Keymap => "default".to_string(),
+        }
+    }
+
+    /// Flip a toggle or move a choice to its next value; text fields are unchanged
+    pub fn advance(self, settings: &mut Settings) {
+        match self {
+            Self::Theme => {
+                let next = ThemeKind::from_name(&settings.ui.theme).next();
+                settings.ui.theme = next.name().to_string();
+            }
+            Self::Compat => settings.ui.compat = !settings.ui.compat,
+            Self::Animations => settings.ui.animations = !settings.ui.animations,
+            Self::TerminalTitle => settings.ui.terminal_title = !settings.ui.terminal_title,
+            Self::IncludePrivate => {
+                settings.analyzer.include_private = !settings.analyzer.include_private
+            }
+            Self::RustAnalyzer => {
+                settings.analyzer.rust_analyzer = !settings.analyzer.rust_analyzer
+            }
+            Self::LightInstalled => {
+                settings.analyzer.light_installed = !settings.analyzer.light_installed
+            }
+            Self::Offline => settings.network.offline = !settings.network.offline,
+            Self::Spelling => settings.spelling.enabled = !settings.spelling.enabled,
+            Self::Keymap => settings.ui.vim_mode = !settings.ui.vim_mode,
+            Self::MaxFps
+            | Self::Exclude
+            | Self::SectionOrder
+            | Self::HiddenSections
+            | Self::DiffRef => {}
+        }
+    }
+
+    /// Validate typed `input` and store it; the error explains what's accepted
+    pub fn set(self, settings: &mut Settings, input: &str) -> Result<(), String> {
+        match self {
+            Self::MaxFps => {
+                let fps = input
+                    .trim()
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|fps| (1..=240).contains(fps))
+                    .ok_or_else(|| format!("\"{}\" is not a number from 1 to 240", input))?;
+                settings.ui.max_fps = fps;
+            }
+            Self::Exclude => {
+                let paths: Vec<String> = input
+                    .split(',')
+                    .map(|p| p.trim().trim_matches('/').to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect();
+                if let Some(bad) = paths.iter().find(|p| p.contains("..")) {
+                    return Err(format!("\"{}\" leaves the project", bad));
+                }
+                settings.analyzer.exclude = paths;
+            }
+            Self::SectionOrder => settings.inspector.order = section_titles(input),
+            Self::HiddenSections => settings.inspector.hidden = section_titles(input),
+            Self::DiffRef => {
+                let rev = input.trim();
+                if rev.is_empty() || rev.starts_with('-') || rev.contains(char::is_whitespace) {
+                    return Err(format!("\"{}\" is not a git revision", input));
+                }
+                settings.inspector.diff_ref = rev.to_string();
+            }
+            _ => self.advance(settings),
+        }
+        Ok(())
+    }
+
+    /// Overridden by the project's `.oracle.yaml`, so edits here wouldn't apply
+    pub fn set_by_project(self, settings: &Settings) -> bool {
+        settings.project_config().is_some_and(|project| match self {
+            Self::IncludePrivate => project.include_private.is_some(),
+            Self::Exclude => project.exclude.is_some(),
+            _ => false,
+        })
+    }
+
+    /// Changing this field means the project has to be analyzed again
+    pub fn needs_reanalysis(self) -> bool {
+        matches!(self, Self::IncludePrivate | Self::Exclude)
+    }
+}
+
+fn section_titles(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// State of the settings overlay: highlighted row, the text of an inline edit in
+/// progress, and why the last edit was rejected
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingsForm {
+    pub selected: usize,
+    pub editing: Option<String>,
+    pub error: Option<String>,
+}
+
+impl SettingsForm {
+    pub fn field(&self) -> SettingField {
+        SettingField::ALL[self.selected % SettingField::ALL.len()]
+    }
+
+    /// Move the highlight by `delta` rows, wrapping around
+    pub fn move_by(&mut self, delta: isize) {
+        let len = SettingField::ALL.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+        self.error = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_edit_settings() {
+        let mut settings = Settings::default();
+        SettingField::Theme.advance(&mut settings);
+        assert_eq!(SettingField::Theme.value(&settings), "Nord");
+        SettingField::Keymap.advance(&mut settings);
+        assert_eq!(SettingField::Keymap.value(&settings), "vim");
+
+        assert!(SettingField::MaxFps.set(&mut settings, "0").is_err());
+        SettingField::MaxFps.set(&mut settings, " 30 ").unwrap();
+        assert_eq!(settings.ui.max_fps, 30);
+
+        SettingField::Exclude
+            .set(&mut settings, "generated/, tests/fixtures,,")
+            .unwrap();
+        assert_eq!(
+            settings.analyzer.exclude,
+            vec!["generated", "tests/fixtures"]
+        );
+        assert!(SettingField::Exclude.set(&mut settings, "../x").is_err());
+        assert_eq!(
+            SettingField::Exclude.value(&settings),
+            "generated, tests/fixtures"
+        );
+
+        SettingField::HiddenSections
+            .set(&mut settings, " Source,, used by ")
+            .unwrap();
+        assert_eq!(settings.inspector.hidden, vec!["Source", "used by"]);
+
+        assert_eq!(SettingField::DiffRef.value(&settings), "HEAD");
+        assert!(SettingField::DiffRef.set(&mut settings, "--all").is_err());
+        SettingField::DiffRef.set(&mut settings, " main ").unwrap();
+        assert_eq!(settings.inspector.diff_ref, "main");
+    }
+
+    #[test]
+    fn test_form_selection_wraps() {
+        let mut form = SettingsForm::default();
+        form.move_by(-1);
+        assert_eq!(form.field(), SettingField::Keymap);
+        form.move_by(1);
+        assert_eq!(form.field(), SettingField::Theme);
+    }
+}
@@ -1,5 +1,17 @@
 //! Configuration system for Oracle
 
+mod form;
+mod project;
+mod review;
+mod session;
 mod settings;
+mod share;
+mod views;
 
+pub use form::*;
+pub use project::*;
+pub use review::*;
+pub use session::*;
 pub use settings::*;
+pub use share::*;
+pub use views::*;
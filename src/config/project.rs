@@ -0,0 +1,166 @@
+//! Per-project overrides from `.oracle.yaml` in the project root
+//!
+//! The file is meant to be committed, so everyone working on the repository sees
+//! the same excludes, item visibility, starting tab and saved views. It is merged
+//! over the user's [`Settings`] when a project is opened; saving settings from the
+//! TUI still writes only the user's own values.
+
+use super::{SavedView, Settings};
+use crate::error::{OracleError, Result};
+use crate::ui::Tab;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Settings a project can override; unset ones keep the user's value
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub include_private: Option<bool>,
+    #[serde(default)]
+    pub exclude: Option<Vec<String>>,
+    #[serde(default)]
+    pub default_tab: Option<Tab>,
+    /// Offered before the user's views; one with the same name replaces theirs
+    #[serde(default)]
+    pub views: Vec<SavedView>,
+}
+
+/// A merged `.oracle.yaml`, with the user's values it replaced so they can be
+/// restored when saving
+#[derive(Debug, Clone)]
+pub struct ProjectLayer {
+    /// Project root the file was read from
+    pub root: PathBuf,
+    pub config: ProjectConfig,
+    replaced: ProjectConfig,
+}
+
+impl ProjectConfig {
+    pub const FILE_NAME: &'static str = ".oracle.yaml";
+
+    /// Read `.oracle.yaml` from `root`; `None` when there is none
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(Self::FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content)
+            .map(Some)
+            .map_err(|e| OracleError::Config(format!("{}: {}", path.display(), e)))
+    }
+}
+
+impl Settings {
+    /// These settings with the `.oracle.yaml` of `root` merged over them; any
+    /// earlier project's overrides are dropped first
+    pub fn with_project(self, root: &Path) -> Result<Self> {
+        let mut settings = self.user();
+        let Some(config) = ProjectConfig::load(root)? else {
+            return Ok(settings);
+        };
+        let mut replaced = ProjectConfig::default();
+        if let Some(include) = config.include_private {
+            replaced.include_private = Some(settings.analyzer.include_private);
+            settings.analyzer.include_private = include;
+        }
+        if let Some(ref exclude) = config.exclude {
+            replaced.exclude = Some(std::mem::replace(
+                &mut settings.analyzer.exclude,
+                exclude.clone(),
+            ));
+        }
+        if let Some(tab) = config.default_tab {
+            replaced.default_tab = Some(settings.ui.default_tab);
+            settings.ui.default_tab = tab;
+        }
+        let (shadowed, kept): (Vec<SavedView>, Vec<SavedView>) =
+            std::mem::take(&mut settings.views)
+                .into_iter()
+                .partition(|v| config.views.iter().any(|p| p.name == v.name));
+        replaced.views = shadowed;
+        settings.views = config.views.iter().cloned().chain(kept).collect();
+        settings.project = Some(ProjectLayer {
+            root: root.to_path_buf(),
+            config,
+            replaced,
+        });
+        Ok(settings)
+    }
+
+    /// The user's own settings, without the project's overrides; what [`Settings::save`] writes
+    pub fn user(&self) -> Self {
+        let mut settings = self.clone();
+        let Some(layer) = settings.project.take() else {
+            return settings;
+        };
+        let replaced = layer.replaced;
+        if let Some(include) = replaced.include_private {
+            settings.analyzer.include_private = include;
+        }
+        if let Some(exclude) = replaced.exclude {
+            settings.analyzer.exclude = exclude;
+        }
+        if let Some(tab) = replaced.default_tab {
+            settings.ui.default_tab = tab;
+        }
+        settings
+            .views
+            .retain(|v| !layer.config.views.iter().any(|p| p.name == v.name));
+        settings.views.extend(replaced.views);
+        settings
+    }
+
+    /// Overrides merged from the current project's `.oracle.yaml`
+    pub fn project_config(&self) -> Option<&ProjectConfig> {
+        self.project.as_ref().map(|layer| &layer.config)
+    }
+
+    /// Whether `.oracle.yaml` of `root` is the one merged in
+    pub fn has_project(&self, root: &Path) -> bool {
+        self.project
+            .as_ref()
+            .is_some_and(|layer| layer.root == root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_overrides_merge_and_unmerge() {
+        let dir = std::env::temp_dir().join(format!("oracle-project-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(ProjectConfig::FILE_NAME),
+            "include_private: false\nexclude: [generated]\ndefault_tab: functions\n\
+             views:\n  - name: Public API\n    query: pub\n",
+        )
+        .unwrap();
+
+        let user = Settings::default();
+        let merged = user.clone().with_project(&dir).unwrap();
+        assert!(!merged.analyzer.include_private);
+        assert_eq!(merged.analyzer.exclude, vec!["generated"]);
+        assert_eq!(merged.ui.default_tab, Tab::Functions);
+        assert_eq!(merged.views[0].query, "pub");
+        assert_eq!(merged.views.len(), user.views.len());
+
+        let restored = merged.user();
+        assert!(restored.analyzer.include_private);
+        assert!(restored.analyzer.exclude.is_empty());
+        assert_eq!(restored.ui.default_tab, Tab::Types);
+        let public = restored
+            .views
+            .iter()
+            .find(|v| v.name == "Public API")
+            .unwrap();
+        assert_eq!(public, &user.views[0]);
+
+        std::fs::write(dir.join(ProjectConfig::FILE_NAME), "colour: blue\n").unwrap();
+        assert!(Settings::default().with_project(&dir).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
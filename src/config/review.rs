@@ -0,0 +1,134 @@
+//! Review notes on items, kept in `.oracle-review.yaml` in the project root
+//!
+//! Notes are keyed by the item's kind and qualified name, so they stay with the
+//! item when it moves within its file. The file can be committed with a branch or
+//! exported as one Markdown comment for the pull request.
+
+use crate::error::{OracleError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewNote {
+    /// Qualified name, e.g. `config::Settings::load`
+    pub item: String,
+    /// `fn`, `struct`, ...; same-named items of different kinds keep apart
+    pub kind: String,
+    pub note: String,
+    /// `path:line` relative to the project root when the note was written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewNotes {
+    #[serde(default)]
+    pub notes: Vec<ReviewNote>,
+}
+
+impl ReviewNotes {
+    pub const FILE_NAME: &'static str = ".oracle-review.yaml";
+
+    /// Notes in `.oracle-review.yaml` of `root`; none when there is no file
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(Self::FILE_NAME);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| OracleError::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = root.join(Self::FILE_NAME);
+        if self.notes.is_empty() && !path.exists() {
+            return Ok(());
+        }
+        let content = serde_yaml::to_string(self)
+            .map_err(|e| OracleError::Config(format!("Failed to serialize notes: {}", e)))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, kind: &str, item: &str) -> Option<&ReviewNote> {
+        self.notes.iter().find(|n| n.kind == kind && n.item == item)
+    }
+
+    /// Add `note`, replacing the item's earlier one; a blank note removes it
+    pub fn set(&mut self, note: ReviewNote) {
+        let existing = self
+            .notes
+            .iter()
+            .position(|n| n.kind == note.kind && n.item == note.item);
+        match (existing, note.note.trim().is_empty()) {
+            (Some(i), true) => {
+                self.notes.remove(i);
+            }
+            (Some(i), false) => self.notes[i] = note,
+            (None, false) => self.notes.push(note),
+            (None, true) => {}
+        }
+    }
+
+    /// The notes as one review comment, in the order they were written
+    pub fn markdown(&self) -> String {
+        let count = match self.notes.len() {
+            1 => "1 note".to_string(),
+            n => format!("{} notes", n),
+        };
+        let mut out = format!("## API review\n\n{}\n", count);
+        for note in &self.notes {
+            out.push_str(&format!("\n### `{} {}`", note.kind, note.item));
+            if let Some(ref location) = note.location {
+                out.push_str(&format!(" — `{}`", location));
+            }
+            out.push_str("\n\n");
+            for line in note.note.lines() {
+                out.push_str(&format!("> {}\n", line));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(item: &str, text: &str) -> ReviewNote {
+        ReviewNote {
+            item: item.to_string(),
+            kind: "fn".to_string(),
+            note: text.to_string(),
+            location: Some("src/lib.rs:3".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_notes_set_save_and_export() {
+        let dir = std::env::temp_dir().join(format!("oracle-review-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(ReviewNotes::load(&dir).unwrap(), ReviewNotes::default());
+
+        let mut notes = ReviewNotes::default();
+        notes.set(note("parse", "Should this return a Result?"));
+        notes.set(note("render", "Rename to draw"));
+        notes.set(note("parse", "Should return Result<Config>"));
+        notes.set(note("render", "  "));
+        assert_eq!(notes.notes.len(), 1);
+        assert_eq!(
+            notes.get("fn", "parse").unwrap().note,
+            "Should return Result<Config>"
+        );
+        assert!(notes.get("struct", "parse").is_none());
+
+        notes.save(&dir).unwrap();
+        assert_eq!(ReviewNotes::load(&dir).unwrap(), notes);
+        assert_eq!(
+            notes.markdown(),
+            "## API review\n\n1 note\n\n### `fn parse` — `src/lib.rs:3`\n\n> Should return Result<Config>\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
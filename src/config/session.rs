@@ -0,0 +1,215 @@
+//! State remembered between runs, per project
+//!
+//! Unlike [`Settings`](super::Settings), this is written by oracle itself: JSON in
+//! the state directory (`~/.local/state/oracle/session.json`), keyed by project path.
+
+use crate::error::{OracleError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Queries kept per project
+pub const SEARCH_HISTORY_LEN: usize = 50;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(default)]
+    pub projects: BTreeMap<PathBuf, ProjectSession>,
+    /// Inspector sections collapsed by the user, by item kind (`fn`, `struct`, ...)
+    #[serde(default)]
+    pub collapsed_sections: BTreeMap<String, BTreeSet<String>>,
+    /// Where [`Session::load`] read from; a default session is never saved, so tests
+    /// and the headless driver leave the user's state alone
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectSession {
+    /// Oldest first
+    #[serde(default)]
+    pub search_history: Vec<String>,
+    /// Crate (or directory) name when the project was last opened
+    #[serde(default)]
+    pub name: String,
+    /// Unix seconds; `None` for projects not offered as recent
+    #[serde(default)]
+    pub last_opened: Option<u64>,
+}
+
+/// A project offered by the recent projects picker (`O`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentProject {
+    pub path: PathBuf,
+    pub name: String,
+    pub last_opened: u64,
+}
+
+impl ProjectSession {
+    /// Remember a submitted query; repeating one moves it to the end
+    pub fn record_search(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.search_history.retain(|q| q != query);
+        self.search_history.push(query.to_string());
+        let excess = self.search_history.len().saturating_sub(SEARCH_HISTORY_LEN);
+        self.search_history.drain(..excess);
+    }
+}
+
+impl Session {
+    /// Read the session file; a missing or unreadable one starts empty
+    pub fn load() -> Self {
+        let Some(path) = Self::session_path() else {
+            return Self::default();
+        };
+        let mut session: Session = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        session.path = Some(path);
+        session
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| OracleError::Config(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn project(&self, root: &Path) -> Option<&ProjectSession> {
+        self.projects.get(root)
+    }
+
+    pub fn project_mut(&mut self, root: &Path) -> &mut ProjectSession {
+        self.projects.entry(root.to_path_buf()).or_default()
+    }
+
+    /// Note that `root` was opened at `now`, under the name shown in the picker
+    pub fn record_open(&mut self, root: &Path, name: &str, now: u64) {
+        let project = self.project_mut(root);
+        project.name = name.to_string();
+        project.last_opened = Some(now);
+    }
+
+    /// Take `root` off the recent projects; its search history stays
+    pub fn forget(&mut self, root: &Path) {
+        if let Some(project) = self.projects.get_mut(root) {
+            project.last_opened = None;
+        }
+    }
+
+    /// Opened projects, most recent first
+    pub fn recent(&self) -> Vec<RecentProject> {
+        let mut recent: Vec<RecentProject> = self
+            .projects
+            .iter()
+            .filter_map(|(path, project)| {
+                Some(RecentProject {
+                    path: path.clone(),
+                    name: project.name.clone(),
+                    last_opened: project.last_opened?,
+                })
+            })
+            .collect();
+        recent.sort_by_key(|p| std::cmp::Reverse(p.last_opened));
+        recent
+    }
+
+    /// Collapse `section` for items of `kind`, or expand it if it was; true when
+    /// it is now collapsed
+    pub fn toggle_section(&mut self, kind: &str, section: &str) -> bool {
+        let collapsed = self.collapsed_sections.entry(kind.to_string()).or_default();
+        let now_collapsed = collapsed.insert(section.to_string());
+        if !now_collapsed {
+            collapsed.remove(section);
+            if collapsed.is_empty() {
+                self.collapsed_sections.remove(kind);
+            }
+        }
+        now_collapsed
+    }
+
+    /// Expand every section of items of `kind`; false if none was collapsed
+    pub fn expand_sections(&mut self, kind: &str) -> bool {
+        self.collapsed_sections.remove(kind).is_some()
+    }
+
+    fn session_path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("oracle").join("session.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_search() {
+        let mut project = ProjectSession::default();
+        project.record_search("  App ");
+        project.record_search("");
+        project.record_search("kind:fn");
+        project.record_search("App");
+        assert_eq!(project.search_history, vec!["kind:fn", "App"]);
+        for i in 0..SEARCH_HISTORY_LEN {
+            project.record_search(&format!("q{}", i));
+        }
+        assert_eq!(project.search_history.len(), SEARCH_HISTORY_LEN);
+        assert_eq!(project.search_history[0], "q0");
+
+        let mut session = Session::default();
+        session.project_mut(Path::new("/p")).record_search("x");
+        let json = serde_json::to_string(&session).unwrap();
+        let loaded: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            loaded.project(Path::new("/p")).unwrap().search_history,
+            vec!["x"]
+        );
+    }
+
+    #[test]
+    fn test_recent_projects() {
+        let mut session = Session::default();
+        session
+            .project_mut(Path::new("/searched"))
+            .record_search("x");
+        session.record_open(Path::new("/a"), "a", 10);
+        session.record_open(Path::new("/b"), "b", 20);
+        let names: Vec<String> = session.recent().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["b", "a"]);
+
+        session.forget(Path::new("/b"));
+        assert_eq!(session.recent().len(), 1);
+        assert!(session.project(Path::new("/b")).is_some());
+    }
+
+    #[test]
+    fn test_collapsed_sections_per_kind() {
+        let mut session = Session::default();
+        assert!(session.toggle_section("fn", "Source"));
+        assert!(session.toggle_section("fn", "Attributes"));
+        assert!(session.toggle_section("struct", "Fields"));
+        assert!(!session.toggle_section("fn", "Source"));
+        assert_eq!(session.collapsed_sections["fn"].len(), 1);
+
+        let json = serde_json::to_string(&session).unwrap();
+        let mut loaded: Session = serde_json::from_str(&json).unwrap();
+        assert!(loaded.collapsed_sections["struct"].contains("Fields"));
+        assert!(loaded.expand_sections("fn"));
+        assert!(!loaded.expand_sections("fn"));
+        assert!(!loaded.toggle_section("struct", "Fields"));
+        assert!(loaded.collapsed_sections.is_empty());
+    }
+}
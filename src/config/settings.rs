@@ -1,6 +1,10 @@
 //! Application settings and configuration
 
+use super::project::ProjectLayer;
+use super::views::{default_views, SavedView};
 use crate::error::Result;
+use crate::ui::animation::{Easing, DEFAULT_MAX_FPS};
+use crate::ui::Tab;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -9,6 +13,18 @@ pub struct Settings {
     pub ui: UiSettings,
     pub analyzer: AnalyzerSettings,
     pub keybindings: KeybindingSettings,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub spelling: SpellingSettings,
+    #[serde(default)]
+    pub inspector: InspectorSettings,
+    /// Saved filters offered in the view picker (`V`)
+    #[serde(default = "default_views")]
+    pub views: Vec<SavedView>,
+    /// Overrides merged from the open project's `.oracle.yaml`; never saved
+    #[serde(skip)]
+    pub project: Option<ProjectLayer>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +35,39 @@ pub struct UiSettings {
     pub tab_width: usize,
     pub wrap_text: bool,
     pub accent_color: String,
+    /// 16-color palette and ASCII glyphs instead of RGB and emoji; always on
+    /// in the Linux console
+    #[serde(default)]
+    pub compat: bool,
+    /// Selection flash and transitions; off means no motion and no redraws
+    /// beyond input and background work
+    #[serde(default = "default_animations")]
+    pub animations: bool,
+    /// `linear`, `ease_in`, `ease_out`, `ease_in_out` or `bounce`
+    #[serde(default)]
+    pub easing: Easing,
+    /// Redraw cap while something is animating
+    #[serde(default = "default_max_fps")]
+    pub max_fps: u32,
+    /// Tab shown when a project is opened
+    #[serde(default)]
+    pub default_tab: Tab,
+    /// Set the terminal title to the crate and selected item, and report the project
+    /// directory (OSC 7) for new tabs and panes
+    #[serde(default = "default_terminal_title")]
+    pub terminal_title: bool,
+}
+
+fn default_animations() -> bool {
+    true
+}
+
+fn default_max_fps() -> u32 {
+    DEFAULT_MAX_FPS
+}
+
+fn default_terminal_title() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +75,61 @@ pub struct AnalyzerSettings {
     pub include_private: bool,
     pub include_tests: bool,
     pub max_depth: usize,
+    /// Paths under `src/` (or file and directory names) left out of the analysis
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Start rust-analyzer with the project for resolved types, definitions and
+    /// references (`*`)
+    #[serde(default)]
+    pub rust_analyzer: bool,
+    /// Analyze installed crates without attributes and panic points, for crates
+    /// too big to browse in full
+    #[serde(default)]
+    pub light_installed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    /// Disable all crates.io, GitHub and Copilot requests
+    pub offline: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpellingSettings {
+    /// Spell check doc comments after analysis (`W` lists the misspellings)
+    pub enabled: bool,
+    /// Words never flagged, e.g. names and jargon; `a` in the spelling view adds one
+    #[serde(default)]
+    pub dictionary: Vec<String>,
+}
+
+/// Inspector sections by title without the count, e.g. `Signature`, `Parameters`,
+/// `Documentation`, `Used By`; matched ignoring case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectorSettings {
+    /// Shown first, in this order; the others follow in their usual order
+    #[serde(default)]
+    pub order: Vec<String>,
+    /// Never shown
+    #[serde(default)]
+    pub hidden: Vec<String>,
+    /// Git revision the item diff (`d`) compares the working tree with
+    #[serde(default = "default_diff_ref")]
+    pub diff_ref: String,
+}
+
+impl Default for InspectorSettings {
+    fn default() -> Self {
+        Self {
+            order: Vec::new(),
+            hidden: Vec::new(),
+            diff_ref: default_diff_ref(),
+        }
+    }
+}
+
+fn default_diff_ref() -> String {
+    "HEAD".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,11 +152,20 @@ impl Default for Settings {
                 tab_width: 4,
                 wrap_text: false,
                 accent_color: "#4EBF71".into(),
+                compat: false,
+                animations: true,
+                easing: Easing::default(),
+                max_fps: DEFAULT_MAX_FPS,
+                default_tab: Tab::default(),
+                terminal_title: true,
             },
             analyzer: AnalyzerSettings {
                 include_private: true,
                 include_tests: false,
                 max_depth: 10,
+                exclude: Vec::new(),
+                rust_analyzer: false,
+                light_installed: false,
             },
             keybindings: KeybindingSettings {
                 quit: "q".into(),
@@ -62,6 +175,11 @@ impl Default for Settings {
                 prev_tab: "Shift+Tab".into(),
                 select: "Enter".into(),
             },
+            network: NetworkSettings::default(),
+            spelling: SpellingSettings::default(),
+            inspector: InspectorSettings::default(),
+            views: default_views(),
+            project: None,
         }
     }
 }
@@ -79,6 +197,7 @@ impl Settings {
         Ok(settings)
     }
 
+    /// Write the user's settings; overrides from `.oracle.yaml` are left out
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_path()?;
 
@@ -86,12 +205,18 @@ impl Settings {
             std::fs::create_dir_all(parent)?;
         }
 
-        let content = serde_yaml::to_string(self)?;
+        let content = serde_yaml::to_string(&self.user())?;
         std::fs::write(&config_path, content)?;
         Ok(())
     }
 
-    fn config_path() -> Result<PathBuf> {
+    /// Whether a config file has been written yet; there is none on the first run
+    pub fn exists() -> bool {
+        Self::config_path().is_ok_and(|path| path.exists())
+    }
+
+    /// `config.yaml` under the platform config directory
+    pub fn config_path() -> Result<PathBuf> {
         let config_dir = dirs::config_dir()
             .ok_or_else(|| crate::error::OracleError::Config("No config directory".into()))?;
         Ok(config_dir.join("oracle").join("config.yaml"))
@@ -121,4 +246,12 @@ mod tests {
         assert_eq!(s.ui.theme, loaded.ui.theme);
         assert_eq!(s.keybindings.quit, loaded.keybindings.quit);
     }
+
+    #[test]
+    fn test_settings_without_network_section_loads() {
+        let mut yaml = serde_yaml::to_value(Settings::default()).unwrap();
+        yaml.as_mapping_mut().unwrap().remove("network");
+        let loaded: Settings = serde_yaml::from_value(yaml).unwrap();
+        assert!(!loaded.network.offline);
+    }
 }
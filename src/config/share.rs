@@ -0,0 +1,134 @@
+//! Inspection sessions shared between teammates
+//!
+//! `&` writes the open project, the list as filtered, the selected and pinned items,
+//! the saved views and the review notes to one JSON file; `oracle --import <file>`
+//! opens the same project and lands on the same items. Items are referred to by
+//! kind and qualified name, so the file works in any checkout of the project.
+
+use super::{ReviewNotes, SavedView};
+use crate::analyzer::AnalyzedItem;
+use crate::error::{OracleError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// An item by kind and qualified name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemRef {
+    pub kind: String,
+    pub name: String,
+}
+
+impl ItemRef {
+    pub fn of(item: &AnalyzedItem) -> Self {
+        Self {
+            kind: item.kind().to_string(),
+            name: item.qualified_name(),
+        }
+    }
+
+    pub fn matches(&self, item: &AnalyzedItem) -> bool {
+        item.kind() == self.kind && item.qualified_name() == self.name
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedSession {
+    pub version: u32,
+    /// Project root on the machine the session was written on
+    pub project: PathBuf,
+    /// Tab, search, sort and visibility of the list
+    pub list: SavedView,
+    #[serde(default)]
+    pub selected: Option<ItemRef>,
+    #[serde(default)]
+    pub pinned: Option<ItemRef>,
+    #[serde(default)]
+    pub views: Vec<SavedView>,
+    #[serde(default)]
+    pub review: ReviewNotes,
+}
+
+impl SharedSession {
+    /// Written into every file; newer files are refused rather than half-read
+    pub const VERSION: u32 = 1;
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let session: Self = serde_json::from_str(&content)
+            .map_err(|e| OracleError::Config(format!("{}: {}", path.display(), e)))?;
+        if session.version > Self::VERSION {
+            return Err(OracleError::Config(format!(
+                "{} is from a newer oracle (session version {})",
+                path.display(),
+                session.version
+            )));
+        }
+        Ok(session)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| OracleError::Config(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// The project to open when no path is given: the one it was written in, if
+    /// that path exists here
+    pub fn project_dir(&self) -> Option<&Path> {
+        Some(self.project.as_path()).filter(|p| p.is_dir())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+    use crate::config::{ListSort, ReviewNote};
+    use crate::ui::Tab;
+
+    #[test]
+    fn test_shared_session_round_trip() {
+        let items = RustAnalyzer::new()
+            .analyze_source("pub mod net { pub fn connect() {} }")
+            .unwrap();
+        let connect = items.iter().find(|i| i.name() == "connect").unwrap();
+        let mut review = ReviewNotes::default();
+        review.set(ReviewNote {
+            item: "net::connect".to_string(),
+            kind: "fn".to_string(),
+            note: "Timeout?".to_string(),
+            location: None,
+        });
+        let session = SharedSession {
+            version: SharedSession::VERSION,
+            project: PathBuf::from("/nowhere/oracle-share-test"),
+            list: SavedView {
+                name: "shared".to_string(),
+                query: "kind:fn".to_string(),
+                tab: Tab::Functions,
+                sort: ListSort::Name,
+                public_only: true,
+            },
+            selected: Some(ItemRef::of(connect)),
+            pinned: None,
+            views: Vec::new(),
+            review,
+        };
+        assert_eq!(session.selected.as_ref().unwrap().name, "net::connect");
+        assert!(session.selected.as_ref().unwrap().matches(connect));
+        assert!(session.project_dir().is_none());
+
+        let path = std::env::temp_dir().join(format!("oracle-share-{}.json", std::process::id()));
+        session.save(&path).unwrap();
+        assert_eq!(SharedSession::load(&path).unwrap(), session);
+
+        let newer = SharedSession {
+            version: SharedSession::VERSION + 1,
+            ..session
+        };
+        newer.save(&path).unwrap();
+        assert!(SharedSession::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
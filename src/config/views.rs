@@ -0,0 +1,85 @@
+//! Saved views: a named search query plus the list settings it can't express
+
+use crate::ui::Tab;
+use serde::{Deserialize, Serialize};
+
+/// Order of the item list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListSort {
+    /// As analyzed: file by file, in source order
+    #[default]
+    Source,
+    Name,
+    /// Grouped by kind (fn, struct, ...), source order within a kind
+    Kind,
+}
+
+impl ListSort {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Source => "source order",
+            Self::Name => "name",
+            Self::Kind => "kind",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: String,
+    /// Search bar contents, filters included (e.g. `async:true`)
+    #[serde(default)]
+    pub query: String,
+    #[serde(default)]
+    pub tab: Tab,
+    #[serde(default)]
+    pub sort: ListSort,
+    /// Only `pub` items
+    #[serde(default)]
+    pub public_only: bool,
+}
+
+impl SavedView {
+    /// One-line description for the picker, e.g. `Types · by name · public only`
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.tab.title().to_string()];
+        if self.sort != ListSort::Source {
+            parts.push(format!("by {}", self.sort.label()));
+        }
+        if self.public_only {
+            parts.push("public only".to_string());
+        }
+        if !self.query.is_empty() {
+            parts.push(format!("\"{}\"", self.query));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Views offered before any are saved
+pub fn default_views() -> Vec<SavedView> {
+    vec![
+        SavedView {
+            name: "Public API".into(),
+            query: String::new(),
+            tab: Tab::Types,
+            sort: ListSort::Name,
+            public_only: true,
+        },
+        SavedView {
+            name: "Async functions".into(),
+            query: "async:true".into(),
+            tab: Tab::Functions,
+            sort: ListSort::Name,
+            public_only: false,
+        },
+        SavedView {
+            name: "Undocumented types".into(),
+            query: "doc:false".into(),
+            tab: Tab::Types,
+            sort: ListSort::Source,
+            public_only: false,
+        },
+    ]
+}
@@ -0,0 +1,253 @@
+//! A 0–100 health score for a dependency from its crates.io and GitHub metadata
+//!
+//! Each signal contributes up to a fixed number of points; an unknown signal (no
+//! GitHub repository, no release dates) gets half, so missing data neither sinks
+//! nor lifts a crate.
+
+use super::{age_in_days, CrateDocInfo};
+
+/// One contribution to a [`HealthScore`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthSignal {
+    pub label: &'static str,
+    pub points: u8,
+    pub max: u8,
+    /// What the points are based on, e.g. `412 days ago`
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthScore {
+    /// Sum of the signals' points, 0–100
+    pub score: u8,
+    pub signals: Vec<HealthSignal>,
+}
+
+impl HealthScore {
+    /// `good` from 75, `fair` from 50, `poor` below
+    pub fn grade(&self) -> &'static str {
+        match self.score {
+            75.. => "good",
+            50.. => "fair",
+            _ => "poor",
+        }
+    }
+}
+
+/// Score `doc` at `now` (Unix seconds). `installed` is the resolved version, and
+/// `versions_in_graph` how many versions of the crate the dependency graph has.
+pub fn health_score(
+    doc: &CrateDocInfo,
+    installed: Option<&str>,
+    versions_in_graph: usize,
+    now: u64,
+) -> HealthScore {
+    let signals = vec![
+        release_signal(doc, now),
+        popularity_signal(doc),
+        trend_signal(doc),
+        issues_signal(doc),
+        maintenance_signal(doc, installed),
+        duplicates_signal(versions_in_graph),
+    ];
+    HealthScore {
+        score: signals.iter().map(|s| s.points).sum(),
+        signals,
+    }
+}
+
+fn signal(label: &'static str, points: u8, max: u8, detail: impl Into<String>) -> HealthSignal {
+    HealthSignal {
+        label,
+        points,
+        max,
+        detail: detail.into(),
+    }
+}
+
+fn release_signal(doc: &CrateDocInfo, now: u64) -> HealthSignal {
+    const MAX: u8 = 25;
+    let newest = doc
+        .versions
+        .iter()
+        .find(|v| !v.yanked)
+        .and_then(|v| age_in_days(v.created_at.as_deref()?, now));
+    let Some(days) = newest else {
+        return signal("Last release", MAX / 2, MAX, "release date unknown");
+    };
+    let points = match days {
+        0..=180 => MAX,
+        181..=365 => 18,
+        366..=730 => 10,
+        731..=1460 => 4,
+        _ => 0,
+    };
+    signal("Last release", points, MAX, format!("{} days ago", days))
+}
+
+fn popularity_signal(doc: &CrateDocInfo) -> HealthSignal {
+    const MAX: u8 = 15;
+    let Some(recent) = doc.recent_downloads else {
+        return signal("Downloads", MAX / 2, MAX, "unknown");
+    };
+    let points = match recent {
+        1_000_000.. => MAX,
+        100_000.. => 12,
+        10_000.. => 8,
+        1_000.. => 4,
+        _ => 1,
+    };
+    signal(
+        "Downloads",
+        points,
+        MAX,
+        format!("{} in the last 90 days", recent),
+    )
+}
+
+/// Share of all-time downloads that happened in the last 90 days: a crate still in
+/// use keeps a steady share, an abandoned one's shrinks
+fn trend_signal(doc: &CrateDocInfo) -> HealthSignal {
+    const MAX: u8 = 10;
+    let (Some(recent), Some(total)) = (doc.recent_downloads, doc.downloads) else {
+        return signal("Download trend", MAX / 2, MAX, "unknown");
+    };
+    let percent = recent as f64 * 100.0 / total.max(1) as f64;
+    let points = match percent {
+        p if p >= 10.0 => MAX,
+        p if p >= 3.0 => 6,
+        p if p >= 1.0 => 3,
+        _ => 0,
+    };
+    signal(
+        "Download trend",
+        points,
+        MAX,
+        format!("{:.1}% of all downloads in the last 90 days", percent),
+    )
+}
+
+/// Open issues per 100 stars, so large projects aren't punished for their size
+fn issues_signal(doc: &CrateDocInfo) -> HealthSignal {
+    const MAX: u8 = 15;
+    let Some((issues, stars)) = doc
+        .github
+        .as_ref()
+        .and_then(|g| Some((g.open_issues_count?, g.stars?)))
+    else {
+        return signal("Open issues", MAX / 2, MAX, "no GitHub data");
+    };
+    let per_100_stars = issues * 100 / stars.max(1);
+    let points = match per_100_stars {
+        0..=5 => MAX,
+        6..=15 => 10,
+        16..=40 => 5,
+        _ => 0,
+    };
+    signal(
+        "Open issues",
+        points,
+        MAX,
+        format!("{} open, {} stars", issues, stars),
+    )
+}
+
+/// Whether the installed version is yanked or a major version behind
+fn maintenance_signal(doc: &CrateDocInfo, installed: Option<&str>) -> HealthSignal {
+    const MAX: u8 = 25;
+    let Some(installed) = installed else {
+        return signal("Installed version", MAX / 2, MAX, "not resolved");
+    };
+    if doc.versions.iter().any(|v| v.num == installed && v.yanked) {
+        return signal(
+            "Installed version",
+            0,
+            MAX,
+            format!("{} is yanked", installed),
+        );
+    }
+    let major = |v: &str| {
+        let v = cargo_metadata::semver::Version::parse(v).ok()?;
+        // 0.x releases are breaking at the minor version
+        Some(if v.major == 0 {
+            (0, v.minor)
+        } else {
+            (v.major, 0)
+        })
+    };
+    match (major(installed), major(&doc.version)) {
+        (Some(a), Some(b)) if a < b => signal(
+            "Installed version",
+            MAX / 2,
+            MAX,
+            format!("{} is behind {} (breaking)", installed, doc.version),
+        ),
+        _ => signal(
+            "Installed version",
+            MAX,
+            MAX,
+            format!("{} is current", installed),
+        ),
+    }
+}
+
+fn duplicates_signal(versions_in_graph: usize) -> HealthSignal {
+    const MAX: u8 = 10;
+    match versions_in_graph {
+        0 | 1 => signal("Duplicates", MAX, MAX, "one version in the graph"),
+        n => signal(
+            "Duplicates",
+            if n == 2 { MAX / 2 } else { 0 },
+            MAX,
+            format!("{} versions in the graph", n),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crates_io::{CrateVersion, GitHubRepoInfo};
+
+    #[test]
+    fn test_health_score() {
+        // 2024-03-11
+        let now = 1_710_115_200;
+        let doc = CrateDocInfo {
+            name: "demo".into(),
+            version: "2.0.0".into(),
+            downloads: Some(10_000_000),
+            recent_downloads: Some(2_000_000),
+            versions: vec![
+                CrateVersion {
+                    num: "2.0.0".into(),
+                    created_at: Some("2024-02-01T00:00:00Z".into()),
+                    ..Default::default()
+                },
+                CrateVersion {
+                    num: "1.0.0".into(),
+                    yanked: true,
+                    ..Default::default()
+                },
+            ],
+            github: Some(GitHubRepoInfo {
+                stars: Some(1000),
+                open_issues_count: Some(20),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let healthy = health_score(&doc, Some("2.0.0"), 1, now);
+        assert_eq!(healthy.score, 100);
+        assert_eq!(healthy.grade(), "good");
+
+        let yanked = health_score(&doc, Some("1.0.0"), 3, now);
+        assert_eq!(yanked.score, 100 - 25 - 10);
+        let behind = health_score(&doc, Some("1.5.0"), 2, now);
+        assert_eq!(behind.signals[4].points, 12);
+
+        let unknown = health_score(&CrateDocInfo::default(), None, 1, now);
+        assert_eq!(unknown.score, 12 + 7 + 5 + 7 + 12 + 10);
+        assert_eq!(unknown.grade(), "fair");
+    }
+}
@@ -0,0 +1,396 @@
+//! Shared HTTP client for crates.io and GitHub requests.
+//!
+//! All network access goes through [`HttpClient::shared`], which
+//! - caps concurrent requests across background threads,
+//! - spaces crates.io requests at least one second apart (crates.io crawler policy),
+//! - retries 429/5xx responses with exponential backoff, honoring `Retry-After`,
+//! - revalidates responses with `If-None-Match` against an ETag cache on disk, keeping
+//!   the newest entries and never storing responses to requests sent with a token.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// Request timeout.
+const TIMEOUT: Duration = Duration::from_secs(15);
+/// User-Agent: crates.io requires it for API requests.
+const USER_AGENT: &str =
+    "Oracle/0.1 (Rust code inspector; https://github.com/yashksaini-coder/oracle)";
+/// Concurrent requests across all threads.
+const MAX_CONCURRENT: usize = 4;
+/// Retries after the first attempt for 429 / 5xx responses.
+const MAX_RETRIES: u32 = 3;
+/// First backoff delay; doubles on each retry.
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// Longest we wait between attempts, whatever `Retry-After` says.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// crates.io crawler policy: at most one request per second.
+const CRATES_IO_INTERVAL: Duration = Duration::from_secs(1);
+/// Responses kept in the on-disk cache; the oldest go first.
+const MAX_CACHE_ENTRIES: usize = 512;
+
+/// A cached response body with its validator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: String,
+    body: String,
+}
+
+/// Rate-limited, retrying HTTP client with an on-disk ETag cache.
+pub struct HttpClient {
+    client: Option<reqwest::blocking::Client>,
+    cache_dir: Option<PathBuf>,
+    in_flight: Mutex<usize>,
+    slot_free: Condvar,
+    /// Host → earliest time the next request may start
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl HttpClient {
+    /// Create a client caching into `cache_dir` (no disk cache when `None`).
+    pub fn new(cache_dir: Option<PathBuf>) -> Self {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .ok();
+        Self {
+            client,
+            cache_dir,
+            in_flight: Mutex::new(0),
+            slot_free: Condvar::new(),
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Process-wide client caching under `<cache dir>/oracle/http`.
+    pub fn shared() -> &'static HttpClient {
+        static SHARED: OnceLock<HttpClient> = OnceLock::new();
+        SHARED.get_or_init(|| {
+            HttpClient::new(dirs::cache_dir().map(|d| d.join("oracle").join("http")))
+        })
+    }
+
+    /// GET `url` as text. Returns `None` on network errors, non-success status, or a
+    /// body larger than `max_bytes`.
+    pub fn get_text(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+        max_bytes: u64,
+    ) -> Option<String> {
+        let Some(client) = self.client.as_ref() else {
+            warn!(url, "no HTTP client (TLS backend failed to initialize)");
+            return None;
+        };
+        // Answers to the user's token may be private; they stay off the disk
+        let authenticated = headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("authorization"));
+        let cached = (!authenticated).then(|| self.read_cache(url)).flatten();
+
+        for attempt in 0..=MAX_RETRIES {
+            // Taken per attempt so backoff sleeps don't hold up other requests
+            let permit = self.acquire();
+            self.throttle(url);
+            let mut req = client.get(url);
+            for (name, value) in headers {
+                req = req.header(*name, value.as_str());
+            }
+            if let Some(ref entry) = cached {
+                req = req.header("If-None-Match", entry.etag.as_str());
+            }
+            debug!(url, attempt, cached = cached.is_some(), "GET");
+            let response = match req.send() {
+                Ok(r) => r,
+                Err(e) if e.is_timeout() || e.is_connect() => {
+                    if attempt < MAX_RETRIES {
+                        debug!(url, error = %e, "retrying after connection error");
+                        drop(permit);
+                        thread::sleep(backoff_delay(attempt, None));
+                        continue;
+                    }
+                    warn!(url, error = %e, "request failed after {} retries", MAX_RETRIES);
+                    return None;
+                }
+                Err(e) => {
+                    warn!(url, error = %e, "request failed");
+                    return None;
+                }
+            };
+            let status = response.status();
+            if status == reqwest::StatusCode::NOT_MODIFIED {
+                debug!(url, "not modified, using cached body");
+                return cached.map(|c| c.body);
+            }
+            if status.is_success() {
+                if response.content_length().unwrap_or(0) > max_bytes {
+                    warn!(url, max_bytes, "response too large");
+                    return None;
+                }
+                let etag = header_str(&response, "etag");
+                let bytes = match response.bytes() {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        warn!(url, error = %e, "reading response body failed");
+                        return None;
+                    }
+                };
+                if bytes.len() as u64 > max_bytes {
+                    warn!(url, max_bytes, "response too large");
+                    return None;
+                }
+                let body = String::from_utf8_lossy(&bytes).into_owned();
+                if let Some(etag) = etag.filter(|_| !authenticated) {
+                    self.write_cache(&CacheEntry {
+                        url: url.to_string(),
+                        etag,
+                        body: body.clone(),
+                    });
+                }
+                return Some(body);
+            }
+            // GitHub reports an exhausted quota as 403 with zero remaining; retrying won't help
+            let exhausted = header_str(&response, "x-ratelimit-remaining").as_deref() == Some("0");
+            let retryable =
+                status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            if !retryable || exhausted || attempt == MAX_RETRIES {
+                warn!(url, status = status.as_u16(), exhausted, "request failed");
+                return None;
+            }
+            let retry_after = header_str(&response, "retry-after");
+            debug!(url, status = status.as_u16(), ?retry_after, "retrying");
+            drop(permit);
+            thread::sleep(backoff_delay(attempt, retry_after.as_deref()));
+        }
+        None
+    }
+
+    /// Status `url` answers a HEAD request with, or a GET where HEAD isn't allowed;
+    /// redirects are followed. `None` when it can't be reached. Not cached or retried.
+    pub fn status(&self, url: &str) -> Option<u16> {
+        let client = self.client.as_ref()?;
+        let _permit = self.acquire();
+        self.throttle(url);
+        debug!(url, "HEAD");
+        let response = match client.head(url).send() {
+            Ok(r) if r.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED => {
+                client.get(url).send()
+            }
+            other => other,
+        };
+        match response {
+            Ok(r) => Some(r.status().as_u16()),
+            Err(e) => {
+                warn!(url, error = %e, "request failed");
+                None
+            }
+        }
+    }
+
+    /// GET `url` and parse the body as JSON.
+    pub fn get_json(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+        max_bytes: u64,
+    ) -> Option<serde_json::Value> {
+        let body = self.get_text(url, headers, max_bytes)?;
+        serde_json::from_str(&body)
+            .map_err(|e| warn!(url, error = %e, "response is not valid JSON"))
+            .ok()
+    }
+
+    /// Block until fewer than `MAX_CONCURRENT` requests are running.
+    fn acquire(&self) -> Permit<'_> {
+        let mut n = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        while *n >= MAX_CONCURRENT {
+            n = self.slot_free.wait(n).unwrap_or_else(|e| e.into_inner());
+        }
+        *n += 1;
+        Permit(self)
+    }
+
+    /// Space out requests to hosts with a crawler policy.
+    fn throttle(&self, url: &str) {
+        let host = host_of(url);
+        if host != "crates.io" && host != "static.crates.io" {
+            return;
+        }
+        let wait = {
+            let mut next = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let start = next.get(host).copied().filter(|t| *t > now).unwrap_or(now);
+            next.insert(host.to_string(), start + CRATES_IO_INTERVAL);
+            start - now
+        };
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+
+    fn cache_path(&self, url: &str) -> Option<PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{:016x}.json", cache_key(url))))
+    }
+
+    fn read_cache(&self, url: &str) -> Option<CacheEntry> {
+        let content = std::fs::read_to_string(self.cache_path(url)?).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        (entry.url == url).then_some(entry)
+    }
+
+    fn write_cache(&self, entry: &CacheEntry) {
+        let Some(path) = self.cache_path(&entry.url) else {
+            return;
+        };
+        if write_atomic(&path, entry).is_ok() {
+            if let Some(dir) = path.parent() {
+                prune_cache(dir, MAX_CACHE_ENTRIES);
+            }
+        }
+    }
+}
+
+/// Releases a concurrency slot on drop.
+struct Permit<'a>(&'a HttpClient);
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        let mut n = self.0.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        *n = n.saturating_sub(1);
+        self.0.slot_free.notify_one();
+    }
+}
+
+fn write_atomic(path: &Path, entry: &CacheEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_vec(entry)?)?;
+    std::fs::rename(tmp, path)
+}
+
+/// Remove the least recently written cache files beyond the newest `keep`.
+fn prune_cache(dir: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| Some((std::fs::metadata(&p).ok()?.modified().ok()?, p)))
+        .collect();
+    if files.len() <= keep {
+        return;
+    }
+    files.sort();
+    for (_, path) in &files[..files.len() - keep] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+fn header_str(response: &reqwest::blocking::Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+}
+
+/// Delay before retry `attempt` (0-based): `Retry-After` seconds if given, else exponential.
+fn backoff_delay(attempt: u32, retry_after: Option<&str>) -> Duration {
+    let delay = retry_after
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or_else(|| BASE_BACKOFF * 2u32.saturating_pow(attempt));
+    delay.min(MAX_BACKOFF)
+}
+
+/// Host part of an `http(s)://host/...` URL.
+fn host_of(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, r)| r);
+    rest.split(['/', '?', ':']).next().unwrap_or(rest)
+}
+
+/// Stable 64-bit FNV-1a hash of the URL (cache file name).
+fn cache_key(url: &str) -> u64 {
+    url.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(0, None), Duration::from_millis(500));
+        assert_eq!(backoff_delay(2, None), Duration::from_secs(2));
+        assert_eq!(backoff_delay(0, Some("3")), Duration::from_secs(3));
+        assert_eq!(backoff_delay(0, Some("3600")), MAX_BACKOFF);
+        assert_eq!(backoff_delay(1, Some("soon")), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_host_and_cache_key() {
+        assert_eq!(
+            host_of("https://crates.io/api/v1/crates/serde"),
+            "crates.io"
+        );
+        assert_eq!(
+            host_of("https://api.github.com:443/repos"),
+            "api.github.com"
+        );
+        assert_ne!(cache_key("https://a"), cache_key("https://b"));
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("oracle-http-test-{}", std::process::id()));
+        let client = HttpClient::new(Some(dir.clone()));
+        let url = "https://crates.io/api/v1/crates/demo";
+        client.write_cache(&CacheEntry {
+            url: url.to_string(),
+            etag: "\"abc\"".to_string(),
+            body: "{}".to_string(),
+        });
+        let entry = client.read_cache(url).unwrap();
+        assert_eq!(entry.etag, "\"abc\"");
+        assert!(client.read_cache("https://crates.io/other").is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_prune_cache_keeps_newest() {
+        let dir = std::env::temp_dir().join(format!("oracle-http-prune-{}", std::process::id()));
+        let client = HttpClient::new(Some(dir.clone()));
+        for i in 0..4 {
+            client.write_cache(&CacheEntry {
+                url: format!("https://crates.io/api/v1/crates/c{}", i),
+                etag: "\"x\"".to_string(),
+                body: "{}".to_string(),
+            });
+            thread::sleep(Duration::from_millis(20));
+        }
+        prune_cache(&dir, 2);
+        assert!(client
+            .read_cache("https://crates.io/api/v1/crates/c1")
+            .is_none());
+        assert!(client
+            .read_cache("https://crates.io/api/v1/crates/c3")
+            .is_some());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,750 @@
+//! Fetch crate metadata from crates.io API and optional GitHub repo metrics.
+//! All requests go through the shared [`HttpClient`] (rate limits, retries, ETag cache).
+//! Calls block, so run them from a background thread.
+
+pub mod health;
+mod http;
+
+pub use health::{health_score, HealthScore, HealthSignal};
+pub use http::HttpClient;
+
+use cargo_metadata::semver::Version;
+
+/// Optional GitHub repository metrics (from GitHub REST API).
+#[derive(Clone, Debug, Default)]
+pub struct GitHubRepoInfo {
+    pub stars: Option<u32>,
+    pub forks: Option<u32>,
+    pub language: Option<String>,
+    pub updated_at: Option<String>,
+    pub open_issues_count: Option<u32>,
+    pub default_branch: Option<String>,
+}
+
+/// One published version of a crate.
+#[derive(Clone, Debug, Default)]
+pub struct CrateVersion {
+    pub num: String,
+    /// RFC 3339 timestamp of publication
+    pub created_at: Option<String>,
+    pub downloads: Option<u64>,
+    pub yanked: bool,
+}
+
+/// Crate metadata from crates.io (for inspector docs view). May include GitHub metrics if repo URL is GitHub.
+#[derive(Clone, Debug, Default)]
+pub struct CrateDocInfo {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub documentation: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub github: Option<GitHubRepoInfo>,
+    /// All-time downloads
+    pub downloads: Option<u64>,
+    /// Downloads in the last 90 days
+    pub recent_downloads: Option<u64>,
+    /// Most recent versions first (at most `MAX_VERSIONS`)
+    pub versions: Vec<CrateVersion>,
+    /// Total number of published versions
+    pub version_count: usize,
+    /// Owner logins (users and teams)
+    pub owners: Vec<String>,
+    /// Number of crates depending on this one
+    pub reverse_dependencies: Option<u64>,
+}
+
+/// An issue or pull request of a dependency's GitHub repository.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GitHubIssue {
+    pub number: u64,
+    pub title: String,
+    pub labels: Vec<String>,
+    pub author: Option<String>,
+    /// RFC 3339 timestamp
+    pub created_at: Option<String>,
+    /// `open`, `closed` or (pull requests) `merged`
+    pub state: String,
+    pub is_pull_request: bool,
+}
+
+/// Open issues and recently updated pull requests of a GitHub repository.
+#[derive(Clone, Debug, Default)]
+pub struct GitHubActivity {
+    /// `owner/repo`
+    pub repo: String,
+    /// Newest first
+    pub issues: Vec<GitHubIssue>,
+    /// Most recently updated first, any state
+    pub pulls: Vec<GitHubIssue>,
+}
+
+/// One hit of a crates.io search.
+#[derive(Clone, Debug, Default)]
+pub struct CrateSearchResult {
+    pub name: String,
+    /// Newest stable version, or the newest one if there is no stable release
+    pub version: String,
+    pub description: Option<String>,
+    pub downloads: Option<u64>,
+}
+
+/// Where release notes were found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReleaseNotesSource {
+    /// GitHub Releases
+    Releases,
+    /// `CHANGELOG.md` in the repository
+    Changelog,
+}
+
+/// Notes for one released version.
+#[derive(Clone, Debug)]
+pub struct ReleaseNote {
+    pub version: String,
+    pub title: String,
+    pub date: Option<String>,
+    pub body: String,
+}
+
+/// Release notes between the installed and the latest version of a dependency (newest first).
+#[derive(Clone, Debug)]
+pub struct ReleaseNotes {
+    pub crate_name: String,
+    pub installed: String,
+    pub latest: String,
+    pub source: ReleaseNotesSource,
+    pub notes: Vec<ReleaseNote>,
+}
+
+/// Max response body size (1 MiB) to avoid unbounded memory.
+const MAX_RESPONSE_BYTES: u64 = 1024 * 1024;
+/// Max GitHub API response (small JSON).
+const MAX_GITHUB_RESPONSE_BYTES: u64 = 64 * 1024;
+/// Issues and pull requests fetched per list.
+const MAX_GITHUB_ISSUES: usize = 30;
+/// Versions kept for the history view.
+const MAX_VERSIONS: usize = 8;
+
+/// Headers for GitHub API requests; adds `GITHUB_TOKEN` auth when set.
+fn github_headers() -> Vec<(&'static str, String)> {
+    let mut headers = vec![("Accept", "application/vnd.github.v3+json".to_string())];
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        if !token.is_empty() {
+            headers.push(("Authorization", format!("Bearer {}", token)));
+        }
+    }
+    headers
+}
+
+/// GET a crates.io API URL as JSON.
+fn get_crates_io_json(url: &str) -> Option<serde_json::Value> {
+    let headers = [("Accept", "application/json".to_string())];
+    HttpClient::shared().get_json(url, &headers, MAX_RESPONSE_BYTES)
+}
+
+/// Parse "https://github.com/owner/repo" or "https://github.com/owner/repo/" into Some(("owner", "repo")).
+fn parse_github_url(repo: &str) -> Option<(String, String)> {
+    let s = repo.trim().trim_end_matches('/');
+    let rest = s
+        .strip_prefix("https://github.com/")
+        .or_else(|| s.strip_prefix("http://github.com/"))?;
+    let mut parts = rest.splitn(2, '/');
+    let owner = parts.next()?.to_string();
+    let repo_name = parts.next()?.split('/').next()?.to_string();
+    if owner.is_empty() || repo_name.is_empty() {
+        return None;
+    }
+    Some((owner, repo_name))
+}
+
+/// Fetch repository metrics from GitHub REST API. Returns None on any error.
+/// GitHub allows 60 req/h unauthenticated; set GITHUB_TOKEN for 5000/h.
+fn fetch_github_repo_info(owner: &str, repo: &str) -> Option<GitHubRepoInfo> {
+    let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+    let body = HttpClient::shared().get_json(&url, &github_headers(), MAX_GITHUB_RESPONSE_BYTES)?;
+    let stars = body
+        .get("stargazers_count")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as u32);
+    let forks = body
+        .get("forks_count")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as u32);
+    let language = body
+        .get("language")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let updated_at = body
+        .get("updated_at")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let open_issues_count = body
+        .get("open_issues_count")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as u32);
+    let default_branch = body
+        .get("default_branch")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    Some(GitHubRepoInfo {
+        stars,
+        forks,
+        language,
+        updated_at,
+        open_issues_count,
+        default_branch,
+    })
+}
+
+/// HTTP status of each URL (`None` when unreachable), checked a few at a time; for
+/// web links in doc comments
+pub fn url_statuses(urls: &[String]) -> Vec<(String, Option<u16>)> {
+    let client = HttpClient::shared();
+    std::thread::scope(|scope| {
+        let checks: Vec<_> = urls
+            .iter()
+            .map(|url| scope.spawn(move || (url.clone(), client.status(url))))
+            .collect();
+        checks.into_iter().filter_map(|c| c.join().ok()).collect()
+    })
+}
+
+/// Fetch open issues and recent pull requests of a GitHub repository URL. Returns `None`
+/// for non-GitHub repos or when the issues request fails; set `GITHUB_TOKEN` for the
+/// higher rate limit.
+pub fn fetch_github_activity(repository: &str) -> Option<GitHubActivity> {
+    let (owner, repo) = parse_github_url(repository)?;
+    tracing::debug!(owner, repo, "fetching GitHub issues and pull requests");
+    let base = format!("https://api.github.com/repos/{}/{}", owner, repo);
+    let headers = github_headers();
+    let issues_url = format!("{}/issues?state=open&per_page={}", base, MAX_GITHUB_ISSUES);
+    let issues = HttpClient::shared().get_json(&issues_url, &headers, MAX_RESPONSE_BYTES)?;
+    let pulls_url = format!(
+        "{}/pulls?state=all&sort=updated&direction=desc&per_page={}",
+        base, MAX_GITHUB_ISSUES
+    );
+    let pulls = HttpClient::shared()
+        .get_json(&pulls_url, &headers, MAX_RESPONSE_BYTES)
+        .map(|body| parse_github_issues(&body))
+        .unwrap_or_default();
+    Some(GitHubActivity {
+        repo: format!("{}/{}", owner, repo),
+        // The issues endpoint lists pull requests too
+        issues: parse_github_issues(&issues)
+            .into_iter()
+            .filter(|i| !i.is_pull_request)
+            .collect(),
+        pulls,
+    })
+}
+
+/// Parse a GitHub issues or pulls list; entries without a number or title are skipped.
+fn parse_github_issues(body: &serde_json::Value) -> Vec<GitHubIssue> {
+    let Some(entries) = body.as_array() else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .map(|e| {
+            let state = match e.get("merged_at") {
+                Some(m) if !m.is_null() => "merged",
+                _ => e.get("state").and_then(|s| s.as_str()).unwrap_or("open"),
+            };
+            GitHubIssue {
+                number: e.get("number").and_then(|n| n.as_u64()).unwrap_or(0),
+                title: e
+                    .get("title")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                labels: e
+                    .get("labels")
+                    .and_then(|l| l.as_array())
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .filter_map(|l| l.get("name")?.as_str())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                author: e
+                    .get("user")
+                    .and_then(|u| u.get("login")?.as_str())
+                    .map(String::from),
+                created_at: e
+                    .get("created_at")
+                    .and_then(|c| c.as_str())
+                    .map(String::from),
+                state: state.to_string(),
+                // Entries of the pulls endpoint have no `pull_request` key, but do have `head`
+                is_pull_request: e.get("pull_request").is_some() || e.get("head").is_some(),
+            }
+        })
+        .collect()
+}
+
+/// Whole days from an RFC 3339 timestamp to `now` (seconds since the Unix epoch).
+pub fn age_in_days(timestamp: &str, now: u64) -> Option<u64> {
+    let date = timestamp.get(..10)?;
+    let mut parts = date.split('-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    // Days since 1970-01-01 in the proleptic Gregorian calendar (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    Some((now / 86_400).saturating_sub(u64::try_from(days).ok()?))
+}
+
+/// Fetch release notes for versions in `(installed, latest]` from GitHub Releases, falling back
+/// to the repository's `CHANGELOG.md`. Returns `None` for non-GitHub repos or on network errors.
+pub fn fetch_release_notes(
+    crate_name: &str,
+    repository: &str,
+    installed: &str,
+    latest: &str,
+) -> Option<ReleaseNotes> {
+    let (owner, repo) = parse_github_url(repository)?;
+    let installed_v = Version::parse(installed).ok()?;
+    let latest_v = Version::parse(latest).ok()?;
+    let client = HttpClient::shared();
+
+    let releases_url = format!(
+        "https://api.github.com/repos/{}/{}/releases?per_page=100",
+        owner, repo
+    );
+    let releases = client.get_json(&releases_url, &github_headers(), MAX_RESPONSE_BYTES);
+    let mut notes = releases
+        .as_ref()
+        .map(|r| parse_github_releases(r, crate_name, &installed_v, &latest_v))
+        .unwrap_or_default();
+    let mut source = ReleaseNotesSource::Releases;
+
+    if notes.is_empty() {
+        let changelog_url = format!(
+            "https://raw.githubusercontent.com/{}/{}/HEAD/CHANGELOG.md",
+            owner, repo
+        );
+        let text = client.get_text(&changelog_url, &[], MAX_RESPONSE_BYTES)?;
+        notes = parse_changelog(&text, crate_name, &installed_v, &latest_v);
+        source = ReleaseNotesSource::Changelog;
+    }
+
+    Some(ReleaseNotes {
+        crate_name: crate_name.to_string(),
+        installed: installed.to_string(),
+        latest: latest.to_string(),
+        source,
+        notes,
+    })
+}
+
+/// Version named by a release tag or changelog heading: `v1.2.3`, `1.2.3`, `name-v1.2.3`,
+/// `name@1.2.3`, `[1.2.3] - 2024-01-01`. Tags prefixed with another crate's name are rejected.
+fn version_from_tag(tag: &str, crate_name: &str) -> Option<Version> {
+    let normalize = |s: &str| s.to_lowercase().replace('_', "-");
+    let tag = tag.trim().trim_start_matches(['#', '[', ' ']);
+    for (i, c) in tag.char_indices() {
+        if !c.is_ascii_digit() {
+            continue;
+        }
+        let prefix = tag[..i].trim_end_matches(['v', 'V', '-', '@', '/', '_', ' ']);
+        if !prefix.is_empty() && normalize(prefix) != normalize(crate_name) {
+            return None;
+        }
+        let end = tag[i..]
+            .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '.' || ch == '-' || ch == '+'))
+            .map_or(tag.len(), |e| i + e);
+        return Version::parse(&tag[i..end]).ok();
+    }
+    None
+}
+
+/// Whether `v` lies in `(installed, latest]`; pre-releases only count when `latest` is one.
+fn in_upgrade_range(v: &Version, installed: &Version, latest: &Version) -> bool {
+    v > installed && v <= latest && (v.pre.is_empty() || !latest.pre.is_empty())
+}
+
+/// Release notes from a GitHub `releases` API response, newest first.
+fn parse_github_releases(
+    releases: &serde_json::Value,
+    crate_name: &str,
+    installed: &Version,
+    latest: &Version,
+) -> Vec<ReleaseNote> {
+    let mut notes: Vec<(Version, ReleaseNote)> = releases
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|r| {
+            let tag = r.get("tag_name")?.as_str()?;
+            let version = version_from_tag(tag, crate_name)?;
+            if !in_upgrade_range(&version, installed, latest) {
+                return None;
+            }
+            let title = r
+                .get("name")
+                .and_then(|n| n.as_str())
+                .filter(|n| !n.is_empty())
+                .unwrap_or(tag)
+                .to_string();
+            let note = ReleaseNote {
+                version: version.to_string(),
+                title,
+                date: r
+                    .get("published_at")
+                    .and_then(|d| d.as_str())
+                    .map(|d| d.get(..10).unwrap_or(d).to_string()),
+                body: r
+                    .get("body")
+                    .and_then(|b| b.as_str())
+                    .unwrap_or("")
+                    .replace("\r\n", "\n"),
+            };
+            Some((version, note))
+        })
+        .collect();
+    notes.sort_by(|a, b| b.0.cmp(&a.0));
+    notes.into_iter().map(|(_, n)| n).collect()
+}
+
+/// Sections of a Markdown changelog whose heading names a version in range, newest first.
+fn parse_changelog(
+    text: &str,
+    crate_name: &str,
+    installed: &Version,
+    latest: &Version,
+) -> Vec<ReleaseNote> {
+    let mut notes: Vec<(Version, ReleaseNote)> = Vec::new();
+    let mut current: Option<(Version, ReleaseNote)> = None;
+    for line in text.lines() {
+        let heading = line.starts_with('#');
+        let version = heading
+            .then(|| version_from_tag(line.trim_start_matches('#'), crate_name))
+            .flatten();
+        if let Some(version) = version {
+            notes.extend(current.take());
+            let title = line.trim_start_matches('#').trim().to_string();
+            current = Some((
+                version.clone(),
+                ReleaseNote {
+                    version: version.to_string(),
+                    title,
+                    date: None,
+                    body: String::new(),
+                },
+            ));
+        } else if let Some((_, ref mut note)) = current {
+            note.body.push_str(line);
+            note.body.push('\n');
+        }
+    }
+    notes.extend(current);
+    notes.retain(|(v, _)| in_upgrade_range(v, installed, latest));
+    for (_, note) in &mut notes {
+        note.body = note.body.trim().to_string();
+    }
+    notes.sort_by(|a, b| b.0.cmp(&a.0));
+    notes.into_iter().map(|(_, n)| n).collect()
+}
+
+/// Fetch crate info from crates.io API. Returns `None` on any error (network, parse, timeout).
+/// If the crate has a GitHub repository URL, also fetches repo metrics (stars, forks, language, etc.).
+/// Set optional `GITHUB_TOKEN` env var for higher GitHub API rate limit.
+/// Safe to call from a background thread; blocks on the shared [`HttpClient`].
+pub fn fetch_crate_docs(crate_name: &str) -> Option<CrateDocInfo> {
+    tracing::debug!(crate_name, "fetching crate docs");
+    let url = format!("https://crates.io/api/v1/crates/{}", crate_name);
+    let body = get_crates_io_json(&url)?;
+    let mut info = parse_crate_response(&body)?;
+
+    // Owners and reverse dependencies are separate endpoints; missing data is not fatal
+    let owners_url = format!("https://crates.io/api/v1/crates/{}/owners", crate_name);
+    if let Some(owners) = get_crates_io_json(&owners_url) {
+        info.owners = owners
+            .get("users")
+            .and_then(|v| v.as_array())
+            .map(|users| {
+                users
+                    .iter()
+                    .filter_map(|u| u.get("login").and_then(|l| l.as_str()))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+    let rev_url = format!(
+        "https://crates.io/api/v1/crates/{}/reverse_dependencies?per_page=1",
+        crate_name
+    );
+    info.reverse_dependencies =
+        get_crates_io_json(&rev_url).and_then(|v| v.get("meta")?.get("total")?.as_u64());
+
+    info.github = info
+        .repository
+        .as_ref()
+        .and_then(|r| parse_github_url(r))
+        .and_then(|(owner, repo)| fetch_github_repo_info(&owner, &repo));
+
+    Some(info)
+}
+
+/// Search crates.io by name and keywords, best matches first. Returns `None` on any error.
+pub fn search_crates(query: &str, per_page: usize) -> Option<Vec<CrateSearchResult>> {
+    tracing::debug!(query, "searching crates.io");
+    let url = format!(
+        "https://crates.io/api/v1/crates?q={}&per_page={}",
+        query_escape(query),
+        per_page
+    );
+    Some(parse_search_response(&get_crates_io_json(&url)?))
+}
+
+/// Features of one version of a crate (`default` excluded), sorted. Returns `None` on any error.
+pub fn fetch_crate_features(crate_name: &str, version: &str) -> Option<Vec<String>> {
+    let url = format!("https://crates.io/api/v1/crates/{}/{}", crate_name, version);
+    let body = get_crates_io_json(&url)?;
+    let mut features: Vec<String> = body
+        .get("version")?
+        .get("features")?
+        .as_object()?
+        .keys()
+        .filter(|f| *f != "default")
+        .cloned()
+        .collect();
+    features.sort();
+    Some(features)
+}
+
+/// Percent-encode everything but unreserved characters, for a query string value.
+fn query_escape(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Parse the body of `GET /api/v1/crates?q=...`.
+fn parse_search_response(body: &serde_json::Value) -> Vec<CrateSearchResult> {
+    let Some(crates) = body.get("crates").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    crates
+        .iter()
+        .filter_map(|c| {
+            let version = c
+                .get("max_stable_version")
+                .and_then(|v| v.as_str())
+                .or_else(|| c.get("newest_version")?.as_str())
+                .or_else(|| c.get("max_version")?.as_str())?;
+            Some(CrateSearchResult {
+                name: c.get("name")?.as_str()?.to_string(),
+                version: version.to_string(),
+                description: c
+                    .get("description")
+                    .and_then(|v| v.as_str())
+                    .map(|d| d.trim().to_string()),
+                downloads: c.get("downloads").and_then(|v| v.as_u64()),
+            })
+        })
+        .collect()
+}
+
+/// Parse the body of `GET /api/v1/crates/{name}` (crate object plus its `versions` list).
+fn parse_crate_response(body: &serde_json::Value) -> Option<CrateDocInfo> {
+    let crate_obj = body.get("crate")?;
+    let name = crate_obj.get("name")?.as_str()?.to_string();
+    let description = crate_obj
+        .get("description")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let documentation = crate_obj
+        .get("documentation")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let homepage = crate_obj
+        .get("homepage")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let repository = crate_obj
+        .get("repository")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let version = crate_obj
+        .get("newest_version")
+        .or_else(|| crate_obj.get("max_version"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("?")
+        .to_string();
+
+    let downloads = crate_obj.get("downloads").and_then(|v| v.as_u64());
+    let recent_downloads = crate_obj.get("recent_downloads").and_then(|v| v.as_u64());
+
+    let all_versions = body
+        .get("versions")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    // crates.io lists versions newest first
+    let versions = all_versions
+        .iter()
+        .take(MAX_VERSIONS)
+        .filter_map(|v| {
+            Some(CrateVersion {
+                num: v.get("num")?.as_str()?.to_string(),
+                created_at: v
+                    .get("created_at")
+                    .and_then(|c| c.as_str())
+                    .map(String::from),
+                downloads: v.get("downloads").and_then(|d| d.as_u64()),
+                yanked: v.get("yanked").and_then(|y| y.as_bool()).unwrap_or(false),
+            })
+        })
+        .collect();
+
+    Some(CrateDocInfo {
+        name,
+        version,
+        description,
+        documentation,
+        homepage,
+        repository,
+        github: None,
+        downloads,
+        recent_downloads,
+        versions,
+        version_count: all_versions.len(),
+        owners: Vec::new(),
+        reverse_dependencies: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_github_url() {
+        assert_eq!(
+            parse_github_url("https://github.com/rust-lang/rust"),
+            Some(("rust-lang".into(), "rust".into()))
+        );
+        assert_eq!(
+            parse_github_url("https://github.com/owner/repo/"),
+            Some(("owner".into(), "repo".into()))
+        );
+        assert_eq!(
+            parse_github_url("http://github.com/a/b"),
+            Some(("a".into(), "b".into()))
+        );
+        assert!(parse_github_url("https://gitlab.com/owner/repo").is_none());
+        assert!(parse_github_url("https://github.com/").is_none());
+        assert!(parse_github_url("").is_none());
+    }
+
+    #[test]
+    fn test_version_from_tag() {
+        let v = |s: &str| version_from_tag(s, "tokio-util");
+        assert_eq!(v("v1.2.3"), Version::parse("1.2.3").ok());
+        assert_eq!(v("tokio-util-0.7.1"), Version::parse("0.7.1").ok());
+        assert_eq!(v("tokio_util@0.7.2"), Version::parse("0.7.2").ok());
+        assert_eq!(v(" [0.7.3] - 2024-01-01"), Version::parse("0.7.3").ok());
+        assert_eq!(v("tokio-macros-v2.0.0"), None);
+        assert_eq!(v("Unreleased"), None);
+    }
+
+    #[test]
+    fn test_parse_changelog_in_range() {
+        let text = "# Changelog\n\n## [1.3.0] - 2024-03-01\n- new api\n\n## 1.2.0\n- fix\n\n## 1.1.0\n- old\n";
+        let installed = Version::parse("1.1.0").unwrap();
+        let latest = Version::parse("1.3.0").unwrap();
+        let notes = parse_changelog(text, "demo", &installed, &latest);
+        let versions: Vec<&str> = notes.iter().map(|n| n.version.as_str()).collect();
+        assert_eq!(versions, vec!["1.3.0", "1.2.0"]);
+        assert_eq!(notes[0].body, "- new api");
+    }
+
+    #[test]
+    fn test_parse_crate_response() {
+        let body = serde_json::json!({
+            "crate": {
+                "name": "demo",
+                "newest_version": "1.2.0",
+                "downloads": 12345,
+                "recent_downloads": 678,
+                "repository": "https://github.com/o/demo"
+            },
+            "versions": [
+                {"num": "1.2.0", "created_at": "2024-05-01T10:00:00Z", "downloads": 100, "yanked": false},
+                {"num": "1.1.0", "created_at": "2024-01-01T10:00:00Z", "downloads": 50, "yanked": true}
+            ]
+        });
+        let info = parse_crate_response(&body).unwrap();
+        assert_eq!(info.name, "demo");
+        assert_eq!(info.version, "1.2.0");
+        assert_eq!(info.downloads, Some(12345));
+        assert_eq!(info.recent_downloads, Some(678));
+        assert_eq!(info.version_count, 2);
+        assert!(info.versions[1].yanked);
+        assert!(info.github.is_none());
+    }
+
+    #[test]
+    fn test_parse_github_issues() {
+        let body = serde_json::json!([
+            {"number": 12, "title": "Crash on empty input", "state": "open",
+             "labels": [{"name": "bug"}, {"name": "good first issue"}],
+             "user": {"login": "alice"}, "created_at": "2024-03-01T10:00:00Z"},
+            {"number": 13, "title": "Add feature", "state": "closed",
+             "merged_at": "2024-03-05T10:00:00Z"}
+        ]);
+        let issues = parse_github_issues(&body);
+        assert_eq!(issues[0].labels, vec!["bug", "good first issue"]);
+        assert_eq!(issues[0].author.as_deref(), Some("alice"));
+        assert_eq!(issues[0].state, "open");
+        assert_eq!(issues[1].state, "merged");
+        assert!(parse_github_issues(&serde_json::json!({"message": "Not Found"})).is_empty());
+    }
+
+    #[test]
+    fn test_age_in_days() {
+        // 2024-03-11T00:00:00Z
+        let now = 1_710_115_200;
+        assert_eq!(age_in_days("2024-03-01T10:00:00Z", now), Some(10));
+        assert_eq!(age_in_days("2023-03-11", now), Some(366));
+        assert_eq!(age_in_days("1970-01-01T00:00:00Z", 86_400 * 3), Some(3));
+        assert_eq!(age_in_days("soon", now), None);
+    }
+
+    #[test]
+    fn test_parse_search_response() {
+        let body = serde_json::json!({
+            "crates": [
+                {"name": "serde", "max_stable_version": "1.0.210", "newest_version": "1.0.211-rc.1",
+                 "description": " A serialization framework\n", "downloads": 9},
+                {"name": "pre", "max_stable_version": null, "newest_version": "0.1.0-alpha"},
+                {"name": "broken"}
+            ]
+        });
+        let hits = parse_search_response(&body);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].version, "1.0.210");
+        assert_eq!(
+            hits[0].description.as_deref(),
+            Some("A serialization framework")
+        );
+        assert_eq!(hits[1].version, "0.1.0-alpha");
+        assert_eq!(query_escape("serde json&x"), "serde%20json%26x");
+    }
+}
@@ -0,0 +1,191 @@
+//! Headless driver for scripting the TUI
+//!
+//! [`Driver`] owns an [`App`] and a ratatui `TestBackend`, so a project can be
+//! loaded, searched and navigated with the same events the terminal produces,
+//! and the rendered screen inspected as text.
+//!
+//! ```no_run
+//! use oracle_lib::driver::Driver;
+//!
+//! let mut driver = Driver::new(120, 40);
+//! driver.load_project(".").unwrap();
+//! driver.search("App");
+//! assert!(driver.screen().contains("App"));
+//! ```
+
+use crate::analyzer::RustAnalyzer;
+use crate::app::{App, AppEvent};
+use crate::error::Result;
+use crate::ui::Tab;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::Terminal;
+use std::path::Path;
+
+/// Scriptable application with an in-memory terminal
+pub struct Driver {
+    app: App,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Driver {
+    /// Driver with a `width` x `height` screen. Settings files are not read and the
+    /// app starts offline, so runs are deterministic.
+    pub fn new(width: u16, height: u16) -> Self {
+        let mut app = App::new();
+        app.offline = true;
+        let terminal =
+            Terminal::new(TestBackend::new(width, height)).expect("TestBackend never fails");
+        Self { app, terminal }
+    }
+
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Analyze a project directory (or single file)
+    pub fn load_project(&mut self, path: impl AsRef<Path>) -> Result<&mut Self> {
+        self.app.analyze_project(path.as_ref())?;
+        self.app.project.list_state.select(Some(0));
+        Ok(self)
+    }
+
+    /// Analyze in-memory source as if it were the project
+    pub fn load_source(&mut self, source: &str) -> Result<&mut Self> {
+        self.app.project.items = RustAnalyzer::new().analyze_source(source)?.into();
+        self.app.filter_items();
+        self.app.project.list_state.select(Some(0));
+        Ok(self)
+    }
+
+    /// Apply an event, then tick once (as the event loop does before drawing)
+    pub fn send(&mut self, event: AppEvent) -> &mut Self {
+        self.app.update(event);
+        self.app.update(AppEvent::Tick);
+        self
+    }
+
+    pub fn press(&mut self, code: KeyCode) -> &mut Self {
+        self.send(AppEvent::key(code))
+    }
+
+    pub fn press_with(&mut self, code: KeyCode, modifiers: KeyModifiers) -> &mut Self {
+        self.send(AppEvent::Key(KeyEvent::new(code, modifiers)))
+    }
+
+    /// Type text into whatever has focus
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        for c in text.chars() {
+            self.press(KeyCode::Char(c));
+        }
+        self
+    }
+
+    /// Replace the search query and apply it, leaving focus on the list
+    pub fn search(&mut self, query: &str) -> &mut Self {
+        self.app.clear_search();
+        self.app.project.focus = crate::ui::Focus::Search;
+        self.type_text(query);
+        self.app.project.show_completion = false;
+        self.press(KeyCode::Enter)
+    }
+
+    pub fn tab(&mut self, tab: Tab) -> &mut Self {
+        self.app.switch_tab(tab);
+        self.send(AppEvent::Tick)
+    }
+
+    /// Select the first listed item named `name`; false if it is not in the current list
+    pub fn select(&mut self, name: &str) -> bool {
+        let Some(index) = self
+            .app
+            .get_filtered_items()
+            .iter()
+            .position(|i| i.name() == name)
+        else {
+            return false;
+        };
+        self.app.project.list_state.select(Some(index));
+        self.app.update(AppEvent::Tick);
+        true
+    }
+
+    /// Draw a frame and return the rendered buffer
+    pub fn render(&mut self) -> &Buffer {
+        let app = &self.app;
+        self.terminal
+            .draw(|frame| app.draw(frame))
+            .expect("TestBackend never fails");
+        self.terminal.backend().buffer()
+    }
+
+    /// Draw a frame and return it as text, one line per row (trailing spaces trimmed)
+    pub fn screen(&mut self) -> String {
+        let buffer = self.render();
+        let width = buffer.area.width as usize;
+        buffer
+            .content()
+            .chunks(width.max(1))
+            .map(|row| {
+                let line: String = row.iter().map(|cell| cell.symbol()).collect();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drive_search_and_select() {
+        let mut driver = Driver::new(140, 40);
+        driver
+            .load_source(
+                "/// Connection settings\npub struct Config { pub url: String }\npub fn connect() {}\npub fn disconnect() {}",
+            )
+            .unwrap();
+        assert!(driver.screen().contains("Config"));
+
+        driver.tab(Tab::Functions).search("disconnect");
+        assert_eq!(driver.app().get_filtered_items().len(), 1);
+        assert!(driver.select("disconnect"));
+        assert!(!driver.select("Config"));
+        let screen = driver.screen();
+        assert!(screen.contains("Items (1/3)"));
+        assert!(screen.contains("zero-argument function"));
+    }
+
+    #[test]
+    fn test_inspector_scroll_is_clamped() {
+        let docs: String = (0..60)
+            .map(|i| format!("/// Line {i} of the docs\n"))
+            .collect();
+        let mut driver = Driver::new(120, 30);
+        driver
+            .load_source(&format!("{docs}pub fn documented() {{}}"))
+            .unwrap();
+        driver.tab(Tab::Functions);
+        driver.app_mut().project.focus = crate::ui::Focus::Inspector;
+        driver.render();
+
+        for _ in 0..20 {
+            driver.press(KeyCode::PageDown);
+        }
+        let clamped = driver.app().project.inspector_scroll;
+        assert!(clamped > 0 && clamped < 200);
+        driver.press(KeyCode::Home).press(KeyCode::End);
+        assert_eq!(driver.app().project.inspector_scroll, clamped);
+        assert!(driver.screen().contains("Line 59 of the docs"));
+        driver.press(KeyCode::Up);
+        assert_eq!(driver.app().project.inspector_scroll, clamped - 1);
+    }
+}
@@ -25,6 +25,9 @@ pub enum OracleError {
     #[error("Analysis error: {0}")]
     Analysis(String),
 
+    #[error("Index error: {0}")]
+    Index(String),
+
     #[error("{0}")]
     Other(String),
 }
@@ -36,3 +39,10 @@ impl From<syn::Error> for OracleError {
         OracleError::Parse(e.to_string())
     }
 }
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for OracleError {
+    fn from(e: rusqlite::Error) -> Self {
+        OracleError::Index(e.to_string())
+    }
+}
@@ -0,0 +1,435 @@
+//! Persistent SQLite index of a project's items (`sqlite` feature)
+//!
+//! For workspaces too large to analyze on every run: [`ItemIndex::update`] re-parses
+//! only the files whose size or modification time changed since they were indexed
+//! and drops the items of deleted ones; lookups and full-text search over names,
+//! paths and doc comments (FTS5) then run in SQLite, and an item is only
+//! deserialized when it's asked for. The index is kept in [`INDEX_FILE`] under the
+//! project directory, next to the build output.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::analyzer::{AnalyzedItem, Edition, RustAnalyzer};
+use crate::error::{OracleError, Result};
+use crate::utils::{rust_files, SkippedPath};
+
+/// Index location, relative to the project directory
+pub const INDEX_FILE: &str = "target/oracle-index.sqlite";
+
+/// Bumped when the tables change; an index of another version is rebuilt
+const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE files (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL UNIQUE,
+    size INTEGER NOT NULL,
+    mtime INTEGER NOT NULL
+);
+CREATE TABLE items (
+    id INTEGER PRIMARY KEY,
+    file_id INTEGER NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    qualified_name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    words TEXT NOT NULL,
+    line INTEGER,
+    docs TEXT,
+    item TEXT NOT NULL
+);
+CREATE INDEX items_name ON items(name);
+CREATE INDEX items_file ON items(file_id);
+CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE VIRTUAL TABLE items_fts USING fts5(
+    words, qualified_name, docs, content='items', content_rowid='id'
+);
+CREATE TRIGGER items_insert AFTER INSERT ON items BEGIN
+    INSERT INTO items_fts(rowid, words, qualified_name, docs)
+    VALUES (new.id, new.words, new.qualified_name, new.docs);
+END;
+CREATE TRIGGER items_delete AFTER DELETE ON items BEGIN
+    INSERT INTO items_fts(items_fts, rowid, words, qualified_name, docs)
+    VALUES ('delete', old.id, old.words, old.qualified_name, old.docs);
+END;
+";
+
+/// An item as stored in the index: enough to list it without loading it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedItem {
+    pub id: i64,
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub file: PathBuf,
+    pub line: Option<usize>,
+}
+
+/// What an [`ItemIndex::update`] did
+#[derive(Debug, Default)]
+pub struct IndexUpdate {
+    /// Files parsed because they were new or changed
+    pub parsed: usize,
+    pub unchanged: usize,
+    /// Files gone from the project, and their items
+    pub removed: usize,
+    /// Unreadable or unparsable files; their previous items are kept
+    pub skipped: Vec<SkippedPath>,
+}
+
+pub struct ItemIndex {
+    conn: Connection,
+    /// Project directory or `.rs` file
+    root: PathBuf,
+    analyzer: RustAnalyzer,
+}
+
+impl ItemIndex {
+    /// Open (or create) the index of the project at `root` in its [`INDEX_FILE`].
+    /// Analyzer options other than those it was built with start it over.
+    pub fn open(root: &Path, include_private: bool, exclude: Vec<String>) -> Result<Self> {
+        let dir = if root.is_file() {
+            root.parent().unwrap_or(root)
+        } else {
+            root
+        };
+        let db = dir.join(INDEX_FILE);
+        if let Some(parent) = db.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Self::open_at(&db, root, include_private, exclude)
+    }
+
+    /// [`Self::open`] with the database at `db`
+    pub fn open_at(
+        db: &Path,
+        root: &Path,
+        include_private: bool,
+        exclude: Vec<String>,
+    ) -> Result<Self> {
+        let conn = Connection::open(db)?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        let version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version != SCHEMA_VERSION {
+            conn.execute_batch(
+                "DROP TABLE IF EXISTS items_fts;
+                 DROP TABLE IF EXISTS items;
+                 DROP TABLE IF EXISTS files;
+                 DROP TABLE IF EXISTS meta;",
+            )?;
+            conn.execute_batch(SCHEMA)?;
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+        let edition = Edition::from_manifest(&root.join("Cargo.toml")).unwrap_or_default();
+        let options = format!(
+            "private={} exclude={:?} edition={}",
+            include_private, exclude, edition
+        );
+        let indexed: Option<String> = conn
+            .query_row("SELECT value FROM meta WHERE key = 'options'", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        if indexed.as_deref() != Some(options.as_str()) {
+            conn.execute("DELETE FROM files", [])?;
+            conn.execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES ('options', ?1)",
+                [&options],
+            )?;
+        }
+        Ok(Self {
+            conn,
+            root: root.to_path_buf(),
+            analyzer: RustAnalyzer::new()
+                .with_private(include_private)
+                .with_excludes(exclude)
+                .with_edition(edition),
+        })
+    }
+
+    /// Bring the index in line with the files on disk, in one transaction
+    pub fn update(&mut self) -> Result<IndexUpdate> {
+        let (files, mut skipped, dir) = self.source_files();
+        let tx = self.conn.transaction()?;
+        let known: HashMap<String, (i64, i64, i64)> = {
+            let mut stmt = tx.prepare("SELECT path, id, size, mtime FROM files")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get(3)?)))
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        let mut update = IndexUpdate::default();
+        let mut seen = HashSet::new();
+        for path in files {
+            if self
+                .analyzer
+                .is_excluded(path.strip_prefix(&dir).unwrap_or(&path))
+            {
+                continue;
+            }
+            let key = path.to_string_lossy().into_owned();
+            seen.insert(key.clone());
+            let (size, mtime) = match std::fs::metadata(&path) {
+                Ok(meta) => (meta.len() as i64, modified_nanos(&meta)),
+                Err(e) => {
+                    skipped.push(SkippedPath {
+                        path,
+                        reason: e.to_string(),
+                        lines: None,
+                    });
+                    continue;
+                }
+            };
+            if known
+                .get(&key)
+                .is_some_and(|&(_, s, m)| s == size && m == mtime)
+            {
+                update.unchanged += 1;
+                continue;
+            }
+            let items = match std::fs::read_to_string(&path) {
+                Ok(source) => {
+                    // Module paths from the directories: following `mod` declarations
+                    // as the full analysis does would mean parsing every file each update
+                    let module = RustAnalyzer::derive_module_path(&path);
+                    let (items, failures) = self.analyzer.analyze_source_recovering(
+                        &source,
+                        Some(path.clone()),
+                        module,
+                    );
+                    skipped.extend(failures.into_iter().map(|failure| SkippedPath {
+                        path: path.clone(),
+                        reason: failure.message,
+                        lines: Some(failure.lines),
+                    }));
+                    items
+                }
+                Err(e) => {
+                    skipped.push(SkippedPath {
+                        path,
+                        reason: e.to_string(),
+                        lines: None,
+                    });
+                    continue;
+                }
+            };
+            let file_id: i64 = tx.query_row(
+                "INSERT INTO files (path, size, mtime) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(path) DO UPDATE SET size = excluded.size, mtime = excluded.mtime
+                 RETURNING id",
+                params![key, size, mtime],
+                |row| row.get(0),
+            )?;
+            tx.execute("DELETE FROM items WHERE file_id = ?1", [file_id])?;
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO items (file_id, name, qualified_name, kind, words, line, docs, item)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for item in &items {
+                let json =
+                    serde_json::to_string(item).map_err(|e| OracleError::Index(e.to_string()))?;
+                insert.execute(params![
+                    file_id,
+                    item.name(),
+                    item.qualified_name(),
+                    item.kind(),
+                    name_words(item.name()),
+                    item.source_location().and_then(|l| l.line),
+                    item.documentation(),
+                    json,
+                ])?;
+            }
+            update.parsed += 1;
+        }
+        for (path, &(id, _, _)) in &known {
+            if !seen.contains(path) {
+                tx.execute("DELETE FROM files WHERE id = ?1", [id])?;
+                update.removed += 1;
+            }
+        }
+        tx.commit()?;
+        update.skipped = skipped;
+        Ok(update)
+    }
+
+    /// The files [`crate::report::analyze_sources`] would read, and the directory
+    /// exclusions are relative to
+    fn source_files(&self) -> (Vec<PathBuf>, Vec<SkippedPath>, PathBuf) {
+        if self.root.is_file() {
+            let dir = self.root.parent().unwrap_or(&self.root).to_path_buf();
+            return (vec![self.root.clone()], Vec::new(), dir);
+        }
+        let src = self.root.join("src");
+        let dir = if src.is_dir() { src } else { self.root.clone() };
+        let (files, skipped) = rust_files(&dir);
+        (files, skipped, dir)
+    }
+
+    /// Number of indexed items
+    pub fn len(&self) -> Result<usize> {
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Items named `name` or whose path ends in `::name`, in file order
+    pub fn find(&self, name: &str) -> Result<Vec<IndexedItem>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT items.id, name, qualified_name, kind, files.path, line
+             FROM items JOIN files ON files.id = items.file_id
+             WHERE name = ?1 OR qualified_name = ?1 OR qualified_name LIKE '%::' || ?1
+             ORDER BY files.path, line",
+        )?;
+        let rows = stmt.query_map([name], indexed_item)?;
+        let suffix = format!("::{}", name);
+        Ok(rows
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|i| {
+                i.name == name || i.qualified_name == name || i.qualified_name.ends_with(&suffix)
+            })
+            .collect())
+    }
+
+    /// Items whose name, path or docs contain words starting like those of `text`,
+    /// best matches first; names weigh more than docs
+    pub fn search(&self, text: &str, limit: usize) -> Result<Vec<IndexedItem>> {
+        let query = fts_query(text);
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT items.id, items.name, items.qualified_name, items.kind, files.path, items.line
+             FROM items_fts
+             JOIN items ON items.id = items_fts.rowid
+             JOIN files ON files.id = items.file_id
+             WHERE items_fts MATCH ?1
+             ORDER BY bm25(items_fts, 10.0, 5.0, 1.0)
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![query, limit as i64], indexed_item)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// The full item, as analyzed when its file was last indexed
+    pub fn load(&self, item: &IndexedItem) -> Result<AnalyzedItem> {
+        let json: String =
+            self.conn
+                .query_row("SELECT item FROM items WHERE id = ?1", [item.id], |row| {
+                    row.get(0)
+                })?;
+        serde_json::from_str(&json).map_err(|e| OracleError::Index(e.to_string()))
+    }
+}
+
+fn indexed_item(row: &Row) -> rusqlite::Result<IndexedItem> {
+    Ok(IndexedItem {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        qualified_name: row.get(2)?,
+        kind: row.get(3)?,
+        file: PathBuf::from(row.get::<_, String>(4)?),
+        line: row.get::<_, Option<i64>>(5)?.map(|l| l as usize),
+    })
+}
+
+fn modified_nanos(meta: &std::fs::Metadata) -> i64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos() as i64)
+}
+
+/// The name, then its camel-case words, so `client` finds `LspClient`
+fn name_words(name: &str) -> String {
+    let mut split = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c.is_uppercase() && previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()) {
+            split.push(' ');
+        }
+        split.push(c);
+        previous = Some(c);
+    }
+    format!("{} {}", name, split)
+}
+
+/// Each word of `text` as a quoted FTS5 prefix term, so `::`, quotes and operators
+/// typed by the user can't break the query
+fn fts_query(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_updates_incrementally() {
+        let dir = std::env::temp_dir().join(format!("oracle-index-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join("src/lib.rs"),
+            "pub mod net;\n/// Loads the settings file\npub fn load_settings() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("src/net.rs"),
+            "/// A TCP connection\npub struct TcpSocket;\n",
+        )
+        .unwrap();
+
+        let mut index = ItemIndex::open(&dir, true, Vec::new()).unwrap();
+        let update = index.update().unwrap();
+        assert_eq!((update.parsed, update.unchanged, update.removed), (2, 0, 0));
+        assert_eq!(index.len().unwrap(), 3);
+
+        let found = index.search("settings", 10).unwrap();
+        assert_eq!(found[0].name, "load_settings");
+        assert_eq!(index.search("tcp conn", 10).unwrap()[0].name, "TcpSocket");
+        assert_eq!(index.search("sock", 10).unwrap()[0].name, "TcpSocket");
+        assert!(index.search("::", 10).unwrap().is_empty());
+        let socket = &index.find("net::TcpSocket").unwrap()[0];
+        assert_eq!(socket.line, Some(2));
+        let item = index.load(socket).unwrap();
+        assert_eq!(item.documentation(), Some("A TCP connection"));
+
+        // Reopened: only the changed file is parsed, the deleted one dropped
+        drop(index);
+        std::fs::write(
+            dir.join("src/lib.rs"),
+            "/// Saves\npub fn save_settings() {}\n",
+        )
+        .unwrap();
+        std::fs::remove_file(dir.join("src/net.rs")).unwrap();
+        let mut index = ItemIndex::open(&dir, true, Vec::new()).unwrap();
+        let update = index.update().unwrap();
+        assert_eq!((update.parsed, update.unchanged, update.removed), (1, 0, 1));
+        assert!(index.find("TcpSocket").unwrap().is_empty());
+        assert_eq!(
+            index.search("settings", 10).unwrap()[0].name,
+            "save_settings"
+        );
+        assert_eq!(index.update().unwrap().unchanged, 1);
+
+        // Other analyzer options start over
+        let mut index = ItemIndex::open(&dir, false, Vec::new()).unwrap();
+        assert!(index.is_empty().unwrap());
+        assert_eq!(index.update().unwrap().parsed, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
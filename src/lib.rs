@@ -2,14 +2,34 @@
 //!
 //! A comprehensive library for analyzing Rust code, parsing cargo metadata,
 //! and providing a beautiful TUI for code inspection.
+//!
+//! [`analyzer`], [`api`], [`audit`], [`crates_io`], [`lsp`], [`report`], [`scaffold`], [`server`] and [`tags`] work without a terminal; the UI
+//! modules need the default `tui` feature, and the persistent `index` the `sqlite` one.
 
 pub mod analyzer;
+pub mod api;
+#[cfg(feature = "tui")]
 pub mod app;
+pub mod audit;
+#[cfg(feature = "tui")]
+pub mod cli;
+#[cfg(feature = "tui")]
 pub mod config;
 pub mod crates_io;
+#[cfg(feature = "tui")]
+pub mod driver;
 pub mod error;
+#[cfg(feature = "sqlite")]
+pub mod index;
+pub mod lsp;
+pub mod report;
+pub mod scaffold;
+pub mod server;
+pub mod tags;
+#[cfg(feature = "tui")]
 pub mod ui;
 pub mod utils;
 
+#[cfg(feature = "tui")]
 pub use app::App;
 pub use error::{OracleError, Result};
@@ -0,0 +1,491 @@
+//! A small LSP client for rust-analyzer
+//!
+//! The syn index knows what an item says, not what it resolves to. With
+//! `analyzer.rust_analyzer` on, oracle starts rust-analyzer in the project and asks
+//! it for an item's resolved signature (hover), its definition and its references.
+//! Messages use the LSP `Content-Length` framing over the child's stdin and stdout;
+//! a reader thread hands responses back and answers the few requests rust-analyzer
+//! sends its client with `null`. Every call blocks, so the app makes them from
+//! background threads.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::analyzer::AnalyzedItem;
+use crate::error::{OracleError, Result};
+
+/// The server binary, found on `PATH`
+pub const COMMAND: &str = "rust-analyzer";
+
+/// How long one request may take once the workspace is loaded
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A position in a file; line and column are 1-based, the column in characters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// Where the item's name is, as recorded by the parser
+    pub fn of_item(item: &AnalyzedItem) -> Option<Self> {
+        let location = item.source_location()?;
+        Some(Self {
+            file: location.file.clone()?,
+            line: location.line?,
+            column: location.column.unwrap_or(1),
+        })
+    }
+}
+
+/// What rust-analyzer knows about the symbol at one position
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Semantics {
+    /// The resolved signature or type, then its docs
+    pub hover: Option<String>,
+    pub definitions: Vec<Location>,
+    /// Uses elsewhere, the definition itself left out
+    pub references: Vec<Location>,
+}
+
+enum Incoming {
+    Response(Value),
+    /// `experimental/serverStatus` reported the workspace loaded and idle
+    Ready,
+}
+
+/// A running rust-analyzer; it's stopped when this is dropped
+#[derive(Debug)]
+pub struct LspClient {
+    child: Child,
+    stdin: Arc<Mutex<ChildStdin>>,
+    incoming: Receiver<Incoming>,
+    next_id: u64,
+    ready: bool,
+}
+
+impl LspClient {
+    /// Start `command` in `root` and initialize it; fails when it isn't installed
+    pub fn start(command: &str, root: &Path) -> Result<Self> {
+        let mut child = Command::new(command)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| failure(format!("{} could not be started: {}", command, e)))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(failure("no pipes to rust-analyzer"));
+        };
+        let stdin = Arc::new(Mutex::new(stdin));
+        let (tx, incoming) = mpsc::channel();
+        let replies = Arc::clone(&stdin);
+        thread::spawn(move || read_loop(BufReader::new(stdout), &replies, &tx));
+
+        let mut client = Self {
+            child,
+            stdin,
+            incoming,
+            next_id: 0,
+            ready: false,
+        };
+        let uri = path_to_uri(root);
+        let name = root
+            .file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        client.request(
+            "initialize",
+            json!({
+                "processId": std::process::id(),
+                "rootUri": uri,
+                "workspaceFolders": [{ "uri": uri, "name": name }],
+                "capabilities": {
+                    "textDocument": {
+                        "hover": { "contentFormat": ["markdown", "plaintext"] },
+                        "definition": { "linkSupport": true },
+                        "references": {},
+                    },
+                    "experimental": { "serverStatusNotification": true },
+                },
+                // Only reading: no `cargo check` on save
+                "initializationOptions": { "checkOnSave": false },
+            }),
+        )?;
+        client.notify("initialized", json!({}))?;
+        Ok(client)
+    }
+
+    /// Block until rust-analyzer has loaded the workspace; `false` when `timeout`
+    /// passed first (answers may then be incomplete, or the server too old to say)
+    pub fn wait_until_ready(&mut self, timeout: Duration) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+        while !self.ready {
+            match self
+                .incoming
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(Incoming::Ready) => self.ready = true,
+                Ok(Incoming::Response(_)) => {}
+                Err(RecvTimeoutError::Timeout) => return Ok(false),
+                Err(RecvTimeoutError::Disconnected) => return Err(exited()),
+            }
+        }
+        Ok(true)
+    }
+
+    /// The hover text at `at`: the resolved signature or type, then docs
+    pub fn hover(&mut self, at: &Location) -> Result<Option<String>> {
+        let params = position_params(at, &mut FileLines::default());
+        let result = self.request("textDocument/hover", params)?;
+        Ok(hover_text(&result))
+    }
+
+    pub fn definition(&mut self, at: &Location) -> Result<Vec<Location>> {
+        let mut lines = FileLines::default();
+        let params = position_params(at, &mut lines);
+        let result = self.request("textDocument/definition", params)?;
+        Ok(locations(&result, &mut lines))
+    }
+
+    /// Uses of the symbol at `at`, without its declaration
+    pub fn references(&mut self, at: &Location) -> Result<Vec<Location>> {
+        let mut lines = FileLines::default();
+        let mut params = position_params(at, &mut lines);
+        params["context"] = json!({ "includeDeclaration": false });
+        let result = self.request("textDocument/references", params)?;
+        Ok(locations(&result, &mut lines))
+    }
+
+    /// [`Self::hover`], [`Self::definition`] and [`Self::references`] at once
+    pub fn semantics(&mut self, at: &Location) -> Result<Semantics> {
+        Ok(Semantics {
+            hover: self.hover(at)?,
+            definitions: self.definition(at)?,
+            references: self.references(at)?,
+        })
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        loop {
+            match self
+                .incoming
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(Incoming::Response(response)) if response["id"] == id => {
+                    if let Some(error) = response.get("error") {
+                        let message = error["message"].as_str().unwrap_or("unknown error");
+                        return Err(failure(format!("{} failed: {}", method, message)));
+                    }
+                    return Ok(response.get("result").cloned().unwrap_or(Value::Null));
+                }
+                // A late answer to a request that timed out
+                Ok(Incoming::Response(_)) => {}
+                Ok(Incoming::Ready) => self.ready = true,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(failure(format!("{} timed out", method)))
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(exited()),
+            }
+        }
+    }
+
+    fn notify(&self, method: &str, params: Value) -> Result<()> {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn send(&self, message: &Value) -> Result<()> {
+        let mut stdin = self.stdin.lock().unwrap_or_else(|e| e.into_inner());
+        write_message(&mut *stdin, message).map_err(|_| exited())
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        let _ = self.notify("exit", Value::Null);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Pass responses and readiness on until rust-analyzer closes its stdout
+fn read_loop(mut stdout: impl BufRead, stdin: &Mutex<ChildStdin>, tx: &Sender<Incoming>) {
+    while let Ok(Some(message)) = read_message(&mut stdout) {
+        let incoming = match (message.get("id"), message.get("method")) {
+            // A request to the client, e.g. `workspace/configuration`
+            (Some(id), Some(_)) => {
+                let items = message["params"]["items"].as_array().map(Vec::len);
+                let result = items.map_or(Value::Null, |n| Value::Array(vec![Value::Null; n]));
+                let reply = json!({ "jsonrpc": "2.0", "id": id, "result": result });
+                let mut stdin = stdin.lock().unwrap_or_else(|e| e.into_inner());
+                let _ = write_message(&mut *stdin, &reply);
+                continue;
+            }
+            (Some(_), None) => Incoming::Response(message),
+            (None, Some(method))
+                if method == "experimental/serverStatus"
+                    && message["params"]["quiescent"] == true =>
+            {
+                Incoming::Ready
+            }
+            _ => continue,
+        };
+        if tx.send(incoming).is_err() {
+            break;
+        }
+    }
+}
+
+fn write_message(out: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(out, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    out.flush()
+}
+
+/// The next message, or `None` at the end of the stream
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length =
+        length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no Content-Length"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Lines of the files positions point into, each file read once; LSP columns count
+/// UTF-16 units, so converting them needs the line
+#[derive(Default)]
+struct FileLines(HashMap<PathBuf, Vec<String>>);
+
+impl FileLines {
+    fn line(&mut self, file: &Path, line: usize) -> Option<&str> {
+        let lines = self.0.entry(file.to_path_buf()).or_insert_with(|| {
+            std::fs::read_to_string(file)
+                .map(|s| s.lines().map(String::from).collect())
+                .unwrap_or_default()
+        });
+        lines.get(line.checked_sub(1)?).map(String::as_str)
+    }
+}
+
+fn position_params(at: &Location, lines: &mut FileLines) -> Value {
+    let character = lines
+        .line(&at.file, at.line)
+        .map_or(at.column.saturating_sub(1), |text| {
+            utf16_offset(text, at.column)
+        });
+    json!({
+        "textDocument": { "uri": path_to_uri(&at.file) },
+        "position": { "line": at.line.saturating_sub(1), "character": character },
+    })
+}
+
+/// UTF-16 offset of the 1-based character `column` in `line`
+fn utf16_offset(line: &str, column: usize) -> usize {
+    line.chars()
+        .take(column.saturating_sub(1))
+        .map(char::len_utf16)
+        .sum()
+}
+
+/// 1-based character column of the UTF-16 offset `units` in `line`
+fn char_column(line: &str, units: usize) -> usize {
+    let mut seen = 0;
+    for (i, c) in line.chars().enumerate() {
+        if seen >= units {
+            return i + 1;
+        }
+        seen += c.len_utf16();
+    }
+    line.chars().count() + 1
+}
+
+/// `Location`, `Location[]` or `LocationLink[]`, as definition and references return
+fn locations(result: &Value, lines: &mut FileLines) -> Vec<Location> {
+    let entries: Vec<&Value> = match result {
+        Value::Array(entries) => entries.iter().collect(),
+        Value::Null => Vec::new(),
+        single => vec![single],
+    };
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let uri = entry.get("targetUri").or_else(|| entry.get("uri"))?;
+            let range = entry
+                .get("targetSelectionRange")
+                .or_else(|| entry.get("range"))?;
+            let file = uri_to_path(uri.as_str()?)?;
+            let line = range["start"]["line"].as_u64()? as usize + 1;
+            let units = range["start"]["character"].as_u64()? as usize;
+            let column = lines
+                .line(&file, line)
+                .map_or(units + 1, |text| char_column(text, units));
+            Some(Location { file, line, column })
+        })
+        .collect()
+}
+
+/// Hover contents as plain text, code fences dropped
+fn hover_text(result: &Value) -> Option<String> {
+    let contents = result.get("contents")?;
+    let parts: Vec<&str> = match contents {
+        Value::String(text) => vec![text.as_str()],
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| p.as_str().or_else(|| p.get("value")?.as_str()))
+            .collect(),
+        markup => markup
+            .get("value")
+            .and_then(Value::as_str)
+            .into_iter()
+            .collect(),
+    };
+    let text: Vec<&str> = parts
+        .iter()
+        .flat_map(|part| part.lines())
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect();
+    let text = text.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(b as char)
+            }
+            // Windows drive letters stay readable: `file:///C:/...`
+            b':' => uri.push(':'),
+            _ => uri.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    uri
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        match (b, tail) {
+            (b'%', [hi, lo, ..]) => {
+                let hex = std::str::from_utf8(&[*hi, *lo]).ok()?.to_string();
+                bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `/C:/...` on Windows
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] if cfg!(windows) => path[1..].to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+fn failure(message: impl Into<String>) -> OracleError {
+    OracleError::Other(message.into())
+}
+
+fn exited() -> OracleError {
+    failure("rust-analyzer exited; is it installed? (rustup component add rust-analyzer)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framing_round_trip() {
+        let mut out = Vec::new();
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "result": "é" });
+        write_message(&mut out, &message).unwrap();
+        write_message(&mut out, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+        assert!(out.starts_with(b"Content-Length: 38\r\n\r\n{"));
+
+        let mut input = io::Cursor::new(out);
+        assert_eq!(read_message(&mut input).unwrap(), Some(message));
+        assert_eq!(read_message(&mut input).unwrap().unwrap()["method"], "exit");
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_uris_and_columns() {
+        let path = Path::new("/home/ada/my crate/src/lib.rs");
+        assert_eq!(path_to_uri(path), "file:///home/ada/my%20crate/src/lib.rs");
+        assert_eq!(uri_to_path(&path_to_uri(path)).as_deref(), Some(path));
+        // `𝓍` is two UTF-16 units
+        let line = "let 𝓍 = name;";
+        assert_eq!(utf16_offset(line, 9), 9);
+        assert_eq!(char_column(line, 9), 9);
+        assert_eq!(char_column(line, 4), 5);
+    }
+
+    #[test]
+    fn test_hover_and_locations() {
+        let hover = json!({ "contents": {
+            "kind": "markdown",
+            "value": "```rust\noracle::config\n```\n\n```rust\npub fn load() -> Settings\n```\n---\nLoads it",
+        }});
+        assert_eq!(
+            hover_text(&hover).unwrap(),
+            "oracle::config\n\npub fn load() -> Settings\n---\nLoads it"
+        );
+        assert_eq!(hover_text(&json!(null)), None);
+
+        let links = json!([{
+            "targetUri": "file:///nowhere/src/lib.rs",
+            "targetRange": { "start": { "line": 0, "character": 0 }, "end": { "line": 3, "character": 1 } },
+            "targetSelectionRange": { "start": { "line": 2, "character": 7 }, "end": { "line": 2, "character": 11 } },
+        }]);
+        let found = locations(&links, &mut FileLines::default());
+        assert_eq!(
+            found,
+            vec![Location {
+                file: PathBuf::from("/nowhere/src/lib.rs"),
+                line: 3,
+                column: 8,
+            }]
+        );
+        assert!(locations(&Value::Null, &mut FileLines::default()).is_empty());
+    }
+}
@@ -0,0 +1,590 @@
+//! Project analysis as plain data, without the terminal UI
+//!
+//! [`ProjectReport::analyze`] runs the same source and `cargo metadata` analysis the
+//! TUI does; [`ProjectReport::to_json`] and [`ProjectReport::to_markdown`] are what
+//! `oracle export` prints, and [`ProjectReport::from_json`] reads the JSON back. This module, `analyzer` and `crates_io` build without the
+//! `tui` feature:
+//!
+//! ```toml
+//! oracle-tui = { version = "0.1", default-features = false }
+//! ```
+//!
+//! ```no_run
+//! use oracle_lib::report::ProjectReport;
+//!
+//! let report = ProjectReport::analyze(std::path::Path::new("."), false)?;
+//! for item in &report.items {
+//!     println!("{} {}", item.kind(), item.qualified_name());
+//! }
+//! # Ok::<(), oracle_lib::OracleError>(())
+//! ```
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::cfg;
+use crate::analyzer::dead_code::count_identifiers;
+use crate::analyzer::edition::{self, Edition, EditionIssue};
+use crate::analyzer::env_vars::{self, EnvRead};
+use crate::analyzer::files::{self, FileMetrics};
+use crate::analyzer::imports::{self, ModuleImport};
+use crate::analyzer::module_tree::scope_path;
+use crate::analyzer::notes::{self, Note};
+use crate::analyzer::{AnalyzedItem, CrateInfo, DependencyAnalyzer, ModuleLayout, RustAnalyzer};
+use crate::error::{OracleError, Result};
+use crate::utils::{rust_files, SkippedPath};
+
+/// Items, tagged comments, `use` declarations and skipped files of one file or
+/// source tree
+#[derive(Debug, Clone, Default)]
+pub struct SourceAnalysis {
+    pub items: Vec<AnalyzedItem>,
+    pub notes: Vec<Note>,
+    pub imports: Vec<ModuleImport>,
+    /// `env!`, `option_env!` and `env::var` reads
+    pub env_reads: Vec<EnvRead>,
+    /// How often each identifier occurs in the code, for finding unused items
+    pub identifiers: HashMap<String, usize>,
+    /// Files that couldn't be read or parsed
+    pub skipped: Vec<SkippedPath>,
+    /// Lines, items, docs and `unsafe` per analyzed file
+    pub files: Vec<FileMetrics>,
+    /// Code the analyzer's edition doesn't accept
+    pub edition_issues: Vec<EditionIssue>,
+}
+
+/// Analyze a `.rs` file, a crate's `src/`, or (flat layout) every `.rs` file under `path`
+pub fn analyze_sources(analyzer: &RustAnalyzer, path: &Path) -> Result<SourceAnalysis> {
+    if is_rust_file(path) {
+        let source = std::fs::read_to_string(path)?;
+        let module = RustAnalyzer::derive_module_path(path);
+        let mut skipped = Vec::new();
+        let mut items = analyze_recovering(analyzer, &source, path, &module, &mut skipped);
+        cfg::apply_module_cfgs(&mut items, &cfg::module_cfgs(&source, &module));
+        let mut identifiers = HashMap::new();
+        count_identifiers(&source, &mut identifiers);
+        let mut files = vec![FileMetrics::scan(&source, path)];
+        files::attach_items(&mut files, &items);
+        return Ok(SourceAnalysis {
+            items,
+            notes: notes::scan(&source, path),
+            imports: imports::scan(&source, path, &module),
+            env_reads: env_vars::scan(&source, path),
+            identifiers,
+            skipped,
+            files,
+            edition_issues: edition::check(&source, path, analyzer.edition()),
+        });
+    }
+    Ok(analyze_directory(analyzer, source_files(analyzer, path)))
+}
+
+/// The items of the files [`analyze_sources`] reads, parsed one file at a time as the
+/// iterator is advanced, for callers that handle each item on its own: memory stays at
+/// one file's worth however large the tree. A file that doesn't parse is analyzed as
+/// [`analyze_sources`] does it, keeping the items around the error; unreadable files
+/// and unparsed lines are logged as warnings. None of the notes, imports or metrics
+/// are collected.
+pub fn source_items<'a>(
+    analyzer: &'a RustAnalyzer,
+    path: &Path,
+) -> impl Iterator<Item = AnalyzedItem> + 'a {
+    let (files, skipped) = if is_rust_file(path) {
+        let module = RustAnalyzer::derive_module_path(path);
+        (vec![(path.to_path_buf(), module)], Vec::new())
+    } else {
+        source_files(analyzer, path)
+    };
+    warn_skipped(&skipped);
+    files.into_iter().flat_map(
+        move |(file, module)| -> Box<dyn Iterator<Item = AnalyzedItem> + 'a> {
+            let source = match std::fs::read_to_string(&file) {
+                Ok(source) => source,
+                Err(e) => {
+                    warn_skipped(&[SkippedPath {
+                        path: file,
+                        reason: e.to_string(),
+                        lines: None,
+                    }]);
+                    return Box::new(std::iter::empty());
+                }
+            };
+            if let Ok(items) =
+                analyzer.analyze_source_iter(&source, Some(file.clone()), module.clone())
+            {
+                return Box::new(items);
+            }
+            let mut skipped = Vec::new();
+            let items = analyze_recovering(analyzer, &source, &file, &module, &mut skipped);
+            warn_skipped(&skipped);
+            Box::new(items.into_iter())
+        },
+    )
+}
+
+fn warn_skipped(skipped: &[SkippedPath]) {
+    for s in skipped {
+        tracing::warn!(path = %s.location(), reason = %s.reason, "skipped during analysis");
+    }
+}
+
+fn is_rust_file(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext == "rs")
+}
+
+/// The `.rs` files of a crate's `src/` (or, flat layout, under `path`) that aren't
+/// excluded, with their module paths, plus the paths that couldn't be walked. Files
+/// outside the directory that `#[path]` declarations load are included.
+fn source_files(
+    analyzer: &RustAnalyzer,
+    path: &Path,
+) -> (Vec<(PathBuf, Vec<String>)>, Vec<SkippedPath>) {
+    let src_path = path.join("src");
+    let root = if src_path.exists() {
+        src_path
+    } else {
+        path.to_path_buf()
+    };
+    if !root.is_dir() {
+        return (Vec::new(), Vec::new());
+    }
+    let layout = ModuleLayout::scan(&root, analyzer.edition());
+    let (mut files, skipped) = rust_files(&root);
+    let walked: HashSet<PathBuf> = files.iter().cloned().collect();
+    files.extend(
+        layout
+            .files()
+            .into_iter()
+            .filter(|f| !walked.contains(*f))
+            .map(Path::to_path_buf),
+    );
+    let files = files
+        .into_iter()
+        .filter(|f| !analyzer.is_excluded(f.strip_prefix(&root).unwrap_or(f)))
+        .map(|f| {
+            let module = layout
+                .module_path(&f)
+                .map(<[String]>::to_vec)
+                .unwrap_or_else(|| RustAnalyzer::derive_module_path(&f));
+            (f, module)
+        })
+        .collect();
+    (files, skipped)
+}
+
+/// The items of `source`, which is `module`, that parse; the lines of those that
+/// don't become warnings
+fn analyze_recovering(
+    analyzer: &RustAnalyzer,
+    source: &str,
+    path: &Path,
+    module: &[String],
+    skipped: &mut Vec<SkippedPath>,
+) -> Vec<AnalyzedItem> {
+    let (items, failures) =
+        analyzer.analyze_source_recovering(source, Some(path.to_path_buf()), module.to_vec());
+    skipped.extend(failures.into_iter().map(|failure| SkippedPath {
+        path: path.to_path_buf(),
+        reason: failure.message,
+        lines: Some(failure.lines),
+    }));
+    items
+}
+
+/// Analyze `files` and collect their tagged comments; unreadable files and code that
+/// doesn't parse become warnings, added to those of the walk
+fn analyze_directory(
+    analyzer: &RustAnalyzer,
+    (files, mut skipped): (Vec<(PathBuf, Vec<String>)>, Vec<SkippedPath>),
+) -> SourceAnalysis {
+    tracing::debug!(files = files.len(), "analyzing");
+    let mut items = Vec::new();
+    let mut notes = Vec::new();
+    let mut imports = Vec::new();
+    let mut env_reads = Vec::new();
+    let mut identifiers = HashMap::new();
+    let mut metrics = Vec::new();
+    let mut module_cfgs = Vec::new();
+    let mut edition_issues = Vec::new();
+    for (path, module) in files {
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                skipped.push(SkippedPath {
+                    path,
+                    reason: e.to_string(),
+                    lines: None,
+                });
+                continue;
+            }
+        };
+        notes.extend(notes::scan(&source, &path));
+        imports.extend(imports::scan(&source, &path, &module));
+        env_reads.extend(env_vars::scan(&source, &path));
+        count_identifiers(&source, &mut identifiers);
+        module_cfgs.extend(cfg::module_cfgs(&source, &module));
+        edition_issues.extend(edition::check(&source, &path, analyzer.edition()));
+        let file_items = analyze_recovering(analyzer, &source, &path, &module, &mut skipped);
+        let mut file = [FileMetrics::scan(&source, &path)];
+        files::attach_items(&mut file, &file_items);
+        metrics.extend(file);
+        items.extend(file_items);
+    }
+    // `#[cfg(..)] mod x;` is declared in one file and applies to another
+    cfg::apply_module_cfgs(&mut items, &module_cfgs);
+    warn_skipped(&skipped);
+    SourceAnalysis {
+        items,
+        notes,
+        imports,
+        env_reads,
+        identifiers,
+        skipped,
+        files: metrics,
+        edition_issues,
+    }
+}
+
+/// Version of the JSON schema written by [`ProjectReport::to_json`]. Adding a field
+/// keeps it; renaming or removing one bumps it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Everything oracle knows about a project, offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectReport {
+    /// [`SCHEMA_VERSION`] of the oracle that wrote the report
+    pub version: u32,
+    pub root: PathBuf,
+    /// Root package from `cargo metadata`, when the project has a manifest
+    pub crate_info: Option<CrateInfo>,
+    /// Dependency tree of the root package as `(name, depth)`, the root at depth 0
+    pub dependencies: Vec<(String, usize)>,
+    pub items: Vec<AnalyzedItem>,
+    pub notes: Vec<Note>,
+    pub skipped: Vec<SkippedPath>,
+    /// Code the crate's edition doesn't accept
+    #[serde(default)]
+    pub edition_issues: Vec<EditionIssue>,
+}
+
+impl ProjectReport {
+    /// Analyze a project directory or a single `.rs` file. A failing `cargo metadata`
+    /// only leaves `crate_info` and `dependencies` empty.
+    pub fn analyze(path: &Path, include_private: bool) -> Result<Self> {
+        Self::analyze_excluding(path, include_private, Vec::new())
+    }
+
+    /// [`ProjectReport::analyze`], skipping the `exclude` paths the way the
+    /// `analyzer.exclude` setting does
+    pub fn analyze_excluding(
+        path: &Path,
+        include_private: bool,
+        exclude: Vec<String>,
+    ) -> Result<Self> {
+        if !path.exists() {
+            return Err(OracleError::Other(format!(
+                "Path does not exist: {}",
+                path.display()
+            )));
+        }
+        let (mut crate_info, mut dependencies) = (None, Vec::new());
+        let manifest = path.join("Cargo.toml");
+        if manifest.exists() {
+            match DependencyAnalyzer::from_manifest(&manifest) {
+                Ok(analyzer) => {
+                    if let Some(root) = analyzer.root_package() {
+                        dependencies = analyzer.dependency_tree(&root.name);
+                        crate_info = Some(root);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "cargo metadata failed")
+                }
+            }
+        }
+        let edition = match crate_info {
+            Some(ref info) => Edition::parse(&info.edition),
+            None => Edition::from_manifest(&manifest),
+        };
+        let analyzer = RustAnalyzer::new()
+            .with_private(include_private)
+            .with_excludes(exclude)
+            .with_edition(edition.unwrap_or_default());
+        let SourceAnalysis {
+            items,
+            mut notes,
+            skipped,
+            edition_issues,
+            ..
+        } = analyze_sources(&analyzer, path)?;
+        notes::attach_items(&mut notes, &items);
+        Ok(Self {
+            version: SCHEMA_VERSION,
+            root: path.to_path_buf(),
+            crate_info,
+            dependencies,
+            items,
+            notes,
+            skipped,
+            edition_issues,
+        })
+    }
+
+    /// The whole report as pretty-printed JSON, items tagged by kind
+    pub fn to_json(&self) -> String {
+        let mut json = serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".into());
+        json.push('\n');
+        json
+    }
+
+    /// Read a report written by [`ProjectReport::to_json`]; one from a newer schema
+    /// is rejected rather than half-understood
+    pub fn from_json(json: &str) -> Result<Self> {
+        let report: Self =
+            serde_json::from_str(json).map_err(|e| OracleError::Parse(e.to_string()))?;
+        if report.version > SCHEMA_VERSION {
+            return Err(OracleError::Parse(format!(
+                "report schema version {} is newer than {}",
+                report.version, SCHEMA_VERSION
+            )));
+        }
+        Ok(report)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        items_to_markdown(&self.items)
+    }
+}
+
+/// One `##` section per kind, each item with its definition and docs
+pub fn items_to_markdown(items: &[AnalyzedItem]) -> String {
+    let mut md = String::new();
+    let mut kinds: Vec<&str> = items.iter().map(AnalyzedItem::kind).collect();
+    kinds.sort_unstable();
+    kinds.dedup();
+    for kind in kinds {
+        md.push_str(&format!("## {}\n\n", kind));
+        for item in items.iter().filter(|i| i.kind() == kind) {
+            md.push_str(&format!(
+                "### `{}`\n\n```rust\n{}\n```\n\n",
+                item.qualified_name(),
+                item.definition()
+            ));
+            if let Some(docs) = item.documentation() {
+                md.push_str(docs.trim());
+                md.push_str("\n\n");
+            }
+        }
+    }
+    md
+}
+
+/// Markdown for one module, written by `oracle doc`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModulePage {
+    /// Module path, e.g. `["analyzer", "parser"]`; empty for the crate root
+    pub module: Vec<String>,
+    /// File of the page relative to the first page's directory, e.g.
+    /// `analyzer/parser.md` under `analyzer.md`
+    pub file: PathBuf,
+    pub markdown: String,
+}
+
+/// One page for `module` and one for each module below it, each listing its
+/// submodules (linked to their pages) and its own items as [`items_to_markdown`]
+/// does. The first page is `module`'s, in `root_file`; a module's submodules go in
+/// a directory named after its page, so the files mirror the module tree. `None`
+/// if no analyzed item is in or below `module`.
+pub fn module_pages(
+    items: &[AnalyzedItem],
+    module: &[String],
+    root_file: &Path,
+) -> Option<Vec<ModulePage>> {
+    // `tests` and `#[cfg(test)]` modules are left out, as `api::surface` does
+    let cfg_test = |item: &AnalyzedItem| item.cfg().iter().any(|c| c == "test");
+    let test_modules: Vec<Vec<String>> = items
+        .iter()
+        .filter(|item| matches!(item, AnalyzedItem::Module(_)) && cfg_test(item))
+        .map(scope_path)
+        .collect();
+    let is_test = |item: &AnalyzedItem| {
+        let path = scope_path(item);
+        cfg_test(item)
+            || path.iter().any(|s| s == "tests")
+            || test_modules.iter().any(|m| path.starts_with(m))
+    };
+    let mut modules: BTreeSet<Vec<String>> = BTreeSet::new();
+    for item in items.iter().filter(|item| !is_test(item)) {
+        let mut path = scope_path(item);
+        while path.len() > module.len() && path.starts_with(module) {
+            modules.insert(path.clone());
+            path.pop();
+        }
+        if path == module {
+            modules.insert(path);
+        }
+    }
+    if !modules.contains(module) {
+        return None;
+    }
+
+    let docs_of = |path: &[String]| {
+        items.iter().find_map(|item| match item {
+            AnalyzedItem::Module(m)
+                if path.split_last().is_some_and(|(name, parent)| {
+                    *name == m.name && parent == &m.module_path[..]
+                }) =>
+            {
+                m.documentation.as_deref()
+            }
+            _ => None,
+        })
+    };
+    let file_of = |path: &[String]| {
+        let mut file = root_file.with_extension("");
+        for segment in &path[module.len()..] {
+            file.push(segment);
+        }
+        file.set_extension("md");
+        file
+    };
+
+    let mut pages = Vec::new();
+    for path in &modules {
+        let file = file_of(path);
+        let stem = file.file_stem().map(|s| s.to_string_lossy().into_owned());
+        let mut md = if path.is_empty() {
+            "# Crate root\n\n".to_string()
+        } else {
+            format!("# Module `{}`\n\n", path.join("::"))
+        };
+        if let Some(docs) = docs_of(path) {
+            md.push_str(docs.trim());
+            md.push_str("\n\n");
+        }
+        let children: Vec<&Vec<String>> = modules
+            .iter()
+            .filter(|m| m.len() == path.len() + 1 && m.starts_with(path))
+            .collect();
+        if !children.is_empty() {
+            md.push_str("## Modules\n\n");
+            for child in children {
+                let name = &child[path.len()];
+                md.push_str(&format!(
+                    "- [`{}`]({}/{}.md)",
+                    name,
+                    stem.as_deref().unwrap_or_default(),
+                    name
+                ));
+                if let Some(summary) = docs_of(child).and_then(|d| d.lines().next()) {
+                    md.push_str(&format!(" — {}", summary.trim()));
+                }
+                md.push('\n');
+            }
+            md.push('\n');
+        }
+        let own: Vec<AnalyzedItem> = items
+            .iter()
+            .filter(|i| i.module_path() == path.as_slice())
+            .filter(|i| !matches!(i, AnalyzedItem::Module(_)) && !is_test(i))
+            .cloned()
+            .collect();
+        md.push_str(&items_to_markdown(&own));
+        pages.push(ModulePage {
+            module: path.clone(),
+            file: file
+                .strip_prefix(root_file.parent().unwrap_or(Path::new("")))
+                .unwrap_or(&file)
+                .to_path_buf(),
+            markdown: md.trim_end().to_string() + "\n",
+        });
+    }
+    Some(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_single_file() {
+        let dir = std::env::temp_dir().join(format!("oracle-report-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("lib.rs");
+        std::fs::write(
+            &file,
+            "/// A point\npub struct Point { x: i32 }\n// TODO: more fields\npub fn origin() {}\n",
+        )
+        .unwrap();
+
+        let report = ProjectReport::analyze(&file, true).unwrap();
+        assert!(report.crate_info.is_none());
+        assert_eq!(report.items.len(), 2);
+        assert_eq!(report.notes.len(), 1);
+        let json = report.to_json();
+        assert!(json.contains("\"kind\": \"struct\""));
+        let read = ProjectReport::from_json(&json).unwrap();
+        assert_eq!(read.items.len(), 2);
+        assert_eq!(
+            read.items[0].qualified_name(),
+            report.items[0].qualified_name()
+        );
+        assert_eq!(read.notes, report.notes);
+        let newer = json.replacen("\"version\": 1", "\"version\": 99", 1);
+        assert!(ProjectReport::from_json(&newer).is_err());
+        assert!(report.to_markdown().starts_with("## fn\n\n### `"));
+        assert!(ProjectReport::analyze(&dir.join("missing"), true).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_source_items_recover_around_syntax_errors() {
+        let dir = std::env::temp_dir().join(format!("oracle-stream-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "pub mod net;\n").unwrap();
+        std::fs::write(
+            dir.join("src/net.rs"),
+            "pub fn before() {}\n\nfn broken() {\n    let = 1;\n}\n\npub fn after() {}\n",
+        )
+        .unwrap();
+
+        let analyzer = RustAnalyzer::new();
+        let names: Vec<String> = source_items(&analyzer, &dir)
+            .map(|item| item.qualified_name())
+            .collect();
+        assert_eq!(names, ["net", "net::before", "net::after"]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_module_pages() {
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                "/// Parsing and checks\npub mod analyzer {\n    /// Reads files\n    pub mod parser {\n        /// Parse one file\n        pub fn parse() {}\n        mod tests { fn parses() {} }\n    }\n    pub struct Analyzer;\n    #[cfg(test)]\n    mod fixtures { fn fixture() {} }\n}\npub fn main() {}",
+            )
+            .unwrap();
+        let module = vec!["analyzer".to_string()];
+        let pages = module_pages(&items, &module, Path::new("docs/analyzer.md")).unwrap();
+        let files: Vec<&Path> = pages.iter().map(|p| p.file.as_path()).collect();
+        assert_eq!(
+            files,
+            vec![Path::new("analyzer.md"), Path::new("analyzer/parser.md")]
+        );
+        let top = &pages[0].markdown;
+        assert!(top.starts_with("# Module `analyzer`\n\nParsing and checks\n"));
+        assert!(top.contains("- [`parser`](analyzer/parser.md) — Reads files"));
+        assert!(top.contains("### `analyzer::Analyzer`"));
+        assert!(!top.contains("parse()"));
+        assert!(pages[1].markdown.contains("Parse one file"));
+        // Test modules get no page, link or items
+        assert!(!top.contains("fixture"));
+        assert!(!pages[1].markdown.contains("tests"));
+
+        let all = module_pages(&items, &[], Path::new("api.md")).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].markdown.contains("(api/analyzer.md)"));
+        assert!(module_pages(&items, &["nope".to_string()], Path::new("x.md")).is_none());
+    }
+}
@@ -0,0 +1,385 @@
+//! `oracle new`: create a crate with its dependencies already chosen
+//!
+//! [`NewCrate`] is what gets written; [`wizard`] fills one in by asking on a terminal,
+//! with dependencies picked from crates.io search results.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table, Value};
+
+use crate::crates_io::CrateSearchResult;
+use crate::error::{OracleError, Result};
+
+pub const EDITIONS: [&str; 4] = ["2015", "2018", "2021", "2024"];
+pub const DEFAULT_EDITION: &str = "2021";
+/// Offered by the wizard; the usual choice for Rust crates
+pub const DEFAULT_LICENSE: &str = "MIT OR Apache-2.0";
+/// Search hits listed per query
+const SEARCH_RESULTS: usize = 8;
+
+/// A `[dependencies]` entry, written `name[@version][:feature,...]` on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewDependency {
+    pub name: String,
+    /// Requirement as written to the manifest; `None` until resolved against crates.io
+    pub version: Option<String>,
+    pub features: Vec<String>,
+}
+
+impl FromStr for NewDependency {
+    type Err = String;
+
+    fn from_str(spec: &str) -> std::result::Result<Self, Self::Err> {
+        let (rest, features) = match spec.split_once(':') {
+            Some((rest, features)) => (rest, split_list(features)),
+            None => (spec, Vec::new()),
+        };
+        let (name, version) = match rest.split_once('@') {
+            Some((name, version)) => (name, Some(version.trim().to_string())),
+            None => (rest, None),
+        };
+        let name = name.trim();
+        validate_name(name)?;
+        if version.as_deref() == Some("") {
+            return Err(format!("missing version after `{}@`", name));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            version,
+            features,
+        })
+    }
+}
+
+/// Everything `oracle new` writes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewCrate {
+    pub name: String,
+    /// `src/lib.rs` instead of `src/main.rs`
+    pub lib: bool,
+    pub edition: String,
+    /// SPDX expression, e.g. `MIT OR Apache-2.0`
+    pub license: Option<String>,
+    pub dependencies: Vec<NewDependency>,
+}
+
+impl NewCrate {
+    /// A binary crate named after the last component of `dir`
+    pub fn for_dir(dir: &Path) -> Self {
+        Self {
+            name: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            lib: false,
+            edition: DEFAULT_EDITION.to_string(),
+            license: None,
+            dependencies: Vec::new(),
+        }
+    }
+
+    pub fn manifest(&self) -> String {
+        let mut doc = DocumentMut::new();
+        let mut package = Table::new();
+        package.insert("name", value(&self.name));
+        package.insert("version", value("0.1.0"));
+        package.insert("edition", value(&self.edition));
+        if let Some(ref license) = self.license {
+            package.insert("license", value(license));
+        }
+        doc.insert("package", Item::Table(package));
+
+        let mut deps = Table::new();
+        for dep in &self.dependencies {
+            let version = dep.version.as_deref().unwrap_or("*");
+            if dep.features.is_empty() {
+                deps.insert(&dep.name, value(version));
+                continue;
+            }
+            let mut entry = InlineTable::new();
+            entry.insert("version", Value::from(version));
+            entry.insert("features", Value::Array(Array::from_iter(&dep.features)));
+            deps.insert(&dep.name, value(entry));
+        }
+        // Kept even when empty, like `cargo new`
+        doc.insert("dependencies", Item::Table(deps));
+        doc.to_string()
+    }
+
+    /// Create `dir` (if needed) with `Cargo.toml`, the crate root and `.gitignore`.
+    /// Refuses to overwrite an existing `Cargo.toml`. Returns the files written.
+    pub fn create(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        validate_name(&self.name).map_err(OracleError::Config)?;
+        let manifest = dir.join("Cargo.toml");
+        if manifest.exists() {
+            return Err(OracleError::Other(format!(
+                "{} already exists",
+                manifest.display()
+            )));
+        }
+        std::fs::create_dir_all(dir.join("src"))?;
+        let (root, source) = if self.lib {
+            ("lib.rs", format!("//! {}\n", self.name))
+        } else {
+            (
+                "main.rs",
+                "fn main() {\n    println!(\"Hello, world!\");\n}\n".to_string(),
+            )
+        };
+        let mut files = vec![
+            (manifest, self.manifest()),
+            (dir.join("src").join(root), source),
+        ];
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            files.push((gitignore, "/target\n".to_string()));
+        }
+        for (path, contents) in &files {
+            std::fs::write(path, contents)?;
+        }
+        Ok(files.into_iter().map(|(path, _)| path).collect())
+    }
+}
+
+/// A valid package name: ASCII letters, digits, `-` and `_`, not starting with a digit
+pub fn validate_name(name: &str) -> std::result::Result<(), String> {
+    if name.is_empty() {
+        return Err("the crate name is empty".to_string());
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(format!("`{}` starts with a digit", name));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '-' && *c != '_')
+    {
+        return Err(format!("`{}` contains `{}`", name, c));
+    }
+    Ok(())
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split([',', ' '])
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Ask for the name, crate type, edition, license and dependencies, starting from
+/// `defaults`. `search` and `features` look up crates.io; offline they return nothing
+/// and dependencies are typed as `name@version`.
+pub fn wizard(
+    defaults: NewCrate,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+    search: &dyn Fn(&str) -> Vec<CrateSearchResult>,
+    features: &dyn Fn(&str, &str) -> Vec<String>,
+) -> Result<NewCrate> {
+    let mut prompt = Prompt { input, out };
+    let mut new = defaults;
+    loop {
+        let name = prompt.ask("Crate name", &new.name)?;
+        match validate_name(&name) {
+            Ok(()) => {
+                new.name = name;
+                break;
+            }
+            Err(e) => prompt.say(&format!("  {}", e))?,
+        }
+    }
+    let kind = prompt.ask(
+        "Binary or library (bin/lib)",
+        if new.lib { "lib" } else { "bin" },
+    )?;
+    new.lib = kind.starts_with('l');
+    loop {
+        let edition = prompt.ask(&format!("Edition ({})", EDITIONS.join("/")), &new.edition)?;
+        if EDITIONS.contains(&edition.as_str()) {
+            new.edition = edition;
+            break;
+        }
+        prompt.say(&format!("  unknown edition {}", edition))?;
+    }
+    let license = new.license.as_deref().unwrap_or(DEFAULT_LICENSE);
+    let license = prompt.ask("License (- for none)", license)?;
+    new.license = (license != "-").then_some(license);
+
+    for dep in &new.dependencies {
+        prompt.say(&format!("  + {}", describe(dep)))?;
+    }
+    loop {
+        let query = prompt.ask("Add a dependency (search crates.io, empty to finish)", "")?;
+        if query.is_empty() {
+            break;
+        }
+        let Some(mut dep) = pick_dependency(&mut prompt, &query, search)? else {
+            continue;
+        };
+        let version = dep.version.clone().unwrap_or_default();
+        let available = features(&dep.name, &version);
+        if !available.is_empty() {
+            prompt.say(&format!("  features: {}", available.join(", ")))?;
+        }
+        let chosen = prompt.ask(
+            "  Enable features (comma-separated)",
+            &dep.features.join(","),
+        )?;
+        dep.features = split_list(&chosen);
+        prompt.say(&format!("  + {}", describe(&dep)))?;
+        new.dependencies.retain(|d| d.name != dep.name);
+        new.dependencies.push(dep);
+    }
+    Ok(new)
+}
+
+/// Search for `query` and let the user pick a hit; a `name@version` query is taken as is
+fn pick_dependency(
+    prompt: &mut Prompt,
+    query: &str,
+    search: &dyn Fn(&str) -> Vec<CrateSearchResult>,
+) -> Result<Option<NewDependency>> {
+    if query.contains(['@', ':']) {
+        return match query.parse::<NewDependency>() {
+            Ok(dep) => Ok(Some(dep)),
+            Err(e) => prompt.say(&format!("  {}", e)).map(|_| None),
+        };
+    }
+    let hits: Vec<CrateSearchResult> = search(query).into_iter().take(SEARCH_RESULTS).collect();
+    if hits.is_empty() {
+        prompt.say("  no results; type name@version to add a crate directly")?;
+        return Ok(None);
+    }
+    for (i, hit) in hits.iter().enumerate() {
+        let description = hit.description.as_deref().unwrap_or("");
+        prompt.say(&format!(
+            "  {}. {} {}  {}",
+            i + 1,
+            hit.name,
+            hit.version,
+            description.lines().next().unwrap_or("")
+        ))?;
+    }
+    let choice = prompt.ask("  Pick a number (empty to search again)", "1")?;
+    let Some(hit) = choice
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| hits.get(n.checked_sub(1)?))
+    else {
+        return Ok(None);
+    };
+    Ok(Some(NewDependency {
+        name: hit.name.clone(),
+        version: Some(hit.version.clone()),
+        features: Vec::new(),
+    }))
+}
+
+fn describe(dep: &NewDependency) -> String {
+    let mut s = dep.name.clone();
+    if let Some(ref version) = dep.version {
+        s.push_str(&format!(" {}", version));
+    }
+    if !dep.features.is_empty() {
+        s.push_str(&format!(" [{}]", dep.features.join(", ")));
+    }
+    s
+}
+
+struct Prompt<'a> {
+    input: &'a mut dyn BufRead,
+    out: &'a mut dyn Write,
+}
+
+impl Prompt<'_> {
+    /// Print `question [default]: ` and read a line; an empty one is the default
+    fn ask(&mut self, question: &str, default: &str) -> Result<String> {
+        if default.is_empty() {
+            write!(self.out, "{}: ", question)?;
+        } else {
+            write!(self.out, "{} [{}]: ", question, default)?;
+        }
+        self.out.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(OracleError::Other(
+                "input ended before the crate was set up".into(),
+            ));
+        }
+        let answer = line.trim();
+        Ok(if answer.is_empty() { default } else { answer }.to_string())
+    }
+
+    fn say(&mut self, line: &str) -> Result<()> {
+        writeln!(self.out, "{}", line)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_spec_and_manifest() {
+        let dep: NewDependency = "serde@1.0:derive,rc".parse().unwrap();
+        assert_eq!(dep.version.as_deref(), Some("1.0"));
+        assert_eq!(dep.features, vec!["derive", "rc"]);
+        assert!("9lives".parse::<NewDependency>().is_err());
+        assert!("anyhow@".parse::<NewDependency>().is_err());
+
+        let new = NewCrate {
+            license: Some(DEFAULT_LICENSE.into()),
+            dependencies: vec![dep, "anyhow@1".parse().unwrap()],
+            ..NewCrate::for_dir(Path::new("/tmp/demo"))
+        };
+        assert_eq!(
+            new.manifest(),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\
+             license = \"MIT OR Apache-2.0\"\n\n[dependencies]\n\
+             serde = { version = \"1.0\", features = [\"derive\", \"rc\"] }\nanyhow = \"1\"\n"
+        );
+    }
+
+    #[test]
+    fn test_wizard_and_create() {
+        let search = |q: &str| {
+            vec![CrateSearchResult {
+                name: q.to_string(),
+                version: "1.0.210".into(),
+                description: Some("A framework".into()),
+                downloads: None,
+            }]
+        };
+        let features = |_: &str, _: &str| vec!["derive".to_string(), "rc".to_string()];
+        let answers = "bad name\nhello\nlib\n2030\n2024\n-\nserde\n1\nderive\nrand@0.8\n\n\n";
+        let mut out = Vec::new();
+        let new = wizard(
+            NewCrate::for_dir(Path::new("demo")),
+            &mut answers.as_bytes(),
+            &mut out,
+            &search,
+            &features,
+        )
+        .unwrap();
+        assert_eq!(new.name, "hello");
+        assert!(new.lib);
+        assert_eq!(new.edition, "2024");
+        assert_eq!(new.license, None);
+        let names: Vec<&str> = new.dependencies.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "rand"]);
+        assert_eq!(new.dependencies[0].features, vec!["derive"]);
+        let transcript = String::from_utf8(out).unwrap();
+        assert!(transcript.contains("1. serde 1.0.210  A framework"));
+        assert!(transcript.contains("features: derive, rc"));
+
+        let dir = std::env::temp_dir().join(format!("oracle-new-{}", std::process::id()));
+        let files = new.create(&dir).unwrap();
+        assert_eq!(files.len(), 3);
+        assert!(dir.join("src/lib.rs").exists());
+        assert!(new.create(&dir).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
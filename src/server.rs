@@ -0,0 +1,463 @@
+//! `oracle --serve`: the project's index over JSON-RPC 2.0, for editor extensions
+//! and other tools
+//!
+//! Each request and each response is one line of JSON, on stdin and stdout or on
+//! every connection to a Unix socket (`--socket PATH`). The project is analyzed once
+//! at start and again on `reload`; queries answer from that index.
+//!
+//! | Method         | Params                                | Result                              |
+//! |----------------|---------------------------------------|-------------------------------------|
+//! | `items`        | `kind`, `module`, `offset`, `limit`   | `{total, items}` of item summaries  |
+//! | `item`         | `name` (required), `kind`             | every item with that name, in full  |
+//! | `search`       | `query` (required), `limit`           | item summaries, as the search bar   |
+//! | `dependencies` |                                       | `{crate, dependencies, tree}`       |
+//! | `dependency`   | `name` (required)                     | the dependency and its tree depth   |
+//! | `reload`       |                                       | `{items}` after analyzing again     |
+//! | `shutdown`     |                                       | `null`, then the stream is closed   |
+//!
+//! ```text
+//! → {"jsonrpc":"2.0","id":1,"method":"item","params":{"name":"Settings::load"}}
+//! ← {"jsonrpc":"2.0","id":1,"result":[{"kind":"fn","name":"config::Settings::load",...}]}
+//! ```
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use serde_json::{json, Value};
+
+use crate::analyzer::{AnalyzedItem, Query};
+use crate::error::Result;
+use crate::report::ProjectReport;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Start of the range JSON-RPC leaves to servers: unknown item, failed reload
+const SERVER_ERROR: i64 = -32000;
+
+/// Summaries returned by `items` and `search` when the request gives no `limit`
+pub const DEFAULT_LIMIT: usize = 200;
+
+/// The analyzed project, shared by every connection
+pub struct Server {
+    root: PathBuf,
+    include_private: bool,
+    /// Paths skipped, as in the `analyzer.exclude` setting
+    exclude: Vec<String>,
+    report: RwLock<ProjectReport>,
+}
+
+/// What to send back for one request line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    /// `None` for notifications, which get no response
+    pub response: Option<String>,
+    /// The request was `shutdown`: close the stream after responding
+    pub shutdown: bool,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+type RpcResult = std::result::Result<Value, RpcError>;
+
+impl Server {
+    /// Analyze the project at `root`, skipping the `exclude` paths
+    pub fn new(root: &Path, include_private: bool, exclude: Vec<String>) -> Result<Self> {
+        let report = ProjectReport::analyze_excluding(root, include_private, exclude.clone())?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            include_private,
+            exclude,
+            report: RwLock::new(report),
+        })
+    }
+
+    /// Answer one line of JSON
+    pub fn handle(&self, line: &str) -> Reply {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => {
+                return Reply {
+                    response: Some(response(
+                        Value::Null,
+                        Err(RpcError::new(PARSE_ERROR, e.to_string())),
+                    )),
+                    shutdown: false,
+                }
+            }
+        };
+        let method = request.get("method").and_then(Value::as_str);
+        let result = match method {
+            Some(method) => self.call(method, request.get("params").unwrap_or(&Value::Null)),
+            None => Err(RpcError::new(INVALID_REQUEST, "Request has no method")),
+        };
+        Reply {
+            response: request.get("id").map(|id| response(id.clone(), result)),
+            shutdown: method == Some("shutdown"),
+        }
+    }
+
+    /// Answer requests from `input` until it ends or asks for `shutdown`
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let reply = self.handle(&line);
+            if let Some(response) = reply.response {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+            if reply.shutdown {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Listen on a Unix socket at `path` (replacing a stale one), each connection on
+    /// its own thread; runs until the process is stopped
+    #[cfg(unix)]
+    pub fn serve_socket(&self, path: &Path) -> Result<()> {
+        use std::io::BufReader;
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::UnixListener;
+
+        if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        tracing::info!(socket = %path.display(), "serving");
+        std::thread::scope(|scope| {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "connection failed");
+                        continue;
+                    }
+                };
+                scope.spawn(move || {
+                    if let Err(e) = self.serve(BufReader::new(&stream), &stream) {
+                        tracing::warn!(error = %e, "connection closed");
+                    }
+                });
+            }
+        });
+        Ok(())
+    }
+
+    fn call(&self, method: &str, params: &Value) -> RpcResult {
+        match method {
+            "items" => self.items(params),
+            "item" => self.item(params),
+            "search" => self.search(params),
+            "dependencies" => self.dependencies(),
+            "dependency" => self.dependency(params),
+            "reload" => self.reload(),
+            "shutdown" => Ok(Value::Null),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method {}", method),
+            )),
+        }
+    }
+
+    fn items(&self, params: &Value) -> RpcResult {
+        let report = self.report.read().unwrap_or_else(|e| e.into_inner());
+        let kind = str_param(params, "kind");
+        let module = str_param(params, "module").map(|m| m.trim_start_matches("crate::"));
+        let matching: Vec<&AnalyzedItem> = report
+            .items
+            .iter()
+            .filter(|item| kind.map_or(true, |k| item.kind() == k))
+            .filter(|item| module.map_or(true, |m| item.module_path().join("::") == m))
+            .collect();
+        let offset = usize_param(params, "offset")?.unwrap_or(0);
+        let limit = usize_param(params, "limit")?.unwrap_or(DEFAULT_LIMIT);
+        let items: Vec<Value> = matching
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|item| summary(item, &self.root))
+            .collect();
+        Ok(json!({ "total": matching.len(), "items": items }))
+    }
+
+    fn item(&self, params: &Value) -> RpcResult {
+        let name = required(params, "name")?;
+        let kind = str_param(params, "kind");
+        let report = self.report.read().unwrap_or_else(|e| e.into_inner());
+        let found: Vec<Value> = report
+            .items
+            .iter()
+            .filter(|item| kind.map_or(true, |k| item.kind() == k))
+            .filter(|item| {
+                let qualified = item.qualified_name();
+                item.name() == name
+                    || qualified == name
+                    || qualified.ends_with(&format!("::{}", name))
+            })
+            .map(|item| detail(item, &self.root))
+            .collect();
+        if found.is_empty() {
+            return Err(RpcError::new(
+                SERVER_ERROR,
+                format!("No item named {}", name),
+            ));
+        }
+        Ok(Value::Array(found))
+    }
+
+    /// Free text and `key:value` filters as in the search bar; exact name matches
+    /// come first
+    fn search(&self, params: &Value) -> RpcResult {
+        let query = Query::parse(required(params, "query")?);
+        let limit = usize_param(params, "limit")?.unwrap_or(DEFAULT_LIMIT);
+        let text = query.text();
+        let report = self.report.read().unwrap_or_else(|e| e.into_inner());
+        let mut found: Vec<&AnalyzedItem> =
+            report.items.iter().filter(|i| query.matches(i)).collect();
+        found.sort_by_key(|item| !item.name().eq_ignore_ascii_case(&text));
+        let items: Vec<Value> = found
+            .iter()
+            .take(limit)
+            .map(|item| summary(item, &self.root))
+            .collect();
+        Ok(Value::Array(items))
+    }
+
+    fn dependencies(&self) -> RpcResult {
+        let report = self.report.read().unwrap_or_else(|e| e.into_inner());
+        let tree: Vec<Value> = report
+            .dependencies
+            .iter()
+            .map(|(name, depth)| json!({ "name": name, "depth": depth }))
+            .collect();
+        let info = report.crate_info.as_ref();
+        Ok(json!({
+            "crate": info.map(|c| json!({
+                "name": c.name,
+                "version": c.version,
+                "edition": c.edition,
+                "rust_version": c.rust_version,
+            })),
+            "dependencies": info.map_or(&[][..], |c| &c.dependencies),
+            "tree": tree,
+        }))
+    }
+
+    fn dependency(&self, params: &Value) -> RpcResult {
+        let name = required(params, "name")?;
+        let report = self.report.read().unwrap_or_else(|e| e.into_inner());
+        let direct = report
+            .crate_info
+            .as_ref()
+            .and_then(|c| c.dependencies.iter().find(|d| d.name == name));
+        // The tree lists a crate once, possibly under another dependency first
+        let depth = direct.map(|_| 1).or_else(|| {
+            report
+                .dependencies
+                .iter()
+                .filter(|(dep, depth)| dep == name && *depth > 0)
+                .map(|(_, depth)| *depth)
+                .min()
+        });
+        if direct.is_none() && depth.is_none() {
+            return Err(RpcError::new(
+                SERVER_ERROR,
+                format!("{} is not a dependency", name),
+            ));
+        }
+        Ok(json!({ "name": name, "direct": direct, "depth": depth }))
+    }
+
+    fn reload(&self) -> RpcResult {
+        let report = ProjectReport::analyze_excluding(
+            &self.root,
+            self.include_private,
+            self.exclude.clone(),
+        )
+        .map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))?;
+        let items = report.items.len();
+        *self.report.write().unwrap_or_else(|e| e.into_inner()) = report;
+        Ok(json!({ "items": items }))
+    }
+}
+
+fn response(id: Value, result: RpcResult) -> String {
+    let body = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": e.code, "message": e.message },
+        }),
+    };
+    body.to_string()
+}
+
+fn str_param<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params.get(key).and_then(Value::as_str)
+}
+
+fn required<'a>(params: &'a Value, key: &str) -> std::result::Result<&'a str, RpcError> {
+    str_param(params, key)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing string param {}", key)))
+}
+
+fn usize_param(params: &Value, key: &str) -> std::result::Result<Option<usize>, RpcError> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(|n| Some(n as usize))
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("{} must be a count", key))),
+    }
+}
+
+/// Kind, qualified name and where it is, the file relative to the project root
+fn summary(item: &AnalyzedItem, root: &Path) -> Value {
+    let location = item.source_location();
+    json!({
+        "kind": item.kind(),
+        "name": item.qualified_name(),
+        "file": location
+            .and_then(|l| l.file.as_deref())
+            .map(|f| f.strip_prefix(root).unwrap_or(f)),
+        "line": location.and_then(|l| l.line),
+    })
+}
+
+/// [`summary`] with the definition, docs and the item as `oracle export` writes it
+fn detail(item: &AnalyzedItem, root: &Path) -> Value {
+    let mut value = summary(item, root);
+    value["definition"] = Value::String(item.definition());
+    value["docs"] = json!(item.documentation());
+    value["item"] = serde_json::to_value(item).unwrap_or(Value::Null);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn server(test: &str) -> (Server, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("oracle-{}-{}", test, std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join("src/lib.rs"),
+            "pub mod config {\n    /// Loads it\n    pub fn load() {}\n    pub struct Config;\n}\npub fn loader() {}\n",
+        )
+        .unwrap();
+        (Server::new(&dir, false, Vec::new()).unwrap(), dir)
+    }
+
+    fn call(server: &Server, request: &str) -> Value {
+        serde_json::from_str(&server.handle(request).response.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_queries() {
+        let (server, dir) = server("serve-queries");
+
+        let items = call(
+            &server,
+            r#"{"jsonrpc":"2.0","id":1,"method":"items","params":{"kind":"fn","limit":1}}"#,
+        );
+        assert_eq!(items["id"], 1);
+        assert_eq!(items["result"]["total"], 2);
+        assert_eq!(items["result"]["items"].as_array().unwrap().len(), 1);
+
+        let item = call(
+            &server,
+            r#"{"jsonrpc":"2.0","id":2,"method":"item","params":{"name":"config::load"}}"#,
+        );
+        let found = &item["result"][0];
+        assert_eq!(found["name"], "config::load");
+        assert_eq!(found["file"], "src/lib.rs");
+        assert_eq!(found["line"], 3);
+        assert_eq!(found["docs"], "Loads it");
+        assert_eq!(found["item"]["kind"], "fn");
+
+        let search = call(
+            &server,
+            r#"{"jsonrpc":"2.0","id":3,"method":"search","params":{"query":"load kind:fn"}}"#,
+        );
+        let names: Vec<&str> = search["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["config::load", "loader"]);
+
+        let missing = call(
+            &server,
+            r#"{"jsonrpc":"2.0","id":4,"method":"item","params":{"name":"nope"}}"#,
+        );
+        assert_eq!(missing["error"]["code"], SERVER_ERROR);
+        let no_params = call(&server, r#"{"jsonrpc":"2.0","id":5,"method":"search"}"#);
+        assert_eq!(no_params["error"]["code"], INVALID_PARAMS);
+        let unknown = call(&server, r#"{"jsonrpc":"2.0","id":6,"method":"hover"}"#);
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+        let garbage = call(&server, "{not json");
+        assert_eq!(garbage["error"]["code"], PARSE_ERROR);
+        assert!(server
+            .handle(r#"{"jsonrpc":"2.0","method":"reload"}"#)
+            .response
+            .is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_serve_stops_at_shutdown() {
+        let (server, dir) = server("serve-shutdown");
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"reload"}"#,
+            "\n\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":3,"method":"items"}"#,
+            "\n",
+        );
+        let mut out = Vec::new();
+        server.serve(Cursor::new(input), &mut out).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""items":4"#));
+        assert!(lines[1].contains(r#""result":null"#));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_excluded_paths_stay_out_after_reload() {
+        let dir = std::env::temp_dir().join(format!("oracle-serve-exclude-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("generated")).unwrap();
+        std::fs::write(dir.join("kept.rs"), "pub fn kept() {}\n").unwrap();
+        std::fs::write(dir.join("generated/out.rs"), "pub fn generated() {}\n").unwrap();
+        let server = Server::new(&dir, false, vec!["generated".to_string()]).unwrap();
+        let items = r#"{"jsonrpc":"2.0","id":1,"method":"items"}"#;
+        assert_eq!(call(&server, items)["result"]["total"], 1);
+        let reload = call(&server, r#"{"jsonrpc":"2.0","id":2,"method":"reload"}"#);
+        assert_eq!(reload["result"]["items"], 1);
+        assert_eq!(call(&server, items)["result"]["total"], 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
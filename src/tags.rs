@@ -0,0 +1,226 @@
+//! Symbol index files for editors: `tags` in the universal-ctags format for vim and
+//! other ctags readers, `TAGS` for Emacs
+//!
+//! Every item with a file and line becomes a tag, impl methods included, named as in
+//! the source and scoped by its module or the type it implements. ctags entries find
+//! their line with a search pattern, as ctags writes them, so they survive lines
+//! moving until the next `oracle tags`; the line number is kept in the `line:` field.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::usage::base_type_name;
+use crate::analyzer::{AnalyzedItem, SourceLocation};
+
+/// Default file names, in the project directory
+pub const CTAGS_FILE: &str = "tags";
+pub const ETAGS_FILE: &str = "TAGS";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+    /// Kind letter of universal-ctags' Rust parser, e.g. `s` for a struct
+    pub kind: char,
+    /// `module:config`, or `implementation:Settings` for methods
+    pub scope: Option<String>,
+}
+
+/// Tags of the items that have a location, sorted by name, file and line
+pub fn tags(items: &[AnalyzedItem]) -> Vec<Tag> {
+    let mut tags = Vec::new();
+    for item in items {
+        let module = item.module_path().join("::");
+        let scope = (!module.is_empty()).then(|| format!("module:{}", module));
+        match item {
+            AnalyzedItem::Impl(im) => {
+                let self_ty = base_type_name(&im.self_ty).unwrap_or(&im.self_ty);
+                tags.extend(tag(self_ty, 'c', &im.source_location, scope));
+                let scope = format!("implementation:{}", self_ty);
+                for method in &im.methods {
+                    tags.extend(tag(
+                        &method.name,
+                        'P',
+                        &method.source_location,
+                        Some(scope.clone()),
+                    ));
+                }
+            }
+            _ => {
+                let location = item.source_location();
+                tags.extend(location.and_then(|l| tag(item.name(), kind(item), l, scope)));
+            }
+        }
+    }
+    tags.sort_by(|a, b| (&a.name, &a.file, a.line).cmp(&(&b.name, &b.file, b.line)));
+    tags
+}
+
+fn tag(name: &str, kind: char, location: &SourceLocation, scope: Option<String>) -> Option<Tag> {
+    Some(Tag {
+        name: name.to_string(),
+        file: location.file.clone()?,
+        line: location.line?,
+        kind,
+        scope,
+    })
+}
+
+fn kind(item: &AnalyzedItem) -> char {
+    match item {
+        AnalyzedItem::Function(_) => 'f',
+        AnalyzedItem::Struct(_) => 's',
+        AnalyzedItem::Enum(_) => 'g',
+        AnalyzedItem::Trait(_) => 'i',
+        AnalyzedItem::Impl(_) => 'c',
+        AnalyzedItem::Module(_) => 'n',
+        AnalyzedItem::TypeAlias(_) => 't',
+        AnalyzedItem::Const(_) => 'C',
+        AnalyzedItem::Static(_) => 'v',
+    }
+}
+
+/// Contents of the tagged files, read once each
+#[derive(Default)]
+struct Sources(HashMap<PathBuf, Option<String>>);
+
+impl Sources {
+    fn get(&mut self, file: &Path) -> Option<&str> {
+        self.0
+            .entry(file.to_path_buf())
+            .or_insert_with(|| std::fs::read_to_string(file).ok())
+            .as_deref()
+    }
+
+    /// Byte offset and text of 1-based line `line`
+    fn line(&mut self, file: &Path, line: usize) -> Option<(usize, &str)> {
+        let content = self.get(file)?;
+        let mut offset = 0;
+        for text in content.split_inclusive('\n').take(line.checked_sub(1)?) {
+            offset += text.len();
+        }
+        let text = content[offset..].lines().next()?;
+        Some((offset, text))
+    }
+}
+
+/// `file` relative to `base`, the directory of the tags file, when it's inside it
+fn relative<'a>(file: &'a Path, base: &Path) -> &'a Path {
+    file.strip_prefix(base).unwrap_or(file)
+}
+
+/// A `tags` file: the pseudo-tags universal-ctags writes, then one sorted line per tag
+pub fn to_ctags(tags: &[Tag], base: &Path) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to lines/\n",
+    );
+    out.push_str("!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/\n");
+    out.push_str("!_TAG_PROGRAM_NAME\toracle\t//\n");
+    let _ = writeln!(
+        out,
+        "!_TAG_PROGRAM_VERSION\t{}\t//",
+        env!("CARGO_PKG_VERSION")
+    );
+    let mut sources = Sources::default();
+    for tag in tags {
+        let address = match sources.line(&tag.file, tag.line) {
+            Some((_, text)) => format!("/^{}$/", text.replace('\\', "\\\\").replace('/', "\\/")),
+            None => tag.line.to_string(),
+        };
+        let _ = write!(
+            out,
+            "{}\t{}\t{};\"\t{}\tline:{}",
+            tag.name,
+            relative(&tag.file, base).display(),
+            address,
+            tag.kind,
+            tag.line
+        );
+        if let Some(scope) = &tag.scope {
+            let _ = write!(out, "\t{}", scope);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// A `TAGS` file: one section per source file, each tag with the start of its line
+/// up to the name, the line number and the line's byte offset
+pub fn to_etags(tags: &[Tag], base: &Path) -> String {
+    let mut by_file: BTreeMap<&Path, Vec<&Tag>> = BTreeMap::new();
+    for tag in tags {
+        by_file.entry(&tag.file).or_default().push(tag);
+    }
+    let mut sources = Sources::default();
+    let mut out = String::new();
+    for (file, mut tags) in by_file {
+        tags.sort_by_key(|tag| tag.line);
+        let mut section = String::new();
+        for tag in tags {
+            let (offset, text) = sources.line(file, tag.line).unwrap_or((0, ""));
+            let prefix = text
+                .find(tag.name.as_str())
+                .map_or(text, |at| &text[..at + tag.name.len()]);
+            let _ = writeln!(
+                section,
+                "{}\x7f{}\x01{},{}",
+                prefix, tag.name, tag.line, offset
+            );
+        }
+        let _ = write!(
+            out,
+            "\x0c\n{},{}\n{}",
+            relative(file, base).display(),
+            section.len(),
+            section
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_ctags_and_etags() {
+        let dir = std::env::temp_dir().join(format!("oracle-tags-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("config.rs");
+        let source = "/// Settings\npub struct Settings;\n\nimpl Settings {\n    pub fn load(path: &str) -> Self { Settings }\n}\n";
+        std::fs::write(&file, source).unwrap();
+        let items = RustAnalyzer::new()
+            .analyze_file_with_module(&file, vec!["config".to_string()])
+            .unwrap();
+        let tags = tags(&items);
+        let names: Vec<(&str, char)> = tags.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+        assert_eq!(names, [("Settings", 's'), ("Settings", 'c'), ("load", 'P')]);
+
+        let ctags = to_ctags(&tags, &dir);
+        assert!(ctags.starts_with("!_TAG_FILE_FORMAT\t2\t"));
+        assert!(ctags.contains(
+            "Settings\tconfig.rs\t/^pub struct Settings;$/;\"\ts\tline:2\tmodule:config\n"
+        ));
+        assert!(ctags.contains(
+            "load\tconfig.rs\t/^    pub fn load(path: &str) -> Self { Settings }$/;\"\tP\tline:5\timplementation:Settings\n"
+        ));
+
+        let etags = to_etags(&tags, &dir);
+        let section = [
+            "pub struct Settings\x7fSettings\x012,13\n",
+            "impl Settings\x7fSettings\x014,35\n",
+            "    pub fn load\x7fload\x015,51\n",
+        ]
+        .concat();
+        assert_eq!(
+            etags,
+            format!("\x0c\nconfig.rs,{}\n{}", section.len(), section)
+        );
+        assert!(source[51..].starts_with("    pub fn load"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
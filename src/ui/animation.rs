@@ -1,9 +1,16 @@
 //! Animation system for smooth UI transitions
 
+use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
+/// Frame rate cap used unless the config sets `ui.max_fps`
+pub const DEFAULT_MAX_FPS: u32 = 60;
+/// Input poll interval while nothing is moving
+const IDLE_POLL: Duration = Duration::from_millis(50);
+
 /// Easing functions for smooth animations
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Easing {
     #[default]
     Linear,
@@ -293,13 +300,25 @@ impl Pulse {
 }
 
 /// Collection of UI animation states
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct AnimationState {
     pub list_scroll: SmoothScroll,
     pub inspector_scroll: SmoothScroll,
     pub search_cursor: Pulse,
     pub selection_highlight: f64, // 0.0-1.0 for selection animation
     pub transition_progress: f64, // For tab transitions
+    pub frame: u64,               // Frames since start, drives spinners
+    /// Off: transitions never start and spinners hold still
+    pub enabled: bool,
+    /// Shape of the selection and tab transitions
+    pub easing: Easing,
+    pub max_fps: u32,
+}
+
+impl Default for AnimationState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AnimationState {
@@ -310,11 +329,59 @@ impl AnimationState {
             search_cursor: Pulse::new().with_speed(0.15),
             selection_highlight: 1.0,
             transition_progress: 1.0,
+            frame: 0,
+            enabled: true,
+            easing: Easing::default(),
+            max_fps: DEFAULT_MAX_FPS,
+        }
+    }
+
+    /// Apply the `ui` settings; turning animations off finishes any in flight
+    pub fn configure(&mut self, enabled: bool, easing: Easing, max_fps: u32) {
+        self.enabled = enabled;
+        self.easing = easing;
+        self.max_fps = max_fps.clamp(1, 240);
+        if !enabled {
+            self.selection_highlight = 1.0;
+            self.transition_progress = 1.0;
+            let (list, inspector) = (self.list_scroll.target, self.inspector_scroll.target);
+            self.list_scroll.set_immediate(list);
+            self.inspector_scroll.set_immediate(inspector);
         }
     }
 
+    /// How long the main loop may wait for input before drawing again: one frame
+    /// at `max_fps` while animating, otherwise the idle interval (or one frame, if
+    /// the cap is lower than that)
+    pub fn poll_interval(&self) -> Duration {
+        let frame = Duration::from_secs(1) / self.max_fps.max(1);
+        if self.is_animating() {
+            frame
+        } else {
+            IDLE_POLL.max(frame)
+        }
+    }
+
+    /// Current frame of a braille spinner (for background work indicators), or of
+    /// `|/-\` where braille can't be drawn
+    pub fn spinner(&self, ascii: bool) -> &'static str {
+        const FRAMES: [&str; 8] = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+        const ASCII_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+        let frames: &[&str] = if ascii { &ASCII_FRAMES } else { &FRAMES };
+        if !self.enabled {
+            return frames[0];
+        }
+        frames[(self.frame / 2 % frames.len() as u64) as usize]
+    }
+
+    /// Selection highlight progress after easing, 0.0 (just selected) to 1.0
+    pub fn selection_intensity(&self) -> f64 {
+        self.easing.apply(self.selection_highlight)
+    }
+
     /// Update all animations (call each frame)
     pub fn update(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
         self.list_scroll.update();
         self.inspector_scroll.update();
         self.search_cursor.update();
@@ -332,12 +399,16 @@ impl AnimationState {
 
     /// Trigger selection animation
     pub fn on_selection_change(&mut self) {
-        self.selection_highlight = 0.0;
+        if self.enabled {
+            self.selection_highlight = 0.0;
+        }
     }
 
     /// Trigger tab transition animation
     pub fn on_tab_change(&mut self) {
-        self.transition_progress = 0.0;
+        if self.enabled {
+            self.transition_progress = 0.0;
+        }
     }
 
     /// Check if any animation is active
@@ -377,4 +448,25 @@ mod tests {
 
         assert!((scroll.position_f64() - 100.0).abs() < 1.0);
     }
+
+    #[test]
+    fn test_configure_and_poll_interval() {
+        let mut anim = AnimationState::new();
+        assert_eq!(anim.poll_interval(), IDLE_POLL);
+        anim.on_selection_change();
+        assert_eq!(anim.poll_interval(), Duration::from_secs(1) / 60);
+
+        anim.configure(true, Easing::EaseIn, 10);
+        assert_eq!(anim.poll_interval(), Duration::from_millis(100));
+        assert!(anim.selection_intensity() < anim.selection_highlight + f64::EPSILON);
+
+        anim.configure(false, Easing::Linear, 0);
+        assert!(!anim.is_animating());
+        anim.on_selection_change();
+        anim.on_tab_change();
+        assert!(!anim.is_animating());
+        assert_eq!(anim.poll_interval(), Duration::from_secs(1));
+        anim.frame = 5;
+        assert_eq!(anim.spinner(true), "|");
+    }
 }
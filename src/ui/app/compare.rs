@@ -0,0 +1,176 @@
+//! Comparison block: second project's item list and side-by-side inspectors.
+
+use crate::analyzer::{diff_status, AnalyzedItem, DiffStatus};
+use crate::ui::inspector::InspectorPanel;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{block::BorderType, Block, Borders, List, ListItem, Paragraph, Widget},
+};
+
+use super::types::Focus;
+use super::OracleUi;
+
+/// Second project shown next to the current one (`oracle <path> <other>`)
+#[derive(Clone, Copy)]
+pub struct CompareView<'a> {
+    /// Display name of the current project
+    pub left_name: &'a str,
+    /// Display name of the compared project
+    pub name: &'a str,
+    pub items: &'a [AnalyzedItem],
+    /// Compared project's items matching the current search, tab and scope
+    pub filtered: &'a [&'a AnalyzedItem],
+    /// Counterpart of the selected item in `filtered`
+    pub selected: Option<usize>,
+}
+
+impl<'a> OracleUi<'a> {
+    /// Marker span for an item's status relative to the other project
+    pub(super) fn diff_marker(&self, status: DiffStatus) -> Span<'static> {
+        let style = match status {
+            DiffStatus::Same => self.theme.style_dim(),
+            DiffStatus::Changed => self.theme.style_keyword(),
+            DiffStatus::Missing => self.theme.style_string(),
+        };
+        Span::styled(format!("{} ", status.marker()), style)
+    }
+
+    /// Compared project's list on top, both inspectors side by side below
+    pub(super) fn render_compare(&self, compare: &CompareView<'a>, area: Rect, buf: &mut Buffer) {
+        let vert = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(area);
+        let horz = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(vert[1]);
+
+        self.render_compare_list(compare, vert[0], buf);
+
+        let left = InspectorPanel::new(self.theme)
+            .item(self.selected_item)
+            .all_items(self.all_items_impl_lookup)
+            .usage_index(self.usage_index)
+            .doc_example_selected(self.doc_example_selected)
+            .focused(self.focus == Focus::Inspector)
+            .scroll(self.inspector_scroll)
+            .report_max_scroll(self.inspector_max_scroll)
+            .report_top_section(self.inspector_top_section)
+            .code_layout(self.code_wrap, self.code_hscroll)
+            .method_sort(self.method_sort)
+            .sections(self.inspector_sections)
+            .raw(self.inspector_raw)
+            .coverage(self.coverage)
+            .doc_links(self.doc_links)
+            .cache(self.inspector_cache);
+        left.render(horz[0], buf);
+
+        let counterpart = compare
+            .selected
+            .and_then(|i| compare.filtered.get(i).copied());
+        match counterpart {
+            Some(item) => {
+                // Scrolls together with the left inspector
+                let right = InspectorPanel::new(self.theme)
+                    .item(Some(item))
+                    .all_items(Some(compare.items))
+                    .focused(self.focus == Focus::Inspector)
+                    .scroll(self.inspector_scroll)
+                    .report_max_scroll(self.inspector_max_scroll)
+                    .code_layout(self.code_wrap, self.code_hscroll)
+                    .method_sort(self.method_sort)
+                    .sections(self.inspector_sections)
+                    .raw(self.inspector_raw)
+                    .coverage(self.coverage)
+                    .cache(self.inspector_cache);
+                right.render(horz[1], buf);
+            }
+            None => self.render_compare_missing(compare, horz[1], buf),
+        }
+    }
+
+    fn render_compare_list(&self, compare: &CompareView<'a>, area: Rect, buf: &mut Buffer) {
+        let visible_height = area.height.saturating_sub(2) as usize;
+        let scroll_offset = match compare.selected {
+            Some(sel) if visible_height > 0 && sel >= visible_height => sel + 1 - visible_height,
+            _ => 0,
+        };
+
+        let rows: Vec<ListItem> = compare
+            .filtered
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(visible_height)
+            .map(|(idx, item)| {
+                let is_selected = Some(idx) == compare.selected;
+                let prefix = if is_selected {
+                    self.theme.glyph("▸ ", "> ")
+                } else {
+                    "  "
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(prefix, self.theme.style_accent()),
+                    self.diff_marker(diff_status(item, self.items)),
+                    Span::styled(format!("{:6} ", item.kind()), self.theme.style_dim()),
+                    Span::styled(item.qualified_name(), self.theme.style_normal()),
+                ]))
+                .style(if is_selected {
+                    self.theme.style_selected()
+                } else {
+                    Style::default()
+                })
+            })
+            .collect();
+
+        let title = format!(
+            " {} ({}/{}) · vs {} ",
+            compare.name,
+            compare.filtered.len(),
+            compare.items.len(),
+            compare.left_name
+        );
+        let list = List::new(rows).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(self.theme.style_border())
+                .style(Style::default().bg(self.theme.bg_panel))
+                .title(title),
+        );
+        Widget::render(list, area, buf);
+    }
+
+    fn render_compare_missing(&self, compare: &CompareView<'a>, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.style_border())
+            .style(Style::default().bg(self.theme.bg_panel))
+            .title(format!(" {}{} ", self.theme.glyph("◇ ", ""), compare.name));
+        let text = match self.selected_item {
+            Some(item) => vec![
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("  ", self.theme.style_dim()),
+                    Span::styled(
+                        format!("{} {}", item.kind(), item.qualified_name()),
+                        self.theme.style_accent(),
+                    ),
+                ]),
+                Line::from(Span::styled(
+                    format!("  not present in {}", compare.name),
+                    self.theme.style_muted(),
+                )),
+            ],
+            None => vec![
+                Line::from(""),
+                Line::from(Span::styled("  No item selected", self.theme.style_muted())),
+            ],
+        };
+        Paragraph::new(text).block(block).render(area, buf);
+    }
+}
@@ -1,4 +1,6 @@
-//! Header block: ORACLE logo + live metrics (items, crates, target size, creator).
+//! Header block: ORACLE logo + live metrics (items, crates, edition, target size, creator),
+//! the open projects when there are several, or a crate › module › item
+//! breadcrumb while browsing an installed crate.
 
 use crate::utils::format_bytes;
 
@@ -9,6 +11,7 @@ use ratatui::{
     widgets::{Paragraph, Widget},
 };
 
+use super::types::Tab;
 use super::OracleUi;
 
 const ORACLE_ART: [&str; 6] = [
@@ -36,20 +39,36 @@ impl<'a> OracleUi<'a> {
         );
         let types_count = struct_count + enum_count + trait_count;
         let line1 = format!(
-            "📦 {} types · {} fns · {} mods",
-            types_count, fn_count, mod_count
+            "{}{} types · {} fns · {} mods",
+            self.theme.glyph("📦 ", ""),
+            types_count,
+            fn_count,
+            mod_count
         );
+        let crates_icon = self.theme.glyph("📚 ", "");
         let crates_count = self.dependency_tree.len();
-        let line2 = if let Some(bytes) = self.target_size_bytes {
+        let mut line2 = if let Some(bytes) = self.target_size_bytes {
             format!(
-                "📚 {} crates · target {}",
+                "{}{} crates · target {}",
+                crates_icon,
                 crates_count,
                 format_bytes(bytes)
             )
         } else {
-            format!("📚 {} crates", crates_count)
+            format!("{}{} crates", crates_icon, crates_count)
         };
-        let line3 = "👤 created by yashksaini-coder";
+        // Parsing follows the edition, so it's always in view
+        if let Some(info) = self.crate_info {
+            line2.push_str(&format!(" · edition {}", info.edition));
+        }
+        if self.offline {
+            line2.push_str(self.theme.glyph(" · ⊘ offline", " · offline"));
+        }
+        let breadcrumb = self.breadcrumb();
+        let line3 = breadcrumb.clone().unwrap_or_else(|| {
+            format!("{}created by yashksaini-coder", self.theme.glyph("👤 ", ""))
+        });
+        let project_tabs = self.project_tabs_line();
 
         let header_chunks = Layout::default()
             .direction(Direction::Horizontal)
@@ -74,14 +93,73 @@ impl<'a> OracleUi<'a> {
             ])
             .split(tagline_area);
 
-        let lines_content = [line1, line2, line3.to_string()];
+        let lines_content = [line1, line2, line3];
         for (i, content) in lines_content.iter().enumerate() {
             if let Some(rect) = tagline_rows.get(i) {
-                let line = Line::from(Span::styled(content.as_str(), self.theme.style_dim()));
+                let style = if i == 2 && breadcrumb.is_some() {
+                    self.theme.style_accent()
+                } else {
+                    self.theme.style_dim()
+                };
+                let line = match project_tabs {
+                    Some(ref tabs) if i == 2 && breadcrumb.is_none() => tabs.clone(),
+                    _ => Line::from(Span::styled(content.as_str(), style)),
+                };
                 Paragraph::new(line)
                     .alignment(Alignment::Right)
                     .render(*rect, buf);
             }
         }
     }
+
+    /// `[ oracle ] serde  tokio` with the shown project highlighted, when more
+    /// than one is open
+    fn project_tabs_line(&self) -> Option<Line<'static>> {
+        let (names, active) = self.project_tabs;
+        if names.len() < 2 {
+            return None;
+        }
+        let mut spans = Vec::new();
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled("  ", self.theme.style_muted()));
+            }
+            if i == active {
+                spans.push(Span::styled(
+                    format!("[ {} ]", name),
+                    self.theme.style_accent_bold(),
+                ));
+            } else {
+                spans.push(Span::styled(name.clone(), self.theme.style_dim()));
+            }
+        }
+        Some(Line::from(spans))
+    }
+
+    /// `📍 crate › module › item` for the open installed crate, `None` elsewhere
+    fn breadcrumb(&self) -> Option<String> {
+        if self.current_tab != Tab::Crates {
+            return None;
+        }
+        let krate = self.selected_installed_crate?;
+        let mut parts = vec![krate.name.as_str()];
+        // The selected item's own module path is deeper than (or equal to) the scope
+        match self.selected_item {
+            Some(item) => {
+                parts.extend(item.module_path().iter().map(String::as_str));
+                parts.push(item.name());
+            }
+            None => parts.extend(
+                self.module_scope
+                    .unwrap_or_default()
+                    .iter()
+                    .map(String::as_str),
+            ),
+        }
+        Some(format!(
+            "{}{}",
+            self.theme.glyph("📍 ", ""),
+            parts.join(self.theme.glyph(" › ", " > "))
+        ))
+    }
 }
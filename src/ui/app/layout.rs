@@ -1,7 +1,11 @@
 //! Layout constants and helpers (frame chunks, tabs rect for hit testing).
 
+use std::rc::Rc;
+
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
+use crate::ui::search::{COMPLETION_ROWS, COMPLETION_VISIBLE};
+
 /// Layout constants for the main frame.
 pub const HEADER_HEIGHT: u16 = 6;
 pub const STATUS_HEIGHT: u16 = 3;
@@ -27,8 +31,31 @@ pub fn content_area(area: Rect, border: bool) -> Rect {
     }
 }
 
+/// Returns the search bar Rect for a given full frame area.
+pub fn search_rect_for_area(area: Rect) -> Rect {
+    top_row(body_columns(area)[0])
+}
+
 /// Returns the tabs bar Rect for a given full frame area (for mouse hit testing).
 pub fn tabs_rect_for_area(area: Rect) -> Option<Rect> {
+    Some(top_row(body_columns(area)[2]))
+}
+
+/// Returns the completion dropdown Rect for `count` candidates under `search`,
+/// clipped to `area`.
+pub fn completion_rect(search: Rect, count: usize, area: Rect) -> Rect {
+    let visible = count.min(COMPLETION_VISIBLE) as u16;
+    let y = search.y + search.height;
+    Rect {
+        x: search.x + 2,
+        y,
+        width: search.width.saturating_sub(4).min(60),
+        height: (visible * COMPLETION_ROWS + 2).min((area.y + area.height).saturating_sub(y)),
+    }
+}
+
+/// Left column, divider and right column of the body.
+fn body_columns(area: Rect) -> Rc<[Rect]> {
     let content = content_area(area, true);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -38,19 +65,20 @@ pub fn tabs_rect_for_area(area: Rect) -> Option<Rect> {
             Constraint::Length(STATUS_HEIGHT),
         ])
         .split(content);
-    let body = chunks[1];
-    let left_div_right = Layout::default()
+    Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
             Constraint::Ratio(1, 3),
             Constraint::Length(1),
             Constraint::Ratio(2, 3),
         ])
-        .split(body);
-    let right_column = left_div_right[2];
-    let right_split = Layout::default()
+        .split(chunks[1])
+}
+
+/// The 3-row bar at the top of a column (search bar or tabs).
+fn top_row(column: Rect) -> Rect {
+    Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Min(6)])
-        .split(right_column);
-    Some(right_split[0])
+        .split(column)[0]
 }
@@ -1,6 +1,8 @@
 //! List block: items list, dependencies list, installed crate items list.
 
-use crate::analyzer::Visibility;
+use crate::analyzer::{diff_status, AnalyzedItem, ProcMacro, TreeRow, Visibility};
+use crate::ui::dependency_view::health_style;
+use crate::utils::format_number;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -12,54 +14,42 @@ use ratatui::{
     },
 };
 
-use super::types::{Focus, Tab};
+use super::rows::{kind_column, scroll_offset};
+use super::types::{Focus, RegistrySearch, Tab};
 use super::OracleUi;
 
 impl<'a> OracleUi<'a> {
     pub(super) fn render_list(&self, area: Rect, buf: &mut Buffer) {
-        if self.current_tab == Tab::Crates {
-            if self.selected_installed_crate.is_some() {
-                self.render_installed_crates_list(area, buf);
-            } else {
-                self.render_dependencies_list(area, buf);
+        if self.current_tab == Tab::Crates && self.selected_installed_crate.is_none() {
+            match self.registry_search {
+                Some(search) => self.render_registry_results(search, area, buf),
+                None => self.render_dependencies_list(area, buf),
             }
             return;
         }
+        if let Some(rows) = self.module_tree_rows {
+            self.render_module_tree(rows, area, buf);
+            return;
+        }
+        if self.current_tab == Tab::Crates {
+            self.render_installed_crates_list(area, buf);
+            return;
+        }
 
         let selected = self.list_selected;
-        let highlight_intensity = self.animation.map(|a| a.selection_highlight).unwrap_or(1.0);
+        let highlight_intensity = self.animation.map_or(1.0, |a| a.selection_intensity());
         let visible_height = area.height.saturating_sub(2) as usize;
         let total_items = self.filtered_items.len();
-        let scroll_offset = if let Some(sel) = selected {
-            if visible_height == 0 {
-                0
-            } else if sel >= visible_height {
-                sel.saturating_sub(visible_height - 1)
-            } else {
-                0
-            }
-        } else {
-            0
-        };
+        let scroll_offset = scroll_offset(selected, visible_height);
 
         let items: Vec<ListItem> = self
             .filtered_items
-            .iter()
-            .enumerate()
-            .skip(scroll_offset)
-            .take(visible_height)
+            .window(scroll_offset, visible_height)
             .map(|(idx, item)| {
-                let kind_style = match item.kind() {
-                    "fn" => self.theme.style_function(),
-                    "struct" | "enum" | "type" => self.theme.style_type(),
-                    "trait" => self.theme.style_keyword(),
-                    "mod" => self.theme.style_accent(),
-                    "const" | "static" => self.theme.style_string(),
-                    _ => self.theme.style_dim(),
-                };
                 let is_selected = Some(idx) == selected;
                 let base_style = if is_selected {
-                    if highlight_intensity < 1.0 {
+                    // Flash bold for the first half of the (eased) transition
+                    if highlight_intensity < 0.5 {
                         self.theme.style_selected().add_modifier(Modifier::BOLD)
                     } else {
                         self.theme.style_selected()
@@ -67,24 +57,12 @@ impl<'a> OracleUi<'a> {
                 } else {
                     Style::default()
                 };
-                let prefix = if is_selected { "▸ " } else { "  " };
-                let vis = item
-                    .visibility()
-                    .map(|v| match v {
-                        Visibility::Public => "●",
-                        Visibility::Crate => "◐",
-                        _ => "○",
-                    })
-                    .unwrap_or("○");
-                let display_name = item.name().to_string();
-                ListItem::new(Line::from(vec![
-                    Span::styled(prefix, self.theme.style_accent()),
-                    Span::styled(vis, self.theme.style_dim()),
-                    Span::raw(" "),
-                    Span::styled(format!("{:6} ", item.kind()), kind_style),
-                    Span::styled(display_name, self.theme.style_normal()),
-                ]))
-                .style(base_style)
+                let diff = self
+                    .compare
+                    .as_ref()
+                    .map(|compare| self.diff_marker(diff_status(item, compare.items)));
+                let name = Span::styled(item.name(), self.theme.style_normal());
+                ListItem::new(self.item_row(item, is_selected, diff, vec![name])).style(base_style)
             })
             .collect();
 
@@ -99,18 +77,30 @@ impl<'a> OracleUi<'a> {
         } else {
             String::new()
         };
+        let mut scope = self
+            .module_scope
+            .map(|s| format!("· in {} ", s.join("::")))
+            .unwrap_or_default();
+        if let Some(file) = self.file_scope.and_then(|f| f.file_name()) {
+            scope.push_str(&format!("· in {} ", file.to_string_lossy()));
+        }
+        if let Some(view) = self.active_view {
+            scope.push_str(&format!("· view: {} ", view));
+        }
         let title = if self.search_input.is_empty() {
             format!(
-                " Items ({}){} ",
+                " Items ({}){} {}",
                 self.filtered_items.len(),
-                scroll_indicator
+                scroll_indicator,
+                scope
             )
         } else {
             format!(
-                " Items ({}/{}){} ",
+                " Items ({}/{}){} {}",
                 self.filtered_items.len(),
                 self.items.len(),
-                scroll_indicator
+                scroll_indicator,
+                scope
             )
         };
         let list_area = Rect {
@@ -123,10 +113,11 @@ impl<'a> OracleUi<'a> {
                     .borders(Borders::ALL)
                     .border_style(border_style)
                     .style(Style::default().bg(self.theme.bg_panel))
-                    .title(title),
+                    .title(title)
+                    .title_bottom(self.filtering_marker()),
             )
             .highlight_style(self.theme.style_selected())
-            .highlight_symbol("▸ ");
+            .highlight_symbol(self.theme.glyph("▸ ", "> "));
         Widget::render(list, list_area, buf);
 
         if total_items > visible_height {
@@ -144,6 +135,145 @@ impl<'a> OracleUi<'a> {
         }
     }
 
+    /// Collapsible module tree: one row per module with its file and item counts
+    fn render_module_tree(&self, rows: &[TreeRow], area: Rect, buf: &mut Buffer) {
+        let visible_height = area.height.saturating_sub(2) as usize;
+        let selected = self.module_tree_selected.min(rows.len().saturating_sub(1));
+        let scroll_offset = scroll_offset(Some(selected), visible_height);
+
+        let items: Vec<ListItem> = rows
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(visible_height)
+            .map(|(idx, row)| {
+                let is_selected = idx == selected;
+                let marker = if !row.has_children {
+                    "  "
+                } else if row.expanded {
+                    "▾ "
+                } else {
+                    self.theme.glyph("▸ ", "> ")
+                };
+                let in_scope = self.module_scope.is_some_and(|s| s == row.path.as_slice())
+                    || (self.module_scope.is_none() && row.path.is_empty());
+                let name_style = if in_scope {
+                    self.theme.style_accent_bold()
+                } else if row.depth == 0 {
+                    self.theme.style_accent()
+                } else {
+                    self.theme.style_normal()
+                };
+                let file = row
+                    .file
+                    .as_ref()
+                    .and_then(|f| f.file_name())
+                    .map(|f| format!(" {}", f.to_string_lossy()))
+                    .unwrap_or_default();
+                let counts = if row.item_count == row.total_count {
+                    format!(" ({})", row.total_count)
+                } else {
+                    format!(" ({}/{})", row.item_count, row.total_count)
+                };
+                let line = Line::from(vec![
+                    Span::raw("  ".repeat(row.depth)),
+                    Span::styled(marker, self.theme.style_dim()),
+                    Span::styled(row.name.clone(), name_style),
+                    Span::styled(counts, self.theme.style_number()),
+                    Span::styled(file, self.theme.style_muted()),
+                ]);
+                let style = if is_selected {
+                    self.theme.style_selected()
+                } else {
+                    Style::default()
+                };
+                ListItem::new(line).style(style)
+            })
+            .collect();
+
+        let border_style = if self.focus == Focus::List {
+            self.theme.style_border_focused()
+        } else {
+            self.theme.style_border()
+        };
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .style(Style::default().bg(self.theme.bg_panel))
+                .title(" Module Tree (Enter scope · ←/→ fold · m close) "),
+        );
+        Widget::render(list, area, buf);
+    }
+
+    /// Bottom-border marker while the typed query is still being applied
+    fn filtering_marker(&self) -> Line<'static> {
+        if self.filtering {
+            Line::styled(" filtering… ", self.theme.style_dim())
+        } else {
+            Line::default()
+        }
+    }
+
+    /// One item row: selection marker, optional diff marker, visibility dot, kind column, name spans
+    fn item_row(
+        &self,
+        item: &'a AnalyzedItem,
+        is_selected: bool,
+        diff: Option<Span<'a>>,
+        name: Vec<Span<'a>>,
+    ) -> Line<'a> {
+        let kind_style = match item.kind() {
+            "fn" => self.theme.style_function(),
+            "struct" | "enum" | "type" => self.theme.style_type(),
+            "trait" => self.theme.style_keyword(),
+            "mod" => self.theme.style_accent(),
+            "const" | "static" => self.theme.style_string(),
+            _ => self.theme.style_dim(),
+        };
+        let prefix = if is_selected {
+            self.theme.glyph("▸ ", "> ")
+        } else {
+            "  "
+        };
+        let vis = item
+            .visibility()
+            .map(|v| match v {
+                Visibility::Public => self.theme.glyph("●", "+"),
+                Visibility::Crate => self.theme.glyph("◐", "~"),
+                _ => self.theme.glyph("○", "-"),
+            })
+            .unwrap_or(self.theme.glyph("○", "-"));
+        let mut spans = Vec::with_capacity(5 + name.len());
+        spans.push(Span::styled(prefix, self.theme.style_accent()));
+        spans.extend(diff);
+        spans.extend([
+            Span::styled(vis, self.theme.style_dim()),
+            Span::raw(" "),
+            Span::styled(kind_column(item.kind()), kind_style),
+        ]);
+        spans.extend(name);
+        if let AnalyzedItem::Function(f) = item {
+            if f.returns_reference() {
+                spans.push(Span::styled(" &", self.theme.style_dim()));
+            }
+            // A proc macro is called by its macro name, not the function's
+            if let Some(m) = ProcMacro::of(f) {
+                spans.push(Span::styled(
+                    format!("  {}", m.usage()),
+                    self.theme.style_keyword(),
+                ));
+            }
+        }
+        if let Some(c) = self.coverage.and_then(|data| data.for_item(item)) {
+            spans.push(Span::styled(
+                format!("  {:.0}%", c.percent()),
+                self.theme.style_coverage(c.percent()),
+            ));
+        }
+        Line::from(spans)
+    }
+
     pub(super) fn render_dependencies_list(&self, area: Rect, buf: &mut Buffer) {
         let border_style = if self.focus == Focus::List {
             self.theme.style_border_focused()
@@ -177,10 +307,14 @@ impl<'a> OracleUi<'a> {
             };
             vec![ListItem::new(Line::from(vec![
                 Span::styled(
-                    if is_selected { "▸ " } else { "  " },
+                    if is_selected {
+                        self.theme.glyph("▸ ", "> ")
+                    } else {
+                        "  "
+                    },
                     self.theme.style_accent(),
                 ),
-                Span::styled("○ ", self.theme.style_muted()),
+                Span::styled(self.theme.glyph("○ ", "- "), self.theme.style_muted()),
                 Span::styled("No Cargo project", self.theme.style_dim()),
             ]))
             .style(style)]
@@ -193,11 +327,22 @@ impl<'a> OracleUi<'a> {
             };
             vec![ListItem::new(Line::from(vec![
                 Span::styled(
-                    if is_selected { "▸ " } else { "  " },
+                    if is_selected {
+                        self.theme.glyph("▸ ", "> ")
+                    } else {
+                        "  "
+                    },
                     self.theme.style_accent(),
                 ),
-                Span::styled("○ ", self.theme.style_muted()),
-                Span::styled("No matches for search", self.theme.style_dim()),
+                Span::styled(self.theme.glyph("○ ", "- "), self.theme.style_muted()),
+                Span::styled(
+                    if self.search_input.starts_with('@') {
+                        "Enter searches crates.io"
+                    } else {
+                        "No matches for search"
+                    },
+                    self.theme.style_dim(),
+                ),
             ]))
             .style(style)]
         } else {
@@ -214,15 +359,31 @@ impl<'a> OracleUi<'a> {
                     } else {
                         Style::default()
                     };
-                    ListItem::new(Line::from(vec![
+                    let mut spans = vec![
                         Span::styled(
-                            if is_selected { "▸ " } else { "  " },
+                            if is_selected {
+                                self.theme.glyph("▸ ", "> ")
+                            } else {
+                                "  "
+                            },
                             self.theme.style_accent(),
                         ),
-                        Span::styled("📦 ", self.theme.style_dim()),
+                        Span::styled(self.theme.glyph("📦 ", ""), self.theme.style_dim()),
                         Span::styled(name.clone(), self.theme.style_normal()),
-                    ]))
-                    .style(style)
+                    ];
+                    if let Some(health) = self.health_scores.and_then(|h| h.get(name)) {
+                        spans.push(Span::styled(
+                            format!("  {}", health.score),
+                            health_style(self.theme, health),
+                        ));
+                    }
+                    if let Some(latest) = self.upgrade_marks.and_then(|m| m.get(name)) {
+                        spans.push(Span::styled(
+                            format!(" {} {}", self.theme.glyph("↑", "^"), latest),
+                            self.theme.style_accent(),
+                        ));
+                    }
+                    ListItem::new(Line::from(spans)).style(style)
                 })
                 .collect()
         };
@@ -232,7 +393,12 @@ impl<'a> OracleUi<'a> {
         } else {
             String::new()
         };
-        let title = format!(" Crates ({}){} ", total, scroll_info);
+        let sort = if self.sort_crates_by_health {
+            " by health"
+        } else {
+            ""
+        };
+        let title = format!(" Crates ({}){}{} ", total, sort, scroll_info);
         let list_area = Rect {
             width: area.width.saturating_sub(1),
             ..area
@@ -246,7 +412,7 @@ impl<'a> OracleUi<'a> {
                     .title(title),
             )
             .highlight_style(self.theme.style_selected())
-            .highlight_symbol("▸ ");
+            .highlight_symbol(self.theme.glyph("▸ ", "> "));
         Widget::render(list, list_area, buf);
 
         if total > visible_height {
@@ -264,6 +430,88 @@ impl<'a> OracleUi<'a> {
         }
     }
 
+    /// crates.io search results: name, newest version, downloads and description
+    fn render_registry_results(&self, search: &RegistrySearch, area: Rect, buf: &mut Buffer) {
+        let border_style = if self.focus == Focus::List {
+            self.theme.style_border_focused()
+        } else {
+            self.theme.style_border()
+        };
+        let visible_height = area.height.saturating_sub(2) as usize;
+        let offset = scroll_offset(self.list_selected, visible_height);
+        let items: Vec<ListItem> = if search.results.is_empty() {
+            let text = if search.loading {
+                "Searching crates.io..."
+            } else {
+                "No crates found"
+            };
+            vec![ListItem::new(Line::from(Span::styled(
+                format!("  {}", text),
+                self.theme.style_dim(),
+            )))]
+        } else {
+            search
+                .results
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(visible_height)
+                .map(|(i, result)| {
+                    let is_selected = Some(i) == self.list_selected;
+                    let mut spans = vec![
+                        Span::styled(
+                            if is_selected {
+                                self.theme.glyph("▸ ", "> ")
+                            } else {
+                                "  "
+                            },
+                            self.theme.style_accent(),
+                        ),
+                        Span::styled(result.name.clone(), self.theme.style_normal()),
+                        Span::styled(format!(" {}", result.version), self.theme.style_muted()),
+                    ];
+                    if let Some(downloads) = result.downloads {
+                        spans.push(Span::styled(
+                            format!(
+                                "  {} {}",
+                                self.theme.glyph("⬇", "dl"),
+                                format_number(downloads)
+                            ),
+                            self.theme.style_accent(),
+                        ));
+                    }
+                    if let Some(ref description) = result.description {
+                        spans.push(Span::styled(
+                            format!("  {}", description.lines().next().unwrap_or("")),
+                            self.theme.style_dim(),
+                        ));
+                    }
+                    let style = if is_selected {
+                        self.theme.style_selected()
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(Line::from(spans)).style(style)
+                })
+                .collect()
+        };
+        let spinner = if search.loading { " searching" } else { "" };
+        let title = format!(
+            " crates.io: {} ({}){} · [a] add to Cargo.toml ",
+            search.query,
+            search.results.len(),
+            spinner
+        );
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .style(Style::default().bg(self.theme.bg_panel))
+                .title(title),
+        );
+        Widget::render(list, area, buf);
+    }
+
     pub(super) fn render_installed_crates_list(&self, area: Rect, buf: &mut Buffer) {
         let selected = self.list_selected;
         let border_style = if self.focus == Focus::List {
@@ -275,65 +523,26 @@ impl<'a> OracleUi<'a> {
 
         if let Some(crate_info) = self.selected_installed_crate {
             let total_items = self.installed_crate_items.len();
-            let scroll_offset = if let Some(sel) = selected {
-                if visible_height == 0 {
-                    0
-                } else if sel >= visible_height {
-                    sel.saturating_sub(visible_height - 1)
-                } else {
-                    0
-                }
-            } else {
-                0
-            };
+            let scroll_offset = scroll_offset(selected, visible_height);
 
             let items: Vec<ListItem> = self
                 .installed_crate_items
-                .iter()
-                .enumerate()
-                .skip(scroll_offset)
-                .take(visible_height)
+                .window(scroll_offset, visible_height)
                 .map(|(idx, item)| {
-                    let kind_style = match item.kind() {
-                        "fn" => self.theme.style_function(),
-                        "struct" | "enum" | "type" => self.theme.style_type(),
-                        "trait" => self.theme.style_keyword(),
-                        "mod" => self.theme.style_accent(),
-                        "const" | "static" => self.theme.style_string(),
-                        _ => self.theme.style_dim(),
-                    };
                     let is_selected = Some(idx) == selected;
                     let base_style = if is_selected {
                         self.theme.style_selected()
                     } else {
                         Style::default()
                     };
-                    let prefix = if is_selected { "▸ " } else { "  " };
-                    let vis = item
-                        .visibility()
-                        .map(|v| match v {
-                            Visibility::Public => "●",
-                            Visibility::Crate => "◐",
-                            _ => "○",
-                        })
-                        .unwrap_or("○");
-                    let module_path = item.module_path();
-                    let display_name = if module_path.len() > 2 {
-                        let last_mod = &module_path[module_path.len() - 1];
-                        format!("{}::{}", last_mod, item.name())
-                    } else if module_path.len() == 2 {
-                        format!("{}::{}", module_path[1], item.name())
-                    } else {
-                        item.name().to_string()
-                    };
-                    ListItem::new(Line::from(vec![
-                        Span::styled(prefix, self.theme.style_accent()),
-                        Span::styled(vis, self.theme.style_dim()),
-                        Span::raw(" "),
-                        Span::styled(format!("{:6} ", item.kind()), kind_style),
-                        Span::styled(display_name, self.theme.style_normal()),
-                    ]))
-                    .style(base_style)
+                    // Innermost module before the name (`de::Deserialize`), borrowed, not formatted
+                    let mut name = Vec::with_capacity(3);
+                    if let [_, .., last_mod] = item.module_path() {
+                        name.push(Span::styled(last_mod.as_str(), self.theme.style_normal()));
+                        name.push(Span::styled("::", self.theme.style_normal()));
+                    }
+                    name.push(Span::styled(item.name(), self.theme.style_normal()));
+                    ListItem::new(self.item_row(item, is_selected, None, name)).style(base_style)
                 })
                 .collect();
 
@@ -342,9 +551,19 @@ impl<'a> OracleUi<'a> {
             } else {
                 String::new()
             };
+            let loading = match (self.installed_loading, self.animation) {
+                (true, Some(anim)) => format!(" {} analyzing…", anim.spinner(self.theme.compat)),
+                (true, None) => " analyzing…".to_string(),
+                (false, _) => String::new(),
+            };
             let title = format!(
-                " 📦 {} v{} ({} items){} [Esc] ",
-                crate_info.name, crate_info.version, total_items, scroll_info
+                " {}{} v{} ({} items){}{} [Esc] ",
+                self.theme.glyph("📦 ", ""),
+                crate_info.name,
+                crate_info.version,
+                total_items,
+                loading,
+                scroll_info
             );
             let list_area = Rect {
                 width: area.width.saturating_sub(1),
@@ -356,7 +575,8 @@ impl<'a> OracleUi<'a> {
                         .borders(Borders::ALL)
                         .border_style(border_style)
                         .style(Style::default().bg(self.theme.bg_panel))
-                        .title(title),
+                        .title(title)
+                        .title_bottom(self.filtering_marker()),
                 )
                 .highlight_style(self.theme.style_selected());
             Widget::render(list, list_area, buf);
@@ -1,23 +1,46 @@
 //! Main Oracle TUI application — composed from blocks (header, list, status, overlays, right_panel).
 
+mod compare;
 mod header;
 mod layout;
 mod list;
 mod overlays;
 mod right_panel;
+mod rows;
 mod status;
 mod types;
+mod warnings;
 
-pub use layout::tabs_rect_for_area;
-pub use types::{Focus, Tab};
+pub use compare::CompareView;
+pub use layout::{completion_rect, search_rect_for_area, tabs_rect_for_area};
+pub use rows::FilteredItems;
+pub use types::{
+    FeaturePicker, FilesView, Focus, ProjectPicker, RegistrySearch, SpellingView, Tab, ViewPicker,
+};
 
 use crate::analyzer::AnalyzedItem;
+use crate::analyzer::CoverageData;
 use crate::analyzer::CrateInfo;
-use crate::crates_io::CrateDocInfo;
+use crate::analyzer::DocLink;
+use crate::analyzer::{
+    DuplicateCrate, EditionIssue, FeatureUnification, MethodSort, MsrvReport, TreeRow,
+    TypeUsageIndex,
+};
+use crate::analyzer::{FileMetrics, Misspelling};
+use crate::config::{ReviewNotes, SavedView, Settings, SettingsForm};
+use crate::crates_io::{CrateDocInfo, HealthScore, ReleaseNotes};
 use crate::ui::animation::AnimationState;
 use crate::ui::components::TabBar;
+use crate::ui::inspector::{InspectorCache, SectionLayout, SourceCursorView};
+use crate::ui::messages::LogEntry;
+use crate::ui::report::Report;
 use crate::ui::search::{CompletionCandidate, SearchBar, SearchCompletion};
 use crate::ui::theme::Theme;
+use crate::utils::SkippedPath;
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 use ratatui::{
     buffer::Buffer,
@@ -31,29 +54,102 @@ pub struct OracleUi<'a> {
     // Data
     pub(super) items: &'a [AnalyzedItem],
     pub(super) all_items_impl_lookup: Option<&'a [AnalyzedItem]>,
-    pub(super) filtered_items: &'a [&'a AnalyzedItem],
+    pub(super) usage_index: Option<&'a TypeUsageIndex>,
+    pub(super) filtered_items: FilteredItems<'a>,
     pub(super) candidates: &'a [CompletionCandidate],
     pub(super) crate_info: Option<&'a CrateInfo>,
     pub(super) dependency_tree: &'a [(String, usize)],
+    pub(super) msrv_report: Option<&'a MsrvReport>,
+    pub(super) edition_issues: &'a [EditionIssue],
+    pub(super) duplicates: &'a [DuplicateCrate],
+    pub(super) feature_unification: &'a [FeatureUnification],
     pub(super) filtered_dependency_indices: &'a [usize],
+    /// Dependencies marked for upgrade: name → latest version
+    pub(super) upgrade_marks: Option<&'a BTreeMap<String, String>>,
+    /// Health scores of the dependencies with crates.io info
+    pub(super) health_scores: Option<&'a HashMap<String, HealthScore>>,
+    pub(super) sort_crates_by_health: bool,
     pub(super) crate_doc: Option<&'a CrateDocInfo>,
     pub(super) crate_doc_loading: bool,
     pub(super) crate_doc_failed: bool,
     pub(super) selected_installed_crate: Option<&'a crate::analyzer::InstalledCrate>,
-    pub(super) installed_crate_items: &'a [&'a AnalyzedItem],
+    pub(super) installed_crate_items: FilteredItems<'a>,
+    pub(super) installed_loading: bool,
+    pub(super) filtering: bool,
     pub(super) target_size_bytes: Option<u64>,
+    pub(super) offline: bool,
+    /// Names of the open projects and the shown one; empty with a single project
+    pub(super) project_tabs: (&'a [String], usize),
+    pub(super) warnings: &'a [SkippedPath],
+    pub(super) show_warnings: bool,
     // UI state
     pub(super) search_input: &'a str,
     pub(super) current_tab: Tab,
     pub(super) focus: Focus,
     pub(super) list_selected: Option<usize>,
+    /// Module tree rows when the tree sidebar is open (replaces the results list)
+    pub(super) module_tree_rows: Option<&'a [TreeRow]>,
+    pub(super) module_tree_selected: usize,
+    pub(super) module_scope: Option<&'a [String]>,
+    /// Second project in comparison mode
+    pub(super) compare: Option<CompareView<'a>>,
     pub(super) selected_item: Option<&'a AnalyzedItem>,
+    pub(super) pinned_item: Option<&'a AnalyzedItem>,
+    pub(super) pinned_scroll: usize,
     pub(super) completion_selected: usize,
+    pub(super) completion_offset: usize,
     pub(super) show_completion: bool,
     pub(super) show_help: bool,
-    pub(super) show_settings: bool,
+    /// Settings overlay, when open, with the values it edits
+    pub(super) settings_form: Option<(&'a SettingsForm, &'a Settings)>,
+    pub(super) show_release_notes: bool,
+    /// `None` while loading
+    pub(super) release_notes: Option<&'a ReleaseNotes>,
+    pub(super) release_notes_scroll: usize,
+    /// Project-wide report overlay (async audit, ...)
+    pub(super) report: Option<&'a Report>,
+    pub(super) report_scroll: usize,
     pub(super) status_message: &'a str,
+    /// Recent warning / error shown above the status bar
+    pub(super) toast: Option<&'a LogEntry>,
+    pub(super) views: &'a [SavedView],
+    pub(super) view_picker: Option<&'a ViewPicker>,
+    pub(super) feature_picker: Option<&'a FeaturePicker>,
+    pub(super) project_picker: Option<&'a ProjectPicker>,
+    /// Spelling view and the misspellings it lists
+    pub(super) spelling: Option<(&'a SpellingView, &'a [Misspelling])>,
+    /// Files view, the files it lists and the project root they're shown relative to
+    pub(super) files_view: Option<(&'a FilesView, &'a [FileMetrics], Option<&'a Path>)>,
+    /// File the item list is scoped to
+    pub(super) file_scope: Option<&'a Path>,
+    /// crates.io results listed instead of the dependencies
+    pub(super) registry_search: Option<&'a RegistrySearch>,
+    pub(super) active_view: Option<&'a str>,
     pub(super) inspector_scroll: usize,
+    /// Filled by the inspectors with their last scrollable row (see `InspectorPanel::report_max_scroll`)
+    pub(super) inspector_max_scroll: Option<&'a Cell<Option<usize>>>,
+    pub(super) pinned_max_scroll: Option<&'a Cell<Option<usize>>>,
+    /// Filled by the item inspector with the section at the top of its view
+    pub(super) inspector_top_section: Option<&'a RefCell<Option<String>>>,
+    /// Section order, hidden and collapsed sections of every inspector
+    pub(super) inspector_sections: SectionLayout<'a>,
+    /// Inspectors show the items' source as written (`v`)
+    pub(super) inspector_raw: bool,
+    /// Identifier under the cursor of the source view (`Enter` in `v`)
+    pub(super) source_cursor: Option<SourceCursorView<'a>>,
+    pub(super) inspector_diff: Option<&'a str>,
+    pub(super) review_notes: Option<&'a ReviewNotes>,
+    /// Text of the review note being written on the selected item
+    pub(super) review_input: Option<&'a str>,
+    pub(super) inspector_cache: Option<&'a InspectorCache>,
+    pub(super) code_wrap: bool,
+    pub(super) code_hscroll: usize,
+    pub(super) method_sort: MethodSort,
+    /// Loaded test coverage (`--coverage`), shown per item in the list and inspector
+    pub(super) coverage: Option<&'a CoverageData>,
+    /// Links in the project's doc comments, for broken-link badges in the inspector
+    pub(super) doc_links: Option<&'a [DocLink]>,
+    pub(super) doc_example_selected: usize,
     pub(super) animation: Option<&'a AnimationState>,
     pub(super) theme: &'a Theme,
     // Copilot in-TUI chat
@@ -69,28 +165,80 @@ impl<'a> OracleUi<'a> {
         Self {
             items: &[],
             all_items_impl_lookup: None,
-            filtered_items: &[],
+            usage_index: None,
+            filtered_items: FilteredItems::default(),
             candidates: &[],
             crate_info: None,
             dependency_tree: &[],
+            msrv_report: None,
+            edition_issues: &[],
+            duplicates: &[],
+            feature_unification: &[],
             filtered_dependency_indices: &[],
+            upgrade_marks: None,
+            health_scores: None,
+            sort_crates_by_health: false,
             crate_doc: None,
             crate_doc_loading: false,
             crate_doc_failed: false,
             selected_installed_crate: None,
-            installed_crate_items: &[],
+            installed_crate_items: FilteredItems::default(),
+            installed_loading: false,
+            filtering: false,
             target_size_bytes: None,
+            offline: false,
+            project_tabs: (&[], 0),
+            warnings: &[],
+            show_warnings: false,
             search_input: "",
             current_tab: Tab::default(),
             focus: Focus::default(),
             list_selected: None,
+            module_tree_rows: None,
+            module_tree_selected: 0,
+            module_scope: None,
+            compare: None,
             selected_item: None,
+            pinned_item: None,
+            pinned_scroll: 0,
             completion_selected: 0,
+            completion_offset: 0,
             show_completion: false,
             show_help: false,
-            show_settings: false,
+            settings_form: None,
+            show_release_notes: false,
+            release_notes: None,
+            release_notes_scroll: 0,
+            report: None,
+            report_scroll: 0,
             status_message: "",
+            toast: None,
+            views: &[],
+            view_picker: None,
+            feature_picker: None,
+            project_picker: None,
+            spelling: None,
+            files_view: None,
+            file_scope: None,
+            registry_search: None,
+            active_view: None,
             inspector_scroll: 0,
+            inspector_max_scroll: None,
+            inspector_cache: None,
+            pinned_max_scroll: None,
+            inspector_top_section: None,
+            inspector_sections: SectionLayout::default(),
+            inspector_raw: false,
+            source_cursor: None,
+            inspector_diff: None,
+            review_notes: None,
+            review_input: None,
+            code_wrap: true,
+            code_hscroll: 0,
+            method_sort: MethodSort::default(),
+            coverage: None,
+            doc_links: None,
+            doc_example_selected: 0,
             animation: None,
             theme,
             show_copilot_chat: false,
@@ -112,7 +260,12 @@ impl<'a> OracleUi<'a> {
         self
     }
     #[must_use]
-    pub fn filtered_items(mut self, items: &'a [&'a AnalyzedItem]) -> Self {
+    pub fn usage_index(mut self, index: Option<&'a TypeUsageIndex>) -> Self {
+        self.usage_index = index;
+        self
+    }
+    #[must_use]
+    pub fn filtered_items(mut self, items: FilteredItems<'a>) -> Self {
         self.filtered_items = items;
         self
     }
@@ -125,16 +278,38 @@ impl<'a> OracleUi<'a> {
         self
     }
     #[must_use]
-    pub fn installed_crate_items(mut self, items: &'a [&'a AnalyzedItem]) -> Self {
+    pub fn installed_crate_items(mut self, items: FilteredItems<'a>) -> Self {
         self.installed_crate_items = items;
         self
     }
+    /// Installed crate items are still being analyzed (spinner in the list title)
+    #[must_use]
+    pub fn installed_loading(mut self, loading: bool) -> Self {
+        self.installed_loading = loading;
+        self
+    }
+    /// The typed query is still being applied (subtle marker in the list title)
+    #[must_use]
+    pub fn filtering(mut self, filtering: bool) -> Self {
+        self.filtering = filtering;
+        self
+    }
     #[must_use]
     pub fn target_size_bytes(mut self, bytes: Option<u64>) -> Self {
         self.target_size_bytes = bytes;
         self
     }
     #[must_use]
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+    #[must_use]
+    pub fn project_tabs(mut self, names: &'a [String], active: usize) -> Self {
+        self.project_tabs = (names, active);
+        self
+    }
+    #[must_use]
     pub fn list_selected(mut self, selected: Option<usize>) -> Self {
         self.list_selected = selected;
         self
@@ -155,11 +330,46 @@ impl<'a> OracleUi<'a> {
         self
     }
     #[must_use]
+    pub fn msrv_report(mut self, report: Option<&'a MsrvReport>) -> Self {
+        self.msrv_report = report;
+        self
+    }
+    #[must_use]
+    pub fn edition_issues(mut self, issues: &'a [EditionIssue]) -> Self {
+        self.edition_issues = issues;
+        self
+    }
+    #[must_use]
+    pub fn duplicates(mut self, duplicates: &'a [DuplicateCrate]) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+    #[must_use]
+    pub fn feature_unification(mut self, features: &'a [FeatureUnification]) -> Self {
+        self.feature_unification = features;
+        self
+    }
+    #[must_use]
     pub fn filtered_dependency_indices(mut self, indices: &'a [usize]) -> Self {
         self.filtered_dependency_indices = indices;
         self
     }
     #[must_use]
+    pub fn upgrade_marks(mut self, marks: &'a BTreeMap<String, String>) -> Self {
+        self.upgrade_marks = Some(marks);
+        self
+    }
+    #[must_use]
+    pub fn health_scores(
+        mut self,
+        scores: &'a HashMap<String, HealthScore>,
+        sorted_by_health: bool,
+    ) -> Self {
+        self.health_scores = Some(scores);
+        self.sort_crates_by_health = sorted_by_health;
+        self
+    }
+    #[must_use]
     pub fn crate_doc(mut self, doc: Option<&'a CrateDocInfo>) -> Self {
         self.crate_doc = doc;
         self
@@ -175,6 +385,27 @@ impl<'a> OracleUi<'a> {
         self
     }
     #[must_use]
+    pub fn module_tree(mut self, rows: Option<&'a [TreeRow]>, selected: usize) -> Self {
+        self.module_tree_rows = rows;
+        self.module_tree_selected = selected;
+        self
+    }
+    #[must_use]
+    pub fn module_scope(mut self, scope: Option<&'a [String]>) -> Self {
+        self.module_scope = scope;
+        self
+    }
+    #[must_use]
+    pub fn compare(mut self, compare: Option<CompareView<'a>>) -> Self {
+        self.compare = compare;
+        self
+    }
+    #[must_use]
+    pub fn doc_example_selected(mut self, index: usize) -> Self {
+        self.doc_example_selected = index;
+        self
+    }
+    #[must_use]
     pub fn search_input(mut self, input: &'a str) -> Self {
         self.search_input = input;
         self
@@ -199,6 +430,12 @@ impl<'a> OracleUi<'a> {
         self.completion_selected = index;
         self
     }
+    /// First completion candidate shown in the dropdown
+    #[must_use]
+    pub fn completion_offset(mut self, offset: usize) -> Self {
+        self.completion_offset = offset;
+        self
+    }
     #[must_use]
     pub fn show_completion(mut self, show: bool) -> Self {
         self.show_completion = show;
@@ -210,8 +447,40 @@ impl<'a> OracleUi<'a> {
         self
     }
     #[must_use]
-    pub fn show_settings(mut self, show: bool) -> Self {
-        self.show_settings = show;
+    pub fn settings_form(mut self, form: Option<&'a SettingsForm>, settings: &'a Settings) -> Self {
+        self.settings_form = form.map(|f| (f, settings));
+        self
+    }
+    #[must_use]
+    pub fn release_notes(
+        mut self,
+        show: bool,
+        notes: Option<&'a ReleaseNotes>,
+        scroll: usize,
+    ) -> Self {
+        self.show_release_notes = show;
+        self.release_notes = notes;
+        self.release_notes_scroll = scroll;
+        self
+    }
+    #[must_use]
+    pub fn report(mut self, report: Option<&'a Report>, scroll: usize) -> Self {
+        self.report = report;
+        self.report_scroll = scroll;
+        self
+    }
+    /// Item pinned next to the selection, and its inspector scroll
+    #[must_use]
+    pub fn pinned(mut self, item: Option<&'a AnalyzedItem>, scroll: usize) -> Self {
+        self.pinned_item = item;
+        self.pinned_scroll = scroll;
+        self
+    }
+    /// Paths skipped during analysis and code that didn't parse; listed in full when `expanded`
+    #[must_use]
+    pub fn warnings(mut self, warnings: &'a [SkippedPath], expanded: bool) -> Self {
+        self.warnings = warnings;
+        self.show_warnings = expanded;
         self
     }
     #[must_use]
@@ -220,10 +489,143 @@ impl<'a> OracleUi<'a> {
         self
     }
     #[must_use]
+    pub fn toast(mut self, entry: Option<&'a LogEntry>) -> Self {
+        self.toast = entry;
+        self
+    }
+    /// Saved views, the picker when open, and the view shown in the list title
+    #[must_use]
+    pub fn views(
+        mut self,
+        views: &'a [SavedView],
+        picker: Option<&'a ViewPicker>,
+        active: Option<&'a str>,
+    ) -> Self {
+        self.views = views;
+        self.view_picker = picker;
+        self.active_view = active;
+        self
+    }
+    #[must_use]
+    pub fn feature_picker(mut self, picker: Option<&'a FeaturePicker>) -> Self {
+        self.feature_picker = picker;
+        self
+    }
+    #[must_use]
+    pub fn project_picker(mut self, picker: Option<&'a ProjectPicker>) -> Self {
+        self.project_picker = picker;
+        self
+    }
+    #[must_use]
+    pub fn spelling(mut self, view: Option<&'a SpellingView>, found: &'a [Misspelling]) -> Self {
+        self.spelling = view.map(|v| (v, found));
+        self
+    }
+    #[must_use]
+    pub fn files_view(
+        mut self,
+        view: Option<&'a FilesView>,
+        files: &'a [FileMetrics],
+        root: Option<&'a Path>,
+    ) -> Self {
+        self.files_view = view.map(|v| (v, files, root));
+        self
+    }
+    #[must_use]
+    pub fn file_scope(mut self, file: Option<&'a Path>) -> Self {
+        self.file_scope = file;
+        self
+    }
+    #[must_use]
+    pub fn registry_search(mut self, search: Option<&'a RegistrySearch>) -> Self {
+        self.registry_search = search;
+        self
+    }
+    #[must_use]
     pub fn inspector_scroll(mut self, scroll: usize) -> Self {
         self.inspector_scroll = scroll;
         self
     }
+    /// Soft-wrap long signatures, or scroll them sideways from column `hscroll`
+    #[must_use]
+    pub fn code_layout(mut self, wrap: bool, hscroll: usize) -> Self {
+        self.code_wrap = wrap;
+        self.code_hscroll = hscroll;
+        self
+    }
+    #[must_use]
+    pub fn method_sort(mut self, sort: MethodSort) -> Self {
+        self.method_sort = sort;
+        self
+    }
+    #[must_use]
+    pub fn coverage(mut self, coverage: Option<&'a CoverageData>) -> Self {
+        self.coverage = coverage;
+        self
+    }
+    #[must_use]
+    pub fn doc_links(mut self, links: &'a [DocLink]) -> Self {
+        self.doc_links = Some(links);
+        self
+    }
+    /// Cells receiving the inspector's and pinned inspector's scroll limits while rendering
+    #[must_use]
+    pub fn report_max_scroll(
+        mut self,
+        inspector: &'a Cell<Option<usize>>,
+        pinned: &'a Cell<Option<usize>>,
+    ) -> Self {
+        self.inspector_max_scroll = Some(inspector);
+        self.pinned_max_scroll = Some(pinned);
+        self
+    }
+
+    /// Cell receiving the title of the section at the top of the item inspector
+    #[must_use]
+    pub fn report_top_section(mut self, cell: &'a RefCell<Option<String>>) -> Self {
+        self.inspector_top_section = Some(cell);
+        self
+    }
+
+    #[must_use]
+    pub fn inspector_sections(mut self, layout: SectionLayout<'a>) -> Self {
+        self.inspector_sections = layout;
+        self
+    }
+
+    #[must_use]
+    pub fn inspector_raw(mut self, raw: bool) -> Self {
+        self.inspector_raw = raw;
+        self
+    }
+
+    #[must_use]
+    pub fn source_cursor(mut self, cursor: Option<SourceCursorView<'a>>) -> Self {
+        self.source_cursor = cursor;
+        self
+    }
+
+    /// Git revision to diff the selected item against, `None` for the usual view
+    #[must_use]
+    pub fn inspector_diff(mut self, rev: Option<&'a str>) -> Self {
+        self.inspector_diff = rev;
+        self
+    }
+
+    /// Review notes shown in the inspector, and the note being written
+    #[must_use]
+    pub fn review(mut self, notes: &'a ReviewNotes, input: Option<&'a str>) -> Self {
+        self.review_notes = Some(notes);
+        self.review_input = input;
+        self
+    }
+
+    /// Lines kept between frames by every inspector panel
+    #[must_use]
+    pub fn inspector_cache(mut self, cache: &'a InspectorCache) -> Self {
+        self.inspector_cache = Some(cache);
+        self
+    }
     #[must_use]
     pub fn animation_state(mut self, animation: &'a AnimationState) -> Self {
         self.animation = Some(animation);
@@ -278,17 +680,11 @@ impl<'a> OracleUi<'a> {
         if !self.show_completion || self.candidates.is_empty() {
             return;
         }
-        let max_height = 12.min(self.candidates.len() as u16 + 2);
-        let dropdown_area = Rect {
-            x: search_area.x + 2,
-            y: search_area.y + search_area.height,
-            width: search_area.width.saturating_sub(4).min(60),
-            height: max_height,
-        };
+        let dropdown_area = completion_rect(search_area, self.candidates.len(), *buf.area());
         let completion = SearchCompletion::new(self.candidates, self.theme)
             .selected(self.completion_selected)
-            .filter(self.search_input)
-            .max_visible(10);
+            .offset(self.completion_offset)
+            .filter(self.search_input);
         completion.render(dropdown_area, buf);
     }
 
@@ -330,7 +726,15 @@ impl Widget for OracleUi<'_> {
 
         self.render_header(chunks[0], buf);
 
-        let body = chunks[1];
+        // Warnings panel sits below the body so the tabs row (mouse hit area) never moves
+        let body_split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(12),
+                Constraint::Length(self.warnings_height()),
+            ])
+            .split(chunks[1]);
+        let body = body_split[0];
         let left_div_right = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -371,12 +775,30 @@ impl Widget for OracleUi<'_> {
         self.render_list(list_rect, buf);
         self.render_vertical_divider(div_rect, buf);
         self.render_tabs(tabs_rect, buf);
-        self.render_inspector(inspector_rect, buf);
+        match self.compare {
+            Some(ref compare) if self.current_tab != Tab::Crates && !self.show_copilot_chat => {
+                self.render_compare(compare, right_content, buf);
+            }
+            _ if self.pinned_item.is_some() && self.shows_items() => {
+                self.render_pinned_split(inspector_rect, buf)
+            }
+            _ => self.render_inspector(inspector_rect, buf),
+        }
         if self.show_copilot_chat {
             self.render_copilot_chat(chat_rect, buf);
         }
+        self.render_warnings(body_split[1], buf);
         self.render_status(chunks[2], buf);
+        self.render_toast(chunks[2], buf);
         self.render_completion(search_rect, buf);
+        self.render_release_notes_overlay(area, buf);
+        self.render_report_overlay(area, buf);
+        self.render_view_picker(area, buf);
+        self.render_review_input(area, buf);
+        self.render_feature_picker(area, buf);
+        self.render_project_picker(area, buf);
+        self.render_spelling(area, buf);
+        self.render_files_view(area, buf);
         self.render_settings_overlay(area, buf);
         self.render_help_overlay(area, buf);
     }
@@ -1,22 +1,31 @@
-//! Overlay blocks: settings popup, help popup.
+//! Overlay blocks: settings popup, help popup, release notes, project reports, toasts.
 
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::Style,
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph, Widget},
+    widgets::{
+        block::BorderType, Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, StatefulWidget, Widget, Wrap,
+    },
 };
 
+use super::right_panel::markdown_line_to_spans;
 use super::OracleUi;
+use crate::config::SettingField;
+use crate::crates_io::ReleaseNotesSource;
+use crate::ui::messages::Severity;
+use crate::ui::report::ReportLevel;
+use crate::utils::truncate;
 
 impl<'a> OracleUi<'a> {
     pub(super) fn render_settings_overlay(&self, area: Rect, buf: &mut Buffer) {
-        if !self.show_settings {
+        let Some((form, settings)) = self.settings_form else {
             return;
-        }
-        let w = 48.min(area.width.saturating_sub(4));
-        let h = 10.min(area.height.saturating_sub(4));
+        };
+        let w = 72.min(area.width.saturating_sub(4));
+        let h = (SettingField::ALL.len() as u16 + 7).min(area.height.saturating_sub(4));
         let settings_area = Rect {
             x: area.x + (area.width - w) / 2,
             y: area.y + (area.height - h) / 2,
@@ -24,22 +33,52 @@ impl<'a> OracleUi<'a> {
             height: h,
         };
         Clear.render(settings_area, buf);
-        let text = vec![
-            Line::from(Span::styled(" Settings ", self.theme.style_accent_bold())),
-            Line::from(""),
-            Line::from(Span::styled("Theme", self.theme.style_dim())),
-            Line::from(vec![
-                Span::raw("  Press "),
-                Span::styled("t", self.theme.style_accent()),
-                Span::raw(" to cycle theme"),
-            ]),
-            Line::from(""),
-            Line::from(Span::styled(
-                "Press Esc or S to close",
-                self.theme.style_muted(),
-            )),
-        ];
-        let block = Paragraph::new(text).block(
+        let mut lines: Vec<Line> = SettingField::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let selected = i == form.selected;
+                let marker = if selected {
+                    self.theme.glyph("▸ ", "> ")
+                } else {
+                    "  "
+                };
+                let label_style = if selected {
+                    self.theme.style_selected()
+                } else {
+                    self.theme.style_normal()
+                };
+                let value = match form.editing {
+                    Some(ref input) if selected => {
+                        Span::styled(format!("{}▏", input), self.theme.style_accent_bold())
+                    }
+                    _ => Span::styled(field.value(settings), self.theme.style_accent()),
+                };
+                let mut spans = vec![
+                    Span::raw(marker),
+                    Span::styled(format!("{:<24}", field.label()), label_style),
+                    value,
+                ];
+                if field.set_by_project(settings) {
+                    spans.push(Span::styled("  (.oracle.yaml)", self.theme.style_muted()));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(match form.error {
+            Some(ref error) => Line::from(Span::styled(error.clone(), self.theme.style_error())),
+            None => Line::from(Span::styled(form.field().help(), self.theme.style_dim())),
+        });
+        lines.push(Line::from(Span::styled(
+            if form.editing.is_some() {
+                "Enter save · Esc cancel"
+            } else {
+                "↑/↓ select · Enter change · Esc close · saved to config.yaml"
+            },
+            self.theme.style_muted(),
+        )));
+        let block = Paragraph::new(lines).block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(self.theme.style_border_focused())
@@ -49,6 +88,424 @@ impl<'a> OracleUi<'a> {
         block.render(settings_area, buf);
     }
 
+    /// Saved view picker: "All items" first, then the views from the config file
+    pub(super) fn render_view_picker(&self, area: Rect, buf: &mut Buffer) {
+        let Some(picker) = self.view_picker else {
+            return;
+        };
+        let w = 72.min(area.width.saturating_sub(4));
+        let h = (self.views.len() as u16 + 6).min(area.height.saturating_sub(4));
+        let popup = Rect {
+            x: area.x + (area.width - w) / 2,
+            y: area.y + (area.height - h) / 2,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+        let row = |i: usize, name: &str, summary: String| {
+            let selected = i == picker.selected;
+            let marker = if selected {
+                self.theme.glyph("▸ ", "> ")
+            } else {
+                "  "
+            };
+            let name_style = if selected {
+                self.theme.style_selected()
+            } else {
+                self.theme.style_accent()
+            };
+            Line::from(vec![
+                Span::raw(marker),
+                Span::styled(format!("{:<22}", name), name_style),
+                Span::styled(summary, self.theme.style_muted()),
+            ])
+        };
+        let mut lines = vec![row(0, "All items", "no view".to_string())];
+        for (i, view) in self.views.iter().enumerate() {
+            lines.push(row(i + 1, &view.name, view.summary()));
+        }
+        lines.push(Line::from(""));
+        lines.push(match picker.naming {
+            Some(ref name) => Line::from(vec![
+                Span::styled("Save current list as: ", self.theme.style_dim()),
+                Span::styled(format!("{}▏", name), self.theme.style_accent_bold()),
+            ]),
+            None => Line::from(Span::styled(
+                "Enter apply · a save current list · d delete · Esc close",
+                self.theme.style_muted(),
+            )),
+        });
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(self.theme.style_border_focused())
+                    .title(" Views ")
+                    .style(Style::default().bg(self.theme.bg_panel)),
+            )
+            .render(popup, buf);
+    }
+
+    /// One-line prompt for the review note on the selected item
+    pub(super) fn render_review_input(&self, area: Rect, buf: &mut Buffer) {
+        let (Some(input), Some(item)) = (self.review_input, self.selected_item) else {
+            return;
+        };
+        let w = 72.min(area.width.saturating_sub(4));
+        let h = 5.min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - w) / 2,
+            y: area.y + (area.height - h) / 2,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+        let lines = vec![
+            Line::from(Span::styled(
+                format!("{}▏", input),
+                self.theme.style_accent_bold(),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Enter save · empty removes the note · Esc cancel",
+                self.theme.style_muted(),
+            )),
+        ];
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(self.theme.style_border_focused())
+                    .title(format!(" Review note on {} ", item.qualified_name()))
+                    .style(Style::default().bg(self.theme.bg_panel)),
+            )
+            .render(popup, buf);
+    }
+
+    /// Recent projects, most recent first, with where they live and when they
+    /// were last opened
+    pub(super) fn render_project_picker(&self, area: Rect, buf: &mut Buffer) {
+        let Some(picker) = self.project_picker else {
+            return;
+        };
+        let w = 80.min(area.width.saturating_sub(4));
+        let h = (picker.projects.len() as u16 + 4).min(area.height.saturating_sub(4));
+        let popup = Rect {
+            x: area.x + (area.width - w) / 2,
+            y: area.y + (area.height - h) / 2,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+        let mut lines: Vec<Line> = picker
+            .projects
+            .iter()
+            .enumerate()
+            .map(|(i, project)| {
+                let selected = i == picker.selected;
+                let marker = if selected {
+                    self.theme.glyph("▸ ", "> ")
+                } else {
+                    "  "
+                };
+                let name_style = if selected {
+                    self.theme.style_selected()
+                } else {
+                    self.theme.style_accent()
+                };
+                let ago = picker.now.saturating_sub(project.last_opened);
+                Line::from(vec![
+                    Span::raw(marker),
+                    Span::styled(format!("{:<22}", project.name), name_style),
+                    Span::styled(format!("{:>8}  ", format_ago(ago)), self.theme.style_dim()),
+                    Span::styled(project.path.display().to_string(), self.theme.style_muted()),
+                ])
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Enter open · t open in a new tab · d forget · Esc close",
+            self.theme.style_muted(),
+        )));
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(self.theme.style_border_focused())
+                    .title(" Recent projects ")
+                    .style(Style::default().bg(self.theme.bg_panel)),
+            )
+            .render(popup, buf);
+    }
+
+    /// Features of a direct dependency with what `Cargo.toml` requests and what the
+    /// resolve turned on anyway
+    pub(super) fn render_feature_picker(&self, area: Rect, buf: &mut Buffer) {
+        let Some(picker) = self.feature_picker else {
+            return;
+        };
+        let name = picker.crate_name.as_str();
+        let resolved = self
+            .feature_unification
+            .iter()
+            .rfind(|f| f.name == name || f.name.replace('-', "_") == name);
+        let available = resolved.map_or(&[][..], |f| f.available.as_slice());
+        let w = 72.min(area.width.saturating_sub(4));
+        let h = (available.len() as u16 + 6).min(area.height.saturating_sub(4));
+        let popup = Rect {
+            x: area.x + (area.width - w) / 2,
+            y: area.y + (area.height - h) / 2,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+        let row = |i: usize, label: &str, on: bool, note: String| {
+            let selected = i == picker.selected;
+            let marker = if selected {
+                self.theme.glyph("▸ ", "> ")
+            } else {
+                "  "
+            };
+            let label_style = if selected {
+                self.theme.style_selected()
+            } else if on {
+                self.theme.style_accent()
+            } else {
+                self.theme.style_normal()
+            };
+            Line::from(vec![
+                Span::raw(marker),
+                Span::styled(if on { "[x] " } else { "[ ] " }, label_style),
+                Span::styled(format!("{:<24}", label), label_style),
+                Span::styled(note, self.theme.style_muted()),
+            ])
+        };
+        let defaults = resolved.map_or(String::new(), |f| f.default.join(", "));
+        let mut lines = vec![row(
+            0,
+            "default features",
+            picker.declared.default_features,
+            defaults,
+        )];
+        // Visible window around the selection; the popup is often shorter than the list
+        let visible = h.saturating_sub(5) as usize;
+        let first = picker
+            .selected
+            .saturating_sub(visible.saturating_sub(1))
+            .max(1);
+        for (i, feature) in available.iter().enumerate().skip(first - 1).take(visible) {
+            let declared = picker.declared.features.contains(feature);
+            let note = match resolved {
+                Some(f) if !declared && f.enabled.contains(feature) => {
+                    let by = f.requesters(feature);
+                    if by.is_empty() {
+                        "on (implied)".to_string()
+                    } else {
+                        format!("on via {}", by.join(", "))
+                    }
+                }
+                Some(f) if f.default.contains(feature) => "default".to_string(),
+                _ => String::new(),
+            };
+            lines.push(row(i + 1, feature, declared, note));
+        }
+        if available.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No optional features",
+                self.theme.style_muted(),
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Enter toggle (writes Cargo.toml, re-resolves) · Esc close",
+            self.theme.style_muted(),
+        )));
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(self.theme.style_border_focused())
+                    .title(format!(" Features · {} ", name))
+                    .style(Style::default().bg(self.theme.bg_panel)),
+            )
+            .render(popup, buf);
+    }
+
+    /// Misspelled words in the docs, grouped under the item they're in
+    pub(super) fn render_spelling(&self, area: Rect, buf: &mut Buffer) {
+        let Some((view, found)) = self.spelling else {
+            return;
+        };
+        let w = 76.min(area.width.saturating_sub(4));
+        let h = area.height.saturating_sub(4).min(24);
+        let popup = Rect {
+            x: area.x + (area.width - w) / 2,
+            y: area.y + (area.height - h) / 2,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+        let mut lines = Vec::new();
+        let mut selected_line = 0;
+        let mut owner = None;
+        for (i, m) in found.iter().enumerate() {
+            if owner != Some(m.owner.as_str()) {
+                owner = Some(m.owner.as_str());
+                let mut header = vec![Span::styled(m.owner.clone(), self.theme.style_accent())];
+                if let (Some(file), Some(line)) = (&m.location.file, m.location.line) {
+                    let name = file.file_name().unwrap_or(file.as_os_str());
+                    header.push(Span::styled(
+                        format!("  {}:{}", name.to_string_lossy(), line),
+                        self.theme.style_muted(),
+                    ));
+                }
+                lines.push(Line::from(header));
+            }
+            let selected = i == view.selected;
+            if selected {
+                selected_line = lines.len();
+            }
+            let marker = if selected {
+                self.theme.glyph("  ▸ ", "  > ")
+            } else {
+                "    "
+            };
+            let word_style = if selected {
+                self.theme.style_selected()
+            } else {
+                self.theme.style_error()
+            };
+            lines.push(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(m.word.clone(), word_style),
+                Span::styled(self.theme.glyph(" → ", " -> "), self.theme.style_muted()),
+                Span::styled(m.suggestion.clone(), self.theme.style_normal()),
+            ]));
+        }
+        if found.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No misspellings in the docs",
+                self.theme.style_muted(),
+            )));
+        }
+        // Visible window around the selection, footer excluded
+        let visible = h.saturating_sub(4) as usize;
+        let first = selected_line.saturating_sub(visible.saturating_sub(1));
+        let mut lines: Vec<Line> = lines.into_iter().skip(first).take(visible).collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "a add the word to the dictionary · Esc close",
+            self.theme.style_muted(),
+        )));
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(self.theme.style_border_focused())
+                    .title(format!(" Spelling · {} ", found.len()))
+                    .style(Style::default().bg(self.theme.bg_panel)),
+            )
+            .render(popup, buf);
+    }
+
+    /// Analyzed files with their size, items, doc coverage and `unsafe` count
+    pub(super) fn render_files_view(&self, area: Rect, buf: &mut Buffer) {
+        let Some((view, files, root)) = self.files_view else {
+            return;
+        };
+        let w = 88.min(area.width.saturating_sub(4));
+        let h = area.height.saturating_sub(4).min(files.len() as u16 + 6);
+        let popup = Rect {
+            x: area.x + (area.width - w) / 2,
+            y: area.y + (area.height - h) / 2,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+        let path_width = (w as usize).saturating_sub(42).max(16);
+        let mut lines = vec![Line::from(Span::styled(
+            format!(
+                "  {:<path_width$} {:>7} {:>6} {:>6} {:>7}",
+                "File", "Lines", "Items", "Docs", "Unsafe"
+            ),
+            self.theme.style_dim(),
+        ))];
+        let visible = h.saturating_sub(5) as usize;
+        let first = view.selected.saturating_sub(visible.saturating_sub(1));
+        for (i, file) in view
+            .sort
+            .sorted(files)
+            .into_iter()
+            .enumerate()
+            .skip(first)
+            .take(visible)
+        {
+            let selected = i == view.selected;
+            let marker = if selected {
+                self.theme.glyph("▸ ", "> ")
+            } else {
+                "  "
+            };
+            let path = root
+                .and_then(|root| file.path.strip_prefix(root).ok())
+                .unwrap_or(&file.path)
+                .display()
+                .to_string();
+            let path = truncate(&path, path_width);
+            let docs = file
+                .doc_coverage()
+                .map_or("-".to_string(), |c| format!("{:.0}%", c * 100.0));
+            let docs_style = match file.doc_coverage() {
+                Some(c) if c < 0.5 => self.theme.style_error(),
+                _ => self.theme.style_normal(),
+            };
+            let unsafe_style = if file.unsafe_count > 0 {
+                self.theme.style_warning()
+            } else {
+                self.theme.style_muted()
+            };
+            let path_style = if selected {
+                self.theme.style_selected()
+            } else {
+                self.theme.style_accent()
+            };
+            lines.push(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(format!("{:<path_width$}", path), path_style),
+                Span::styled(
+                    format!(" {:>7} {:>6}", file.lines, file.items),
+                    self.theme.style_normal(),
+                ),
+                Span::styled(format!(" {:>6}", docs), docs_style),
+                Span::styled(format!(" {:>7}", file.unsafe_count), unsafe_style),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Enter show its items · s sort (by {}) · Esc close",
+                view.sort.label()
+            ),
+            self.theme.style_muted(),
+        )));
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(self.theme.style_border_focused())
+                    .title(format!(" Files · {} ", files.len()))
+                    .style(Style::default().bg(self.theme.bg_panel)),
+            )
+            .render(popup, buf);
+    }
+
     pub(super) fn render_help_overlay(&self, area: Rect, buf: &mut Buffer) {
         if !self.show_help {
             return;
@@ -64,7 +521,7 @@ impl<'a> OracleUi<'a> {
         Clear.render(help_area, buf);
         let help_text = vec![
             Line::from(Span::styled(
-                "⌨️  Keyboard Shortcuts",
+                format!("{}Keyboard Shortcuts", self.theme.glyph("⌨️  ", "")),
                 self.theme.style_accent_bold(),
             )),
             Line::from(""),
@@ -81,10 +538,18 @@ impl<'a> OracleUi<'a> {
                 Span::styled("  /          ", self.theme.style_accent()),
                 Span::raw("Focus search"),
             ]),
+            Line::from(vec![
+                Span::styled("  ↑ / ↓      ", self.theme.style_accent()),
+                Span::raw("Recall earlier searches (in an empty search bar)"),
+            ]),
             Line::from(vec![
                 Span::styled("  Esc        ", self.theme.style_accent()),
                 Span::raw("Clear search / Back / Close popup"),
             ]),
+            Line::from(vec![
+                Span::styled("  key:value  ", self.theme.style_accent()),
+                Span::raw("Filter: derive: attr: vis: async: returns: kind: mod: file:"),
+            ]),
             Line::from(""),
             Line::from(Span::styled("List & inspector", self.theme.style_dim())),
             Line::from(vec![
@@ -105,12 +570,60 @@ impl<'a> OracleUi<'a> {
             ]),
             Line::from(vec![
                 Span::styled("  G  End     ", self.theme.style_accent()),
-                Span::raw("Last item"),
+                Span::raw("Last item · inspector bottom"),
             ]),
             Line::from(vec![
                 Span::styled("  PgUp  PgDn  ", self.theme.style_accent()),
                 Span::raw("Page up / down"),
             ]),
+            Line::from(vec![
+                Span::styled("  m          ", self.theme.style_accent()),
+                Span::raw("Module tree (Enter scopes list, Esc clears)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  e  y  x    ", self.theme.style_accent()),
+                Span::raw("Doc example: next · copy · cargo test --doc"),
+            ]),
+            Line::from(vec![
+                Span::styled("  z          ", self.theme.style_accent()),
+                Span::raw("Signatures: soft-wrap / scroll sideways (←/→)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  v          ", self.theme.style_accent()),
+                Span::raw("Inspector: source as written / analysis"),
+            ]),
+            Line::from(vec![
+                Span::styled("  Enter      ", self.theme.style_accent()),
+                Span::raw("Source: cursor on identifiers, Enter goes to definition"),
+            ]),
+            Line::from(vec![
+                Span::styled("  d          ", self.theme.style_accent()),
+                Span::raw("Inspector: changes since HEAD (or the set revision)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  n          ", self.theme.style_accent()),
+                Span::raw("Write a review note on the item (.oracle-review.yaml)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  P          ", self.theme.style_accent()),
+                Span::raw("Export item as an HTML page (target/oracle-doc)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  Space  Z   ", self.theme.style_accent()),
+                Span::raw("Inspector: collapse top section · expand all"),
+            ]),
+            Line::from(vec![
+                Span::styled("  M          ", self.theme.style_accent()),
+                Span::raw("Method table: sort by origin · name · receiver"),
+            ]),
+            Line::from(vec![
+                Span::styled("  p          ", self.theme.style_accent()),
+                Span::raw("Pin item to compare with the selection"),
+            ]),
+            Line::from(vec![
+                Span::styled("  w          ", self.theme.style_accent()),
+                Span::raw("Show / hide skipped-path warnings"),
+            ]),
             Line::from(""),
             Line::from(Span::styled("Tabs", self.theme.style_dim())),
             Line::from(vec![
@@ -118,6 +631,12 @@ impl<'a> OracleUi<'a> {
                 Span::raw("Types · Functions · Modules · Crates"),
             ]),
             Line::from(""),
+            Line::from(Span::styled("Modules tab only", self.theme.style_dim())),
+            Line::from(vec![
+                Span::styled("  I          ", self.theme.style_accent()),
+                Span::raw("Import graph between modules, with cycles"),
+            ]),
+            Line::from(""),
             Line::from(Span::styled("Crates tab only", self.theme.style_dim())),
             Line::from(vec![
                 Span::styled("  [o]        ", self.theme.style_accent()),
@@ -127,6 +646,42 @@ impl<'a> OracleUi<'a> {
                 Span::styled("  [c]        ", self.theme.style_accent()),
                 Span::raw("Open crates.io in browser"),
             ]),
+            Line::from(vec![
+                Span::styled("  [r]        ", self.theme.style_accent()),
+                Span::raw("Release notes since installed version"),
+            ]),
+            Line::from(vec![
+                Span::styled("  [u]  U     ", self.theme.style_accent()),
+                Span::raw("Mark for upgrade · review, apply or undo the plan"),
+            ]),
+            Line::from(vec![
+                Span::styled("  [f]        ", self.theme.style_accent()),
+                Span::raw("Toggle features of a direct dependency"),
+            ]),
+            Line::from(vec![
+                Span::styled("  [y]  Y     ", self.theme.style_accent()),
+                Span::raw("Copy cargo add command · Cargo.toml line"),
+            ]),
+            Line::from(vec![
+                Span::styled("  H          ", self.theme.style_accent()),
+                Span::raw("Sort crates by health score"),
+            ]),
+            Line::from(vec![
+                Span::styled("  [i]        ", self.theme.style_accent()),
+                Span::raw("GitHub issues and pull requests of a dependency"),
+            ]),
+            Line::from(vec![
+                Span::styled("  /@name  a  ", self.theme.style_accent()),
+                Span::raw("Search crates.io · add the result to Cargo.toml"),
+            ]),
+            Line::from(vec![
+                Span::styled("  Enter  ←   ", self.theme.style_accent()),
+                Span::raw("In a crate: drill into module · up a level"),
+            ]),
+            Line::from(vec![
+                Span::styled("  u          ", self.theme.style_accent()),
+                Span::raw("In a crate: where the project uses the type or trait"),
+            ]),
             Line::from(""),
             Line::from(Span::styled("Other", self.theme.style_dim())),
             Line::from(vec![
@@ -137,6 +692,98 @@ impl<'a> OracleUi<'a> {
                 Span::styled("  t          ", self.theme.style_accent()),
                 Span::raw("Cycle theme"),
             ]),
+            Line::from(vec![
+                Span::styled("  A          ", self.theme.style_accent()),
+                Span::raw("Async / Send audit of the public API"),
+            ]),
+            Line::from(vec![
+                Span::styled("  E          ", self.theme.style_accent()),
+                Span::raw("Errors: error types and who returns them"),
+            ]),
+            Line::from(vec![
+                Span::styled("  B          ", self.theme.style_accent()),
+                Span::raw("FFI: extern \"C\" fns, repr(C) types (x export C header)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  T          ", self.theme.style_accent()),
+                Span::raw("WASM: will it build for wasm32 / WASI, and what blocks it"),
+            ]),
+            Line::from(vec![
+                Span::styled("  K          ", self.theme.style_accent()),
+                Span::raw("no_std: dependencies needing std, std-only imports"),
+            ]),
+            Line::from(vec![
+                Span::styled("  J          ", self.theme.style_accent()),
+                Span::raw("cfg: items by #[cfg], per target (t target, f features)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  Q          ", self.theme.style_accent()),
+                Span::raw("Panics: unwrap/expect/panic!/todo!/indexing (t filter)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  %          ", self.theme.style_accent()),
+                Span::raw("Allocations: functions ranked by clones / allocations"),
+            ]),
+            Line::from(vec![
+                Span::styled("  $          ", self.theme.style_accent()),
+                Span::raw("Environment variables read, and where (x export)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  @          ", self.theme.style_accent()),
+                Span::raw("History of the item over its commits (←/→ step)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  #          ", self.theme.style_accent()),
+                Span::raw("Review notes on items (x export Markdown)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  *          ", self.theme.style_accent()),
+                Span::raw("Type, definition and references (rust-analyzer)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  &          ", self.theme.style_accent()),
+                Span::raw("Share session to target/oracle-session.json"),
+            ]),
+            Line::from(vec![
+                Span::styled("  N          ", self.theme.style_accent()),
+                Span::raw("Notes: TODO/FIXME/HACK/SAFETY (t filter, x export)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  R          ", self.theme.style_accent()),
+                Span::raw("Possibly unused items, by confidence"),
+            ]),
+            Line::from(vec![
+                Span::styled("  L          ", self.theme.style_accent()),
+                Span::raw("Broken links in doc comments (web links checked once)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  W          ", self.theme.style_accent()),
+                Span::raw("Misspellings in doc comments (a adds a word to the dictionary)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  !          ", self.theme.style_accent()),
+                Span::raw("Message history (errors and warnings with times)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  V          ", self.theme.style_accent()),
+                Span::raw("Saved views: pick, save (a) or delete (d)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  F          ", self.theme.style_accent()),
+                Span::raw("Files: lines, items, docs, unsafe; Enter scopes the list"),
+            ]),
+            Line::from(vec![
+                Span::styled("  O          ", self.theme.style_accent()),
+                Span::raw("Recent projects: switch (Enter), new tab (t), forget (d)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  [  ]  X    ", self.theme.style_accent()),
+                Span::raw("Project tabs: previous · next · close"),
+            ]),
+            Line::from(vec![
+                Span::styled("  D          ", self.theme.style_accent()),
+                Span::raw("Debug log (recent lines of oracle.log)"),
+            ]),
             Line::from(vec![
                 Span::styled("  S          ", self.theme.style_accent()),
                 Span::raw("Settings overlay"),
@@ -174,4 +821,246 @@ impl<'a> OracleUi<'a> {
         );
         help.render(help_area, buf);
     }
+
+    /// Latest warning or error in a box above the right end of the status bar
+    pub(super) fn render_toast(&self, status: Rect, buf: &mut Buffer) {
+        let Some(entry) = self.toast else {
+            return;
+        };
+        let (icon, style) = match entry.severity {
+            Severity::Error => (self.theme.glyph("✖", "x"), self.theme.style_error()),
+            _ => (self.theme.glyph("⚠", "!"), self.theme.style_warning()),
+        };
+        let text = format!(" {} {} ", icon, entry.text);
+        let w = (text.chars().count() as u16 + 2)
+            .min(status.width * 2 / 3)
+            .max(12);
+        let h = 3;
+        if status.y < h || status.width < w {
+            return;
+        }
+        let popup = Rect {
+            x: status.x + status.width - w - 1,
+            y: status.y - h,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+        Paragraph::new(Line::from(Span::styled(text, style)))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(style)
+                    .title_bottom(Line::from(Span::styled(
+                        " ! history ",
+                        self.theme.style_muted(),
+                    )))
+                    .style(Style::default().bg(self.theme.bg_panel)),
+            )
+            .render(popup, buf);
+    }
+
+    /// Scrollable project-wide report (see [`crate::ui::report`])
+    pub(super) fn render_report_overlay(&self, area: Rect, buf: &mut Buffer) {
+        let Some(report) = self.report else {
+            return;
+        };
+        let w = (area.width * 4 / 5)
+            .max(40)
+            .min(area.width.saturating_sub(2));
+        let h = (area.height * 4 / 5)
+            .max(10)
+            .min(area.height.saturating_sub(2));
+        let popup = Rect {
+            x: area.x + (area.width - w) / 2,
+            y: area.y + (area.height - h) / 2,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+
+        let mut lines: Vec<Line> = vec![
+            Line::from(Span::styled(
+                report.summary.as_str(),
+                self.theme.style_muted(),
+            )),
+            Line::from(""),
+        ];
+        for section in &report.sections {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{}{} ({})",
+                    self.theme.glyph("▸ ", "> "),
+                    section.heading,
+                    section.rows.len()
+                ),
+                self.theme.style_accent_bold(),
+            )));
+            if section.rows.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", section.empty),
+                    self.theme.style_dim(),
+                )));
+            }
+            for row in &section.rows {
+                let style = match row.level {
+                    ReportLevel::Normal => self.theme.style_normal(),
+                    ReportLevel::Warning => self.theme.style_warning(),
+                    ReportLevel::Error => self.theme.style_error(),
+                    ReportLevel::Muted => self.theme.style_dim(),
+                };
+                lines.push(Line::from(Span::styled(format!("  {}", row.text), style)));
+                if let Some(ref detail) = row.detail {
+                    lines.push(Line::from(Span::styled(
+                        format!("      {}", detail),
+                        self.theme.style_muted(),
+                    )));
+                }
+            }
+            lines.push(Line::from(""));
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.style_border_focused())
+            .title(format!(" {} ", report.title))
+            .title_bottom(Line::from(Span::styled(
+                " j/k scroll · Esc close ",
+                self.theme.style_muted(),
+            )))
+            .style(Style::default().bg(self.theme.bg_panel));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+
+        let total = lines.len();
+        let scroll = self
+            .report_scroll
+            .min(total.saturating_sub(inner.height as usize));
+        Paragraph::new(lines.into_iter().skip(scroll).collect::<Vec<_>>())
+            .wrap(Wrap { trim: false })
+            .render(inner, buf);
+        if total > inner.height as usize {
+            let mut state = ScrollbarState::new(total).position(scroll);
+            StatefulWidget::render(
+                Scrollbar::new(ScrollbarOrientation::VerticalRight),
+                inner,
+                buf,
+                &mut state,
+            );
+        }
+    }
+
+    /// Scrollable release notes between the installed and latest version of a dependency
+    pub(super) fn render_release_notes_overlay(&self, area: Rect, buf: &mut Buffer) {
+        if !self.show_release_notes {
+            return;
+        }
+        let w = (area.width * 4 / 5)
+            .max(40)
+            .min(area.width.saturating_sub(2));
+        let h = (area.height * 4 / 5)
+            .max(10)
+            .min(area.height.saturating_sub(2));
+        let popup = Rect {
+            x: area.x + (area.width - w) / 2,
+            y: area.y + (area.height - h) / 2,
+            width: w,
+            height: h,
+        };
+        Clear.render(popup, buf);
+
+        let mut lines: Vec<Line> = Vec::new();
+        let title = match self.release_notes {
+            Some(notes) => {
+                let source = match notes.source {
+                    ReleaseNotesSource::Releases => "GitHub releases",
+                    ReleaseNotesSource::Changelog => "CHANGELOG.md",
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(notes.installed.clone(), self.theme.style_muted()),
+                    Span::styled(" → ", self.theme.style_dim()),
+                    Span::styled(notes.latest.clone(), self.theme.style_accent_bold()),
+                    Span::styled(format!("  ({})", source), self.theme.style_dim()),
+                ]));
+                lines.push(Line::from(""));
+                if notes.notes.is_empty() {
+                    lines.push(Line::from(Span::styled(
+                        "No notes found for versions in this range.",
+                        self.theme.style_muted(),
+                    )));
+                }
+                for note in &notes.notes {
+                    let mut header = vec![Span::styled(
+                        format!("{}{}", self.theme.glyph("▸ ", "> "), note.title),
+                        self.theme.style_accent_bold(),
+                    )];
+                    if let Some(ref date) = note.date {
+                        header.push(Span::styled(
+                            format!("  {}", date),
+                            self.theme.style_muted(),
+                        ));
+                    }
+                    lines.push(Line::from(header));
+                    for line in note.body.lines() {
+                        lines.push(Line::from(markdown_line_to_spans(
+                            line,
+                            self.theme,
+                            self.theme.style_normal(),
+                        )));
+                    }
+                    lines.push(Line::from(""));
+                }
+                format!(" Release notes · {} ", notes.crate_name)
+            }
+            None => {
+                lines.push(Line::from(Span::styled(
+                    "Fetching release notes…",
+                    self.theme.style_muted(),
+                )));
+                " Release notes ".to_string()
+            }
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(self.theme.style_border_focused())
+            .title(title)
+            .title_bottom(Line::from(Span::styled(
+                " j/k scroll · Esc close ",
+                self.theme.style_muted(),
+            )))
+            .style(Style::default().bg(self.theme.bg_panel));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+
+        let total = lines.len();
+        let scroll = self
+            .release_notes_scroll
+            .min(total.saturating_sub(inner.height as usize));
+        Paragraph::new(lines.into_iter().skip(scroll).collect::<Vec<_>>())
+            .wrap(Wrap { trim: false })
+            .render(inner, buf);
+        if total > inner.height as usize {
+            let mut state = ScrollbarState::new(total).position(scroll);
+            StatefulWidget::render(
+                Scrollbar::new(ScrollbarOrientation::VerticalRight),
+                inner,
+                buf,
+                &mut state,
+            );
+        }
+    }
+}
+
+/// `just now`, `5m ago`, `3h ago`, `2d ago`
+fn format_ago(secs: u64) -> String {
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
 }
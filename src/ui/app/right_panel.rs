@@ -18,7 +18,11 @@ use super::types::{Focus, Tab};
 use super::OracleUi;
 
 /// Parse a line of markdown into styled spans: **bold**, `code`, ## header.
-fn markdown_line_to_spans(line: &str, theme: &Theme, base_style: Style) -> Vec<Span<'static>> {
+pub(super) fn markdown_line_to_spans(
+    line: &str,
+    theme: &Theme,
+    base_style: Style,
+) -> Vec<Span<'static>> {
     let mut spans: Vec<Span> = Vec::new();
     let bold = base_style.add_modifier(Modifier::BOLD);
     let code_style = theme.style_type();
@@ -98,17 +102,35 @@ impl<'a> OracleUi<'a> {
                 let inspector = InspectorPanel::new(self.theme)
                     .item(self.selected_item)
                     .all_items(self.all_items_impl_lookup)
+                    .usage_index(self.usage_index)
+                    .doc_example_selected(self.doc_example_selected)
                     .focused(self.focus == Focus::Inspector)
-                    .scroll(self.inspector_scroll);
+                    .scroll(self.inspector_scroll)
+                    .report_max_scroll(self.inspector_max_scroll)
+                    .report_top_section(self.inspector_top_section)
+                    .code_layout(self.code_wrap, self.code_hscroll)
+                    .method_sort(self.method_sort)
+                    .sections(self.inspector_sections)
+                    .raw(self.inspector_raw)
+                    .source_cursor(self.source_cursor)
+                    .diff(self.inspector_diff)
+                    .coverage(self.coverage)
+                    .cache(self.inspector_cache);
                 inspector.render(area, buf);
             }
         } else if self.current_tab == Tab::Crates {
             let root_name = self.dependency_tree.first().map(|(n, _)| n.as_str());
-            let selected_name = self
-                .list_selected
-                .and_then(|i| self.filtered_dependency_indices.get(i).copied())
-                .and_then(|tree_idx| self.dependency_tree.get(tree_idx))
-                .map(|(n, _)| n.as_str());
+            let selected_name = match self.registry_search {
+                Some(search) => self
+                    .list_selected
+                    .and_then(|i| search.results.get(i))
+                    .map(|r| r.name.as_str()),
+                None => self
+                    .list_selected
+                    .and_then(|i| self.filtered_dependency_indices.get(i).copied())
+                    .and_then(|tree_idx| self.dependency_tree.get(tree_idx))
+                    .map(|(n, _)| n.as_str()),
+            };
             let showing_root = root_name
                 .zip(selected_name)
                 .map(|(r, s)| r == s)
@@ -116,17 +138,32 @@ impl<'a> OracleUi<'a> {
             if showing_root {
                 let dep_view = DependencyView::new(self.theme)
                     .crate_info(self.crate_info)
+                    .msrv(self.msrv_report)
+                    .edition_issues(self.edition_issues)
+                    .duplicates(self.duplicates)
                     .focused(self.focus == Focus::Inspector)
                     .scroll(self.inspector_scroll)
                     .show_browser_hint(true);
                 dep_view.render(area, buf);
             } else if let Some(name) = selected_name {
-                if self.crate_doc_loading {
+                if self.offline && self.crate_doc.is_none() {
+                    dependency_view::render_doc_offline(self.theme, area, buf, name);
+                } else if self.crate_doc_loading {
                     dependency_view::render_doc_loading(self.theme, area, buf, name);
                 } else if self.crate_doc_failed {
                     dependency_view::render_doc_failed(self.theme, area, buf, name);
                 } else if let Some(doc) = self.crate_doc {
+                    let features = self
+                        .feature_unification
+                        .iter()
+                        .rfind(|f| f.name == name || f.name.replace('-', "_") == name);
+                    let mut direct: Vec<&str> = root_name.into_iter().collect();
+                    if let Some(info) = self.crate_info {
+                        direct.extend(info.dependencies.iter().map(|d| d.name.as_str()));
+                    }
                     let doc_view = DependencyDocView::new(self.theme, doc)
+                        .health(self.health_scores.and_then(|h| h.get(name)))
+                        .features(features, &direct)
                         .focused(self.focus == Focus::Inspector)
                         .scroll(self.inspector_scroll)
                         .show_browser_hint(true);
@@ -137,6 +174,9 @@ impl<'a> OracleUi<'a> {
             } else {
                 let dep_view = DependencyView::new(self.theme)
                     .crate_info(self.crate_info)
+                    .msrv(self.msrv_report)
+                    .edition_issues(self.edition_issues)
+                    .duplicates(self.duplicates)
                     .focused(self.focus == Focus::Inspector)
                     .scroll(self.inspector_scroll)
                     .show_browser_hint(true);
@@ -146,12 +186,58 @@ impl<'a> OracleUi<'a> {
             let inspector = InspectorPanel::new(self.theme)
                 .item(self.selected_item)
                 .all_items(self.all_items_impl_lookup)
+                .usage_index(self.usage_index)
+                .doc_example_selected(self.doc_example_selected)
                 .focused(self.focus == Focus::Inspector)
-                .scroll(self.inspector_scroll);
+                .scroll(self.inspector_scroll)
+                .report_max_scroll(self.inspector_max_scroll)
+                .report_top_section(self.inspector_top_section)
+                .code_layout(self.code_wrap, self.code_hscroll)
+                .method_sort(self.method_sort)
+                .sections(self.inspector_sections)
+                .raw(self.inspector_raw)
+                .source_cursor(self.source_cursor)
+                .diff(self.inspector_diff)
+                .coverage(self.coverage)
+                .doc_links(self.doc_links)
+                .review(self.review_notes)
+                .cache(self.inspector_cache);
             inspector.render(area, buf);
         }
     }
 
+    /// Whether the inspector shows code items (not the dependency list of the Crates tab)
+    pub(super) fn shows_items(&self) -> bool {
+        self.current_tab != Tab::Crates || self.selected_installed_crate.is_some()
+    }
+
+    /// Pinned item on the left, the current selection on the right
+    pub(super) fn render_pinned_split(&self, area: Rect, buf: &mut Buffer) {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let pinned = InspectorPanel::new(self.theme)
+            .item(self.pinned_item)
+            .all_items(self.all_items_impl_lookup)
+            .usage_index(self.usage_index)
+            .focused(self.focus == Focus::Pinned)
+            .scroll(self.pinned_scroll)
+            .report_max_scroll(self.pinned_max_scroll)
+            .code_layout(self.code_wrap, self.code_hscroll)
+            .method_sort(self.method_sort)
+            .sections(self.inspector_sections)
+            .raw(self.inspector_raw)
+            .diff(self.inspector_diff)
+            .coverage(self.coverage)
+            .doc_links(self.doc_links)
+            .review(self.review_notes)
+            .cache(self.inspector_cache)
+            .pinned(true);
+        pinned.render(halves[0], buf);
+        self.render_inspector(halves[1], buf);
+    }
+
     pub(super) fn render_installed_crate_info(&self, area: Rect, buf: &mut Buffer) {
         let crate_info = match self.selected_installed_crate {
             Some(c) => c,
@@ -164,7 +250,7 @@ impl<'a> OracleUi<'a> {
         };
         let mut lines = vec![
             Line::from(vec![
-                Span::styled("📦 ", Style::default()),
+                Span::styled(self.theme.glyph("📦 ", ""), Style::default()),
                 Span::styled(
                     &crate_info.name,
                     self.theme
@@ -254,7 +340,7 @@ impl<'a> OracleUi<'a> {
         ]));
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
-            Span::raw("  📁 "),
+            Span::raw(self.theme.glyph("  📁 ", "  ")),
             Span::styled(
                 crate_info.path.display().to_string(),
                 self.theme.style_muted(),
@@ -289,7 +375,7 @@ impl<'a> OracleUi<'a> {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(border_style)
-            .title(" ◇ Crate Info ");
+            .title(format!(" {}Crate Info ", self.theme.glyph("◇ ", "")));
         let paragraph = Paragraph::new(lines)
             .block(block)
             .wrap(Wrap { trim: false });
@@ -309,7 +395,7 @@ impl<'a> OracleUi<'a> {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(border_style)
-            .title(" ◇ Copilot ");
+            .title(format!(" {}Copilot ", self.theme.glyph("◇ ", "")));
         let inner = block.inner(area);
         block.render(area, buf);
 
@@ -361,7 +447,7 @@ impl<'a> OracleUi<'a> {
 
         let total_lines = lines.len();
         let visible_height = messages_area.height as usize;
-        let max_scroll = total_lines.saturating_sub(visible_height).max(0);
+        let max_scroll = total_lines.saturating_sub(visible_height);
         let scroll = self.copilot_chat_scroll.min(max_scroll);
 
         // Line-based scroll: slice the content (like inspector) so scroll is in line units, not rows.
@@ -413,7 +499,7 @@ impl<'a> OracleUi<'a> {
         let input_inner = input_block.inner(input_area);
         input_block.render(input_area, buf);
         let input_line = Paragraph::new(Line::from(vec![
-            Span::styled(" ▸ ", self.theme.style_dim()),
+            Span::styled(self.theme.glyph(" ▸ ", " > "), self.theme.style_dim()),
             Span::styled(input_display, input_style),
         ]));
         input_line.render(input_inner, buf);
@@ -0,0 +1,102 @@
+//! Virtualized rows: lists borrow the analyzed items through their filtered indices
+//! and only format the window that fits on screen, so a 50k-item crate costs the same
+//! per frame as a 50-item one.
+
+use crate::analyzer::AnalyzedItem;
+
+/// Filtered view of an item slice (indices into `items`, in display order)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FilteredItems<'a> {
+    items: &'a [AnalyzedItem],
+    indices: &'a [usize],
+}
+
+impl<'a> FilteredItems<'a> {
+    pub fn new(items: &'a [AnalyzedItem], indices: &'a [usize]) -> Self {
+        Self { items, indices }
+    }
+
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Item at display position `row`
+    pub fn get(&self, row: usize) -> Option<&'a AnalyzedItem> {
+        self.indices.get(row).and_then(|&i| self.items.get(i))
+    }
+
+    /// `(row, item)` pairs of the `height` rows starting at `offset`
+    pub fn window(
+        &self,
+        offset: usize,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, &'a AnalyzedItem)> + 'a {
+        let items = self.items;
+        let end = offset.saturating_add(height).min(self.indices.len());
+        let start = offset.min(end);
+        self.indices[start..end]
+            .iter()
+            .enumerate()
+            .filter_map(move |(n, &i)| items.get(i).map(|item| (start + n, item)))
+    }
+}
+
+/// First row to draw so that `selected` stays in a window of `visible_height` rows
+pub(super) fn scroll_offset(selected: Option<usize>, visible_height: usize) -> usize {
+    match selected {
+        Some(sel) if visible_height > 0 && sel >= visible_height => sel + 1 - visible_height,
+        _ => 0,
+    }
+}
+
+/// Item kind padded to the list's kind column; static so rows don't format it each frame
+pub(super) fn kind_column(kind: &'static str) -> &'static str {
+    match kind {
+        "fn" => "fn     ",
+        "struct" => "struct ",
+        "enum" => "enum   ",
+        "trait" => "trait  ",
+        "mod" => "mod    ",
+        "type" => "type   ",
+        "const" => "const  ",
+        "static" => "static ",
+        "impl" => "impl   ",
+        _ => kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_window_only_visits_visible_rows() {
+        let source: String = (0..50_000).map(|i| format!("fn f{i}() {{}}\n")).collect();
+        let items = RustAnalyzer::new().analyze_source(&source).unwrap();
+        let indices: Vec<usize> = (0..items.len()).rev().collect();
+        let filtered = FilteredItems::new(&items, &indices);
+
+        let offset = scroll_offset(Some(49_990), 20);
+        let rows: Vec<_> = filtered.window(offset, 20).collect();
+        assert_eq!(rows.len(), 20);
+        assert_eq!(rows[0].0, 49_971);
+        assert_eq!(rows[19].1.name(), "f9");
+        assert_eq!(filtered.window(49_995, 20).count(), 5);
+        assert_eq!(filtered.window(60_000, 20).count(), 0);
+        assert_eq!(filtered.get(0).unwrap().name(), "f49999");
+    }
+
+    #[test]
+    fn test_kind_column_width() {
+        for kind in [
+            "fn", "struct", "enum", "trait", "impl", "mod", "type", "const", "static",
+        ] {
+            assert_eq!(kind_column(kind), format!("{:6} ", kind));
+        }
+    }
+}
@@ -14,11 +14,13 @@ use super::OracleUi;
 impl<'a> OracleUi<'a> {
     pub(super) fn render_status(&self, area: Rect, buf: &mut Buffer) {
         let focus_indicator = match self.focus {
-            Focus::Search => ("🔍", "Search"),
-            Focus::List => ("📋", "List"),
-            Focus::Inspector => ("🔬", "Inspector"),
-            Focus::CopilotChat => ("💬", "Copilot"),
+            Focus::Search => (self.theme.glyph("🔍", ""), "Search"),
+            Focus::List => (self.theme.glyph("📋", ""), "List"),
+            Focus::Inspector => (self.theme.glyph("🔬", ""), "Inspector"),
+            Focus::Pinned => (self.theme.glyph("📌", ""), "Pinned"),
+            Focus::CopilotChat => (self.theme.glyph("💬", ""), "Copilot"),
         };
+        let crate_icon = self.theme.glyph("📦", "");
 
         let status_line =
             if self.current_tab == Tab::Crates && self.selected_installed_crate.is_some() {
@@ -29,7 +31,7 @@ impl<'a> OracleUi<'a> {
                         format!("[0/{}]", self.installed_crate_items.len())
                     };
                     Line::from(vec![
-                        Span::styled(" 📦 ", self.theme.style_accent()),
+                        Span::styled(format!(" {} ", crate_icon), self.theme.style_accent()),
                         Span::styled(&crate_info.name, self.theme.style_normal()),
                         Span::styled(format!(" v{}", crate_info.version), self.theme.style_dim()),
                         Span::styled(" │ ", self.theme.style_muted()),
@@ -53,7 +55,10 @@ impl<'a> OracleUi<'a> {
                     ])
                 } else {
                     Line::from(vec![
-                        Span::styled(" 📦 Crates ", self.theme.style_accent()),
+                        Span::styled(
+                            format!(" {} Crates ", crate_icon),
+                            self.theme.style_accent(),
+                        ),
                         Span::styled(focus_indicator.0, self.theme.style_accent()),
                         Span::styled(format!(" {} ", focus_indicator.1), self.theme.style_dim()),
                         Span::styled(" │ Tab ↑/↓ Enter / Esc back ", self.theme.style_muted()),
@@ -80,7 +85,7 @@ impl<'a> OracleUi<'a> {
                     Span::styled("[c]", self.theme.style_accent()),
                     Span::styled(" crates.io ", self.theme.style_muted()),
                     Span::styled("│ ", self.theme.style_dim()),
-                    Span::styled("📦", self.theme.style_accent()),
+                    Span::styled(crate_icon, self.theme.style_accent()),
                     Span::styled(
                         format!(" Crates ({}) ", self.filtered_dependency_indices.len()),
                         self.theme.style_normal(),
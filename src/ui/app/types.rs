@@ -1,7 +1,63 @@
 //! Shared UI types: tabs, focus.
 
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::FileSort;
+use crate::config::RecentProject;
+use crate::crates_io::CrateSearchResult;
+use crate::utils::manifest::DeclaredFeatures;
+
+/// Saved view picker (`V`): highlighted row, 0 being "all items", and the name
+/// typed so far while saving the current view
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewPicker {
+    pub selected: usize,
+    pub naming: Option<String>,
+}
+
+/// Recent projects picker (`O`): projects from the session, most recent first,
+/// and the time it was opened, for their ages
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectPicker {
+    pub selected: usize,
+    pub projects: Vec<RecentProject>,
+    pub now: u64,
+}
+
+/// Feature toggles of a direct dependency (`f` in the Crates tab): highlighted row,
+/// 0 being `default-features`, and what `Cargo.toml` currently requests
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeaturePicker {
+    pub crate_name: String,
+    pub selected: usize,
+    pub declared: DeclaredFeatures,
+}
+
+/// Spelling view (`W`): highlighted misspelling, in the order they were found
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpellingView {
+    pub selected: usize,
+}
+
+/// Files view (`F`): highlighted row in the current order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilesView {
+    pub selected: usize,
+    pub sort: FileSort,
+}
+
+/// crates.io search in the Crates tab (a query starting with `@`); `results` are
+/// from the last finished search, `loading` while one for `query` runs
+#[derive(Debug, Clone, Default)]
+pub struct RegistrySearch {
+    pub query: String,
+    pub results: Vec<CrateSearchResult>,
+    pub loading: bool,
+}
+
 /// Active tab in the UI (Crates = project crates from Cargo.toml + open crate items)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Tab {
     #[default]
     Types,
@@ -58,17 +114,20 @@ pub enum Focus {
     Search,
     List,
     Inspector,
+    /// Pinned item inspector (only when an item is pinned)
+    Pinned,
     /// In-TUI Copilot chat panel (only when copilot_chat_open)
     CopilotChat,
 }
 
 impl Focus {
-    /// Next focus; when `copilot_chat_open` is true, Inspector -> CopilotChat -> Search.
-    pub fn next(&self, copilot_chat_open: bool) -> Self {
+    /// Next focus: Search -> List -> Inspector -> Pinned (if `pinned`) -> CopilotChat (if open) -> Search.
+    pub fn next(&self, copilot_chat_open: bool, pinned: bool) -> Self {
         match self {
             Focus::Search => Focus::List,
             Focus::List => Focus::Inspector,
-            Focus::Inspector => {
+            Focus::Inspector if pinned => Focus::Pinned,
+            Focus::Inspector | Focus::Pinned => {
                 if copilot_chat_open {
                     Focus::CopilotChat
                 } else {
@@ -80,18 +139,13 @@ impl Focus {
     }
 
     /// Previous focus.
-    pub fn prev(&self, copilot_chat_open: bool) -> Self {
+    pub fn prev(&self, copilot_chat_open: bool, pinned: bool) -> Self {
         match self {
-            Focus::Search => {
-                if copilot_chat_open {
-                    Focus::CopilotChat
-                } else {
-                    Focus::Inspector
-                }
-            }
+            Focus::Search if copilot_chat_open => Focus::CopilotChat,
+            Focus::Search | Focus::CopilotChat if pinned => Focus::Pinned,
+            Focus::Search | Focus::CopilotChat | Focus::Pinned => Focus::Inspector,
             Focus::List => Focus::Search,
             Focus::Inspector => Focus::List,
-            Focus::CopilotChat => Focus::Inspector,
         }
     }
 }
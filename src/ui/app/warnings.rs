@@ -0,0 +1,98 @@
+//! Collapsible panel listing paths skipped during analysis and code that didn't parse.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use super::OracleUi;
+
+/// Most warning rows shown when the panel is expanded.
+const MAX_ROWS: u16 = 6;
+
+impl<'a> OracleUi<'a> {
+    /// Rows the warnings panel needs (0 when there is nothing to report)
+    pub(super) fn warnings_height(&self) -> u16 {
+        match self.warnings.len() {
+            0 => 0,
+            _ if !self.show_warnings => 1,
+            n => (n as u16).min(MAX_ROWS) + 1,
+        }
+    }
+
+    pub(super) fn render_warnings(&self, area: Rect, buf: &mut Buffer) {
+        if self.warnings.is_empty() || area.height == 0 {
+            return;
+        }
+        let count = self.warnings.len();
+        let spans = self.warnings.iter().filter(|w| w.lines.is_some()).count();
+        let paths = count - spans;
+        let mut counts = Vec::new();
+        if paths > 0 {
+            counts.push(format!(
+                "{} path{}",
+                paths,
+                if paths == 1 { "" } else { "s" }
+            ));
+        }
+        if spans > 0 {
+            counts.push(format!(
+                "{} unparsed span{}",
+                spans,
+                if spans == 1 { "" } else { "s" }
+            ));
+        }
+        let (arrow, hint) = if self.show_warnings {
+            (self.theme.glyph("▾", "v"), "[w] hide")
+        } else {
+            (self.theme.glyph("▸", ">"), "[w] show")
+        };
+        let summary = Line::from(vec![
+            Span::styled(
+                format!("{} {} ", arrow, self.theme.glyph("⚠", "!")),
+                self.theme.style_accent(),
+            ),
+            Span::styled(
+                format!("{} skipped during analysis ", counts.join(", ")),
+                self.theme.style_normal(),
+            ),
+            Span::styled(hint, self.theme.style_muted()),
+        ]);
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .border_style(self.theme.style_border())
+            .style(Style::default().bg(self.theme.bg_panel))
+            .title(summary);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if !self.show_warnings {
+            return;
+        }
+
+        let rows = inner.height as usize;
+        if rows == 0 {
+            return;
+        }
+        let mut lines: Vec<Line> = self
+            .warnings
+            .iter()
+            .take(if count > rows { rows - 1 } else { rows })
+            .map(|w| {
+                Line::from(vec![
+                    Span::styled(format!("  {}", w.location()), self.theme.style_dim()),
+                    Span::styled(format!("  {}", w.reason), self.theme.style_muted()),
+                ])
+            })
+            .collect();
+        if count > rows {
+            lines.push(Line::from(Span::styled(
+                format!("  … and {} more", count - lines.len()),
+                self.theme.style_muted(),
+            )));
+        }
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
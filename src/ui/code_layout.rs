@@ -0,0 +1,103 @@
+//! Laying out code wider than the inspector: Rust-aware soft wrapping, or a
+//! horizontally scrolled window onto each line.
+
+/// Continuation lines are indented this much past the original line
+const CONTINUATION_INDENT: usize = 4;
+/// Below this width breaking lines up does more harm than good
+const MIN_WRAP_WIDTH: usize = 16;
+
+/// Split `line` into lines of at most `width` chars, breaking after `,` and before
+/// `->` / `where` so parameters and return types stay whole. Pieces that are still
+/// too long are left for the paragraph's own wrapping.
+pub fn wrap_rust_line(line: &str, width: usize) -> Vec<String> {
+    if width < MIN_WRAP_WIDTH || line.chars().count() <= width {
+        return vec![line.to_string()];
+    }
+    let indent_len = line.len() - line.trim_start().len();
+    let continuation = " ".repeat(indent_len + CONTINUATION_INDENT);
+
+    let mut out: Vec<String> = Vec::new();
+    let mut current = line[..indent_len].to_string();
+    for piece in break_points(&line[indent_len..]) {
+        let fresh = current.trim().is_empty();
+        if !fresh && current.chars().count() + piece.chars().count() > width {
+            out.push(current.trim_end().to_string());
+            current = format!("{continuation}{}", piece.trim_start());
+        } else {
+            current.push_str(piece);
+        }
+    }
+    out.push(current.trim_end().to_string());
+    out
+}
+
+/// Pieces of `code` ending after each `, ` and before each ` -> ` / ` where `
+fn break_points(code: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    for (i, c) in code.char_indices() {
+        let split_at = if c == ',' && code[i + 1..].starts_with(' ') {
+            Some(i + 2)
+        } else if code[i..].starts_with(" -> ") || code[i..].starts_with(" where ") {
+            Some(i)
+        } else {
+            None
+        };
+        if let Some(at) = split_at.filter(|&at| at > start) {
+            pieces.push(&code[start..at]);
+            start = at;
+        }
+    }
+    pieces.push(&code[start..]);
+    pieces
+}
+
+/// The `width` chars of `line` starting at char `offset`, with `‹` / `›` marking
+/// text cut off on either side
+pub fn scroll_line(line: &str, offset: usize, width: usize) -> String {
+    let total = line.chars().count();
+    if width == 0 {
+        return String::new();
+    }
+    let offset = offset.min(total.saturating_sub(1));
+    let mut visible: Vec<char> = line.chars().skip(offset).take(width).collect();
+    if offset > 0 && !visible.is_empty() {
+        visible[0] = '‹';
+    }
+    if offset + width < total {
+        if let Some(last) = visible.last_mut() {
+            *last = '›';
+        }
+    }
+    visible.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_breaks_on_commas_and_arrow() {
+        let sig =
+            "pub fn connect(host: &str, port: u16, timeout: Duration) -> Result<Connection, Error>";
+        let lines = wrap_rust_line(sig, 40);
+        assert_eq!(
+            lines,
+            vec![
+                "pub fn connect(host: &str, port: u16,",
+                "    timeout: Duration)",
+                "    -> Result<Connection, Error>",
+            ]
+        );
+        assert!(lines.iter().all(|l| l.chars().count() <= 40));
+        assert_eq!(wrap_rust_line("fn short()", 40), vec!["fn short()"]);
+    }
+
+    #[test]
+    fn test_scroll_line_marks_cut_edges() {
+        assert_eq!(scroll_line("fn abcdefgh()", 0, 6), "fn ab›");
+        assert_eq!(scroll_line("fn abcdefgh()", 3, 6), "‹bcde›");
+        assert_eq!(scroll_line("fn abcdefgh()", 9, 6), "‹h()");
+        assert_eq!(scroll_line("fn a()", 0, 10), "fn a()");
+    }
+}
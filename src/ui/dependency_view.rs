@@ -3,7 +3,7 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Modifier,
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
         block::BorderType, Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation,
@@ -11,13 +11,19 @@ use ratatui::{
     },
 };
 
-use crate::analyzer::{CrateInfo, DependencyKind};
-use crate::crates_io::CrateDocInfo;
+use crate::analyzer::{
+    CrateInfo, DependencyKind, DuplicateCrate, EditionIssue, FeatureUnification, MsrvReport,
+};
+use crate::crates_io::{CrateDocInfo, HealthScore};
 use crate::ui::theme::Theme;
+use crate::utils::{format_bytes, format_number};
 
 /// View for displaying dependency information (scrollable). No tree chart; list is in the list panel.
 pub struct DependencyView<'a> {
     crate_info: Option<&'a CrateInfo>,
+    msrv: Option<&'a MsrvReport>,
+    edition_issues: &'a [EditionIssue],
+    duplicates: &'a [DuplicateCrate],
     theme: &'a Theme,
     focused: bool,
     scroll_offset: usize,
@@ -28,6 +34,9 @@ impl<'a> DependencyView<'a> {
     pub fn new(theme: &'a Theme) -> Self {
         Self {
             crate_info: None,
+            msrv: None,
+            edition_issues: &[],
+            duplicates: &[],
             theme,
             focused: false,
             scroll_offset: 0,
@@ -45,6 +54,22 @@ impl<'a> DependencyView<'a> {
         self
     }
 
+    pub fn msrv(mut self, report: Option<&'a MsrvReport>) -> Self {
+        self.msrv = report;
+        self
+    }
+
+    /// Code the crate's edition doesn't accept, listed under the edition
+    pub fn edition_issues(mut self, issues: &'a [EditionIssue]) -> Self {
+        self.edition_issues = issues;
+        self
+    }
+
+    pub fn duplicates(mut self, duplicates: &'a [DuplicateCrate]) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
     pub fn focused(mut self, focused: bool) -> Self {
         self.focused = focused;
         self
@@ -68,7 +93,7 @@ impl<'a> DependencyView<'a> {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(self.theme.style_border())
-            .title(" ◇ Crates ");
+            .title(format!(" {}Crates ", self.theme.glyph("◇ ", "")));
 
         let inner = block.inner(area);
         block.render(area, buf);
@@ -148,8 +173,9 @@ impl<'a> DependencyView<'a> {
 
         lines.push(Line::from(vec![
             Span::styled("Edition: ", self.theme.style_dim()),
-            Span::raw(info.edition.clone()),
+            Span::styled(info.edition.clone(), self.theme.style_accent_bold()),
         ]));
+        self.push_edition_lines(&mut lines);
 
         if let Some(ref rust_ver) = info.rust_version {
             lines.push(Line::from(vec![
@@ -158,6 +184,10 @@ impl<'a> DependencyView<'a> {
             ]));
         }
 
+        if let Some(report) = self.msrv {
+            self.push_msrv_lines(report, &mut lines);
+        }
+
         // Links
         lines.push(Line::from(""));
         if let Some(ref repo) = info.repository {
@@ -248,9 +278,153 @@ impl<'a> DependencyView<'a> {
             ]));
         }
 
+        self.push_duplicate_lines(&mut lines);
+
         lines
     }
 
+    /// Crates resolved at several versions, who pulls each one, and how to dedupe
+    fn push_duplicate_lines(&self, lines: &mut Vec<Line<'static>>) {
+        if self.duplicates.is_empty() {
+            return;
+        }
+        let total: u64 = self.duplicates.iter().map(|d| d.duplicated_bytes()).sum();
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("Duplicates ({}):", self.duplicates.len()),
+                self.theme.style_dim(),
+            ),
+            Span::styled(
+                format!(" ~{} of extra sources to compile", format_bytes(total)),
+                self.theme.style_warning(),
+            ),
+        ]));
+        for dup in self.duplicates {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(dup.name.clone(), self.theme.style_type()),
+                Span::styled(
+                    format!(" ×{}", dup.versions.len()),
+                    self.theme.style_warning(),
+                ),
+                Span::styled(
+                    format!("  +{}", format_bytes(dup.duplicated_bytes())),
+                    self.theme.style_muted(),
+                ),
+            ]));
+            for version in &dup.versions {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("    {:<10}", version.version),
+                        self.theme.style_accent(),
+                    ),
+                    Span::styled(
+                        format!(" ← {}", version.dependents.join(", ")),
+                        self.theme.style_muted(),
+                    ),
+                ]));
+            }
+            for suggestion in dup.suggestions() {
+                lines.push(Line::from(vec![
+                    Span::styled("    $ ", self.theme.style_dim()),
+                    Span::styled(suggestion, self.theme.style_string()),
+                ]));
+            }
+        }
+    }
+
+    /// Up to five places using syntax the edition doesn't have, and how many more
+    fn push_edition_lines(&self, lines: &mut Vec<Line<'static>>) {
+        const SHOWN: usize = 5;
+        for issue in self.edition_issues.iter().take(SHOWN) {
+            let file = issue.file.file_name().unwrap_or(issue.file.as_os_str());
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}:{} ", file.to_string_lossy(), issue.line),
+                    self.theme.style_muted(),
+                ),
+                Span::styled(issue.message.clone(), self.theme.style_warning()),
+            ]));
+        }
+        if self.edition_issues.len() > SHOWN {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  ... and {} more (oracle audit lists them)",
+                    self.edition_issues.len() - SHOWN
+                ),
+                self.theme.style_muted(),
+            )));
+        }
+    }
+
+    /// Effective MSRV across dependencies and those exceeding the declared `rust-version`
+    fn push_msrv_lines(&self, report: &MsrvReport, lines: &mut Vec<Line<'static>>) {
+        let Some(effective) = report.effective() else {
+            return;
+        };
+        let exceeding = report.exceeding();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Rust Version (MSRV):",
+            self.theme.style_dim(),
+        )));
+        lines.push(Line::from(vec![
+            Span::styled("  declared   ", self.theme.style_dim()),
+            match report.declared {
+                Some(ref v) => Span::raw(v.clone()),
+                None => Span::styled("not set", self.theme.style_muted()),
+            },
+        ]));
+        let effective_style = if exceeding.is_empty() {
+            self.theme.style_success()
+        } else {
+            self.theme.style_warning()
+        };
+        lines.push(Line::from(vec![
+            Span::styled("  effective  ", self.theme.style_dim()),
+            Span::styled(effective.rust_version.clone(), effective_style),
+            Span::styled(
+                format!(" (from {} {})", effective.name, effective.version),
+                self.theme.style_muted(),
+            ),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {} of {} dependencies declare rust-version",
+                report.entries.len(),
+                report.entries.len() + report.undeclared
+            ),
+            self.theme.style_muted(),
+        )));
+        if let (false, Some(declared)) = (exceeding.is_empty(), report.declared.as_ref()) {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {} {} dependencies need a newer toolchain than {}:",
+                    self.theme.glyph("⚠", "!"),
+                    exceeding.len(),
+                    declared
+                ),
+                self.theme.style_warning(),
+            )));
+            for entry in exceeding.iter().take(10) {
+                lines.push(Line::from(vec![
+                    Span::raw("    "),
+                    Span::styled(entry.name.clone(), self.theme.style_type()),
+                    Span::styled(format!(" {}", entry.version), self.theme.style_muted()),
+                    Span::styled(" requires ", self.theme.style_dim()),
+                    Span::styled(entry.rust_version.clone(), self.theme.style_warning()),
+                ]));
+            }
+            if exceeding.len() > 10 {
+                lines.push(Line::from(Span::styled(
+                    format!("    ... and {} more", exceeding.len() - 10),
+                    self.theme.style_muted(),
+                )));
+            }
+        }
+    }
+
     fn render_crate_info(&self, info: &CrateInfo, area: Rect, buf: &mut Buffer) {
         let lines = self.build_crate_info_lines(info);
         let total_lines = lines.len();
@@ -267,7 +441,7 @@ impl<'a> DependencyView<'a> {
             } else {
                 self.theme.style_border()
             })
-            .title(" ◇ Crates ");
+            .title(format!(" {}Crates ", self.theme.glyph("◇ ", "")));
 
         let inner = block.inner(area);
         block.render(area, buf);
@@ -317,6 +491,10 @@ impl Widget for DependencyView<'_> {
 /// View for a dependency's docs from crates.io (scrollable).
 pub struct DependencyDocView<'a> {
     doc: &'a CrateDocInfo,
+    health: Option<&'a HealthScore>,
+    features: Option<&'a FeatureUnification>,
+    /// Root crate and its direct dependencies (highlighted as feature requesters)
+    direct: &'a [&'a str],
     theme: &'a Theme,
     focused: bool,
     scroll_offset: usize,
@@ -327,6 +505,9 @@ impl<'a> DependencyDocView<'a> {
     pub fn new(theme: &'a Theme, doc: &'a CrateDocInfo) -> Self {
         Self {
             doc,
+            health: None,
+            features: None,
+            direct: &[],
             theme,
             focused: false,
             scroll_offset: 0,
@@ -344,14 +525,185 @@ impl<'a> DependencyDocView<'a> {
         self
     }
 
+    pub fn features(
+        mut self,
+        features: Option<&'a FeatureUnification>,
+        direct: &'a [&'a str],
+    ) -> Self {
+        self.features = features;
+        self.direct = direct;
+        self
+    }
+
     pub fn show_browser_hint(mut self, show: bool) -> Self {
         self.show_browser_hint = show;
         self
     }
 
+    pub fn health(mut self, health: Option<&'a HealthScore>) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Health score and the points each signal contributed
+    fn push_health_lines(&self, health: &HealthScore, lines: &mut Vec<Line<'_>>) {
+        lines.push(self.section_title("Health"));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {}/100 ", health.score),
+                health_style(self.theme, health),
+            ),
+            Span::styled(health.grade(), self.theme.style_muted()),
+        ]));
+        for s in &health.signals {
+            let style = if s.points == s.max {
+                self.theme.style_success()
+            } else if s.points * 2 >= s.max {
+                self.theme.style_normal()
+            } else {
+                self.theme.style_warning()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<18}", s.label), self.theme.style_dim()),
+                Span::styled(format!("{:>2}/{:<2} ", s.points, s.max), style),
+                Span::styled(s.detail.clone(), self.theme.style_muted()),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    /// Downloads, reverse dependencies, owners and recent releases from crates.io
+    fn push_registry_lines(&self, lines: &mut Vec<Line<'_>>) {
+        let doc = self.doc;
+        let has_stats = doc.downloads.is_some()
+            || doc.reverse_dependencies.is_some()
+            || !doc.owners.is_empty()
+            || !doc.versions.is_empty();
+        if !has_stats {
+            return;
+        }
+        lines.push(self.section_title("crates.io"));
+        lines.push(Line::from(""));
+        if let Some(total) = doc.downloads {
+            let mut spans = vec![
+                Span::styled("  Downloads: ", self.theme.style_dim()),
+                Span::styled(format_number(total), self.theme.style_accent()),
+            ];
+            if let Some(recent) = doc.recent_downloads {
+                spans.push(Span::styled(
+                    format!(" ({} last 90 days)", format_number(recent)),
+                    self.theme.style_muted(),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        if let Some(n) = doc.reverse_dependencies {
+            lines.push(Line::from(vec![
+                Span::styled("  Used by:   ", self.theme.style_dim()),
+                Span::styled(format_number(n), self.theme.style_accent()),
+                Span::styled(" crates", self.theme.style_muted()),
+            ]));
+        }
+        if !doc.owners.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("  Owners:    ", self.theme.style_dim()),
+                Span::styled(doc.owners.join(", "), self.theme.style_normal()),
+            ]));
+        }
+        if !doc.versions.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("  Releases ({} total):", doc.version_count),
+                self.theme.style_dim(),
+            )));
+            for v in &doc.versions {
+                let date = v
+                    .created_at
+                    .as_deref()
+                    .map(|d| d.get(..10).unwrap_or(d))
+                    .unwrap_or("");
+                let mut spans = vec![
+                    Span::styled(format!("    {:<12}", v.num), self.theme.style_type()),
+                    Span::styled(format!("{:<11}", date), self.theme.style_muted()),
+                ];
+                if let Some(d) = v.downloads {
+                    spans.push(Span::styled(
+                        format!("{:>7} ↓", format_number(d)),
+                        self.theme.style_dim(),
+                    ));
+                }
+                if v.yanked {
+                    spans.push(Span::styled(" yanked", self.theme.style_error()));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        lines.push(Line::from(""));
+    }
+
+    /// Resolved features vs defaults, with who requested each one
+    fn push_feature_lines(&self, features: &FeatureUnification, lines: &mut Vec<Line<'_>>) {
+        lines.push(self.section_title("Features (resolved)"));
+        lines.push(Line::from(""));
+        let disabled: Vec<&String> = features.disabled_defaults().collect();
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {} enabled · {} default · {} extra · {} default off",
+                features.enabled.len(),
+                features.default.len(),
+                features.non_default().count(),
+                disabled.len()
+            ),
+            self.theme.style_muted(),
+        )));
+        for feature in &features.enabled {
+            let tag = if features.default.contains(feature) {
+                Span::styled(" [default]", self.theme.style_muted())
+            } else if feature != "default" {
+                Span::styled(" [extra]", self.theme.style_warning())
+            } else {
+                Span::raw("")
+            };
+            let mut spans = vec![
+                Span::styled(self.theme.glyph("  ✓ ", "  + "), self.theme.style_success()),
+                Span::styled(feature.clone(), self.theme.style_string()),
+                tag,
+            ];
+            let requesters = features.requesters(feature);
+            if requesters.is_empty() {
+                spans.push(Span::styled(" (implied)", self.theme.style_dim()));
+            } else {
+                spans.push(Span::styled(
+                    self.theme.glyph(" ← ", " <- "),
+                    self.theme.style_dim(),
+                ));
+                for (i, requester) in requesters.iter().enumerate() {
+                    if i > 0 {
+                        spans.push(Span::styled(", ", self.theme.style_dim()));
+                    }
+                    let style = if self.direct.contains(&requester.as_str()) {
+                        self.theme.style_accent()
+                    } else {
+                        self.theme.style_muted()
+                    };
+                    spans.push(Span::styled(requester.clone(), style));
+                }
+            }
+            lines.push(Line::from(spans));
+        }
+        for feature in disabled {
+            lines.push(Line::from(vec![
+                Span::styled(self.theme.glyph("  ✗ ", "  - "), self.theme.style_dim()),
+                Span::styled(feature.clone(), self.theme.style_muted()),
+                Span::styled(" [default, off]", self.theme.style_dim()),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
     fn section_title(&self, title: &str) -> Line<'static> {
         Line::from(vec![
-            Span::styled("▸ ", self.theme.style_accent()),
+            Span::styled(self.theme.glyph("▸ ", "> "), self.theme.style_accent()),
             Span::styled(
                 title.to_string(),
                 self.theme.style_accent().add_modifier(Modifier::BOLD),
@@ -391,6 +743,16 @@ impl<'a> DependencyDocView<'a> {
             lines.push(Line::from(""));
         }
 
+        if let Some(health) = self.health {
+            self.push_health_lines(health, &mut lines);
+        }
+
+        self.push_registry_lines(&mut lines);
+
+        if let Some(features) = self.features {
+            self.push_feature_lines(features, &mut lines);
+        }
+
         let has_links = self.doc.documentation.is_some()
             || self.doc.homepage.is_some()
             || self.doc.repository.is_some();
@@ -499,7 +861,11 @@ impl Widget for DependencyDocView<'_> {
             } else {
                 self.theme.style_border()
             })
-            .title(format!(" ◇ {} (docs) ", self.doc.name));
+            .title(format!(
+                " {}{} (docs) ",
+                self.theme.glyph("◇ ", ""),
+                self.doc.name
+            ));
 
         let inner = block.inner(area);
         block.render(area, buf);
@@ -523,7 +889,9 @@ impl Widget for DependencyDocView<'_> {
                 Span::styled(" [o] ", self.theme.style_accent()),
                 Span::styled("docs.rs  ", self.theme.style_dim()),
                 Span::styled(" [c] ", self.theme.style_accent()),
-                Span::styled("crates.io", self.theme.style_dim()),
+                Span::styled("crates.io  ", self.theme.style_dim()),
+                Span::styled(" [r] ", self.theme.style_accent()),
+                Span::styled("release notes", self.theme.style_dim()),
             ]);
             Paragraph::new(hint_line).render(
                 Rect {
@@ -538,12 +906,21 @@ impl Widget for DependencyDocView<'_> {
 }
 
 /// Render "Loading documentation for X..." in the inspector area.
+/// Color of a health score: success, warning or error by grade
+pub fn health_style(theme: &Theme, health: &HealthScore) -> Style {
+    match health.score {
+        75.. => theme.style_success(),
+        50.. => theme.style_warning(),
+        _ => theme.style_error(),
+    }
+}
+
 pub fn render_doc_loading(theme: &Theme, area: Rect, buf: &mut Buffer, crate_name: &str) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(theme.style_border())
-        .title(format!(" ◇ {} ", crate_name));
+        .title(format!(" {}{} ", theme.glyph("◇ ", ""), crate_name));
 
     let inner = block.inner(area);
     block.render(area, buf);
@@ -575,12 +952,44 @@ pub fn render_doc_loading(theme: &Theme, area: Rect, buf: &mut Buffer, crate_nam
 }
 
 /// Render "Failed to load docs for X" in the inspector area.
+/// Offline mode and nothing cached: explain why there is no crates.io data.
+pub fn render_doc_offline(theme: &Theme, area: Rect, buf: &mut Buffer, crate_name: &str) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.style_border())
+        .title(format!(" {}{} ", theme.glyph("◇ ", ""), crate_name));
+    let inner = block.inner(area);
+    block.render(area, buf);
+    let text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            theme.glyph("⊘ offline", "offline"),
+            theme.style_warning(),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "crates.io and GitHub data for {} is not cached.",
+                crate_name
+            ),
+            theme.style_muted(),
+        )),
+        Line::from(Span::styled(
+            "Disable `network.offline` in the config (or drop --offline) to fetch it.",
+            theme.style_dim(),
+        )),
+    ];
+    Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .render(inner, buf);
+}
+
 pub fn render_doc_failed(theme: &Theme, area: Rect, buf: &mut Buffer, crate_name: &str) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(theme.style_border())
-        .title(format!(" ◇ {} ", crate_name));
+        .title(format!(" {}{} ", theme.glyph("◇ ", ""), crate_name));
 
     let inner = block.inner(area);
     block.render(area, buf);
@@ -0,0 +1,156 @@
+//! Lightweight Rust syntax highlighting for code shown in panels
+//!
+//! Line-based and lexer-free: good enough for doc examples and definitions, not a
+//! substitute for a real parser (multi-line strings/comments are not tracked).
+
+use ratatui::text::Span;
+
+use crate::ui::theme::Theme;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Highlight one line of Rust code
+pub fn highlight_rust_line(line: &str, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let mut i = 0;
+    let mut plain_start = 0;
+
+    let flush = |spans: &mut Vec<Span<'static>>, from: usize, to: usize| {
+        if to > from {
+            spans.push(Span::styled(
+                line[from..to].to_string(),
+                theme.style_normal(),
+            ));
+        }
+    };
+    let end_of = |idx: usize| chars.get(idx).map(|(b, _)| *b).unwrap_or(line.len());
+
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        if c == '/' && chars.get(i + 1).is_some_and(|(_, n)| *n == '/') {
+            flush(&mut spans, plain_start, start);
+            spans.push(Span::styled(
+                line[start..].to_string(),
+                theme.style_comment(),
+            ));
+            return spans;
+        }
+        if c == '"' {
+            flush(&mut spans, plain_start, start);
+            let mut j = i + 1;
+            while j < chars.len() && chars[j].1 != '"' {
+                if chars[j].1 == '\\' {
+                    j += 1;
+                }
+                j += 1;
+            }
+            let end = end_of((j + 1).min(chars.len()));
+            spans.push(Span::styled(
+                line[start..end].to_string(),
+                theme.style_string(),
+            ));
+            i = j + 1;
+            plain_start = end;
+            continue;
+        }
+        if c.is_ascii_digit() && (i == 0 || !is_ident_char(chars[i - 1].1)) {
+            flush(&mut spans, plain_start, start);
+            let mut j = i;
+            while j < chars.len()
+                && (chars[j].1.is_ascii_alphanumeric() || chars[j].1 == '_' || chars[j].1 == '.')
+            {
+                j += 1;
+            }
+            let end = end_of(j);
+            spans.push(Span::styled(
+                line[start..end].to_string(),
+                theme.style_number(),
+            ));
+            i = j;
+            plain_start = end;
+            continue;
+        }
+        if is_ident_start(c) && (i == 0 || !is_ident_char(chars[i - 1].1)) {
+            let mut j = i;
+            while j < chars.len() && is_ident_char(chars[j].1) {
+                j += 1;
+            }
+            let end = end_of(j);
+            let word = &line[start..end];
+            let next = chars.get(j).map(|(_, n)| *n);
+            let style = if KEYWORDS.contains(&word) {
+                Some(theme.style_keyword())
+            } else if next == Some('!') || next == Some('(') {
+                Some(theme.style_function())
+            } else if word.chars().next().is_some_and(char::is_uppercase) {
+                Some(theme.style_type())
+            } else {
+                None
+            };
+            if let Some(style) = style {
+                flush(&mut spans, plain_start, start);
+                spans.push(Span::styled(word.to_string(), style));
+                plain_start = end;
+            }
+            i = j;
+            continue;
+        }
+        i += 1;
+    }
+    flush(&mut spans, plain_start, line.len());
+    spans
+}
+
+/// Char columns (start, end) of the identifiers on one line of Rust code: keywords,
+/// numbers, lifetimes, strings and comments left out, with the same line-based
+/// limits as [`highlight_rust_line`]
+pub fn identifiers(line: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            break;
+        }
+        if c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+        if is_ident_char(c) {
+            let start = i;
+            while i < chars.len() && is_ident_char(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let lifetime = start > 0 && chars[start - 1] == '\'';
+            if is_ident_start(c) && !lifetime && !KEYWORDS.contains(&word.as_str()) {
+                found.push((start, i));
+            }
+            continue;
+        }
+        i += 1;
+    }
+    found
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
@@ -0,0 +1,418 @@
+//! One item as a standalone HTML page, rustdoc-style
+//!
+//! The page has no external stylesheet, script or font: colors come from the
+//! current theme (its RGB palette, even in 16-color mode) and code is highlighted
+//! with the same rules as the inspector, so the file can be attached to a chat or
+//! a code review as is.
+
+use ratatui::style::Color;
+
+use crate::analyzer::usage::base_type_name;
+use crate::analyzer::{
+    method_table, AnalyzedItem, ImplInfo, MethodSort, TraitMethod, Variant, VariantFields,
+};
+use crate::ui::highlight::highlight_rust_line;
+use crate::ui::theme::Theme;
+
+/// Complete HTML document for `item`; `all_items` supplies its methods and impls
+pub fn item_page(item: &AnalyzedItem, all_items: &[AnalyzedItem], theme: &Theme) -> String {
+    let theme = Theme::from_name(&theme.name);
+    let mut body = String::new();
+    body.push_str(&format!(
+        "<h1><span class=\"kind\">{}</span> {}</h1>\n",
+        item.kind(),
+        escape(&item.qualified_name())
+    ));
+    if let Some(location) = item.source_location().filter(|l| l.file.is_some()) {
+        body.push_str(&format!(
+            "<p class=\"muted\">{}</p>\n",
+            escape(&location.to_string())
+        ));
+    }
+    body.push_str(&code_block(&item.definition(), &theme));
+    if let Some(docs) = item.documentation() {
+        body.push_str(&docs_html(docs, &theme));
+    }
+
+    match item {
+        AnalyzedItem::Struct(st) => {
+            let fields: Vec<Entry> = st
+                .fields
+                .iter()
+                .map(|f| Entry {
+                    code: format!("{}: {}", f.name, f.ty),
+                    docs: f.documentation.as_deref(),
+                })
+                .collect();
+            section(&mut body, "Fields", &fields, &theme);
+            type_members(&mut body, &st.name, all_items, &theme);
+        }
+        AnalyzedItem::Enum(en) => {
+            let variants: Vec<Entry> = en
+                .variants
+                .iter()
+                .map(|v| Entry {
+                    code: variant_code(v),
+                    docs: v.documentation.as_deref(),
+                })
+                .collect();
+            section(&mut body, "Variants", &variants, &theme);
+            type_members(&mut body, &en.name, all_items, &theme);
+        }
+        AnalyzedItem::Trait(tr) => {
+            let (provided, required): (Vec<_>, Vec<_>) =
+                tr.methods.iter().partition(|m| m.has_default);
+            section(
+                &mut body,
+                "Required Methods",
+                &trait_methods(required),
+                &theme,
+            );
+            section(
+                &mut body,
+                "Provided Methods",
+                &trait_methods(provided),
+                &theme,
+            );
+            let implementors: Vec<Entry> = impls(all_items)
+                .filter(|im| {
+                    im.trait_name
+                        .as_deref()
+                        .is_some_and(|t| t == tr.name || t.ends_with(&format!("::{}", tr.name)))
+                })
+                .map(|im| Entry {
+                    code: im.full_definition(),
+                    docs: None,
+                })
+                .collect();
+            section(&mut body, "Implementors", &implementors, &theme);
+        }
+        AnalyzedItem::Impl(im) => {
+            let methods: Vec<Entry> = im
+                .methods
+                .iter()
+                .map(|m| Entry {
+                    code: m.signature.clone(),
+                    docs: m.documentation.as_deref(),
+                })
+                .collect();
+            section(&mut body, "Methods", &methods, &theme);
+        }
+        AnalyzedItem::Module(module) => {
+            let items: Vec<Entry> = module
+                .items
+                .iter()
+                .map(|name| Entry {
+                    code: name.clone(),
+                    docs: None,
+                })
+                .collect();
+            section(&mut body, "Items", &items, &theme);
+        }
+        AnalyzedItem::Function(_)
+        | AnalyzedItem::TypeAlias(_)
+        | AnalyzedItem::Const(_)
+        | AnalyzedItem::Static(_) => {}
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>\n{style}</style>\n</head>\n<body>\n<main>\n{body}\
+         <footer class=\"muted\">Generated by oracle</footer>\n</main>\n</body>\n</html>\n",
+        title = escape(&item.qualified_name()),
+        style = stylesheet(&theme),
+    )
+}
+
+/// A row of a member section: code (signature, field, variant) and its docs
+struct Entry<'a> {
+    code: String,
+    docs: Option<&'a str>,
+}
+
+fn section(body: &mut String, title: &str, entries: &[Entry], theme: &Theme) {
+    if entries.is_empty() {
+        return;
+    }
+    body.push_str(&format!("<h2>{}</h2>\n", escape(title)));
+    for entry in entries {
+        body.push_str("<div class=\"member\">\n");
+        body.push_str(&code_block(&entry.code, theme));
+        if let Some(docs) = entry.docs {
+            body.push_str(&docs_html(docs, theme));
+        }
+        body.push_str("</div>\n");
+    }
+}
+
+/// Methods (inherent and from trait impls) and the trait impls of a struct or enum
+fn type_members(body: &mut String, name: &str, all_items: &[AnalyzedItem], theme: &Theme) {
+    let methods: Vec<Entry> = method_table(name, all_items, MethodSort::Origin)
+        .into_iter()
+        .map(|m| Entry {
+            code: format!("fn {}  // {}", m.signature, m.origin.label()),
+            docs: None,
+        })
+        .collect();
+    section(body, "Methods", &methods, theme);
+    let traits: Vec<Entry> = impls(all_items)
+        .filter(|im| im.trait_name.is_some() && base_type_name(&im.self_ty) == Some(name))
+        .map(|im| Entry {
+            code: im.full_definition(),
+            docs: None,
+        })
+        .collect();
+    section(body, "Trait Implementations", &traits, theme);
+}
+
+fn trait_methods(methods: Vec<&TraitMethod>) -> Vec<Entry<'_>> {
+    methods
+        .into_iter()
+        .map(|m| Entry {
+            code: m.signature.clone(),
+            docs: m.documentation.as_deref(),
+        })
+        .collect()
+}
+
+fn impls(items: &[AnalyzedItem]) -> impl Iterator<Item = &ImplInfo> {
+    items.iter().filter_map(|item| match item {
+        AnalyzedItem::Impl(im) => Some(im),
+        _ => None,
+    })
+}
+
+fn variant_code(variant: &Variant) -> String {
+    let mut code = variant.name.clone();
+    match &variant.fields {
+        VariantFields::Named(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|f| format!("{}: {}", f.name, f.ty))
+                .collect();
+            code.push_str(&format!(" {{ {} }}", fields.join(", ")));
+        }
+        VariantFields::Unnamed(types) => code.push_str(&format!("({})", types.join(", "))),
+        VariantFields::Unit => {}
+    }
+    if let Some(ref value) = variant.discriminant {
+        code.push_str(&format!(" = {}", value));
+    }
+    code
+}
+
+/// `<pre>` with each line highlighted like the inspector
+fn code_block(code: &str, theme: &Theme) -> String {
+    let mut html = String::from("<pre><code>");
+    for (i, line) in code.lines().enumerate() {
+        if i > 0 {
+            html.push('\n');
+        }
+        for span in highlight_rust_line(line, theme) {
+            match span.style.fg {
+                Some(color) => html.push_str(&format!(
+                    "<span style=\"color:{}\">{}</span>",
+                    css_color(color),
+                    escape(&span.content)
+                )),
+                None => html.push_str(&escape(&span.content)),
+            }
+        }
+    }
+    html.push_str("</code></pre>\n");
+    html
+}
+
+/// Doc comment Markdown, minimally: paragraphs, `#` headings, bullet lists, fenced
+/// code blocks (highlighted when Rust) and inline code
+fn docs_html(docs: &str, theme: &Theme) -> String {
+    let mut html = String::from("<div class=\"docs\">\n");
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut fence: Option<(bool, Vec<&str>)> = None;
+    let flush = |paragraph: &mut Vec<&str>, html: &mut String| {
+        if !paragraph.is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", inline(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+    for line in docs.lines() {
+        let trimmed = line.trim();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            match fence.take() {
+                Some((rust, code)) => {
+                    // Hidden doctest lines (`# use ...`) are left out, as rustdoc does
+                    let code: Vec<&str> = code
+                        .into_iter()
+                        .filter(|l| {
+                            !(rust && (l.trim() == "#" || l.trim_start().starts_with("# ")))
+                        })
+                        .collect();
+                    if rust {
+                        html.push_str(&code_block(&code.join("\n"), theme));
+                    } else {
+                        html.push_str(&format!(
+                            "<pre><code>{}</code></pre>\n",
+                            escape(&code.join("\n"))
+                        ));
+                    }
+                }
+                None => {
+                    flush(&mut paragraph, &mut html);
+                    let rust = info.is_empty() || info.split(',').any(|t| t.trim() == "rust");
+                    fence = Some((rust, Vec::new()));
+                }
+            }
+            continue;
+        }
+        if let Some((_, ref mut code)) = fence {
+            code.push(line);
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut html);
+        } else if let Some(heading) = trimmed.strip_prefix('#') {
+            flush(&mut paragraph, &mut html);
+            let level = 3 + heading.chars().take_while(|&c| c == '#').count().min(3);
+            let text = heading.trim_start_matches('#').trim();
+            html.push_str(&format!("<h{level}>{}</h{level}>\n", inline(text)));
+        } else if let Some(bullet) = trimmed.strip_prefix("- ").or(trimmed.strip_prefix("* ")) {
+            flush(&mut paragraph, &mut html);
+            html.push_str(&format!("<ul><li>{}</li></ul>\n", inline(bullet)));
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    if let Some((_, code)) = fence {
+        html.push_str(&format!(
+            "<pre><code>{}</code></pre>\n",
+            escape(&code.join("\n"))
+        ));
+    }
+    flush(&mut paragraph, &mut html);
+    html.push_str("</div>\n");
+    html
+}
+
+/// Escape text and turn `code` spans into `<code>`
+fn inline(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(i, part)| match i % 2 {
+            1 => format!("<code>{}</code>", escape(part)),
+            _ => escape(part),
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// CSS value for a theme color; named colors get their usual xterm values
+fn css_color(color: Color) -> String {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 49, 49),
+        Color::Green => (13, 188, 121),
+        Color::Yellow => (229, 229, 16),
+        Color::Blue => (36, 114, 200),
+        Color::Magenta => (188, 63, 188),
+        Color::Cyan => (17, 168, 205),
+        Color::Gray => (204, 204, 204),
+        Color::DarkGray => (118, 118, 118),
+        Color::LightRed => (241, 76, 76),
+        Color::LightGreen => (35, 209, 139),
+        Color::LightYellow => (245, 245, 67),
+        Color::LightBlue => (59, 142, 234),
+        Color::LightMagenta => (214, 112, 214),
+        Color::LightCyan => (41, 184, 219),
+        Color::White => (229, 229, 229),
+        _ => return "inherit".to_string(),
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn stylesheet(theme: &Theme) -> String {
+    format!(
+        "body {{ margin: 0; background: {bg}; color: {fg}; \
+         font: 16px/1.5 system-ui, -apple-system, \"Segoe UI\", sans-serif; }}\n\
+         main {{ max-width: 860px; margin: 0 auto; padding: 2rem 1.5rem; }}\n\
+         h1 {{ color: {accent}; font-size: 1.6rem; margin: 0 0 .25rem; }}\n\
+         h2 {{ color: {accent}; font-size: 1.2rem; border-bottom: 1px solid {border}; \
+         padding-bottom: .25rem; margin-top: 2rem; }}\n\
+         h3, h4, h5, h6 {{ color: {fg}; font-size: 1rem; }}\n\
+         .kind {{ color: {keyword}; font-weight: normal; }}\n\
+         .muted, footer {{ color: {muted}; font-size: .875rem; }}\n\
+         footer {{ margin-top: 3rem; }}\n\
+         pre {{ background: {panel}; border: 1px solid {border}; border-radius: 6px; \
+         padding: .75rem 1rem; overflow-x: auto; }}\n\
+         code {{ font: .9rem/1.45 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }}\n\
+         p code, li code {{ background: {panel}; padding: .1rem .3rem; border-radius: 4px; }}\n\
+         .member {{ margin-left: 1rem; }}\n\
+         .member pre {{ margin-bottom: .25rem; }}\n\
+         .docs {{ color: {dim}; }}\n\
+         ul {{ margin: .25rem 0; }}\n",
+        bg = css_color(theme.bg),
+        fg = css_color(theme.fg),
+        dim = css_color(theme.fg_dim),
+        muted = css_color(theme.fg_muted),
+        accent = css_color(theme.accent),
+        keyword = css_color(theme.keyword),
+        panel = css_color(theme.bg_panel),
+        border = css_color(theme.border),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_item_page() {
+        let items = RustAnalyzer::new()
+            .analyze_source(
+                "/// A point on the `grid`\n///\n/// ```\n/// # use demo::Point;\n/// let p = Point::new(1, 2);\n/// ```\npub struct Point { pub x: i32, pub y: i32 }\n\
+                 impl Point { /// Makes one\n pub fn new(x: i32, y: i32) -> Self { Point { x, y } } }\n\
+                 impl Clone for Point { fn clone(&self) -> Self { *self } }",
+            )
+            .unwrap();
+        let page = item_page(&items[0], &items, &Theme::nord());
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>Point</title>"));
+        assert!(page.contains("A point on the <code>grid</code>"));
+        let text: String = page
+            .split('<')
+            .map(|part| part.split_once('>').map_or(part, |(_, text)| text))
+            .collect();
+        assert!(text.contains("let p = Point::new(1, 2);"));
+        assert!(!page.contains("use demo::Point"));
+        assert!(page.contains("<h2>Fields</h2>"));
+        assert!(page.contains("<h2>Methods</h2>"));
+        assert!(text.contains("impl Clone for Point"));
+        // Nord's background, and no external resources
+        assert!(page.contains("background: #2e3440"));
+        assert!(!page.contains("href=") && !page.contains("src="));
+    }
+
+    #[test]
+    fn test_escape_and_colors() {
+        assert_eq!(escape("Vec<&str>"), "Vec&lt;&amp;str&gt;");
+        assert_eq!(
+            inline("use `a<b>` here"),
+            "use <code>a&lt;b&gt;</code> here"
+        );
+        assert_eq!(css_color(Color::Rgb(78, 191, 113)), "#4ebf71");
+        assert_eq!(css_color(Color::Reset), "inherit");
+    }
+}
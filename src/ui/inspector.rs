@@ -1,30 +1,261 @@
 //! Inspector panel for displaying code item details
 
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::rc::Rc;
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
-        block::BorderType, Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation,
+        block::BorderType, Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation,
         ScrollbarState, StatefulWidget, Widget, Wrap,
     },
 };
 
+use crate::analyzer::const_eval;
+use crate::analyzer::extract_doc_examples;
+use crate::analyzer::item_diff::{self, DiffTag, ItemChange};
 use crate::analyzer::{
-    AnalyzedItem, ConstInfo, EnumInfo, FunctionInfo, ImplInfo, ModuleInfo, StaticInfo, StructInfo,
-    StructKind, TraitInfo, TypeAliasInfo, VariantFields, Visibility,
+    c_declaration, constructors, method_table, AnalyzedItem, AutoTraits, BorrowSource, ConstInfo,
+    ConstTable, CoverageData, DocLink, EnumInfo, EnumLayout, FunctionInfo, GenericParamKind,
+    GenericsInfo, ImplInfo, LifetimeSummary, LinkStatus, MethodOrigin, MethodSort, ModuleInfo,
+    ObjectSafety, ProcMacro, StaticInfo, StructInfo, StructKind, TraitInfo, TypeAliasInfo,
+    TypeUsageIndex, UsageKind, VariantFields, Verdict, Visibility,
 };
+use crate::config::{InspectorSettings, ReviewNotes};
+use crate::ui::code_layout::{scroll_line, wrap_rust_line};
+use crate::ui::highlight::highlight_rust_line;
 use crate::ui::theme::Theme;
+use crate::utils::git;
 
 /// Panel for inspecting code items with scrolling support
 pub struct InspectorPanel<'a> {
     item: Option<&'a AnalyzedItem>,
     /// All items (for "Implementations" of a trait)
     all_items: Option<&'a [AnalyzedItem]>,
+    /// Reverse type references (for "Used By" of structs/enums)
+    usage_index: Option<&'a TypeUsageIndex>,
     theme: &'a Theme,
     focused: bool,
     scroll_offset: usize,
+    /// Doc example targeted by copy/run keys
+    doc_example_selected: usize,
+    /// Shown as the pinned side of a pinned/selected split
+    pinned: bool,
+    /// Receives the largest useful scroll offset once the content is laid out
+    max_scroll_out: Option<&'a Cell<Option<usize>>>,
+    /// Signatures and definitions: soft-wrap (true) or scroll horizontally by `code_hscroll`
+    wrap_code: bool,
+    code_hscroll: usize,
+    /// Order of the method table of structs and enums
+    method_sort: MethodSort,
+    /// Line coverage from a loaded LCOV / llvm-cov report
+    coverage: Option<&'a CoverageData>,
+    /// Links in the project's docs; the item's broken ones get a badge and a section
+    doc_links: Option<&'a [DocLink]>,
+    sections: SectionLayout<'a>,
+    /// The item's source as written instead of the analyzed sections
+    raw: bool,
+    /// Diff of the item's source against this git revision instead of the sections
+    diff: Option<&'a str>,
+    /// Review notes of the project; the item's own gets a badge and a section
+    review: Option<&'a ReviewNotes>,
+    /// Receives the title of the section at the top of the view
+    top_section_out: Option<&'a RefCell<Option<String>>>,
+    /// Identifier marked in the source view, with what is known about it
+    source_cursor: Option<SourceCursorView<'a>>,
+    cache: Option<&'a InspectorCache>,
+}
+
+/// Lines above the first line of source in the source view (`v`): kind and name,
+/// file, blank
+const RAW_HEADER_LINES: usize = 3;
+
+/// Cursor of the source view: the identifier under it and the popup resolving it
+#[derive(Debug, Clone, Copy)]
+pub struct SourceCursorView<'a> {
+    /// Line counted from the first line of the item's source
+    pub line: usize,
+    /// Char columns (start, end) of the identifier on that line
+    pub columns: (usize, usize),
+    /// Type, definition and location of the identifier, one entry per line
+    pub hover: &'a [String],
+    /// Where `hover` comes from, for the popup title
+    pub source: &'a str,
+}
+
+/// Which sections the inspector shows, in what order, and which are collapsed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SectionLayout<'a> {
+    pub settings: Option<&'a InspectorSettings>,
+    /// Collapsed section titles by item kind (`fn`, `struct`, ...)
+    pub collapsed: Option<&'a BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl SectionLayout<'_> {
+    fn is_default(&self, kind: &str) -> bool {
+        !self
+            .settings
+            .is_some_and(|s| !s.order.is_empty() || !s.hidden.is_empty())
+            && self.collapsed_for(kind).map_or(true, BTreeSet::is_empty)
+    }
+
+    fn collapsed_for(&self, kind: &str) -> Option<&BTreeSet<String>> {
+        self.collapsed.and_then(|c| c.get(kind))
+    }
+
+    fn is_collapsed(&self, kind: &str, title: &str) -> bool {
+        self.collapsed_for(kind).is_some_and(|c| c.contains(title))
+    }
+
+    fn is_hidden(&self, title: &str) -> bool {
+        self.settings
+            .is_some_and(|s| s.hidden.iter().any(|h| h.eq_ignore_ascii_case(title)))
+    }
+
+    /// Position in the configured order; unlisted sections sort after all listed ones
+    fn rank(&self, title: &str) -> usize {
+        self.settings
+            .and_then(|s| s.order.iter().position(|o| o.eq_ignore_ascii_case(title)))
+            .unwrap_or(usize::MAX)
+    }
+
+    fn signature(&self, kind: &str) -> String {
+        match self.settings {
+            _ if self.is_default(kind) => String::new(),
+            Some(s) => format!(
+                "{:?}|{:?}|{:?}",
+                s.order,
+                s.hidden,
+                self.collapsed_for(kind)
+            ),
+            None => format!("{:?}", self.collapsed_for(kind)),
+        }
+    }
+}
+
+/// Trailing rule of a section header; marks header lines when sections are rearranged
+const SECTION_RULE: &str = " ─────────────────";
+
+/// Title of a section as configured and remembered: the header without its count,
+/// e.g. `Parameters` for "Parameters (3)"
+fn section_title(header: &str) -> &str {
+    match header.rfind(" (") {
+        Some(i) if header.ends_with(')') => &header[..i],
+        _ => header,
+    }
+}
+
+fn is_blank(line: &Line) -> bool {
+    line.spans.iter().all(|s| s.content.is_empty())
+}
+
+/// Panels kept by [`InspectorCache`]: the pinned split and compare mode show two at once
+const CACHED_PANELS: usize = 4;
+
+/// Lines built for recently shown items, reused while nothing they depend on changes,
+/// so scrolling or a redraw for unrelated state doesn't rebuild a big enum every frame.
+///
+/// Entries are keyed by the item's address, the theme, the panel width and the layout
+/// options. The owner must [`clear`](InspectorCache::clear) it whenever the analyzed
+/// items are replaced, since a new item may reuse an old one's address.
+#[derive(Debug, Default)]
+pub struct InspectorCache {
+    entries: RefCell<VecDeque<(CacheKey, Rc<CachedPanel>)>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CacheKey {
+    item: usize,
+    /// `all_items`, `usage_index`, `coverage` and `doc_links`, by address
+    sources: [usize; 4],
+    theme: String,
+    compat: bool,
+    width: u16,
+    doc_example_selected: usize,
+    wrap_code: bool,
+    code_hscroll: usize,
+    method_sort: MethodSort,
+    sections: String,
+    raw: bool,
+    diff: Option<String>,
+    review_note: Option<String>,
+}
+
+#[derive(Debug)]
+struct CachedPanel {
+    lines: Vec<Line<'static>>,
+    /// Line index and title of each section header, in display order
+    sections: Vec<(usize, String)>,
+    /// Wrapped row count, measured on first render (the width is part of the key)
+    rows: Cell<Option<usize>>,
+    /// First wrapped row of each section, measured when the top section is asked for
+    section_rows: OnceCell<Vec<usize>>,
+}
+
+impl CachedPanel {
+    fn new(lines: Vec<Line<'static>>, sections: Vec<(usize, String)>) -> Self {
+        Self {
+            lines,
+            sections,
+            rows: Cell::new(None),
+            section_rows: OnceCell::new(),
+        }
+    }
+
+    /// Section containing wrapped row `offset`, or the first one while the item
+    /// header is still in view
+    fn section_at(&self, offset: usize, width: u16) -> Option<&str> {
+        let starts = self.section_rows.get_or_init(|| {
+            let mut starts = Vec::with_capacity(self.sections.len());
+            let mut row = 0;
+            let mut next = self.sections.iter().map(|(i, _)| *i).peekable();
+            for (i, line) in self.lines.iter().enumerate() {
+                if next.peek().is_none() {
+                    break;
+                }
+                if next.next_if_eq(&i).is_some() {
+                    starts.push(row);
+                }
+                row += Paragraph::new(line.clone())
+                    .wrap(Wrap { trim: false })
+                    .line_count(width);
+            }
+            starts
+        });
+        let index = starts
+            .iter()
+            .rposition(|&start| start <= offset)
+            .unwrap_or(0);
+        self.sections.get(index).map(|(_, title)| title.as_str())
+    }
+}
+
+impl InspectorCache {
+    pub fn clear(&self) {
+        self.entries.borrow_mut().clear();
+    }
+
+    fn get_or_build(&self, key: CacheKey, build: impl FnOnce() -> CachedPanel) -> Rc<CachedPanel> {
+        let mut entries = self.entries.borrow_mut();
+        if let Some(i) = entries.iter().position(|(k, _)| *k == key) {
+            let entry = entries.remove(i).expect("index from position");
+            let panel = Rc::clone(&entry.1);
+            entries.push_front(entry);
+            return panel;
+        }
+        let panel = Rc::new(build());
+        entries.push_front((key, Rc::clone(&panel)));
+        entries.truncate(CACHED_PANELS);
+        panel
+    }
+}
+
+fn address<T: ?Sized>(value: Option<&T>) -> usize {
+    value.map_or(0, |v| v as *const T as *const () as usize)
 }
 
 impl<'a> InspectorPanel<'a> {
@@ -32,9 +263,25 @@ impl<'a> InspectorPanel<'a> {
         Self {
             item: None,
             all_items: None,
+            usage_index: None,
             theme,
             focused: false,
             scroll_offset: 0,
+            doc_example_selected: 0,
+            pinned: false,
+            max_scroll_out: None,
+            wrap_code: true,
+            code_hscroll: 0,
+            method_sort: MethodSort::default(),
+            coverage: None,
+            doc_links: None,
+            sections: SectionLayout::default(),
+            raw: false,
+            diff: None,
+            review: None,
+            top_section_out: None,
+            source_cursor: None,
+            cache: None,
         }
     }
 
@@ -48,6 +295,11 @@ impl<'a> InspectorPanel<'a> {
         self
     }
 
+    pub fn usage_index(mut self, index: Option<&'a TypeUsageIndex>) -> Self {
+        self.usage_index = index;
+        self
+    }
+
     pub fn focused(mut self, focused: bool) -> Self {
         self.focused = focused;
         self
@@ -58,17 +310,188 @@ impl<'a> InspectorPanel<'a> {
         self
     }
 
+    pub fn doc_example_selected(mut self, index: usize) -> Self {
+        self.doc_example_selected = index;
+        self
+    }
+
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// Soft-wrap long signatures, or (`wrap = false`) show them unwrapped from column `hscroll`
+    pub fn code_layout(mut self, wrap: bool, hscroll: usize) -> Self {
+        self.wrap_code = wrap;
+        self.code_hscroll = hscroll;
+        self
+    }
+
+    pub fn method_sort(mut self, sort: MethodSort) -> Self {
+        self.method_sort = sort;
+        self
+    }
+
+    pub fn coverage(mut self, coverage: Option<&'a CoverageData>) -> Self {
+        self.coverage = coverage;
+        self
+    }
+
+    pub fn doc_links(mut self, links: Option<&'a [DocLink]>) -> Self {
+        self.doc_links = links;
+        self
+    }
+
+    /// Reuse lines built by an earlier frame when the item and layout are unchanged
+    /// Section order, hidden sections and collapsed sections
+    pub fn sections(mut self, layout: SectionLayout<'a>) -> Self {
+        self.sections = layout;
+        self
+    }
+
+    /// Show the source as written in the file (`v`)
+    pub fn raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// Mark an identifier of the source view and show what it resolves to
+    pub fn source_cursor(mut self, cursor: Option<SourceCursorView<'a>>) -> Self {
+        self.source_cursor = cursor;
+        self
+    }
+
+    /// Show what changed in the item since git revision `rev` (`d`)
+    pub fn diff(mut self, rev: Option<&'a str>) -> Self {
+        self.diff = rev;
+        self
+    }
+
+    pub fn review(mut self, notes: Option<&'a ReviewNotes>) -> Self {
+        self.review = notes;
+        self
+    }
+
+    /// Report the title of the section at the top of the view into `cell` when drawn
+    pub fn report_top_section(mut self, cell: Option<&'a RefCell<Option<String>>>) -> Self {
+        self.top_section_out = cell;
+        self
+    }
+
+    pub fn cache(mut self, cache: Option<&'a InspectorCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Report the last scrollable row (wrapped rows minus the viewport) into `cell`,
+    /// keeping the larger value when several panels share one scroll offset
+    pub fn report_max_scroll(mut self, cell: Option<&'a Cell<Option<usize>>>) -> Self {
+        self.max_scroll_out = cell;
+        self
+    }
+
+    /// Signature / definition lines, laid out to fit a panel `area_width` columns wide
+    fn push_code(&self, code: &str, area_width: u16, lines: &mut Vec<Line<'static>>) {
+        // Borders, the two-space indent and the scrollbar column
+        let width = (area_width as usize).saturating_sub(5);
+        for code_line in code.lines() {
+            let laid_out = if self.wrap_code {
+                wrap_rust_line(code_line, width)
+            } else {
+                vec![scroll_line(code_line, self.code_hscroll, width)]
+            };
+            for line in laid_out {
+                lines.push(Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(line, self.theme.style_function()),
+                ]));
+            }
+        }
+    }
+
     fn section_header(&self, title: &str) -> Line<'static> {
         Line::from(vec![
-            Span::styled("▸ ", self.theme.style_accent()),
+            Span::styled(self.theme.glyph("▸ ", "> "), self.theme.style_accent()),
             Span::styled(
                 title.to_string(),
                 self.theme.style_accent().add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" ─────────────────", self.theme.style_muted()),
+            Span::styled(SECTION_RULE, self.theme.style_muted()),
+        ])
+    }
+
+    /// Header of a collapsed section, with how many lines it hides
+    fn collapsed_header(&self, title: Span<'static>, hidden: usize) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(self.theme.glyph("▹ ", "+ "), self.theme.style_muted()),
+            title,
+            Span::styled(
+                match hidden {
+                    1 => " ··· 1 line hidden".to_string(),
+                    n => format!(" ··· {} lines hidden", n),
+                },
+                self.theme.style_muted(),
+            ),
         ])
     }
 
+    /// Apply the section layout to the lines built for an item of `kind`: hidden
+    /// sections are dropped, listed ones moved to the front, collapsed ones reduced
+    /// to their header. Each section is its header and the lines up to the blank
+    /// line before the next header.
+    fn arrange(&self, kind: &str, lines: Vec<Line<'static>>) -> CachedPanel {
+        let title_of = |line: &Line| match line.spans.as_slice() {
+            [_, title, rule, ..] if rule.content == SECTION_RULE => {
+                Some(section_title(&title.content).to_string())
+            }
+            _ => None,
+        };
+        let mut preamble = Vec::new();
+        let mut sections: Vec<(String, Vec<Line<'static>>)> = Vec::new();
+        for line in lines {
+            match title_of(&line) {
+                Some(title) => {
+                    let previous = sections.last_mut().map_or(&mut preamble, |(_, l)| l);
+                    if previous.last().is_some_and(is_blank) {
+                        previous.pop();
+                    }
+                    sections.push((title, vec![line]));
+                }
+                None => match sections.last_mut() {
+                    Some((_, section)) => section.push(line),
+                    None => preamble.push(line),
+                },
+            }
+        }
+        if self.sections.is_default(kind) {
+            let mut lines = preamble;
+            let mut headers = Vec::new();
+            for (title, section) in sections {
+                lines.push(Line::from(""));
+                headers.push((lines.len(), title));
+                lines.extend(section);
+            }
+            return CachedPanel::new(lines, headers);
+        }
+
+        sections.retain(|(title, _)| !self.sections.is_hidden(title));
+        sections.sort_by_key(|(title, _)| self.sections.rank(title));
+        let mut lines = preamble;
+        let mut headers = Vec::new();
+        for (title, mut section) in sections {
+            lines.push(Line::from(""));
+            headers.push((lines.len(), title.clone()));
+            if self.sections.is_collapsed(kind, &title) {
+                let hidden = section.iter().skip(1).filter(|l| !is_blank(l)).count();
+                let name = section.swap_remove(0).spans.swap_remove(1);
+                lines.push(self.collapsed_header(name, hidden));
+            } else {
+                lines.extend(section);
+            }
+        }
+        CachedPanel::new(lines, headers)
+    }
+
     fn key_value(&self, key: &str, value: String) -> Line<'static> {
         Line::from(vec![
             Span::styled(format!("  {} ", key), self.theme.style_dim()),
@@ -85,13 +508,156 @@ impl<'a> InspectorPanel<'a> {
         Span::styled(format!(" [{}] ", text), style)
     }
 
+    /// Generics section: one row per lifetime/type/const parameter with its bounds,
+    /// `where` bounds shown under the parameter they constrain.
+    fn push_generics(&self, generics: &GenericsInfo, lines: &mut Vec<Line<'static>>) {
+        if generics.is_empty() {
+            return;
+        }
+        lines.push(Line::from(""));
+        lines.push(self.section_header(&format!("Generics ({})", generics.params.len())));
+        lines.push(Line::from(""));
+
+        let groups = [
+            ("lifetime", generics.lifetimes().collect::<Vec<_>>()),
+            ("type", generics.types().collect()),
+            ("const", generics.consts().collect()),
+        ];
+        for (label, params) in groups {
+            for param in params {
+                let name_style = match param.kind {
+                    GenericParamKind::Lifetime => self.theme.style_keyword(),
+                    GenericParamKind::Type => self.theme.style_type(),
+                    GenericParamKind::Const(_) => self.theme.style_number(),
+                };
+                let mut spans = vec![
+                    Span::styled(format!("  {:<9}", label), self.theme.style_dim()),
+                    Span::styled(param.name.clone(), name_style),
+                ];
+                if let GenericParamKind::Const(ref ty) = param.kind {
+                    spans.push(Span::styled(": ", self.theme.style_muted()));
+                    spans.push(Span::styled(ty.clone(), self.theme.style_type()));
+                } else if !param.bounds.is_empty() {
+                    spans.push(Span::styled(": ", self.theme.style_muted()));
+                    spans.push(Span::styled(
+                        param.bounds.join(" + "),
+                        self.theme.style_type(),
+                    ));
+                }
+                if let Some(ref default) = param.default {
+                    spans.push(Span::styled(" = ", self.theme.style_muted()));
+                    spans.push(Span::styled(default.clone(), self.theme.style_normal()));
+                }
+                lines.push(Line::from(spans));
+                if !param.where_bounds.is_empty() {
+                    lines.push(Line::from(vec![
+                        Span::raw("           "),
+                        Span::styled("↳ where ", self.theme.style_keyword()),
+                        Span::styled(param.where_bounds.join(" + "), self.theme.style_type()),
+                    ]));
+                }
+            }
+        }
+        for predicate in &generics.other_predicates {
+            lines.push(Line::from(vec![
+                Span::styled("  where    ", self.theme.style_keyword()),
+                Span::styled(predicate.clone(), self.theme.style_type()),
+            ]));
+        }
+    }
+
+    /// Doc Examples section: fenced code blocks from the docs, highlighted when Rust
+    fn push_doc_examples(&self, docs: Option<&str>, lines: &mut Vec<Line<'static>>) {
+        let examples = docs.map(extract_doc_examples).unwrap_or_default();
+        if examples.is_empty() {
+            return;
+        }
+        let selected = self.doc_example_selected % examples.len();
+        lines.push(Line::from(""));
+        lines.push(self.section_header(&format!("Doc Examples ({})", examples.len())));
+        lines.push(Line::from(""));
+        for (i, example) in examples.iter().enumerate() {
+            let marker = if i == selected {
+                self.theme.glyph("▶ ", "> ")
+            } else {
+                "  "
+            };
+            let mode = if !example.is_rust() {
+                "not Rust"
+            } else if example.is_run() {
+                "runs"
+            } else {
+                "compile only"
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}", marker), self.theme.style_accent()),
+                Span::styled(format!("{}. ", i + 1), self.theme.style_number()),
+                Span::styled(example.label(), self.theme.style_keyword()),
+                Span::styled(format!("  ({})", mode), self.theme.style_muted()),
+            ]));
+            for code_line in example.visible_lines() {
+                let mut spans = vec![Span::styled("     │ ", self.theme.style_muted())];
+                if example.is_rust() {
+                    spans.extend(highlight_rust_line(code_line, self.theme));
+                } else {
+                    spans.push(Span::styled(
+                        code_line.to_string(),
+                        self.theme.style_normal(),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        lines.push(Line::from(vec![
+            Span::styled("  [e]", self.theme.style_accent()),
+            Span::styled(" next  ", self.theme.style_muted()),
+            Span::styled("[y]", self.theme.style_accent()),
+            Span::styled(" copy  ", self.theme.style_muted()),
+            Span::styled("[x]", self.theme.style_accent()),
+            Span::styled(" cargo test --doc", self.theme.style_muted()),
+        ]));
+    }
+
+    /// "Used By" section: functions, fields and impls referring to the type `name`
+    fn push_usages(&self, name: &str, lines: &mut Vec<Line<'static>>) {
+        let Some(index) = self.usage_index else {
+            return;
+        };
+        let usages = index.usages_of(name);
+        lines.push(Line::from(""));
+        lines.push(self.section_header(&format!("Used By ({})", usages.len())));
+        lines.push(Line::from(""));
+        if usages.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No references found in analyzed items",
+                self.theme.style_muted(),
+            )));
+            return;
+        }
+        for usage in usages {
+            let kind_style = match usage.kind {
+                UsageKind::Impl | UsageKind::Derive => self.theme.style_keyword(),
+                UsageKind::Field => self.theme.style_type(),
+                UsageKind::Parameter | UsageKind::Return => self.theme.style_function(),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<8}", usage.kind.to_string()), kind_style),
+                Span::styled(usage.owner.clone(), self.theme.style_accent()),
+            ]));
+            lines.push(Line::from(vec![
+                Span::raw("           "),
+                Span::styled(usage.detail.clone(), self.theme.style_muted()),
+            ]));
+        }
+    }
+
     fn render_empty(&self, area: Rect, buf: &mut Buffer) {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(self.theme.style_border())
             .style(Style::default().bg(self.theme.bg_panel))
-            .title(" ◇ Inspector ");
+            .title(format!(" {}Inspector ", self.theme.glyph("◇ ", "")));
 
         let inner = block.inner(area);
         block.render(area, buf);
@@ -132,7 +698,7 @@ impl<'a> InspectorPanel<'a> {
             .render(inner, buf);
     }
 
-    fn render_function(&self, func: &FunctionInfo, area: Rect, buf: &mut Buffer) {
+    fn function_lines(&self, func: &FunctionInfo, width: u16) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
 
         // Header with name and badges
@@ -155,15 +721,28 @@ impl<'a> InspectorPanel<'a> {
         if func.is_unsafe {
             header.push(self.badge("unsafe", true));
         }
+        let lifetimes = LifetimeSummary::of(func);
+        if lifetimes.returns_borrow() {
+            header.push(self.badge("returns &", false));
+        }
+        let proc_macro = ProcMacro::of(func);
+        if proc_macro.is_some() {
+            header.push(self.badge("proc macro", false));
+        }
+        let panics = func.panics.total();
+        if panics > 0 {
+            header.push(self.badge(&format!("panics {}", panics), true));
+        }
 
         lines.push(Line::from(header));
 
-        // Show qualified path if present
+        // Show qualified path if present; a proc macro is imported by its macro name
         if !func.module_path.is_empty() {
+            let name = proc_macro.as_ref().map_or(&func.name, |m| &m.name);
             lines.push(Line::from(vec![
                 Span::styled("  use ", self.theme.style_keyword()),
                 Span::styled(
-                    format!("{}::{}", func.module_path.join("::"), func.name),
+                    format!("{}::{}", func.module_path.join("::"), name),
                     self.theme.style_type(),
                 ),
                 Span::styled(";", self.theme.style_normal()),
@@ -174,11 +753,19 @@ impl<'a> InspectorPanel<'a> {
         // Full signature with syntax highlighting
         lines.push(self.section_header("Signature"));
         lines.push(Line::from(""));
-        for sig_line in func.signature.lines() {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(sig_line.to_string(), self.theme.style_function()),
-            ]));
+        self.push_code(&func.signature, width, &mut lines);
+
+        if let Some(ref m) = proc_macro {
+            lines.push(Line::from(""));
+            lines.push(self.section_header("Procedural Macro"));
+            lines.push(Line::from(""));
+            lines.push(self.key_value("Kind:", m.kind.label().to_string()));
+            lines.push(self.key_value("Usage:", m.usage()));
+            if !m.helpers.is_empty() {
+                let helpers: Vec<String> =
+                    m.helpers.iter().map(|h| format!("#[{}(..)]", h)).collect();
+                lines.push(self.key_value("Helper attributes:", helpers.join(", ")));
+            }
         }
 
         // Source Location
@@ -187,7 +774,7 @@ impl<'a> InspectorPanel<'a> {
             lines.push(self.section_header("Source"));
             lines.push(Line::from(""));
             lines.push(Line::from(vec![
-                Span::raw("  📍 "),
+                Span::raw(self.theme.glyph("  📍 ", "  ")),
                 Span::styled(func.source_location.to_string(), self.theme.style_muted()),
             ]));
         }
@@ -197,6 +784,19 @@ impl<'a> InspectorPanel<'a> {
         lines.push(self.section_header("Overview"));
         lines.push(Line::from(""));
         lines.push(self.key_value("Visibility:", func.visibility.to_string()));
+        if let Some(data) = self.coverage {
+            let value = match data.for_function(func) {
+                Some(c) => Span::styled(
+                    format!("{:.0}% ({}/{} lines)", c.percent(), c.covered, c.total),
+                    self.theme.style_coverage(c.percent()),
+                ),
+                None => Span::styled("no data", self.theme.style_muted()),
+            };
+            lines.push(Line::from(vec![
+                Span::styled("  Coverage: ", self.theme.style_dim()),
+                value,
+            ]));
+        }
 
         // Function properties
         let mut props = Vec::new();
@@ -212,11 +812,12 @@ impl<'a> InspectorPanel<'a> {
         if !props.is_empty() {
             lines.push(self.key_value("Modifiers:", props.join(", ")));
         }
-
-        if !func.generics.is_empty() {
-            lines.push(self.key_value("Generics:", format!("<{}>", func.generics.join(", "))));
+        if panics > 0 {
+            lines.push(self.key_value("Panic points:", func.panics.summary()));
         }
 
+        self.push_generics(&func.generics_info, &mut lines);
+
         // Parameters section with detailed analysis
         if !func.parameters.is_empty() {
             lines.push(Line::from(""));
@@ -256,7 +857,7 @@ impl<'a> InspectorPanel<'a> {
                         Span::styled(format!("  {}. ", i + 1), self.theme.style_number()),
                         Span::styled(param.name.clone(), self.theme.style_accent()),
                         Span::styled(": ", self.theme.style_muted()),
-                        Span::styled(param.ty.clone(), self.theme.style_type()),
+                        Span::styled(param.ty.to_string(), self.theme.style_type()),
                     ]));
 
                     // Ownership/borrowing analysis
@@ -309,14 +910,20 @@ impl<'a> InspectorPanel<'a> {
                 lines.push(Line::from(vec![
                     Span::raw("       "),
                     Span::styled(
-                        "⚠ Can fail - handle errors appropriately",
+                        format!(
+                            "{}Can fail - handle errors appropriately",
+                            self.theme.glyph("⚠ ", "! ")
+                        ),
                         self.theme.style_warning(),
                     ),
                 ]));
             } else if ret_lower.contains("option") {
                 lines.push(Line::from(vec![
                     Span::raw("       "),
-                    Span::styled("⚠ May return None", self.theme.style_warning()),
+                    Span::styled(
+                        self.theme.glyph("⚠ May return None", "! May return None"),
+                        self.theme.style_warning(),
+                    ),
                 ]));
             }
         } else {
@@ -328,17 +935,7 @@ impl<'a> InspectorPanel<'a> {
             ]));
         }
 
-        // Where clause
-        if let Some(ref where_clause) = func.where_clause {
-            lines.push(Line::from(""));
-            lines.push(self.section_header("Constraints"));
-            lines.push(Line::from(""));
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled("where ", self.theme.style_keyword()),
-                Span::styled(where_clause.clone(), self.theme.style_type()),
-            ]));
-        }
+        self.push_lifetimes(&lifetimes, &mut lines);
 
         // Documentation
         if let Some(ref docs) = func.documentation {
@@ -367,10 +964,12 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        self.render_panel(" 🔧 Function ", lines, area, buf);
+        self.push_doc_examples(func.documentation.as_deref(), &mut lines);
+
+        lines
     }
 
-    fn render_struct(&self, st: &StructInfo, area: Rect, buf: &mut Buffer) {
+    fn struct_lines(&self, st: &StructInfo, width: u16) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
 
         // Header with type badge
@@ -408,12 +1007,7 @@ impl<'a> InspectorPanel<'a> {
         // Full Definition
         lines.push(self.section_header("Definition"));
         lines.push(Line::from(""));
-        for line in st.full_definition().lines() {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(line.to_string(), self.theme.style_function()),
-            ]));
-        }
+        self.push_code(&st.full_definition(), width, &mut lines);
 
         // Source Location
         if st.source_location.file.is_some() {
@@ -421,7 +1015,7 @@ impl<'a> InspectorPanel<'a> {
             lines.push(self.section_header("Source"));
             lines.push(Line::from(""));
             lines.push(Line::from(vec![
-                Span::raw("  📍 "),
+                Span::raw(self.theme.glyph("  📍 ", "  ")),
                 Span::styled(st.source_location.to_string(), self.theme.style_muted()),
             ]));
         }
@@ -434,13 +1028,7 @@ impl<'a> InspectorPanel<'a> {
         lines.push(self.key_value("Kind:", kind_str.to_string()));
         lines.push(self.key_value("Field Count:", st.fields.len().to_string()));
 
-        if !st.generics.is_empty() {
-            lines.push(self.key_value("Generics:", format!("<{}>", st.generics.join(", "))));
-        }
-
-        if let Some(ref wc) = st.where_clause {
-            lines.push(self.key_value("Where:", wc.clone()));
-        }
+        self.push_generics(&st.generics_info, &mut lines);
 
         // Derives with categorization
         if !st.derives.is_empty() {
@@ -542,7 +1130,7 @@ impl<'a> InspectorPanel<'a> {
                     Span::styled(vis_str.to_string(), self.theme.style_keyword()),
                     Span::styled(field.name.clone(), self.theme.style_accent()),
                     Span::styled(": ", self.theme.style_muted()),
-                    Span::styled(field.ty.clone(), self.theme.style_type()),
+                    Span::styled(field.ty.to_string(), self.theme.style_type()),
                 ]));
 
                 // Type analysis hints
@@ -550,7 +1138,10 @@ impl<'a> InspectorPanel<'a> {
                 if ty_lower.contains("option") {
                     lines.push(Line::from(vec![
                         Span::raw("       "),
-                        Span::styled("⚪ Optional field", self.theme.style_muted()),
+                        Span::styled(
+                            self.theme.glyph("⚪ Optional field", "Optional field"),
+                            self.theme.style_muted(),
+                        ),
                     ]));
                 } else if ty_lower.contains("vec")
                     || ty_lower.contains("hashmap")
@@ -558,7 +1149,10 @@ impl<'a> InspectorPanel<'a> {
                 {
                     lines.push(Line::from(vec![
                         Span::raw("       "),
-                        Span::styled("📦 Collection type", self.theme.style_muted()),
+                        Span::styled(
+                            self.theme.glyph("📦 Collection type", "Collection type"),
+                            self.theme.style_muted(),
+                        ),
                     ]));
                 } else if ty_lower.contains("box")
                     || ty_lower.contains("rc")
@@ -566,7 +1160,11 @@ impl<'a> InspectorPanel<'a> {
                 {
                     lines.push(Line::from(vec![
                         Span::raw("       "),
-                        Span::styled("🔗 Heap-allocated/Shared", self.theme.style_muted()),
+                        Span::styled(
+                            self.theme
+                                .glyph("🔗 Heap-allocated/Shared", "Heap-allocated/Shared"),
+                            self.theme.style_muted(),
+                        ),
                     ]));
                 }
 
@@ -584,57 +1182,26 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        // Type usage hints
+        let auto_traits = AutoTraits::of_struct(st, self.all_items.unwrap_or_default());
+        self.push_auto_traits(&auto_traits, &mut lines);
+
+        // Construction snippets: constructors from impls, then the struct literal
         lines.push(Line::from(""));
         lines.push(self.section_header("Usage"));
-        lines.push(Line::from(""));
-
-        // Construction hint
-        match st.kind {
-            StructKind::Named => {
-                lines.push(Line::from(vec![
-                    Span::raw("  "),
-                    Span::styled("let instance = ", self.theme.style_dim()),
-                    Span::styled(
-                        format!("{} {{ ... }};", st.name),
-                        self.theme.style_function(),
-                    ),
-                ]));
-            }
-            StructKind::Tuple => {
-                let placeholders = (0..st.fields.len())
-                    .map(|_| "_")
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                lines.push(Line::from(vec![
-                    Span::raw("  "),
-                    Span::styled("let instance = ", self.theme.style_dim()),
-                    Span::styled(
-                        format!("{}({});", st.name, placeholders),
-                        self.theme.style_function(),
-                    ),
-                ]));
-            }
-            StructKind::Unit => {
-                lines.push(Line::from(vec![
-                    Span::raw("  "),
-                    Span::styled("let instance = ", self.theme.style_dim()),
-                    Span::styled(format!("{};", st.name), self.theme.style_function()),
-                ]));
+        for ctor in constructors(st, self.all_items.unwrap_or_default()) {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("  // {}", ctor.label),
+                self.theme.style_comment(),
+            )));
+            for code_line in ctor.snippet.lines() {
+                let mut spans = vec![Span::raw("  ")];
+                spans.extend(highlight_rust_line(code_line, self.theme));
+                lines.push(Line::from(spans));
             }
         }
 
-        // Show Default hint if derived
-        if st.derives.iter().any(|d| d.to_lowercase() == "default") {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled("let instance = ", self.theme.style_dim()),
-                Span::styled(
-                    format!("{}::default();", st.name),
-                    self.theme.style_function(),
-                ),
-            ]));
-        }
+        self.push_method_table(&st.name, &mut lines);
 
         // Documentation
         if let Some(ref docs) = st.documentation {
@@ -663,10 +1230,14 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        self.render_panel(" 📦 Struct ", lines, area, buf);
+        self.push_usages(&st.name, &mut lines);
+
+        self.push_doc_examples(st.documentation.as_deref(), &mut lines);
+
+        lines
     }
 
-    fn render_enum(&self, en: &EnumInfo, area: Rect, buf: &mut Buffer) {
+    fn enum_lines(&self, en: &EnumInfo) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
 
         lines.push(Line::from(vec![
@@ -696,12 +1267,21 @@ impl<'a> InspectorPanel<'a> {
         lines.push(self.section_header("Overview"));
         lines.push(Line::from(""));
         lines.push(self.key_value("Visibility:", en.visibility.to_string()));
-        lines.push(self.key_value("Variants:", en.variants.len().to_string()));
-
-        if !en.generics.is_empty() {
-            lines.push(self.key_value("Generics:", format!("<{}>", en.generics.join(", "))));
+        let consts = ConstTable::new(self.all_items.unwrap_or_default());
+        let layout = EnumLayout::with_consts(en, &consts);
+        lines.push(self.key_value("Variants:", layout.variant_count.to_string()));
+        let kind = if layout.fieldless {
+            "fieldless (C-like)"
+        } else {
+            "data-carrying"
+        };
+        lines.push(self.key_value("Kind:", kind.to_string()));
+        if let Some(repr) = layout.repr.summary() {
+            lines.push(self.key_value("Repr:", repr));
         }
 
+        self.push_generics(&en.generics_info, &mut lines);
+
         // Derives
         if !en.derives.is_empty() {
             lines.push(Line::from(""));
@@ -720,7 +1300,7 @@ impl<'a> InspectorPanel<'a> {
         lines.push(self.section_header(&format!("Variants ({})", en.variants.len())));
         lines.push(Line::from(""));
 
-        for (i, variant) in en.variants.iter().enumerate() {
+        for (i, (variant, disc)) in en.variants.iter().zip(&layout.discriminants).enumerate() {
             let fields_str = match &variant.fields {
                 VariantFields::Named(fields) => {
                     let f: Vec<_> = fields
@@ -733,18 +1313,35 @@ impl<'a> InspectorPanel<'a> {
                 VariantFields::Unit => String::new(),
             };
 
-            let discriminant = variant
-                .discriminant
-                .as_ref()
-                .map(|d| format!(" = {}", d))
-                .unwrap_or_default();
-
-            lines.push(Line::from(vec![
+            let mut row = vec![
                 Span::styled(format!("  {}. ", i + 1), self.theme.style_dim()),
                 Span::styled(variant.name.clone(), self.theme.style_type()),
                 Span::styled(fields_str, self.theme.style_muted()),
-                Span::styled(discriminant, self.theme.style_number()),
-            ]));
+            ];
+            // Written discriminants as in the source (plus their value when that
+            // differs), implicit ones of C-like enums dimmed
+            match (&variant.discriminant, disc.value) {
+                (Some(expr), value) => {
+                    row.push(Span::styled(
+                        format!(" = {}", expr),
+                        self.theme.style_number(),
+                    ));
+                    if let Some(value) = value.filter(|v| v.to_string() != *expr) {
+                        row.push(Span::styled(
+                            format!("  ({})", value),
+                            self.theme.style_dim(),
+                        ));
+                    }
+                }
+                (None, Some(value)) if layout.fieldless => {
+                    row.push(Span::styled(
+                        format!(" = {}", value),
+                        self.theme.style_dim(),
+                    ));
+                }
+                _ => {}
+            }
+            lines.push(Line::from(row));
 
             if let Some(ref doc) = variant.documentation {
                 let first_line = doc.lines().next().unwrap_or("");
@@ -755,6 +1352,13 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
+        self.push_enum_layout(&layout, &mut lines);
+
+        let auto_traits = AutoTraits::of_enum(en, self.all_items.unwrap_or_default());
+        self.push_auto_traits(&auto_traits, &mut lines);
+
+        self.push_method_table(&en.name, &mut lines);
+
         // Documentation
         if let Some(ref docs) = en.documentation {
             lines.push(Line::from(""));
@@ -769,10 +1373,204 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        self.render_panel(" 🏷️ Enum ", lines, area, buf);
+        self.push_usages(&en.name, &mut lines);
+
+        self.push_doc_examples(en.documentation.as_deref(), &mut lines);
+
+        lines
     }
 
-    fn render_trait(&self, tr: &TraitInfo, area: Rect, buf: &mut Buffer) {
+    /// Lifetimes section: declared and elided lifetimes and what the return value
+    /// borrows from; skipped for signatures without any
+    fn push_lifetimes(&self, summary: &LifetimeSummary, lines: &mut Vec<Line<'static>>) {
+        if summary.declared.is_empty() && summary.params.is_empty() && summary.output.is_empty() {
+            return;
+        }
+        lines.push(Line::from(""));
+        lines.push(self.section_header("Lifetimes"));
+        lines.push(Line::from(""));
+
+        if !summary.declared.is_empty() {
+            lines.push(self.key_value("Declared:", summary.declared.join(", ")));
+        }
+        let inputs: Vec<String> = summary
+            .params
+            .iter()
+            .map(|(name, uses)| {
+                let mut parts = uses.named.clone();
+                if uses.elided > 0 {
+                    parts.push(format!("{} elided", uses.elided));
+                }
+                format!("{}: {}", name, parts.join(", "))
+            })
+            .collect();
+        if !inputs.is_empty() {
+            lines.push(self.key_value("Inputs:", inputs.join(" · ")));
+        }
+        let elided: Vec<String> = summary
+            .elided_positions()
+            .into_iter()
+            .map(|(name, n)| format!("{} ({})", name, n))
+            .collect();
+        if !elided.is_empty() {
+            lines.push(self.key_value("Elided:", elided.join(", ")));
+        }
+
+        let (note, style) = match summary.borrows_from {
+            BorrowSource::Owned => (
+                "Return value owns its data".to_string(),
+                self.theme.style_muted(),
+            ),
+            BorrowSource::Static => (
+                "Returns 'static data only".to_string(),
+                self.theme.style_muted(),
+            ),
+            BorrowSource::SelfRef => (
+                "↳ returned reference borrows from self".to_string(),
+                self.theme.style_accent(),
+            ),
+            BorrowSource::Params(ref names) => (
+                format!("↳ returned reference borrows from {}", names.join(", ")),
+                self.theme.style_accent(),
+            ),
+            BorrowSource::Ambiguous => (
+                format!(
+                    "{}Elided return lifetime with several borrowed inputs needs an annotation",
+                    self.theme.glyph("⚠ ", "! ")
+                ),
+                self.theme.style_warning(),
+            ),
+            BorrowSource::Unknown => (
+                "↳ return lifetime is not tied to any parameter".to_string(),
+                self.theme.style_muted(),
+            ),
+        };
+        lines.push(Line::from(vec![Span::raw("  "), Span::styled(note, style)]));
+    }
+
+    /// Layout section: what the `#[repr]` means, the tag type and spare niches of a
+    /// C-like enum, and discriminant problems
+    fn push_enum_layout(&self, layout: &EnumLayout, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::from(""));
+        lines.push(self.section_header("Layout"));
+        lines.push(Line::from(""));
+        for note in layout.repr.explain(layout.fieldless) {
+            lines.push(Line::from(vec![
+                Span::raw("  • "),
+                Span::styled(note, self.theme.style_normal()),
+            ]));
+        }
+        if let Some(tag) = layout.tag_type() {
+            lines.push(self.key_value("Tag type:", tag));
+        }
+        if let Some((lo, hi)) = layout.value_range().filter(|_| layout.fieldless) {
+            lines.push(self.key_value("Range:", format!("{} ..= {}", lo, hi)));
+        }
+        if let Some(niches) = layout.niche_count() {
+            let note = if niches > 0 {
+                format!(
+                    "{} unused values; Option<Self> costs no extra space",
+                    niches
+                )
+            } else {
+                "none; Option<Self> needs a separate tag".to_string()
+            };
+            lines.push(self.key_value("Niches:", note));
+        }
+        for warning in &layout.warnings {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(self.theme.glyph("⚠ ", "! "), self.theme.style_error()),
+                Span::styled(warning.clone(), self.theme.style_warning()),
+            ]));
+        }
+    }
+
+    /// Thread Safety section: likely `Send` / `Sync` / `Unpin` from the field types,
+    /// and the fields with interior mutability
+    fn push_auto_traits(&self, traits: &AutoTraits, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::from(""));
+        lines.push(self.section_header("Thread Safety"));
+        lines.push(Line::from(""));
+        for (name, verdict) in [
+            ("Send", &traits.send),
+            ("Sync", &traits.sync),
+            ("Unpin", &traits.unpin),
+        ] {
+            let (answer, style, reason) = match verdict {
+                Verdict::Yes => ("likely", self.theme.style_success(), None),
+                Verdict::No(reason) => ("no", self.theme.style_error(), Some(reason)),
+                Verdict::Unknown(reason) => ("unknown", self.theme.style_warning(), Some(reason)),
+            };
+            let mut row = vec![
+                Span::styled(format!("  {:<6}", name), self.theme.style_type()),
+                Span::styled(format!("{:<8}", answer), style),
+            ];
+            if let Some(reason) = reason {
+                row.push(Span::styled(reason.clone(), self.theme.style_muted()));
+            }
+            lines.push(Line::from(row));
+        }
+        if !traits.interior_mutability.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "  Interior mutability:",
+                self.theme.style_dim(),
+            )));
+            for field in &traits.interior_mutability {
+                lines.push(Line::from(vec![
+                    Span::styled(self.theme.glyph("  ⚠ ", "  ! "), self.theme.style_warning()),
+                    Span::styled(field.clone(), self.theme.style_normal()),
+                ]));
+            }
+        }
+    }
+
+    /// Methods section of a struct or enum: inherent and trait methods in one table
+    /// with an origin column, ordered by `method_sort`
+    fn push_method_table(&self, type_name: &str, lines: &mut Vec<Line<'static>>) {
+        let methods = method_table(
+            type_name,
+            self.all_items.unwrap_or_default(),
+            self.method_sort,
+        );
+        if methods.is_empty() {
+            return;
+        }
+        lines.push(Line::from(""));
+        lines.push(self.section_header(&format!(
+            "Methods ({}) · by {}",
+            methods.len(),
+            self.method_sort.label()
+        )));
+        lines.push(Line::from(""));
+
+        let origins: Vec<String> = methods.iter().map(|m| m.origin.label()).collect();
+        let name_width = methods.iter().map(|m| m.name.len()).max().unwrap_or(0);
+        let origin_width = origins.iter().map(|o| o.chars().count()).max().unwrap_or(0);
+        for (method, origin) in methods.iter().zip(origins) {
+            let origin_style = match method.origin {
+                MethodOrigin::Inherent => self.theme.style_accent(),
+                MethodOrigin::Trait(_) => self.theme.style_type(),
+                MethodOrigin::Provided(_) => self.theme.style_muted(),
+            };
+            let rest = method
+                .signature
+                .strip_prefix(method.name.as_str())
+                .unwrap_or(&method.signature);
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(
+                    format!("{:<w$}  ", method.name, w = name_width),
+                    self.theme.style_function(),
+                ),
+                Span::styled(format!("{:<w$}  ", origin, w = origin_width), origin_style),
+                Span::styled(rest.to_string(), self.theme.style_dim()),
+            ]));
+        }
+    }
+
+    fn trait_lines(&self, tr: &TraitInfo) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
 
         let mut header = vec![
@@ -817,6 +1615,24 @@ impl<'a> InspectorPanel<'a> {
             lines.push(self.key_value("Associated Types:", tr.associated_types.len().to_string()));
         }
 
+        let safety = ObjectSafety::of(tr);
+        lines.push(Line::from(vec![
+            Span::styled("  Object Safe: ", self.theme.style_dim()),
+            if safety.is_safe() {
+                Span::styled("yes", self.theme.style_success())
+            } else {
+                Span::styled("no", self.theme.style_warning())
+            },
+        ]));
+        for reason in &safety.reasons {
+            lines.push(Line::from(vec![
+                Span::styled("    · ", self.theme.style_dim()),
+                Span::styled(reason.clone(), self.theme.style_muted()),
+            ]));
+        }
+
+        self.push_generics(&tr.generics_info, &mut lines);
+
         // Supertraits
         if !tr.supertraits.is_empty() {
             lines.push(Line::from(""));
@@ -933,10 +1749,12 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        self.render_panel(" 📜 Trait ", lines, area, buf);
+        self.push_doc_examples(tr.documentation.as_deref(), &mut lines);
+
+        lines
     }
 
-    fn render_impl(&self, im: &ImplInfo, area: Rect, buf: &mut Buffer) {
+    fn impl_lines(&self, im: &ImplInfo) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
 
         let title = if let Some(ref trait_name) = im.trait_name {
@@ -970,11 +1788,18 @@ impl<'a> InspectorPanel<'a> {
         }
 
         lines.push(self.key_value("Methods:", im.methods.len().to_string()));
-
-        if !im.generics.is_empty() {
-            lines.push(self.key_value("Generics:", format!("<{}>", im.generics.join(", "))));
+        if let Some(c) = self.coverage.and_then(|data| data.for_impl(im)) {
+            lines.push(Line::from(vec![
+                Span::styled("  Coverage: ", self.theme.style_dim()),
+                Span::styled(
+                    format!("{:.0}% ({}/{} lines)", c.percent(), c.covered, c.total),
+                    self.theme.style_coverage(c.percent()),
+                ),
+            ]));
         }
 
+        self.push_generics(&im.generics_info, &mut lines);
+
         // Methods
         if !im.methods.is_empty() {
             lines.push(Line::from(""));
@@ -1007,27 +1832,21 @@ impl<'a> InspectorPanel<'a> {
                     method_line.push(Span::styled(" → ", self.theme.style_accent()));
                     method_line.push(Span::styled(ret.clone(), self.theme.style_type()));
                 }
+                if let Some(c) = self.coverage.and_then(|data| data.for_function(method)) {
+                    method_line.push(Span::styled(
+                        format!("  {:.0}%", c.percent()),
+                        self.theme.style_coverage(c.percent()),
+                    ));
+                }
 
                 lines.push(Line::from(method_line));
             }
         }
 
-        // Where clause
-        if let Some(ref where_clause) = im.where_clause {
-            lines.push(Line::from(""));
-            lines.push(self.section_header("Constraints"));
-            lines.push(Line::from(""));
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled("where ", self.theme.style_keyword()),
-                Span::styled(where_clause.clone(), self.theme.style_type()),
-            ]));
-        }
-
-        self.render_panel(" ⚙️ Implementation ", lines, area, buf);
+        lines
     }
 
-    fn render_module(&self, module: &ModuleInfo, area: Rect, buf: &mut Buffer) {
+    fn module_lines(&self, module: &ModuleInfo) -> Vec<Line<'static>> {
         let mut lines = vec![
             Line::from(vec![
                 Span::styled("mod ", self.theme.style_keyword()),
@@ -1107,10 +1926,12 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        self.render_panel(" 📁 Module ", lines, area, buf);
+        self.push_doc_examples(module.documentation.as_deref(), &mut lines);
+
+        lines
     }
 
-    fn render_type_alias(&self, alias: &TypeAliasInfo, area: Rect, buf: &mut Buffer) {
+    fn type_alias_lines(&self, alias: &TypeAliasInfo) -> Vec<Line<'static>> {
         let mut lines = vec![
             Line::from(vec![
                 Span::styled("type ", self.theme.style_keyword()),
@@ -1130,9 +1951,7 @@ impl<'a> InspectorPanel<'a> {
             self.key_value("Aliased Type:", alias.ty.clone()),
         ];
 
-        if !alias.generics.is_empty() {
-            lines.push(self.key_value("Generics:", format!("<{}>", alias.generics.join(", "))));
-        }
+        self.push_generics(&alias.generics_info, &mut lines);
 
         if let Some(ref docs) = alias.documentation {
             lines.push(Line::from(""));
@@ -1147,10 +1966,12 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        self.render_panel(" 🔗 Type Alias ", lines, area, buf);
+        self.push_doc_examples(alias.documentation.as_deref(), &mut lines);
+
+        lines
     }
 
-    fn render_const(&self, c: &ConstInfo, area: Rect, buf: &mut Buffer) {
+    fn const_lines(&self, c: &ConstInfo) -> Vec<Line<'static>> {
         let mut lines = vec![
             Line::from(vec![
                 Span::styled("const ", self.theme.style_keyword()),
@@ -1170,8 +1991,24 @@ impl<'a> InspectorPanel<'a> {
             self.key_value("Type:", c.ty.clone()),
         ];
 
-        if let Some(ref value) = c.value {
-            lines.push(self.key_value("Value:", value.clone()));
+        let consts = ConstTable::new(self.all_items.unwrap_or_default());
+        let computed = consts
+            .get(&c.name)
+            .or_else(|| consts.eval_as(c.value.as_deref()?, &c.ty));
+        match (computed, &c.value) {
+            (Some(value), expr) => {
+                lines.push(self.key_value("Value:", value.to_string()));
+                if let Some(expr) = expr.as_ref().filter(|e| **e != value.to_string()) {
+                    lines.push(self.key_value("Expression:", expr.clone()));
+                }
+                lines.push(self.key_value("Hex:", const_eval::hex(value, &c.ty)));
+                lines.push(self.key_value("Binary:", const_eval::binary(value, &c.ty)));
+                if let Some(flags) = consts.flags(&c.name, value, &c.ty) {
+                    lines.push(self.key_value("Flags:", flags.join(" | ")));
+                }
+            }
+            (None, Some(value)) => lines.push(self.key_value("Value:", value.clone())),
+            (None, None) => {}
         }
 
         if let Some(ref docs) = c.documentation {
@@ -1187,10 +2024,12 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        self.render_panel(" 📌 Constant ", lines, area, buf);
+        self.push_doc_examples(c.documentation.as_deref(), &mut lines);
+
+        lines
     }
 
-    fn render_static(&self, s: &StaticInfo, area: Rect, buf: &mut Buffer) {
+    fn static_lines(&self, s: &StaticInfo) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
 
         let mut header = vec![Span::styled("static ", self.theme.style_keyword())];
@@ -1219,16 +2058,26 @@ impl<'a> InspectorPanel<'a> {
         lines.push(Line::from(""));
         lines.push(self.key_value("Visibility:", s.visibility.to_string()));
         lines.push(self.key_value("Type:", s.ty.clone()));
-        lines.push(self.key_value(
-            "Mutable:",
-            if s.is_mut { "yes ⚠️" } else { "no" }.to_string(),
-        ));
+        lines.push(
+            self.key_value(
+                "Mutable:",
+                if s.is_mut {
+                    self.theme.glyph("yes ⚠️", "yes !")
+                } else {
+                    "no"
+                }
+                .to_string(),
+            ),
+        );
 
         if s.is_mut {
             lines.push(Line::from(""));
             lines.push(Line::from(vec![
                 Span::raw("  "),
-                Span::styled("⚠ Warning: ", self.theme.style_error()),
+                Span::styled(
+                    self.theme.glyph("⚠ Warning: ", "! Warning: "),
+                    self.theme.style_error(),
+                ),
                 Span::styled("Mutable statics are unsafe!", self.theme.style_warning()),
             ]));
         }
@@ -1246,13 +2095,278 @@ impl<'a> InspectorPanel<'a> {
             }
         }
 
-        self.render_panel(" 🌐 Static ", lines, area, buf);
+        self.push_doc_examples(s.documentation.as_deref(), &mut lines);
+
+        lines
     }
 
-    fn render_panel(&self, title: &str, lines: Vec<Line<'static>>, area: Rect, buf: &mut Buffer) {
-        let total_lines = lines.len();
+    fn cache_key(&self, item: &AnalyzedItem, width: u16) -> CacheKey {
+        CacheKey {
+            item: address(Some(item)),
+            sources: [
+                address(self.all_items),
+                address(self.usage_index),
+                address(self.coverage),
+                address(self.doc_links),
+            ],
+            theme: self.theme.name.clone(),
+            compat: self.theme.compat,
+            width,
+            doc_example_selected: self.doc_example_selected,
+            wrap_code: self.wrap_code,
+            code_hscroll: if self.wrap_code { 0 } else { self.code_hscroll },
+            method_sort: self.method_sort,
+            sections: self.sections.signature(item.kind()),
+            raw: self.raw,
+            diff: self.diff.map(str::to_string),
+            review_note: self.review_note(item).map(str::to_string),
+        }
+    }
 
-        let block = Block::default()
+    fn lines(&self, item: &AnalyzedItem, width: u16) -> Vec<Line<'static>> {
+        let mut lines = match item {
+            AnalyzedItem::Function(f) => self.function_lines(f, width),
+            AnalyzedItem::Struct(s) => self.struct_lines(s, width),
+            AnalyzedItem::Enum(e) => self.enum_lines(e),
+            AnalyzedItem::Trait(t) => self.trait_lines(t),
+            AnalyzedItem::Impl(i) => self.impl_lines(i),
+            AnalyzedItem::Module(m) => self.module_lines(m),
+            AnalyzedItem::TypeAlias(t) => self.type_alias_lines(t),
+            AnalyzedItem::Const(c) => self.const_lines(c),
+            AnalyzedItem::Static(s) => self.static_lines(s),
+        };
+        self.push_c_header(item, &mut lines);
+        self.push_broken_links(item, &mut lines);
+        self.push_review_note(item, &mut lines);
+        lines
+    }
+
+    fn review_note(&self, item: &AnalyzedItem) -> Option<&'a str> {
+        let note = self.review?.get(item.kind(), &item.qualified_name())?;
+        Some(note.note.as_str())
+    }
+
+    /// Badge on the header and a "Review Note" section with the note written on
+    /// the item (`n`)
+    fn push_review_note(&self, item: &AnalyzedItem, lines: &mut Vec<Line<'static>>) {
+        let Some(note) = self.review_note(item) else {
+            return;
+        };
+        if let Some(header) = lines.first_mut() {
+            header.spans.push(self.badge("review note", false));
+        }
+        lines.push(Line::from(""));
+        lines.push(self.section_header("Review Note"));
+        lines.push(Line::from(""));
+        for line in note.lines() {
+            lines.push(Line::from(vec![
+                Span::styled("  │ ", self.theme.style_accent()),
+                Span::styled(line.to_string(), self.theme.style_normal()),
+            ]));
+        }
+    }
+
+    /// C Header section: the item as cbindgen would declare it, for exported
+    /// functions and types with a C layout
+    fn push_c_header(&self, item: &AnalyzedItem, lines: &mut Vec<Line<'static>>) {
+        let Some(declaration) = c_declaration(item, self.all_items.unwrap_or_default()) else {
+            return;
+        };
+        lines.push(Line::from(""));
+        lines.push(self.section_header("C Header"));
+        lines.push(Line::from(""));
+        for code_line in declaration.lines() {
+            lines.push(Line::from(vec![
+                Span::styled("  │ ", self.theme.style_muted()),
+                Span::styled(code_line.to_string(), self.theme.style_normal()),
+            ]));
+        }
+    }
+
+    /// Source of the item read from its file, highlighted, with line numbers
+    /// after [`RAW_HEADER_LINES`] lines of header
+    fn raw_lines(&self, item: &AnalyzedItem) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(vec![
+            Span::styled(format!("{} ", item.kind()), self.theme.style_keyword()),
+            Span::styled(item.name().to_string(), self.theme.style_accent_bold()),
+        ])];
+        let location = item.source_location();
+        let Some((first, source)) = location.and_then(|l| l.read_source()) else {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "  No source file for this item (v shows the analysis)",
+                self.theme.style_muted(),
+            )));
+            return lines;
+        };
+        if let Some(file) = location.and_then(|l| l.file.as_ref()) {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", file.display()),
+                self.theme.style_muted(),
+            )));
+        }
+        lines.push(Line::from(""));
+        let digits = (first + source.lines().count()).to_string().len();
+        for (n, code) in source.lines().enumerate() {
+            let mut spans = vec![Span::styled(
+                format!("{:>digits$} │ ", first + n),
+                self.theme.style_dim(),
+            )];
+            spans.extend(highlight_rust_line(code, self.theme));
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    /// The item's source in the working tree against git revision `rev`: removed
+    /// lines, added lines and a few unchanged ones around them
+    fn diff_lines(&self, item: &AnalyzedItem, rev: &str) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(vec![
+            Span::styled(format!("{} ", item.kind()), self.theme.style_keyword()),
+            Span::styled(item.name().to_string(), self.theme.style_accent_bold()),
+        ])];
+        let note = |lines: &mut Vec<Line<'static>>, text: String, style: Style| {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(format!("  {}", text), style)));
+        };
+        let file = item.source_location().and_then(|l| l.file.as_ref());
+        let Some((file, source)) = file.and_then(|f| Some((f, std::fs::read_to_string(f).ok()?)))
+        else {
+            note(
+                &mut lines,
+                "No source file for this item (d shows the analysis)".to_string(),
+                self.theme.style_muted(),
+            );
+            return lines;
+        };
+        lines.push(Line::from(Span::styled(
+            format!("  {}", file.display()),
+            self.theme.style_muted(),
+        )));
+        let old = match git::show_file(file, rev) {
+            Ok(old) => old,
+            Err(e) => {
+                note(&mut lines, format!("git: {}", e), self.theme.style_error());
+                return lines;
+            }
+        };
+        let diff = match item_diff::item_change(item, file, &source, old.as_deref()) {
+            None => {
+                note(
+                    &mut lines,
+                    "The item's lines aren't known".to_string(),
+                    self.theme.style_muted(),
+                );
+                return lines;
+            }
+            Some(ItemChange::Unchanged) => {
+                note(
+                    &mut lines,
+                    format!("No changes since {}", rev),
+                    self.theme.style_muted(),
+                );
+                return lines;
+            }
+            Some(ItemChange::Added(diff)) => {
+                if let Some(header) = lines.first_mut() {
+                    header
+                        .spans
+                        .push(self.badge(&format!("new since {}", rev), false));
+                }
+                diff
+            }
+            Some(ItemChange::Modified(diff)) => diff,
+        };
+        lines.push(Line::from(""));
+        for line in diff {
+            let text = line.text;
+            lines.push(match line.tag {
+                DiffTag::Added => Line::from(Span::styled(
+                    format!("  + {}", text),
+                    self.theme.style_success(),
+                )),
+                DiffTag::Removed => Line::from(Span::styled(
+                    format!("  - {}", text),
+                    self.theme.style_error(),
+                )),
+                DiffTag::Context => {
+                    let mut spans = vec![Span::raw("    ")];
+                    spans.extend(highlight_rust_line(&text, self.theme));
+                    Line::from(spans)
+                }
+                DiffTag::Skipped => Line::from(Span::styled(
+                    format!("  ⋯ {}", text),
+                    self.theme.style_dim(),
+                )),
+            });
+        }
+        lines
+    }
+
+    /// Badge on the header and a "Broken Links" section for links in the docs of
+    /// the item (or of its methods) that lead nowhere
+    fn push_broken_links(&self, item: &AnalyzedItem, lines: &mut Vec<Line<'static>>) {
+        let Some(links) = self.doc_links else {
+            return;
+        };
+        let name = item.qualified_name();
+        let members = format!("{}::", name);
+        let is_module = matches!(item, AnalyzedItem::Module(_));
+        let broken: Vec<&DocLink> = links
+            .iter()
+            .filter(|l| l.is_broken())
+            .filter(|l| l.owner == name || (!is_module && l.owner.starts_with(&members)))
+            .collect();
+        if broken.is_empty() {
+            return;
+        }
+        let label = match broken.len() {
+            1 => "1 broken link".to_string(),
+            n => format!("{} broken links", n),
+        };
+        if let Some(header) = lines.first_mut() {
+            header.spans.push(self.badge(&label, true));
+        }
+        lines.push(Line::from(""));
+        lines.push(self.section_header(&format!("Broken Links ({})", broken.len())));
+        lines.push(Line::from(""));
+        for link in broken {
+            let mut spans = vec![Span::styled(
+                format!("  {}", link.target),
+                self.theme.style_error(),
+            )];
+            if let LinkStatus::Broken(ref reason) = link.status {
+                spans.push(Span::styled(
+                    format!("  {}", reason),
+                    self.theme.style_muted(),
+                ));
+            }
+            if link.owner != name {
+                spans.push(Span::styled(
+                    format!("  in {}", link.owner),
+                    self.theme.style_dim(),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    fn title(&self, item: &AnalyzedItem) -> &'static str {
+        match item {
+            AnalyzedItem::Function(_) => self.theme.glyph(" 🔧 Function ", " Function "),
+            AnalyzedItem::Struct(_) => self.theme.glyph(" 📦 Struct ", " Struct "),
+            AnalyzedItem::Enum(_) => self.theme.glyph(" 🏷️ Enum ", " Enum "),
+            AnalyzedItem::Trait(_) => self.theme.glyph(" 📜 Trait ", " Trait "),
+            AnalyzedItem::Impl(_) => self.theme.glyph(" ⚙️ Implementation ", " Implementation "),
+            AnalyzedItem::Module(_) => self.theme.glyph(" 📁 Module ", " Module "),
+            AnalyzedItem::TypeAlias(_) => self.theme.glyph(" 🔗 Type Alias ", " Type Alias "),
+            AnalyzedItem::Const(_) => self.theme.glyph(" 📌 Constant ", " Constant "),
+            AnalyzedItem::Static(_) => self.theme.glyph(" 🌐 Static ", " Static "),
+        }
+    }
+
+    fn render_panel(&self, title: &str, panel: &CachedPanel, area: Rect, buf: &mut Buffer) {
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(if self.focused {
@@ -1262,43 +2376,313 @@ impl<'a> InspectorPanel<'a> {
             })
             .style(Style::default().bg(self.theme.bg_panel))
             .title(title);
+        if self.pinned {
+            block = block.title_bottom(Line::from(Span::styled(
+                self.theme
+                    .glyph(" 📌 pinned · [p] unpin ", " pinned · [p] unpin "),
+                self.theme.style_muted(),
+            )));
+        }
 
         let inner = block.inner(area);
         block.render(area, buf);
 
-        // Apply scroll offset
-        let visible_lines: Vec<Line> = lines.into_iter().skip(self.scroll_offset).collect();
+        let mut lines = panel.lines.clone();
+        let cursor = self
+            .source_cursor
+            .filter(|_| self.raw && self.diff.is_none());
+        if let Some(cursor) = cursor {
+            if let Some(line) = lines.get_mut(RAW_HEADER_LINES + cursor.line) {
+                // The gutter is the first span; columns count from the code after it
+                let gutter = line.spans.first().map_or(0, |s| s.content.chars().count());
+                let (start, end) = cursor.columns;
+                mark_columns(
+                    line,
+                    gutter + start,
+                    gutter + end,
+                    self.theme.style_selected(),
+                );
+            }
+        }
 
-        Paragraph::new(visible_lines)
-            .wrap(Wrap { trim: false })
+        // Scroll in wrapped rows so the offset, its limit and the scrollbar match what is drawn
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+        let total_rows = panel.rows.get().unwrap_or_else(|| {
+            let rows = paragraph.line_count(inner.width);
+            panel.rows.set(Some(rows));
+            rows
+        });
+        let max_scroll = total_rows.saturating_sub(inner.height as usize);
+        let mut offset = self.scroll_offset.min(max_scroll);
+        let mut inner = inner;
+        if let Some(cursor) = cursor {
+            let popup = self.render_hover(cursor, inner, buf);
+            inner.height -= popup;
+            // Keep the marked line in the rows left above the popup
+            let before = RAW_HEADER_LINES + cursor.line;
+            let row = Paragraph::new(panel.lines[..before.min(panel.lines.len())].to_vec())
+                .wrap(Wrap { trim: false })
+                .line_count(inner.width);
+            let visible = (inner.height as usize).max(1);
+            offset = offset.min(row).max((row + 1).saturating_sub(visible));
+        }
+        if let Some(cell) = self.top_section_out {
+            *cell.borrow_mut() = panel.section_at(offset, inner.width).map(str::to_string);
+        }
+        if let Some(cell) = self.max_scroll_out {
+            cell.set(Some(cell.get().map_or(max_scroll, |m| m.max(max_scroll))));
+        }
+        paragraph
+            .scroll((offset.min(u16::MAX as usize) as u16, 0))
             .render(inner, buf);
 
         // Render scrollbar if content exceeds view
-        if total_lines > inner.height as usize {
+        if max_scroll > 0 {
             let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
                 .begin_symbol(Some("↑"))
                 .end_symbol(Some("↓"));
 
-            let mut scrollbar_state = ScrollbarState::new(total_lines).position(self.scroll_offset);
+            let mut scrollbar_state = ScrollbarState::new(max_scroll + 1)
+                .viewport_content_length(inner.height as usize)
+                .position(offset);
 
             scrollbar.render(inner, buf, &mut scrollbar_state);
         }
     }
+
+    /// The source cursor's popup, docked at the bottom of `inner`; returns its height
+    fn render_hover(&self, cursor: SourceCursorView, inner: Rect, buf: &mut Buffer) -> u16 {
+        let lines: Vec<Line> = cursor
+            .hover
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let style = if i == 0 {
+                    self.theme.style_accent_bold()
+                } else {
+                    self.theme.style_normal()
+                };
+                Line::from(Span::styled(text.clone(), style))
+            })
+            .collect();
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .border_style(self.theme.style_border_focused())
+            .title(Span::styled(
+                format!(" {} · Enter: go to definition · Esc ", cursor.source),
+                self.theme.style_muted(),
+            ));
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block);
+        let height = (paragraph.line_count(inner.width) as u16).min(inner.height / 2);
+        let area = Rect {
+            y: inner.y + inner.height - height,
+            height,
+            ..inner
+        };
+        Clear.render(area, buf);
+        paragraph
+            .style(Style::default().bg(self.theme.bg_panel))
+            .render(area, buf);
+        height
+    }
+}
+
+/// Restyle the chars `start..end` of `line`, splitting the spans they fall in
+fn mark_columns(line: &mut Line<'static>, start: usize, end: usize, style: Style) {
+    let mut spans = Vec::with_capacity(line.spans.len() + 2);
+    let mut column = 0;
+    for span in line.spans.drain(..) {
+        let len = span.content.chars().count();
+        let (from, to) = (
+            start.clamp(column, column + len),
+            end.clamp(column, column + len),
+        );
+        if from == to {
+            spans.push(span);
+        } else {
+            let chars: Vec<char> = span.content.chars().collect();
+            let part = |range: std::ops::Range<usize>| -> String {
+                chars[range.start - column..range.end - column]
+                    .iter()
+                    .collect()
+            };
+            for (text, part_style) in [
+                (part(column..from), span.style),
+                (part(from..to), span.style.patch(style)),
+                (part(to..column + len), span.style),
+            ] {
+                if !text.is_empty() {
+                    spans.push(Span::styled(text, part_style));
+                }
+            }
+        }
+        column += len;
+    }
+    line.spans = spans;
 }
 
 impl Widget for InspectorPanel<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        match self.item {
-            None => self.render_empty(area, buf),
-            Some(AnalyzedItem::Function(f)) => self.render_function(f, area, buf),
-            Some(AnalyzedItem::Struct(s)) => self.render_struct(s, area, buf),
-            Some(AnalyzedItem::Enum(e)) => self.render_enum(e, area, buf),
-            Some(AnalyzedItem::Trait(t)) => self.render_trait(t, area, buf),
-            Some(AnalyzedItem::Impl(i)) => self.render_impl(i, area, buf),
-            Some(AnalyzedItem::Module(m)) => self.render_module(m, area, buf),
-            Some(AnalyzedItem::TypeAlias(t)) => self.render_type_alias(t, area, buf),
-            Some(AnalyzedItem::Const(c)) => self.render_const(c, area, buf),
-            Some(AnalyzedItem::Static(s)) => self.render_static(s, area, buf),
-        }
+        let Some(item) = self.item else {
+            return self.render_empty(area, buf);
+        };
+        let build = || {
+            if let Some(rev) = self.diff {
+                CachedPanel::new(self.diff_lines(item, rev), Vec::new())
+            } else if self.raw {
+                CachedPanel::new(self.raw_lines(item), Vec::new())
+            } else {
+                self.arrange(item.kind(), self.lines(item, area.width))
+            }
+        };
+        let panel = match self.cache {
+            Some(cache) => cache.get_or_build(self.cache_key(item, area.width), build),
+            None => Rc::new(build()),
+        };
+        let title = if let Some(rev) = self.diff {
+            format!("{}· diff vs {} ", self.title(item), rev)
+        } else if self.raw {
+            format!("{}· source ", self.title(item))
+        } else {
+            self.title(item).to_string()
+        };
+        self.render_panel(&title, &panel, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_cache_reuses_lines_until_key_changes() {
+        let items = RustAnalyzer::new()
+            .analyze_source("/// A big enum\npub enum Big { A, B(u8), C { x: i32 } }")
+            .unwrap();
+        let theme = Theme::default();
+        let cache = InspectorCache::default();
+        let area = Rect::new(0, 0, 60, 20);
+        let render = |theme: &Theme, area: Rect| {
+            let mut buf = Buffer::empty(area);
+            InspectorPanel::new(theme)
+                .item(items.first())
+                .cache(Some(&cache))
+                .render(area, &mut buf);
+            buf
+        };
+
+        let first = render(&theme, area);
+        let built = Rc::clone(&cache.entries.borrow()[0].1);
+        assert_eq!(render(&theme, area), first);
+        assert_eq!(cache.entries.borrow().len(), 1);
+        assert!(Rc::ptr_eq(&cache.entries.borrow()[0].1, &built));
+        assert!(built.rows.get().is_some());
+
+        render(&theme, Rect::new(0, 0, 40, 20));
+        render(&Theme::nord(), area);
+        assert_eq!(cache.entries.borrow().len(), 3);
+        cache.clear();
+        assert!(cache.entries.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_sections_hidden_reordered_and_collapsed() {
+        let items = RustAnalyzer::new()
+            .analyze_source("/// Adds one\n#[inline]\npub fn bump(x: u32) -> u32 { x + 1 }")
+            .unwrap();
+        let theme = Theme::default();
+        let settings = InspectorSettings {
+            order: vec!["documentation".into()],
+            hidden: vec!["Signature".into()],
+            ..Default::default()
+        };
+        let mut collapsed = BTreeMap::new();
+        collapsed.insert("fn".to_string(), BTreeSet::from(["Attributes".to_string()]));
+        let top = RefCell::new(None);
+        let render = |layout: SectionLayout| {
+            let area = Rect::new(0, 0, 60, 200);
+            let mut buf = Buffer::empty(area);
+            InspectorPanel::new(&theme)
+                .item(items.first())
+                .sections(layout)
+                .report_top_section(Some(&top))
+                .render(area, &mut buf);
+            crate::ui::testing::buffer_text(&buf)
+        };
+        let position = |screen: &str, text: &str| screen.find(text);
+
+        let plain = render(SectionLayout::default());
+        assert!(position(&plain, "Signature") < position(&plain, "Documentation"));
+        assert_eq!(top.borrow().as_deref(), Some("Signature"));
+
+        let arranged = render(SectionLayout {
+            settings: Some(&settings),
+            collapsed: Some(&collapsed),
+        });
+        assert!(!arranged.contains("Signature"));
+        assert!(position(&arranged, "Documentation") < position(&arranged, "Overview"));
+        assert!(arranged.contains("Attributes ··· 1 line hidden"));
+        assert!(!arranged.contains("#[inline]"));
+        assert_eq!(top.borrow().as_deref(), Some("Documentation"));
+        assert_eq!(section_title("Parameters (1)"), "Parameters");
+    }
+
+    #[test]
+    fn test_raw_view_shows_source_as_written() {
+        let dir = std::env::temp_dir().join(format!("oracle-raw-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("lib.rs");
+        std::fs::write(
+            &file,
+            "use std::fmt;\n\n/// Adds one\n#[inline]\npub fn bump(x: u32) -> u32 {\n    x + 1 // keep\n}\n",
+        )
+        .unwrap();
+        let items = RustAnalyzer::new().analyze_file(&file).unwrap();
+        let theme = Theme::default();
+        let render = |item: Option<&AnalyzedItem>| {
+            let area = Rect::new(0, 0, 60, 14);
+            let mut buf = Buffer::empty(area);
+            InspectorPanel::new(&theme)
+                .item(item)
+                .raw(true)
+                .render(area, &mut buf);
+            crate::ui::testing::buffer_text(&buf)
+        };
+
+        let screen = render(items.first());
+        assert!(screen.contains("Function · source"));
+        assert!(screen.contains("3 │ /// Adds one"));
+        assert!(screen.contains("6 │     x + 1 // keep"));
+        assert!(!screen.contains("use std::fmt"));
+        assert!(!screen.contains("Signature"));
+
+        let inline = RustAnalyzer::new().analyze_source("pub fn f() {}").unwrap();
+        assert!(render(inline.first()).contains("No source file"));
+
+        // The cursor's identifier is marked and its popup docked at the bottom
+        let hover = vec!["struct u32".to_string(), "  builtin".to_string()];
+        let area = Rect::new(0, 0, 60, 14);
+        let mut buf = Buffer::empty(area);
+        InspectorPanel::new(&theme)
+            .item(items.first())
+            .raw(true)
+            .source_cursor(Some(SourceCursorView {
+                line: 2,
+                columns: (15, 18),
+                hover: &hover,
+                source: "test",
+            }))
+            .render(area, &mut buf);
+        let screen = crate::ui::testing::buffer_text(&buf);
+        assert!(screen.contains("5 │ pub fn bump(x: u32) -> u32 {"));
+        assert!(screen.contains("test · Enter: go to definition"));
+        assert!(screen.contains("struct u32"));
+        let (row, column) = (6, 1 + "5 │ pub fn bump(x: ".chars().count() as u16);
+        assert_eq!(buf[(column, row)].symbol(), "u");
+        assert_eq!(buf[(column, row)].bg, theme.style_selected().bg.unwrap());
+        std::fs::remove_dir_all(&dir).ok();
     }
 }
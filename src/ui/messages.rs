@@ -0,0 +1,189 @@
+//! Status message history
+//!
+//! Every status bar message lands in a bounded [`MessageLog`] with its severity and
+//! time, so errors that are immediately overwritten can still be read back in the
+//! history overlay (`!`). Warnings and errors also pop up briefly as a toast.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::report::{Report, ReportLevel, ReportRow};
+
+/// Entries kept before the oldest are dropped
+const LOG_CAPACITY: usize = 200;
+/// How long a warning or error stays up as a toast
+pub const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub severity: Severity,
+    pub text: String,
+    pub at: Instant,
+    /// Times the same message was repeated in a row
+    pub count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageLog {
+    entries: VecDeque<LogEntry>,
+    started: Instant,
+}
+
+impl MessageLog {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Record a message; a repeat of the latest one bumps its count and time instead
+    pub fn push(&mut self, severity: Severity, text: impl Into<String>) {
+        let text = text.into();
+        let now = Instant::now();
+        if let Some(last) = self
+            .entries
+            .back_mut()
+            .filter(|e| e.text == text && e.severity == severity)
+        {
+            last.count += 1;
+            last.at = now;
+            return;
+        }
+        if self.entries.len() == LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry {
+            severity,
+            text,
+            at: now,
+            count: 1,
+        });
+    }
+
+    pub fn latest(&self) -> Option<&LogEntry> {
+        self.entries.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    /// Newest warning or error logged within the toast duration, even if info
+    /// messages came after it
+    pub fn toast(&self) -> Option<&LogEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .take_while(|e| e.at.elapsed() < TOAST_DURATION)
+            .find(|e| e.severity >= Severity::Warning)
+    }
+
+    /// `hh:mm:ss` since the session started
+    pub fn timestamp(&self, entry: &LogEntry) -> String {
+        let secs = entry.at.saturating_duration_since(self.started).as_secs();
+        format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    }
+
+    /// History overlay: newest first
+    pub fn report(&self) -> Report {
+        let count = |severity| self.iter().filter(|e| e.severity == severity).count();
+        let rows = self
+            .iter()
+            .rev()
+            .map(|e| {
+                let level = match e.severity {
+                    Severity::Info => ReportLevel::Normal,
+                    Severity::Warning => ReportLevel::Warning,
+                    Severity::Error => ReportLevel::Error,
+                };
+                let repeat = if e.count > 1 {
+                    format!(" (×{})", e.count)
+                } else {
+                    String::new()
+                };
+                ReportRow::new(
+                    format!(
+                        "{}  {:<5}  {}{}",
+                        self.timestamp(e),
+                        e.severity.label(),
+                        e.text,
+                        repeat
+                    ),
+                    level,
+                )
+            })
+            .collect();
+        Report::new(
+            "Messages",
+            format!(
+                "{} messages · {} errors · {} warnings · times since start (last {} kept)",
+                self.len(),
+                count(Severity::Error),
+                count(Severity::Warning),
+                LOG_CAPACITY
+            ),
+        )
+        .section("History", rows, "No messages yet")
+    }
+}
+
+impl Default for MessageLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_repeats_capacity_and_toast() {
+        let mut log = MessageLog::new();
+        log.push(Severity::Info, "Ready");
+        assert!(log.toast().is_none());
+        log.push(Severity::Error, "Copy failed");
+        log.push(Severity::Error, "Copy failed");
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.latest().unwrap().count, 2);
+        log.push(Severity::Info, "Found 3 items");
+        assert_eq!(log.toast().unwrap().text, "Copy failed");
+
+        for i in 0..LOG_CAPACITY {
+            log.push(Severity::Info, format!("message {}", i));
+        }
+        assert_eq!(log.len(), LOG_CAPACITY);
+        assert_eq!(log.iter().next().unwrap().text, "message 0");
+
+        let report = log.report();
+        assert!(report.sections[0].rows[0]
+            .text
+            .ends_with(&format!("info   message {}", LOG_CAPACITY - 1)));
+    }
+}
@@ -2,17 +2,31 @@
 
 pub mod animation;
 pub mod app;
+pub mod code_layout;
 pub mod components;
 pub mod dependency_view;
+pub mod highlight;
+pub mod html;
 pub mod inspector;
+pub mod messages;
+pub mod report;
 pub mod search;
 pub mod splash;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod theme;
 
 pub use animation::{AnimationState, Easing, SmoothScroll};
-pub use app::{tabs_rect_for_area, Focus, OracleUi, Tab};
+pub use app::{
+    completion_rect, search_rect_for_area, tabs_rect_for_area, CompareView, FeaturePicker,
+    FilesView, FilteredItems, Focus, OracleUi, ProjectPicker, RegistrySearch, SpellingView, Tab,
+    ViewPicker,
+};
 pub use dependency_view::DependencyView;
-pub use inspector::InspectorPanel;
+pub use inspector::{InspectorCache, InspectorPanel, SectionLayout, SourceCursorView};
+pub use messages::{LogEntry, MessageLog, Severity};
+pub use report::{Report, ReportLevel, ReportRow, ReportSection};
 pub use search::{
-    filter_candidates, CandidateKind, CompletionCandidate, SearchBar, SearchCompletion,
+    completion_at, completion_offset, filter_candidates, path_candidates, CandidateKind,
+    CompletionCandidate, SearchBar, SearchCompletion, COMPLETION_VISIBLE,
 };
@@ -0,0 +1,1172 @@
+//! Project-wide reports shown in a scrollable overlay
+//!
+//! Analyses that summarize the whole crate rather than one item (e.g. the async
+//! audit) are turned into a [`Report`]: titled sections of rows, each with an
+//! optional detail line and a severity that picks its color.
+
+use std::path::Path;
+
+use crate::analyzer::allocations::Hotspot;
+use crate::analyzer::cfg::group_by_cfg;
+use crate::analyzer::env_vars::{EnvReadKind, EnvVar};
+use crate::analyzer::history::{ItemHistory, StepKind};
+use crate::analyzer::item_diff::DiffTag;
+use crate::analyzer::panics::{panicking_functions, PanicKind, PanickingFn};
+use crate::analyzer::{
+    AnalyzedItem, AsyncAudit, CfgTarget, Confidence, DocLink, ErrorCatalog, FfiSurface,
+    ImportGraph, LinkStatus, NoStdAttr, NoStdReport, NoStdSupport, Note, NoteTag, Outlook,
+    TypeUsage, UnusedItem, UsageKind, WasmImpact, WasmIssue, WasmScan, WasmTarget,
+};
+use crate::config::ReviewNotes;
+use crate::crates_io::{age_in_days, GitHubActivity, GitHubIssue};
+use crate::utils::logging::LogLine;
+use crate::utils::manifest::VersionBump;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportLevel {
+    Normal,
+    Warning,
+    Error,
+    /// Context rather than a finding
+    Muted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportRow {
+    pub text: String,
+    /// Shown indented under the row
+    pub detail: Option<String>,
+    pub level: ReportLevel,
+}
+
+impl ReportRow {
+    pub fn new(text: impl Into<String>, level: ReportLevel) -> Self {
+        Self {
+            text: text.into(),
+            detail: None,
+            level,
+        }
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportSection {
+    pub heading: String,
+    pub rows: Vec<ReportRow>,
+    /// Shown instead of the rows when there are none
+    pub empty: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub title: String,
+    /// One-line summary above the sections
+    pub summary: String,
+    pub sections: Vec<ReportSection>,
+}
+
+impl Report {
+    pub fn new(title: impl Into<String>, summary: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            summary: summary.into(),
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn section(
+        mut self,
+        heading: impl Into<String>,
+        rows: Vec<ReportRow>,
+        empty: &'static str,
+    ) -> Self {
+        self.sections.push(ReportSection {
+            heading: heading.into(),
+            rows,
+            empty,
+        });
+        self
+    }
+
+    /// Functions callable from C and the C-compatible types, each with the types
+    /// in its signature or fields that aren't FFI-safe
+    pub fn ffi_audit(surface: &FfiSurface) -> Self {
+        let functions: Vec<ReportRow> = surface
+            .functions
+            .iter()
+            .map(|f| {
+                let mut text = match f.abi {
+                    Some(ref abi) => format!("extern \"{}\" fn {}", abi, f.path),
+                    None => format!("fn {}", f.path),
+                };
+                if f.no_mangle {
+                    text.push_str("  #[no_mangle]");
+                }
+                if !f.is_public {
+                    text.push_str("  (private)");
+                }
+                if f.issues.is_empty() {
+                    ReportRow::new(text, ReportLevel::Normal)
+                } else {
+                    ReportRow::new(text, ReportLevel::Warning).detail(f.issues.join(" · "))
+                }
+            })
+            .collect();
+
+        let types: Vec<ReportRow> = surface
+            .types
+            .iter()
+            .map(|t| {
+                let text = format!(
+                    "{}  {}",
+                    t.path,
+                    t.repr.as_deref().unwrap_or("default Rust layout")
+                );
+                if t.issues.is_empty() {
+                    ReportRow::new(text, ReportLevel::Normal)
+                } else {
+                    ReportRow::new(text, ReportLevel::Error).detail(t.issues.join(" · "))
+                }
+            })
+            .collect();
+
+        Self::new(
+            "FFI",
+            format!(
+                "{} exported functions · {} types · {} with problems",
+                surface.functions.len(),
+                surface.types.len(),
+                surface.issue_count()
+            ),
+        )
+        .section(
+            "Functions",
+            functions,
+            "No extern \"C\" or #[no_mangle] functions",
+        )
+        .section("Types", types, "No repr(C) types")
+    }
+
+    /// How likely the project builds for `wasm32-unknown-unknown` and WASI, the
+    /// dependencies in the way (with the direct dependency pulling each in) and the
+    /// std modules that don't work there
+    pub fn wasm_scan(scan: &WasmScan, root: Option<&Path>) -> Self {
+        let impacts = |issue: &WasmIssue| -> (ReportLevel, String) {
+            let notes: Vec<String> = WasmTarget::ALL
+                .iter()
+                .filter_map(|&t| {
+                    let verb = match issue.impact(t)? {
+                        WasmImpact::Blocks => "blocks",
+                        WasmImpact::Caution => "caution",
+                    };
+                    Some(format!("{} {}", verb, t.label()))
+                })
+                .collect();
+            let level = if issue.unknown.max(issue.wasi) == Some(WasmImpact::Blocks) {
+                ReportLevel::Error
+            } else {
+                ReportLevel::Warning
+            };
+            (level, notes.join(" · "))
+        };
+
+        let outlook = WasmTarget::ALL
+            .iter()
+            .map(|&target| {
+                let outlook = scan.outlook(target);
+                let level = match outlook {
+                    Outlook::Likely => ReportLevel::Normal,
+                    Outlook::WithChanges => ReportLevel::Warning,
+                    Outlook::Unlikely => ReportLevel::Error,
+                };
+                let row = ReportRow::new(format!("{}: {}", target.label(), outlook.label()), level);
+                match scan.blocking_dependencies(target) {
+                    blocking if blocking.is_empty() => row,
+                    blocking => row.detail(format!("blocked by {}", blocking.join(", "))),
+                }
+            })
+            .collect();
+
+        let dependencies = scan
+            .dependencies
+            .iter()
+            .map(|issue| {
+                let (level, impact) = impacts(issue);
+                let mut text = format!("{}  {}", issue.subject, impact);
+                if let Some(ref via) = issue.via {
+                    text.push_str(&format!("  (via {})", via));
+                }
+                ReportRow::new(text, level).detail(issue.reason)
+            })
+            .collect();
+
+        let apis = scan
+            .apis
+            .iter()
+            .map(|issue| {
+                let (level, impact) = impacts(issue);
+                let places: Vec<String> = issue
+                    .locations
+                    .iter()
+                    .map(|(file, line)| {
+                        let file = root
+                            .and_then(|root| file.strip_prefix(root).ok())
+                            .unwrap_or(file);
+                        format!("{}:{}", file.display(), line)
+                    })
+                    .collect();
+                ReportRow::new(format!("{}  {}", issue.subject, impact), level).detail(format!(
+                    "{} — {}",
+                    issue.reason,
+                    places.join(", ")
+                ))
+            })
+            .collect();
+
+        Self::new(
+            "WASM",
+            format!(
+                "{} of {} dependencies flagged · {} std modules",
+                scan.dependencies.len(),
+                scan.checked,
+                scan.apis.len()
+            ),
+        )
+        .section("Outlook", outlook, "")
+        .section(
+            "Dependencies",
+            dependencies,
+            "No known WASM-incompatible crates",
+        )
+        .section("Std APIs", apis, "No imports of OS-only std modules")
+    }
+
+    /// Conditionally compiled items grouped by their `#[cfg(..)]`s, muted where they
+    /// don't exist for `target`
+    pub fn cfg_surface(items: &[AnalyzedItem], target: &CfgTarget, features: &str) -> Self {
+        let groups = group_by_cfg(items);
+        let compiled = items.iter().filter(|i| target.compiles(i)).count();
+        let mut report = Self::new(
+            "cfg",
+            format!(
+                "{} · {} · {} of {} items compiled · t: target · f: features",
+                target.triple,
+                features,
+                compiled,
+                items.len()
+            ),
+        );
+        let conditional = groups.iter().filter(|g| g.condition.is_some()).count();
+        if conditional == 0 {
+            return report.section("Conditions", Vec::new(), "No #[cfg(..)] items");
+        }
+        for group in &groups {
+            let Some(ref condition) = group.condition else {
+                let row = ReportRow::new(
+                    format!("{} items on every target", group.items.len()),
+                    ReportLevel::Muted,
+                );
+                report = report.section("Unconditional", vec![row], "");
+                continue;
+            };
+            let rows = group
+                .items
+                .iter()
+                .map(|&i| {
+                    let item = &items[i];
+                    let text = format!("{} {}", item.kind(), item.qualified_name());
+                    if target.compiles(item) {
+                        ReportRow::new(text, ReportLevel::Normal)
+                    } else {
+                        ReportRow::new(text, ReportLevel::Muted).detail("not compiled")
+                    }
+                })
+                .collect();
+            report = report.section(format!("cfg({})", condition), rows, "");
+        }
+        report
+    }
+
+    /// Functions ranked by clones and allocations, each with the lines of every
+    /// occurrence by kind
+    pub fn allocations(hotspots: &[Hotspot], root: Option<&Path>) -> Self {
+        let rows = hotspots
+            .iter()
+            .map(|h| {
+                let file = root
+                    .and_then(|root| h.file.strip_prefix(root).ok())
+                    .unwrap_or(&h.file);
+                let kinds: Vec<String> = h
+                    .by_kind()
+                    .into_iter()
+                    .map(|(kind, lines)| {
+                        let lines: Vec<String> = lines.iter().map(usize::to_string).collect();
+                        format!(
+                            "{} ×{} (lines {})",
+                            kind.label(),
+                            lines.len(),
+                            lines.join(", ")
+                        )
+                    })
+                    .collect();
+                let level = if h.allocations.len() >= 10 {
+                    ReportLevel::Warning
+                } else {
+                    ReportLevel::Normal
+                };
+                ReportRow::new(
+                    format!(
+                        "{}  {}  {}:{}",
+                        h.name,
+                        h.allocations.len(),
+                        file.display(),
+                        h.line
+                    ),
+                    level,
+                )
+                .detail(kinds.join(" · "))
+            })
+            .collect();
+        let total: usize = hotspots.iter().map(|h| h.allocations.len()).sum();
+        Self::new(
+            "Allocations",
+            format!(
+                "{} clones / allocations in {} functions · clone(), to_string(), to_owned(), Vec::new(), format!",
+                total,
+                hotspots.len()
+            ),
+        )
+        .section("Hotspots", rows, "No clones or allocations found")
+    }
+
+    /// One step of an item's history: the commit, how the definition changed from
+    /// the step before, and every step with the shown one marked
+    pub fn item_history(history: &ItemHistory, step: usize) -> Self {
+        let title = format!("History of {}", history.name);
+        let Some(current) = history.steps.get(step) else {
+            return Self::new(title, "No commits with this item").section(
+                "Timeline",
+                Vec::new(),
+                "The file isn't committed, or isn't in a git repository",
+            );
+        };
+        let commit = &current.commit;
+        let mut report = Self::new(
+            title,
+            format!(
+                "Step {} of {} · ←/→ older / newer",
+                step + 1,
+                history.steps.len()
+            ),
+        )
+        .section(
+            "Commit",
+            vec![
+                ReportRow::new(commit.subject.clone(), ReportLevel::Normal).detail(format!(
+                    "{} · {} · {} · {}",
+                    commit.short_hash(),
+                    commit.date,
+                    commit.author,
+                    current.kind.label()
+                )),
+            ],
+            "",
+        );
+        report = if current.kind == StepKind::Definition {
+            let rows = current
+                .changes
+                .iter()
+                .map(|line| match line.tag {
+                    DiffTag::Added => {
+                        ReportRow::new(format!("+ {}", line.text), ReportLevel::Normal)
+                    }
+                    DiffTag::Removed => {
+                        ReportRow::new(format!("- {}", line.text), ReportLevel::Error)
+                    }
+                    _ => ReportRow::new(format!("  {}", line.text), ReportLevel::Muted),
+                })
+                .collect();
+            report.section("Definition Changes", rows, "")
+        } else {
+            let rows = current
+                .definition
+                .lines()
+                .map(|line| ReportRow::new(line, ReportLevel::Normal))
+                .collect();
+            report.section("Definition", rows, "")
+        };
+        let timeline = history
+            .steps
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, s)| {
+                let (marker, level) = if i == step {
+                    ("▶", ReportLevel::Normal)
+                } else {
+                    (" ", ReportLevel::Muted)
+                };
+                ReportRow::new(
+                    format!(
+                        "{} {}  {}  {}",
+                        marker,
+                        s.commit.short_hash(),
+                        s.commit.date,
+                        s.commit.subject
+                    ),
+                    level,
+                )
+            })
+            .collect();
+        report.section("Timeline", timeline, "")
+    }
+
+    /// Functions and methods that can panic, the public API apart, most panic
+    /// points first; `filter` keeps one kind
+    pub fn panics(items: &[AnalyzedItem], filter: Option<PanicKind>, root: Option<&Path>) -> Self {
+        let found = panicking_functions(items, filter);
+        let row = |f: &PanickingFn| {
+            let points = &f.function.panics;
+            let count = filter.map_or(points.total(), |k| points.count(k));
+            let mut detail = points.summary();
+            let loc = &f.function.source_location;
+            if let (Some(file), Some(line)) = (&loc.file, loc.line) {
+                let file = root
+                    .and_then(|root| file.strip_prefix(root).ok())
+                    .unwrap_or(file);
+                detail.push_str(&format!(" · {}:{}", file.display(), line));
+            }
+            let level = if f.public {
+                ReportLevel::Warning
+            } else {
+                ReportLevel::Muted
+            };
+            ReportRow::new(format!("{}  {}", f.name, count), level).detail(detail)
+        };
+        let public: Vec<ReportRow> = found.iter().filter(|f| f.public).map(row).collect();
+        let private: Vec<ReportRow> = found.iter().filter(|f| !f.public).map(row).collect();
+        let total: usize = found
+            .iter()
+            .map(|f| filter.map_or(f.function.panics.total(), |k| f.function.panics.count(k)))
+            .sum();
+        Self::new(
+            "Panics",
+            format!(
+                "{} panic points in {} functions ({} public) · showing {} · t: filter",
+                total,
+                found.len(),
+                public.len(),
+                filter.map_or("all", PanicKind::label)
+            ),
+        )
+        .section("Public API", public, "No panic points in public functions")
+        .section("Private", private, "No panic points in private functions")
+    }
+
+    /// Environment variables the project reads, with where; those `env!` needs at
+    /// build time and those set by cargo apart from runtime configuration
+    pub fn env_vars(vars: &[EnvVar], root: Option<&Path>) -> Self {
+        let row = |var: &EnvVar, level: ReportLevel| {
+            let text = if var.literal {
+                var.name.clone()
+            } else {
+                format!("{}  (computed name)", var.name)
+            };
+            let places: Vec<String> = var
+                .reads
+                .iter()
+                .map(|r| {
+                    let file = root
+                        .and_then(|root| r.file.strip_prefix(root).ok())
+                        .unwrap_or(&r.file);
+                    format!("{} {}:{}", r.kind.label(), file.display(), r.line)
+                })
+                .collect();
+            ReportRow::new(text, level).detail(places.join(", "))
+        };
+        let (cargo, vars): (Vec<&EnvVar>, Vec<&EnvVar>) =
+            vars.iter().partition(|v| v.set_by_cargo());
+        let build: Vec<ReportRow> = vars
+            .iter()
+            .filter(|v| v.reads.iter().all(|r| r.kind != EnvReadKind::Runtime))
+            .map(|v| {
+                let level = if v.required_at_build() {
+                    ReportLevel::Warning
+                } else {
+                    ReportLevel::Normal
+                };
+                row(v, level)
+            })
+            .collect();
+        let runtime: Vec<ReportRow> = vars
+            .iter()
+            .filter(|v| v.read_at_runtime())
+            .map(|v| row(v, ReportLevel::Normal))
+            .collect();
+        let cargo: Vec<ReportRow> = cargo.iter().map(|v| row(v, ReportLevel::Muted)).collect();
+        Self::new(
+            "Environment",
+            format!(
+                "{} runtime · {} build-time · {} set by cargo · x: export Markdown",
+                runtime.len(),
+                build.len(),
+                cargo.len()
+            ),
+        )
+        .section("Runtime", runtime, "No env::var reads")
+        .section("Build Time", build, "No env! / option_env! reads")
+        .section("Set by Cargo", cargo, "No CARGO_* variables")
+    }
+
+    /// The crate's `no_std` attribute, the dependencies that need std (or have it
+    /// switched on) and the project's imports from std, with `core` / `alloc`
+    /// replacements where there are some
+    pub fn no_std(report: &NoStdReport, root: Option<&Path>) -> Self {
+        let claimed = report.attr.is_some();
+        let crate_row = match report.attr {
+            Some(ref attr) => ReportRow::new(attr.label(), ReportLevel::Normal),
+            None => ReportRow::new("No #![no_std]: the crate links std", ReportLevel::Muted),
+        };
+
+        let dependencies = report
+            .dependencies
+            .iter()
+            .filter(|d| d.support != NoStdSupport::Always || d.std_enabled)
+            .map(|d| {
+                let mut text = format!("{} {}", d.name, d.version);
+                if d.direct != d.name {
+                    text.push_str(&format!("  (via {})", d.direct));
+                }
+                let (level, detail) = match (&d.support, d.std_enabled) {
+                    (NoStdSupport::RequiresStd, _) => (ReportLevel::Error, "requires std"),
+                    (_, true) => (
+                        ReportLevel::Warning,
+                        "supports no_std, but its `std` feature is on",
+                    ),
+                    _ => (ReportLevel::Muted, "supports no_std with `std` off"),
+                };
+                let level = if claimed { level } else { ReportLevel::Muted };
+                ReportRow::new(text, level).detail(detail)
+            })
+            .collect();
+
+        let imports = report
+            .std_imports
+            .iter()
+            .map(|i| {
+                let file = root
+                    .and_then(|root| i.file.strip_prefix(root).ok())
+                    .unwrap_or(&i.file);
+                let text = format!("{}  {}:{}", i.path, file.display(), i.line);
+                match i.replacement {
+                    Some(ref path) => {
+                        ReportRow::new(text, ReportLevel::Warning).detail(format!("use {}", path))
+                    }
+                    None if claimed => ReportRow::new(text, ReportLevel::Error).detail("std only"),
+                    None => ReportRow::new(text, ReportLevel::Muted).detail("std only"),
+                }
+            })
+            .collect();
+
+        let needing = report.needing_std().count();
+        Self::new(
+            "no_std",
+            format!(
+                "{} · {} of {} dependencies need std · {} std-only imports",
+                report
+                    .attr
+                    .as_ref()
+                    .map_or("std".to_string(), NoStdAttr::label),
+                needing,
+                report.dependencies.len(),
+                report.std_only().count()
+            ),
+        )
+        .section("Crate", vec![crate_row], "")
+        .section(
+            "Dependencies",
+            dependencies,
+            "Every dependency is #![no_std]",
+        )
+        .section("Std Imports", imports, "No use std::.. declarations")
+    }
+
+    /// Async fns per module, public ones with a likely `!Send` future, and public
+    /// types likely missing `Send` / `Sync`
+    pub fn async_audit(audit: &AsyncAudit) -> Self {
+        let flagged: Vec<ReportRow> = audit
+            .flagged()
+            .map(|f| {
+                ReportRow::new(format!("{}()", f.name), ReportLevel::Warning).detail(format!(
+                    "future is likely !Send: holds {}",
+                    f.not_send.as_deref().unwrap_or_default()
+                ))
+            })
+            .collect();
+
+        let types: Vec<ReportRow> = audit
+            .types
+            .iter()
+            .map(|t| {
+                let missing = match (t.safety.is_send(), t.safety.is_sync()) {
+                    (false, false) => "!Send + !Sync",
+                    (false, true) => "!Send",
+                    _ => "!Sync",
+                };
+                let reasons: Vec<String> = [
+                    t.safety.not_send.as_ref().map(|r| format!("!Send: {}", r)),
+                    t.safety.not_sync.as_ref().map(|r| format!("!Sync: {}", r)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                ReportRow::new(format!("{}  {}", t.name, missing), ReportLevel::Normal)
+                    .detail(reasons.join(" · "))
+            })
+            .collect();
+
+        let mut by_module = Vec::new();
+        for (module, fns) in &audit.async_fns {
+            by_module.push(ReportRow::new(
+                format!("{} ({})", module, fns.len()),
+                ReportLevel::Muted,
+            ));
+            for f in fns {
+                let vis = if f.is_public { "pub " } else { "" };
+                let marker = if f.not_send.is_some() {
+                    "  ⚠ !Send"
+                } else {
+                    ""
+                };
+                let level = if f.not_send.is_some() && f.is_public {
+                    ReportLevel::Warning
+                } else {
+                    ReportLevel::Normal
+                };
+                by_module.push(ReportRow::new(
+                    format!("  {}async fn {}{}", vis, f.name, marker),
+                    level,
+                ));
+            }
+        }
+
+        Self::new(
+            "Async / Send audit",
+            format!(
+                "{} async fns · {} likely !Send futures in the public API · {} public types likely !Send or !Sync",
+                audit.async_fn_count(),
+                flagged.len(),
+                types.len()
+            ),
+        )
+        .section(
+            "Public async fns with !Send futures",
+            flagged,
+            "None found",
+        )
+        .section("Public types likely !Send / !Sync", types, "None found")
+        .section("Async fns by module", by_module, "No async fns")
+    }
+
+    /// Error types defined in the crate and the functions returning each, then
+    /// errors from other crates that show up in `Result`s
+    pub fn error_catalog(catalog: &ErrorCatalog) -> Self {
+        let returned_by = |fns: &[&str]| {
+            if fns.is_empty() {
+                "not returned by any function".to_string()
+            } else {
+                format!("returned by {}", fns.join(", "))
+            }
+        };
+
+        let local: Vec<ReportRow> = catalog
+            .error_types
+            .iter()
+            .map(|t| {
+                let mut text = t.path.clone();
+                match t.variants {
+                    Some(n) => text.push_str(&format!("  enum · {} variants", n)),
+                    None => text.push_str("  struct"),
+                }
+                if t.thiserror {
+                    text.push_str(" · thiserror");
+                }
+                let fns = catalog.functions_returning(&t.name);
+                let level = if fns.is_empty() {
+                    ReportLevel::Muted
+                } else {
+                    ReportLevel::Normal
+                };
+                ReportRow::new(text, level).detail(returned_by(&fns))
+            })
+            .collect();
+
+        let external: Vec<ReportRow> = catalog
+            .external_errors()
+            .map(|(error, fns)| {
+                let fns: Vec<&str> = fns.iter().map(String::as_str).collect();
+                ReportRow::new(error.clone(), ReportLevel::Normal).detail(returned_by(&fns))
+            })
+            .collect();
+
+        Self::new(
+            "Errors",
+            format!(
+                "{} error types · {} fallible functions",
+                catalog.error_types.len(),
+                catalog.fallible_fn_count()
+            ),
+        )
+        .section("Defined here", local, "No error types found")
+        .section("From other crates", external, "None")
+    }
+
+    /// Dependencies marked for upgrade as `(name, installed, latest)`, the requirement
+    /// changes they need and the `Cargo.toml` lines that would change
+    pub fn upgrade_plan(
+        marked: &[(String, String, String)],
+        bumps: &[VersionBump],
+        diff: &[(usize, &str, &str)],
+        can_undo: bool,
+    ) -> Self {
+        let upgrades = marked
+            .iter()
+            .map(|(name, installed, latest)| {
+                let changes: Vec<String> = bumps
+                    .iter()
+                    .filter(|b| b.name == *name)
+                    .map(|b| format!("\"{}\" → \"{}\" in [{}]", b.from, b.to, b.table))
+                    .collect();
+                let row = ReportRow::new(
+                    format!("{} {} → {}", name, installed, latest),
+                    ReportLevel::Normal,
+                );
+                if changes.is_empty() {
+                    row.detail("already allowed by Cargo.toml; only Cargo.lock changes")
+                } else {
+                    row.detail(changes.join(" · "))
+                }
+            })
+            .collect();
+        let lines = diff
+            .iter()
+            .flat_map(|(line, old, new)| {
+                [
+                    ReportRow::new(format!("-{:>4}  {}", line, old), ReportLevel::Error),
+                    ReportRow::new(format!("+{:>4}  {}", line, new), ReportLevel::Normal),
+                ]
+            })
+            .collect();
+        let mut keys =
+            String::from("u: mark in the Crates list · a: apply · c: apply and cargo update");
+        if can_undo {
+            keys.push_str(" · z: undo last apply");
+        }
+        Self::new(
+            "Upgrade plan",
+            format!("{} marked · {}", marked.len(), keys),
+        )
+        .section("Marked dependencies", upgrades, "None marked")
+        .section("Cargo.toml", lines, "No changes")
+    }
+
+    /// Tagged comments grouped by tag, each with its location and enclosing item;
+    /// `filter` limits the view to one tag. Paths are shown relative to `root`.
+    pub fn notes(notes: &[&Note], filter: Option<NoteTag>, root: Option<&Path>) -> Self {
+        let counts: Vec<String> = NoteTag::ALL
+            .iter()
+            .map(|&tag| {
+                let n = notes.iter().filter(|n| n.tag == tag).count();
+                format!("{} {}", n, tag.label())
+            })
+            .collect();
+        let mut report = Self::new(
+            "Notes",
+            format!(
+                "{} · showing {} · t: filter by tag · x: export JSON",
+                counts.join(" · "),
+                filter.map_or("all", NoteTag::label)
+            ),
+        );
+        for tag in NoteTag::ALL {
+            if filter.is_some_and(|f| f != tag) {
+                continue;
+            }
+            let level = match tag {
+                NoteTag::Fixme | NoteTag::Hack => ReportLevel::Warning,
+                NoteTag::Todo => ReportLevel::Normal,
+                NoteTag::Safety => ReportLevel::Muted,
+            };
+            let rows = notes
+                .iter()
+                .filter(|n| n.tag == tag)
+                .map(|n| {
+                    let file = root
+                        .and_then(|root| n.file.strip_prefix(root).ok())
+                        .unwrap_or(&n.file);
+                    let mut text = format!("{}:{}", file.display(), n.line);
+                    if let Some(ref item) = n.item {
+                        text.push_str(&format!("  in {}", item));
+                    }
+                    ReportRow::new(text, level).detail(n.text.clone())
+                })
+                .collect();
+            report = report.section(tag.label(), rows, "None");
+        }
+        report
+    }
+
+    /// Review notes on items, in the order they were written
+    pub fn review(notes: &ReviewNotes) -> Self {
+        let rows = notes
+            .notes
+            .iter()
+            .map(|n| {
+                let mut text = format!("{} {}", n.kind, n.item);
+                if let Some(ref location) = n.location {
+                    text.push_str(&format!("  {}", location));
+                }
+                ReportRow::new(text, ReportLevel::Normal).detail(n.note.clone())
+            })
+            .collect();
+        Self::new(
+            "Review",
+            format!(
+                "{} notes in {} · n on an item writes one · x: export Markdown",
+                notes.notes.len(),
+                ReviewNotes::FILE_NAME
+            ),
+        )
+        .section("Notes", rows, "No review notes yet")
+    }
+
+    /// An item's resolved type, definition and references as `path:line:column`,
+    /// each reference with the item it's in. `fallback` says why oracle's own index
+    /// answered instead of rust-analyzer.
+    pub fn semantics(
+        name: &str,
+        hover: Option<&str>,
+        definitions: Vec<String>,
+        references: Vec<(String, Option<String>)>,
+        fallback: Option<&str>,
+    ) -> Self {
+        let source = match fallback {
+            None => "from rust-analyzer".to_string(),
+            Some(reason) => format!("from oracle's index, matched by name ({})", reason),
+        };
+        let type_rows = hover
+            .map(|h| {
+                h.lines()
+                    .map(|line| ReportRow::new(line, ReportLevel::Normal))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let definition_rows = definitions
+            .into_iter()
+            .map(|place| ReportRow::new(place, ReportLevel::Normal))
+            .collect();
+        let count = references.len();
+        let reference_rows = references
+            .into_iter()
+            .map(|(place, owner)| {
+                let row = ReportRow::new(place, ReportLevel::Normal);
+                match owner {
+                    Some(owner) => row.detail(format!("in {}", owner)),
+                    None => row,
+                }
+            })
+            .collect();
+        Self::new(
+            format!("Semantics of {}", name),
+            format!("{} references · {}", count, source),
+        )
+        .section("Type", type_rows, "No type information")
+        .section("Definition", definition_rows, "No definition found")
+        .section("References", reference_rows, "No references")
+    }
+
+    /// Import cycles between modules, then each module with the modules it imports
+    /// from and the ones importing it
+    pub fn import_graph(graph: &ImportGraph) -> Self {
+        let cycles = graph.cycles();
+        let in_cycle = |module: &str| cycles.iter().any(|c| c.contains(&module));
+        let cycle_rows = cycles
+            .iter()
+            .map(|cycle| {
+                ReportRow::new(cycle.join(" → "), ReportLevel::Warning)
+                    .detail("these modules import each other; move the shared part out")
+            })
+            .collect();
+        let module_rows = graph
+            .edges
+            .iter()
+            .map(|(module, targets)| {
+                let level = if in_cycle(module) {
+                    ReportLevel::Warning
+                } else if targets.is_empty() {
+                    ReportLevel::Muted
+                } else {
+                    ReportLevel::Normal
+                };
+                let text = if targets.is_empty() {
+                    module.clone()
+                } else {
+                    let targets: Vec<&str> = targets.iter().map(String::as_str).collect();
+                    format!("{} → {}", module, targets.join(", "))
+                };
+                let importers = graph.importers(module);
+                let detail = if importers.is_empty() {
+                    "not imported by other modules".to_string()
+                } else {
+                    format!("imported by {}", importers.join(", "))
+                };
+                ReportRow::new(text, level).detail(detail)
+            })
+            .collect();
+        Self::new(
+            "Module imports",
+            format!(
+                "{} modules · {} imports between them · {} cycles",
+                graph.edges.len(),
+                graph.edge_count(),
+                cycles.len()
+            ),
+        )
+        .section("Cycles", cycle_rows, "No import cycles")
+        .section("Modules", module_rows, "No modules")
+    }
+
+    /// Items never referenced elsewhere in the project, most likely dead first.
+    /// Without `include_private` the analysis only saw `pub` items.
+    pub fn unused_items(unused: &[UnusedItem], root: Option<&Path>, include_private: bool) -> Self {
+        let mut summary = format!("{} possibly unused", unused.len());
+        if !include_private {
+            summary.push_str(" · private items are hidden (enable them in settings)");
+        }
+        let mut report = Self::new("Possibly unused", summary);
+        for (confidence, heading, level) in [
+            (
+                Confidence::High,
+                "High confidence: not pub",
+                ReportLevel::Warning,
+            ),
+            (
+                Confidence::Medium,
+                "Medium confidence: pub in a binary",
+                ReportLevel::Normal,
+            ),
+            (
+                Confidence::Low,
+                "Low confidence: public API of a library",
+                ReportLevel::Muted,
+            ),
+        ] {
+            let rows = unused
+                .iter()
+                .filter(|u| u.confidence == confidence)
+                .map(|u| {
+                    let row = ReportRow::new(format!("{} {}", u.kind, u.path), level);
+                    match (&u.location.file, u.location.line) {
+                        (Some(file), Some(line)) => {
+                            let file = root
+                                .and_then(|root| file.strip_prefix(root).ok())
+                                .unwrap_or(file);
+                            row.detail(format!("{}:{}", file.display(), line))
+                        }
+                        _ => row,
+                    }
+                })
+                .collect();
+            report = report.section(heading, rows, "None");
+        }
+        report
+    }
+
+    /// Project items implementing or referring to `path`, an item of an installed
+    /// crate; `usages` are matched by its last segment
+    pub fn where_used(path: &str, usages: &[TypeUsage], root: Option<&Path>) -> Self {
+        let row = |usage: &TypeUsage| {
+            let row = ReportRow::new(
+                format!("{:<8} {}", usage.kind.to_string(), usage.owner),
+                ReportLevel::Normal,
+            );
+            match (&usage.location.file, usage.location.line) {
+                (Some(file), Some(line)) => {
+                    let file = root
+                        .and_then(|root| file.strip_prefix(root).ok())
+                        .unwrap_or(file);
+                    row.detail(format!("{} · {}:{}", usage.detail, file.display(), line))
+                }
+                _ => row.detail(usage.detail.clone()),
+            }
+        };
+        let (implementing, referring): (Vec<&TypeUsage>, Vec<&TypeUsage>) = usages
+            .iter()
+            .partition(|u| matches!(u.kind, UsageKind::Impl | UsageKind::Derive));
+        Self::new(
+            format!("Where {} is used", path),
+            format!("{} uses in this project · matched by name", usages.len()),
+        )
+        .section(
+            "Implemented or derived",
+            implementing.into_iter().map(row).collect(),
+            "No impls or derives",
+        )
+        .section(
+            "Fields, parameters and return types",
+            referring.into_iter().map(row).collect(),
+            "No references",
+        )
+    }
+
+    /// Broken intra-doc and web links in the docs, then web links not checked yet.
+    /// `web_state` says why some are unchecked, e.g. `checking…` or `offline`.
+    pub fn doc_links(links: &[DocLink], root: Option<&Path>, web_state: Option<&str>) -> Self {
+        let broken = links.iter().filter(|l| l.is_broken()).count();
+        let web = links.iter().filter(|l| l.is_url).count();
+        let mut summary = format!(
+            "{} links in docs · {} broken · {} web links",
+            links.len(),
+            broken,
+            web
+        );
+        if let Some(state) = web_state {
+            summary.push_str(&format!(" ({})", state));
+        }
+        let row = |link: &DocLink, level: ReportLevel| {
+            let text = format!("{}  → {}", link.owner, link.target);
+            let file = link.location.file.as_ref().map(|file| {
+                root.and_then(|root| file.strip_prefix(root).ok())
+                    .unwrap_or(file)
+                    .display()
+                    .to_string()
+            });
+            let place = match (file, link.location.line) {
+                (Some(file), Some(line)) => format!("{}:{}", file, line),
+                (Some(file), None) => file,
+                _ => String::new(),
+            };
+            let detail = match &link.status {
+                LinkStatus::Broken(reason) if place.is_empty() => reason.clone(),
+                LinkStatus::Broken(reason) => format!("{} · {}", reason, place),
+                _ => place,
+            };
+            ReportRow::new(text, level).detail(detail)
+        };
+        let rows = |is_url: bool| {
+            links
+                .iter()
+                .filter(|l| l.is_url == is_url && l.is_broken())
+                .map(|l| row(l, ReportLevel::Error))
+                .collect()
+        };
+        let unchecked = links
+            .iter()
+            .filter(|l| l.status == LinkStatus::Unchecked)
+            .map(|l| row(l, ReportLevel::Muted))
+            .collect();
+        Self::new("Doc links", summary)
+            .section("Broken intra-doc links", rows(false), "None")
+            .section("Broken web links", rows(true), "None")
+            .section("Web links not checked", unchecked, "None")
+    }
+
+    /// Recent `tracing` output, newest first
+    pub fn debug_log(lines: &[LogLine], file: Option<&Path>) -> Self {
+        let rows = lines
+            .iter()
+            .rev()
+            .map(|line| {
+                let level = match line.level {
+                    tracing::Level::ERROR => ReportLevel::Error,
+                    tracing::Level::WARN => ReportLevel::Warning,
+                    tracing::Level::INFO => ReportLevel::Normal,
+                    _ => ReportLevel::Muted,
+                };
+                ReportRow::new(
+                    format!("{:<5}  {}: {}", line.level, line.target, line.message),
+                    level,
+                )
+            })
+            .collect();
+        let file = file.map_or_else(
+            || "not written to a file".to_string(),
+            |f| format!("full log in {}", f.display()),
+        );
+        Self::new(
+            "Debug log",
+            format!(
+                "{} recent lines · {} · --verbose logs debug output",
+                lines.len(),
+                file
+            ),
+        )
+        .section("Log", rows, "Nothing logged yet")
+    }
+
+    /// Open issues and recent pull requests of a dependency's repository, with labels,
+    /// authors and ages relative to `now` (Unix seconds)
+    pub fn github_activity(crate_name: &str, activity: &GitHubActivity, now: u64) -> Self {
+        let row = |issue: &GitHubIssue| {
+            let level = match issue.state.as_str() {
+                "open" => ReportLevel::Normal,
+                "merged" => ReportLevel::Muted,
+                _ => ReportLevel::Warning,
+            };
+            let text = if issue.is_pull_request {
+                format!("#{} [{}] {}", issue.number, issue.state, issue.title)
+            } else {
+                format!("#{} {}", issue.number, issue.title)
+            };
+            let mut detail = Vec::new();
+            if let Some(days) = issue
+                .created_at
+                .as_deref()
+                .and_then(|c| age_in_days(c, now))
+            {
+                detail.push(format_age(days));
+            }
+            if let Some(ref author) = issue.author {
+                detail.push(format!("@{}", author));
+            }
+            if !issue.labels.is_empty() {
+                detail.push(issue.labels.join(", "));
+            }
+            ReportRow::new(text, level).detail(detail.join(" · "))
+        };
+        Self::new(
+            format!("{} on GitHub", crate_name),
+            format!(
+                "{} · {} open issues · {} pull requests, recently updated first",
+                activity.repo,
+                activity.issues.len(),
+                activity.pulls.len()
+            ),
+        )
+        .section(
+            "Open issues",
+            activity.issues.iter().map(row).collect(),
+            "No open issues",
+        )
+        .section(
+            "Pull requests",
+            activity.pulls.iter().map(row).collect(),
+            "No pull requests",
+        )
+    }
+}
+
+/// `3d old`, `5mo old`, `2y old`
+fn format_age(days: u64) -> String {
+    match days {
+        0 => "today".to_string(),
+        1..=59 => format!("{}d old", days),
+        60..=729 => format!("{}mo old", days / 30),
+        _ => format!("{}y old", days / 365),
+    }
+}
@@ -6,17 +6,29 @@ use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Color, Modifier, Style},
-    text::{Line, Span},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Widget},
 };
 
+use std::collections::BTreeMap;
+
+use crate::analyzer::AnalyzedItem;
 use crate::ui::theme::Theme;
+use crate::utils::truncate;
+
+/// Candidates shown at once in the dropdown; the rest scroll
+pub const COMPLETION_VISIBLE: usize = 6;
+/// Rows per candidate: name and module path, then the doc snippet
+pub const COMPLETION_ROWS: u16 = 2;
 
 /// A completion candidate
 #[derive(Debug, Clone)]
 pub struct CompletionCandidate {
     pub primary: String,
+    /// Doc summary or hint, shown dimmed on the second row
     pub secondary: Option<String>,
+    /// Module path of an item, e.g. `analyzer::query`
+    pub path: Option<String>,
     pub kind: CandidateKind,
     pub score: i64,
 }
@@ -31,10 +43,44 @@ pub enum CandidateKind {
     Type,
     Const,
     Crate,
+    Filter,
+    /// A query submitted earlier in this project
+    History,
     Other,
 }
 
 impl CandidateKind {
+    pub fn of(item: &AnalyzedItem) -> Self {
+        match item {
+            AnalyzedItem::Function(_) => CandidateKind::Function,
+            AnalyzedItem::Struct(_) => CandidateKind::Struct,
+            AnalyzedItem::Enum(_) => CandidateKind::Enum,
+            AnalyzedItem::Trait(_) => CandidateKind::Trait,
+            AnalyzedItem::Module(_) => CandidateKind::Module,
+            AnalyzedItem::TypeAlias(_) => CandidateKind::Type,
+            AnalyzedItem::Const(_) | AnalyzedItem::Static(_) => CandidateKind::Const,
+            AnalyzedItem::Impl(_) => CandidateKind::Other,
+        }
+    }
+
+    /// Icon in the dropdown, with a two-letter fallback in compat mode
+    pub fn glyph(&self, theme: &Theme) -> &'static str {
+        let fancy = match self {
+            CandidateKind::Function => "🔧",
+            CandidateKind::Struct => "📦",
+            CandidateKind::Enum => "🏷",
+            CandidateKind::Trait => "📜",
+            CandidateKind::Module => "📁",
+            CandidateKind::Type => "🔗",
+            CandidateKind::Const => "📌",
+            CandidateKind::Crate => "📚",
+            CandidateKind::Filter => "🔎",
+            CandidateKind::History => "🕘",
+            CandidateKind::Other => "  ",
+        };
+        theme.glyph(fancy, self.icon())
+    }
+
     pub fn icon(&self) -> &'static str {
         match self {
             CandidateKind::Function => "fn",
@@ -45,6 +91,8 @@ impl CandidateKind {
             CandidateKind::Type => "ty",
             CandidateKind::Const => "ct",
             CandidateKind::Crate => "cr",
+            CandidateKind::Filter => "::",
+            CandidateKind::History => "<-",
             CandidateKind::Other => "  ",
         }
     }
@@ -56,6 +104,8 @@ impl CandidateKind {
             CandidateKind::Trait => theme.keyword,
             CandidateKind::Module | CandidateKind::Crate => theme.accent,
             CandidateKind::Const => theme.number,
+            CandidateKind::Filter => theme.string,
+            CandidateKind::History => theme.fg_dim,
             CandidateKind::Other => theme.fg_dim,
         }
     }
@@ -115,7 +165,7 @@ impl Widget for SearchBar<'_> {
         block.render(area, buf);
 
         // Render prompt
-        let prompt = Span::styled("❯ ", self.theme.style_accent_bold());
+        let prompt = Span::styled(self.theme.glyph("❯ ", "> "), self.theme.style_accent_bold());
 
         let (input_text, input_style) = if self.input.is_empty() {
             (self.placeholder, self.theme.style_dim())
@@ -149,6 +199,7 @@ impl Widget for SearchBar<'_> {
 pub struct SearchCompletion<'a> {
     candidates: &'a [CompletionCandidate],
     selected: usize,
+    offset: usize,
     filter: &'a str,
     theme: &'a Theme,
     max_visible: usize,
@@ -159,9 +210,10 @@ impl<'a> SearchCompletion<'a> {
         Self {
             candidates,
             selected: 0,
+            offset: 0,
             filter: "",
             theme,
-            max_visible: 10,
+            max_visible: COMPLETION_VISIBLE,
         }
     }
 
@@ -170,6 +222,12 @@ impl<'a> SearchCompletion<'a> {
         self
     }
 
+    /// First candidate shown; moved if the selection would be off screen
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
     pub fn filter(mut self, filter: &'a str) -> Self {
         self.filter = filter;
         self
@@ -179,6 +237,42 @@ impl<'a> SearchCompletion<'a> {
         self.max_visible = max;
         self
     }
+
+    fn rows<'b>(&self, candidate: &'b CompletionCandidate, width: usize) -> Text<'b> {
+        let kind_span = Span::styled(
+            format!("{} ", candidate.kind.glyph(self.theme)),
+            Style::default().fg(candidate.kind.color(self.theme)),
+        );
+        let mut first = vec![kind_span];
+        first.extend(highlight_fuzzy(&candidate.primary, self.filter, self.theme));
+        if let Some(ref path) = candidate.path {
+            let room = width.saturating_sub(candidate.primary.len() + 4);
+            if room > 3 {
+                first.push(Span::styled(
+                    format!("  {}", truncate(path, room)),
+                    self.theme.style_dim(),
+                ));
+            }
+        }
+        let snippet = candidate.secondary.as_deref().unwrap_or("");
+        let second = Line::from(Span::styled(
+            format!("   {}", truncate(snippet, width.saturating_sub(3))),
+            self.theme.style_muted(),
+        ));
+        Text::from(vec![Line::from(first), second])
+    }
+}
+
+/// Scroll offset that keeps `selected` among `visible` candidates, moving
+/// `offset` as little as possible
+pub fn completion_offset(offset: usize, selected: usize, visible: usize) -> usize {
+    if selected < offset {
+        selected
+    } else if selected >= offset + visible {
+        selected + 1 - visible
+    } else {
+        offset
+    }
 }
 
 impl Widget for SearchCompletion<'_> {
@@ -190,53 +284,65 @@ impl Widget for SearchCompletion<'_> {
         // Clear the area first
         Clear.render(area, buf);
 
-        let visible_count = self.candidates.len().min(self.max_visible);
+        let visible = self.candidates.len().min(self.max_visible).max(1);
+        let offset = completion_offset(self.offset, self.selected, visible);
+        let width = area.width.saturating_sub(2) as usize;
 
         let items: Vec<ListItem> = self
             .candidates
             .iter()
-            .take(visible_count)
             .enumerate()
+            .skip(offset)
+            .take(visible)
             .map(|(i, candidate)| {
-                let kind_span = Span::styled(
-                    format!("{} ", candidate.kind.icon()),
-                    Style::default().fg(candidate.kind.color(self.theme)),
-                );
-
-                let name = highlight_fuzzy(&candidate.primary, self.filter, self.theme);
-
-                let secondary = candidate
-                    .secondary
-                    .as_ref()
-                    .map(|s| Span::styled(format!(" {}", s), self.theme.style_muted()))
-                    .unwrap_or_else(|| Span::raw(""));
-
-                let mut spans = vec![kind_span];
-                spans.extend(name);
-                spans.push(secondary);
-
-                let line = Line::from(spans);
                 let style = if i == self.selected {
                     self.theme.style_selected()
                 } else {
                     Style::default()
                 };
-
-                ListItem::new(line).style(style)
+                ListItem::new(self.rows(candidate, width)).style(style)
             })
             .collect();
 
-        let list = List::new(items).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(self.theme.style_border_focused())
-                .style(Style::default().bg(self.theme.bg_panel)),
-        );
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.style_border_focused())
+            .style(Style::default().bg(self.theme.bg_panel));
+        if self.candidates.len() > visible {
+            block = block.title_bottom(
+                Line::from(Span::styled(
+                    format!(" {}/{} ", self.selected + 1, self.candidates.len()),
+                    self.theme.style_dim(),
+                ))
+                .right_aligned(),
+            );
+        }
 
-        list.render(area, buf);
+        List::new(items).block(block).render(area, buf);
     }
 }
 
+/// Index of the candidate drawn at (`column`, `row`) of a dropdown at `area`
+/// scrolled to `offset`, for mouse selection
+pub fn completion_at(
+    area: Rect,
+    column: u16,
+    row: u16,
+    offset: usize,
+    len: usize,
+) -> Option<usize> {
+    let inner_top = area.y + 1;
+    if column <= area.x
+        || column + 1 >= area.x + area.width
+        || row < inner_top
+        || row + 1 >= area.y + area.height
+    {
+        return None;
+    }
+    let index = offset + ((row - inner_top) / COMPLETION_ROWS) as usize;
+    (index < len).then_some(index)
+}
+
 /// Highlight matching characters in fuzzy search
 fn highlight_fuzzy<'a>(text: &'a str, query: &str, theme: &Theme) -> Vec<Span<'a>> {
     if query.is_empty() {
@@ -272,6 +378,79 @@ fn highlight_fuzzy<'a>(text: &'a str, query: &str, theme: &Theme) -> Vec<Span<'a
     }
 }
 
+/// Completions for a path into another crate: `tokio::sync::Mu` offers the modules
+/// and items directly under `tokio::sync` whose name fuzzy-matches `Mu`. `items`
+/// are the crate's own, with module paths starting at the crate name. Modules end
+/// in `::` so picking one continues the path.
+pub fn path_candidates(items: &[AnalyzedItem], typed: &str) -> Vec<CompletionCandidate> {
+    let Some((parent, partial)) = typed.rsplit_once("::") else {
+        return Vec::new();
+    };
+    let parent: Vec<&str> = parent.split("::").collect();
+    let under_parent = |path: &[String]| {
+        path.len() >= parent.len() && path.iter().zip(&parent).all(|(a, b)| a == b)
+    };
+    // Child modules, from `mod` items and from the paths of items inside them
+    let mut modules: BTreeMap<&str, Option<&str>> = BTreeMap::new();
+    let mut candidates = Vec::new();
+    for item in items {
+        let path = item.module_path();
+        if !under_parent(path) {
+            continue;
+        }
+        if path.len() > parent.len() {
+            modules.entry(&path[parent.len()]).or_default();
+            continue;
+        }
+        match item {
+            AnalyzedItem::Impl(_) => {}
+            AnalyzedItem::Module(_) => {
+                let doc = modules.entry(item.name()).or_default();
+                *doc = doc.or(item.documentation());
+            }
+            _ => candidates.push(CompletionCandidate {
+                primary: format!("{}::{}", parent.join("::"), item.name()),
+                secondary: item.documentation().map(first_doc_line),
+                path: None,
+                kind: CandidateKind::of(item),
+                score: 0,
+            }),
+        }
+    }
+    candidates.extend(modules.into_iter().map(|(name, doc)| CompletionCandidate {
+        primary: format!("{}::{}::", parent.join("::"), name),
+        secondary: doc.map(first_doc_line),
+        path: None,
+        kind: CandidateKind::Module,
+        score: 0,
+    }));
+
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<CompletionCandidate> = candidates
+        .into_iter()
+        .filter_map(|mut c| {
+            let name = c.primary.trim_end_matches("::").rsplit("::").next()?;
+            c.score = if partial.is_empty() {
+                0
+            } else {
+                matcher.fuzzy_match(name, partial)?
+            };
+            Some(c)
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.primary.cmp(&b.primary))
+    });
+    scored.dedup_by(|a, b| a.primary == b.primary);
+    scored
+}
+
+fn first_doc_line(doc: &str) -> String {
+    doc.lines().next().unwrap_or("").trim().to_string()
+}
+
 /// Filter and sort candidates based on fuzzy matching
 pub fn filter_candidates(
     candidates: &[CompletionCandidate],
@@ -293,6 +472,44 @@ pub fn filter_candidates(
         })
         .collect();
 
-    scored.sort_by(|a, b| b.score.cmp(&a.score));
+    scored.sort_by_key(|c| std::cmp::Reverse(c.score));
     scored
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+
+    #[test]
+    fn test_path_candidates() {
+        let analyzer = RustAnalyzer::new();
+        let root = vec!["kit".to_string()];
+        let sync = vec!["kit".to_string(), "sync".to_string()];
+        let mut items = analyzer
+            .analyze_source_with_module(
+                "/// Sync primitives\npub mod sync {}\npub fn spawn() {}",
+                None,
+                root,
+            )
+            .unwrap();
+        items.extend(
+            analyzer
+                .analyze_source_with_module("pub struct Mutex;\npub struct RwLock;", None, sync)
+                .unwrap(),
+        );
+        let names = |typed: &str| -> Vec<String> {
+            path_candidates(&items, typed)
+                .into_iter()
+                .map(|c| c.primary)
+                .collect()
+        };
+        assert_eq!(names("kit::"), vec!["kit::spawn", "kit::sync::"]);
+        assert_eq!(
+            path_candidates(&items, "kit::")[1].secondary.as_deref(),
+            Some("Sync primitives")
+        );
+        assert_eq!(names("kit::sync::rw"), vec!["kit::sync::RwLock"]);
+        assert!(names("kit::net::").is_empty());
+    }
+}
@@ -1,15 +1,18 @@
-//! Splash screen with waves animation shown before the main TUI.
+//! Splash screen with waves animation, and the first-run tour built around it.
 
-use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::{
     backend::CrosstermBackend,
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::Modifier,
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Widget},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
     Frame, Terminal,
 };
 use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
 use crate::ui::theme::Theme;
@@ -48,6 +51,39 @@ fn draw_waves(frame: &mut Frame, area: Rect, phase: f64, theme: &Theme) {
     }
 }
 
+/// Title bar shared by the splash screen and the tour
+fn render_title(area: Rect, buf: &mut Buffer, theme: &Theme) {
+    Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                "ORACLE",
+                theme.style_accent_bold().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  ·  ", theme.style_muted()),
+            Span::styled("Rust Code Inspector", theme.style_dim()),
+        ]),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(theme.style_border()),
+    )
+    .render(area, buf);
+}
+
+fn screen_layout(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(10),
+            Constraint::Length(3),
+        ])
+        .split(area)
+}
+
 /// Run the splash screen: waves animation + title. Returns when duration elapsed or any key pressed.
 pub fn run_splash(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyhow::Result<()> {
     let theme = Theme::default();
@@ -61,35 +97,8 @@ pub fn run_splash(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyh
         let phase = elapsed.as_secs_f64() * 2.0;
 
         terminal.draw(|frame| {
-            let area = frame.area();
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(3),
-                    Constraint::Min(10),
-                    Constraint::Length(3),
-                ])
-                .split(area);
-
-            let title = Paragraph::new(vec![
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled(
-                        "ORACLE",
-                        theme.style_accent_bold().add_modifier(Modifier::BOLD),
-                    ),
-                    Span::styled("  ·  ", theme.style_muted()),
-                    Span::styled("Rust Code Inspector", theme.style_dim()),
-                ]),
-            ])
-            .alignment(Alignment::Center)
-            .block(
-                Block::default()
-                    .borders(Borders::BOTTOM)
-                    .border_style(theme.style_border()),
-            );
-            title.render(chunks[0], frame.buffer_mut());
-
+            let chunks = screen_layout(frame.area());
+            render_title(chunks[0], frame.buffer_mut(), &theme);
             draw_waves(frame, chunks[1], phase, &theme);
 
             let hint = Paragraph::new(Line::from(vec![
@@ -111,3 +120,297 @@ pub fn run_splash(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyh
 
     Ok(())
 }
+
+/// An optional tool or setting some panels rely on, and whether it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCheck {
+    pub name: &'static str,
+    pub found: bool,
+    /// What works with it, or what's missing without it
+    pub note: &'static str,
+}
+
+/// Look for git, cargo and a GitHub token
+pub fn check_tools() -> Vec<ToolCheck> {
+    let runs = |program: &str| {
+        Command::new(program)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    };
+    let cargo = runs("cargo");
+    let token = std::env::var("GITHUB_TOKEN").is_ok_and(|t| !t.trim().is_empty());
+    vec![
+        ToolCheck {
+            name: "cargo",
+            found: cargo,
+            note: if cargo {
+                "dependency tree, features and doc tests"
+            } else {
+                "install Rust via rustup for dependencies, features and doc tests"
+            },
+        },
+        ToolCheck {
+            name: "git",
+            found: runs("git"),
+            note: "optional; browsing a project doesn't need it",
+        },
+        ToolCheck {
+            name: "GITHUB_TOKEN",
+            found: token,
+            note: if token {
+                "higher GitHub rate limit for repository stats"
+            } else {
+                "optional: set it (or put it in .env) for more GitHub requests per hour"
+            },
+        },
+    ]
+}
+
+/// Pages of the first-run tour, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourPage {
+    Welcome,
+    Panels,
+    Keys,
+    Environment,
+    Ready,
+}
+
+impl TourPage {
+    pub const ALL: [TourPage; 5] = [
+        TourPage::Welcome,
+        TourPage::Panels,
+        TourPage::Keys,
+        TourPage::Environment,
+        TourPage::Ready,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Welcome => "Welcome",
+            Self::Panels => "The screen",
+            Self::Keys => "Getting around",
+            Self::Environment => "Your setup",
+            Self::Ready => "Ready",
+        }
+    }
+}
+
+/// Position in the tour
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tour {
+    pub page: usize,
+}
+
+impl Tour {
+    pub fn current(&self) -> TourPage {
+        TourPage::ALL[self.page.min(TourPage::ALL.len() - 1)]
+    }
+
+    /// Go to the next page; `false` once past the last one
+    pub fn advance(&mut self) -> bool {
+        self.page += 1;
+        self.page < TourPage::ALL.len()
+    }
+
+    pub fn back(&mut self) {
+        self.page = self.page.saturating_sub(1);
+    }
+}
+
+/// Run the tour: welcome, the panels, essential keys, missing tools and where
+/// settings are kept. Returns when it is finished or skipped.
+pub fn run_onboarding(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    theme: &Theme,
+    config_path: Option<&Path>,
+) -> anyhow::Result<()> {
+    let tools = check_tools();
+    let mut tour = Tour::default();
+    let start = Instant::now();
+
+    loop {
+        let phase = start.elapsed().as_secs_f64() * 2.0;
+        terminal.draw(|frame| {
+            let chunks = screen_layout(frame.area());
+            render_title(chunks[0], frame.buffer_mut(), theme);
+            let page = tour.current();
+            if page == TourPage::Welcome {
+                draw_waves(frame, chunks[1], phase, theme);
+            }
+            let body = tour_body(page, theme, &tools, config_path);
+            let width = 72.min(chunks[1].width);
+            let height = (body.len() as u16 + 2).min(chunks[1].height);
+            let y = if page == TourPage::Welcome {
+                chunks[1].bottom().saturating_sub(height)
+            } else {
+                chunks[1].y + (chunks[1].height - height) / 2
+            };
+            let area = Rect::new(
+                chunks[1].x + (chunks[1].width - width) / 2,
+                y,
+                width,
+                height,
+            );
+            ratatui::widgets::Clear.render(area, frame.buffer_mut());
+            Paragraph::new(body)
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(theme.style_border_focused())
+                        .title(format!(
+                            " {} ({}/{}) ",
+                            page.title(),
+                            tour.page + 1,
+                            TourPage::ALL.len()
+                        )),
+                )
+                .render(area, frame.buffer_mut());
+
+            let last = page == TourPage::Ready;
+            Paragraph::new(Line::from(vec![
+                Span::styled(
+                    if last {
+                        "Enter start"
+                    } else {
+                        "Enter/→ next"
+                    },
+                    theme.style_accent(),
+                ),
+                Span::styled("  ·  ← back  ·  Esc skip the tour", theme.style_dim()),
+            ]))
+            .alignment(Alignment::Center)
+            .render(chunks[2], frame.buffer_mut());
+        })?;
+
+        // Only the welcome page animates; the rest wait for a key
+        let timeout = if tour.current() == TourPage::Welcome {
+            Duration::from_millis(50)
+        } else {
+            Duration::from_secs(60)
+        };
+        if !event::poll(timeout)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => break,
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace => tour.back(),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l' | ' ') if !tour.advance() => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn tour_body(
+    page: TourPage,
+    theme: &Theme,
+    tools: &[ToolCheck],
+    config_path: Option<&Path>,
+) -> Vec<Line<'static>> {
+    let key = |k: &str, text: &str| {
+        Line::from(vec![
+            Span::styled(format!("  {:<12}", k), theme.style_accent()),
+            Span::raw(text.to_string()),
+        ])
+    };
+    let text = |s: &str| Line::from(Span::raw(s.to_string()));
+    match page {
+        TourPage::Welcome => vec![
+            text("Oracle reads a Rust project and lets you browse its items,"),
+            text("docs and dependencies without leaving the terminal."),
+            Line::from(""),
+            Line::from(Span::styled(
+                "A short tour of the screen and the keys you need first.",
+                theme.style_dim(),
+            )),
+        ],
+        TourPage::Panels => vec![
+            key(
+                "Search",
+                "top: type to filter, e.g. `async:true` or `vis:pub`",
+            ),
+            key(
+                "List",
+                "left: items by tab, Types · Functions · Modules · Crates",
+            ),
+            key(
+                "Inspector",
+                "right: signature, docs, fields, impls and usages",
+            ),
+            key("Status", "bottom: what's running, messages and warnings"),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Tab moves focus between them; the focused one has a bright border.",
+                theme.style_dim(),
+            )),
+        ],
+        TourPage::Keys => vec![
+            key("/", "Search"),
+            key("↑/↓  j/k", "Move selection, scroll the inspector"),
+            key("Enter  →", "Open item, focus the inspector"),
+            key("1 2 3 4", "Switch tab"),
+            key("m", "Module tree"),
+            key("S", "Settings"),
+            key("?", "Every key"),
+            key("q", "Quit"),
+        ],
+        TourPage::Environment => tools
+            .iter()
+            .map(|tool| {
+                let (mark, style) = if tool.found {
+                    (theme.glyph("✓", "+"), theme.style_accent())
+                } else {
+                    (theme.glyph("✗", "-"), theme.style_error())
+                };
+                Line::from(vec![
+                    Span::styled(format!("  {} {:<14}", mark, tool.name), style),
+                    Span::styled(tool.note, theme.style_dim()),
+                ])
+            })
+            .collect(),
+        TourPage::Ready => {
+            let mut lines = vec![text("Settings are saved to")];
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {}",
+                    config_path.map_or("(no config directory)".into(), |p| p.display().to_string())
+                ),
+                theme.style_accent(),
+            )));
+            lines.extend([
+                Line::from(""),
+                text("Change them any time with S. A project can commit its own"),
+                text("defaults in .oracle.yaml. `oracle --tour` shows this again."),
+            ]);
+            lines
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tour_pages() {
+        let mut tour = Tour::default();
+        tour.back();
+        assert_eq!(tour.current(), TourPage::Welcome);
+        for _ in 1..TourPage::ALL.len() {
+            assert!(tour.advance());
+        }
+        assert_eq!(tour.current(), TourPage::Ready);
+        assert!(!tour.advance());
+    }
+}
@@ -0,0 +1,157 @@
+//! Snapshot helpers for widgets
+//!
+//! Render a widget into a ratatui `TestBackend` and get the screen back as plain
+//! text: one line per row, trailing spaces trimmed, so snapshots do not change
+//! with terminal colors or padding. Built for the crate's own tests and, with the
+//! `testing` feature, for theme and plugin authors checking their customizations.
+//!
+//! ```no_run
+//! use oracle_lib::analyzer::RustAnalyzer;
+//! use oracle_lib::ui::testing::snapshot_inspector;
+//! use oracle_lib::ui::theme::Theme;
+//!
+//! let items = RustAnalyzer::new().analyze_source("pub fn ping() {}").unwrap();
+//! let screen = snapshot_inspector(&Theme::nord(), items.first(), 60, 12);
+//! assert!(screen.contains("ping"));
+//! ```
+
+use crate::analyzer::{AnalyzedItem, CrateInfo};
+use crate::ui::theme::Theme;
+use crate::ui::{DependencyView, InspectorPanel, OracleUi};
+
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::widgets::Widget;
+use ratatui::Terminal;
+
+/// Draw `widget` over a `width` x `height` screen and return the buffer
+pub fn render(widget: impl Widget, width: u16, height: u16) -> Buffer {
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).expect("TestBackend never fails");
+    terminal
+        .draw(|frame| frame.render_widget(widget, frame.area()))
+        .expect("TestBackend never fails");
+    terminal.backend().buffer().clone()
+}
+
+/// Buffer as text, one line per row with trailing spaces trimmed
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let width = buffer.area.width as usize;
+    buffer
+        .content()
+        .chunks(width.max(1))
+        .map(|row| {
+            let line: String = row.iter().map(|cell| cell.symbol()).collect();
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Render `widget` and return the screen as text
+pub fn snapshot(widget: impl Widget, width: u16, height: u16) -> String {
+    buffer_text(&render(widget, width, height))
+}
+
+/// Snapshot of the whole UI as configured by the caller
+pub fn snapshot_ui(ui: OracleUi<'_>, width: u16, height: u16) -> String {
+    snapshot(ui, width, height)
+}
+
+/// Snapshot of the inspector showing `item` (or its empty state)
+pub fn snapshot_inspector(
+    theme: &Theme,
+    item: Option<&AnalyzedItem>,
+    width: u16,
+    height: u16,
+) -> String {
+    snapshot(InspectorPanel::new(theme).item(item), width, height)
+}
+
+/// Snapshot of the dependency view for `info` (or its placeholder)
+pub fn snapshot_dependencies(
+    theme: &Theme,
+    info: Option<&CrateInfo>,
+    width: u16,
+    height: u16,
+) -> String {
+    snapshot(DependencyView::new(theme).crate_info(info), width, height)
+}
+
+/// Compare snapshots line by line; panics naming the first differing row, with both
+/// screens printed. Trailing blank lines are ignored on both sides.
+#[track_caller]
+pub fn assert_snapshot(actual: &str, expected: &str) {
+    let actual = actual.trim_end_matches('\n');
+    let expected = expected.trim_end_matches('\n');
+    if actual == expected {
+        return;
+    }
+    let row = actual
+        .lines()
+        .zip(expected.lines())
+        .position(|(a, e)| a.trim_end() != e.trim_end())
+        .unwrap_or_else(|| actual.lines().count().min(expected.lines().count()));
+    panic!(
+        "snapshot differs at row {row}\n--- expected ---\n{expected}\n--- actual ---\n{actual}\n"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::RustAnalyzer;
+    use crate::ui::{FilteredItems, Tab};
+
+    fn crate_info() -> CrateInfo {
+        CrateInfo {
+            name: "demo".into(),
+            version: "0.3.1".into(),
+            authors: vec![],
+            license: Some("MIT".into()),
+            description: Some("A demo crate".into()),
+            homepage: None,
+            repository: None,
+            documentation: None,
+            dependencies: vec![],
+            features: vec![],
+            default_features: vec![],
+            edition: "2021".into(),
+            rust_version: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshots_are_trimmed_and_stable() {
+        let items = RustAnalyzer::new()
+            .analyze_source("pub fn ping() {}")
+            .unwrap();
+        let theme = Theme::default();
+        let first = snapshot_inspector(&theme, items.first(), 50, 12);
+        assert_eq!(first.lines().count(), 12);
+        assert!(first.lines().all(|l| l == l.trim_end()));
+        assert!(first.contains("fn ping()"));
+        assert_snapshot(&snapshot_inspector(&theme, items.first(), 50, 12), &first);
+        assert_ne!(snapshot_inspector(&theme, None, 50, 12), first);
+
+        let info = crate_info();
+        let deps = snapshot_dependencies(&theme, Some(&info), 60, 16);
+        assert!(deps.contains("demo") && deps.contains("0.3.1"));
+
+        let screen = snapshot_ui(
+            OracleUi::new(&theme)
+                .items(&items)
+                .filtered_items(FilteredItems::new(&items, &[0]))
+                .current_tab(Tab::Functions),
+            100,
+            30,
+        );
+        assert!(screen.contains("ping"));
+    }
+
+    #[test]
+    #[should_panic(expected = "snapshot differs at row 1")]
+    fn test_assert_snapshot_reports_row() {
+        assert_snapshot("same\nleft", "same\nright");
+    }
+}
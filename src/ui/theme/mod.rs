@@ -87,6 +87,8 @@ pub struct Theme {
     pub string: Color,
     pub number: Color,
     pub comment: Color,
+    /// 16-color palette and ASCII glyphs, see [`Theme::with_compat`]
+    pub compat: bool,
 }
 
 impl Theme {
@@ -115,6 +117,7 @@ impl Theme {
             string: Color::Rgb(152, 195, 121),  // Green
             number: Color::Rgb(209, 154, 102),  // Orange
             comment: Color::Rgb(92, 99, 112),   // Gray
+            compat: false,
         }
     }
 
@@ -143,6 +146,7 @@ impl Theme {
             string: Color::Rgb(163, 190, 140),      // Nord14
             number: Color::Rgb(208, 135, 112),      // Nord12
             comment: Color::Rgb(76, 86, 106),       // Nord3
+            compat: false,
         }
     }
 
@@ -172,6 +176,7 @@ impl Theme {
             string: Color::Rgb(166, 227, 161),       // Green
             number: Color::Rgb(250, 179, 135),       // Peach
             comment: Color::Rgb(108, 112, 134),      // Overlay0
+            compat: false,
         }
     }
 
@@ -201,6 +206,7 @@ impl Theme {
             string: Color::Rgb(241, 250, 140),       // Yellow
             number: Color::Rgb(189, 147, 249),       // Purple
             comment: Color::Rgb(98, 114, 164),       // Comment
+            compat: false,
         }
     }
 
@@ -221,6 +227,53 @@ impl Theme {
         ThemeKind::from_name(&self.name)
     }
 
+    /// Compatibility mode for the Linux console, plain SSH sessions and terminals
+    /// without truecolor: every color becomes its nearest of the 16 ANSI colors and
+    /// [`Theme::glyph`] picks the ASCII fallback.
+    ///
+    /// Backgrounds are left to the terminal (only the selection keeps one), since the
+    /// themes' near-black greys would all collapse to the same ANSI black.
+    pub fn with_compat(self, compat: bool) -> Self {
+        if !compat {
+            return self;
+        }
+        Self {
+            accent: ansi16(self.accent),
+            accent_dim: ansi16(self.accent_dim),
+            bg: Color::Reset,
+            bg_highlight: Color::DarkGray,
+            bg_panel: Color::Reset,
+            fg: Color::Reset,
+            fg_dim: Color::Gray,
+            fg_muted: Color::DarkGray,
+            border: Color::DarkGray,
+            border_focused: ansi16(self.border_focused),
+            tab_active_bg: ansi16(self.tab_active_bg),
+            tab_active_fg: Color::White,
+            error: ansi16(self.error),
+            warning: ansi16(self.warning),
+            success: ansi16(self.success),
+            info: ansi16(self.info),
+            keyword: ansi16(self.keyword),
+            function: ansi16(self.function),
+            type_: ansi16(self.type_),
+            string: ansi16(self.string),
+            number: ansi16(self.number),
+            comment: Color::DarkGray,
+            compat: true,
+            ..self
+        }
+    }
+
+    /// `fancy` normally, `plain` in compatibility mode, e.g. `glyph("📦 ", "")`
+    pub fn glyph(&self, fancy: &'static str, plain: &'static str) -> &'static str {
+        if self.compat {
+            plain
+        } else {
+            fancy
+        }
+    }
+
     // Style builders
     pub fn style_accent(&self) -> Style {
         Style::default().fg(self.accent)
@@ -289,6 +342,17 @@ impl Theme {
         Style::default().fg(self.success)
     }
 
+    /// Coverage percentage: full is success, none is error, anything between a warning
+    pub fn style_coverage(&self, percent: f64) -> Style {
+        if percent >= 100.0 {
+            self.style_success()
+        } else if percent <= 0.0 {
+            self.style_error()
+        } else {
+            self.style_warning()
+        }
+    }
+
     pub fn style_info(&self) -> Style {
         Style::default().fg(self.info)
     }
@@ -324,6 +388,55 @@ impl Default for Theme {
     }
 }
 
+/// Terminals known to lack truecolor and emoji: the Linux console and `TERM=dumb`
+pub fn terminal_needs_compat() -> bool {
+    std::env::var("TERM").is_ok_and(|term| term == "linux" || term == "dumb")
+}
+
+/// Nearest of the 16 ANSI colors by hue, falling back to the greys for unsaturated
+/// colors. Anything that isn't RGB is already a palette color and passes through.
+fn ansi16(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    let (r, g, b) = (f64::from(r), f64::from(g), f64::from(b));
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+    if saturation < 0.2 {
+        return match max as u8 {
+            0..=63 => Color::Black,
+            64..=127 => Color::DarkGray,
+            128..=209 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+    let delta = max - min;
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    // Pastels (bright but washed out) read as the light variants
+    let light = max >= 200.0 && saturation < 0.6;
+    match (hue as u16, light) {
+        (0..=19 | 330.., false) => Color::Red,
+        (0..=19 | 330.., true) => Color::LightRed,
+        (20..=69, false) => Color::Yellow,
+        (20..=69, true) => Color::LightYellow,
+        (70..=159, false) => Color::Green,
+        (70..=159, true) => Color::LightGreen,
+        (160..=199, false) => Color::Cyan,
+        (160..=199, true) => Color::LightCyan,
+        (200..=259, false) => Color::Blue,
+        (200..=259, true) => Color::LightBlue,
+        (_, false) => Color::Magenta,
+        (_, true) => Color::LightMagenta,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -362,4 +475,24 @@ mod tests {
         let t2 = Theme::from_name("dracula");
         assert_eq!(t2.name, "Dracula");
     }
+
+    #[test]
+    fn test_compat_palette() {
+        assert_eq!(ansi16(Color::Rgb(244, 67, 54)), Color::Red);
+        assert_eq!(ansi16(Color::Rgb(78, 191, 113)), Color::Green);
+        assert_eq!(ansi16(Color::Rgb(136, 192, 208)), Color::LightCyan);
+        assert_eq!(ansi16(Color::Rgb(255, 152, 0)), Color::Yellow);
+        assert_eq!(ansi16(Color::Rgb(140, 140, 145)), Color::Gray);
+        assert_eq!(ansi16(Color::Rgb(24, 24, 24)), Color::Black);
+        assert_eq!(ansi16(Color::Blue), Color::Blue);
+
+        for &kind in ThemeKind::ALL {
+            let theme = Theme::from_kind(kind).with_compat(true);
+            assert_eq!(theme.kind(), kind);
+            let colors = [theme.accent, theme.bg_highlight, theme.error, theme.keyword];
+            assert!(colors.iter().all(|c| !matches!(c, Color::Rgb(..))));
+            assert_eq!(theme.glyph("📦 ", ""), "");
+        }
+        assert_eq!(Theme::default().glyph("📦 ", ""), "📦 ");
+    }
 }
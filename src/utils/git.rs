@@ -0,0 +1,93 @@
+//! Reading files from git history
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use crate::error::{OracleError, Result};
+
+/// A commit as listed by `git log`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    pub author: String,
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub subject: String,
+}
+
+impl Commit {
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(8)]
+    }
+}
+
+/// Contents of `file` at `rev` (e.g. `HEAD`, `main`, `v1.2.0`) in the repository it
+/// is in; `Ok(None)` when the file doesn't exist at `rev`
+pub fn show_file(file: &Path, rev: &str) -> Result<Option<String>> {
+    let (dir, name) = split(file)?;
+    // `./name` is relative to `-C`, wherever the repository root is
+    let output = git(dir, &["show", &format!("{}:./{}", rev, name)])?;
+    if output.status.success() {
+        return Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()));
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("exists on disk, but not in") || stderr.contains("does not exist in") {
+        return Ok(None);
+    }
+    Err(failure(&stderr))
+}
+
+/// Commits that changed `file`, newest first, at most `limit`
+pub fn file_log(file: &Path, limit: usize) -> Result<Vec<Commit>> {
+    let (dir, name) = split(file)?;
+    let output = git(
+        dir,
+        &[
+            "log",
+            &format!("--max-count={}", limit),
+            "--date=short",
+            "--format=%H%x1f%an%x1f%ad%x1f%s",
+            "--",
+            &name,
+        ],
+    )?;
+    if !output.status.success() {
+        return Err(failure(&String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\x1f');
+            Some(Commit {
+                hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Directory to run git in and the file name relative to it
+fn split(file: &Path) -> Result<(&Path, String)> {
+    let (Some(dir), Some(name)) = (file.parent(), file.file_name()) else {
+        return Err(OracleError::Other(format!(
+            "{} is not a file",
+            file.display()
+        )));
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    Ok((dir, name.to_string_lossy().into_owned()))
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<Output> {
+    Ok(Command::new("git").arg("-C").arg(dir).args(args).output()?)
+}
+
+fn failure(stderr: &str) -> OracleError {
+    OracleError::Other(stderr.lines().next().unwrap_or("git failed").to_string())
+}
@@ -0,0 +1,187 @@
+//! Structured logging to a file
+//!
+//! The TUI owns stdout and stderr, so diagnostics go through `tracing` into
+//! `~/.local/state/oracle/oracle.log` instead (warnings and errors by default, debug
+//! with `--verbose`, or any filter in `ORACLE_LOG`). The most recent lines are also
+//! kept in memory for the debug overlay (`D`).
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+/// Lines kept for the debug overlay
+const RECENT_CAPACITY: usize = 500;
+/// Environment variable overriding the level filter, e.g. `ORACLE_LOG=trace`
+const FILTER_ENV: &str = "ORACLE_LOG";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+static RECENT: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+static LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// `~/.local/state/oracle/oracle.log`; platforms without a state directory use
+/// the local data directory
+pub fn log_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("oracle").join("oracle.log"))
+}
+
+/// Install the global subscriber. Returns the log file path when it could be
+/// opened; recent lines are kept in memory either way.
+pub fn init(verbose: bool) -> Option<PathBuf> {
+    let default = if verbose {
+        "oracle=debug,oracle_lib=debug"
+    } else {
+        "oracle=warn,oracle_lib=warn"
+    };
+    let filter = EnvFilter::try_from_env(FILTER_ENV).unwrap_or_else(|_| EnvFilter::new(default));
+    let file = log_path().and_then(|path| {
+        std::fs::create_dir_all(path.parent()?).ok()?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .ok()?;
+        Some((path, file))
+    });
+    let (path, writer) = match file {
+        Some((path, file)) => (Some(path), Some(Mutex::new(file))),
+        None => (None, None),
+    };
+    let file_layer = writer.map(|writer| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(writer)
+    });
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(RecentLayer)
+        .try_init()
+        .is_ok();
+    if let Some(path) = path.as_ref().filter(|_| installed) {
+        let _ = LOG_FILE.set(path.clone());
+    }
+    path
+}
+
+/// Where this session is logging, once [`init`] succeeded
+pub fn log_file() -> Option<&'static PathBuf> {
+    LOG_FILE.get()
+}
+
+/// Recent log lines, oldest first
+pub fn recent_lines() -> Vec<LogLine> {
+    recent().iter().cloned().collect()
+}
+
+fn recent() -> std::sync::MutexGuard<'static, VecDeque<LogLine>> {
+    RECENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn push_recent(line: LogLine) {
+    let mut recent = recent();
+    if recent.len() == RECENT_CAPACITY {
+        recent.pop_front();
+    }
+    recent.push_back(line);
+}
+
+/// Keeps formatted events in [`RECENT`] for the debug overlay
+struct RecentLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let meta = event.metadata();
+        push_recent(LogLine {
+            level: *meta.level(),
+            target: meta.target().to_string(),
+            message: visitor.finish(),
+        });
+    }
+}
+
+/// `message` first, then the remaining fields as `key=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        match (self.message.is_empty(), self.fields.is_empty()) {
+            (_, true) => self.message,
+            (true, false) => self.fields,
+            (false, false) => format!("{} {}", self.message, self.fields),
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+            return;
+        }
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+        let _ = write!(self.fields, "{}={:?}", field.name(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_layer_formats_fields() {
+        let subscriber = tracing_subscriber::registry().with(RecentLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(url = "https://crates.io", status = 503, "request failed");
+            tracing::debug!(files = 3);
+        });
+        let lines = recent_lines();
+        let last: Vec<(Level, &str)> = lines
+            .iter()
+            .rev()
+            .take(2)
+            .map(|l| (l.level, l.message.as_str()))
+            .collect();
+        assert_eq!(
+            last,
+            vec![
+                (Level::DEBUG, "files=3"),
+                (
+                    Level::WARN,
+                    "request failed url=\"https://crates.io\" status=503"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,422 @@
+//! Format-preserving edits to `Cargo.toml`
+//!
+//! Only the touched values change; comments, key order and spacing are kept.
+
+use cargo_metadata::semver::{Version, VersionReq};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, TableLike, Value};
+
+use crate::error::{OracleError, Result};
+
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// A dependency requirement that has to change to allow a newer version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionBump {
+    pub name: String,
+    /// Table the entry is in, e.g. `dev-dependencies` or `target.'cfg(unix)'.dependencies`
+    pub table: String,
+    pub from: String,
+    pub to: String,
+}
+
+pub fn parse(manifest: &str) -> Result<DocumentMut> {
+    manifest
+        .parse()
+        .map_err(|e: toml_edit::TomlError| OracleError::Parse(e.to_string()))
+}
+
+/// Rewrite the requirements of each `(package, version)` that don't already allow
+/// `version`, in every dependency table. Returns the new manifest and what changed.
+pub fn upgrade_requirements(
+    manifest: &str,
+    upgrades: &[(String, Version)],
+) -> Result<(String, Vec<VersionBump>)> {
+    let mut doc = parse(manifest)?;
+    let mut bumps = Vec::new();
+    visit_dependencies(&mut doc, |table, key, item| {
+        let name = package_name(key, item);
+        let Some((_, version)) = upgrades.iter().find(|(n, _)| *n == name) else {
+            return;
+        };
+        let Some(value) = requirement_mut(item) else {
+            return; // path, git or `workspace = true` dependency
+        };
+        let Some(from) = value.as_str().map(str::to_string) else {
+            return;
+        };
+        let Some(to) = bump_requirement(&from, version) else {
+            return;
+        };
+        let decor = value.decor().clone();
+        *value = Value::from(to.clone());
+        *value.decor_mut() = decor;
+        bumps.push(VersionBump {
+            name,
+            table: table.to_string(),
+            from,
+            to,
+        });
+    });
+    Ok((doc.to_string(), bumps))
+}
+
+/// New requirement allowing `version` with the same operator and precision as `req`
+/// (`"0.9"` → `"1.2"`, `"~1.0.3"` → `"~2.0.1"`); `None` when `req` already allows it
+/// or isn't a single comparator
+pub fn bump_requirement(req: &str, version: &Version) -> Option<String> {
+    let trimmed = req.trim();
+    if VersionReq::parse(trimmed).ok()?.matches(version) {
+        return None;
+    }
+    let op_len = trimmed.len() - trimmed.trim_start_matches(['^', '~', '=']).len();
+    let (op, rest) = trimmed.split_at(op_len);
+    let rest = rest.trim_start();
+    if rest.contains([',', '*', '<', '>']) {
+        return None;
+    }
+    let new = match rest.split('.').count() {
+        1 => version.major.to_string(),
+        2 => format!("{}.{}", version.major, version.minor),
+        _ => version.to_string(),
+    };
+    Some(format!("{}{}", op, new))
+}
+
+/// Features a `[dependencies]` entry turns on, as written in the manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeclaredFeatures {
+    pub features: Vec<String>,
+    /// `default-features = false` is absent
+    pub default_features: bool,
+}
+
+impl DeclaredFeatures {
+    /// `cargo add name@version --features a,b --no-default-features`, as needed
+    pub fn cargo_add(&self, package: &str, version: &str) -> String {
+        let mut command = format!("cargo add {}@{}", package, version);
+        if !self.features.is_empty() {
+            command.push_str(&format!(" --features {}", self.features.join(",")));
+        }
+        if !self.default_features {
+            command.push_str(" --no-default-features");
+        }
+        command
+    }
+
+    /// `name = "version"`, or an inline table when features are involved
+    pub fn toml_entry(&self, package: &str, version: &str) -> String {
+        if self.features.is_empty() && self.default_features {
+            return format!("{} = {}", package, Value::from(version));
+        }
+        let mut table = InlineTable::new();
+        table.insert("version", Value::from(version));
+        if !self.default_features {
+            table.insert("default-features", Value::from(false));
+        }
+        if !self.features.is_empty() {
+            table.insert("features", Value::Array(self.features.iter().collect()));
+        }
+        table.fmt();
+        format!("{} = {}", package, table)
+    }
+}
+
+/// What the `[dependencies]` entry for `package` requests; `None` if there is none
+pub fn declared_features(manifest: &str, package: &str) -> Result<Option<DeclaredFeatures>> {
+    let doc = parse(manifest)?;
+    let Some(deps) = doc.get("dependencies").and_then(Item::as_table_like) else {
+        return Ok(None);
+    };
+    let Some((_, item)) = deps
+        .iter()
+        .find(|(key, item)| package_name(key, item) == package)
+    else {
+        return Ok(None);
+    };
+    let Some(table) = item.as_table_like() else {
+        return Ok(Some(DeclaredFeatures {
+            features: Vec::new(),
+            default_features: true,
+        }));
+    };
+    let features = table
+        .get("features")
+        .and_then(Item::as_array)
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    let default_features = table
+        .get("default-features")
+        .or_else(|| table.get("default_features"))
+        .and_then(Item::as_bool)
+        .unwrap_or(true);
+    Ok(Some(DeclaredFeatures {
+        features,
+        default_features,
+    }))
+}
+
+/// Add or remove `feature` in the `features` list of `package`'s `[dependencies]`
+/// entry, turning `name = "1"` into an inline table when needed
+pub fn set_feature(manifest: &str, package: &str, feature: &str, enabled: bool) -> Result<String> {
+    edit_dependency(manifest, package, |table| {
+        if !table.contains_key("features") {
+            if !enabled {
+                return;
+            }
+            table.insert("features", Item::Value(Value::Array(Array::new())));
+        }
+        let Some(features) = table.get_mut("features").and_then(Item::as_array_mut) else {
+            return;
+        };
+        if enabled {
+            if !features.iter().any(|f| f.as_str() == Some(feature)) {
+                features.push(feature);
+            }
+        } else {
+            features.retain(|f| f.as_str() != Some(feature));
+            if features.is_empty() {
+                table.remove("features");
+            }
+        }
+    })
+}
+
+/// Set `default-features = false` on `package`'s `[dependencies]` entry, or remove it
+pub fn set_default_features(manifest: &str, package: &str, enabled: bool) -> Result<String> {
+    edit_dependency(manifest, package, |table| {
+        table.remove("default_features");
+        if enabled {
+            table.remove("default-features");
+        } else {
+            table.insert("default-features", Item::Value(Value::from(false)));
+        }
+    })
+}
+
+/// Add `package = "version"` to `[dependencies]`, creating the table if needed; fails
+/// if the package is already there
+pub fn add_dependency(manifest: &str, package: &str, version: &str) -> Result<String> {
+    let mut doc = parse(manifest)?;
+    let deps = doc
+        .entry("dependencies")
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| OracleError::Parse("[dependencies] is not a table".into()))?;
+    if deps
+        .iter()
+        .any(|(key, item)| package_name(key, item) == package)
+    {
+        return Err(OracleError::Other(format!(
+            "{} is already in [dependencies]",
+            package
+        )));
+    }
+    deps.insert(package, toml_edit::value(version));
+    Ok(doc.to_string())
+}
+
+fn edit_dependency(
+    manifest: &str,
+    package: &str,
+    edit: impl FnOnce(&mut dyn TableLike),
+) -> Result<String> {
+    let mut doc = parse(manifest)?;
+    let not_found = || OracleError::Other(format!("{} is not in [dependencies]", package));
+    let deps = doc
+        .get_mut("dependencies")
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(not_found)?;
+    let (_, item) = deps
+        .iter_mut()
+        .find(|(key, item)| package_name(key.get(), item) == package)
+        .ok_or_else(not_found)?;
+    if let Some(version) = item.as_str().map(String::from) {
+        let mut table = InlineTable::new();
+        table.insert("version", Value::from(version));
+        let decor = item
+            .as_value()
+            .map(|v| v.decor().clone())
+            .unwrap_or_default();
+        let mut value = Value::InlineTable(table);
+        *value.decor_mut() = decor;
+        *item = Item::Value(value);
+    }
+    let table = item.as_table_like_mut().ok_or_else(not_found)?;
+    edit(table);
+    // Removing the last key leaves `{ version = "1"}`
+    if let Some(inline) = item.as_inline_table_mut() {
+        inline.fmt();
+    }
+    Ok(doc.to_string())
+}
+
+/// `(line number, old, new)` of each line that differs; edits here never add or
+/// remove lines
+pub fn changed_lines<'a>(before: &'a str, after: &'a str) -> Vec<(usize, &'a str, &'a str)> {
+    before
+        .lines()
+        .zip(after.lines())
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(i, (old, new))| (i + 1, old, new))
+        .collect()
+}
+
+/// Call `visit(table, key, entry)` for every entry of every dependency table,
+/// target-specific ones included
+fn visit_dependencies(doc: &mut DocumentMut, mut visit: impl FnMut(&str, &str, &mut Item)) {
+    let root = doc.as_table_mut();
+    for kind in DEPENDENCY_TABLES {
+        if let Some(deps) = root.get_mut(kind).and_then(Item::as_table_like_mut) {
+            visit_table(deps, kind, &mut visit);
+        }
+    }
+    if let Some(targets) = root.get_mut("target").and_then(Item::as_table_like_mut) {
+        for (cfg, target) in targets.iter_mut() {
+            let Some(target) = target.as_table_like_mut() else {
+                continue;
+            };
+            for kind in DEPENDENCY_TABLES {
+                if let Some(deps) = target.get_mut(kind).and_then(Item::as_table_like_mut) {
+                    let table = format!("target.{}.{}", cfg.display_repr(), kind);
+                    visit_table(deps, &table, &mut visit);
+                }
+            }
+        }
+    }
+}
+
+fn visit_table(
+    deps: &mut dyn TableLike,
+    table: &str,
+    visit: &mut impl FnMut(&str, &str, &mut Item),
+) {
+    for (key, item) in deps.iter_mut() {
+        visit(table, key.get(), item);
+    }
+}
+
+/// Package an entry refers to: its key, or `package = "..."` when renamed
+fn package_name(key: &str, item: &Item) -> String {
+    item.as_table_like()
+        .and_then(|t| t.get("package"))
+        .and_then(Item::as_str)
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// The version requirement of `serde = "1"` or `serde = { version = "1", ... }`
+fn requirement_mut(item: &mut Item) -> Option<&mut Value> {
+    match item {
+        Item::Value(Value::String(_)) => item.as_value_mut(),
+        _ => item.as_table_like_mut()?.get_mut("version")?.as_value_mut(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_requirement() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert_eq!(bump_requirement("0.9", &v("1.2.3")), Some("1.2".into()));
+        assert_eq!(bump_requirement("1", &v("2.0.1")), Some("2".into()));
+        assert_eq!(
+            bump_requirement("~1.0.3", &v("2.0.1")),
+            Some("~2.0.1".into())
+        );
+        assert_eq!(
+            bump_requirement("=1.0.3", &v("1.0.4")),
+            Some("=1.0.4".into())
+        );
+        assert_eq!(bump_requirement("1.0", &v("1.4.0")), None);
+        assert_eq!(bump_requirement(">=1, <2", &v("2.0.0")), None);
+    }
+
+    #[test]
+    fn test_upgrade_requirements_keeps_formatting() {
+        let manifest = "[package]\nname = \"demo\"\n\n[dependencies]\n\
+                        serde = { version = \"0.9\", features = [\"derive\"] } # pinned\n\
+                        json = { package = \"serde_json\", version = \"1.0\" }\n\
+                        local = { path = \"../local\" }\n\n\
+                        [target.'cfg(unix)'.dev-dependencies]\nserde = \"0.9\"\n";
+        let v = |s: &str| Version::parse(s).unwrap();
+        let upgrades = vec![
+            ("serde".to_string(), v("1.0.200")),
+            ("serde_json".to_string(), v("1.0.100")),
+            ("local".to_string(), v("2.0.0")),
+        ];
+        let (updated, bumps) = upgrade_requirements(manifest, &upgrades).unwrap();
+        assert!(
+            updated.contains("serde = { version = \"1.0\", features = [\"derive\"] } # pinned\n")
+        );
+        assert!(updated.contains("json = { package = \"serde_json\", version = \"1.0\" }"));
+        assert!(updated.ends_with("[target.'cfg(unix)'.dev-dependencies]\nserde = \"1.0\"\n"));
+        let tables: Vec<&str> = bumps.iter().map(|b| b.table.as_str()).collect();
+        assert_eq!(
+            tables,
+            vec!["dependencies", "target.'cfg(unix)'.dev-dependencies"]
+        );
+        assert_eq!(changed_lines(manifest, &updated).len(), 2);
+        assert!(upgrade_requirements("[dependencies", &upgrades).is_err());
+    }
+
+    #[test]
+    fn test_toggle_features() {
+        let manifest = "[dependencies]\nserde = \"1\" # ser\ntokio = { version = \"1\", features = [\"rt\"] }\n";
+        let with_derive = set_feature(manifest, "serde", "derive", true).unwrap();
+        assert_eq!(
+            with_derive,
+            "[dependencies]\nserde = { version = \"1\", features = [\"derive\"] } # ser\n\
+             tokio = { version = \"1\", features = [\"rt\"] }\n"
+        );
+        let declared = declared_features(&with_derive, "serde").unwrap().unwrap();
+        assert_eq!(declared.features, vec!["derive"]);
+        assert!(declared.default_features);
+
+        let without_rt = set_feature(&with_derive, "tokio", "rt", false).unwrap();
+        assert!(without_rt.contains("tokio = { version = \"1\" }"));
+        let no_defaults = set_default_features(&without_rt, "tokio", false).unwrap();
+        let declared = declared_features(&no_defaults, "tokio").unwrap().unwrap();
+        assert!(declared.features.is_empty() && !declared.default_features);
+        assert!(declared_features(manifest, "rand").unwrap().is_none());
+        assert!(set_feature(manifest, "rand", "std", true).is_err());
+    }
+
+    #[test]
+    fn test_add_dependency() {
+        let manifest = "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"1\" # ser\n";
+        let added = add_dependency(manifest, "rand", "0.8.5").unwrap();
+        assert!(added.ends_with("serde = \"1\" # ser\nrand = \"0.8.5\"\n"));
+        assert!(add_dependency(&added, "rand", "0.9").is_err());
+        let fresh = add_dependency("[package]\nname = \"demo\"\n", "rand", "0.8").unwrap();
+        assert!(fresh.ends_with("[dependencies]\nrand = \"0.8\"\n"));
+    }
+
+    #[test]
+    fn test_dependency_lines() {
+        let plain = DeclaredFeatures {
+            features: Vec::new(),
+            default_features: true,
+        };
+        assert_eq!(plain.cargo_add("rand", "0.8.5"), "cargo add rand@0.8.5");
+        assert_eq!(plain.toml_entry("rand", "0.8.5"), "rand = \"0.8.5\"");
+        let featured = DeclaredFeatures {
+            features: vec!["derive".into(), "rc".into()],
+            default_features: false,
+        };
+        assert_eq!(
+            featured.cargo_add("serde", "1.0.210"),
+            "cargo add serde@1.0.210 --features derive,rc --no-default-features"
+        );
+        assert_eq!(
+            featured.toml_entry("serde", "1.0.210"),
+            "serde = { version = \"1.0.210\", default-features = false, features = [\"derive\", \"rc\"] }"
+        );
+    }
+}
@@ -1,9 +1,23 @@
 //! Utility functions and helpers
 
+pub mod clipboard;
 pub mod crate_check;
+pub mod git;
+#[cfg(feature = "tui")]
+pub mod logging;
+pub mod manifest;
 pub mod path;
+#[cfg(feature = "tui")]
+pub mod terminal;
 pub mod text;
+pub mod walk;
 
+pub use clipboard::copy_to_clipboard;
 pub use crate_check::*;
 pub use path::{dir_size, format_bytes};
+#[cfg(feature = "tui")]
+pub use terminal::{
+    install_panic_hook, report_cwd, restore_terminal, restore_title, set_title, TerminalGuard,
+};
 pub use text::*;
+pub use walk::{rust_files, SkippedPath};
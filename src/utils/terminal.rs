@@ -0,0 +1,183 @@
+//! Terminal setup and crash-safe teardown
+//!
+//! [`TerminalGuard`] enters raw mode and the alternate screen and undoes both when
+//! dropped, on early returns and errors alike. [`install_panic_hook`] restores the
+//! terminal before the panic message is printed (otherwise it lands on the alternate
+//! screen and vanishes) and keeps a copy with a backtrace in the config directory;
+//! a background thread's panic is only logged, as the UI keeps running.
+//! [`set_title`] and [`report_cwd`] tell the terminal (or tmux) what's open; the
+//! title it had before is put back on restore.
+
+use std::backtrace::Backtrace;
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
+};
+
+/// Set once [`set_title`] has saved the terminal's own title
+static TITLE_SAVED: AtomicBool = AtomicBool::new(false);
+
+/// Raw mode, alternate screen and (optionally) mouse capture for as long as it lives
+pub struct TerminalGuard {
+    _private: (),
+}
+
+impl TerminalGuard {
+    pub fn enter(mouse_capture: bool) -> io::Result<Self> {
+        enable_raw_mode()?;
+        // Built before the screen switch so a failure below still drops it
+        let guard = Self { _private: () };
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        if mouse_capture {
+            execute!(io::stdout(), EnableMouseCapture)?;
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Leave raw mode, the alternate screen and mouse capture, and show the cursor;
+/// errors are ignored since this also runs while panicking
+pub fn restore_terminal() {
+    restore_title();
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        crossterm::cursor::Show
+    );
+}
+
+/// Set the window (and tmux pane) title; the first call saves the current title on
+/// the terminal's title stack (xterm, VTE, kitty and others keep one)
+pub fn set_title(title: &str) -> io::Result<()> {
+    let mut out = io::stdout();
+    if !TITLE_SAVED.swap(true, Ordering::SeqCst) {
+        write!(out, "\x1b[22;0t")?;
+    }
+    execute!(out, SetTitle(title))
+}
+
+/// Put back the title saved by [`set_title`], if it was called
+pub fn restore_title() {
+    if TITLE_SAVED.swap(false, Ordering::SeqCst) {
+        let mut out = io::stdout();
+        let _ = write!(out, "\x1b[23;0t");
+        let _ = out.flush();
+    }
+}
+
+/// Report `dir` as the working directory (OSC 7), so new tabs and panes open there
+pub fn report_cwd(dir: &Path) -> io::Result<()> {
+    let mut out = io::stdout();
+    out.write_all(osc7(dir, &hostname()).as_bytes())?;
+    out.flush()
+}
+
+/// `ESC ] 7 ; file://host/path ST` with the path percent-encoded
+fn osc7(dir: &Path, host: &str) -> String {
+    let mut path = String::new();
+    for b in dir.to_string_lossy().bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                path.push(b as char)
+            }
+            _ => path.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    format!("\x1b]7;file://{}{}\x1b\\", host, path)
+}
+
+/// Terminals compare the host with their own to tell local directories from remote
+/// ones; empty when it can't be found, which most read as local
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// `~/.config/oracle/panic.log` (or the platform's config directory)
+pub fn panic_log_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("oracle").join("panic.log"))
+}
+
+/// Append a panic and its backtrace to [`panic_log_path`]. When it's the calling
+/// (main) thread that panics, first restore the terminal and afterwards run the
+/// previous hook (which prints the message); other threads' panics are caught by
+/// whoever spawned them, and printing would scribble over the running UI
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    let main = thread::current().id();
+    panic::set_hook(Box::new(move |info| {
+        let on_main = thread::current().id() == main;
+        if on_main {
+            restore_terminal();
+        }
+        tracing::error!("panic: {}", info);
+        let log = write_panic_log(info);
+        if on_main {
+            if let Some(path) = log {
+                eprintln!("oracle crashed; details were written to {}", path.display());
+            }
+            previous(info);
+        }
+    }));
+}
+
+/// `info` is the hook's panic info, taken as `Display` so this builds on toolchains
+/// from before its rename to `PanicHookInfo`
+fn write_panic_log(info: &dyn Display) -> Option<PathBuf> {
+    let path = panic_log_path()?;
+    std::fs::create_dir_all(path.parent()?).ok()?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .ok()?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    writeln!(
+        file,
+        "--- oracle {} panicked (unix time {}) ---\n{}\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        secs,
+        info,
+        Backtrace::force_capture()
+    )
+    .ok()?;
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc7_encodes_path() {
+        assert_eq!(
+            osc7(Path::new("/home/ada/my crate"), "box"),
+            "\x1b]7;file://box/home/ada/my%20crate\x1b\\"
+        );
+    }
+}
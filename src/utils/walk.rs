@@ -0,0 +1,115 @@
+//! Source file discovery
+//!
+//! Walks a directory tree for `.rs` files without following symlinks into
+//! loops, stopping at a depth limit, and collecting unreadable entries as
+//! warnings instead of failing the whole walk.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+/// Directories nested deeper than this are not descended into.
+pub const MAX_WALK_DEPTH: usize = 32;
+
+/// A path that was skipped during analysis, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedPath {
+    pub path: PathBuf,
+    pub reason: String,
+    /// First and last line when only part of the file was skipped: code that
+    /// didn't parse while the items around it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<(usize, usize)>,
+}
+
+impl SkippedPath {
+    /// `path` and the skipped lines, e.g. `src/app.rs:12-20`
+    pub fn location(&self) -> String {
+        match self.lines {
+            Some((start, end)) if start == end => format!("{}:{}", self.path.display(), start),
+            Some((start, end)) => format!("{}:{}-{}", self.path.display(), start, end),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SkippedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location(), self.reason)
+    }
+}
+
+/// `.rs` files under `root` (sorted) plus the paths that could not be walked.
+pub fn rust_files(root: &Path) -> (Vec<PathBuf>, Vec<SkippedPath>) {
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    let walker = WalkDir::new(root)
+        .follow_links(true)
+        .max_depth(MAX_WALK_DEPTH)
+        .sort_by_file_name();
+    for entry in walker {
+        match entry {
+            Ok(entry) => {
+                if entry.file_type().is_dir() {
+                    if entry.depth() == MAX_WALK_DEPTH {
+                        skipped.push(SkippedPath {
+                            path: entry.into_path(),
+                            reason: format!("deeper than {} levels", MAX_WALK_DEPTH),
+                            lines: None,
+                        });
+                    }
+                } else if entry.path().extension().is_some_and(|ext| ext == "rs") {
+                    files.push(entry.into_path());
+                }
+            }
+            Err(err) => {
+                let path = err.path().unwrap_or(root).to_path_buf();
+                let reason = if let Some(ancestor) = err.loop_ancestor() {
+                    format!("symlink cycle back to {}", ancestor.display())
+                } else if let Some(io) = err.io_error() {
+                    io.to_string()
+                } else {
+                    err.to_string()
+                };
+                skipped.push(SkippedPath {
+                    path,
+                    reason,
+                    lines: None,
+                });
+            }
+        }
+    }
+    (files, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_files_sorted_and_filtered() {
+        let dir = std::env::temp_dir().join(format!("oracle-walk-test-{}", std::process::id()));
+        let nested = dir.join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(dir.join("lib.rs"), "").unwrap();
+        std::fs::write(dir.join("notes.md"), "").unwrap();
+        std::fs::write(nested.join("deep.rs"), "").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&dir, nested.join("loop")).unwrap();
+
+        let (files, skipped) = rust_files(&dir);
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| f.strip_prefix(&dir).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            names,
+            vec![PathBuf::from("a/b/deep.rs"), PathBuf::from("lib.rs")]
+        );
+        #[cfg(unix)]
+        assert!(skipped[0].reason.starts_with("symlink cycle"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}